@@ -0,0 +1,183 @@
+//! Benchmarks for hot aggregation paths.
+//!
+//! These benchmarks compare a 1,000-book quote refresh and a Greeks
+//! aggregation done by collecting into a `Vec` first against the
+//! allocation-free visitor APIs, and both against the incrementally
+//! maintained totals together with the cost a trade pays to update them.
+//! A counting allocator reports the allocations made by one pass of each
+//! before the timings run.
+
+use criterion::{Criterion, Throughput};
+use option_chain_orderbook::inventory::{InventoryManager, PositionLimits};
+use option_chain_orderbook::orderbook::{Quote, UnderlyingOrderBookManager};
+use option_chain_orderbook::pricing::Greeks;
+use optionstratlib::prelude::{ExpirationDate, Positive};
+use orderbook_rs::{OrderId, Side};
+use rust_decimal::Decimal;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// System allocator that counts allocations.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Returns the allocations made by `f`.
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+/// Builds 500 strikes (1,000 books) with a resting bid on every book.
+fn setup_manager() -> UnderlyingOrderBookManager {
+    let manager = UnderlyingOrderBookManager::new();
+    let exp_book = manager
+        .get_or_create("BTC")
+        .get_or_create_expiration(ExpirationDate::Days(Positive::THIRTY));
+    for i in 0..500 {
+        let strike = exp_book.get_or_create_strike(40000 + i * 100);
+        strike
+            .call()
+            .add_limit_order(OrderId::new(), Side::Buy, 100, 10)
+            .unwrap();
+        strike
+            .put()
+            .add_limit_order(OrderId::new(), Side::Buy, 100, 10)
+            .unwrap();
+    }
+    manager
+}
+
+/// Collects every quote into a `Vec`, as callers had to before the
+/// visitor APIs.
+fn collect_quotes(manager: &UnderlyingOrderBookManager) -> Vec<Quote> {
+    let mut quotes = Vec::new();
+    for underlying in manager.iter() {
+        for expiration in underlying.value().expirations().iter() {
+            for strike in expiration.value().chain().strikes().iter() {
+                quotes.push(strike.value().call_quote());
+                quotes.push(strike.value().put_quote());
+            }
+        }
+    }
+    quotes
+}
+
+/// Builds an inventory with 1,000 positions carrying Greeks.
+fn setup_inventory() -> InventoryManager {
+    let inventory = InventoryManager::new("BTC", PositionLimits::default()).unwrap();
+    for i in 0..1000 {
+        let symbol = format!("BTC-20240329-{}-C", 40000 + i);
+        inventory
+            .record_trade(&symbol, Decimal::ONE, Decimal::TEN)
+            .unwrap();
+        inventory
+            .set_greeks(
+                &symbol,
+                Greeks::new(
+                    Decimal::new(5, 1),
+                    Decimal::new(1, 2),
+                    Decimal::NEGATIVE_ONE,
+                    Decimal::ONE,
+                    Decimal::ZERO,
+                ),
+            )
+            .unwrap();
+    }
+    inventory
+}
+
+/// Benchmarks for a 1,000-book quote refresh loop.
+pub fn quote_refresh(c: &mut Criterion) {
+    let manager = setup_manager();
+    let visit = |manager: &UnderlyingOrderBookManager| {
+        let mut bid_size = 0u64;
+        manager.for_each_quote(|_, quote| bid_size += quote.bid_size());
+        bid_size
+    };
+    let collected = count_allocations(|| {
+        let total: u64 = collect_quotes(&manager).iter().map(Quote::bid_size).sum();
+        assert_eq!(total, 10_000);
+    });
+    let visited = count_allocations(|| assert_eq!(visit(&manager), 10_000));
+    println!("quote_refresh allocations per pass: collect={collected} for_each_quote={visited}");
+
+    let mut group = c.benchmark_group("quote_refresh");
+    group.throughput(Throughput::Elements(1000));
+    group.bench_function("collect_quotes", |b| {
+        b.iter(|| {
+            collect_quotes(&manager)
+                .iter()
+                .map(Quote::bid_size)
+                .sum::<u64>()
+        });
+    });
+    group.bench_function("for_each_quote", |b| b.iter(|| visit(&manager)));
+    group.finish();
+}
+
+/// Benchmarks for aggregating Greeks over 1,000 positions.
+pub fn greeks_aggregation(c: &mut Criterion) {
+    let inventory = setup_inventory();
+    let collected = count_allocations(|| {
+        let _ = inventory
+            .positions()
+            .iter()
+            .map(|p| p.greeks())
+            .sum::<Greeks>();
+    });
+    let folded = count_allocations(|| {
+        let _ = inventory.fold_greeks(Greeks::zero(), |acc, _, g| acc + g);
+    });
+    println!("greeks_aggregation allocations per pass: positions={collected} fold_greeks={folded}");
+
+    let mut group = c.benchmark_group("greeks_aggregation");
+    group.throughput(Throughput::Elements(1000));
+    group.bench_function("collect_positions", |b| {
+        b.iter(|| {
+            inventory
+                .positions()
+                .iter()
+                .map(|p| p.greeks())
+                .sum::<Greeks>()
+        });
+    });
+    group.bench_function("fold_greeks", |b| {
+        b.iter(|| inventory.fold_greeks(Greeks::zero(), |acc, _, g| acc + g));
+    });
+    group.bench_function("total_greeks", |b| b.iter(|| inventory.total_greeks()));
+    group.bench_function("expiration_greeks", |b| {
+        b.iter(|| inventory.expiration_greeks("20240329"));
+    });
+    group.finish();
+
+    // A buy and a sell per iteration, so the position size stays put.
+    let mut group = c.benchmark_group("greeks_incremental_update");
+    group.throughput(Throughput::Elements(2));
+    group.bench_function("record_trade_round_trip", |b| {
+        b.iter(|| {
+            inventory
+                .record_trade("BTC-20240329-40500-C", Decimal::ONE, Decimal::TEN)
+                .unwrap();
+            inventory
+                .record_trade("BTC-20240329-40500-C", Decimal::NEGATIVE_ONE, Decimal::TEN)
+                .unwrap();
+        });
+    });
+    group.finish();
+}
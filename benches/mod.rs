@@ -8,11 +8,17 @@
 //! - **expiration_bench**: Expiration order book and manager operations
 //! - **underlying_bench**: Underlying order book and manager operations
 //! - **hierarchy_bench**: Full hierarchy traversal and trading scenarios
+//! - **registry_bench**: Contract id interning and id-based lookups
+//! - **aggregation_bench**: Allocation-free quote and Greeks aggregation
+//! - **pricing_bench**: Fast `f64` batch pricing versus the OptionStratLib reference
 
+mod aggregation_bench;
 mod chain_bench;
 mod expiration_bench;
 mod hierarchy_bench;
 mod orderbook_bench;
+mod pricing_bench;
+mod registry_bench;
 mod strike_bench;
 mod underlying_bench;
 
@@ -65,11 +71,36 @@ criterion_group!(
     hierarchy_bench::hierarchy_scaling,
 );
 
+// Contract registry benchmarks
+criterion_group!(
+    registry_benches,
+    registry_bench::registry_lookup,
+    registry_bench::inventory_booking,
+    registry_bench::registry_scaling,
+);
+
+// Hot aggregation path benchmarks
+criterion_group!(
+    aggregation_benches,
+    aggregation_bench::quote_refresh,
+    aggregation_bench::greeks_aggregation,
+);
+
+// Fast pricing path benchmarks
+criterion_group!(
+    pricing_benches,
+    pricing_bench::expiry_pricing,
+    pricing_bench::chain_quoting,
+);
+
 criterion_main!(
     orderbook_benches,
     strike_benches,
     chain_benches,
     expiration_benches,
     underlying_benches,
-    hierarchy_benches
+    hierarchy_benches,
+    registry_benches,
+    aggregation_benches,
+    pricing_benches
 );
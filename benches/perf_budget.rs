@@ -0,0 +1,129 @@
+//! Performance budgets for option-chain-orderbook.
+//!
+//! Times the hot paths against fixed budgets and writes a machine-readable
+//! pass/fail report. Run with `cargo perf`; the process exits non-zero if
+//! any budget fails.
+//!
+//! - `PERF_BUDGET_SCALE`: factor applied to every budget (default 1.0)
+//! - `PERF_REPORT`: report path (default `target/perf-report.json`)
+
+use option_chain_orderbook::inventory::{InventoryManager, PositionLimits};
+use option_chain_orderbook::orderbook::UnderlyingOrderBookManager;
+use option_chain_orderbook::perf::PerfHarness;
+use option_chain_orderbook::pricing::Greeks;
+use option_chain_orderbook::quoting::{QuoteEngine, QuoteParams, SpreadCalculator};
+use optionstratlib::prelude::{ExpirationDate, Positive};
+use orderbook_rs::{OrderId, Side};
+use rust_decimal::Decimal;
+use std::hint::black_box;
+use std::time::Duration;
+
+/// Untimed passes before measuring each budget.
+const WARMUP: u32 = 20;
+/// Timed passes per budget.
+const ITERATIONS: u32 = 200;
+
+/// Quote parameters for 500 contracts.
+fn quote_params() -> Vec<QuoteParams> {
+    (0..500)
+        .map(|i| QuoteParams::new(Decimal::new(500 + i, 2), Decimal::new(30, 2)))
+        .collect()
+}
+
+/// Inventory with 10,000 positions carrying Greeks.
+fn inventory() -> InventoryManager {
+    let inventory = InventoryManager::new("BTC", PositionLimits::default()).unwrap();
+    let greeks = Greeks::new(
+        Decimal::new(5, 1),
+        Decimal::new(1, 2),
+        Decimal::NEGATIVE_ONE,
+        Decimal::ONE,
+        Decimal::ZERO,
+    );
+    for i in 0..10_000 {
+        let symbol = format!("BTC-20240329-{}-C", 10_000 + i);
+        inventory
+            .record_trade(&symbol, Decimal::ONE, Decimal::TEN)
+            .unwrap();
+        inventory.set_greeks(&symbol, greeks).unwrap();
+    }
+    inventory
+}
+
+/// Manager with 500 strikes (1,000 books) and a resting bid on every book.
+fn books() -> UnderlyingOrderBookManager {
+    let manager = UnderlyingOrderBookManager::new();
+    let expiration = manager
+        .get_or_create("BTC")
+        .get_or_create_expiration(ExpirationDate::Days(Positive::THIRTY));
+    for i in 0..500 {
+        let strike = expiration.get_or_create_strike(40000 + i * 100);
+        for book in [strike.call(), strike.put()] {
+            book.add_limit_order(OrderId::new(), Side::Buy, 100, 10)
+                .unwrap();
+        }
+    }
+    manager
+}
+
+fn main() {
+    let scale = std::env::var("PERF_BUDGET_SCALE")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(1.0);
+    let path =
+        std::env::var("PERF_REPORT").unwrap_or_else(|_| "target/perf-report.json".to_string());
+    let mut harness = PerfHarness::new(WARMUP, ITERATIONS)
+        .and_then(|h| h.with_scale(scale))
+        .expect("invalid perf harness settings");
+
+    let engine = QuoteEngine::new(SpreadCalculator::new(10));
+    let params = quote_params();
+    harness.measure(
+        "quote_generation_500_contracts",
+        Duration::from_millis(2),
+        || {
+            for p in &params {
+                black_box(engine.generate(p).unwrap());
+            }
+        },
+    );
+
+    let inventory = inventory();
+    harness.measure(
+        "greeks_aggregation_10k_positions",
+        Duration::from_millis(1),
+        || {
+            black_box(inventory.total_greeks());
+        },
+    );
+
+    let manager = books();
+    harness.measure("quote_refresh_1000_books", Duration::from_millis(1), || {
+        let mut bid_size = 0u64;
+        manager.for_each_quote(|_, quote| bid_size += quote.bid_size());
+        black_box(bid_size);
+    });
+
+    let report = harness.into_report();
+    for r in &report.results {
+        println!(
+            "{:<36} {:>6} median {:>10} ns  p99 {:>10} ns  budget {:>10} ns  ({:.0}%)",
+            r.name,
+            if r.passed { "PASS" } else { "FAIL" },
+            r.median_ns,
+            r.p99_ns,
+            r.budget_ns,
+            r.utilization() * 100.0
+        );
+    }
+    let json = report.to_json().expect("perf report serializes");
+    if let Err(e) = std::fs::write(&path, json) {
+        eprintln!("could not write perf report to {path}: {e}");
+    } else {
+        println!("perf report written to {path}");
+    }
+    if !report.passed() {
+        std::process::exit(1);
+    }
+}
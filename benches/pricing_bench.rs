@@ -0,0 +1,97 @@
+//! Benchmarks for the fast pricing path.
+//!
+//! These benchmarks price a 200-contract expiry through the `Decimal`
+//! reference pricer, which goes through OptionStratLib, and through the
+//! `f64` batch pricer, then quote a whole chain with and without
+//! [`ChainQuoter::with_fast_pricing`].
+
+use criterion::{Criterion, Throughput};
+use option_chain_orderbook::inventory::{InventoryManager, PositionLimits};
+use option_chain_orderbook::orderbook::UnderlyingOrderBook;
+use option_chain_orderbook::pricing::{
+    FastOption, PricingParams, SmileParams, VolatilitySurface, price_and_delta_batch, price_batch,
+};
+use option_chain_orderbook::quoting::{ChainQuoter, QuoteParams, SpreadCalculator};
+use optionstratlib::OptionStyle;
+use optionstratlib::prelude::{ExpirationDate, Positive};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::hint::black_box;
+
+/// Builds the pricing inputs of 100 strikes, calls and puts.
+fn expiry_params() -> Vec<PricingParams> {
+    (0..100)
+        .flat_map(|i| {
+            [OptionStyle::Call, OptionStyle::Put].map(|style| {
+                PricingParams::new(dec!(100), Decimal::from(50 + i), dec!(30), dec!(0.5), style)
+                    .with_rate(dec!(0.03))
+            })
+        })
+        .collect()
+}
+
+/// Benchmarks pricing one expiry with each pricer.
+pub fn expiry_pricing(c: &mut Criterion) {
+    let params = expiry_params();
+    let options: Vec<FastOption> = params
+        .iter()
+        .map(|p| FastOption::from_params(p).unwrap())
+        .collect();
+    let mut prices = vec![0.0; options.len()];
+
+    let mut group = c.benchmark_group("expiry_pricing");
+    group.throughput(Throughput::Elements(params.len() as u64));
+    group.bench_function("optionstratlib_price", |b| {
+        b.iter(|| {
+            for p in &params {
+                black_box(p.price().unwrap());
+            }
+        });
+    });
+    group.bench_function("optionstratlib_price_and_greeks", |b| {
+        b.iter(|| {
+            for p in &params {
+                black_box((p.price().unwrap(), p.greeks().unwrap()));
+            }
+        });
+    });
+    group.bench_function("fast_price_batch", |b| {
+        b.iter(|| price_batch(black_box(&options), &mut prices));
+    });
+    group.bench_function("fast_price_and_delta_batch", |b| {
+        b.iter(|| price_and_delta_batch(black_box(&options)));
+    });
+    group.finish();
+}
+
+/// Benchmarks quoting a 100-strike chain with each pricer.
+pub fn chain_quoting(c: &mut Criterion) {
+    let underlying = UnderlyingOrderBook::new("SPX");
+    let expiration = underlying.get_or_create_expiration(ExpirationDate::Days(Positive::THIRTY));
+    for strike in 50..150 {
+        expiration.get_or_create_strike(strike);
+    }
+    let surface = VolatilitySurface::new()
+        .with_pillar(dec!(30), SmileParams::new(dec!(0.5), dec!(-0.1), dec!(0.2)));
+    let inventory = InventoryManager::new("SPX", PositionLimits::default()).unwrap();
+    let quoter = ChainQuoter::new(SpreadCalculator::new(10), dec!(0.5))
+        .unwrap()
+        .with_rate(dec!(0.03))
+        .with_template(
+            QuoteParams::new(Decimal::ZERO, Decimal::ZERO).with_time_horizon(dec!(0.01)),
+        );
+
+    let mut group = c.benchmark_group("chain_quoting");
+    group.throughput(Throughput::Elements(200));
+    for (name, fast) in [("reference", false), ("fast_pricing", true)] {
+        let quoter = quoter.clone().with_fast_pricing(fast);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                quoter
+                    .quote_chain(&underlying, dec!(100), &surface, &inventory)
+                    .unwrap()
+            });
+        });
+    }
+    group.finish();
+}
@@ -0,0 +1,165 @@
+//! Benchmarks for contract registry operations.
+//!
+//! These benchmarks compare resolving an option book through the string-keyed
+//! hierarchy against resolving it by interned `ContractId`, and booking
+//! inventory trades by symbol against booking them by id.
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+use option_chain_orderbook::inventory::{InventoryManager, PositionLimits};
+use option_chain_orderbook::orderbook::{ContractId, ContractRegistry, UnderlyingOrderBookManager};
+use optionstratlib::prelude::{ExpirationDate, Positive};
+use rust_decimal_macros::dec;
+
+/// Builds a manager with one underlying, one expiration and `strikes` strikes.
+fn setup_manager(strikes: u64) -> (UnderlyingOrderBookManager, ExpirationDate, Vec<u64>) {
+    let manager = UnderlyingOrderBookManager::new();
+    let exp = ExpirationDate::Days(Positive::THIRTY);
+    let strike_prices: Vec<u64> = (0..strikes).map(|i| 40000 + i * 100).collect();
+    {
+        let exp_book = manager.get_or_create("BTC").get_or_create_expiration(exp);
+        for strike in &strike_prices {
+            exp_book.get_or_create_strike(*strike);
+        }
+    }
+    (manager, exp, strike_prices)
+}
+
+/// Benchmarks for contract lookup by symbol path versus by `ContractId`.
+pub fn registry_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("registry_lookup");
+
+    let (manager, exp, strikes) = setup_manager(100);
+    let ids: Vec<ContractId> = strikes
+        .iter()
+        .map(|strike| {
+            let book = manager
+                .get("BTC")
+                .unwrap()
+                .get_expiration(&exp)
+                .unwrap()
+                .get_strike(*strike)
+                .unwrap();
+            manager.contract_id(book.call().symbol()).unwrap()
+        })
+        .collect();
+    let symbols: Vec<String> = ids
+        .iter()
+        .map(|id| manager.registry().symbol_of(*id).unwrap())
+        .collect();
+
+    group.throughput(Throughput::Elements(strikes.len() as u64));
+
+    // Baseline: walk the hierarchy with string and date keys
+    group.bench_function("hierarchy_path", |b| {
+        b.iter(|| {
+            for strike in &strikes {
+                let book = manager
+                    .get("BTC")
+                    .unwrap()
+                    .get_expiration(&exp)
+                    .unwrap()
+                    .get_strike(*strike)
+                    .unwrap();
+                let _quote = book.call().best_quote();
+            }
+        });
+    });
+
+    // Edge lookup: resolve the symbol string on every access
+    group.bench_function("symbol_lookup", |b| {
+        b.iter(|| {
+            for symbol in &symbols {
+                let id = manager.contract_id(symbol).unwrap();
+                let _quote = manager.best_quote(id).unwrap();
+            }
+        });
+    });
+
+    // Hot path: keep the interned id
+    group.bench_function("contract_id_lookup", |b| {
+        b.iter(|| {
+            for id in &ids {
+                let _quote = manager.best_quote(*id).unwrap();
+            }
+        });
+    });
+
+    group.finish();
+}
+
+/// Benchmarks for booking inventory trades by symbol versus by `ContractId`.
+pub fn inventory_booking(c: &mut Criterion) {
+    let mut group = c.benchmark_group("inventory_booking");
+
+    let (manager, _exp, _strikes) = setup_manager(100);
+    let books = manager.registry().books();
+    let inventory =
+        InventoryManager::with_registry("BTC", PositionLimits::default(), manager.registry_arc())
+            .unwrap();
+    let ids: Vec<ContractId> = books.iter().filter_map(|b| b.contract_id()).collect();
+    let symbols: Vec<String> = books.iter().map(|b| b.symbol().to_string()).collect();
+
+    group.throughput(Throughput::Elements(ids.len() as u64));
+
+    // Edge path: resolve the symbol on every trade
+    group.bench_function("record_trade_by_symbol", |b| {
+        b.iter(|| {
+            for symbol in &symbols {
+                inventory.record_trade(symbol, dec!(1), dec!(10)).unwrap();
+                inventory.record_trade(symbol, dec!(-1), dec!(10)).unwrap();
+            }
+        });
+    });
+
+    // Hot path: book by interned id
+    group.bench_function("record_trade_by_id", |b| {
+        b.iter(|| {
+            for id in &ids {
+                inventory
+                    .record_trade_by_id(*id, dec!(1), dec!(10))
+                    .unwrap();
+                inventory
+                    .record_trade_by_id(*id, dec!(-1), dec!(10))
+                    .unwrap();
+            }
+        });
+    });
+
+    group.finish();
+}
+
+/// Benchmarks for interning symbols at different registry sizes.
+pub fn registry_scaling(c: &mut Criterion) {
+    let mut group = c.benchmark_group("registry_scaling");
+
+    for size in [100u32, 1000, 10000] {
+        let registry = ContractRegistry::new();
+        let symbols: Vec<String> = (0..size)
+            .map(|i| format!("BTC-20240329-{}-C", 10000 + i))
+            .collect();
+        for symbol in &symbols {
+            registry.intern(symbol);
+        }
+
+        group.throughput(Throughput::Elements(1));
+        group.bench_with_input(BenchmarkId::new("intern_existing", size), &size, |b, _| {
+            let mut i = 0usize;
+            b.iter(|| {
+                let id = registry.intern(&symbols[i % symbols.len()]);
+                i += 1;
+                id
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("symbol_of", size), &size, |b, _| {
+            let mut i = 0u32;
+            b.iter(|| {
+                let symbol = registry.symbol_of(ContractId::new(i % size));
+                i += 1;
+                symbol
+            });
+        });
+    }
+
+    group.finish();
+}
@@ -0,0 +1,111 @@
+//! Example: Shared Memory - Cross-Process Hot Data
+//!
+//! This example demonstrates sharing per-contract hot data (mark, theo,
+//! Greeks and best quote) between processes. The parent process acts as the
+//! pricer: it lists a chain, publishes snapshots into a shared memory region
+//! indexed by `ContractId`, then spawns itself as a reader process that looks
+//! up contracts by symbol and reads their snapshots.
+//!
+//! Run with: `cargo run --example 07_shared_memory --features shm`
+
+use option_chain_orderbook::orderbook::UnderlyingOrderBookManager;
+use option_chain_orderbook::shm::{ContractSnapshot, ShmReader, ShmWriter};
+use optionstratlib::ExpirationDate;
+use optionstratlib::prelude::pos_or_panic;
+use orderbook_rs::{OrderId, Side};
+use std::process::Command;
+use tracing::info;
+
+const STRIKES: [u64; 3] = [45000, 50000, 55000];
+
+/// Lists the same chain in both processes so contract ids line up.
+fn list_chain() -> UnderlyingOrderBookManager {
+    let manager = UnderlyingOrderBookManager::new();
+    let expiration = manager
+        .get_or_create("BTC")
+        .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(30.0)));
+    for strike in STRIKES {
+        drop(expiration.get_or_create_strike(strike));
+    }
+    manager
+}
+
+fn run_writer(path: &std::path::Path) {
+    let manager = list_chain();
+    let mut writer = ShmWriter::create(path, manager.registry().len()).unwrap();
+    info!("Pricer: created region with {} slots", writer.capacity());
+
+    for book in manager.registry().books() {
+        let id = book.contract_id().unwrap();
+        book.add_limit_order(OrderId::new(), Side::Buy, 1000 + u128::from(id.as_u32()), 5)
+            .unwrap();
+        book.add_limit_order(
+            OrderId::new(),
+            Side::Sell,
+            1010 + u128::from(id.as_u32()),
+            5,
+        )
+        .unwrap();
+
+        let snapshot = ContractSnapshot {
+            timestamp_ms: orderbook_rs::current_time_millis(),
+            mark: 1005.0 + f64::from(id.as_u32()),
+            theo: 1004.5 + f64::from(id.as_u32()),
+            delta: 0.5,
+            gamma: 0.0001,
+            theta: -12.0,
+            vega: 35.0,
+            rho: 2.0,
+            ..ContractSnapshot::default()
+        }
+        .with_quote(&book.best_quote());
+        writer.write(id, &snapshot).unwrap();
+        info!("Pricer: published {} as {}", book.symbol(), id);
+    }
+}
+
+fn run_reader(path: &std::path::Path) {
+    let manager = list_chain();
+    let reader = ShmReader::open(path).unwrap();
+    info!("Reader (pid {}): opened region", std::process::id());
+
+    for book in manager.registry().books() {
+        let id = manager.contract_id(book.symbol()).unwrap();
+        match reader.read(id).unwrap() {
+            Some(snapshot) => info!(
+                "Reader: {} mark={} theo={} delta={} bid={:?} ask={:?}",
+                book.symbol(),
+                snapshot.mark,
+                snapshot.theo,
+                snapshot.delta,
+                snapshot.bid_price,
+                snapshot.ask_price
+            ),
+            None => info!("Reader: {} not published", book.symbol()),
+        }
+    }
+}
+
+fn main() {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+
+    let path = std::env::temp_dir().join("option_chain_orderbook_example.shm");
+
+    if std::env::args().nth(1).as_deref() == Some("reader") {
+        run_reader(&path);
+        return;
+    }
+
+    info!("=== Shared Memory Example ===\n");
+    run_writer(&path);
+
+    let status = Command::new(std::env::current_exe().unwrap())
+        .arg("reader")
+        .status()
+        .unwrap();
+    info!("Reader process exited with {}", status);
+
+    std::fs::remove_file(&path).unwrap();
+}
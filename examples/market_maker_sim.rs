@@ -0,0 +1,501 @@
+//! Example: Market Maker Simulation - Full Simulated Session
+//!
+//! This example wires every subsystem together for a multi-hour simulated
+//! market making session on one BTC expiry:
+//!
+//! - A seeded synthetic market: spot random walk and public option prints
+//! - The order book hierarchy holding our resting quotes
+//! - Avellaneda-Stoikov quoting skewed by inventory
+//! - A batch risk check that withholds quote sides near the limits
+//! - Queue-aware simulated fills booked into inventory
+//! - Delta hedging in a perpetual, with hedge costs attributed to round trips
+//! - A final report of activity, exposures and P&L explain
+//!
+//! The session is deterministic for a given seed, and ends with sanity
+//! checks that panic if the subsystems disagree. The integration tests run
+//! a shorter session through [`run_session`], so the checks also guard the
+//! subsystems together on every `cargo test`.
+//!
+//! Run with: `cargo run --example market_maker_sim`
+
+use option_chain_orderbook::backtest::{FillModel, FillModelConfig, PublicTrade};
+use option_chain_orderbook::hedging::{DeltaHedger, HedgeParams};
+use option_chain_orderbook::inventory::{InventoryManager, Position, PositionLimits};
+use option_chain_orderbook::orderbook::{OptionOrderBook, UnderlyingOrderBookManager};
+use option_chain_orderbook::pnl::{RoundTripFill, RoundTripTracker};
+use option_chain_orderbook::pricing::{Greeks, PricingParams};
+use option_chain_orderbook::quoting::{QuoteParams, SpreadCalculator};
+use option_chain_orderbook::risk::{ProjectedExposure, RiskBatch, RiskController, RiskLimits};
+use optionstratlib::prelude::pos_or_panic;
+use optionstratlib::{ExpirationDate, OptionStyle};
+use orderbook_rs::{OrderId, Side};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::info;
+
+/// Underlying symbol.
+const UNDERLYING: &str = "BTC";
+/// Hedge instrument symbol.
+const HEDGE: &str = "BTC-PERP";
+/// Length of the example's session in minutes.
+const SESSION_MINUTES: u64 = 4 * 60;
+/// Milliseconds per simulation step.
+const STEP_MS: u64 = 60_000;
+/// Quoted strikes.
+const STRIKES: [u64; 5] = [45_000, 47_500, 50_000, 52_500, 55_000];
+/// Quote size per side.
+const QUOTE_SIZE: u64 = 5;
+
+/// One quoted contract.
+struct Contract {
+    book: Arc<OptionOrderBook>,
+    strike: Decimal,
+    style: OptionStyle,
+}
+
+/// Seeded random source of the synthetic market (SplitMix64).
+struct Market {
+    state: u64,
+}
+
+impl Market {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in `[low, high]`.
+    fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.next_u64() % (high - low + 1)
+    }
+
+    /// Returns true with probability `numerator / denominator`.
+    fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.range(1, denominator) <= numerator
+    }
+
+    /// Returns a decimal in `[low, high]` with two decimal places.
+    fn decimal(&mut self, low: i64, high: i64) -> Decimal {
+        let cents = self.range(0, (high - low).unsigned_abs() * 100);
+        Decimal::from(low) + Decimal::new(cents as i64, 2)
+    }
+}
+
+/// Session counters for the final report.
+#[derive(Default)]
+struct Stats {
+    quotes_sent: u64,
+    sides_withheld: u64,
+    public_trades: u64,
+    fills: u64,
+    contracts_traded: u64,
+    rejected_fills: u64,
+    hedges: u64,
+    hedge_units: u64,
+    hedge_cost: Decimal,
+    breaches: usize,
+}
+
+fn main() {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+
+    info!("=== Market Maker Simulation ===\n");
+    run_session(SESSION_MINUTES);
+}
+
+/// Runs a session of `minutes` one-minute steps, logs its report and
+/// checks that the subsystems agree at the end.
+///
+/// # Panics
+///
+/// Panics if a sanity check fails.
+pub fn run_session(minutes: u64) {
+    // Market and stack configuration.
+    let tick = dec!(0.5);
+    let fee_per_contract = dec!(0.3);
+    let hedge_half_spread = dec!(2.5);
+    let implied_vol = dec!(0.6);
+    let start_days = dec!(30);
+    let mut spot = dec!(50000);
+    let mut market = Market::new(42);
+
+    let manager = UnderlyingOrderBookManager::new();
+    let expiry = manager
+        .get_or_create(UNDERLYING)
+        .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(30.0)));
+    let contracts: Vec<Contract> = STRIKES
+        .iter()
+        .flat_map(|&strike| {
+            let books = expiry.get_or_create_strike(strike);
+            [
+                (books.call_arc(), OptionStyle::Call),
+                (books.put_arc(), OptionStyle::Put),
+            ]
+            .map(|(book, style)| Contract {
+                book,
+                strike: Decimal::from(strike),
+                style,
+            })
+        })
+        .collect();
+
+    let calculator = SpreadCalculator::new(QUOTE_SIZE)
+        .with_spread_bounds(dec!(2), dec!(40))
+        .expect("valid spread bounds");
+    let inventory =
+        InventoryManager::new(UNDERLYING, PositionLimits::default()).expect("valid limits");
+    for contract in &contracts {
+        inventory.add_position(Position::new(contract.book.symbol()));
+    }
+    inventory.add_position(Position::new(HEDGE));
+    inventory
+        .set_greeks(
+            HEDGE,
+            Greeks::new(Decimal::ONE, dec!(0), dec!(0), dec!(0), dec!(0)),
+        )
+        .expect("hedge position exists");
+
+    let risk = RiskController::new(RiskLimits {
+        max_delta: dec!(25),
+        max_gamma: dec!(1),
+        max_vega: dec!(2500),
+        ..RiskLimits::default()
+    })
+    .expect("valid risk limits");
+    let hedger = DeltaHedger::new(HedgeParams::new(HEDGE, dec!(5))).expect("valid hedge params");
+    let mut fill_model = FillModel::new(FillModelConfig {
+        fill_probability: dec!(0.6),
+        queue_fraction: dec!(0.5),
+        seed: 7,
+    })
+    .expect("valid fill model");
+    let round_trips = RoundTripTracker::new();
+    let mut stats = Stats::default();
+
+    info!(
+        "Quoting {} contracts on {} for {} minutes\n",
+        contracts.len(),
+        UNDERLYING,
+        minutes
+    );
+
+    let mut resting: Vec<(Arc<OptionOrderBook>, OrderId, u64)> = Vec::new();
+    let mut theos: HashMap<String, Decimal> = HashMap::new();
+    for step in 0..minutes {
+        let now_ms = step * STEP_MS;
+        let days = start_days - Decimal::from(step) / dec!(1440);
+
+        // Market moves: roughly 60% annualized spot volatility per minute.
+        spot += spot * market.decimal(-1, 1) / dec!(900);
+
+        // Reprice every contract and refresh unit Greeks.
+        let mut unit_greeks: HashMap<String, Greeks> = HashMap::new();
+        for contract in &contracts {
+            let params =
+                PricingParams::new(spot, contract.strike, days, implied_vol, contract.style);
+            let symbol = contract.book.symbol().to_string();
+            let greeks = params.greeks().expect("greeks");
+            theos.insert(symbol.clone(), params.price().expect("price"));
+            inventory
+                .set_greeks(&symbol, greeks)
+                .expect("position exists");
+            unit_greeks.insert(symbol, greeks);
+        }
+        let marks = |symbol: &str| {
+            if symbol == HEDGE { spot } else { theos[symbol] }
+        };
+        let pnl_today: Decimal = inventory
+            .positions()
+            .iter()
+            .map(|p| p.total_pnl(marks(p.symbol())))
+            .sum();
+        let exposure = inventory.total_greeks();
+        stats.breaches += risk.check(&exposure, pnl_today).len();
+
+        // Pull the previous quotes.
+        for (book, order_id, quote_id) in resting.drain(..) {
+            let _ = book.cancel_order(order_id);
+            fill_model.cancel(quote_id);
+        }
+
+        // Check the whole outgoing batch, then quote what risk allows.
+        let batch = RiskBatch {
+            underlying: UNDERLYING.to_string(),
+            current: exposure,
+            pnl_today,
+            quotes: contracts
+                .iter()
+                .map(|c| {
+                    let greeks = unit_greeks[c.book.symbol()];
+                    let size = Decimal::from(QUOTE_SIZE);
+                    ProjectedExposure {
+                        symbol: c.book.symbol().to_string(),
+                        bid_fill: greeks.scaled(size),
+                        ask_fill: greeks.scaled(-size),
+                    }
+                })
+                .collect(),
+        };
+        let verdict = risk.check_batch(&batch);
+        stats.sides_withheld += verdict.withheld_sides() as u64;
+
+        for contract in &contracts {
+            let symbol = contract.book.symbol();
+            let Some(allowed) = verdict.verdict(symbol) else {
+                continue;
+            };
+            let greeks = unit_greeks[symbol];
+            let position = inventory
+                .position(symbol)
+                .map_or(Decimal::ZERO, |p| p.quantity());
+            // Price volatility of the contract, in price units per root year.
+            let price_vol = (greeks.delta * spot * implied_vol).abs().max(dec!(1));
+            let params = QuoteParams::new(theos[symbol], price_vol)
+                .with_inventory(position)
+                .with_risk_aversion(dec!(0.001))
+                .with_time_horizon(dec!(1) / dec!(525600));
+            let quote = calculator
+                .generate(&params)
+                .expect("valid quote params")
+                .round_to_tick(tick);
+
+            let sides = [
+                (Side::Buy, quote.bid_price, allowed.bid_allowed),
+                (Side::Sell, quote.ask_price, allowed.ask_allowed),
+            ];
+            for (side, price, allowed) in sides {
+                let Some(ticks) = to_ticks(price, tick).filter(|_| allowed) else {
+                    continue;
+                };
+                let quote_id =
+                    fill_model.place_in_book(&contract.book, side, ticks, QUOTE_SIZE, now_ms);
+                let order_id = OrderId::new();
+                contract
+                    .book
+                    .add_limit_order(order_id, side, ticks, QUOTE_SIZE)
+                    .expect("quote accepted");
+                resting.push((Arc::clone(&contract.book), order_id, quote_id));
+                stats.quotes_sent += 1;
+            }
+        }
+
+        // Public prints around theo; those through our quotes fill us.
+        for contract in &contracts {
+            if !market.chance(1, 3) {
+                continue;
+            }
+            let symbol = contract.book.symbol();
+            let aggressor = if market.chance(1, 2) {
+                Side::Buy
+            } else {
+                Side::Sell
+            };
+            let offset = market.decimal(0, 6);
+            let price = match aggressor {
+                Side::Buy => theos[symbol] + offset,
+                Side::Sell => (theos[symbol] - offset).max(tick),
+            };
+            let Some(ticks) = to_ticks(price, tick) else {
+                continue;
+            };
+            stats.public_trades += 1;
+            let trade = PublicTrade {
+                symbol: symbol.to_string(),
+                price: ticks,
+                quantity: market.range(1, 10),
+                aggressor,
+                timestamp_ms: now_ms + STEP_MS / 2,
+            };
+            for fill in fill_model.on_trade(&trade) {
+                let quantity = Decimal::from(fill.quantity);
+                let price = Decimal::from(fill.price) * tick;
+                let signed = match fill.side {
+                    Side::Buy => quantity,
+                    Side::Sell => -quantity,
+                };
+                if inventory.record_trade(symbol, signed, price).is_err() {
+                    stats.rejected_fills += 1;
+                    continue;
+                }
+                round_trips.record_fill(&RoundTripFill {
+                    symbol: symbol.to_string(),
+                    bucket: match contract.style {
+                        OptionStyle::Call => "calls".to_string(),
+                        OptionStyle::Put => "puts".to_string(),
+                    },
+                    side: fill.side,
+                    quantity,
+                    price,
+                    theo: theos[symbol],
+                    fee: quantity * fee_per_contract,
+                    timestamp_ms: fill.timestamp_ms,
+                });
+                stats.fills += 1;
+                stats.contracts_traded += fill.quantity;
+            }
+        }
+
+        // Hedge the residual delta in the perpetual, crossing its spread.
+        let delta = inventory.total_greeks().delta;
+        if let Some(order) = hedger.hedge(delta, now_ms + STEP_MS - 1) {
+            let (signed, price) = match order.side {
+                Side::Buy => (Decimal::from(order.quantity), spot + hedge_half_spread),
+                Side::Sell => (-Decimal::from(order.quantity), spot - hedge_half_spread),
+            };
+            inventory
+                .record_trade(HEDGE, signed, price)
+                .expect("hedge within limits");
+            hedger.hedge_completed();
+            let cost = Decimal::from(order.quantity) * hedge_half_spread;
+            attribute_hedge_cost(&round_trips, &inventory, cost);
+            stats.hedges += 1;
+            stats.hedge_units += order.quantity;
+            stats.hedge_cost += cost;
+        }
+
+        if (step + 1).is_multiple_of(60) {
+            info!(
+                "[{:>3} min] spot {:.2} | delta {:.2} | fills {} | hedges {}",
+                step + 1,
+                spot,
+                inventory.total_greeks().delta,
+                stats.fills,
+                stats.hedges
+            );
+        }
+    }
+
+    report(&inventory, &round_trips, &risk, &stats, spot, &theos);
+
+    // Sanity checks: the stack stayed consistent end to end.
+    let book_orders: usize = contracts.iter().map(|c| c.book.order_count()).sum();
+    assert_eq!(book_orders, resting.len(), "book holds exactly our quotes");
+    assert!(stats.fills > 0, "the session produced fills");
+    assert!(
+        inventory.total_greeks().delta.abs() <= risk.limits().max_delta,
+        "delta hedged within limits"
+    );
+    for contract in &contracts {
+        let symbol = contract.book.symbol();
+        let position = inventory
+            .position(symbol)
+            .map_or(Decimal::ZERO, |p| p.quantity());
+        assert_eq!(
+            position,
+            round_trips.open_quantity(symbol),
+            "round trips match inventory for {symbol}"
+        );
+    }
+}
+
+/// Converts a price to book ticks, rejecting non-positive prices.
+fn to_ticks(price: Decimal, tick: Decimal) -> Option<u128> {
+    (price > Decimal::ZERO)
+        .then(|| (price / tick).round().to_u128())
+        .flatten()
+}
+
+/// Spreads a hedge cost over open option positions by absolute delta.
+fn attribute_hedge_cost(tracker: &RoundTripTracker, inventory: &InventoryManager, cost: Decimal) {
+    let deltas: Vec<(String, Decimal)> = inventory
+        .positions()
+        .iter()
+        .filter(|p| p.symbol() != HEDGE && !p.is_flat())
+        .map(|p| (p.symbol().to_string(), p.greeks().delta.abs()))
+        .collect();
+    let total: Decimal = deltas.iter().map(|(_, d)| *d).sum();
+    if total.is_zero() {
+        return;
+    }
+    for (symbol, delta) in deltas {
+        tracker.record_hedge_cost(&symbol, cost * delta / total);
+    }
+}
+
+/// Logs the end-of-session report.
+fn report(
+    inventory: &InventoryManager,
+    round_trips: &RoundTripTracker,
+    risk: &RiskController,
+    stats: &Stats,
+    spot: Decimal,
+    theos: &HashMap<String, Decimal>,
+) {
+    info!("\n=== Session Report ===\n");
+    info!("--- Activity ---");
+    info!("Quotes sent: {}", stats.quotes_sent);
+    info!("Sides withheld by risk: {}", stats.sides_withheld);
+    info!("Public trades: {}", stats.public_trades);
+    info!(
+        "Fills: {} ({} contracts, {} rejected by limits)",
+        stats.fills, stats.contracts_traded, stats.rejected_fills
+    );
+    info!(
+        "Hedges: {} ({} units, cost {:.2})",
+        stats.hedges, stats.hedge_units, stats.hedge_cost
+    );
+
+    info!("\n--- Risk ---");
+    info!("Final Greeks: {}", inventory.total_greeks());
+    info!("Limit breaches: {}", stats.breaches);
+    info!("Trading state: {:?}", risk.state());
+
+    info!("\n--- Positions ---");
+    let mut realized = Decimal::ZERO;
+    let mut unrealized = Decimal::ZERO;
+    for position in inventory.positions() {
+        let mark = if position.symbol() == HEDGE {
+            spot
+        } else {
+            theos[position.symbol()]
+        };
+        realized += position.realized_pnl();
+        unrealized += position.unrealized_pnl(mark);
+        if !position.is_flat() {
+            info!(
+                "{:<24} qty {:>6} | avg {:>10.2} | mark {:>10.2}",
+                position.symbol(),
+                position.quantity(),
+                position.average_price(),
+                mark
+            );
+        }
+    }
+
+    info!("\n--- P&L ---");
+    info!("Realized: {:.2}", realized);
+    info!("Unrealized: {:.2}", unrealized);
+    let total = round_trips.total();
+    info!(
+        "Round trips: {} ({} contracts)",
+        total.round_trips, total.quantity
+    );
+    info!(
+        "  spread capture {:.2} entry + {:.2} exit",
+        total.entry_spread_capture, total.exit_spread_capture
+    );
+    info!("  theo drift {:.2}", total.theo_drift);
+    info!("  hedge cost {:.2}", total.hedge_cost);
+    info!("  fees {:.2}", total.fees);
+    info!("  net {:.2}", total.net_pnl);
+    for (bucket, summary) in round_trips.by_bucket() {
+        info!(
+            "  {:<5} {} trips, net {:.2}",
+            bucket, summary.round_trips, summary.net_pnl
+        );
+    }
+
+    info!("\n=== Simulation Complete ===");
+}
@@ -0,0 +1,339 @@
+//! Account context module.
+//!
+//! This module provides the [`AccountContext`], which bundles the state
+//! owned by one trading account, and [`AccountPnL`], which values the
+//! account's inventory against marks for its daily P&L, honoring any
+//! mark-to-model overrides.
+
+use crate::error::Result;
+use crate::inventory::{InventoryManager, PositionLimits};
+use crate::orderbook::QueuePositionTracker;
+use crate::pnl::MarkOverrideRegistry;
+use crate::pricing::Greeks;
+use crate::risk::{LimitBreach, PnLSource, RiskController, RiskLimits, TradingState};
+use crossbeam_skiplist::SkipMap;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+
+/// Daily P&L of one account.
+///
+/// Positions without a mark are valued at their average price, i.e. with
+/// no unrealized P&L. With a [`MarkOverrideRegistry`] attached, overridden
+/// contracts are marked from the override's source instead of the market.
+pub struct AccountPnL {
+    /// Inventory valued.
+    inventory: Arc<InventoryManager>,
+    /// Market mark prices by symbol.
+    marks: SkipMap<String, Decimal>,
+    /// Model mark prices by symbol.
+    model_marks: SkipMap<String, Decimal>,
+    /// Mark overrides, if attached.
+    overrides: RwLock<Option<Arc<MarkOverrideRegistry>>>,
+    /// Total P&L at the start of the trading day.
+    day_start: Mutex<Decimal>,
+}
+
+impl AccountPnL {
+    /// Creates the P&L of an inventory, starting the day at zero.
+    #[must_use]
+    pub fn new(inventory: Arc<InventoryManager>) -> Self {
+        Self {
+            inventory,
+            marks: SkipMap::new(),
+            model_marks: SkipMap::new(),
+            overrides: RwLock::new(None),
+            day_start: Mutex::new(Decimal::ZERO),
+        }
+    }
+
+    /// Sets the market mark price of a symbol.
+    pub fn mark(&self, symbol: impl Into<String>, price: Decimal) {
+        self.marks.insert(symbol.into(), price);
+    }
+
+    /// Sets the model mark price of a symbol.
+    pub fn mark_model(&self, symbol: impl Into<String>, price: Decimal) {
+        self.model_marks.insert(symbol.into(), price);
+    }
+
+    /// Attaches or detaches the mark override registry.
+    pub fn set_overrides(&self, overrides: Option<Arc<MarkOverrideRegistry>>) {
+        *self
+            .overrides
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = overrides;
+    }
+
+    /// Returns the mark price of a symbol, honoring any override.
+    #[must_use]
+    pub fn mark_of(&self, symbol: &str) -> Option<Decimal> {
+        let market = self.marks.get(symbol).map(|e| *e.value());
+        let overrides = self
+            .overrides
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        match overrides.as_ref() {
+            Some(registry) => {
+                let model = self.model_marks.get(symbol).map(|e| *e.value());
+                registry.resolve(symbol, market, model).map(|m| m.price)
+            }
+            None => market,
+        }
+    }
+
+    /// Returns the realized P&L since inception.
+    #[must_use]
+    pub fn realized(&self) -> Decimal {
+        let mut realized = Decimal::ZERO;
+        self.inventory
+            .for_each_position(|p| realized += p.realized_pnl());
+        realized
+    }
+
+    /// Returns the unrealized P&L at the current marks.
+    #[must_use]
+    pub fn unrealized(&self) -> Decimal {
+        let mut unrealized = Decimal::ZERO;
+        self.inventory.for_each_position(|p| {
+            if let Some(mark) = self.mark_of(p.symbol()) {
+                unrealized += p.unrealized_pnl(mark);
+            }
+        });
+        unrealized
+    }
+
+    /// Returns realized plus unrealized P&L since inception.
+    #[must_use]
+    pub fn total(&self) -> Decimal {
+        self.realized() + self.unrealized()
+    }
+
+    /// Starts a new trading day at the current total P&L.
+    pub fn start_day(&self) {
+        let total = self.total();
+        *self.lock() = total;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Decimal> {
+        self.day_start
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl PnLSource for AccountPnL {
+    fn pnl_today(&self) -> Decimal {
+        self.total() - *self.lock()
+    }
+}
+
+/// Isolated state of one trading account.
+///
+/// The risk controller pulls its Greeks and P&L from this account's
+/// inventory only, so limits and breaches never mix accounts.
+pub struct AccountContext {
+    /// Account identifier.
+    id: String,
+    /// Positions and position limits.
+    inventory: Arc<InventoryManager>,
+    /// Daily P&L.
+    pnl: Arc<AccountPnL>,
+    /// Greek and loss limits, breaches and trading state.
+    risk: RiskController,
+    /// Queue positions of the account's resting orders.
+    orders: QueuePositionTracker,
+}
+
+impl AccountContext {
+    /// Creates an account with empty inventory.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Account identifier
+    /// * `underlying` - Underlying symbol traded
+    /// * `position_limits` - Position limits
+    /// * `risk_limits` - Greek and loss limits
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if either set of limits is
+    /// invalid.
+    pub fn new(
+        id: impl Into<String>,
+        underlying: impl Into<String>,
+        position_limits: PositionLimits,
+        risk_limits: RiskLimits,
+    ) -> Result<Self> {
+        let inventory = Arc::new(InventoryManager::new(underlying, position_limits)?);
+        let pnl = Arc::new(AccountPnL::new(Arc::clone(&inventory)));
+        let risk = RiskController::new(risk_limits)?
+            .with_greeks_source(Arc::clone(&inventory) as _)
+            .with_pnl_source(Arc::clone(&pnl) as _);
+        Ok(Self {
+            id: id.into(),
+            inventory,
+            pnl,
+            risk,
+            orders: QueuePositionTracker::new(),
+        })
+    }
+
+    /// Returns the account identifier.
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the account's inventory.
+    #[must_use]
+    pub fn inventory(&self) -> &InventoryManager {
+        &self.inventory
+    }
+
+    /// Returns the account's P&L.
+    #[must_use]
+    pub fn pnl(&self) -> &AccountPnL {
+        &self.pnl
+    }
+
+    /// Returns the account's risk controller.
+    #[must_use]
+    pub const fn risk(&self) -> &RiskController {
+        &self.risk
+    }
+
+    /// Returns the account's own-order tracker.
+    #[must_use]
+    pub const fn orders(&self) -> &QueuePositionTracker {
+        &self.orders
+    }
+
+    /// Checks the account's Greeks and daily P&L against its limits,
+    /// recording any breaches.
+    pub fn check_limits(&self) -> Vec<LimitBreach> {
+        self.risk
+            .check(&self.inventory.total_greeks(), self.pnl.pnl_today())
+    }
+
+    /// Returns a summary of the account.
+    #[must_use]
+    pub fn summary(&self) -> AccountSummary {
+        AccountSummary {
+            account: self.id.clone(),
+            state: self.risk.state(),
+            greeks: self.inventory.total_greeks(),
+            realized_pnl: self.pnl.realized(),
+            unrealized_pnl: self.pnl.unrealized(),
+            pnl_today: self.pnl.pnl_today(),
+            positions: self.inventory.len(),
+            open_orders: self.orders.len(),
+        }
+    }
+}
+
+/// Point-in-time summary of one account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountSummary {
+    /// Account identifier.
+    pub account: String,
+    /// Trading state granted by the account's risk controller.
+    pub state: TradingState,
+    /// Aggregate Greeks.
+    pub greeks: Greeks,
+    /// Realized P&L since inception.
+    pub realized_pnl: Decimal,
+    /// Unrealized P&L at the current marks.
+    pub unrealized_pnl: Decimal,
+    /// P&L of the current trading day.
+    pub pnl_today: Decimal,
+    /// Number of positions.
+    pub positions: usize,
+    /// Number of tracked resting orders.
+    pub open_orders: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::pnl::MarkSource;
+    use crate::risk::LimitKind;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_pnl_today_from_marks() {
+        let account = AccountContext::new(
+            "prop",
+            "BTC",
+            PositionLimits::default(),
+            RiskLimits::default(),
+        )
+        .unwrap();
+        account
+            .inventory()
+            .record_trade("C1", dec!(10), dec!(5))
+            .unwrap();
+        account.pnl().mark("C1", dec!(6));
+        assert_eq!(account.pnl().pnl_today(), dec!(10));
+
+        account.pnl().start_day();
+        account.pnl().mark("C1", dec!(5.5));
+        assert_eq!(account.pnl().pnl_today(), dec!(-5));
+        assert_eq!(account.summary().unrealized_pnl, dec!(5));
+    }
+
+    #[test]
+    fn test_mark_override_reprices_pnl() {
+        let account = AccountContext::new(
+            "prop",
+            "BTC",
+            PositionLimits::default(),
+            RiskLimits::default(),
+        )
+        .unwrap();
+        account
+            .inventory()
+            .record_trade("C1", dec!(10), dec!(5))
+            .unwrap();
+        account.pnl().mark("C1", dec!(9));
+        account.pnl().mark_model("C1", dec!(6));
+
+        let clock = Arc::new(ManualClock::new(0));
+        let overrides = Arc::new(MarkOverrideRegistry::new(Arc::clone(&clock) as _));
+        account.pnl().set_overrides(Some(Arc::clone(&overrides)));
+        overrides
+            .set("C1", MarkSource::Model, 1_000, "one-sided market", "risk")
+            .unwrap();
+        assert_eq!(account.pnl().unrealized(), dec!(10));
+
+        clock.advance(1_000);
+        assert_eq!(account.pnl().unrealized(), dec!(40));
+    }
+
+    #[test]
+    fn test_limits_use_account_greeks() {
+        let account = AccountContext::new(
+            "client",
+            "BTC",
+            PositionLimits::default(),
+            RiskLimits::default(),
+        )
+        .unwrap();
+        account
+            .inventory()
+            .record_trade("C1", dec!(100), dec!(5))
+            .unwrap();
+        account
+            .inventory()
+            .set_greeks(
+                "C1",
+                Greeks::new(dec!(2), dec!(0), dec!(0), dec!(0), dec!(0)),
+            )
+            .unwrap();
+
+        let breaches = account.check_limits();
+        assert!(breaches.iter().any(|b| b.kind == LimitKind::Delta));
+        assert_eq!(account.risk().dashboard().greeks.delta, dec!(200));
+    }
+}
@@ -0,0 +1,18 @@
+//! Account module.
+//!
+//! This module provides multi-account support within one process. Each
+//! trading account (prop, client-facing, ...) gets an [`AccountContext`]
+//! with its own inventory, P&L, risk limits and own-order tracker, so books
+//! stay isolated while sharing the order book hierarchy and quote engine.
+//!
+//! ## Components
+//!
+//! - [`AccountContext`]: Isolated inventory, P&L, risk controller and own orders of one account
+//! - [`AccountPnL`]: Daily P&L of an account's inventory against marks
+//! - [`AccountRegistry`]: Accounts by id, with per-account summaries and a [`ConsolidatedView`]
+
+mod context;
+mod registry;
+
+pub use context::{AccountContext, AccountPnL, AccountSummary};
+pub use registry::{AccountRegistry, ConsolidatedView};
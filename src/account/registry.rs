@@ -0,0 +1,221 @@
+//! Account registry module.
+//!
+//! This module provides the [`AccountRegistry`], which holds the
+//! [`AccountContext`] of every account served by the process and builds the
+//! [`ConsolidatedView`] across them.
+
+use super::context::{AccountContext, AccountSummary};
+use crate::error::{Error, Result};
+use crate::pricing::Greeks;
+use crossbeam_skiplist::SkipMap;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Aggregate of every account at one point in time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsolidatedView {
+    /// Per-account summaries sorted by account id.
+    pub accounts: Vec<AccountSummary>,
+    /// Greeks summed across accounts.
+    pub greeks: Greeks,
+    /// Realized P&L summed across accounts.
+    pub realized_pnl: Decimal,
+    /// Unrealized P&L summed across accounts.
+    pub unrealized_pnl: Decimal,
+    /// Daily P&L summed across accounts.
+    pub pnl_today: Decimal,
+    /// Net quantity per symbol across accounts, flat symbols omitted.
+    pub net_positions: BTreeMap<String, Decimal>,
+    /// Tracked resting orders across accounts.
+    pub open_orders: usize,
+}
+
+/// Accounts served by one engine instance.
+///
+/// Uses `SkipMap` for thread-safe concurrent access across accounts.
+#[derive(Default)]
+pub struct AccountRegistry {
+    /// Accounts indexed by id.
+    accounts: SkipMap<String, Arc<AccountContext>>,
+}
+
+impl AccountRegistry {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers an account.
+    ///
+    /// Returns the shared context.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if an account with the same id is
+    /// already registered.
+    pub fn register(&self, account: AccountContext) -> Result<Arc<AccountContext>> {
+        if self.accounts.contains_key(account.id()) {
+            return Err(Error::validation(format!(
+                "account {} is already registered",
+                account.id()
+            )));
+        }
+        let account = Arc::new(account);
+        self.accounts
+            .insert(account.id().to_string(), Arc::clone(&account));
+        Ok(account)
+    }
+
+    /// Removes an account.
+    pub fn remove(&self, id: &str) -> Option<Arc<AccountContext>> {
+        self.accounts.remove(id).map(|e| Arc::clone(e.value()))
+    }
+
+    /// Returns an account.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the account is unknown.
+    pub fn get(&self, id: &str) -> Result<Arc<AccountContext>> {
+        self.accounts
+            .get(id)
+            .map(|e| Arc::clone(e.value()))
+            .ok_or_else(|| Error::validation(format!("unknown account {id}")))
+    }
+
+    /// Returns the account ids in order.
+    #[must_use]
+    pub fn ids(&self) -> Vec<String> {
+        self.accounts.iter().map(|e| e.key().clone()).collect()
+    }
+
+    /// Returns the number of accounts.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    /// Returns true if no account is registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Calls `f` on every account in id order.
+    pub fn for_each_account<F: FnMut(&AccountContext)>(&self, mut f: F) {
+        for entry in &self.accounts {
+            f(entry.value());
+        }
+    }
+
+    /// Returns the summary of every account in id order.
+    #[must_use]
+    pub fn summaries(&self) -> Vec<AccountSummary> {
+        self.accounts.iter().map(|e| e.value().summary()).collect()
+    }
+
+    /// Returns the aggregate of every account.
+    #[must_use]
+    pub fn consolidated(&self) -> ConsolidatedView {
+        let mut view = ConsolidatedView {
+            accounts: Vec::with_capacity(self.accounts.len()),
+            greeks: Greeks::zero(),
+            realized_pnl: Decimal::ZERO,
+            unrealized_pnl: Decimal::ZERO,
+            pnl_today: Decimal::ZERO,
+            net_positions: BTreeMap::new(),
+            open_orders: 0,
+        };
+        for entry in &self.accounts {
+            let account = entry.value();
+            let summary = account.summary();
+            view.greeks += summary.greeks;
+            view.realized_pnl += summary.realized_pnl;
+            view.unrealized_pnl += summary.unrealized_pnl;
+            view.pnl_today += summary.pnl_today;
+            view.open_orders += summary.open_orders;
+            account.inventory().for_each_position(|p| {
+                *view
+                    .net_positions
+                    .entry(p.symbol().to_string())
+                    .or_default() += p.quantity();
+            });
+            view.accounts.push(summary);
+        }
+        view.net_positions.retain(|_, q| !q.is_zero());
+        view
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::PositionLimits;
+    use crate::risk::RiskLimits;
+    use rust_decimal_macros::dec;
+
+    fn account(id: &str) -> AccountContext {
+        AccountContext::new(id, "BTC", PositionLimits::default(), RiskLimits::default()).unwrap()
+    }
+
+    #[test]
+    fn test_accounts_are_isolated() {
+        let registry = AccountRegistry::new();
+        let prop = registry.register(account("prop")).unwrap();
+        let client = registry.register(account("client")).unwrap();
+        assert!(registry.register(account("prop")).is_err());
+
+        prop.inventory()
+            .record_trade("C1", dec!(10), dec!(5))
+            .unwrap();
+        client
+            .inventory()
+            .record_trade("C1", dec!(-4), dec!(6))
+            .unwrap();
+        client
+            .inventory()
+            .record_trade("P1", dec!(2), dec!(3))
+            .unwrap();
+
+        assert_eq!(prop.inventory().len(), 1);
+        assert_eq!(
+            registry
+                .get("client")
+                .unwrap()
+                .inventory()
+                .position("C1")
+                .unwrap()
+                .quantity(),
+            dec!(-4)
+        );
+        assert!(registry.get("other").is_err());
+        assert_eq!(registry.ids(), vec!["client", "prop"]);
+    }
+
+    #[test]
+    fn test_consolidated_view() {
+        let registry = AccountRegistry::new();
+        let prop = registry.register(account("prop")).unwrap();
+        let client = registry.register(account("client")).unwrap();
+        prop.inventory()
+            .record_trade("C1", dec!(10), dec!(5))
+            .unwrap();
+        client
+            .inventory()
+            .record_trade("C1", dec!(-10), dec!(6))
+            .unwrap();
+        prop.pnl().mark("C1", dec!(5.5));
+        client.pnl().mark("C1", dec!(5.5));
+
+        let view = registry.consolidated();
+        assert_eq!(view.accounts.len(), 2);
+        assert!(view.net_positions.is_empty());
+        assert_eq!(view.unrealized_pnl, dec!(10));
+        assert_eq!(view.accounts[1].unrealized_pnl, dec!(5));
+        assert!(registry.remove("prop").is_some());
+        assert_eq!(registry.len(), 1);
+    }
+}
@@ -0,0 +1,563 @@
+//! Fill routing module.
+//!
+//! This module provides the [`Fill`] event, one execution of one of our
+//! orders, and the [`FillRouter`], which captures executions from order
+//! books, books them into inventory, accumulates realized P&L per contract
+//! and re-checks risk limits after every fill.
+//!
+//! ## Ownership
+//!
+//! A book execution pairs a resting (maker) order with an aggressive
+//! (taker) order. Only the sides belonging to orders registered with
+//! [`FillRouter::track`] are ours. An aggressive order executes inside
+//! `submit`, before its id is known to the caller, so executions of
+//! untracked takers are held and booked when the order is tracked.
+//!
+//! Orders tracked with [`FillRouter::track_for`] carry a strategy tag; their
+//! fills are booked under it so positions and P&L can be reported per
+//! strategy as well as in aggregate. An order is forgotten once its
+//! remaining quantity reaches zero.
+//!
+//! ## Limits
+//!
+//! An execution has already happened, so it is booked with
+//! [`InventoryManager::record_execution`], which does not refuse a trade
+//! that breaches a position limit. The breach is reported to the
+//! [`RiskController`] instead, as a [`LimitKind::Position`] breach, which
+//! trips the kill switch if it is configured as a hard limit.
+
+use crate::error::{Error, Result};
+use crate::inventory::InventoryManager;
+use crate::orderbook::{OptionOrderBook, UnderlyingOrderBook};
+use crate::risk::{LimitBreach, LimitKind, RiskController};
+use orderbook_rs::{OrderId, Side, TradeListener, TradeResult};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Most executions held for orders that are not tracked yet.
+const MAX_PENDING_FILLS: usize = 1024;
+
+/// Most booked fills kept for [`FillRouter::fills`].
+const MAX_BOOKED_FILLS: usize = 4096;
+
+/// One execution of one of our orders.
+///
+/// Prices and quantities are in smallest units, matching `OptionOrderBook`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fill {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Our order.
+    pub order_id: OrderId,
+    /// Our side.
+    pub side: Side,
+    /// Execution price in smallest units.
+    pub price: u128,
+    /// Executed quantity.
+    pub quantity: u64,
+    /// Whether our order was resting in the book.
+    pub is_maker: bool,
+    /// Execution time in milliseconds.
+    pub timestamp_ms: u64,
+    /// Strategy tag of our order, if any.
+    pub strategy: Option<String>,
+}
+
+impl Fill {
+    /// Returns the quantity signed by side: positive when we bought.
+    #[must_use]
+    pub fn signed_quantity(&self) -> Decimal {
+        let quantity = Decimal::from(self.quantity);
+        match self.side {
+            Side::Buy => quantity,
+            Side::Sell => -quantity,
+        }
+    }
+
+    /// Splits a book execution into the maker and taker fills it contains.
+    #[must_use]
+    pub fn from_trade(trade: &TradeResult) -> Vec<Self> {
+        let mut fills = Vec::new();
+        for tx in trade.match_result.transactions.as_vec() {
+            fills.push(Self {
+                symbol: trade.symbol.clone(),
+                order_id: tx.maker_order_id,
+                side: tx.taker_side.opposite(),
+                price: tx.price,
+                quantity: tx.quantity,
+                is_maker: true,
+                timestamp_ms: tx.timestamp,
+                strategy: None,
+            });
+            fills.push(Self {
+                symbol: trade.symbol.clone(),
+                order_id: tx.taker_order_id,
+                side: tx.taker_side,
+                price: tx.price,
+                quantity: tx.quantity,
+                is_maker: false,
+                timestamp_ms: tx.timestamp,
+                strategy: None,
+            });
+        }
+        fills
+    }
+}
+
+/// Counters of a [`FillRouter`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FillStats {
+    /// Fills booked into inventory.
+    pub booked: u64,
+    /// Fills inventory refused, for example for a negative price.
+    pub rejected: u64,
+    /// Fills held for orders not tracked yet.
+    pub pending: u64,
+    /// Position limit breaches of booked fills and limit breaches found by
+    /// the post-fill risk checks.
+    pub breaches: u64,
+}
+
+/// Mutable state of a [`FillRouter`].
+#[derive(Debug, Default)]
+struct FillState {
+    /// Our live orders, with their strategy tags.
+    own_orders: HashMap<OrderId, Option<String>>,
+    /// Taker fills of orders not tracked yet, oldest first, each with
+    /// whether it completed its order.
+    pending: VecDeque<(Fill, bool)>,
+    /// Most recent booked fills, in booking order.
+    fills: VecDeque<Fill>,
+    /// Realized P&L per contract.
+    realized: BTreeMap<String, Decimal>,
+    /// Counters.
+    stats: FillStats,
+    /// Last inventory error or position limit breach, for diagnostics.
+    last_error: Option<String>,
+}
+
+/// Routes executions of our orders to inventory, P&L and risk.
+///
+/// Attach the router to books with [`FillRouter::attach`]; books listed
+/// afterwards must be attached as well.
+pub struct FillRouter {
+    /// Positions fills are booked into.
+    inventory: Arc<InventoryManager>,
+    /// Controller notified after every booked fill.
+    risk: Option<Arc<RiskController>>,
+    /// Book price units per inventory price unit.
+    price_scale: Decimal,
+    /// Mutable state.
+    state: Mutex<FillState>,
+}
+
+impl FillRouter {
+    /// Creates a fill router booking into an inventory.
+    #[must_use]
+    pub fn new(inventory: Arc<InventoryManager>) -> Self {
+        Self {
+            inventory,
+            risk: None,
+            price_scale: Decimal::ONE,
+            state: Mutex::new(FillState::default()),
+        }
+    }
+
+    /// Attaches the risk controller checked after every booked fill.
+    #[must_use]
+    pub fn with_risk(mut self, risk: Arc<RiskController>) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    /// Sets how many book price units make one inventory price unit
+    /// (e.g. 100 for books priced in cents).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the scale is not positive.
+    pub fn with_price_scale(mut self, price_scale: Decimal) -> Result<Self> {
+        if price_scale <= Decimal::ZERO {
+            return Err(Error::configuration("fill price scale must be positive"));
+        }
+        self.price_scale = price_scale;
+        Ok(self)
+    }
+
+    /// Returns the inventory fills are booked into.
+    #[must_use]
+    pub fn inventory(&self) -> &Arc<InventoryManager> {
+        &self.inventory
+    }
+
+    /// Captures the executions of a book, replacing its trade listener.
+    pub fn attach(self: &Arc<Self>, book: &OptionOrderBook) {
+        let router = Arc::clone(self);
+        let listener: TradeListener = Arc::new(move |trade: &TradeResult| {
+            router.on_trade(trade);
+        });
+        book.set_trade_listener(listener);
+    }
+
+    /// Captures the executions of every call and put book of an underlying.
+    ///
+    /// Returns the number of books attached.
+    pub fn attach_underlying(self: &Arc<Self>, underlying: &UnderlyingOrderBook) -> usize {
+        let mut attached = 0;
+        for expiration in underlying.expirations().iter() {
+            for strike in expiration.value().chain().strikes().iter() {
+                self.attach(strike.value().call());
+                self.attach(strike.value().put());
+                attached += 2;
+            }
+        }
+        attached
+    }
+
+    /// Registers one of our orders, booking any fills it already had.
+    ///
+    /// Returns the fills booked by this call.
+    pub fn track(&self, order_id: OrderId) -> Vec<Fill> {
+        self.track_tagged(order_id, None)
+    }
+
+    /// Registers one of our orders placed by a strategy, booking any fills
+    /// it already had under the strategy's tag.
+    ///
+    /// Returns the fills booked by this call.
+    pub fn track_for(&self, order_id: OrderId, strategy: impl Into<String>) -> Vec<Fill> {
+        self.track_tagged(order_id, Some(strategy.into()))
+    }
+
+    /// Registers an order with an optional strategy tag.
+    ///
+    /// An order its held fills already completed is not kept.
+    fn track_tagged(&self, order_id: OrderId, strategy: Option<String>) -> Vec<Fill> {
+        let mut state = self.lock();
+        let (ready, held): (Vec<_>, Vec<_>) = state
+            .pending
+            .drain(..)
+            .partition(|(f, _)| f.order_id == order_id);
+        state.pending = held.into();
+        if !ready.iter().any(|&(_, complete)| complete) {
+            state.own_orders.insert(order_id, strategy.clone());
+        }
+        ready
+            .into_iter()
+            .map(|(fill, _)| Fill {
+                strategy: strategy.clone(),
+                ..fill
+            })
+            .filter(|fill| self.book(&mut state, fill).is_ok())
+            .collect()
+    }
+
+    /// Forgets an order that is no longer live.
+    ///
+    /// Returns whether the order was tracked.
+    pub fn untrack(&self, order_id: OrderId) -> bool {
+        self.lock().own_orders.remove(&order_id).is_some()
+    }
+
+    /// Books a fill reported outside the attached books (e.g. by a venue),
+    /// under its strategy tag if set.
+    ///
+    /// Returns the P&L realized by the fill.
+    ///
+    /// # Errors
+    ///
+    /// Returns the inventory error if the fill cannot be booked.
+    pub fn route(&self, fill: &Fill) -> Result<Decimal> {
+        let mut state = self.lock();
+        self.book(&mut state, fill)
+    }
+
+    /// Returns the most recent booked fills, in booking order.
+    ///
+    /// At most the last 4096 fills are kept.
+    #[must_use]
+    pub fn fills(&self) -> Vec<Fill> {
+        self.lock().fills.iter().cloned().collect()
+    }
+
+    /// Removes and returns the kept booked fills, in booking order.
+    pub fn take_fills(&self) -> Vec<Fill> {
+        self.lock().fills.drain(..).collect()
+    }
+
+    /// Returns the realized P&L of a contract.
+    #[must_use]
+    pub fn realized_pnl(&self, symbol: &str) -> Decimal {
+        self.lock()
+            .realized
+            .get(symbol)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the realized P&L over every contract.
+    #[must_use]
+    pub fn total_realized_pnl(&self) -> Decimal {
+        self.lock().realized.values().sum()
+    }
+
+    /// Returns the realized P&L per contract.
+    #[must_use]
+    pub fn realized_by_contract(&self) -> BTreeMap<String, Decimal> {
+        self.lock().realized.clone()
+    }
+
+    /// Replaces the realized P&L per contract, e.g. after a restart.
+    pub fn restore_realized_pnl(&self, realized: BTreeMap<String, Decimal>) {
+        self.lock().realized = realized;
+    }
+
+    /// Returns the router's counters.
+    #[must_use]
+    pub fn stats(&self) -> FillStats {
+        let state = self.lock();
+        FillStats {
+            pending: state.pending.len() as u64,
+            ..state.stats
+        }
+    }
+
+    /// Returns the last error raised by inventory while booking a fill, or
+    /// the last position limit a booked fill breached.
+    #[must_use]
+    pub fn last_error(&self) -> Option<String> {
+        self.lock().last_error.clone()
+    }
+
+    /// Handles one execution reported by a book.
+    fn on_trade(&self, trade: &TradeResult) {
+        let mut state = self.lock();
+        let taker_complete = trade.match_result.is_complete;
+        for mut fill in Fill::from_trade(trade) {
+            if let Some(strategy) = state.own_orders.get(&fill.order_id) {
+                fill.strategy = strategy.clone();
+                let _ = self.book(&mut state, &fill);
+            } else if !fill.is_maker {
+                if state.pending.len() == MAX_PENDING_FILLS {
+                    state.pending.pop_front();
+                }
+                state.pending.push_back((fill, taker_complete));
+            }
+        }
+        for order_id in &trade.match_result.filled_order_ids {
+            state.own_orders.remove(order_id);
+        }
+        if taker_complete {
+            state.own_orders.remove(&trade.match_result.order_id);
+        }
+    }
+
+    /// Books a fill into inventory and P&L, reports any position limit it
+    /// breaches, then re-checks risk.
+    fn book(&self, state: &mut FillState, fill: &Fill) -> Result<Decimal> {
+        let price = Decimal::from(fill.price) / self.price_scale;
+        let booked = self.inventory.record_execution(
+            fill.strategy.as_deref(),
+            &fill.symbol,
+            fill.signed_quantity(),
+            price,
+        );
+        let (realized, breach) = match booked {
+            Ok(booked) => booked,
+            Err(e) => {
+                state.stats.rejected += 1;
+                state.last_error = Some(e.to_string());
+                return Err(e);
+            }
+        };
+        *state.realized.entry(fill.symbol.clone()).or_default() += realized;
+        if state.fills.len() == MAX_BOOKED_FILLS {
+            state.fills.pop_front();
+        }
+        state.fills.push_back(fill.clone());
+        state.stats.booked += 1;
+
+        if let Some(breach) = breach {
+            state.stats.breaches += 1;
+            state.last_error = Some(Error::from(breach).to_string());
+            if let Some(risk) = &self.risk {
+                risk.report_breaches(&[LimitBreach {
+                    kind: LimitKind::Position,
+                    current: breach.current,
+                    limit: breach.limit,
+                    timestamp_ms: fill.timestamp_ms,
+                }]);
+            }
+        }
+        if let Some(risk) = &self.risk {
+            let pnl: Decimal = state.realized.values().sum();
+            let breaches = risk.check(&self.inventory.total_greeks(), pnl);
+            state.stats.breaches += breaches.len() as u64;
+        }
+        Ok(realized)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FillState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::{OrderRequest, OrderRouter};
+    use crate::inventory::PositionLimits;
+    use crate::orderbook::UnderlyingOrderBookManager;
+    use crate::risk::RiskLimits;
+    use optionstratlib::ExpirationDate;
+    use optionstratlib::prelude::pos_or_panic;
+    use rust_decimal_macros::dec;
+
+    fn setup() -> (OrderRouter, Arc<FillRouter>, String) {
+        let (manager, fills, symbol) = books();
+        (OrderRouter::new(manager), fills, symbol)
+    }
+
+    fn books() -> (Arc<UnderlyingOrderBookManager>, Arc<FillRouter>, String) {
+        let manager = Arc::new(UnderlyingOrderBookManager::new());
+        let strike = manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(30.0)))
+            .get_or_create_strike(50000);
+        let symbol = strike.call().symbol().to_string();
+        let inventory = Arc::new(InventoryManager::new("BTC", PositionLimits::default()).unwrap());
+        let fills = Arc::new(
+            FillRouter::new(inventory)
+                .with_risk(Arc::new(
+                    RiskController::new(RiskLimits::default()).unwrap(),
+                ))
+                .with_price_scale(dec!(100))
+                .unwrap(),
+        );
+        let attached = fills.attach_underlying(&manager.get_or_create("BTC"));
+        assert_eq!(attached, 2);
+        (manager, fills, symbol)
+    }
+
+    #[test]
+    fn test_maker_fill_flows_to_inventory_and_pnl() {
+        let (router, fills, symbol) = setup();
+        // Our offer rests, another participant lifts part of it.
+        let ours = router
+            .submit(&OrderRequest::new(&symbol, Side::Sell, 500, 10))
+            .unwrap();
+        assert!(fills.track(ours.order_id()).is_empty());
+        router
+            .submit(&OrderRequest::new(&symbol, Side::Buy, 500, 4))
+            .unwrap();
+
+        let position = fills.inventory().position(&symbol).unwrap();
+        assert_eq!(position.quantity(), dec!(-4));
+        let booked = fills.fills();
+        assert_eq!(booked.len(), 1);
+        assert!(booked[0].is_maker);
+        assert_eq!(booked[0].side, Side::Sell);
+        // The other side is held in case it turns out to be ours.
+        assert_eq!(fills.stats().pending, 1);
+        assert!(fills.untrack(ours.order_id()));
+    }
+
+    #[test]
+    fn test_taker_fill_held_until_tracked() {
+        let (router, fills, symbol) = setup();
+        router
+            .submit(&OrderRequest::new(&symbol, Side::Sell, 300, 5))
+            .unwrap();
+        let ours = router
+            .submit(&OrderRequest::new(&symbol, Side::Buy, 300, 5))
+            .unwrap();
+        // Executions of unknown orders are not ours yet.
+        assert_eq!(fills.stats().pending, 1);
+        assert!(fills.inventory().position(&symbol).is_none());
+
+        let booked = fills.track(ours.order_id());
+        assert_eq!(booked.len(), 1);
+        assert!(!booked[0].is_maker);
+        assert_eq!(
+            fills.inventory().position(&symbol).unwrap().quantity(),
+            dec!(5)
+        );
+
+        // Closing at a higher price realizes the P&L on the contract.
+        router
+            .submit(&OrderRequest::new(&symbol, Side::Buy, 350, 5))
+            .unwrap();
+        let exit = router
+            .submit(&OrderRequest::new(&symbol, Side::Sell, 350, 5))
+            .unwrap();
+        fills.track(exit.order_id());
+        assert_eq!(fills.realized_pnl(&symbol), dec!(2.5));
+        assert_eq!(fills.total_realized_pnl(), dec!(2.5));
+        let stats = fills.stats();
+        assert_eq!((stats.booked, stats.pending, stats.rejected), (2, 0, 0));
+    }
+
+    #[test]
+    fn test_strategy_tags_segregate_inventory() {
+        let (manager, fills, symbol) = books();
+        let router = OrderRouter::new(manager).with_fill_router(Arc::clone(&fills));
+        router
+            .submit(&OrderRequest::new(&symbol, Side::Sell, 500, 10).with_strategy("mm"))
+            .unwrap();
+        // Our RFQ desk lifts our own offer: both sides are ours.
+        router
+            .submit(&OrderRequest::new(&symbol, Side::Buy, 500, 4).with_strategy("rfq"))
+            .unwrap();
+
+        let inventory = fills.inventory();
+        assert!(inventory.position(&symbol).unwrap().is_flat());
+        let mm = inventory.strategy_position("mm", &symbol).unwrap();
+        assert_eq!(mm.quantity(), dec!(-4));
+        let rfq = inventory.strategy_position("rfq", &symbol).unwrap();
+        assert_eq!(rfq.quantity(), dec!(4));
+        assert!(fills.fills().iter().all(|fill| fill.strategy.is_some()));
+        assert_eq!(fills.stats().pending, 0);
+    }
+
+    #[test]
+    fn test_breaching_fill_is_booked_and_reported() {
+        let (manager, _, symbol) = books();
+        let limits = PositionLimits {
+            per_option: dec!(5),
+            ..PositionLimits::default()
+        };
+        let risk = Arc::new(
+            RiskController::new(RiskLimits::default())
+                .unwrap()
+                .with_hard_limits([LimitKind::Position]),
+        );
+        let fills = Arc::new(
+            FillRouter::new(Arc::new(InventoryManager::new("BTC", limits).unwrap()))
+                .with_risk(Arc::clone(&risk)),
+        );
+        fills.attach_underlying(&manager.get_or_create("BTC"));
+        let ours = OrderRouter::new(Arc::clone(&manager))
+            .with_fill_router(Arc::clone(&fills))
+            .submit(&OrderRequest::new(&symbol, Side::Sell, 500, 8))
+            .unwrap();
+        OrderRouter::new(manager)
+            .submit(&OrderRequest::new(&symbol, Side::Buy, 500, 8))
+            .unwrap();
+
+        // The execution happened, so inventory books it past the limit.
+        let position = fills.inventory().position(&symbol).unwrap();
+        assert_eq!(position.quantity(), dec!(-8));
+        let stats = fills.stats();
+        assert_eq!((stats.booked, stats.rejected, stats.breaches), (1, 0, 1));
+        let trip = risk.kill_switch().unwrap();
+        assert_eq!(trip.breaches[0].kind, LimitKind::Position);
+        assert_eq!(trip.breaches[0].current, dec!(8));
+        // The order is fully filled, so it is no longer tracked.
+        assert!(!fills.untrack(ours.order_id()));
+        assert_eq!(fills.take_fills().len(), 1);
+        assert!(fills.fills().is_empty());
+    }
+}
@@ -0,0 +1,815 @@
+//! FIX protocol module.
+//!
+//! This module provides a session-less FIX codec for option venues:
+//!
+//! - [`FixMessage`]: An ordered list of tag/value fields with a message type
+//! - [`FixCodec`]: Frames messages with `BeginString`, `BodyLength` and
+//!   `CheckSum`, splits a byte stream into messages, and maps
+//!   [`OrderRequest`]s to `NewOrderSingle` (`35=D`) and
+//!   `OrderCancelRequest` (`35=F`) and `ExecutionReport` (`35=8`) to
+//!   [`OrderResponse`]s and [`Fill`]s
+//! - [`FixInstrument`]: The option tags of a contract symbol
+//!
+//! Session fields such as `SenderCompID`, `TargetCompID`, `MsgSeqNum` and
+//! `SendingTime` are left to the caller's session layer, which adds them as
+//! ordinary fields before encoding; the codec owns no sequence state, so it
+//! can be plugged into any transport.
+//!
+//! ## Option tags
+//!
+//! A contract `UND-YYYYMMDD-STRIKE-C|P` is sent as `Symbol(55)=UND`,
+//! `SecurityType(167)=OPT`, `MaturityMonthYear(200)=YYYYMM`,
+//! `MaturityDate(541)=YYYYMMDD`, `StrikePrice(202)=STRIKE` and
+//! `PutOrCall(201)=1|0`. Prices and quantities are converted between book
+//! units and venue units with a [`FeedScale`].
+//!
+//! An order's strategy tag is sent as `Account(1)` and read back from
+//! execution reports onto their fills.
+
+use super::fill::Fill;
+use super::order::{OrderRequest, OrderResponse};
+use crate::error::{Error, Result};
+use crate::market_data::{FeedScale, contract_symbol};
+use chrono::{DateTime, NaiveDateTime};
+use orderbook_rs::{OrderId, Side, TimeInForce};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Field separator.
+pub const SOH: u8 = 0x01;
+
+/// Venue name used in adapter errors.
+const VENUE: &str = "FIX";
+
+/// `UTCTimestamp` format with milliseconds.
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H:%M:%S%.3f";
+
+const ACCOUNT: u32 = 1;
+const BEGIN_STRING: u32 = 8;
+const BODY_LENGTH: u32 = 9;
+const CHECK_SUM: u32 = 10;
+const CL_ORD_ID: u32 = 11;
+const CUM_QTY: u32 = 14;
+const EXEC_ID: u32 = 17;
+const LAST_PX: u32 = 31;
+const LAST_QTY: u32 = 32;
+const MSG_TYPE: u32 = 35;
+const ORDER_ID: u32 = 37;
+const ORDER_QTY: u32 = 38;
+const ORD_TYPE: u32 = 40;
+const ORIG_CL_ORD_ID: u32 = 41;
+const PRICE: u32 = 44;
+const SIDE: u32 = 54;
+const SYMBOL: u32 = 55;
+const TEXT: u32 = 58;
+const TIME_IN_FORCE: u32 = 59;
+const TRANSACT_TIME: u32 = 60;
+const EXPIRE_TIME: u32 = 126;
+const LEAVES_QTY: u32 = 151;
+const EXEC_TYPE: u32 = 150;
+const SECURITY_TYPE: u32 = 167;
+const MATURITY_MONTH_YEAR: u32 = 200;
+const PUT_OR_CALL: u32 = 201;
+const STRIKE_PRICE: u32 = 202;
+const MATURITY_DATE: u32 = 541;
+const AGGRESSOR_INDICATOR: u32 = 1057;
+
+/// A FIX message: its type and body fields in order.
+///
+/// `BeginString`, `BodyLength`, `MsgType` and `CheckSum` are not stored as
+/// fields; the codec writes and checks them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixMessage {
+    /// Message type (`35`).
+    msg_type: String,
+    /// Body fields in order.
+    fields: Vec<(u32, String)>,
+}
+
+impl FixMessage {
+    /// Creates a message with no fields.
+    #[must_use]
+    pub fn new(msg_type: impl Into<String>) -> Self {
+        Self {
+            msg_type: msg_type.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Appends a field.
+    #[must_use]
+    pub fn with_field(mut self, tag: u32, value: impl ToString) -> Self {
+        self.push(tag, value);
+        self
+    }
+
+    /// Appends a field.
+    pub fn push(&mut self, tag: u32, value: impl ToString) {
+        self.fields.push((tag, value.to_string()));
+    }
+
+    /// Returns the message type.
+    #[must_use]
+    pub fn msg_type(&self) -> &str {
+        &self.msg_type
+    }
+
+    /// Returns the first value of a tag.
+    #[must_use]
+    pub fn get(&self, tag: u32) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(t, _)| *t == tag)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the body fields in order.
+    #[must_use]
+    pub fn fields(&self) -> &[(u32, String)] {
+        &self.fields
+    }
+
+    /// Returns the value of a required tag.
+    fn required(&self, tag: u32) -> Result<&str> {
+        self.get(tag).ok_or_else(|| {
+            Error::adapter(
+                VENUE,
+                format!("{} message missing tag {tag}", self.msg_type),
+            )
+        })
+    }
+
+    /// Parses the value of a required tag.
+    fn parse<T: FromStr>(&self, tag: u32) -> Result<T> {
+        let value = self.required(tag)?;
+        value
+            .parse()
+            .map_err(|_| Error::adapter(VENUE, format!("invalid value {value:?} for tag {tag}")))
+    }
+
+    /// Parses the value of an optional tag.
+    fn parse_optional<T: FromStr>(&self, tag: u32) -> Result<Option<T>> {
+        match self.get(tag) {
+            Some(_) => self.parse(tag).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Option contract fields of a symbol.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixInstrument {
+    /// Underlying symbol (`55`).
+    pub underlying: String,
+    /// Expiration as `YYYYMMDD` (`541`).
+    pub maturity_date: String,
+    /// Strike price (`202`).
+    pub strike: u64,
+    /// True for a call (`201=1`), false for a put (`201=0`).
+    pub is_call: bool,
+}
+
+impl FixInstrument {
+    /// Splits a contract symbol `UND-YYYYMMDD-STRIKE-C|P` into its fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::AdapterError` if the symbol is not a contract symbol.
+    pub fn from_symbol(symbol: &str) -> Result<Self> {
+        let invalid = || Error::adapter(VENUE, format!("not an option symbol: {symbol}"));
+        let mut parts = symbol.rsplitn(4, '-');
+        let is_call = match parts.next() {
+            Some("C") => true,
+            Some("P") => false,
+            _ => return Err(invalid()),
+        };
+        let strike = parts
+            .next()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(invalid)?;
+        let maturity_date = parts
+            .next()
+            .filter(|d| d.len() == 8 && d.bytes().all(|b| b.is_ascii_digit()))
+            .ok_or_else(invalid)?;
+        let underlying = parts.next().filter(|u| !u.is_empty()).ok_or_else(invalid)?;
+        Ok(Self {
+            underlying: underlying.to_string(),
+            maturity_date: maturity_date.to_string(),
+            strike,
+            is_call,
+        })
+    }
+
+    /// Returns the contract symbol.
+    #[must_use]
+    pub fn symbol(&self) -> String {
+        contract_symbol(
+            &self.underlying,
+            &self.maturity_date,
+            self.strike,
+            self.is_call,
+        )
+    }
+
+    /// Appends the instrument tags to a message.
+    fn write(&self, message: &mut FixMessage) {
+        message.push(SYMBOL, &self.underlying);
+        message.push(SECURITY_TYPE, "OPT");
+        message.push(MATURITY_MONTH_YEAR, &self.maturity_date[..6]);
+        message.push(MATURITY_DATE, &self.maturity_date);
+        message.push(STRIKE_PRICE, self.strike);
+        message.push(PUT_OR_CALL, if self.is_call { 1 } else { 0 });
+    }
+
+    /// Reads the instrument tags of a message.
+    fn read(message: &FixMessage) -> Result<Self> {
+        let maturity_date = match message.get(MATURITY_DATE) {
+            Some(date) => date.to_string(),
+            None => format!("{}01", message.required(MATURITY_MONTH_YEAR)?),
+        };
+        let strike: Decimal = message.parse(STRIKE_PRICE)?;
+        let strike = strike
+            .fract()
+            .is_zero()
+            .then(|| strike.to_u64())
+            .flatten()
+            .ok_or_else(|| Error::adapter(VENUE, format!("invalid strike {strike}")))?;
+        Ok(Self {
+            underlying: message.required(SYMBOL)?.to_string(),
+            maturity_date,
+            strike,
+            is_call: message.required(PUT_OR_CALL)? == "1",
+        })
+    }
+}
+
+/// Execution type (`150`) of an execution report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ExecType {
+    /// Order accepted (`0`).
+    New,
+    /// Order canceled (`4`).
+    Canceled,
+    /// Order replaced (`5`).
+    Replaced,
+    /// Order rejected (`8`).
+    Rejected,
+    /// Order expired (`C`).
+    Expired,
+    /// Partial or full execution (`F`).
+    Trade,
+    /// Any other execution type.
+    Other(char),
+}
+
+impl ExecType {
+    fn from_code(code: &str) -> Self {
+        match code {
+            "0" => Self::New,
+            "4" => Self::Canceled,
+            "5" => Self::Replaced,
+            "8" => Self::Rejected,
+            "C" => Self::Expired,
+            "F" => Self::Trade,
+            other => Self::Other(other.chars().next().unwrap_or('?')),
+        }
+    }
+}
+
+/// A decoded `ExecutionReport` (`35=8`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionReport {
+    /// Our order's client id (`11`).
+    pub cl_ord_id: String,
+    /// Venue order id (`37`).
+    pub venue_order_id: String,
+    /// Execution id (`17`).
+    pub exec_id: String,
+    /// Execution type (`150`).
+    pub exec_type: ExecType,
+    /// Contract symbol rebuilt from the instrument tags.
+    pub symbol: String,
+    /// Order side (`54`).
+    pub side: Side,
+    /// Execution price in book ticks (`31`), if any.
+    pub last_price: Option<u128>,
+    /// Executed quantity in book lots (`32`).
+    pub last_quantity: u64,
+    /// Open quantity in book lots (`151`).
+    pub leaves_quantity: u64,
+    /// Total executed quantity in book lots (`14`).
+    pub cumulative_quantity: u64,
+    /// True if our order took liquidity (`1057=Y`), if reported.
+    pub aggressor: Option<bool>,
+    /// Transaction time in milliseconds (`60`).
+    pub transact_time_ms: u64,
+    /// Free text (`58`), e.g. a reject reason.
+    pub text: Option<String>,
+    /// Account (`1`), our strategy tag, if reported.
+    #[serde(default)]
+    pub account: Option<String>,
+}
+
+impl ExecutionReport {
+    /// Returns the acknowledgement of an accepted order.
+    ///
+    /// Returns `None` unless the report is an acceptance (`150=0`).
+    ///
+    /// # Arguments
+    ///
+    /// * `order_id` - Our id of the order the report's `ClOrdID` maps to
+    #[must_use]
+    pub fn to_order_response(&self, order_id: OrderId) -> Option<OrderResponse> {
+        (self.exec_type == ExecType::New)
+            .then(|| OrderResponse::new(order_id, self.transact_time_ms, false))
+    }
+
+    /// Returns the execution as a fill.
+    ///
+    /// Returns `None` unless the report is a trade (`150=F`) with a price
+    /// and a positive quantity. Without an aggressor indicator the order is
+    /// assumed to have been resting.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_id` - Our id of the order the report's `ClOrdID` maps to
+    #[must_use]
+    pub fn to_fill(&self, order_id: OrderId) -> Option<Fill> {
+        if self.exec_type != ExecType::Trade || self.last_quantity == 0 {
+            return None;
+        }
+        Some(Fill {
+            symbol: self.symbol.clone(),
+            order_id,
+            side: self.side,
+            price: self.last_price?,
+            quantity: self.last_quantity,
+            is_maker: !self.aggressor.unwrap_or(false),
+            timestamp_ms: self.transact_time_ms,
+            strategy: self.account.clone(),
+        })
+    }
+}
+
+/// Session-less FIX encoder and decoder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixCodec {
+    /// `BeginString` (`8`), e.g. `FIX.4.4`.
+    begin_string: String,
+    /// Conversion between book and venue units.
+    scale: FeedScale,
+}
+
+impl FixCodec {
+    /// Creates a codec.
+    ///
+    /// # Arguments
+    ///
+    /// * `begin_string` - Protocol version, e.g. `FIX.4.4`
+    /// * `scale` - Tick and lot size of the venue
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the begin string is empty or
+    /// the tick or lot size is not positive.
+    pub fn new(begin_string: impl Into<String>, scale: FeedScale) -> Result<Self> {
+        let begin_string = begin_string.into();
+        if begin_string.is_empty() || begin_string.contains(char::from(SOH)) {
+            return Err(Error::configuration("invalid FIX begin string"));
+        }
+        scale.validate()?;
+        Ok(Self {
+            begin_string,
+            scale,
+        })
+    }
+
+    /// Returns the begin string.
+    #[must_use]
+    pub fn begin_string(&self) -> &str {
+        &self.begin_string
+    }
+
+    /// Frames a message with its header and checksum.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::AdapterError` if a value contains the field
+    /// separator.
+    pub fn encode(&self, message: &FixMessage) -> Result<Vec<u8>> {
+        let mut body = Vec::new();
+        write_field(&mut body, MSG_TYPE, &message.msg_type)?;
+        for (tag, value) in &message.fields {
+            write_field(&mut body, *tag, value)?;
+        }
+        let mut out = Vec::with_capacity(body.len() + 32);
+        write_field(&mut out, BEGIN_STRING, &self.begin_string)?;
+        write_field(&mut out, BODY_LENGTH, &body.len().to_string())?;
+        out.extend_from_slice(&body);
+        let checksum = checksum(&out);
+        write_field(&mut out, CHECK_SUM, &format!("{checksum:03}"))?;
+        Ok(out)
+    }
+
+    /// Returns the length of the first complete message in a buffer, or
+    /// `None` if more bytes are needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::AdapterError` if the buffer does not start with a
+    /// valid header.
+    pub fn frame_len(&self, buffer: &[u8]) -> Result<Option<usize>> {
+        let Some((header_len, body_len)) = header(buffer)? else {
+            return Ok(None);
+        };
+        // "10=NNN" and its separator.
+        let total = header_len + body_len + 7;
+        Ok((buffer.len() >= total).then_some(total))
+    }
+
+    /// Decodes one complete message.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::AdapterError` if the message is truncated, has a
+    /// different begin string, a wrong body length or checksum, or a
+    /// malformed field.
+    pub fn decode(&self, bytes: &[u8]) -> Result<FixMessage> {
+        let total = self
+            .frame_len(bytes)?
+            .ok_or_else(|| Error::adapter(VENUE, "truncated message"))?;
+        if total != bytes.len() {
+            return Err(Error::adapter(VENUE, "trailing bytes after message"));
+        }
+        let trailer = total - 7;
+        let expected = checksum(&bytes[..trailer]);
+        let fields = split_fields(bytes)?;
+        let Some(((BEGIN_STRING, begin), rest)) = fields.split_first().map(|(f, r)| (*f, r)) else {
+            return Err(Error::adapter(VENUE, "message must start with tag 8"));
+        };
+        if begin != self.begin_string {
+            return Err(Error::adapter(
+                VENUE,
+                format!("unexpected begin string {begin}"),
+            ));
+        }
+        let Some((&(CHECK_SUM, sum), body)) = rest[1..].split_last() else {
+            return Err(Error::adapter(VENUE, "message must end with tag 10"));
+        };
+        if sum.parse::<u32>().ok() != Some(expected) {
+            return Err(Error::adapter(
+                VENUE,
+                format!("checksum {sum} does not match {expected:03}"),
+            ));
+        }
+        let Some((&(MSG_TYPE, msg_type), body)) = body.split_first() else {
+            return Err(Error::adapter(VENUE, "message type must follow tag 9"));
+        };
+        Ok(FixMessage {
+            msg_type: msg_type.to_string(),
+            fields: body
+                .iter()
+                .map(|(tag, value)| (*tag, value.to_string()))
+                .collect(),
+        })
+    }
+
+    /// Builds a limit `NewOrderSingle` (`35=D`) for an order request.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The order
+    /// * `cl_ord_id` - Client order id (`11`)
+    /// * `transact_time_ms` - Transaction time in milliseconds (`60`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::AdapterError` if the symbol is not a contract symbol
+    /// or a time is out of range.
+    pub fn new_order_single(
+        &self,
+        request: &OrderRequest,
+        cl_ord_id: &str,
+        transact_time_ms: u64,
+    ) -> Result<FixMessage> {
+        let mut message = FixMessage::new("D").with_field(CL_ORD_ID, cl_ord_id);
+        if let Some(strategy) = &request.strategy {
+            message.push(ACCOUNT, strategy);
+        }
+        FixInstrument::from_symbol(&request.symbol)?.write(&mut message);
+        message.push(SIDE, side_code(request.side));
+        message.push(ORDER_QTY, self.venue_quantity(request.quantity));
+        message.push(ORD_TYPE, 2);
+        message.push(PRICE, self.venue_price(request.price));
+        let (code, expire_ms) = match request.time_in_force {
+            TimeInForce::Day => (0, None),
+            TimeInForce::Gtc => (1, None),
+            TimeInForce::Ioc => (3, None),
+            TimeInForce::Fok => (4, None),
+            TimeInForce::Gtd(seconds) => (6, Some(seconds.saturating_mul(1000))),
+        };
+        message.push(TIME_IN_FORCE, code);
+        if let Some(expire_ms) = expire_ms {
+            message.push(EXPIRE_TIME, format_timestamp(expire_ms)?);
+        }
+        message.push(TRANSACT_TIME, format_timestamp(transact_time_ms)?);
+        Ok(message)
+    }
+
+    /// Builds an `OrderCancelRequest` (`35=F`) for a previously sent order.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The order being canceled
+    /// * `orig_cl_ord_id` - Client order id of the order (`41`)
+    /// * `cl_ord_id` - Client id of the cancel request (`11`)
+    /// * `transact_time_ms` - Transaction time in milliseconds (`60`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::AdapterError` if the symbol is not a contract symbol
+    /// or the time is out of range.
+    pub fn order_cancel_request(
+        &self,
+        request: &OrderRequest,
+        orig_cl_ord_id: &str,
+        cl_ord_id: &str,
+        transact_time_ms: u64,
+    ) -> Result<FixMessage> {
+        let mut message = FixMessage::new("F")
+            .with_field(ORIG_CL_ORD_ID, orig_cl_ord_id)
+            .with_field(CL_ORD_ID, cl_ord_id);
+        FixInstrument::from_symbol(&request.symbol)?.write(&mut message);
+        message.push(SIDE, side_code(request.side));
+        message.push(ORDER_QTY, self.venue_quantity(request.quantity));
+        message.push(TRANSACT_TIME, format_timestamp(transact_time_ms)?);
+        Ok(message)
+    }
+
+    /// Reads an `ExecutionReport` (`35=8`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::AdapterError` if the message is not an execution
+    /// report or a required tag is missing or malformed.
+    pub fn execution_report(&self, message: &FixMessage) -> Result<ExecutionReport> {
+        if message.msg_type != "8" {
+            return Err(Error::adapter(
+                VENUE,
+                format!("expected an execution report, got 35={}", message.msg_type),
+            ));
+        }
+        let side = match message.required(SIDE)? {
+            "1" => Side::Buy,
+            "2" => Side::Sell,
+            other => return Err(Error::adapter(VENUE, format!("unsupported side {other}"))),
+        };
+        let quantity = |tag| -> Result<u64> {
+            match message.parse_optional::<Decimal>(tag)? {
+                Some(quantity) => self.scale.quantity(quantity),
+                None => Ok(0),
+            }
+        };
+        let last_price = match message.parse_optional::<Decimal>(LAST_PX)? {
+            Some(price) => Some(self.scale.price(price)?),
+            None => None,
+        };
+        Ok(ExecutionReport {
+            cl_ord_id: message.required(CL_ORD_ID)?.to_string(),
+            venue_order_id: message.required(ORDER_ID)?.to_string(),
+            exec_id: message.required(EXEC_ID)?.to_string(),
+            exec_type: ExecType::from_code(message.required(EXEC_TYPE)?),
+            symbol: FixInstrument::read(message)?.symbol(),
+            side,
+            last_price,
+            last_quantity: quantity(LAST_QTY)?,
+            leaves_quantity: quantity(LEAVES_QTY)?,
+            cumulative_quantity: quantity(CUM_QTY)?,
+            aggressor: message.get(AGGRESSOR_INDICATOR).map(|flag| flag == "Y"),
+            transact_time_ms: parse_timestamp(message.required(TRANSACT_TIME)?)?,
+            text: message.get(TEXT).map(str::to_string),
+            account: message.get(ACCOUNT).map(str::to_string),
+        })
+    }
+
+    /// Converts book ticks to a venue price.
+    fn venue_price(&self, ticks: u128) -> Decimal {
+        Decimal::from(ticks) * self.scale.tick_size
+    }
+
+    /// Converts book lots to a venue quantity.
+    fn venue_quantity(&self, lots: u64) -> Decimal {
+        Decimal::from(lots) * self.scale.lot_size
+    }
+}
+
+/// Appends `tag=value` and a separator.
+fn write_field(out: &mut Vec<u8>, tag: u32, value: &str) -> Result<()> {
+    if value.as_bytes().contains(&SOH) {
+        return Err(Error::adapter(
+            VENUE,
+            format!("value of tag {tag} contains the field separator"),
+        ));
+    }
+    out.extend_from_slice(tag.to_string().as_bytes());
+    out.push(b'=');
+    out.extend_from_slice(value.as_bytes());
+    out.push(SOH);
+    Ok(())
+}
+
+/// Returns the sum of the bytes modulo 256.
+fn checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().map(|&b| u32::from(b)).sum::<u32>() % 256
+}
+
+/// Returns the header length up to the end of `BodyLength` and the body
+/// length, or `None` if the header is incomplete.
+fn header(buffer: &[u8]) -> Result<Option<(usize, usize)>> {
+    let Some(first) = buffer.iter().position(|&b| b == SOH) else {
+        return Ok(None);
+    };
+    if !buffer.starts_with(b"8=") {
+        return Err(Error::adapter(VENUE, "message must start with tag 8"));
+    }
+    let rest = &buffer[first + 1..];
+    let Some(second) = rest.iter().position(|&b| b == SOH) else {
+        return Ok(None);
+    };
+    let length = rest[..second]
+        .strip_prefix(b"9=")
+        .and_then(|v| std::str::from_utf8(v).ok())
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| Error::adapter(VENUE, "tag 9 must follow tag 8"))?;
+    Ok(Some((first + 1 + second + 1, length)))
+}
+
+/// Splits a framed message into its fields.
+fn split_fields(bytes: &[u8]) -> Result<Vec<(u32, &str)>> {
+    bytes
+        .strip_suffix(&[SOH])
+        .unwrap_or(bytes)
+        .split(|&b| b == SOH)
+        .map(|field| {
+            let field = std::str::from_utf8(field)
+                .map_err(|_| Error::adapter(VENUE, "field is not UTF-8"))?;
+            let (tag, value) = field
+                .split_once('=')
+                .ok_or_else(|| Error::adapter(VENUE, format!("malformed field {field:?}")))?;
+            let tag = tag
+                .parse()
+                .map_err(|_| Error::adapter(VENUE, format!("malformed tag {tag:?}")))?;
+            Ok((tag, value))
+        })
+        .collect()
+}
+
+/// Returns the FIX code of a side.
+const fn side_code(side: Side) -> u8 {
+    match side {
+        Side::Buy => 1,
+        Side::Sell => 2,
+    }
+}
+
+/// Formats milliseconds as a `UTCTimestamp`.
+fn format_timestamp(timestamp_ms: u64) -> Result<String> {
+    i64::try_from(timestamp_ms)
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|time| time.format(TIMESTAMP_FORMAT).to_string())
+        .ok_or_else(|| Error::adapter(VENUE, format!("timestamp {timestamp_ms} out of range")))
+}
+
+/// Parses a `UTCTimestamp` with or without fractional seconds.
+fn parse_timestamp(value: &str) -> Result<u64> {
+    NaiveDateTime::parse_from_str(value, "%Y%m%d-%H:%M:%S%.f")
+        .ok()
+        .and_then(|time| u64::try_from(time.and_utc().timestamp_millis()).ok())
+        .ok_or_else(|| Error::adapter(VENUE, format!("invalid timestamp {value:?}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    const SYMBOL: &str = "BTC-20240329-50000-C";
+
+    fn codec() -> FixCodec {
+        FixCodec::new("FIX.4.4", FeedScale::new(dec!(0.5), dec!(0.1)).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_codec_rejects_zero_scale() {
+        let scale = FeedScale {
+            tick_size: Decimal::ZERO,
+            lot_size: dec!(0.1),
+        };
+        assert!(FixCodec::new("FIX.4.4", scale).is_err());
+    }
+
+    #[test]
+    fn test_new_order_single_roundtrip() {
+        let codec = codec();
+        let request = OrderRequest::new(SYMBOL, Side::Buy, 100, 30).with_strategy("mm");
+        let message = codec
+            .new_order_single(&request, "ord-1", 1_711_670_400_000)
+            .unwrap()
+            .with_field(49, "MM");
+        assert_eq!(message.get(1), Some("mm"));
+        assert_eq!(message.get(55), Some("BTC"));
+        assert_eq!(message.get(200), Some("202403"));
+        assert_eq!(message.get(202), Some("50000"));
+        assert_eq!(message.get(201), Some("1"));
+        assert_eq!(message.get(44), Some("50.0"));
+        assert_eq!(message.get(38), Some("3.0"));
+        assert_eq!(message.get(59), Some("1"));
+        assert_eq!(message.get(60), Some("20240329-00:00:00.000"));
+
+        let bytes = codec.encode(&message).unwrap();
+        let text = String::from_utf8(bytes.clone())
+            .unwrap()
+            .replace('\u{1}', "|");
+        assert!(text.starts_with("8=FIX.4.4|9="));
+        assert!(text.contains("|35=D|11=ord-1|1=mm|"));
+        assert_eq!(codec.decode(&bytes).unwrap(), message);
+
+        // Streams split on message boundaries.
+        let mut stream = bytes.clone();
+        stream.extend_from_slice(&bytes[..10]);
+        assert_eq!(codec.frame_len(&stream).unwrap(), Some(bytes.len()));
+        assert_eq!(codec.frame_len(&bytes[..10]).unwrap(), None);
+
+        let mut corrupted = bytes;
+        let at = corrupted.len() - 10;
+        corrupted[at] = b'9';
+        assert!(codec.decode(&corrupted).is_err());
+
+        let cancel = codec
+            .order_cancel_request(&request, "ord-1", "cxl-1", 0)
+            .unwrap();
+        assert_eq!(cancel.msg_type(), "F");
+        assert_eq!(cancel.get(41), Some("ord-1"));
+        assert!(
+            codec
+                .new_order_single(&OrderRequest::new("BTC", Side::Buy, 1, 1), "x", 0)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_execution_report_decoding() {
+        let codec = codec();
+        let report = FixMessage::new("8")
+            .with_field(37, "V-9")
+            .with_field(11, "ord-1")
+            .with_field(17, "E-1")
+            .with_field(150, "F")
+            .with_field(39, "1")
+            .with_field(55, "BTC")
+            .with_field(167, "OPT")
+            .with_field(200, "202403")
+            .with_field(541, "20240329")
+            .with_field(202, "50000")
+            .with_field(201, "0")
+            .with_field(54, "2")
+            .with_field(31, "49.5")
+            .with_field(32, "1.2")
+            .with_field(151, "0.8")
+            .with_field(14, "1.2")
+            .with_field(1057, "N")
+            .with_field(1, "rfq")
+            .with_field(60, "20240329-00:00:01");
+        let bytes = codec.encode(&report).unwrap();
+        let decoded = codec
+            .execution_report(&codec.decode(&bytes).unwrap())
+            .unwrap();
+        assert_eq!(decoded.symbol, "BTC-20240329-50000-P");
+        assert_eq!(decoded.exec_type, ExecType::Trade);
+        assert_eq!(decoded.last_price, Some(99));
+        assert_eq!(decoded.last_quantity, 12);
+        assert_eq!(decoded.leaves_quantity, 8);
+        assert_eq!(decoded.transact_time_ms, 1_711_670_401_000);
+
+        let order_id = OrderId::new();
+        let fill = decoded.to_fill(order_id).unwrap();
+        assert_eq!(fill.side, Side::Sell);
+        assert!(fill.is_maker);
+        assert_eq!(fill.strategy.as_deref(), Some("rfq"));
+        assert!(decoded.to_order_response(order_id).is_none());
+
+        let ack = ExecutionReport {
+            exec_type: ExecType::New,
+            ..decoded
+        };
+        assert_eq!(
+            ack.to_order_response(order_id).unwrap().accepted_at_ms(),
+            1_711_670_401_000
+        );
+        assert!(ack.to_fill(order_id).is_none());
+        assert!(codec.execution_report(&FixMessage::new("D")).is_err());
+    }
+}
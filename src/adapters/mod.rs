@@ -0,0 +1,66 @@
+//! Exchange adapter module.
+//!
+//! This module provides the order entry types shared by exchange adapters and
+//! the [`OrderRouter`] that submits them into the order book hierarchy.
+//!
+//! ## Components
+//!
+//! - [`OrderRequest`]: An order intent addressed by contract symbol
+//! - [`IdempotencyKey`]: Client-generated key identifying one order intent
+//! - [`OrderResponse`]: Acknowledgement returned for a submitted request
+//! - [`OrderRouter`]: Routes requests to books with duplicate-submit protection
+//! - [`OptimisticTracker`]: Unacknowledged orders assumed live, with caps and [`Compensation`]s for late answers
+//! - [`FillRouter`]: Books executions of our orders into inventory and P&L and re-checks risk
+//! - [`FixCodec`]: Session-less FIX codec mapping order requests to `NewOrderSingle`/`OrderCancelRequest` and execution reports to fills
+//! - [`RateLimiter`]: Token buckets per [`MessageKind`] and overall keeping us within venue message limits
+//! - [`OrderSweeper`]: Cancels our stale or unintended resting orders and reports what was swept
+//!
+//! ## Example
+//!
+//! ```rust
+//! use option_chain_orderbook::adapters::{IdempotencyKey, OrderRequest, OrderRouter};
+//! use option_chain_orderbook::orderbook::UnderlyingOrderBookManager;
+//! use optionstratlib::prelude::pos_or_panic;
+//! use optionstratlib::ExpirationDate;
+//! use orderbook_rs::Side;
+//! use std::sync::Arc;
+//!
+//! let manager = Arc::new(UnderlyingOrderBookManager::new());
+//! let strike = manager
+//!     .get_or_create("BTC")
+//!     .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(30.0)))
+//!     .get_or_create_strike(50000);
+//!
+//! let router = OrderRouter::new(Arc::clone(&manager));
+//! let key = IdempotencyKey::new("client-1");
+//! let request = OrderRequest::new(strike.call().symbol(), Side::Buy, 100, 10)
+//!     .with_idempotency_key(key);
+//!
+//! let first = router.submit(&request).unwrap();
+//! // A retry after a lost acknowledgement returns the original order
+//! let retry = router.submit(&request).unwrap();
+//! assert!(retry.is_duplicate());
+//! assert_eq!(first.order_id(), retry.order_id());
+//! ```
+
+mod fill;
+mod fix;
+mod optimistic;
+mod order;
+mod rate_limit;
+mod router;
+mod sweep;
+
+pub use fill::{Fill, FillRouter, FillStats};
+pub use fix::{ExecType, ExecutionReport, FixCodec, FixInstrument, FixMessage, SOH};
+pub use optimistic::{
+    AckOutcome, Compensation, CompensationReason, OptimisticConfig, OptimisticStats,
+    OptimisticTracker,
+};
+pub use order::{IdempotencyKey, OrderRequest, OrderResponse};
+pub use rate_limit::{MessageKind, RateLimit, RateLimiter, RateLimiterStats};
+pub use router::OrderRouter;
+pub use sweep::{
+    IntendedQuote, OrderSweeper, OwnOrder, SweepConfig, SweepReason, SweepReport, SweepStats,
+    SweptOrder,
+};
@@ -0,0 +1,447 @@
+//! Optimistic order entry module.
+//!
+//! This module provides the [`OptimisticTracker`], which lets the quoting
+//! engine keep cycling on high-latency venues without waiting for
+//! acknowledgements. Each sent order is assumed live; the tracker keeps it
+//! as unconfirmed until the venue answers and caps how much unconfirmed
+//! state may build up.
+//!
+//! When a late answer contradicts the assumption, the tracker returns a
+//! [`Compensation`] for the engine to apply:
+//!
+//! - A late reject: the order never rested, so local state is rolled back
+//! - An acknowledgement timeout: the order is abandoned and cancelled at
+//!   the venue
+//! - An acknowledgement of an abandoned order: the order is live after all,
+//!   so it is cancelled at the venue again
+
+use super::order::OrderRequest;
+use crate::clock::Clock;
+use crate::error::{Error, Result};
+use orderbook_rs::{OrderId, Side};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Optimistic mode configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OptimisticConfig {
+    /// Most orders awaiting acknowledgement at once.
+    pub max_unconfirmed_orders: usize,
+    /// Most quantity awaiting acknowledgement at once, in smallest units.
+    pub max_unconfirmed_quantity: u64,
+    /// Time after which an unacknowledged order is abandoned.
+    pub ack_timeout_ms: u64,
+}
+
+impl Default for OptimisticConfig {
+    fn default() -> Self {
+        Self {
+            max_unconfirmed_orders: 50,
+            max_unconfirmed_quantity: 500,
+            ack_timeout_ms: 2_000,
+        }
+    }
+}
+
+impl OptimisticConfig {
+    /// Validates the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if a cap or the timeout is zero.
+    pub fn validate(&self) -> Result<()> {
+        if self.max_unconfirmed_orders == 0 || self.max_unconfirmed_quantity == 0 {
+            return Err(Error::configuration(
+                "unconfirmed order caps must be positive",
+            ));
+        }
+        if self.ack_timeout_ms == 0 {
+            return Err(Error::configuration("ack timeout must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// Venue answer to a sent order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AckOutcome {
+    /// The order is resting at the venue.
+    Accepted,
+    /// The venue refused the order.
+    Rejected {
+        /// Venue reason.
+        reason: String,
+    },
+}
+
+/// Why a compensation is needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CompensationReason {
+    /// The venue rejected an order assumed live.
+    LateReject,
+    /// No acknowledgement arrived within the timeout.
+    AckTimeout,
+    /// An abandoned order was acknowledged.
+    LateAck,
+}
+
+/// Action that undoes a wrong optimistic assumption.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Compensation {
+    /// Order the assumption was about.
+    pub order_id: OrderId,
+    /// Contract symbol.
+    pub symbol: String,
+    /// Order side.
+    pub side: Side,
+    /// Order quantity in smallest units.
+    pub quantity: u64,
+    /// Why the compensation is needed.
+    pub reason: CompensationReason,
+    /// True if a cancel must be sent to the venue; otherwise only local
+    /// state is rolled back.
+    pub cancel_at_venue: bool,
+}
+
+/// Counters of the optimistic mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OptimisticStats {
+    /// Orders sent optimistically.
+    pub sent: u64,
+    /// Orders acknowledged in time.
+    pub accepted: u64,
+    /// Orders rejected after being assumed live.
+    pub late_rejects: u64,
+    /// Orders abandoned on timeout.
+    pub timeouts: u64,
+    /// Abandoned orders acknowledged afterwards.
+    pub late_acks: u64,
+    /// Sends refused by the unconfirmed caps.
+    pub throttled: u64,
+}
+
+impl OptimisticStats {
+    /// Returns the number of compensations issued.
+    #[must_use]
+    pub const fn compensations(&self) -> u64 {
+        self.late_rejects + self.timeouts + self.late_acks
+    }
+
+    /// Returns compensations per order sent, if any was sent.
+    #[must_use]
+    pub fn compensation_rate(&self) -> Option<Decimal> {
+        (self.sent > 0).then(|| Decimal::from(self.compensations()) / Decimal::from(self.sent))
+    }
+}
+
+/// An order sent without waiting for its acknowledgement.
+#[derive(Debug, Clone)]
+struct Unconfirmed {
+    /// The sent request.
+    request: OrderRequest,
+    /// Send time in milliseconds.
+    sent_ms: u64,
+}
+
+/// Unconfirmed orders and counters, guarded together.
+#[derive(Debug, Default)]
+struct State {
+    /// Orders awaiting acknowledgement.
+    unconfirmed: HashMap<OrderId, Unconfirmed>,
+    /// Quantity awaiting acknowledgement.
+    unconfirmed_quantity: u64,
+    /// Orders abandoned on timeout, by id.
+    abandoned: HashMap<OrderId, OrderRequest>,
+    /// Orders acknowledged in time.
+    confirmed: HashSet<OrderId>,
+    /// Counters.
+    stats: OptimisticStats,
+}
+
+impl State {
+    fn take(&mut self, order_id: OrderId) -> Option<Unconfirmed> {
+        let order = self.unconfirmed.remove(&order_id)?;
+        self.unconfirmed_quantity -= order.request.quantity;
+        Some(order)
+    }
+}
+
+/// Tracks optimistically sent orders and derives compensations.
+pub struct OptimisticTracker {
+    /// Caps and timeout.
+    config: OptimisticConfig,
+    /// Time source for send and timeout checks.
+    clock: Arc<dyn Clock>,
+    /// Unconfirmed orders and counters.
+    state: Mutex<State>,
+}
+
+impl OptimisticTracker {
+    /// Creates a tracker with nothing in flight.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the configuration is invalid.
+    pub fn new(config: OptimisticConfig, clock: Arc<dyn Clock>) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            clock,
+            state: Mutex::new(State::default()),
+        })
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub const fn config(&self) -> &OptimisticConfig {
+        &self.config
+    }
+
+    /// Records an order as sent and assumed live.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_id` - Identifier the order was sent under
+    /// * `request` - The sent request
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::RiskLimitBreached` if sending the order would exceed
+    /// the unconfirmed order or quantity cap; the engine should then wait
+    /// for acknowledgements before sending more.
+    pub fn send(&self, order_id: OrderId, request: &OrderRequest) -> Result<()> {
+        let now_ms = self.clock.now_ms();
+        let mut state = self.lock();
+        let quantity = state.unconfirmed_quantity.saturating_add(request.quantity);
+        if state.unconfirmed.len() >= self.config.max_unconfirmed_orders
+            || quantity > self.config.max_unconfirmed_quantity
+        {
+            state.stats.throttled += 1;
+            return Err(Error::risk_limit_breached("unconfirmed order exposure"));
+        }
+        state.unconfirmed_quantity = quantity;
+        state.unconfirmed.insert(
+            order_id,
+            Unconfirmed {
+                request: request.clone(),
+                sent_ms: now_ms,
+            },
+        );
+        state.stats.sent += 1;
+        Ok(())
+    }
+
+    /// Applies a venue answer.
+    ///
+    /// Returns the compensation the answer calls for, or `None` if it
+    /// confirms the assumption or refers to an unknown order.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_id` - Order the answer refers to
+    /// * `outcome` - Venue answer
+    pub fn on_ack(&self, order_id: OrderId, outcome: &AckOutcome) -> Option<Compensation> {
+        let mut state = self.lock();
+        if let Some(order) = state.take(order_id) {
+            return match outcome {
+                AckOutcome::Accepted => {
+                    state.stats.accepted += 1;
+                    state.confirmed.insert(order_id);
+                    None
+                }
+                AckOutcome::Rejected { .. } => {
+                    state.stats.late_rejects += 1;
+                    Some(compensation(
+                        order_id,
+                        &order.request,
+                        CompensationReason::LateReject,
+                    ))
+                }
+            };
+        }
+        let request = state.abandoned.remove(&order_id)?;
+        match outcome {
+            AckOutcome::Accepted => {
+                state.stats.late_acks += 1;
+                Some(compensation(
+                    order_id,
+                    &request,
+                    CompensationReason::LateAck,
+                ))
+            }
+            AckOutcome::Rejected { .. } => None,
+        }
+    }
+
+    /// Abandons orders unacknowledged past the timeout.
+    ///
+    /// Returns a venue cancel for each, oldest first. An abandoned order
+    /// no longer counts against the caps.
+    pub fn expire(&self) -> Vec<Compensation> {
+        let now_ms = self.clock.now_ms();
+        let mut state = self.lock();
+        let mut stale: Vec<(u64, OrderId)> = state
+            .unconfirmed
+            .iter()
+            .filter(|(_, o)| now_ms.saturating_sub(o.sent_ms) >= self.config.ack_timeout_ms)
+            .map(|(id, o)| (o.sent_ms, *id))
+            .collect();
+        stale.sort_by_key(|&(sent_ms, _)| sent_ms);
+
+        let mut compensations = Vec::with_capacity(stale.len());
+        for (_, order_id) in stale {
+            let Some(order) = state.take(order_id) else {
+                continue;
+            };
+            state.stats.timeouts += 1;
+            compensations.push(compensation(
+                order_id,
+                &order.request,
+                CompensationReason::AckTimeout,
+            ));
+            state.abandoned.insert(order_id, order.request);
+        }
+        compensations
+    }
+
+    /// Forgets an order that left the book, e.g. after a fill or cancel.
+    pub fn forget(&self, order_id: OrderId) {
+        let mut state = self.lock();
+        state.take(order_id);
+        state.confirmed.remove(&order_id);
+        state.abandoned.remove(&order_id);
+    }
+
+    /// Returns true if the order is still awaiting acknowledgement.
+    #[must_use]
+    pub fn is_unconfirmed(&self, order_id: OrderId) -> bool {
+        self.lock().unconfirmed.contains_key(&order_id)
+    }
+
+    /// Returns true if the venue acknowledged the order in time.
+    #[must_use]
+    pub fn is_confirmed(&self, order_id: OrderId) -> bool {
+        self.lock().confirmed.contains(&order_id)
+    }
+
+    /// Returns the number of orders awaiting acknowledgement.
+    #[must_use]
+    pub fn unconfirmed_orders(&self) -> usize {
+        self.lock().unconfirmed.len()
+    }
+
+    /// Returns the quantity awaiting acknowledgement.
+    #[must_use]
+    pub fn unconfirmed_quantity(&self) -> u64 {
+        self.lock().unconfirmed_quantity
+    }
+
+    /// Returns the counters.
+    #[must_use]
+    pub fn stats(&self) -> OptimisticStats {
+        self.lock().stats
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Builds the compensation for an order.
+fn compensation(
+    order_id: OrderId,
+    request: &OrderRequest,
+    reason: CompensationReason,
+) -> Compensation {
+    Compensation {
+        order_id,
+        symbol: request.symbol.clone(),
+        side: request.side,
+        quantity: request.quantity,
+        reason,
+        cancel_at_venue: reason != CompensationReason::LateReject,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use rust_decimal_macros::dec;
+
+    fn tracker(max_orders: usize) -> (Arc<ManualClock>, OptimisticTracker) {
+        let clock = Arc::new(ManualClock::new(0));
+        let config = OptimisticConfig {
+            max_unconfirmed_orders: max_orders,
+            max_unconfirmed_quantity: 25,
+            ack_timeout_ms: 1_000,
+        };
+        let tracker = OptimisticTracker::new(config, Arc::clone(&clock) as Arc<dyn Clock>).unwrap();
+        (clock, tracker)
+    }
+
+    fn request(quantity: u64) -> OrderRequest {
+        OrderRequest::new("BTC-20240329-50000-C", Side::Buy, 100, quantity)
+    }
+
+    #[test]
+    fn test_ack_and_late_reject() {
+        let (_, tracker) = tracker(10);
+        let (accepted, rejected) = (OrderId::new(), OrderId::new());
+        tracker.send(accepted, &request(10)).unwrap();
+        tracker.send(rejected, &request(5)).unwrap();
+        assert_eq!(tracker.unconfirmed_quantity(), 15);
+
+        assert!(tracker.on_ack(accepted, &AckOutcome::Accepted).is_none());
+        assert!(tracker.is_confirmed(accepted));
+        let undo = tracker
+            .on_ack(
+                rejected,
+                &AckOutcome::Rejected {
+                    reason: "post only".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(undo.reason, CompensationReason::LateReject);
+        assert!(!undo.cancel_at_venue);
+        assert_eq!(tracker.unconfirmed_orders(), 0);
+    }
+
+    #[test]
+    fn test_timeout_then_late_ack_cancels_again() {
+        let (clock, tracker) = tracker(10);
+        let id = OrderId::new();
+        tracker.send(id, &request(10)).unwrap();
+        clock.advance(999);
+        assert!(tracker.expire().is_empty());
+
+        clock.advance(1);
+        let cancels = tracker.expire();
+        assert_eq!(cancels.len(), 1);
+        assert!(cancels[0].cancel_at_venue);
+        assert!(!tracker.is_unconfirmed(id));
+
+        let undo = tracker.on_ack(id, &AckOutcome::Accepted).unwrap();
+        assert_eq!(undo.reason, CompensationReason::LateAck);
+        assert!(tracker.on_ack(id, &AckOutcome::Accepted).is_none());
+
+        let stats = tracker.stats();
+        assert_eq!(stats.compensations(), 2);
+        assert_eq!(stats.compensation_rate(), Some(dec!(2)));
+    }
+
+    #[test]
+    fn test_caps_throttle_sends() {
+        let (_, tracker) = tracker(2);
+        tracker.send(OrderId::new(), &request(10)).unwrap();
+        assert!(tracker.send(OrderId::new(), &request(20)).is_err());
+        tracker.send(OrderId::new(), &request(10)).unwrap();
+        assert!(tracker.send(OrderId::new(), &request(1)).is_err());
+        assert_eq!(tracker.stats().throttled, 2);
+        assert_eq!(tracker.stats().sent, 2);
+    }
+}
@@ -0,0 +1,224 @@
+//! Order entry types.
+//!
+//! This module provides [`OrderRequest`], [`IdempotencyKey`] and
+//! [`OrderResponse`], the venue-neutral representation of an order intent and
+//! its acknowledgement.
+
+use orderbook_rs::{OrderId, Side, TimeInForce};
+use serde::{Deserialize, Serialize};
+
+/// Client-generated key identifying a single order intent.
+///
+/// Resubmitting a request with the same key (for example after a network
+/// retry) is recognized as the same intent and not placed twice.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct IdempotencyKey(String);
+
+impl IdempotencyKey {
+    /// Creates an idempotency key from a client-supplied value.
+    #[must_use]
+    pub fn new(key: impl Into<String>) -> Self {
+        Self(key.into())
+    }
+
+    /// Generates a new unique idempotency key.
+    #[must_use]
+    pub fn generate() -> Self {
+        Self(OrderId::new().to_string())
+    }
+
+    /// Returns the key as a string slice.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for IdempotencyKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A limit order intent addressed by contract symbol.
+///
+/// Prices and quantities are in smallest units, matching `OptionOrderBook`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderRequest {
+    /// The option contract symbol (e.g., "BTC-20240329-50000-C").
+    pub symbol: String,
+    /// Buy or Sell side.
+    pub side: Side,
+    /// Limit price in smallest units.
+    pub price: u128,
+    /// Order quantity in smallest units.
+    pub quantity: u64,
+    /// Time-in-force of the order.
+    pub time_in_force: TimeInForce,
+    /// Optional client-generated idempotency key.
+    pub idempotency_key: Option<IdempotencyKey>,
+    /// Optional strategy or account tag the order's fills are booked under.
+    #[serde(default)]
+    pub strategy: Option<String>,
+    /// Optional participant tag the book's self-trade prevention checks,
+    /// e.g. `"mm"` for both our quoter and our hedger.
+    #[serde(default)]
+    pub participant: Option<String>,
+}
+
+impl OrderRequest {
+    /// Creates a new GTC limit order request without an idempotency key.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The option contract symbol
+    /// * `side` - Buy or Sell side
+    /// * `price` - Limit price in smallest units
+    /// * `quantity` - Order quantity in smallest units
+    #[must_use]
+    pub fn new(symbol: impl Into<String>, side: Side, price: u128, quantity: u64) -> Self {
+        Self {
+            symbol: symbol.into(),
+            side,
+            price,
+            quantity,
+            time_in_force: TimeInForce::Gtc,
+            idempotency_key: None,
+            strategy: None,
+            participant: None,
+        }
+    }
+
+    /// Sets the idempotency key.
+    #[must_use]
+    pub fn with_idempotency_key(mut self, key: IdempotencyKey) -> Self {
+        self.idempotency_key = Some(key);
+        self
+    }
+
+    /// Sets the time-in-force.
+    #[must_use]
+    pub fn with_time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = time_in_force;
+        self
+    }
+
+    /// Sets the strategy tag, e.g. `"mm"`, `"hedge"` or `"rfq"`.
+    #[must_use]
+    pub fn with_strategy(mut self, strategy: impl Into<String>) -> Self {
+        self.strategy = Some(strategy.into());
+        self
+    }
+
+    /// Sets the participant tag orders must not trade against.
+    #[must_use]
+    pub fn with_participant(mut self, participant: impl Into<String>) -> Self {
+        self.participant = Some(participant.into());
+        self
+    }
+
+    /// Returns true if both requests describe the same order intent.
+    ///
+    /// The idempotency key itself is not part of the comparison.
+    #[must_use]
+    pub fn same_intent(&self, other: &Self) -> bool {
+        self.symbol == other.symbol
+            && self.side == other.side
+            && self.price == other.price
+            && self.quantity == other.quantity
+            && self.time_in_force == other.time_in_force
+            && self.strategy == other.strategy
+            && self.participant == other.participant
+    }
+}
+
+/// Acknowledgement for a submitted order request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderResponse {
+    /// The order id assigned in the book.
+    order_id: OrderId,
+    /// Timestamp of the original acceptance in milliseconds.
+    accepted_at_ms: u64,
+    /// True if this response answers a resubmission of an already accepted intent.
+    duplicate: bool,
+}
+
+impl OrderResponse {
+    /// Creates a new order response.
+    #[must_use]
+    pub const fn new(order_id: OrderId, accepted_at_ms: u64, duplicate: bool) -> Self {
+        Self {
+            order_id,
+            accepted_at_ms,
+            duplicate,
+        }
+    }
+
+    /// Returns the order id assigned in the book.
+    #[must_use]
+    pub const fn order_id(&self) -> OrderId {
+        self.order_id
+    }
+
+    /// Returns the timestamp of the original acceptance in milliseconds.
+    #[must_use]
+    pub const fn accepted_at_ms(&self) -> u64 {
+        self.accepted_at_ms
+    }
+
+    /// Returns true if the request was recognized as a resubmission.
+    #[must_use]
+    pub const fn is_duplicate(&self) -> bool {
+        self.duplicate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_request_builder() {
+        let key = IdempotencyKey::new("abc");
+        let request = OrderRequest::new("BTC-20240329-50000-C", Side::Buy, 100, 10)
+            .with_idempotency_key(key.clone())
+            .with_time_in_force(TimeInForce::Ioc)
+            .with_strategy("mm")
+            .with_participant("desk");
+
+        assert_eq!(request.symbol, "BTC-20240329-50000-C");
+        assert_eq!(request.time_in_force, TimeInForce::Ioc);
+        assert_eq!(request.strategy.as_deref(), Some("mm"));
+        assert_eq!(request.participant.as_deref(), Some("desk"));
+        assert_eq!(request.idempotency_key, Some(key));
+    }
+
+    #[test]
+    fn test_same_intent_ignores_key() {
+        let a = OrderRequest::new("BTC-20240329-50000-C", Side::Buy, 100, 10)
+            .with_idempotency_key(IdempotencyKey::new("a"));
+        let b = OrderRequest::new("BTC-20240329-50000-C", Side::Buy, 100, 10)
+            .with_idempotency_key(IdempotencyKey::new("b"));
+        let c = OrderRequest::new("BTC-20240329-50000-C", Side::Buy, 101, 10);
+
+        assert!(a.same_intent(&b));
+        assert!(!a.same_intent(&c));
+    }
+
+    #[test]
+    fn test_generated_keys_are_unique() {
+        assert_ne!(IdempotencyKey::generate(), IdempotencyKey::generate());
+        assert_eq!(IdempotencyKey::new("k1").as_str(), "k1");
+        assert_eq!(IdempotencyKey::new("k1").to_string(), "k1");
+    }
+
+    #[test]
+    fn test_order_response_accessors() {
+        let id = OrderId::new();
+        let response = OrderResponse::new(id, 42, true);
+
+        assert_eq!(response.order_id(), id);
+        assert_eq!(response.accepted_at_ms(), 42);
+        assert!(response.is_duplicate());
+    }
+}
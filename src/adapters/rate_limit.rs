@@ -0,0 +1,260 @@
+//! Rate limiter module.
+//!
+//! This module provides the [`RateLimiter`], token buckets that keep our
+//! message rate within a venue's limits. Each [`MessageKind`] may have its
+//! own bucket, and an optional global bucket caps all messages together; a
+//! message is sent only when every bucket it draws from has a token.
+//!
+//! Buckets start full, hold at most `capacity` tokens and refill
+//! continuously at `refill_per_second`. Tokens are kept in thousandths so
+//! refills of a fraction of a token per millisecond are not lost.
+
+use crate::clock::Clock;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Thousandths of a token per token.
+const MILLI: u64 = 1_000;
+
+/// Kind of message sent to a venue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum MessageKind {
+    /// New order.
+    NewOrder,
+    /// Order cancel.
+    Cancel,
+    /// Order replace.
+    Replace,
+}
+
+/// Size and refill rate of a token bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Largest burst of messages.
+    pub capacity: u64,
+    /// Messages per second sustained.
+    pub refill_per_second: u64,
+}
+
+impl RateLimit {
+    /// Creates a rate limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the capacity or refill rate
+    /// is zero.
+    pub fn new(capacity: u64, refill_per_second: u64) -> Result<Self> {
+        if capacity == 0 || refill_per_second == 0 {
+            return Err(Error::configuration(
+                "rate limit capacity and refill rate must be positive",
+            ));
+        }
+        Ok(Self {
+            capacity,
+            refill_per_second,
+        })
+    }
+}
+
+/// Counters of a [`RateLimiter`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimiterStats {
+    /// Messages allowed.
+    pub granted: u64,
+    /// Requests refused for lack of tokens.
+    pub throttled: u64,
+}
+
+/// Tokens of one bucket.
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    /// Limit of the bucket.
+    limit: RateLimit,
+    /// Tokens held, in thousandths.
+    milli_tokens: u64,
+    /// Time of the last refill in milliseconds.
+    refilled_at_ms: u64,
+}
+
+impl Bucket {
+    fn new(limit: RateLimit, now_ms: u64) -> Self {
+        Self {
+            limit,
+            milli_tokens: limit.capacity.saturating_mul(MILLI),
+            refilled_at_ms: now_ms,
+        }
+    }
+
+    /// Adds the tokens accrued since the last refill.
+    fn refill(&mut self, now_ms: u64) {
+        // One token per second is one thousandth per millisecond.
+        let accrued = now_ms
+            .saturating_sub(self.refilled_at_ms)
+            .saturating_mul(self.limit.refill_per_second);
+        self.milli_tokens = self
+            .milli_tokens
+            .saturating_add(accrued)
+            .min(self.limit.capacity.saturating_mul(MILLI));
+        self.refilled_at_ms = self.refilled_at_ms.max(now_ms);
+    }
+
+    fn tokens(&self) -> u64 {
+        self.milli_tokens / MILLI
+    }
+}
+
+/// Mutable state of a [`RateLimiter`].
+#[derive(Debug, Default)]
+struct LimiterState {
+    /// Per-kind buckets.
+    buckets: BTreeMap<MessageKind, Bucket>,
+    /// Bucket shared by all messages.
+    global: Option<Bucket>,
+    /// Counters.
+    stats: RateLimiterStats,
+}
+
+/// Token-bucket message throttle.
+pub struct RateLimiter {
+    /// Time source for refills.
+    clock: Arc<dyn Clock>,
+    /// Buckets and counters.
+    state: Mutex<LimiterState>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter without limits.
+    #[must_use]
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            state: Mutex::new(LimiterState::default()),
+        }
+    }
+
+    /// Limits one kind of message.
+    #[must_use]
+    pub fn with_limit(self, kind: MessageKind, limit: RateLimit) -> Self {
+        let bucket = Bucket::new(limit, self.clock.now_ms());
+        self.lock().buckets.insert(kind, bucket);
+        self
+    }
+
+    /// Limits all messages together.
+    #[must_use]
+    pub fn with_global_limit(self, limit: RateLimit) -> Self {
+        let bucket = Bucket::new(limit, self.clock.now_ms());
+        self.lock().global = Some(bucket);
+        self
+    }
+
+    /// Takes `count` tokens for messages of a kind, or none if any bucket
+    /// the kind draws from is short.
+    ///
+    /// Returns whether the messages may be sent.
+    pub fn try_acquire(&self, kind: MessageKind, count: u64) -> bool {
+        if count == 0 {
+            return true;
+        }
+        let now_ms = self.clock.now_ms();
+        let mut state = self.lock();
+        let state = &mut *state;
+        let mut buckets: Vec<&mut Bucket> = state
+            .buckets
+            .get_mut(&kind)
+            .into_iter()
+            .chain(state.global.as_mut())
+            .collect();
+        for bucket in &mut buckets {
+            bucket.refill(now_ms);
+        }
+        let needed = count.saturating_mul(MILLI);
+        if buckets.iter().any(|b| b.milli_tokens < needed) {
+            state.stats.throttled += 1;
+            return false;
+        }
+        for bucket in buckets {
+            bucket.milli_tokens -= needed;
+        }
+        state.stats.granted += count;
+        true
+    }
+
+    /// Returns the number of messages of a kind that may be sent now.
+    #[must_use]
+    pub fn available(&self, kind: MessageKind) -> u64 {
+        let now_ms = self.clock.now_ms();
+        let mut state = self.lock();
+        let state = &mut *state;
+        state
+            .buckets
+            .get_mut(&kind)
+            .into_iter()
+            .chain(state.global.as_mut())
+            .map(|bucket| {
+                bucket.refill(now_ms);
+                bucket.tokens()
+            })
+            .min()
+            .unwrap_or(u64::MAX)
+    }
+
+    /// Returns the limiter's counters.
+    #[must_use]
+    pub fn stats(&self) -> RateLimiterStats {
+        self.lock().stats
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LimiterState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    #[test]
+    fn test_bucket_bursts_and_refills() {
+        let clock = Arc::new(ManualClock::new(0));
+        let limiter = RateLimiter::new(clock.clone())
+            .with_limit(MessageKind::NewOrder, RateLimit::new(3, 2).unwrap());
+
+        assert!(limiter.try_acquire(MessageKind::NewOrder, 2));
+        assert!(!limiter.try_acquire(MessageKind::NewOrder, 2));
+        assert!(limiter.try_acquire(MessageKind::NewOrder, 1));
+        assert_eq!(limiter.available(MessageKind::NewOrder), 0);
+
+        // Two tokens per second: one every 500 ms, never above capacity.
+        clock.advance(499);
+        assert_eq!(limiter.available(MessageKind::NewOrder), 0);
+        clock.advance(1);
+        assert_eq!(limiter.available(MessageKind::NewOrder), 1);
+        clock.advance(10_000);
+        assert_eq!(limiter.available(MessageKind::NewOrder), 3);
+
+        // Unlimited kinds always pass.
+        assert!(limiter.try_acquire(MessageKind::Cancel, 1_000));
+        assert_eq!(limiter.stats().throttled, 1);
+    }
+
+    #[test]
+    fn test_global_limit_is_shared() {
+        let clock = Arc::new(ManualClock::new(0));
+        let limiter = RateLimiter::new(clock)
+            .with_limit(MessageKind::Cancel, RateLimit::new(10, 10).unwrap())
+            .with_global_limit(RateLimit::new(4, 1).unwrap());
+
+        assert!(limiter.try_acquire(MessageKind::NewOrder, 3));
+        assert_eq!(limiter.available(MessageKind::Cancel), 1);
+        // A refusal takes nothing from the buckets that had room.
+        assert!(!limiter.try_acquire(MessageKind::Cancel, 2));
+        assert!(limiter.try_acquire(MessageKind::Cancel, 1));
+        assert!(RateLimit::new(0, 1).is_err());
+    }
+}
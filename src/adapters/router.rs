@@ -0,0 +1,662 @@
+//! Order router module.
+//!
+//! This module provides the [`OrderRouter`], which submits [`OrderRequest`]s
+//! into the order book hierarchy and protects against duplicate submits using
+//! client-generated [`IdempotencyKey`]s.
+//!
+//! With a [`FillRouter`] attached, every accepted order is tracked by it
+//! under the request's strategy tag, so its fills are booked into inventory
+//! per strategy.
+//!
+//! Requests with a participant tag, or every request once the router has a
+//! default participant, are added through
+//! [`OptionOrderBook::add_limit_order_as`], so the book's self-trade
+//! prevention stops hedges and other router orders from trading against
+//! our own quotes.
+
+use super::fill::FillRouter;
+use super::order::{IdempotencyKey, OrderRequest, OrderResponse};
+use crate::clock::{Clock, SystemClock};
+use crate::error::{Error, Result};
+use crate::orderbook::{OptionOrderBook, UnderlyingOrderBookManager};
+use crate::quoting::TickScheduleRegistry;
+use crate::risk::{RiskController, TradingState};
+use crossbeam_skiplist::SkipMap;
+use orderbook_rs::OrderId;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+
+/// Where a submission under an idempotency key stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SubmissionState {
+    /// The order is being placed.
+    Pending,
+    /// The order was placed.
+    Accepted,
+    /// Placement failed and the key was released.
+    Failed,
+}
+
+/// A request submitted under an idempotency key.
+#[derive(Debug)]
+struct TrackedSubmission {
+    /// The original request.
+    request: OrderRequest,
+    /// The order id assigned to the request.
+    order_id: OrderId,
+    /// Acceptance timestamp in milliseconds.
+    accepted_at_ms: u64,
+    /// Placement state.
+    state: Mutex<SubmissionState>,
+    /// Signalled when placement finishes.
+    resolved: Condvar,
+}
+
+impl TrackedSubmission {
+    fn pending(request: &OrderRequest, order_id: OrderId, accepted_at_ms: u64) -> Self {
+        Self {
+            request: request.clone(),
+            order_id,
+            accepted_at_ms,
+            state: Mutex::new(SubmissionState::Pending),
+            resolved: Condvar::new(),
+        }
+    }
+
+    fn state(&self) -> SubmissionState {
+        *self.lock()
+    }
+
+    fn resolve(&self, state: SubmissionState) {
+        *self.lock() = state;
+        self.resolved.notify_all();
+    }
+
+    /// Blocks until placement finishes and returns its outcome.
+    fn wait(&self) -> SubmissionState {
+        let mut state = self.lock();
+        while *state == SubmissionState::Pending {
+            state = self
+                .resolved
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        *state
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SubmissionState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Routes order requests into the order book hierarchy.
+///
+/// Requests carrying an [`IdempotencyKey`] are tracked so that a resubmission
+/// of the same intent (e.g. a retry after a lost acknowledgement) returns the
+/// original acknowledgement instead of placing a second order. Reusing a key
+/// for a different intent is rejected. A resubmission that arrives while the
+/// original is still being placed waits for its outcome: it is acknowledged
+/// as a duplicate if the original was placed, and placed itself if the
+/// original failed.
+///
+/// Uses `SkipMap` for thread-safe concurrent access.
+pub struct OrderRouter {
+    /// The order book hierarchy orders are routed into.
+    manager: Arc<UnderlyingOrderBookManager>,
+    /// Submissions indexed by idempotency key.
+    submissions: SkipMap<IdempotencyKey, Arc<TrackedSubmission>>,
+    /// Clock stamping acceptances.
+    clock: Arc<dyn Clock>,
+    /// Controller whose trading state gates submissions.
+    risk: Option<Arc<RiskController>>,
+    /// Tick tables request prices must be on.
+    tick_schedules: Option<Arc<TickScheduleRegistry>>,
+    /// Router the fills of accepted orders are booked through.
+    fills: Option<Arc<FillRouter>>,
+    /// Participant tag of requests that carry none.
+    participant: Option<String>,
+}
+
+impl OrderRouter {
+    /// Creates a new order router over an order book hierarchy.
+    ///
+    /// # Arguments
+    ///
+    /// * `manager` - The order book hierarchy to route orders into
+    #[must_use]
+    pub fn new(manager: Arc<UnderlyingOrderBookManager>) -> Self {
+        Self {
+            manager,
+            submissions: SkipMap::new(),
+            clock: Arc::new(SystemClock),
+            risk: None,
+            tick_schedules: None,
+            fills: None,
+            participant: None,
+        }
+    }
+
+    /// Sets the clock stamping acceptances, e.g. a simulated clock in tests
+    /// and replays.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Attaches the risk controller whose trading state gates submissions.
+    ///
+    /// New orders are rejected while trading is halted.
+    #[must_use]
+    pub fn with_risk(mut self, risk: Arc<RiskController>) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    /// Attaches the tick tables request prices are validated against.
+    ///
+    /// Request prices are then read as ticks of the smallest tick of the
+    /// contract's schedule, and orders off the grid of their price band are
+    /// rejected.
+    #[must_use]
+    pub fn with_tick_schedules(mut self, tick_schedules: Arc<TickScheduleRegistry>) -> Self {
+        self.tick_schedules = Some(tick_schedules);
+        self
+    }
+
+    /// Attaches the fill router that tracks accepted orders, under their
+    /// request's strategy tag if set.
+    #[must_use]
+    pub fn with_fill_router(mut self, fills: Arc<FillRouter>) -> Self {
+        self.fills = Some(fills);
+        self
+    }
+
+    /// Sets the participant tag of requests that carry none, e.g. the tag
+    /// our quoter uses, so self-trade prevention covers every routed order.
+    #[must_use]
+    pub fn with_participant(mut self, participant: impl Into<String>) -> Self {
+        self.participant = Some(participant.into());
+        self
+    }
+
+    /// Returns a reference to the order book hierarchy.
+    #[must_use]
+    pub fn manager(&self) -> &UnderlyingOrderBookManager {
+        &self.manager
+    }
+
+    /// Returns the number of tracked idempotency keys.
+    #[must_use]
+    pub fn tracked_count(&self) -> usize {
+        self.submissions.len()
+    }
+
+    /// Submits an order request.
+    ///
+    /// If the request carries an idempotency key that was already accepted for
+    /// the same intent, the original acknowledgement is returned with
+    /// [`OrderResponse::is_duplicate`] set and nothing is placed. If the
+    /// original is still being placed, this call blocks until it finishes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::RiskLimitBreached` if trading is halted,
+    /// `Error::ContractNotFound` if the symbol is not listed,
+    /// `Error::ValidationError` if the price is off the tick table or the
+    /// key was used for a different intent,
+    /// or `Error::OrderBookError` if the book rejects the order.
+    pub fn submit(&self, request: &OrderRequest) -> Result<OrderResponse> {
+        if let Some(risk) = &self.risk
+            && risk.state() == TradingState::Halted
+        {
+            return Err(Error::risk_limit_breached("trading halted"));
+        }
+        if let Some(schedules) = &self.tick_schedules {
+            schedules
+                .for_symbol(&request.symbol)
+                .validate_ticks(request.price)?;
+        }
+        let book = self
+            .manager
+            .book(self.manager.contract_id(&request.symbol)?)?;
+        let order_id = OrderId::new();
+        let accepted_at_ms = self.clock.now_ms();
+
+        let Some(key) = &request.idempotency_key else {
+            self.place(&book, order_id, request)?;
+            self.track(order_id, request);
+            return Ok(OrderResponse::new(order_id, accepted_at_ms, false));
+        };
+
+        loop {
+            // Reserve the key before placing so concurrent retries cannot both place
+            let ours = Arc::new(TrackedSubmission::pending(
+                request,
+                order_id,
+                accepted_at_ms,
+            ));
+            let entry = self
+                .submissions
+                .get_or_insert_with(key.clone(), || Arc::clone(&ours));
+            let tracked = Arc::clone(entry.value());
+            if !Arc::ptr_eq(&tracked, &ours) {
+                if !tracked.request.same_intent(request) {
+                    return Err(Error::validation(format!(
+                        "idempotency key {} already used for a different order",
+                        key
+                    )));
+                }
+                match tracked.wait() {
+                    SubmissionState::Accepted => {
+                        return Ok(OrderResponse::new(
+                            tracked.order_id,
+                            tracked.accepted_at_ms,
+                            true,
+                        ));
+                    }
+                    // The original failed and released the key: place this one.
+                    _ => continue,
+                }
+            }
+
+            if let Err(e) = self.place(&book, order_id, request) {
+                // Release the reservation so the client can retry
+                entry.remove();
+                ours.resolve(SubmissionState::Failed);
+                return Err(e);
+            }
+            self.track(order_id, request);
+            ours.resolve(SubmissionState::Accepted);
+            return Ok(OrderResponse::new(order_id, accepted_at_ms, false));
+        }
+    }
+
+    /// Adds a request's order to its book, on behalf of its participant if
+    /// it has one.
+    fn place(
+        &self,
+        book: &OptionOrderBook,
+        order_id: OrderId,
+        request: &OrderRequest,
+    ) -> Result<()> {
+        match request.participant.as_ref().or(self.participant.as_ref()) {
+            Some(participant) => book.add_limit_order_as(
+                order_id,
+                request.side,
+                request.price,
+                request.quantity,
+                request.time_in_force,
+                participant,
+            ),
+            None => book.add_limit_order_with_tif(
+                order_id,
+                request.side,
+                request.price,
+                request.quantity,
+                request.time_in_force,
+            ),
+        }
+    }
+
+    /// Tracks an accepted order with the fill router, if attached.
+    fn track(&self, order_id: OrderId, request: &OrderRequest) {
+        if let Some(fills) = &self.fills {
+            match &request.strategy {
+                Some(strategy) => fills.track_for(order_id, strategy.as_str()),
+                None => fills.track(order_id),
+            };
+        }
+    }
+
+    /// Returns the order id accepted under an idempotency key, if any.
+    #[must_use]
+    pub fn order_for_key(&self, key: &IdempotencyKey) -> Option<OrderId> {
+        self.submissions
+            .get(key)
+            .filter(|e| e.value().state() == SubmissionState::Accepted)
+            .map(|e| e.value().order_id)
+    }
+
+    /// Stops tracking an idempotency key.
+    ///
+    /// Returns true if the key was tracked.
+    pub fn forget(&self, key: &IdempotencyKey) -> bool {
+        self.submissions.remove(key).is_some()
+    }
+
+    /// Stops tracking keys accepted before the given timestamp.
+    ///
+    /// Returns the number of keys purged.
+    pub fn purge_before(&self, timestamp_ms: u64) -> usize {
+        let stale: Vec<IdempotencyKey> = self
+            .submissions
+            .iter()
+            .filter(|e| {
+                e.value().accepted_at_ms < timestamp_ms
+                    && e.value().state() == SubmissionState::Accepted
+            })
+            .map(|e| e.key().clone())
+            .collect();
+        stale.iter().filter(|key| self.forget(key)).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::StrikeOrderBook;
+    use optionstratlib::ExpirationDate;
+    use optionstratlib::prelude::pos_or_panic;
+    use orderbook_rs::{Side, TimeInForce};
+
+    fn setup() -> (OrderRouter, Arc<StrikeOrderBook>) {
+        let manager = Arc::new(UnderlyingOrderBookManager::new());
+        let strike = manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(30.0)))
+            .get_or_create_strike(50000);
+        (OrderRouter::new(manager), strike)
+    }
+
+    #[test]
+    fn test_submit_without_key_is_not_deduplicated() {
+        let (router, strike) = setup();
+        let request = OrderRequest::new(strike.call().symbol(), Side::Buy, 100, 10);
+
+        let first = router.submit(&request).unwrap();
+        let second = router.submit(&request).unwrap();
+
+        assert_ne!(first.order_id(), second.order_id());
+        assert!(!second.is_duplicate());
+        assert_eq!(strike.call().order_count(), 2);
+        assert_eq!(router.tracked_count(), 0);
+    }
+
+    #[test]
+    fn test_ack_lost_retry_returns_original_order() {
+        let (router, strike) = setup();
+        let request = OrderRequest::new(strike.call().symbol(), Side::Buy, 100, 10)
+            .with_idempotency_key(IdempotencyKey::new("retry-1"));
+
+        // The acknowledgement of the first submit is lost by the client
+        let original = router.submit(&request).unwrap();
+        let retry = router.submit(&request).unwrap();
+
+        assert!(!original.is_duplicate());
+        assert!(retry.is_duplicate());
+        assert_eq!(retry.order_id(), original.order_id());
+        assert_eq!(retry.accepted_at_ms(), original.accepted_at_ms());
+        assert_eq!(strike.call().order_count(), 1);
+    }
+
+    #[test]
+    fn test_retry_after_fill_does_not_replace_order() {
+        let (router, strike) = setup();
+        let request = OrderRequest::new(strike.put().symbol(), Side::Sell, 50, 5)
+            .with_idempotency_key(IdempotencyKey::new("retry-2"));
+
+        let original = router.submit(&request).unwrap();
+        strike.put().cancel_order(original.order_id()).unwrap();
+
+        // Even once the order is gone, the retry must not place it again
+        let retry = router.submit(&request).unwrap();
+        assert!(retry.is_duplicate());
+        assert_eq!(strike.put().order_count(), 0);
+    }
+
+    #[test]
+    fn test_acceptance_stamped_by_injected_clock() {
+        let (router, strike) = setup();
+        let router = router.with_clock(Arc::new(crate::clock::ManualClock::new(42)));
+        let request = OrderRequest::new(strike.call().symbol(), Side::Buy, 100, 10);
+        assert_eq!(router.submit(&request).unwrap().accepted_at_ms(), 42);
+    }
+
+    #[test]
+    fn test_good_till_date_request_in_seconds() {
+        use crate::adapters::FixCodec;
+        use crate::market_data::FeedScale;
+        use rust_decimal_macros::dec;
+
+        let (router, strike) = setup();
+        let clock = Arc::new(crate::clock::ManualClock::new(1_711_670_400_000));
+        router.manager.set_clock(clock.clone());
+        let request = OrderRequest::new(strike.call().symbol(), Side::Buy, 100, 10)
+            .with_time_in_force(TimeInForce::Gtd(1_711_670_460));
+
+        let response = router.submit(&request).unwrap();
+        let book = strike.call();
+        assert_eq!(
+            book.order_expiry(response.order_id()),
+            Some(1_711_670_460_000)
+        );
+
+        let codec =
+            FixCodec::new("FIX.4.4", FeedScale::new(dec!(0.5), dec!(0.1)).unwrap()).unwrap();
+        let message = codec
+            .new_order_single(&request, "gtd-1", clock.now_ms())
+            .unwrap();
+        assert_eq!(message.get(59), Some("6"));
+        assert_eq!(message.get(126), Some("20240329-00:01:00.000"));
+
+        clock.advance(59_999);
+        assert_eq!(book.run_timers().expired, 0);
+        clock.advance(1);
+        assert_eq!(book.run_timers().expired, 1);
+        assert!(book.is_empty());
+    }
+
+    #[test]
+    fn test_retry_during_placement_waits_for_outcome() {
+        use std::sync::mpsc;
+
+        let (router, strike) = setup();
+        let router = Arc::new(router);
+        let call = strike.call_arc();
+        call.add_limit_order(OrderId::new(), Side::Sell, 100, 10)
+            .unwrap();
+        // The first submit crosses the resting ask; its placement is held
+        // inside the trade listener until the retry has been sent.
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let release_rx = Mutex::new(release_rx);
+        call.set_trade_listener(Arc::new(move |_| {
+            let _ = entered_tx.send(());
+            let _ = release_rx.lock().unwrap().recv();
+        }));
+
+        let request = OrderRequest::new(call.symbol(), Side::Buy, 100, 4)
+            .with_idempotency_key(IdempotencyKey::new("in-flight"));
+        let first = {
+            let (router, request) = (Arc::clone(&router), request.clone());
+            std::thread::spawn(move || router.submit(&request).unwrap())
+        };
+        entered_rx.recv().unwrap();
+        let retry = {
+            let (router, request) = (Arc::clone(&router), request.clone());
+            std::thread::spawn(move || router.submit(&request).unwrap())
+        };
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        assert!(!retry.is_finished());
+        assert!(
+            router
+                .order_for_key(&IdempotencyKey::new("in-flight"))
+                .is_none()
+        );
+
+        release_tx.send(()).unwrap();
+        let (first, retry) = (first.join().unwrap(), retry.join().unwrap());
+        assert!(!first.is_duplicate());
+        assert!(retry.is_duplicate());
+        assert_eq!(retry.order_id(), first.order_id());
+        assert_eq!(call.ask_depth_at_price(100), 6);
+    }
+
+    #[test]
+    fn test_failed_placement_releases_key() {
+        let (router, strike) = setup();
+        let key = IdempotencyKey::new("fok");
+        let request = OrderRequest::new(strike.call().symbol(), Side::Buy, 100, 10)
+            .with_time_in_force(orderbook_rs::TimeInForce::Fok)
+            .with_idempotency_key(key.clone());
+
+        // Nothing to fill against: the book rejects the order.
+        assert!(router.submit(&request).is_err());
+        assert!(router.order_for_key(&key).is_none());
+        assert_eq!(router.tracked_count(), 0);
+
+        strike
+            .call()
+            .add_limit_order(OrderId::new(), Side::Sell, 100, 10)
+            .unwrap();
+        let retry = router.submit(&request).unwrap();
+        assert!(!retry.is_duplicate());
+        assert_eq!(router.order_for_key(&key), Some(retry.order_id()));
+    }
+
+    #[test]
+    fn test_key_reuse_for_different_intent_is_rejected() {
+        let (router, strike) = setup();
+        let key = IdempotencyKey::new("reused");
+        let first = OrderRequest::new(strike.call().symbol(), Side::Buy, 100, 10)
+            .with_idempotency_key(key.clone());
+        let different =
+            OrderRequest::new(strike.call().symbol(), Side::Buy, 101, 10).with_idempotency_key(key);
+
+        router.submit(&first).unwrap();
+        let result = router.submit(&different);
+
+        assert!(matches!(result, Err(Error::ValidationError { .. })));
+        assert_eq!(strike.call().order_count(), 1);
+    }
+
+    #[test]
+    fn test_unknown_symbol_does_not_reserve_key() {
+        let (router, _strike) = setup();
+        let key = IdempotencyKey::new("unknown");
+        let request = OrderRequest::new("BTC-19700101-1-C", Side::Buy, 100, 10)
+            .with_idempotency_key(key.clone());
+
+        assert!(router.submit(&request).is_err());
+        assert!(router.order_for_key(&key).is_none());
+    }
+
+    #[test]
+    fn test_forget_and_purge() {
+        let (router, strike) = setup();
+        let router = router.with_clock(Arc::new(crate::clock::ManualClock::new(5_000)));
+        for i in 0..3 {
+            let request = OrderRequest::new(strike.call().symbol(), Side::Buy, 100, 10)
+                .with_idempotency_key(IdempotencyKey::new(format!("k{}", i)));
+            router.submit(&request).unwrap();
+        }
+
+        assert!(router.order_for_key(&IdempotencyKey::new("k0")).is_some());
+        assert!(router.forget(&IdempotencyKey::new("k0")));
+        assert!(!router.forget(&IdempotencyKey::new("k0")));
+
+        assert_eq!(router.purge_before(5_000), 0);
+        let purged = router.purge_before(5_001);
+        assert_eq!(purged, 2);
+        assert_eq!(router.tracked_count(), 0);
+    }
+
+    #[test]
+    fn test_halt_rejects_submissions_until_reset() {
+        let (router, strike) = setup();
+        let risk = Arc::new(RiskController::new(crate::risk::RiskLimits::default()).unwrap());
+        let router = router.with_risk(Arc::clone(&risk));
+        let request = OrderRequest::new(strike.call().symbol(), Side::Buy, 100, 10);
+
+        risk.trip("manual");
+        let err = router.submit(&request).unwrap_err();
+        assert!(matches!(err, Error::RiskLimitBreached { .. }));
+        risk.reset_kill_switch();
+        assert!(router.submit(&request).is_ok());
+    }
+
+    #[test]
+    fn test_tick_table_rejects_off_grid_prices() {
+        use crate::quoting::{TickBand, TickSchedule};
+        use rust_decimal::Decimal;
+        use rust_decimal_macros::dec;
+
+        let (router, strike) = setup();
+        let schedule = TickSchedule::new(vec![
+            TickBand::new(Decimal::ZERO, dec!(0.01)),
+            TickBand::new(dec!(3), dec!(0.05)),
+        ])
+        .unwrap();
+        let router = router.with_tick_schedules(Arc::new(TickScheduleRegistry::new(schedule)));
+
+        let off_grid = OrderRequest::new(strike.call().symbol(), Side::Buy, 302, 10);
+        let err = router.submit(&off_grid).unwrap_err();
+        assert!(matches!(err, Error::ValidationError { .. }));
+        assert!(
+            router
+                .submit(&OrderRequest::new(
+                    strike.call().symbol(),
+                    Side::Buy,
+                    299,
+                    10
+                ))
+                .is_ok()
+        );
+        assert_eq!(strike.call().order_count(), 1);
+    }
+
+    #[test]
+    fn test_participant_requests_are_checked_for_self_trades() {
+        use crate::orderbook::SelfTradePrevention;
+
+        let (router, strike) = setup();
+        let call = strike.call();
+        call.set_self_trade_prevention(Some(SelfTradePrevention::CancelNewest));
+        let quote = OrderRequest::new(call.symbol(), Side::Sell, 105, 10).with_participant("mm");
+        router.submit(&quote).unwrap();
+
+        let crossing = OrderRequest::new(call.symbol(), Side::Buy, 105, 2);
+        assert!(
+            router
+                .submit(&crossing.clone().with_participant("mm"))
+                .is_err()
+        );
+        // An untagged request is not checked, unless the router tags it.
+        let router = router.with_participant("mm");
+        assert!(router.submit(&crossing).is_err());
+        assert!(router.submit(&crossing.with_participant("client")).is_ok());
+        assert_eq!(call.ask_depth_at_price(105), 8);
+        assert_eq!(call.self_trades_prevented(), 2);
+    }
+
+    #[test]
+    fn test_hedge_children_do_not_trade_against_our_quotes() {
+        use crate::hedging::{ExecutionPlanner, ExecutionStrategy, HedgeOrder};
+        use crate::orderbook::SelfTradePrevention;
+        use rust_decimal::Decimal;
+
+        let (router, strike) = setup();
+        let call = strike.call();
+        call.set_self_trade_prevention(Some(SelfTradePrevention::CancelNewest));
+        let quote = OrderRequest::new(call.symbol(), Side::Sell, 105, 10).with_participant("mm");
+        router.submit(&quote).unwrap();
+
+        let hedge = HedgeOrder {
+            symbol: call.symbol().to_string(),
+            side: Side::Buy,
+            quantity: 4,
+            delta_per_unit: Decimal::ONE,
+            timestamp_ms: 0,
+        };
+        let plan = ExecutionPlanner::new(ExecutionStrategy::Aggressive { slippage_ticks: 0 })
+            .unwrap()
+            .with_participant("mm")
+            .plan(&hedge);
+        let child = plan.children[0].to_order_request(100, 105);
+        assert_eq!(child.participant.as_deref(), Some("mm"));
+
+        assert!(router.submit(&child).is_err());
+        assert_eq!(call.ask_depth_at_price(105), 10);
+        assert_eq!(call.self_trades_prevented(), 1);
+    }
+}
@@ -0,0 +1,426 @@
+//! Order sweep module.
+//!
+//! This module provides the [`OrderSweeper`], which keeps a register of our
+//! own resting GTC orders and periodically cancels those the strategy no
+//! longer wants: orders older than a configurable age, and orders that do
+//! not match the current intended quote set. Such orders linger after a
+//! crash recovery without a full journal, or when a strategy forgets them.
+//!
+//! Each sweep returns a [`SweepReport`] listing what was swept and why.
+
+use super::order::OrderRequest;
+use crate::clock::Clock;
+use crate::error::{Error, Result};
+use crate::orderbook::UnderlyingOrderBookManager;
+use orderbook_rs::{OrderId, Side};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Sweeper configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SweepConfig {
+    /// Age after which a resting order is swept.
+    pub max_age_ms: u64,
+}
+
+impl Default for SweepConfig {
+    fn default() -> Self {
+        Self {
+            max_age_ms: 15 * 60 * 1000,
+        }
+    }
+}
+
+impl SweepConfig {
+    /// Validates the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the maximum age is zero.
+    pub fn validate(&self) -> Result<()> {
+        if self.max_age_ms == 0 {
+            return Err(Error::configuration("sweep max age must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// One of our resting orders.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnOrder {
+    /// Order identifier in the book.
+    pub order_id: OrderId,
+    /// Contract symbol.
+    pub symbol: String,
+    /// Order side.
+    pub side: Side,
+    /// Limit price in smallest units.
+    pub price: u128,
+    /// Order quantity in smallest units.
+    pub quantity: u64,
+    /// Placement time in milliseconds.
+    pub placed_at_ms: u64,
+}
+
+/// A quote level the strategy currently wants to rest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntendedQuote {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Quote side.
+    pub side: Side,
+    /// Limit price in smallest units.
+    pub price: u128,
+}
+
+impl IntendedQuote {
+    /// Creates an intended quote level.
+    #[must_use]
+    pub fn new(symbol: impl Into<String>, side: Side, price: u128) -> Self {
+        Self {
+            symbol: symbol.into(),
+            side,
+            price,
+        }
+    }
+}
+
+/// Why an order was swept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SweepReason {
+    /// The order rested longer than the maximum age.
+    Stale {
+        /// Age of the order at the sweep.
+        age_ms: u64,
+    },
+    /// The order is not part of the intended quote set.
+    NotIntended,
+}
+
+/// An order removed by a sweep.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SweptOrder {
+    /// The swept order.
+    pub order: OwnOrder,
+    /// Why it was swept.
+    pub reason: SweepReason,
+    /// True if the order was still resting and is now cancelled; false if
+    /// it had already left the book.
+    pub cancelled: bool,
+}
+
+/// Outcome of one sweep.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SweepReport {
+    /// Sweep time in milliseconds.
+    pub timestamp_ms: u64,
+    /// Orders examined.
+    pub scanned: usize,
+    /// Orders swept, oldest first.
+    pub swept: Vec<SweptOrder>,
+}
+
+impl SweepReport {
+    /// Returns the number of orders swept for their age.
+    #[must_use]
+    pub fn stale_count(&self) -> usize {
+        self.swept
+            .iter()
+            .filter(|s| matches!(s.reason, SweepReason::Stale { .. }))
+            .count()
+    }
+
+    /// Returns the number of orders swept for not being intended.
+    #[must_use]
+    pub fn not_intended_count(&self) -> usize {
+        self.swept
+            .iter()
+            .filter(|s| s.reason == SweepReason::NotIntended)
+            .count()
+    }
+}
+
+/// Counters of the sweeper.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SweepStats {
+    /// Sweeps run.
+    pub sweeps: u64,
+    /// Orders swept for their age.
+    pub stale: u64,
+    /// Orders swept for not being intended.
+    pub not_intended: u64,
+    /// Swept orders that were still resting and got cancelled.
+    pub cancelled: u64,
+}
+
+#[derive(Default)]
+struct State {
+    orders: HashMap<OrderId, OwnOrder>,
+    stats: SweepStats,
+}
+
+/// Register of our resting orders with periodic sweeping.
+pub struct OrderSweeper {
+    /// Maximum order age.
+    config: SweepConfig,
+    /// Books swept orders are cancelled in.
+    manager: Arc<UnderlyingOrderBookManager>,
+    /// Time source for order ages.
+    clock: Arc<dyn Clock>,
+    /// Tracked orders and counters.
+    state: Mutex<State>,
+}
+
+impl OrderSweeper {
+    /// Creates a sweeper with no tracked order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the configuration is invalid.
+    pub fn new(
+        config: SweepConfig,
+        manager: Arc<UnderlyingOrderBookManager>,
+        clock: Arc<dyn Clock>,
+    ) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            manager,
+            clock,
+            state: Mutex::new(State::default()),
+        })
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub const fn config(&self) -> &SweepConfig {
+        &self.config
+    }
+
+    /// Tracks an order we placed, timestamped now.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_id` - Identifier the order rests under
+    /// * `request` - The placed request
+    pub fn track(&self, order_id: OrderId, request: &OrderRequest) {
+        let order = OwnOrder {
+            order_id,
+            symbol: request.symbol.clone(),
+            side: request.side,
+            price: request.price,
+            quantity: request.quantity,
+            placed_at_ms: self.clock.now_ms(),
+        };
+        self.lock().orders.insert(order_id, order);
+    }
+
+    /// Stops tracking an order that was filled or cancelled.
+    pub fn untrack(&self, order_id: OrderId) -> Option<OwnOrder> {
+        self.lock().orders.remove(&order_id)
+    }
+
+    /// Returns the tracked orders, oldest first.
+    #[must_use]
+    pub fn orders(&self) -> Vec<OwnOrder> {
+        let mut orders: Vec<OwnOrder> = self.lock().orders.values().cloned().collect();
+        orders.sort_by_key(|o| o.placed_at_ms);
+        orders
+    }
+
+    /// Returns the number of tracked orders.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().orders.len()
+    }
+
+    /// Returns true if no order is tracked.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().orders.is_empty()
+    }
+
+    /// Cancels stale and unintended orders and stops tracking them.
+    ///
+    /// An order older than the maximum age is swept as stale. When an
+    /// intended quote set is given, any other order whose symbol, side and
+    /// price match no intended level is swept as not intended.
+    ///
+    /// # Arguments
+    ///
+    /// * `intended` - Current intended quote set, if known
+    pub fn sweep(&self, intended: Option<&[IntendedQuote]>) -> SweepReport {
+        let now_ms = self.clock.now_ms();
+        let intended: Option<HashSet<(&str, bool, u128)>> = intended.map(|quotes| {
+            quotes
+                .iter()
+                .map(|q| level_key(&q.symbol, q.side, q.price))
+                .collect()
+        });
+        let mut state = self.lock();
+        let scanned = state.orders.len();
+
+        let mut doomed: Vec<(OwnOrder, SweepReason)> = state
+            .orders
+            .values()
+            .filter_map(|order| {
+                let age_ms = now_ms.saturating_sub(order.placed_at_ms);
+                if age_ms >= self.config.max_age_ms {
+                    return Some((order.clone(), SweepReason::Stale { age_ms }));
+                }
+                let level = level_key(&order.symbol, order.side, order.price);
+                intended
+                    .as_ref()
+                    .is_some_and(|set| !set.contains(&level))
+                    .then(|| (order.clone(), SweepReason::NotIntended))
+            })
+            .collect();
+        doomed.sort_by_key(|(o, _)| o.placed_at_ms);
+
+        let mut swept = Vec::with_capacity(doomed.len());
+        for (order, reason) in doomed {
+            state.orders.remove(&order.order_id);
+            let cancelled = self.cancel(&order);
+            match reason {
+                SweepReason::Stale { .. } => state.stats.stale += 1,
+                SweepReason::NotIntended => state.stats.not_intended += 1,
+            }
+            if cancelled {
+                state.stats.cancelled += 1;
+            }
+            swept.push(SweptOrder {
+                order,
+                reason,
+                cancelled,
+            });
+        }
+        state.stats.sweeps += 1;
+        SweepReport {
+            timestamp_ms: now_ms,
+            scanned,
+            swept,
+        }
+    }
+
+    /// Returns the sweeper counters.
+    #[must_use]
+    pub fn stats(&self) -> SweepStats {
+        self.lock().stats
+    }
+
+    /// Cancels an order in its book, returning true if it was resting.
+    fn cancel(&self, order: &OwnOrder) -> bool {
+        self.manager
+            .contract_id(&order.symbol)
+            .and_then(|id| self.manager.book(id))
+            .and_then(|book| book.cancel_order(order.order_id))
+            .unwrap_or(false)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Returns the lookup key of a quote level.
+fn level_key(symbol: &str, side: Side, price: u128) -> (&str, bool, u128) {
+    (symbol, side == Side::Buy, price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::OrderRouter;
+    use crate::clock::ManualClock;
+    use optionstratlib::ExpirationDate;
+    use optionstratlib::prelude::pos_or_panic;
+
+    fn setup() -> (Arc<ManualClock>, OrderRouter, OrderSweeper, String) {
+        let manager = Arc::new(UnderlyingOrderBookManager::new());
+        let symbol = manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(30.0)))
+            .get_or_create_strike(50000)
+            .call()
+            .symbol()
+            .to_string();
+        let clock = Arc::new(ManualClock::new(0));
+        let config = SweepConfig { max_age_ms: 1_000 };
+        let sweeper = OrderSweeper::new(config, Arc::clone(&manager), clock.clone()).unwrap();
+        (clock, OrderRouter::new(manager), sweeper, symbol)
+    }
+
+    fn place(router: &OrderRouter, sweeper: &OrderSweeper, request: &OrderRequest) -> OrderId {
+        let order_id = router.submit(request).unwrap().order_id();
+        sweeper.track(order_id, request);
+        order_id
+    }
+
+    #[test]
+    fn test_stale_orders_swept() {
+        let (clock, router, sweeper, symbol) = setup();
+        let old = place(
+            &router,
+            &sweeper,
+            &OrderRequest::new(symbol.as_str(), Side::Buy, 100, 10),
+        );
+        clock.advance(600);
+        let young = place(
+            &router,
+            &sweeper,
+            &OrderRequest::new(symbol.as_str(), Side::Sell, 110, 10),
+        );
+        clock.advance(500);
+
+        let report = sweeper.sweep(None);
+        assert_eq!(report.scanned, 2);
+        assert_eq!(report.swept.len(), 1);
+        assert_eq!(report.swept[0].order.order_id, old);
+        assert_eq!(report.swept[0].reason, SweepReason::Stale { age_ms: 1_100 });
+        assert!(report.swept[0].cancelled);
+        assert_eq!(sweeper.orders()[0].order_id, young);
+        assert_eq!(
+            router
+                .manager()
+                .book(router.manager().contract_id(&symbol).unwrap())
+                .unwrap()
+                .order_count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_orders_outside_intended_set_swept() {
+        let (_clock, router, sweeper, symbol) = setup();
+        place(
+            &router,
+            &sweeper,
+            &OrderRequest::new(symbol.as_str(), Side::Buy, 100, 10),
+        );
+        let forgotten = place(
+            &router,
+            &sweeper,
+            &OrderRequest::new(symbol.as_str(), Side::Buy, 95, 10),
+        );
+
+        let intended = [
+            IntendedQuote::new(symbol.as_str(), Side::Buy, 100),
+            IntendedQuote::new(symbol.as_str(), Side::Sell, 105),
+        ];
+        let report = sweeper.sweep(Some(&intended));
+        assert_eq!(report.not_intended_count(), 1);
+        assert_eq!(report.stale_count(), 0);
+        assert_eq!(report.swept[0].order.order_id, forgotten);
+        assert_eq!(sweeper.len(), 1);
+
+        let stats = sweeper.stats();
+        assert_eq!(
+            (stats.sweeps, stats.not_intended, stats.cancelled),
+            (1, 1, 1)
+        );
+        assert!(SweepConfig { max_age_ms: 0 }.validate().is_err());
+    }
+}
@@ -0,0 +1,429 @@
+//! Alert engine module.
+//!
+//! This module provides the [`AlertEngine`], which evaluates registered
+//! [`AlertRule`]s against metric snapshots and emits deduplicated fire and
+//! resolve events.
+
+use super::expr::Condition;
+use crate::error::{Error, Result};
+use crate::risk::RiskDashboard;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Severity attached to an alert rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub enum AlertSeverity {
+    /// Informational.
+    Info,
+    /// Requires attention.
+    #[default]
+    Warning,
+    /// Requires immediate action.
+    Critical,
+}
+
+/// A named alert rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertRule {
+    /// Unique rule name.
+    name: String,
+    /// Original condition text.
+    expression: String,
+    /// Parsed condition.
+    condition: Condition,
+    /// Severity of fired alerts.
+    severity: AlertSeverity,
+}
+
+impl AlertRule {
+    /// Creates a rule by parsing its condition.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Unique rule name
+    /// * `expression` - Condition text, e.g. `quote_coverage < 90% for 1m`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the condition cannot be parsed.
+    pub fn new(name: impl Into<String>, expression: impl Into<String>) -> Result<Self> {
+        let expression = expression.into();
+        Ok(Self {
+            name: name.into(),
+            condition: Condition::parse(&expression)?,
+            expression,
+            severity: AlertSeverity::default(),
+        })
+    }
+
+    /// Sets the severity of fired alerts.
+    #[must_use]
+    pub fn with_severity(mut self, severity: AlertSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Returns the rule name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the condition text.
+    #[must_use]
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Returns the severity.
+    #[must_use]
+    pub const fn severity(&self) -> AlertSeverity {
+        self.severity
+    }
+}
+
+/// Kind of alert event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertEventKind {
+    /// The condition has held for the rule's duration.
+    Fired,
+    /// A fired condition no longer holds.
+    Resolved,
+}
+
+/// An alert state transition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertEvent {
+    /// Rule name.
+    pub rule: String,
+    /// Transition kind.
+    pub kind: AlertEventKind,
+    /// Rule severity.
+    pub severity: AlertSeverity,
+    /// Value of the condition's left-hand side at the transition.
+    pub value: Option<Decimal>,
+    /// Transition timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+impl std::fmt::Display for AlertEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{:?}] {} {:?}", self.severity, self.rule, self.kind)?;
+        if let Some(value) = self.value {
+            write!(f, " (value {value})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Evaluation state of a rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RuleState {
+    /// Condition does not hold.
+    Inactive,
+    /// Condition holds but not yet for the rule's duration.
+    Pending { since_ms: u64 },
+    /// Alert has fired and not yet resolved.
+    Firing,
+}
+
+/// Metric values keyed by name.
+///
+/// Build one per evaluation from whatever the application exposes; a snapshot
+/// can be seeded from a [`RiskDashboard`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricSnapshot {
+    /// Metric values.
+    values: HashMap<String, Decimal>,
+}
+
+impl MetricSnapshot {
+    /// Creates an empty snapshot.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a metric value.
+    pub fn set(&mut self, name: impl Into<String>, value: Decimal) {
+        self.values.insert(name.into(), value);
+    }
+
+    /// Sets a metric value, returning the snapshot.
+    #[must_use]
+    pub fn with(mut self, name: impl Into<String>, value: Decimal) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Returns a metric value.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<Decimal> {
+        self.values.get(name).copied()
+    }
+}
+
+impl From<&RiskDashboard> for MetricSnapshot {
+    /// Exposes `portfolio_<greek>`, `pnl_today`, `<kind>_limit`,
+    /// `<kind>_utilization`, `breaches_last_hour`, `quote_coverage` and
+    /// `hedger_residual_delta`.
+    fn from(dashboard: &RiskDashboard) -> Self {
+        let greeks = &dashboard.greeks;
+        let mut snapshot = Self::new()
+            .with("portfolio_delta", greeks.delta)
+            .with("portfolio_gamma", greeks.gamma)
+            .with("portfolio_theta", greeks.theta)
+            .with("portfolio_vega", greeks.vega)
+            .with("portfolio_rho", greeks.rho)
+            .with("pnl_today", dashboard.pnl_today)
+            .with(
+                "breaches_last_hour",
+                Decimal::from(dashboard.recent_breaches.len()),
+            );
+        for usage in &dashboard.utilization {
+            snapshot.set(format!("{}_limit", usage.kind), usage.limit);
+            snapshot.set(format!("{}_utilization", usage.kind), usage.utilization);
+        }
+        if let Some(coverage) = &dashboard.quote_coverage {
+            snapshot.set("quote_coverage", coverage.two_sided_ratio());
+        }
+        if let Some(hedger) = &dashboard.hedger {
+            snapshot.set("hedger_residual_delta", hedger.residual_delta);
+        }
+        snapshot
+    }
+}
+
+/// Evaluates alert rules and tracks their state.
+///
+/// Call [`evaluate`](Self::evaluate) periodically with fresh metrics. A rule
+/// fires once when its condition has held for the rule's duration and emits a
+/// single resolve event when it stops holding. Rules referencing a missing
+/// metric keep their current state.
+#[derive(Debug, Default)]
+pub struct AlertEngine {
+    /// Registered rules with their state, indexed by name.
+    rules: BTreeMap<String, (AlertRule, RuleState)>,
+}
+
+impl AlertEngine {
+    /// Creates an engine with no rules.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a rule.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if a rule with the same name exists.
+    pub fn register(&mut self, rule: AlertRule) -> Result<()> {
+        if self.rules.contains_key(rule.name()) {
+            return Err(Error::validation(format!(
+                "alert rule {} already registered",
+                rule.name()
+            )));
+        }
+        self.rules
+            .insert(rule.name().to_string(), (rule, RuleState::Inactive));
+        Ok(())
+    }
+
+    /// Removes a rule, returning true if it existed.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.rules.remove(name).is_some()
+    }
+
+    /// Returns the number of registered rules.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Returns true if no rules are registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the names of rules currently firing.
+    #[must_use]
+    pub fn firing(&self) -> Vec<&str> {
+        self.rules
+            .values()
+            .filter(|(_, state)| *state == RuleState::Firing)
+            .map(|(rule, _)| rule.name())
+            .collect()
+    }
+
+    /// Evaluates every rule against a metric snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `metrics` - Current metric values
+    /// * `now_ms` - Evaluation timestamp in milliseconds
+    ///
+    /// Returns the fire and resolve events produced by this evaluation.
+    pub fn evaluate(&mut self, metrics: &MetricSnapshot, now_ms: u64) -> Vec<AlertEvent> {
+        let mut events = Vec::new();
+        for (rule, state) in self.rules.values_mut() {
+            let Some(holds) = rule.condition.eval(&metrics.values) else {
+                continue;
+            };
+            let event = |kind| AlertEvent {
+                rule: rule.name.clone(),
+                kind,
+                severity: rule.severity,
+                value: rule.condition.lhs.eval(&metrics.values),
+                timestamp_ms: now_ms,
+            };
+            *state = match (*state, holds) {
+                (RuleState::Firing, true) => RuleState::Firing,
+                (RuleState::Firing, false) => {
+                    events.push(event(AlertEventKind::Resolved));
+                    RuleState::Inactive
+                }
+                (_, false) => RuleState::Inactive,
+                (RuleState::Inactive, true) if rule.condition.hold_ms > 0 => {
+                    RuleState::Pending { since_ms: now_ms }
+                }
+                (RuleState::Pending { since_ms }, true)
+                    if now_ms.saturating_sub(since_ms) < rule.condition.hold_ms =>
+                {
+                    RuleState::Pending { since_ms }
+                }
+                (_, true) => {
+                    events.push(event(AlertEventKind::Fired));
+                    RuleState::Firing
+                }
+            };
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::risk::{RiskController, RiskLimits};
+    use rust_decimal_macros::dec;
+
+    fn delta(value: Decimal) -> MetricSnapshot {
+        MetricSnapshot::new()
+            .with("portfolio_delta", value)
+            .with("delta_limit", dec!(100))
+    }
+
+    #[test]
+    fn test_fire_once_and_resolve() {
+        let mut engine = AlertEngine::new();
+        engine
+            .register(AlertRule::new("delta", "abs(portfolio_delta) > 0.8*delta_limit").unwrap())
+            .unwrap();
+
+        let fired = engine.evaluate(&delta(dec!(-90)), 0);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].kind, AlertEventKind::Fired);
+        assert_eq!(fired[0].value, Some(dec!(90)));
+        assert_eq!(engine.firing(), vec!["delta"]);
+
+        assert!(engine.evaluate(&delta(dec!(-95)), 1_000).is_empty());
+
+        let resolved = engine.evaluate(&delta(dec!(10)), 2_000);
+        assert_eq!(resolved[0].kind, AlertEventKind::Resolved);
+        assert!(engine.firing().is_empty());
+    }
+
+    #[test]
+    fn test_hold_duration() {
+        let mut engine = AlertEngine::new();
+        engine
+            .register(
+                AlertRule::new("delta", "abs(portfolio_delta) > 80 for 5m")
+                    .unwrap()
+                    .with_severity(AlertSeverity::Critical),
+            )
+            .unwrap();
+
+        assert!(engine.evaluate(&delta(dec!(90)), 0).is_empty());
+        assert!(engine.evaluate(&delta(dec!(90)), 299_999).is_empty());
+        let fired = engine.evaluate(&delta(dec!(90)), 300_000);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].severity, AlertSeverity::Critical);
+    }
+
+    #[test]
+    fn test_hold_resets_when_condition_clears() {
+        let mut engine = AlertEngine::new();
+        engine
+            .register(AlertRule::new("delta", "portfolio_delta > 80 for 1m").unwrap())
+            .unwrap();
+
+        engine.evaluate(&delta(dec!(90)), 0);
+        assert!(engine.evaluate(&delta(dec!(50)), 30_000).is_empty());
+        assert!(engine.evaluate(&delta(dec!(90)), 60_000).is_empty());
+        assert!(engine.evaluate(&delta(dec!(90)), 119_999).is_empty());
+        assert_eq!(engine.evaluate(&delta(dec!(90)), 120_000).len(), 1);
+    }
+
+    #[test]
+    fn test_missing_metric_keeps_state() {
+        let mut engine = AlertEngine::new();
+        engine
+            .register(AlertRule::new("slippage", "hedge_slippage_bps > 5").unwrap())
+            .unwrap();
+
+        let high = MetricSnapshot::new().with("hedge_slippage_bps", dec!(8));
+        assert_eq!(engine.evaluate(&high, 0).len(), 1);
+        assert!(engine.evaluate(&MetricSnapshot::new(), 1).is_empty());
+        assert_eq!(engine.firing(), vec!["slippage"]);
+    }
+
+    #[test]
+    fn test_register_duplicate_and_unregister() {
+        let mut engine = AlertEngine::new();
+        engine
+            .register(AlertRule::new("a", "x > 1").unwrap())
+            .unwrap();
+
+        assert!(
+            engine
+                .register(AlertRule::new("a", "x > 2").unwrap())
+                .is_err()
+        );
+        assert_eq!(engine.len(), 1);
+        assert!(engine.unregister("a"));
+        assert!(engine.is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_from_dashboard() {
+        let controller = RiskController::new(RiskLimits::default()).unwrap();
+        let snapshot = MetricSnapshot::from(&controller.dashboard());
+
+        assert_eq!(snapshot.get("portfolio_delta"), Some(Decimal::ZERO));
+        assert_eq!(snapshot.get("delta_limit"), Some(dec!(100)));
+        assert_eq!(snapshot.get("daily_loss_utilization"), Some(Decimal::ZERO));
+        assert!(snapshot.get("quote_coverage").is_none());
+
+        let mut engine = AlertEngine::new();
+        engine
+            .register(AlertRule::new("delta", "abs(portfolio_delta) > 0.8*delta_limit").unwrap())
+            .unwrap();
+        assert!(engine.evaluate(&snapshot, 0).is_empty());
+    }
+
+    #[test]
+    fn test_event_display() {
+        let event = AlertEvent {
+            rule: "delta".to_string(),
+            kind: AlertEventKind::Fired,
+            severity: AlertSeverity::Warning,
+            value: Some(dec!(90)),
+            timestamp_ms: 0,
+        };
+        assert_eq!(event.to_string(), "[Warning] delta Fired (value 90)");
+    }
+}
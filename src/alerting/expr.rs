@@ -0,0 +1,435 @@
+//! Alert expression module.
+//!
+//! This module provides the parser and evaluator for alert conditions such as
+//! `abs(portfolio_delta) > 0.8 * delta_limit for 5m`.
+//!
+//! ## Grammar
+//!
+//! ```text
+//! rule      := expr cmp expr [ "for" duration ]
+//! cmp       := ">" | ">=" | "<" | "<=" | "==" | "!="
+//! expr      := term (("+" | "-") term)*
+//! term      := factor (("*" | "/") factor)*
+//! factor    := number ["%"] | metric | func "(" args ")" | "(" expr ")" | "-" factor
+//! func      := "abs" | "min" | "max"
+//! duration  := number ("s" | "m" | "h")
+//! ```
+//!
+//! Percent literals are divided by one hundred, so `90%` is `0.9`.
+
+use crate::error::{Error, Result};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Comparison operator of a condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterOrEqual,
+    /// `<`
+    Less,
+    /// `<=`
+    LessOrEqual,
+    /// `==`
+    Equal,
+    /// `!=`
+    NotEqual,
+}
+
+impl Comparison {
+    /// Applies the comparison.
+    #[must_use]
+    pub fn apply(&self, lhs: Decimal, rhs: Decimal) -> bool {
+        match self {
+            Self::Greater => lhs > rhs,
+            Self::GreaterOrEqual => lhs >= rhs,
+            Self::Less => lhs < rhs,
+            Self::LessOrEqual => lhs <= rhs,
+            Self::Equal => lhs == rhs,
+            Self::NotEqual => lhs != rhs,
+        }
+    }
+}
+
+/// Arithmetic expression over metrics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    /// Numeric literal.
+    Literal(Decimal),
+    /// Named metric.
+    Metric(String),
+    /// Negation.
+    Neg(Box<Expr>),
+    /// Binary arithmetic.
+    Binary(Box<Expr>, char, Box<Expr>),
+    /// Function call.
+    Call(String, Vec<Expr>),
+}
+
+impl Expr {
+    /// Evaluates the expression against metric values.
+    ///
+    /// Returns `None` if a referenced metric is missing or on division by zero.
+    #[must_use]
+    pub fn eval(&self, metrics: &HashMap<String, Decimal>) -> Option<Decimal> {
+        match self {
+            Self::Literal(value) => Some(*value),
+            Self::Metric(name) => metrics.get(name).copied(),
+            Self::Neg(inner) => inner.eval(metrics).map(|v| -v),
+            Self::Binary(lhs, op, rhs) => {
+                let (lhs, rhs) = (lhs.eval(metrics)?, rhs.eval(metrics)?);
+                match op {
+                    '+' => lhs.checked_add(rhs),
+                    '-' => lhs.checked_sub(rhs),
+                    '*' => lhs.checked_mul(rhs),
+                    _ => lhs.checked_div(rhs),
+                }
+            }
+            Self::Call(name, args) => {
+                let values: Option<Vec<Decimal>> = args.iter().map(|a| a.eval(metrics)).collect();
+                let values = values?;
+                match name.as_str() {
+                    "abs" => Some(values[0].abs()),
+                    "min" => values.into_iter().min(),
+                    _ => values.into_iter().max(),
+                }
+            }
+        }
+    }
+}
+
+/// A parsed alert condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    /// Left-hand side.
+    pub lhs: Expr,
+    /// Comparison operator.
+    pub comparison: Comparison,
+    /// Right-hand side.
+    pub rhs: Expr,
+    /// How long the comparison must hold before the alert fires, in milliseconds.
+    pub hold_ms: u64,
+}
+
+impl Condition {
+    /// Parses a condition.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the text does not match the grammar.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut parser = Parser::new(text)?;
+        let lhs = parser.expr()?;
+        let comparison = parser.comparison()?;
+        let rhs = parser.expr()?;
+        let hold_ms = parser.hold()?;
+        if let Some(token) = parser.peek() {
+            return Err(Error::validation(format!(
+                "unexpected token {token:?} in alert condition"
+            )));
+        }
+        Ok(Self {
+            lhs,
+            comparison,
+            rhs,
+            hold_ms,
+        })
+    }
+
+    /// Evaluates the comparison against metric values.
+    ///
+    /// Returns `None` if a referenced metric is missing.
+    #[must_use]
+    pub fn eval(&self, metrics: &HashMap<String, Decimal>) -> Option<bool> {
+        Some(
+            self.comparison
+                .apply(self.lhs.eval(metrics)?, self.rhs.eval(metrics)?),
+        )
+    }
+}
+
+/// Lexical token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// Number, with a flag for a trailing `%`.
+    Number(Decimal, bool),
+    /// Identifier.
+    Ident(String),
+    /// Operator or punctuation.
+    Symbol(&'static str),
+}
+
+/// Recursive-descent parser over a token list.
+struct Parser {
+    /// Tokens of the input.
+    tokens: Vec<Token>,
+    /// Index of the next token.
+    pos: usize,
+}
+
+impl Parser {
+    /// Tokenizes the input.
+    fn new(text: &str) -> Result<Self> {
+        const SYMBOLS: [&str; 13] = [
+            ">=", "<=", "==", "!=", ">", "<", "+", "-", "*", "/", "(", ")", ",",
+        ];
+        let chars: Vec<char> = text.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if c.is_whitespace() {
+                i += 1;
+            } else if c.is_ascii_digit() || c == '.' {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let literal: String = chars[start..i].iter().collect();
+                let value = Decimal::from_str(&literal)
+                    .map_err(|_| Error::validation(format!("invalid number {literal}")))?;
+                let percent = chars.get(i) == Some(&'%');
+                if percent {
+                    i += 1;
+                }
+                tokens.push(Token::Number(value, percent));
+            } else if c.is_alphabetic() || c == '_' {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            } else {
+                let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+                let symbol = SYMBOLS
+                    .iter()
+                    .find(|s| rest.starts_with(*s))
+                    .ok_or_else(|| Error::validation(format!("unexpected character {c:?}")))?;
+                i += symbol.len();
+                tokens.push(Token::Symbol(symbol));
+            }
+        }
+        Ok(Self { tokens, pos: 0 })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<()> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            Err(Error::validation(format!("expected '{symbol}'")))
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let mut lhs = self.term()?;
+        loop {
+            let op = if self.eat("+") {
+                '+'
+            } else if self.eat("-") {
+                '-'
+            } else {
+                return Ok(lhs);
+            };
+            lhs = Expr::Binary(Box::new(lhs), op, Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr> {
+        let mut lhs = self.factor()?;
+        loop {
+            let op = if self.eat("*") {
+                '*'
+            } else if self.eat("/") {
+                '/'
+            } else {
+                return Ok(lhs);
+            };
+            lhs = Expr::Binary(Box::new(lhs), op, Box::new(self.factor()?));
+        }
+    }
+
+    fn factor(&mut self) -> Result<Expr> {
+        if self.eat("-") {
+            return Ok(Expr::Neg(Box::new(self.factor()?)));
+        }
+        if self.eat("(") {
+            let inner = self.expr()?;
+            self.expect(")")?;
+            return Ok(inner);
+        }
+        match self.next() {
+            Some(Token::Number(value, percent)) => Ok(Expr::Literal(if percent {
+                value / Decimal::ONE_HUNDRED
+            } else {
+                value
+            })),
+            Some(Token::Ident(name)) if self.eat("(") => {
+                let mut args = vec![self.expr()?];
+                while self.eat(",") {
+                    args.push(self.expr()?);
+                }
+                self.expect(")")?;
+                let arity_ok = match name.as_str() {
+                    "abs" => args.len() == 1,
+                    "min" | "max" => !args.is_empty(),
+                    _ => return Err(Error::validation(format!("unknown function {name}"))),
+                };
+                if !arity_ok {
+                    return Err(Error::validation(format!(
+                        "wrong number of arguments for {name}"
+                    )));
+                }
+                Ok(Expr::Call(name, args))
+            }
+            Some(Token::Ident(name)) => Ok(Expr::Metric(name)),
+            other => Err(Error::validation(format!(
+                "expected a value, found {other:?}"
+            ))),
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Comparison> {
+        let comparison = match self.next() {
+            Some(Token::Symbol(">")) => Comparison::Greater,
+            Some(Token::Symbol(">=")) => Comparison::GreaterOrEqual,
+            Some(Token::Symbol("<")) => Comparison::Less,
+            Some(Token::Symbol("<=")) => Comparison::LessOrEqual,
+            Some(Token::Symbol("==")) => Comparison::Equal,
+            Some(Token::Symbol("!=")) => Comparison::NotEqual,
+            other => {
+                return Err(Error::validation(format!(
+                    "expected a comparison, found {other:?}"
+                )));
+            }
+        };
+        Ok(comparison)
+    }
+
+    fn hold(&mut self) -> Result<u64> {
+        if !matches!(self.peek(), Some(Token::Ident(word)) if word == "for") {
+            return Ok(0);
+        }
+        self.pos += 1;
+        let value = match self.next() {
+            Some(Token::Number(value, false)) => value,
+            other => {
+                return Err(Error::validation(format!(
+                    "expected a duration, found {other:?}"
+                )));
+            }
+        };
+        let unit_ms = match self.next() {
+            Some(Token::Ident(unit)) if unit == "s" => 1_000,
+            Some(Token::Ident(unit)) if unit == "m" => 60_000,
+            Some(Token::Ident(unit)) if unit == "h" => 3_600_000,
+            other => {
+                return Err(Error::validation(format!(
+                    "expected a duration unit (s, m, h), found {other:?}"
+                )));
+            }
+        };
+        (value * Decimal::from(unit_ms))
+            .trunc()
+            .to_u64()
+            .ok_or_else(|| Error::validation("duration out of range"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn metrics(pairs: &[(&str, Decimal)]) -> HashMap<String, Decimal> {
+        pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn test_parse_limit_rule() {
+        let condition = Condition::parse("abs(portfolio_delta) > 0.8*delta_limit for 5m").unwrap();
+
+        assert_eq!(condition.hold_ms, 300_000);
+        assert_eq!(condition.comparison, Comparison::Greater);
+        let m = metrics(&[("portfolio_delta", dec!(-90)), ("delta_limit", dec!(100))]);
+        assert_eq!(condition.eval(&m), Some(true));
+    }
+
+    #[test]
+    fn test_percent_literal() {
+        let condition = Condition::parse("quote_coverage < 90%").unwrap();
+
+        assert_eq!(condition.hold_ms, 0);
+        assert_eq!(
+            condition.eval(&metrics(&[("quote_coverage", dec!(0.85))])),
+            Some(true)
+        );
+        assert_eq!(
+            condition.eval(&metrics(&[("quote_coverage", dec!(0.95))])),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_precedence_and_functions() {
+        let condition = Condition::parse("-(a + b) * 2 <= max(c, 1, -3) - 10 / 5").unwrap();
+        let m = metrics(&[("a", dec!(1)), ("b", dec!(2)), ("c", dec!(4))]);
+
+        // -6 <= 4 - 2
+        assert_eq!(condition.eval(&m), Some(true));
+    }
+
+    #[test]
+    fn test_missing_metric_is_unknown() {
+        let condition = Condition::parse("hedge_slippage_bps > 5").unwrap();
+        assert_eq!(condition.eval(&HashMap::new()), None);
+    }
+
+    #[test]
+    fn test_division_by_zero_is_unknown() {
+        let condition = Condition::parse("a / b > 1").unwrap();
+        let m = metrics(&[("a", dec!(1)), ("b", dec!(0))]);
+        assert_eq!(condition.eval(&m), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Condition::parse("a >").is_err());
+        assert!(Condition::parse("a b").is_err());
+        assert!(Condition::parse("sqrt(a) > 1").is_err());
+        assert!(Condition::parse("abs(a, b) > 1").is_err());
+        assert!(Condition::parse("a > 1 for 5d").is_err());
+        assert!(Condition::parse("a > 1 extra").is_err());
+        assert!(Condition::parse("a # 1").is_err());
+    }
+
+    #[test]
+    fn test_fractional_duration() {
+        let condition = Condition::parse("a > 1 for 1.5s").unwrap();
+        assert_eq!(condition.hold_ms, 1_500);
+    }
+}
@@ -0,0 +1,35 @@
+//! Alerting module.
+//!
+//! This module provides an embedded rule engine for alerting on exposed
+//! metrics, so basic alerts do not require an external rule engine.
+//!
+//! ## Components
+//!
+//! - [`AlertRule`]: Named condition such as `abs(portfolio_delta) > 0.8*delta_limit for 5m`
+//! - [`AlertEngine`]: Evaluates rules periodically and emits deduplicated events
+//! - [`MetricSnapshot`]: Metric values for one evaluation
+//! - [`AlertEvent`]: Fire or resolve notification
+//!
+//! ## Example
+//!
+//! ```rust
+//! use option_chain_orderbook::alerting::{AlertEngine, AlertRule, MetricSnapshot};
+//! use rust_decimal_macros::dec;
+//!
+//! let mut engine = AlertEngine::new();
+//! engine
+//!     .register(AlertRule::new("coverage", "quote_coverage < 90%").unwrap())
+//!     .unwrap();
+//!
+//! let metrics = MetricSnapshot::new().with("quote_coverage", dec!(0.75));
+//! let events = engine.evaluate(&metrics, 0);
+//! assert_eq!(events.len(), 1);
+//! ```
+
+mod engine;
+mod expr;
+
+pub use engine::{
+    AlertEngine, AlertEvent, AlertEventKind, AlertRule, AlertSeverity, MetricSnapshot,
+};
+pub use expr::{Comparison, Condition, Expr};
@@ -0,0 +1,471 @@
+//! Fill model module.
+//!
+//! This module provides the [`FillModel`], which simulates fills for our
+//! resting quotes from replayed public trade prints using a price/time
+//! priority heuristic with queue position estimation.
+
+use crate::error::{Error, Result};
+use crate::orderbook::OptionOrderBook;
+use orderbook_rs::Side;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A public trade print replayed from historical data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicTrade {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Trade price in smallest units.
+    pub price: u128,
+    /// Trade quantity in smallest units.
+    pub quantity: u64,
+    /// Side of the aggressor (`Buy` lifted offers, `Sell` hit bids).
+    pub aggressor: Side,
+    /// Trade timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// One side of our quote resting in the book.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestingQuote {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Our side.
+    pub side: Side,
+    /// Limit price in smallest units.
+    pub price: u128,
+    /// Remaining quantity in smallest units.
+    pub quantity: u64,
+    /// Estimated quantity ahead of us at our price.
+    pub queue_ahead: u64,
+    /// Placement timestamp in milliseconds.
+    pub placed_at_ms: u64,
+}
+
+impl RestingQuote {
+    /// Creates a resting quote with no queue ahead of it.
+    #[must_use]
+    pub fn new(
+        symbol: impl Into<String>,
+        side: Side,
+        price: u128,
+        quantity: u64,
+        placed_at_ms: u64,
+    ) -> Self {
+        Self {
+            symbol: symbol.into(),
+            side,
+            price,
+            quantity,
+            queue_ahead: 0,
+            placed_at_ms,
+        }
+    }
+
+    /// Sets the estimated queue ahead of the quote.
+    #[must_use]
+    pub fn with_queue_ahead(mut self, queue_ahead: u64) -> Self {
+        self.queue_ahead = queue_ahead;
+        self
+    }
+
+    /// Returns true if a trade by this aggressor could fill the quote.
+    #[must_use]
+    fn is_hit_by(&self, aggressor: Side) -> bool {
+        self.side != aggressor
+    }
+
+    /// Returns true if the trade printed through our price.
+    #[must_use]
+    fn traded_through(&self, price: u128) -> bool {
+        match self.side {
+            Side::Buy => price < self.price,
+            Side::Sell => price > self.price,
+        }
+    }
+}
+
+/// A fill produced by the fill model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulatedFill {
+    /// Identifier of the filled quote within the model.
+    pub quote_id: u64,
+    /// Contract symbol.
+    pub symbol: String,
+    /// Our side.
+    pub side: Side,
+    /// Fill price in smallest units (our limit price).
+    pub price: u128,
+    /// Filled quantity in smallest units.
+    pub quantity: u64,
+    /// Fill timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// Fill model configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FillModelConfig {
+    /// Probability that volume reaching our place in the queue fills us.
+    pub fill_probability: Decimal,
+    /// Fraction of the visible depth assumed ahead of us at placement
+    /// (1 = back of the queue, 0 = front).
+    pub queue_fraction: Decimal,
+    /// Seed for the deterministic random source.
+    pub seed: u64,
+}
+
+impl Default for FillModelConfig {
+    fn default() -> Self {
+        Self {
+            fill_probability: Decimal::ONE,
+            queue_fraction: Decimal::ONE,
+            seed: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+}
+
+impl FillModelConfig {
+    /// Validates the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if a probability or fraction is
+    /// outside `[0, 1]`.
+    pub fn validate(&self) -> Result<()> {
+        let unit = Decimal::ZERO..=Decimal::ONE;
+        if !unit.contains(&self.fill_probability) {
+            return Err(Error::configuration("fill_probability must be in [0, 1]"));
+        }
+        if !unit.contains(&self.queue_fraction) {
+            return Err(Error::configuration("queue_fraction must be in [0, 1]"));
+        }
+        Ok(())
+    }
+}
+
+/// Simulates fills for resting quotes from replayed public trades.
+///
+/// Results are deterministic for a given configuration seed and input
+/// sequence, so backtests are reproducible.
+#[derive(Debug, Clone)]
+pub struct FillModel {
+    /// Model configuration.
+    config: FillModelConfig,
+    /// Resting quotes indexed by identifier.
+    quotes: BTreeMap<u64, RestingQuote>,
+    /// Next quote identifier.
+    next_id: u64,
+    /// State of the xorshift random source.
+    rng_state: u64,
+}
+
+impl FillModel {
+    /// Creates a new fill model.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the configuration is invalid.
+    pub fn new(config: FillModelConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            quotes: BTreeMap::new(),
+            next_id: 0,
+            rng_state: config.seed.max(1),
+        })
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub const fn config(&self) -> &FillModelConfig {
+        &self.config
+    }
+
+    /// Returns the number of resting quotes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.quotes.len()
+    }
+
+    /// Returns true if no quotes are resting.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.quotes.is_empty()
+    }
+
+    /// Returns a resting quote by identifier.
+    #[must_use]
+    pub fn quote(&self, quote_id: u64) -> Option<&RestingQuote> {
+        self.quotes.get(&quote_id)
+    }
+
+    /// Adds a resting quote, returning its identifier.
+    pub fn place(&mut self, quote: RestingQuote) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.quotes.insert(id, quote);
+        id
+    }
+
+    /// Adds a resting quote, estimating its queue from the book's visible depth.
+    ///
+    /// # Arguments
+    ///
+    /// * `book` - The order book the quote joins
+    /// * `side` - Our side
+    /// * `price` - Limit price in smallest units
+    /// * `quantity` - Quantity in smallest units
+    /// * `placed_at_ms` - Placement timestamp in milliseconds
+    pub fn place_in_book(
+        &mut self,
+        book: &OptionOrderBook,
+        side: Side,
+        price: u128,
+        quantity: u64,
+        placed_at_ms: u64,
+    ) -> u64 {
+        let depth = match side {
+            Side::Buy => book.bid_depth_at_price(price),
+            Side::Sell => book.ask_depth_at_price(price),
+        };
+        let queue_ahead = (Decimal::from(depth) * self.config.queue_fraction)
+            .floor()
+            .to_u64()
+            .unwrap_or(depth);
+        self.place(
+            RestingQuote::new(book.symbol(), side, price, quantity, placed_at_ms)
+                .with_queue_ahead(queue_ahead),
+        )
+    }
+
+    /// Removes a resting quote.
+    pub fn cancel(&mut self, quote_id: u64) -> Option<RestingQuote> {
+        self.quotes.remove(&quote_id)
+    }
+
+    /// Replays a public trade against the resting quotes.
+    ///
+    /// Quotes are considered best price first, then in placement order. Quotes
+    /// placed after the trade cannot be filled by it. Fully filled quotes are
+    /// removed.
+    ///
+    /// Returns the simulated fills.
+    pub fn on_trade(&mut self, trade: &PublicTrade) -> Vec<SimulatedFill> {
+        let mut candidates: Vec<(u64, u128)> = self
+            .quotes
+            .iter()
+            .filter(|(_, q)| {
+                q.symbol == trade.symbol
+                    && q.is_hit_by(trade.aggressor)
+                    && q.placed_at_ms <= trade.timestamp_ms
+                    && (q.traded_through(trade.price) || q.price == trade.price)
+            })
+            .map(|(&id, q)| (id, q.price))
+            .collect();
+        match trade.aggressor {
+            Side::Buy => candidates.sort_by_key(|&(id, price)| (price, id)),
+            Side::Sell => candidates.sort_by_key(|&(id, price)| (std::cmp::Reverse(price), id)),
+        }
+
+        let mut remaining = trade.quantity;
+        let mut fills = Vec::new();
+        for (id, _) in candidates {
+            if remaining == 0 {
+                break;
+            }
+            let roll = self.next_unit();
+            let Some(quote) = self.quotes.get_mut(&id) else {
+                continue;
+            };
+
+            let available = if quote.traded_through(trade.price) {
+                remaining
+            } else {
+                let consumed = quote.queue_ahead.min(remaining);
+                quote.queue_ahead -= consumed;
+                remaining -= consumed;
+                if roll >= self.config.fill_probability {
+                    continue;
+                }
+                remaining
+            };
+
+            let filled = available.min(quote.quantity);
+            if filled == 0 {
+                continue;
+            }
+            quote.quantity -= filled;
+            remaining -= filled;
+            fills.push(SimulatedFill {
+                quote_id: id,
+                symbol: quote.symbol.clone(),
+                side: quote.side,
+                price: quote.price,
+                quantity: filled,
+                timestamp_ms: trade.timestamp_ms,
+            });
+            if quote.quantity == 0 {
+                self.quotes.remove(&id);
+            }
+        }
+        fills
+    }
+
+    /// Returns the next value of the random source in `[0, 1)`.
+    fn next_unit(&mut self) -> Decimal {
+        let mut x = self.rng_state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.rng_state = x;
+        Decimal::from(x >> 11) / Decimal::from(1u64 << 53)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::OptionStyle;
+    use orderbook_rs::OrderId;
+    use rust_decimal_macros::dec;
+
+    const SYMBOL: &str = "BTC-20240329-50000-C";
+
+    fn trade(price: u128, quantity: u64, aggressor: Side, timestamp_ms: u64) -> PublicTrade {
+        PublicTrade {
+            symbol: SYMBOL.to_string(),
+            price,
+            quantity,
+            aggressor,
+            timestamp_ms,
+        }
+    }
+
+    fn model() -> FillModel {
+        FillModel::new(FillModelConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn test_trade_through_fills_first() {
+        let mut model = model();
+        let id = model.place(RestingQuote::new(SYMBOL, Side::Sell, 100, 5, 0).with_queue_ahead(50));
+
+        let fills = model.on_trade(&trade(105, 3, Side::Buy, 10));
+
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].quote_id, id);
+        assert_eq!(fills[0].quantity, 3);
+        assert_eq!(fills[0].price, 100);
+        assert_eq!(model.quote(id).unwrap().quantity, 2);
+    }
+
+    #[test]
+    fn test_queue_consumed_before_fill() {
+        let mut model = model();
+        let id = model.place(RestingQuote::new(SYMBOL, Side::Buy, 100, 5, 0).with_queue_ahead(10));
+
+        assert!(model.on_trade(&trade(100, 6, Side::Sell, 10)).is_empty());
+        assert_eq!(model.quote(id).unwrap().queue_ahead, 4);
+
+        let fills = model.on_trade(&trade(100, 6, Side::Sell, 20));
+        assert_eq!(fills[0].quantity, 2);
+        assert_eq!(model.quote(id).unwrap().quantity, 3);
+    }
+
+    #[test]
+    fn test_no_fill_away_from_price_or_wrong_side() {
+        let mut model = model();
+        model.place(RestingQuote::new(SYMBOL, Side::Sell, 100, 5, 0));
+
+        assert!(model.on_trade(&trade(99, 5, Side::Buy, 10)).is_empty());
+        assert!(model.on_trade(&trade(100, 5, Side::Sell, 10)).is_empty());
+        assert_eq!(model.len(), 1);
+    }
+
+    #[test]
+    fn test_time_priority() {
+        let mut model = model();
+        model.place(RestingQuote::new(SYMBOL, Side::Sell, 100, 5, 50));
+
+        assert!(model.on_trade(&trade(100, 5, Side::Buy, 10)).is_empty());
+    }
+
+    #[test]
+    fn test_best_price_filled_first_and_removed() {
+        let mut model = model();
+        let worse = model.place(RestingQuote::new(SYMBOL, Side::Sell, 101, 5, 0));
+        let better = model.place(RestingQuote::new(SYMBOL, Side::Sell, 100, 5, 0));
+
+        let fills = model.on_trade(&trade(101, 7, Side::Buy, 10));
+
+        assert_eq!(fills.len(), 2);
+        assert_eq!(fills[0].quote_id, better);
+        assert_eq!(fills[0].quantity, 5);
+        assert_eq!(fills[1].quote_id, worse);
+        assert_eq!(fills[1].quantity, 2);
+        assert!(model.quote(better).is_none());
+    }
+
+    #[test]
+    fn test_zero_probability_never_fills_at_price() {
+        let config = FillModelConfig {
+            fill_probability: Decimal::ZERO,
+            ..FillModelConfig::default()
+        };
+        let mut model = FillModel::new(config).unwrap();
+        model.place(RestingQuote::new(SYMBOL, Side::Sell, 100, 5, 0));
+
+        assert!(model.on_trade(&trade(100, 5, Side::Buy, 10)).is_empty());
+        assert_eq!(model.on_trade(&trade(101, 5, Side::Buy, 10)).len(), 1);
+    }
+
+    #[test]
+    fn test_partial_probability_is_deterministic() {
+        let config = FillModelConfig {
+            fill_probability: dec!(0.5),
+            ..FillModelConfig::default()
+        };
+        let run = || {
+            let mut model = FillModel::new(config).unwrap();
+            (0..20)
+                .map(|i| {
+                    model.place(RestingQuote::new(SYMBOL, Side::Sell, 100, 1, 0));
+                    model.on_trade(&trade(100, 1, Side::Buy, i)).len()
+                })
+                .sum::<usize>()
+        };
+
+        let filled = run();
+        assert_eq!(filled, run());
+        assert!(filled > 0 && filled < 20);
+    }
+
+    #[test]
+    fn test_place_in_book_estimates_queue() {
+        let book = OptionOrderBook::new(SYMBOL, OptionStyle::Call);
+        book.add_limit_order(OrderId::new(), Side::Buy, 100, 8)
+            .unwrap();
+        let config = FillModelConfig {
+            queue_fraction: dec!(0.5),
+            ..FillModelConfig::default()
+        };
+        let mut model = FillModel::new(config).unwrap();
+
+        let id = model.place_in_book(&book, Side::Buy, 100, 2, 0);
+
+        assert_eq!(model.quote(id).unwrap().queue_ahead, 4);
+        assert!(model.cancel(id).is_some());
+        assert!(model.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let config = FillModelConfig {
+            fill_probability: dec!(1.5),
+            ..FillModelConfig::default()
+        };
+        assert!(FillModel::new(config).is_err());
+    }
+}
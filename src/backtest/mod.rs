@@ -0,0 +1,22 @@
+//! Backtest module.
+//!
+//! This module provides tools for running the market making stack against
+//! historical data when only public information is available.
+//!
+//! ## Components
+//!
+//! - [`FillModel`]: Decides whether our resting quotes would have been filled
+//!   by replayed public trades, producing [`SimulatedFill`]s
+//! - [`PublicTrade`]: A replayed public trade print
+//! - [`RestingQuote`]: One side of our quote resting in the book
+//!
+//! ## Fill Heuristic
+//!
+//! A public trade that prints through our price fills us first (price
+//! priority). A trade at our price first consumes the estimated queue ahead of
+//! us (time priority), and any remainder fills us with a configurable
+//! probability to account for hidden liquidity and queue uncertainty.
+
+mod fill_model;
+
+pub use fill_model::{FillModel, FillModelConfig, PublicTrade, RestingQuote, SimulatedFill};
@@ -0,0 +1,347 @@
+//! Expiry clock module.
+//!
+//! This module provides the [`ExpiryClock`], which updates the time to
+//! expiry of every option chain in an [`UnderlyingOrderBookManager`] once
+//! per configured interval and publishes the resulting [`TauBatch`].
+
+use super::source::Clock;
+use crate::error::{Error, Result};
+use crate::orderbook::{MassCancelScope, ScheduledCancel, UnderlyingOrderBookManager};
+use optionstratlib::ExpirationDate;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal_macros::dec;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Milliseconds in a day.
+const MS_PER_DAY: Decimal = dec!(86_400_000);
+
+/// Sentinel for "no tick has run yet".
+const NEVER: u64 = u64::MAX;
+
+/// Time to expiry of one chain at a tick.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TauUpdate {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Expiration of the chain.
+    pub expiration: ExpirationDate,
+    /// Milliseconds until expiry, zero once expired.
+    pub time_to_expiry_ms: u64,
+    /// Time to expiry in years (365-day basis).
+    pub tau: Decimal,
+}
+
+/// Every chain's time to expiry computed by one tick.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TauBatch {
+    /// Clock time of the tick in milliseconds.
+    pub timestamp_ms: u64,
+    /// One update per chain.
+    pub updates: Vec<TauUpdate>,
+}
+
+/// Receiver of time-to-expiry batches, implemented by pricing and quoting
+/// components that cache per-expiry state.
+pub trait TauListener: Send + Sync {
+    /// Called once per tick with every chain's new time to expiry.
+    fn on_tau_batch(&self, batch: &TauBatch);
+}
+
+/// Chain-level clock that recomputes time to expiry in batches.
+///
+/// Expirations given as [`ExpirationDate::DateTime`] are exact. Expirations
+/// given as [`ExpirationDate::Days`] are anchored at the clock time when the
+/// `ExpiryClock` was created, so they decay with the clock rather than
+/// staying a fixed distance from "now".
+pub struct ExpiryClock {
+    /// Time source.
+    clock: Arc<dyn Clock>,
+    /// Minimum time between ticks in milliseconds.
+    interval_ms: u64,
+    /// Clock time that `ExpirationDate::Days` are measured from.
+    anchor_ms: u64,
+    /// Clock time of the last tick.
+    last_tick_ms: AtomicU64,
+    /// Receivers of each batch.
+    listeners: RwLock<Vec<Arc<dyn TauListener>>>,
+}
+
+impl ExpiryClock {
+    /// Creates an expiry clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - Time source
+    /// * `interval_ms` - Minimum time between ticks in milliseconds
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the interval is zero.
+    pub fn new(clock: Arc<dyn Clock>, interval_ms: u64) -> Result<Self> {
+        if interval_ms == 0 {
+            return Err(Error::configuration("tick interval must be positive"));
+        }
+        Ok(Self {
+            anchor_ms: clock.now_ms(),
+            clock,
+            interval_ms,
+            last_tick_ms: AtomicU64::new(NEVER),
+            listeners: RwLock::new(Vec::new()),
+        })
+    }
+
+    /// Registers a receiver of each published batch.
+    pub fn subscribe(&self, listener: Arc<dyn TauListener>) {
+        if let Ok(mut listeners) = self.listeners.write() {
+            listeners.push(listener);
+        }
+    }
+
+    /// Returns the tick interval in milliseconds.
+    #[must_use]
+    pub const fn interval_ms(&self) -> u64 {
+        self.interval_ms
+    }
+
+    /// Returns the clock time of the last tick, if any.
+    #[must_use]
+    pub fn last_tick_ms(&self) -> Option<u64> {
+        match self.last_tick_ms.load(Ordering::Acquire) {
+            NEVER => None,
+            ms => Some(ms),
+        }
+    }
+
+    /// Returns the current clock time in milliseconds.
+    #[must_use]
+    pub fn now_ms(&self) -> u64 {
+        self.clock.now_ms()
+    }
+
+    /// Returns the expiry of an expiration in clock milliseconds.
+    #[must_use]
+    pub fn expiry_ms(&self, expiration: &ExpirationDate) -> u64 {
+        match expiration {
+            ExpirationDate::DateTime(dt) => u64::try_from(dt.timestamp_millis()).unwrap_or(0),
+            ExpirationDate::Days(days) => {
+                let offset = (days.to_dec() * MS_PER_DAY).to_u64().unwrap_or(u64::MAX);
+                self.anchor_ms.saturating_add(offset)
+            }
+        }
+    }
+
+    /// Schedules a mass cancel in every book of every expiration a lead
+    /// time before its expiry, e.g. pulling our orders one minute before.
+    ///
+    /// The cancels run on the books' `run_timers`, so the books should read
+    /// the same clock. Returns the number of expirations scheduled.
+    pub fn schedule_expiry_cancels(
+        &self,
+        manager: &UnderlyingOrderBookManager,
+        lead_ms: u64,
+        scope: &MassCancelScope,
+    ) -> usize {
+        let mut scheduled = 0;
+        for underlying in manager.iter() {
+            for expiration in underlying.value().expirations().iter() {
+                let at = self.expiry_ms(expiration.key());
+                expiration
+                    .value()
+                    .schedule_mass_cancel(&ScheduledCancel::before(at, lead_ms, scope.clone()));
+                scheduled += 1;
+            }
+        }
+        scheduled
+    }
+
+    /// Ticks if at least one interval has passed since the last tick.
+    ///
+    /// Returns the published batch, or `None` if it was too early.
+    pub fn tick(&self, manager: &UnderlyingOrderBookManager) -> Option<TauBatch> {
+        let now = self.clock.now_ms();
+        let last = self.last_tick_ms.load(Ordering::Acquire);
+        if last != NEVER && now.saturating_sub(last) < self.interval_ms {
+            return None;
+        }
+        if self
+            .last_tick_ms
+            .compare_exchange(last, now, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            // Another thread ticked concurrently.
+            return None;
+        }
+        Some(self.publish(manager, now))
+    }
+
+    /// Ticks unconditionally.
+    ///
+    /// Returns the published batch.
+    pub fn force_tick(&self, manager: &UnderlyingOrderBookManager) -> TauBatch {
+        let now = self.clock.now_ms();
+        self.last_tick_ms.store(now, Ordering::Release);
+        self.publish(manager, now)
+    }
+
+    /// Updates every chain and notifies listeners.
+    fn publish(&self, manager: &UnderlyingOrderBookManager, now: u64) -> TauBatch {
+        let mut updates = Vec::new();
+        for underlying in manager.iter() {
+            for expiration in underlying.value().expirations().iter() {
+                let chain = expiration.value().chain();
+                let remaining = self.expiry_ms(expiration.key()).saturating_sub(now);
+                chain.set_time_to_expiry_ms(remaining);
+                updates.push(TauUpdate {
+                    underlying: underlying.key().clone(),
+                    expiration: *expiration.key(),
+                    time_to_expiry_ms: remaining,
+                    tau: chain.tau().unwrap_or(Decimal::ZERO),
+                });
+            }
+        }
+
+        let batch = TauBatch {
+            timestamp_ms: now,
+            updates,
+        };
+        if let Ok(listeners) = self.listeners.read() {
+            for listener in listeners.iter() {
+                listener.on_tau_batch(&batch);
+            }
+        }
+        batch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use chrono::{TimeZone, Utc};
+    use optionstratlib::prelude::pos_or_panic;
+    use std::sync::Mutex;
+
+    const START_MS: u64 = 1_700_000_000_000;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<usize>>);
+
+    impl TauListener for Recorder {
+        fn on_tau_batch(&self, batch: &TauBatch) {
+            self.0.lock().unwrap().push(batch.updates.len());
+        }
+    }
+
+    fn setup() -> (Arc<ManualClock>, ExpiryClock, UnderlyingOrderBookManager) {
+        let clock = Arc::new(ManualClock::new(START_MS));
+        let expiry = ExpiryClock::new(clock.clone(), 1_000).unwrap();
+        let manager = UnderlyingOrderBookManager::new();
+        let btc = manager.get_or_create("BTC");
+        drop(btc.get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(1.0))));
+        drop(btc.get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(365.0))));
+        (clock, expiry, manager)
+    }
+
+    #[test]
+    fn test_zero_interval_rejected() {
+        assert!(ExpiryClock::new(Arc::new(ManualClock::new(0)), 0).is_err());
+    }
+
+    #[test]
+    fn test_tick_updates_all_chains() {
+        let (_clock, expiry, manager) = setup();
+
+        let batch = expiry.tick(&manager).unwrap();
+
+        assert_eq!(batch.timestamp_ms, START_MS);
+        assert_eq!(batch.updates.len(), 2);
+        let year = batch
+            .updates
+            .iter()
+            .find(|u| u.time_to_expiry_ms == 31_536_000_000)
+            .unwrap();
+        assert_eq!(year.tau, Decimal::ONE);
+
+        let chain = manager
+            .get("BTC")
+            .unwrap()
+            .get_expiration(&ExpirationDate::Days(pos_or_panic!(1.0)))
+            .unwrap()
+            .chain_arc();
+        assert_eq!(chain.time_to_expiry_ms(), Some(86_400_000));
+    }
+
+    #[test]
+    fn test_tick_respects_interval() {
+        let (clock, expiry, manager) = setup();
+        let recorder = Arc::new(Recorder::default());
+        expiry.subscribe(recorder.clone());
+
+        assert!(expiry.tick(&manager).is_some());
+        clock.advance(999);
+        assert!(expiry.tick(&manager).is_none());
+        clock.advance(1);
+        let batch = expiry.tick(&manager).unwrap();
+
+        assert_eq!(expiry.last_tick_ms(), Some(START_MS + 1_000));
+        assert!(
+            batch
+                .updates
+                .iter()
+                .any(|u| u.time_to_expiry_ms == 86_399_000)
+        );
+        assert_eq!(*recorder.0.lock().unwrap(), vec![2, 2]);
+    }
+
+    #[test]
+    fn test_expired_chain_clamps_to_zero() {
+        let (clock, expiry, manager) = setup();
+        clock.advance(2 * 86_400_000);
+
+        let batch = expiry.force_tick(&manager);
+
+        assert!(batch.updates.iter().any(|u| u.time_to_expiry_ms == 0));
+    }
+
+    #[test]
+    fn test_datetime_expiry_is_exact() {
+        let (_clock, expiry, _manager) = setup();
+        let date = Utc.with_ymd_and_hms(2030, 1, 1, 8, 0, 0).unwrap();
+        assert_eq!(
+            expiry.expiry_ms(&ExpirationDate::DateTime(date)),
+            date.timestamp_millis() as u64
+        );
+    }
+
+    #[test]
+    fn test_expiry_cancels_fire_by_clock() {
+        use orderbook_rs::{OrderId, Side};
+
+        let (clock, expiry, manager) = setup();
+        let btc = manager.get("BTC").unwrap();
+        for days in [1.0, 365.0] {
+            btc.get_expiration(&ExpirationDate::Days(pos_or_panic!(days)))
+                .unwrap()
+                .get_or_create_strike(50000)
+                .call()
+                .add_limit_order(OrderId::new(), Side::Sell, 100, 1)
+                .unwrap();
+        }
+        manager.set_clock(clock.clone());
+
+        let all = MassCancelScope::All;
+        assert_eq!(expiry.schedule_expiry_cancels(&manager, 60_000, &all), 2);
+        clock.advance(86_400_000 - 60_001);
+        assert_eq!(manager.run_timers().mass_cancels, 0);
+        assert_eq!(manager.total_order_count(), 2);
+
+        // One minute before the one-day expiry, its call and put books run
+        // their cancel; the one-year chain is untouched.
+        clock.advance(1);
+        let report = manager.run_timers();
+        assert_eq!((report.mass_cancels, report.mass_cancelled), (2, 1));
+        assert_eq!(manager.total_order_count(), 1);
+    }
+}
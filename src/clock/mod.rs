@@ -0,0 +1,24 @@
+//! Clock module.
+//!
+//! This module provides the [`Clock`] abstraction used instead of reading
+//! the system time directly, and the [`ExpiryClock`], which recomputes the
+//! time to expiry of every chain once per interval in a single pass and
+//! publishes the batch to pricing and quoting.
+//!
+//! ## Components
+//!
+//! - [`Clock`]: Source of the current time in milliseconds
+//! - [`SystemClock`]: Wall-clock implementation
+//! - [`ManualClock`]: Deterministic clock for tests, simulations and replays
+//! - [`TimeSource`]: Wall-clock and monotonic readings taken as one [`Timestamp`]
+//! - [`DriftMonitor`]: Detects wall-clock steps and drifting external timestamps
+//! - [`ExpiryClock`]: Batched time-to-expiry updates across the hierarchy, and mass cancels a lead time before each expiry
+//! - [`TauListener`]: Receiver of each published [`TauBatch`]
+
+mod expiry;
+mod source;
+mod time;
+
+pub use expiry::{ExpiryClock, TauBatch, TauListener, TauUpdate};
+pub use source::{Clock, ManualClock, SystemClock};
+pub use time::{ClockDrift, DriftMonitor, TimeSource, Timestamp};
@@ -0,0 +1,84 @@
+//! Clock source module.
+//!
+//! This module provides the [`Clock`] trait and its wall-clock and manual
+//! implementations.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Source of the current time.
+pub trait Clock: Send + Sync {
+    /// Returns the current time in milliseconds since the Unix epoch.
+    fn now_ms(&self) -> u64;
+}
+
+/// Clock backed by the system wall clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        orderbook_rs::current_time_millis()
+    }
+}
+
+/// Clock that only moves when told to.
+///
+/// Used to make time-dependent logic deterministic in tests, simulations
+/// and replays.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    /// Current time in milliseconds.
+    now_ms: AtomicU64,
+}
+
+impl ManualClock {
+    /// Creates a manual clock at the given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `now_ms` - Initial time in milliseconds since the Unix epoch
+    #[must_use]
+    pub const fn new(now_ms: u64) -> Self {
+        Self {
+            now_ms: AtomicU64::new(now_ms),
+        }
+    }
+
+    /// Sets the current time.
+    pub fn set(&self, now_ms: u64) {
+        self.now_ms.store(now_ms, Ordering::Release);
+    }
+
+    /// Moves the clock forward.
+    ///
+    /// Returns the new time.
+    pub fn advance(&self, ms: u64) -> u64 {
+        self.now_ms.fetch_add(ms, Ordering::AcqRel) + ms
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ms(&self) -> u64 {
+        self.now_ms.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock() {
+        let clock = ManualClock::new(1_000);
+        assert_eq!(clock.now_ms(), 1_000);
+        assert_eq!(clock.advance(500), 1_500);
+        clock.set(10);
+        assert_eq!(clock.now_ms(), 10);
+    }
+
+    #[test]
+    fn test_system_clock_is_recent() {
+        // 2020-01-01T00:00:00Z
+        assert!(SystemClock.now_ms() > 1_577_836_800_000);
+    }
+}
@@ -0,0 +1,247 @@
+//! Time source module.
+//!
+//! This module provides the [`TimeSource`] trait, which pairs the wall
+//! clock with a monotonic clock, the [`Timestamp`] it produces, and the
+//! [`DriftMonitor`], which detects when the wall clock or an external
+//! timestamp source drifts away from local monotonic time.
+//!
+//! ## Policy
+//!
+//! - Wall-clock milliseconds are used for anything stored, compared across
+//!   processes or shown to people: quote, breach and config event
+//!   timestamps, P&L instants.
+//! - Monotonic nanoseconds are used for intervals and latencies within a
+//!   process. They never go backwards but have no meaning across restarts.
+
+use super::source::{Clock, ManualClock, SystemClock};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::Instant;
+
+/// Nanoseconds per millisecond.
+const NS_PER_MS: u64 = 1_000_000;
+
+/// A wall-clock and monotonic reading taken together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Timestamp {
+    /// Milliseconds since the Unix epoch.
+    pub wall_ms: u64,
+    /// Monotonic nanoseconds since an arbitrary process-local origin.
+    pub monotonic_ns: u64,
+}
+
+impl Timestamp {
+    /// Returns the monotonic nanoseconds elapsed since an earlier reading,
+    /// or zero if `earlier` is not earlier.
+    #[must_use]
+    pub const fn elapsed_ns_since(&self, earlier: &Self) -> u64 {
+        self.monotonic_ns.saturating_sub(earlier.monotonic_ns)
+    }
+
+    /// Estimates the wall-clock time of another monotonic reading using
+    /// this reading as the anchor.
+    #[must_use]
+    pub const fn wall_ms_at(&self, monotonic_ns: u64) -> u64 {
+        if monotonic_ns >= self.monotonic_ns {
+            self.wall_ms + (monotonic_ns - self.monotonic_ns) / NS_PER_MS
+        } else {
+            self.wall_ms
+                .saturating_sub((self.monotonic_ns - monotonic_ns) / NS_PER_MS)
+        }
+    }
+
+    /// Returns the wall-clock time as a UTC date-time.
+    #[must_use]
+    pub fn to_utc(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp_millis(i64::try_from(self.wall_ms).ok()?)
+    }
+}
+
+/// Source of wall-clock and monotonic time.
+pub trait TimeSource: Clock {
+    /// Returns monotonic nanoseconds since a process-local origin.
+    fn monotonic_ns(&self) -> u64;
+
+    /// Returns both readings.
+    fn timestamp(&self) -> Timestamp {
+        Timestamp {
+            wall_ms: self.now_ms(),
+            monotonic_ns: self.monotonic_ns(),
+        }
+    }
+}
+
+/// Origin of the process-wide monotonic clock.
+fn monotonic_origin() -> Instant {
+    static ORIGIN: OnceLock<Instant> = OnceLock::new();
+    *ORIGIN.get_or_init(Instant::now)
+}
+
+impl TimeSource for SystemClock {
+    fn monotonic_ns(&self) -> u64 {
+        u64::try_from(monotonic_origin().elapsed().as_nanos()).unwrap_or(u64::MAX)
+    }
+}
+
+/// Monotonic time of a manual clock is its wall time in nanoseconds, so
+/// setting it backwards moves both.
+impl TimeSource for ManualClock {
+    fn monotonic_ns(&self) -> u64 {
+        self.now_ms().saturating_mul(NS_PER_MS)
+    }
+}
+
+/// A detected difference between a time source and local monotonic time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockDrift {
+    /// Source that drifted (`"wall"` for the local wall clock).
+    pub source: String,
+    /// Source time minus expected time in milliseconds.
+    pub drift_ms: i64,
+    /// Local reading the drift was measured at.
+    pub timestamp: Timestamp,
+}
+
+/// Detects drift of the wall clock and external sources against local
+/// monotonic time.
+///
+/// Wall-clock drift is measured from an anchor reading: after a drift is
+/// reported the monitor re-anchors, so a single clock step is reported
+/// once.
+#[derive(Debug)]
+pub struct DriftMonitor {
+    /// Largest tolerated drift in milliseconds.
+    max_drift_ms: u64,
+    /// Reading wall-clock drift is measured against.
+    anchor: Mutex<Option<Timestamp>>,
+}
+
+impl DriftMonitor {
+    /// Creates a monitor.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_drift_ms` - Largest tolerated drift in milliseconds
+    #[must_use]
+    pub const fn new(max_drift_ms: u64) -> Self {
+        Self {
+            max_drift_ms,
+            anchor: Mutex::new(None),
+        }
+    }
+
+    /// Returns the largest tolerated drift in milliseconds.
+    #[must_use]
+    pub const fn max_drift_ms(&self) -> u64 {
+        self.max_drift_ms
+    }
+
+    /// Checks the wall clock against monotonic time since the anchor.
+    ///
+    /// The first reading becomes the anchor.
+    pub fn observe(&self, timestamp: Timestamp) -> Option<ClockDrift> {
+        let mut anchor = self.lock();
+        let Some(base) = *anchor else {
+            *anchor = Some(timestamp);
+            return None;
+        };
+        let expected = base.wall_ms_at(timestamp.monotonic_ns);
+        let drift = self.exceeds("wall", timestamp.wall_ms, expected, timestamp);
+        if drift.is_some() {
+            *anchor = Some(timestamp);
+        }
+        drift
+    }
+
+    /// Checks an external timestamp (e.g. an exchange's) against local
+    /// wall-clock time.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Name of the external source
+    /// * `source_ms` - External timestamp in milliseconds
+    /// * `local` - Local reading taken when the external timestamp arrived
+    #[must_use]
+    pub fn compare(&self, source: &str, source_ms: u64, local: Timestamp) -> Option<ClockDrift> {
+        self.exceeds(source, source_ms, local.wall_ms, local)
+    }
+
+    fn exceeds(
+        &self,
+        source: &str,
+        actual_ms: u64,
+        expected_ms: u64,
+        timestamp: Timestamp,
+    ) -> Option<ClockDrift> {
+        let drift_ms = i128::from(actual_ms) - i128::from(expected_ms);
+        (drift_ms.unsigned_abs() > u128::from(self.max_drift_ms)).then(|| ClockDrift {
+            source: source.to_string(),
+            drift_ms: i64::try_from(drift_ms).unwrap_or(i64::MAX),
+            timestamp,
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<Timestamp>> {
+        self.anchor
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(wall_ms: u64, monotonic_ms: u64) -> Timestamp {
+        Timestamp {
+            wall_ms,
+            monotonic_ns: monotonic_ms * NS_PER_MS,
+        }
+    }
+
+    #[test]
+    fn test_system_monotonic_never_decreases() {
+        let a = SystemClock.timestamp();
+        let b = SystemClock.timestamp();
+        assert!(b.monotonic_ns >= a.monotonic_ns);
+        assert!(a.to_utc().is_some());
+    }
+
+    #[test]
+    fn test_conversions() {
+        let anchor = reading(10_000, 500);
+        assert_eq!(anchor.wall_ms_at(700 * NS_PER_MS), 10_200);
+        assert_eq!(anchor.wall_ms_at(400 * NS_PER_MS), 9_900);
+        assert_eq!(reading(0, 800).elapsed_ns_since(&anchor), 300 * NS_PER_MS);
+        assert_eq!(anchor.elapsed_ns_since(&reading(0, 800)), 0);
+    }
+
+    #[test]
+    fn test_wall_clock_step_is_reported_once() {
+        let monitor = DriftMonitor::new(50);
+        assert!(monitor.observe(reading(10_000, 0)).is_none());
+        assert!(monitor.observe(reading(11_020, 1_000)).is_none());
+
+        // Wall clock stepped back by a second.
+        let drift = monitor.observe(reading(11_000, 2_000)).unwrap();
+        assert_eq!(drift.source, "wall");
+        assert_eq!(drift.drift_ms, -1_000);
+        assert!(monitor.observe(reading(12_000, 3_000)).is_none());
+    }
+
+    #[test]
+    fn test_external_source_drift() {
+        let monitor = DriftMonitor::new(100);
+        let local = reading(50_000, 0);
+        assert!(monitor.compare("deribit", 50_080, local).is_none());
+        let drift = monitor.compare("deribit", 50_250, local).unwrap();
+        assert_eq!(drift.drift_ms, 250);
+    }
+
+    #[test]
+    fn test_manual_time_source() {
+        let clock = ManualClock::new(5);
+        assert_eq!(clock.timestamp(), reading(5, 5));
+    }
+}
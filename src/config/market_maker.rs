@@ -0,0 +1,112 @@
+//! Market maker configuration module.
+//!
+//! This module provides [`MarketMakerConfig`] and its [`QuotingConfig`]
+//! section. Every section defaults when omitted from a file.
+
+use crate::error::Result;
+use crate::inventory::PositionLimits;
+use crate::quoting::{ParityConfig, QuoteEngine, SpreadCalculator};
+use crate::risk::RiskLimits;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Quote model and parity settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuotingConfig {
+    /// Size quoted on each side.
+    pub base_size: u64,
+    /// Narrowest spread allowed.
+    pub min_spread: Decimal,
+    /// Widest spread allowed.
+    pub max_spread: Decimal,
+    /// Parity-consistency pass settings.
+    pub parity: ParityConfig,
+}
+
+impl Default for QuotingConfig {
+    fn default() -> Self {
+        Self {
+            base_size: 10,
+            min_spread: dec!(0.01),
+            max_spread: dec!(100),
+            parity: ParityConfig::default(),
+        }
+    }
+}
+
+impl QuotingConfig {
+    /// Builds the quote engine described by this configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the spread bounds or parity
+    /// settings are invalid.
+    pub fn engine(&self) -> Result<QuoteEngine> {
+        let calculator = SpreadCalculator::new(self.base_size)
+            .with_spread_bounds(self.min_spread, self.max_spread)?;
+        QuoteEngine::new(calculator).with_parity(self.parity)
+    }
+}
+
+/// Top-level market maker configuration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarketMakerConfig {
+    /// Greek and loss limits.
+    pub risk: RiskLimits,
+    /// Position limits.
+    pub positions: PositionLimits,
+    /// Quoting parameters.
+    pub quoting: QuotingConfig,
+}
+
+impl MarketMakerConfig {
+    /// Validates every section.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` describing the first invalid
+    /// section.
+    pub fn validate(&self) -> Result<()> {
+        self.risk.validate()?;
+        self.positions.validate()?;
+        self.quoting.engine().map(|_| ())
+    }
+
+    /// Parses and validates a configuration from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::SerializationError` if the JSON is malformed, or
+    /// `Error::ConfigurationError` if the configuration is invalid.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let config: Self = serde_json::from_str(json)?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_valid() {
+        assert!(MarketMakerConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_partial_json_uses_defaults() {
+        let config = MarketMakerConfig::from_json(r#"{"quoting": {"base_size": 25}}"#).unwrap();
+        assert_eq!(config.quoting.base_size, 25);
+        assert_eq!(config.risk, RiskLimits::default());
+    }
+
+    #[test]
+    fn test_invalid_section_rejected() {
+        let json = r#"{"quoting": {"min_spread": 5, "max_spread": 1}}"#;
+        assert!(MarketMakerConfig::from_json(json).is_err());
+    }
+}
@@ -0,0 +1,25 @@
+//! Configuration module.
+//!
+//! This module provides [`MarketMakerConfig`], the serializable top-level
+//! configuration of the risk, inventory and quoting layers, and the
+//! [`ConfigWatcher`], which reloads it from a file at runtime.
+//!
+//! ## Components
+//!
+//! - [`MarketMakerConfig`]: Risk limits, position limits and quoting parameters
+//! - [`QuotingConfig`]: Quote model and parity settings
+//! - [`ConfigWatcher`]: File reload with validation, staging, diff and rollback
+//! - [`ConfigChange`]: One changed effective parameter
+//! - [`ConfigEvent`]: Audit record of each staged, applied, rejected or rolled-back version
+//!
+//! ## Staged Application
+//!
+//! [`ConfigWatcher::poll`] only validates and stages a new version. The
+//! quoting loop calls [`ConfigWatcher::apply_staged`] between cycles, so a
+//! cycle never sees a mix of old and new parameters.
+
+mod market_maker;
+mod watcher;
+
+pub use market_maker::{MarketMakerConfig, QuotingConfig};
+pub use watcher::{ConfigChange, ConfigEvent, ConfigEventKind, ConfigWatcher};
@@ -0,0 +1,424 @@
+//! Configuration watcher module.
+//!
+//! This module provides the [`ConfigWatcher`], which reloads a
+//! [`MarketMakerConfig`] from a JSON file, validates it, stages it with a
+//! diff of the effective parameters, and applies it when the caller
+//! reaches a safe point.
+//!
+//! An invalid file never replaces the running configuration: it is
+//! rejected, any previously staged version is discarded, and the running
+//! version stays in effect.
+
+use super::market_maker::MarketMakerConfig;
+use crate::clock::Clock;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// One changed effective parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigChange {
+    /// Dotted parameter path, e.g. `quoting.base_size`.
+    pub path: String,
+    /// Previous value, rendered as JSON.
+    pub old: String,
+    /// New value, rendered as JSON.
+    pub new: String,
+}
+
+impl std::fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} -> {}", self.path, self.old, self.new)
+    }
+}
+
+/// What happened to a configuration version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConfigEventKind {
+    /// Validated and waiting for a safe point.
+    Staged,
+    /// Became the running configuration.
+    Applied,
+    /// Failed to parse or validate; the running configuration was kept.
+    Rejected {
+        /// Parse or validation error.
+        reason: String,
+    },
+    /// The previous configuration was restored.
+    RolledBack,
+}
+
+/// Audit record of a configuration change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigEvent {
+    /// Version the event refers to.
+    pub version: u64,
+    /// Clock time of the event in milliseconds.
+    pub timestamp_ms: u64,
+    /// What happened.
+    pub kind: ConfigEventKind,
+    /// Parameters changed relative to the running configuration.
+    pub changes: Vec<ConfigChange>,
+}
+
+/// A validated version waiting to be applied.
+struct Staged {
+    /// Version number.
+    version: u64,
+    /// Configuration.
+    config: MarketMakerConfig,
+    /// Changes relative to the running version when staged.
+    changes: Vec<ConfigChange>,
+}
+
+/// Mutable watcher state.
+struct WatcherState {
+    /// Running configuration.
+    current: Arc<MarketMakerConfig>,
+    /// Running version number.
+    version: u64,
+    /// Configuration replaced by the last apply, for rollback.
+    previous: Option<(Arc<MarketMakerConfig>, u64)>,
+    /// Version waiting for a safe point.
+    staged: Option<Staged>,
+    /// Last version number handed out.
+    last_version: u64,
+    /// File contents at the last poll.
+    last_contents: Option<String>,
+    /// Audit trail.
+    events: Vec<ConfigEvent>,
+}
+
+/// Reloads, validates and stages configuration changes from a file.
+pub struct ConfigWatcher {
+    /// Watched file.
+    path: PathBuf,
+    /// Time source for event timestamps.
+    clock: Arc<dyn Clock>,
+    /// Watcher state.
+    state: Mutex<WatcherState>,
+}
+
+impl ConfigWatcher {
+    /// Creates a watcher running `initial` as version 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - JSON file to watch
+    /// * `initial` - Configuration in effect until the first apply
+    /// * `clock` - Time source for event timestamps
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if `initial` is invalid.
+    pub fn new(
+        path: impl AsRef<Path>,
+        initial: MarketMakerConfig,
+        clock: Arc<dyn Clock>,
+    ) -> Result<Self> {
+        initial.validate()?;
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            clock,
+            state: Mutex::new(WatcherState {
+                current: Arc::new(initial),
+                version: 0,
+                previous: None,
+                staged: None,
+                last_version: 0,
+                last_contents: None,
+                events: Vec::new(),
+            }),
+        })
+    }
+
+    /// Returns the watched file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the running configuration.
+    #[must_use]
+    pub fn current(&self) -> Arc<MarketMakerConfig> {
+        Arc::clone(&self.lock().current)
+    }
+
+    /// Returns the running version number.
+    #[must_use]
+    pub fn version(&self) -> u64 {
+        self.lock().version
+    }
+
+    /// Returns the changes of the staged version, if any.
+    #[must_use]
+    pub fn staged_changes(&self) -> Option<Vec<ConfigChange>> {
+        self.lock().staged.as_ref().map(|s| s.changes.clone())
+    }
+
+    /// Returns the audit trail, oldest first.
+    #[must_use]
+    pub fn events(&self) -> Vec<ConfigEvent> {
+        self.lock().events.clone()
+    }
+
+    /// Re-reads the file and stages it if its contents changed.
+    ///
+    /// Returns the staged changes, or `None` if the file is unchanged or
+    /// matches the running configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::IoError` if the file cannot be read, or the parse or
+    /// validation error if the new contents are rejected.
+    pub fn poll(&self) -> Result<Option<Vec<ConfigChange>>> {
+        let contents = std::fs::read_to_string(&self.path)?;
+        {
+            let mut state = self.lock();
+            if state.last_contents.as_deref() == Some(contents.as_str()) {
+                return Ok(None);
+            }
+            state.last_contents = Some(contents.clone());
+        }
+        self.stage_parsed(MarketMakerConfig::from_json(&contents))
+    }
+
+    /// Validates and stages a configuration supplied directly.
+    ///
+    /// Returns the staged changes, or `None` if it matches the running
+    /// configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns the validation error if the configuration is rejected.
+    pub fn stage(&self, config: MarketMakerConfig) -> Result<Option<Vec<ConfigChange>>> {
+        self.stage_parsed(config.validate().map(|()| config))
+    }
+
+    /// Makes the staged version the running configuration.
+    ///
+    /// Call between quoting cycles. Returns the applied version, or `None`
+    /// if nothing was staged.
+    pub fn apply_staged(&self) -> Option<u64> {
+        let now = self.clock.now_ms();
+        let mut state = self.lock();
+        let staged = state.staged.take()?;
+        let replaced = std::mem::replace(&mut state.current, Arc::new(staged.config));
+        state.previous = Some((replaced, state.version));
+        state.version = staged.version;
+        state.events.push(ConfigEvent {
+            version: staged.version,
+            timestamp_ms: now,
+            kind: ConfigEventKind::Applied,
+            changes: staged.changes,
+        });
+        Some(staged.version)
+    }
+
+    /// Restores the configuration replaced by the last apply.
+    ///
+    /// Returns the restored version.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if there is nothing to roll back to.
+    pub fn rollback(&self) -> Result<u64> {
+        let now = self.clock.now_ms();
+        let mut state = self.lock();
+        let (previous, version) = state
+            .previous
+            .take()
+            .ok_or_else(|| Error::configuration("no previous configuration to roll back to"))?;
+        let changes = diff(&state.current, &previous);
+        state.current = previous;
+        state.version = version;
+        state.staged = None;
+        state.events.push(ConfigEvent {
+            version,
+            timestamp_ms: now,
+            kind: ConfigEventKind::RolledBack,
+            changes,
+        });
+        Ok(version)
+    }
+
+    /// Stages a parsed configuration or records its rejection.
+    fn stage_parsed(&self, parsed: Result<MarketMakerConfig>) -> Result<Option<Vec<ConfigChange>>> {
+        let now = self.clock.now_ms();
+        let mut state = self.lock();
+        let config = match parsed {
+            Ok(config) => config,
+            Err(e) => {
+                state.last_version += 1;
+                let version = state.last_version;
+                state.staged = None;
+                state.events.push(ConfigEvent {
+                    version,
+                    timestamp_ms: now,
+                    kind: ConfigEventKind::Rejected {
+                        reason: e.to_string(),
+                    },
+                    changes: Vec::new(),
+                });
+                return Err(e);
+            }
+        };
+
+        let changes = diff(&state.current, &config);
+        if changes.is_empty() {
+            state.staged = None;
+            return Ok(None);
+        }
+        state.last_version += 1;
+        let version = state.last_version;
+        state.events.push(ConfigEvent {
+            version,
+            timestamp_ms: now,
+            kind: ConfigEventKind::Staged,
+            changes: changes.clone(),
+        });
+        state.staged = Some(Staged {
+            version,
+            config,
+            changes: changes.clone(),
+        });
+        Ok(Some(changes))
+    }
+
+    /// Locks the state, recovering from poisoning since every update
+    /// leaves it consistent.
+    fn lock(&self) -> MutexGuard<'_, WatcherState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Returns the effective parameters that differ between two configurations.
+fn diff(old: &MarketMakerConfig, new: &MarketMakerConfig) -> Vec<ConfigChange> {
+    let mut changes = Vec::new();
+    if let (Ok(old), Ok(new)) = (serde_json::to_value(old), serde_json::to_value(new)) {
+        diff_values("", &old, &new, &mut changes);
+    }
+    changes
+}
+
+/// Collects differing leaves of two JSON values.
+fn diff_values(path: &str, old: &Value, new: &Value, changes: &mut Vec<ConfigChange>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            let mut keys: Vec<&String> = old_map.keys().chain(new_map.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                diff_values(
+                    &child,
+                    old_map.get(key).unwrap_or(&Value::Null),
+                    new_map.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ if old != new => changes.push(ConfigChange {
+            path: path.to_string(),
+            old: old.to_string(),
+            new: new.to_string(),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    fn watcher(name: &str) -> (ConfigWatcher, PathBuf) {
+        let path =
+            std::env::temp_dir().join(format!("occ-config-{name}-{}.json", std::process::id()));
+        let watcher = ConfigWatcher::new(
+            &path,
+            MarketMakerConfig::default(),
+            Arc::new(ManualClock::new(1_000)),
+        )
+        .unwrap();
+        (watcher, path)
+    }
+
+    #[test]
+    fn test_poll_stages_then_applies() {
+        let (watcher, path) = watcher("apply");
+        std::fs::write(&path, r#"{"quoting": {"base_size": 25}}"#).unwrap();
+
+        let changes = watcher.poll().unwrap().unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, "quoting.base_size");
+        assert_eq!(changes[0].new, "25");
+        assert_eq!(watcher.current().quoting.base_size, 10);
+
+        // Unchanged file is not staged twice.
+        assert!(watcher.poll().unwrap().is_none());
+
+        assert_eq!(watcher.apply_staged(), Some(1));
+        assert_eq!(watcher.current().quoting.base_size, 25);
+        assert_eq!(watcher.apply_staged(), None);
+
+        let kinds: Vec<_> = watcher.events().into_iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![ConfigEventKind::Staged, ConfigEventKind::Applied]
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_file_keeps_running_config() {
+        let (watcher, path) = watcher("invalid");
+        std::fs::write(&path, r#"{"quoting": {"base_size": 25}}"#).unwrap();
+        watcher.poll().unwrap();
+
+        std::fs::write(&path, r#"{"positions": {"per_option": -1}}"#).unwrap();
+        assert!(watcher.poll().is_err());
+
+        // The rejected version also discards what was staged before it.
+        assert!(watcher.staged_changes().is_none());
+        assert_eq!(watcher.apply_staged(), None);
+        assert_eq!(*watcher.current(), MarketMakerConfig::default());
+        assert!(matches!(
+            watcher.events().last().unwrap().kind,
+            ConfigEventKind::Rejected { .. }
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_rollback() {
+        let (watcher, _) = watcher("rollback");
+        assert!(watcher.rollback().is_err());
+
+        let mut config = MarketMakerConfig::default();
+        config.quoting.base_size = 50;
+        watcher.stage(config).unwrap();
+        watcher.apply_staged();
+
+        assert_eq!(watcher.rollback().unwrap(), 0);
+        assert_eq!(watcher.version(), 0);
+        assert_eq!(watcher.current().quoting.base_size, 10);
+        let last = watcher.events().pop().unwrap();
+        assert_eq!(last.kind, ConfigEventKind::RolledBack);
+        assert_eq!(last.changes[0].new, "10");
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let (watcher, _) = watcher("missing");
+        assert!(matches!(watcher.poll(), Err(Error::IoError(_))));
+    }
+}
@@ -136,6 +136,10 @@ pub enum Error {
     #[error("serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
 
+    /// Error from an I/O operation.
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+
     /// Error when a decimal conversion fails.
     #[error("decimal conversion error: {message}")]
     DecimalError {
@@ -0,0 +1,339 @@
+//! Hedge book module.
+//!
+//! This module provides the [`HedgeBook`], the positions held in linear
+//! hedge instruments (futures, perpetuals, spot) built from the fills of
+//! [`HedgeOrder`]s. Each [`HedgePosition`] tracks its signed quantity,
+//! average entry price, realized P&L, fees and the funding or financing
+//! paid while it is held.
+//!
+//! ## P&L
+//!
+//! ```text
+//! unrealized = quantity * (mark - average price)
+//! total      = realized + unrealized - funding - fees
+//! ```
+//!
+//! Funding is signed from the holder's point of view: a positive payment is
+//! a cost, so longs pay a positive perpetual funding rate and receive a
+//! negative one.
+//!
+//! ## Residual delta
+//!
+//! The hedge delta of the book added to the option delta gives the residual
+//! the [`DeltaHedger`](super::DeltaHedger) still has to hedge; see
+//! [`DeltaHedger::on_fill`](super::DeltaHedger::on_fill) and
+//! [`DeltaHedger::rebalance`](super::DeltaHedger::rebalance).
+
+use super::order::HedgeOrder;
+use crate::error::{Error, Result};
+use orderbook_rs::Side;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// An execution in a hedge instrument.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HedgeFill {
+    /// Hedge instrument symbol.
+    pub symbol: String,
+    /// Fill side.
+    pub side: Side,
+    /// Quantity in instrument units.
+    pub quantity: u64,
+    /// Fill price.
+    pub price: Decimal,
+    /// Fees paid on the fill.
+    pub fee: Decimal,
+    /// Delta of one instrument unit.
+    pub delta_per_unit: Decimal,
+    /// Fill timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+impl HedgeFill {
+    /// Creates a fee-free fill of a hedge order.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The order filled
+    /// * `quantity` - Quantity filled, at most the order's quantity for a partial fill
+    /// * `price` - Fill price
+    /// * `timestamp_ms` - Fill timestamp in milliseconds
+    #[must_use]
+    pub fn new(order: &HedgeOrder, quantity: u64, price: Decimal, timestamp_ms: u64) -> Self {
+        Self {
+            symbol: order.symbol.clone(),
+            side: order.side,
+            quantity,
+            price,
+            fee: Decimal::ZERO,
+            delta_per_unit: order.delta_per_unit,
+            timestamp_ms,
+        }
+    }
+
+    /// Sets the fees paid on the fill.
+    #[must_use]
+    pub const fn with_fee(mut self, fee: Decimal) -> Self {
+        self.fee = fee;
+        self
+    }
+
+    /// Returns the signed quantity, positive when bought.
+    #[must_use]
+    pub fn signed_quantity(&self) -> Decimal {
+        match self.side {
+            Side::Buy => Decimal::from(self.quantity),
+            Side::Sell => -Decimal::from(self.quantity),
+        }
+    }
+}
+
+/// A position in one hedge instrument.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HedgePosition {
+    /// Hedge instrument symbol.
+    pub symbol: String,
+    /// Signed quantity in instrument units, positive when long.
+    pub quantity: Decimal,
+    /// Average entry price of the open quantity; zero when flat.
+    pub average_price: Decimal,
+    /// Delta of one instrument unit, from the latest fill.
+    pub delta_per_unit: Decimal,
+    /// P&L realized by reducing or flipping the position.
+    pub realized_pnl: Decimal,
+    /// Funding and financing paid, negative when received.
+    pub funding: Decimal,
+    /// Fees paid.
+    pub fees: Decimal,
+    /// Timestamp of the latest fill in milliseconds.
+    pub last_fill_ms: u64,
+}
+
+impl HedgePosition {
+    /// Returns the delta of the position.
+    #[must_use]
+    pub fn delta(&self) -> Decimal {
+        self.quantity * self.delta_per_unit
+    }
+
+    /// Returns the P&L of the open quantity at a mark price.
+    #[must_use]
+    pub fn unrealized_pnl(&self, mark: Decimal) -> Decimal {
+        self.quantity * (mark - self.average_price)
+    }
+
+    /// Returns the total P&L of the position at a mark price, net of
+    /// funding and fees.
+    #[must_use]
+    pub fn total_pnl(&self, mark: Decimal) -> Decimal {
+        self.realized_pnl + self.unrealized_pnl(mark) - self.funding - self.fees
+    }
+
+    /// Applies a fill to the position.
+    fn apply(&mut self, fill: &HedgeFill) {
+        let traded = fill.signed_quantity();
+        let same_direction = self.quantity.is_zero()
+            || self.quantity.is_sign_positive() == traded.is_sign_positive();
+        if same_direction {
+            let open = self.quantity.abs();
+            let added = traded.abs();
+            self.average_price = (open * self.average_price + added * fill.price) / (open + added);
+        } else {
+            let closed = traded.abs().min(self.quantity.abs());
+            let direction = if self.quantity.is_sign_positive() {
+                Decimal::ONE
+            } else {
+                Decimal::NEGATIVE_ONE
+            };
+            self.realized_pnl += closed * (fill.price - self.average_price) * direction;
+            if traded.abs() > self.quantity.abs() {
+                self.average_price = fill.price;
+            }
+        }
+        self.quantity += traded;
+        if self.quantity.is_zero() {
+            self.average_price = Decimal::ZERO;
+        }
+        self.delta_per_unit = fill.delta_per_unit;
+        self.fees += fill.fee;
+        self.last_fill_ms = fill.timestamp_ms;
+    }
+}
+
+/// Positions in hedge instruments.
+#[derive(Debug, Default)]
+pub struct HedgeBook {
+    /// Positions by symbol.
+    positions: Mutex<BTreeMap<String, HedgePosition>>,
+}
+
+impl HedgeBook {
+    /// Creates an empty hedge book.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a fill, opening the position if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the quantity is zero, the price
+    /// is not positive or the fee is negative.
+    pub fn record_fill(&self, fill: &HedgeFill) -> Result<()> {
+        if fill.quantity == 0 {
+            return Err(Error::validation("hedge fill quantity must be positive"));
+        }
+        if fill.price <= Decimal::ZERO {
+            return Err(Error::validation("hedge fill price must be positive"));
+        }
+        if fill.fee < Decimal::ZERO {
+            return Err(Error::validation("hedge fill fee must not be negative"));
+        }
+        self.lock()
+            .entry(fill.symbol.clone())
+            .or_insert_with(|| HedgePosition {
+                symbol: fill.symbol.clone(),
+                quantity: Decimal::ZERO,
+                average_price: Decimal::ZERO,
+                delta_per_unit: fill.delta_per_unit,
+                realized_pnl: Decimal::ZERO,
+                funding: Decimal::ZERO,
+                fees: Decimal::ZERO,
+                last_fill_ms: fill.timestamp_ms,
+            })
+            .apply(fill);
+        Ok(())
+    }
+
+    /// Charges one funding or financing period to a position and returns
+    /// the payment.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - Hedge instrument symbol
+    /// * `mark` - Mark price the notional is taken at
+    /// * `rate` - Rate for the period: a perpetual's funding rate, or a
+    ///   financing rate times the period's year fraction
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataError` if there is no position in the symbol.
+    pub fn apply_funding(&self, symbol: &str, mark: Decimal, rate: Decimal) -> Result<Decimal> {
+        let mut positions = self.lock();
+        let position = positions
+            .get_mut(symbol)
+            .ok_or_else(|| Error::no_data(format!("no hedge position in {symbol}")))?;
+        let payment = position.quantity * mark * rate;
+        position.funding += payment;
+        Ok(payment)
+    }
+
+    /// Returns the position in a symbol.
+    #[must_use]
+    pub fn position(&self, symbol: &str) -> Option<HedgePosition> {
+        self.lock().get(symbol).cloned()
+    }
+
+    /// Returns every position sorted by symbol, flat ones included.
+    #[must_use]
+    pub fn positions(&self) -> Vec<HedgePosition> {
+        self.lock().values().cloned().collect()
+    }
+
+    /// Returns the delta of all hedge positions.
+    #[must_use]
+    pub fn hedge_delta(&self) -> Decimal {
+        self.lock().values().map(HedgePosition::delta).sum()
+    }
+
+    /// Returns the delta left after the hedges.
+    ///
+    /// # Arguments
+    ///
+    /// * `option_delta` - Delta of the option positions
+    #[must_use]
+    pub fn residual_delta(&self, option_delta: Decimal) -> Decimal {
+        option_delta + self.hedge_delta()
+    }
+
+    /// Returns the total P&L of the hedge positions, each marked by
+    /// `mark`; positions without a mark count their realized P&L, funding
+    /// and fees only.
+    #[must_use]
+    pub fn total_pnl(&self, mark: impl Fn(&str) -> Option<Decimal>) -> Decimal {
+        self.lock()
+            .values()
+            .map(|position| match mark(&position.symbol) {
+                Some(price) => position.total_pnl(price),
+                None => position.realized_pnl - position.funding - position.fees,
+            })
+            .sum()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, HedgePosition>> {
+        self.positions
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn fill(side: Side, quantity: u64, price: Decimal) -> HedgeFill {
+        HedgeFill {
+            symbol: "BTC-PERP".to_string(),
+            side,
+            quantity,
+            price,
+            fee: Decimal::ZERO,
+            delta_per_unit: Decimal::ONE,
+            timestamp_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_average_price_and_realized_pnl() {
+        let book = HedgeBook::new();
+        book.record_fill(&fill(Side::Sell, 2, dec!(100))).unwrap();
+        book.record_fill(&fill(Side::Sell, 2, dec!(110))).unwrap();
+        let position = book.position("BTC-PERP").unwrap();
+        assert_eq!(position.quantity, dec!(-4));
+        assert_eq!(position.average_price, dec!(105));
+
+        // Buying 6 closes the short at a profit of 5 per unit and opens a
+        // long of 2 at the fill price.
+        book.record_fill(&fill(Side::Buy, 6, dec!(100)).with_fee(dec!(1)))
+            .unwrap();
+        let position = book.position("BTC-PERP").unwrap();
+        assert_eq!(position.quantity, dec!(2));
+        assert_eq!(position.average_price, dec!(100));
+        assert_eq!(position.realized_pnl, dec!(20));
+        assert_eq!(position.total_pnl(dec!(103)), dec!(25));
+
+        assert!(book.record_fill(&fill(Side::Buy, 0, dec!(100))).is_err());
+    }
+
+    #[test]
+    fn test_funding_and_residual_delta() {
+        let book = HedgeBook::new();
+        book.record_fill(&fill(Side::Sell, 10, dec!(100))).unwrap();
+        // Shorts receive a positive funding rate.
+        assert_eq!(
+            book.apply_funding("BTC-PERP", dec!(100), dec!(0.001))
+                .unwrap(),
+            dec!(-1)
+        );
+        assert_eq!(book.total_pnl(|_| Some(dec!(99))), dec!(11));
+        assert_eq!(book.residual_delta(dec!(12)), dec!(2));
+        assert!(
+            book.apply_funding("ETH-PERP", dec!(1), dec!(0.001))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! Delta hedger module.
+//!
+//! This module provides the [`DeltaHedger`], which emits a [`HedgeOrder`]
+//! when portfolio delta leaves a band around its target.
+
+use super::book::{HedgeBook, HedgeFill};
+use super::order::HedgeOrder;
+use crate::error::{Error, Result};
+use crate::risk::{HedgerStatus, HedgerStatusSource};
+use orderbook_rs::Side;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
+/// Sentinel for "never hedged".
+const NEVER: u64 = u64::MAX;
+
+/// Delta hedging parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HedgeParams {
+    /// Hedge instrument symbol.
+    pub symbol: String,
+    /// Delta of one instrument unit.
+    pub delta_per_unit: Decimal,
+    /// Portfolio delta to hedge back to.
+    pub target_delta: Decimal,
+    /// Distance from the target tolerated before hedging.
+    pub threshold: Decimal,
+    /// Smallest order worth sending, in instrument units.
+    pub min_quantity: u64,
+    /// Largest single order, in instrument units.
+    pub max_quantity: u64,
+}
+
+impl HedgeParams {
+    /// Creates hedge parameters for a delta-one instrument.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - Hedge instrument symbol
+    /// * `threshold` - Distance from zero delta tolerated before hedging
+    #[must_use]
+    pub fn new(symbol: impl Into<String>, threshold: Decimal) -> Self {
+        Self {
+            symbol: symbol.into(),
+            delta_per_unit: Decimal::ONE,
+            target_delta: Decimal::ZERO,
+            threshold,
+            min_quantity: 1,
+            max_quantity: u64::MAX,
+        }
+    }
+
+    /// Validates the parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the delta per unit is not
+    /// positive, the threshold is negative, or the quantity bounds are
+    /// inverted.
+    pub fn validate(&self) -> Result<()> {
+        if self.delta_per_unit <= Decimal::ZERO {
+            return Err(Error::configuration("delta per unit must be positive"));
+        }
+        if self.threshold < Decimal::ZERO {
+            return Err(Error::configuration("hedge threshold must be non-negative"));
+        }
+        if self.min_quantity > self.max_quantity {
+            return Err(Error::configuration(
+                "minimum hedge quantity exceeds maximum",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Band-based delta hedger.
+pub struct DeltaHedger {
+    /// Hedging parameters.
+    params: HedgeParams,
+    /// Whether the hedger emits orders.
+    enabled: AtomicBool,
+    /// Hedge orders emitted but not yet completed.
+    pending_orders: AtomicUsize,
+    /// Timestamp of the last emitted order.
+    last_hedge_ms: AtomicU64,
+    /// Delta left after the last evaluation and its pending order.
+    residual_delta: RwLock<Decimal>,
+}
+
+impl DeltaHedger {
+    /// Creates an enabled delta hedger.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the parameters are invalid.
+    pub fn new(params: HedgeParams) -> Result<Self> {
+        params.validate()?;
+        Ok(Self {
+            params,
+            enabled: AtomicBool::new(true),
+            pending_orders: AtomicUsize::new(0),
+            last_hedge_ms: AtomicU64::new(NEVER),
+            residual_delta: RwLock::new(Decimal::ZERO),
+        })
+    }
+
+    /// Returns the hedging parameters.
+    #[must_use]
+    pub const fn params(&self) -> &HedgeParams {
+        &self.params
+    }
+
+    /// Enables or disables order emission.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Release);
+    }
+
+    /// Returns true if the hedger emits orders.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+
+    /// Returns the order that brings delta back to target, if the delta is
+    /// outside the band.
+    ///
+    /// Does not change hedger state.
+    ///
+    /// # Arguments
+    ///
+    /// * `portfolio_delta` - Current portfolio delta
+    /// * `timestamp_ms` - Order timestamp in milliseconds
+    #[must_use]
+    pub fn compute(&self, portfolio_delta: Decimal, timestamp_ms: u64) -> Option<HedgeOrder> {
+        let excess = portfolio_delta - self.params.target_delta;
+        if excess.abs() <= self.params.threshold {
+            return None;
+        }
+        let quantity = (excess.abs() / self.params.delta_per_unit)
+            .round()
+            .to_u64()
+            .unwrap_or(u64::MAX)
+            .min(self.params.max_quantity);
+        if quantity == 0 || quantity < self.params.min_quantity {
+            return None;
+        }
+        Some(HedgeOrder {
+            symbol: self.params.symbol.clone(),
+            side: if excess > Decimal::ZERO {
+                Side::Sell
+            } else {
+                Side::Buy
+            },
+            quantity,
+            delta_per_unit: self.params.delta_per_unit,
+            timestamp_ms,
+        })
+    }
+
+    /// Evaluates the portfolio delta and emits a hedge order if needed.
+    ///
+    /// Emitted orders count as pending until [`Self::hedge_completed`].
+    ///
+    /// # Arguments
+    ///
+    /// * `portfolio_delta` - Current portfolio delta
+    /// * `timestamp_ms` - Order timestamp in milliseconds
+    pub fn hedge(&self, portfolio_delta: Decimal, timestamp_ms: u64) -> Option<HedgeOrder> {
+        let order = if self.is_enabled() {
+            self.compute(portfolio_delta, timestamp_ms)
+        } else {
+            None
+        };
+        let residual = portfolio_delta
+            + order
+                .as_ref()
+                .map_or(Decimal::ZERO, HedgeOrder::delta_change);
+        if let Ok(mut r) = self.residual_delta.write() {
+            *r = residual;
+        }
+        if order.is_some() {
+            self.pending_orders.fetch_add(1, Ordering::AcqRel);
+            self.last_hedge_ms.store(timestamp_ms, Ordering::Release);
+        }
+        order
+    }
+
+    /// Evaluates the delta left after the hedge book's positions and emits
+    /// a hedge order if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `option_delta` - Delta of the option positions
+    /// * `book` - Hedge positions already held
+    /// * `timestamp_ms` - Order timestamp in milliseconds
+    pub fn rebalance(
+        &self,
+        option_delta: Decimal,
+        book: &HedgeBook,
+        timestamp_ms: u64,
+    ) -> Option<HedgeOrder> {
+        self.hedge(book.residual_delta(option_delta), timestamp_ms)
+    }
+
+    /// Records a hedge fill in the book and sets the residual delta to what
+    /// the book leaves unhedged, returning it.
+    ///
+    /// The pending order stays pending; call [`Self::hedge_completed`] once
+    /// it is fully filled or cancelled.
+    ///
+    /// # Arguments
+    ///
+    /// * `fill` - The hedge fill
+    /// * `book` - Hedge book the fill is recorded in
+    /// * `option_delta` - Delta of the option positions
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`HedgeBook::record_fill`].
+    pub fn on_fill(
+        &self,
+        fill: &HedgeFill,
+        book: &HedgeBook,
+        option_delta: Decimal,
+    ) -> Result<Decimal> {
+        book.record_fill(fill)?;
+        let residual = book.residual_delta(option_delta);
+        if let Ok(mut r) = self.residual_delta.write() {
+            *r = residual;
+        }
+        Ok(residual)
+    }
+
+    /// Marks one pending hedge order as filled or cancelled.
+    pub fn hedge_completed(&self) {
+        let _ = self
+            .pending_orders
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1));
+    }
+
+    /// Returns the current hedger status.
+    #[must_use]
+    pub fn status(&self) -> HedgerStatus {
+        HedgerStatus {
+            enabled: self.is_enabled(),
+            residual_delta: self.residual_delta.read().map_or(Decimal::ZERO, |r| *r),
+            pending_orders: self.pending_orders.load(Ordering::Acquire),
+            last_hedge_ms: match self.last_hedge_ms.load(Ordering::Acquire) {
+                NEVER => None,
+                ms => Some(ms),
+            },
+        }
+    }
+}
+
+impl HedgerStatusSource for DeltaHedger {
+    fn hedger_status(&self) -> HedgerStatus {
+        self.status()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn hedger() -> DeltaHedger {
+        DeltaHedger::new(HedgeParams::new("BTC-PERP", dec!(5))).unwrap()
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let mut params = HedgeParams::new("BTC-PERP", dec!(5));
+        params.delta_per_unit = Decimal::ZERO;
+        assert!(DeltaHedger::new(params).is_err());
+    }
+
+    #[test]
+    fn test_inside_band_no_order() {
+        assert!(hedger().hedge(dec!(4), 1).is_none());
+    }
+
+    #[test]
+    fn test_long_delta_sells() {
+        let hedger = hedger();
+        let order = hedger.hedge(dec!(12.4), 7).unwrap();
+
+        assert_eq!(order.side, Side::Sell);
+        assert_eq!(order.quantity, 12);
+
+        let status = hedger.status();
+        assert_eq!(status.pending_orders, 1);
+        assert_eq!(status.last_hedge_ms, Some(7));
+        assert_eq!(status.residual_delta, dec!(0.4));
+
+        hedger.hedge_completed();
+        hedger.hedge_completed();
+        assert_eq!(hedger.status().pending_orders, 0);
+    }
+
+    #[test]
+    fn test_fills_feed_residual_from_hedge_book() {
+        let hedger = hedger();
+        let book = HedgeBook::new();
+        let order = hedger.rebalance(dec!(12.4), &book, 1).unwrap();
+
+        let partial = HedgeFill::new(&order, 8, dec!(100), 2);
+        assert_eq!(
+            hedger.on_fill(&partial, &book, dec!(12.4)).unwrap(),
+            dec!(4.4)
+        );
+        assert_eq!(hedger.status().residual_delta, dec!(4.4));
+        // Inside the band after the partial fill: no further order.
+        assert!(hedger.rebalance(dec!(12.4), &book, 3).is_none());
+    }
+
+    #[test]
+    fn test_max_quantity_caps_order() {
+        let mut params = HedgeParams::new("BTC-PERP", dec!(1));
+        params.max_quantity = 3;
+        let order = DeltaHedger::new(params)
+            .unwrap()
+            .compute(dec!(-10), 0)
+            .unwrap();
+        assert_eq!(order.side, Side::Buy);
+        assert_eq!(order.quantity, 3);
+    }
+
+    #[test]
+    fn test_disabled_hedger() {
+        let hedger = hedger();
+        hedger.set_enabled(false);
+        assert!(hedger.hedge(dec!(50), 1).is_none());
+        assert!(!hedger.status().enabled);
+    }
+}
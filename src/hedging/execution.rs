@@ -0,0 +1,396 @@
+//! Hedge execution module.
+//!
+//! This module provides the [`ExecutionPlanner`], which slices a
+//! [`HedgeOrder`] into [`ChildOrder`]s according to an
+//! [`ExecutionStrategy`] so hedges are not always sent as one marketable
+//! order:
+//!
+//! - `Aggressive`: one immediate-or-cancel order crossing the spread
+//! - `Passive`: one resting order joining our side of the book
+//! - `Twap`: equal slices crossing the spread at fixed intervals
+//! - `Iceberg`: resting slices of the display size, each sent once the
+//!   previous one has filled
+//!
+//! Children carry how they are priced rather than a price, so a slice
+//! scheduled for later is priced from the book when it is sent. A planner
+//! with a participant tags every child with it, so the requests it builds
+//! go through the book's self-trade prevention and never cross our own
+//! quotes.
+
+use super::order::HedgeOrder;
+use crate::adapters::OrderRequest;
+use crate::error::{Error, Result};
+use orderbook_rs::{Side, TimeInForce};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// How a hedge order is worked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecutionStrategy {
+    /// Cross the spread at once, paying up to `slippage_ticks` beyond the
+    /// touch.
+    Aggressive {
+        /// Ticks beyond the far touch the order may trade at.
+        slippage_ticks: u128,
+    },
+    /// Rest the whole order at our side's best price.
+    Passive,
+    /// Cross the spread in equal slices at fixed intervals.
+    Twap {
+        /// Number of slices.
+        slices: u32,
+        /// Time between slices in milliseconds.
+        interval_ms: u64,
+    },
+    /// Rest slices of at most `display_quantity` one after another.
+    Iceberg {
+        /// Largest quantity shown in the book at a time.
+        display_quantity: u64,
+    },
+}
+
+impl ExecutionStrategy {
+    /// Validates the strategy.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if a TWAP has no slices or no
+    /// interval, or an iceberg has no display quantity.
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            Self::Twap {
+                slices,
+                interval_ms,
+            } if slices == 0 || interval_ms == 0 => Err(Error::configuration(
+                "TWAP slices and interval must be positive",
+            )),
+            Self::Iceberg {
+                display_quantity: 0,
+            } => Err(Error::configuration(
+                "iceberg display quantity must be positive",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// How a child order is priced when it is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChildPricing {
+    /// Join our side's best price: the bid for buys, the ask for sells.
+    Join,
+    /// Take the far touch, up to `slippage_ticks` through it.
+    Cross {
+        /// Ticks beyond the far touch.
+        slippage_ticks: u128,
+    },
+}
+
+impl ChildPricing {
+    /// Returns the limit price for a side given the current touch.
+    #[must_use]
+    pub const fn price(&self, side: Side, best_bid: u128, best_ask: u128) -> u128 {
+        match (*self, side) {
+            (Self::Join, Side::Buy) => best_bid,
+            (Self::Join, Side::Sell) => best_ask,
+            (Self::Cross { slippage_ticks }, Side::Buy) => best_ask.saturating_add(slippage_ticks),
+            (Self::Cross { slippage_ticks }, Side::Sell) => best_bid.saturating_sub(slippage_ticks),
+        }
+    }
+}
+
+/// When a child order is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChildTrigger {
+    /// At or after a timestamp in milliseconds.
+    At(u64),
+    /// Once every earlier child has completed.
+    AfterPrevious,
+}
+
+/// One slice of a hedge order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChildOrder {
+    /// Position of the child in its plan.
+    pub sequence: usize,
+    /// Hedge instrument symbol.
+    pub symbol: String,
+    /// Order side.
+    pub side: Side,
+    /// Quantity in instrument units.
+    pub quantity: u64,
+    /// Delta of one instrument unit.
+    pub delta_per_unit: Decimal,
+    /// How the child is priced.
+    pub pricing: ChildPricing,
+    /// Time in force of the child.
+    pub time_in_force: TimeInForce,
+    /// When the child is sent.
+    pub trigger: ChildTrigger,
+    /// Participant tag checked by self-trade prevention.
+    #[serde(default)]
+    pub participant: Option<String>,
+}
+
+impl ChildOrder {
+    /// Returns the limit price given the current touch.
+    #[must_use]
+    pub const fn price(&self, best_bid: u128, best_ask: u128) -> u128 {
+        self.pricing.price(self.side, best_bid, best_ask)
+    }
+
+    /// Returns the order request for the child priced at the current touch,
+    /// tagged with the child's participant.
+    #[must_use]
+    pub fn to_order_request(&self, best_bid: u128, best_ask: u128) -> OrderRequest {
+        let request = OrderRequest::new(
+            &self.symbol,
+            self.side,
+            self.price(best_bid, best_ask),
+            self.quantity,
+        )
+        .with_time_in_force(self.time_in_force);
+        match &self.participant {
+            Some(participant) => request.with_participant(participant.as_str()),
+            None => request,
+        }
+    }
+
+    /// Returns the portfolio delta change if the child fills completely.
+    #[must_use]
+    pub fn delta_change(&self) -> Decimal {
+        let magnitude = Decimal::from(self.quantity) * self.delta_per_unit;
+        match self.side {
+            Side::Buy => magnitude,
+            Side::Sell => -magnitude,
+        }
+    }
+}
+
+/// Child orders of one hedge order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionPlan {
+    /// Strategy the plan was built with.
+    pub strategy: ExecutionStrategy,
+    /// Child orders in sending order.
+    pub children: Vec<ChildOrder>,
+}
+
+impl ExecutionPlan {
+    /// Returns the total quantity of the children.
+    #[must_use]
+    pub fn total_quantity(&self) -> u64 {
+        self.children.iter().map(|c| c.quantity).sum()
+    }
+
+    /// Returns the children to send now.
+    ///
+    /// # Arguments
+    ///
+    /// * `now_ms` - Current time in milliseconds
+    /// * `completed` - Number of children already completed, in order
+    pub fn ready(&self, now_ms: u64, completed: usize) -> impl Iterator<Item = &ChildOrder> {
+        self.children
+            .iter()
+            .skip(completed)
+            .filter(move |child| match child.trigger {
+                ChildTrigger::At(at) => at <= now_ms,
+                ChildTrigger::AfterPrevious => child.sequence == completed,
+            })
+    }
+}
+
+/// Slices hedge orders into child orders.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionPlanner {
+    /// Strategy applied to every order.
+    strategy: ExecutionStrategy,
+    /// Participant tag of every child.
+    participant: Option<String>,
+}
+
+impl ExecutionPlanner {
+    /// Creates a planner.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the strategy is invalid.
+    pub fn new(strategy: ExecutionStrategy) -> Result<Self> {
+        strategy.validate()?;
+        Ok(Self {
+            strategy,
+            participant: None,
+        })
+    }
+
+    /// Tags every child with a participant, e.g. the tag our quoter uses,
+    /// so hedges cannot trade against our own quotes.
+    #[must_use]
+    pub fn with_participant(mut self, participant: impl Into<String>) -> Self {
+        self.participant = Some(participant.into());
+        self
+    }
+
+    /// Returns the participant tag of the children, if any.
+    #[must_use]
+    pub fn participant(&self) -> Option<&str> {
+        self.participant.as_deref()
+    }
+
+    /// Returns the strategy.
+    #[must_use]
+    pub const fn strategy(&self) -> ExecutionStrategy {
+        self.strategy
+    }
+
+    /// Slices a hedge order into child orders.
+    ///
+    /// Quantities are split as evenly as possible, earlier slices taking the
+    /// remainder; slices that would be empty are dropped. The schedule
+    /// starts at the order's timestamp.
+    #[must_use]
+    pub fn plan(&self, order: &HedgeOrder) -> ExecutionPlan {
+        let start = order.timestamp_ms;
+        let slices: Vec<(u64, ChildPricing, TimeInForce, ChildTrigger)> = match self.strategy {
+            ExecutionStrategy::Aggressive { slippage_ticks } => vec![(
+                order.quantity,
+                ChildPricing::Cross { slippage_ticks },
+                TimeInForce::Ioc,
+                ChildTrigger::At(start),
+            )],
+            ExecutionStrategy::Passive => vec![(
+                order.quantity,
+                ChildPricing::Join,
+                TimeInForce::Gtc,
+                ChildTrigger::At(start),
+            )],
+            ExecutionStrategy::Twap {
+                slices,
+                interval_ms,
+            } => split(order.quantity, u64::from(slices))
+                .enumerate()
+                .map(|(i, quantity)| {
+                    let at = start.saturating_add(interval_ms.saturating_mul(i as u64));
+                    (
+                        quantity,
+                        ChildPricing::Cross { slippage_ticks: 0 },
+                        TimeInForce::Ioc,
+                        ChildTrigger::At(at),
+                    )
+                })
+                .collect(),
+            ExecutionStrategy::Iceberg { display_quantity } => {
+                let count = order.quantity.div_ceil(display_quantity);
+                split(order.quantity, count)
+                    .enumerate()
+                    .map(|(i, quantity)| {
+                        let trigger = if i == 0 {
+                            ChildTrigger::At(start)
+                        } else {
+                            ChildTrigger::AfterPrevious
+                        };
+                        (quantity, ChildPricing::Join, TimeInForce::Gtc, trigger)
+                    })
+                    .collect()
+            }
+        };
+        let children = slices
+            .into_iter()
+            .filter(|(quantity, ..)| *quantity > 0)
+            .enumerate()
+            .map(
+                |(sequence, (quantity, pricing, time_in_force, trigger))| ChildOrder {
+                    sequence,
+                    symbol: order.symbol.clone(),
+                    side: order.side,
+                    quantity,
+                    delta_per_unit: order.delta_per_unit,
+                    pricing,
+                    time_in_force,
+                    trigger,
+                    participant: self.participant.clone(),
+                },
+            )
+            .collect();
+        ExecutionPlan {
+            strategy: self.strategy,
+            children,
+        }
+    }
+}
+
+/// Splits a quantity into `parts` near-equal parts, largest first.
+fn split(quantity: u64, parts: u64) -> impl Iterator<Item = u64> {
+    let parts = parts.max(1);
+    let base = quantity / parts;
+    let remainder = quantity % parts;
+    (0..parts).map(move |i| base + u64::from(i < remainder))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn order(side: Side, quantity: u64) -> HedgeOrder {
+        HedgeOrder {
+            symbol: "BTC-PERP".to_string(),
+            side,
+            quantity,
+            delta_per_unit: Decimal::ONE,
+            timestamp_ms: 1_000,
+        }
+    }
+
+    #[test]
+    fn test_aggressive_and_passive_pricing() {
+        let aggressive = ExecutionPlanner::new(ExecutionStrategy::Aggressive { slippage_ticks: 2 })
+            .unwrap()
+            .plan(&order(Side::Buy, 7));
+        assert_eq!(aggressive.children.len(), 1);
+        let request = aggressive.children[0].to_order_request(100, 101);
+        assert_eq!(request.price, 103);
+        assert_eq!(request.time_in_force, TimeInForce::Ioc);
+
+        let passive = ExecutionPlanner::new(ExecutionStrategy::Passive)
+            .unwrap()
+            .plan(&order(Side::Sell, 7));
+        assert_eq!(passive.children[0].price(100, 101), 101);
+        assert_eq!(passive.children[0].delta_change(), dec!(-7));
+    }
+
+    #[test]
+    fn test_twap_and_iceberg_schedules() {
+        let twap = ExecutionPlanner::new(ExecutionStrategy::Twap {
+            slices: 3,
+            interval_ms: 500,
+        })
+        .unwrap()
+        .plan(&order(Side::Buy, 10));
+        let quantities: Vec<u64> = twap.children.iter().map(|c| c.quantity).collect();
+        assert_eq!(quantities, vec![4, 3, 3]);
+        assert_eq!(twap.total_quantity(), 10);
+        assert_eq!(twap.ready(1_000, 0).count(), 1);
+        assert_eq!(twap.ready(2_000, 1).count(), 2);
+
+        let iceberg = ExecutionPlanner::new(ExecutionStrategy::Iceberg {
+            display_quantity: 4,
+        })
+        .unwrap()
+        .plan(&order(Side::Sell, 10));
+        assert_eq!(iceberg.children.len(), 3);
+        assert_eq!(iceberg.total_quantity(), 10);
+        // Only the first slice is shown until it fills.
+        assert_eq!(iceberg.ready(u64::MAX, 0).count(), 1);
+        let next: Vec<usize> = iceberg.ready(1_000, 1).map(|c| c.sequence).collect();
+        assert_eq!(next, vec![1]);
+
+        assert!(
+            ExecutionPlanner::new(ExecutionStrategy::Twap {
+                slices: 0,
+                interval_ms: 1
+            })
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,289 @@
+//! Greek forecast module.
+//!
+//! This module provides the [`GreekForecaster`], which projects portfolio
+//! delta, gamma and vega to later times today (e.g. +2h, end of day,
+//! expiry) under pure time decay at the current spot and volatility.
+//!
+//! Delta drifts with time alone (charm): in-the-money options head to a
+//! delta of one, out-of-the-money options to zero. A book that sits inside
+//! the hedge band now can be pushed through it by the afternoon; the
+//! forecast flags the first horizon at which that happens so the hedge can
+//! be placed ahead of time instead of after the breach.
+
+use super::delta::HedgeParams;
+use crate::error::{Error, Result};
+use crate::pricing::{Greeks, PricingParams};
+use optionstratlib::OptionStyle;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Hours per day used to convert horizons to days.
+const HOURS_PER_DAY: Decimal = dec!(24);
+
+/// An option position projected forward.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForecastPosition {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Current pricing inputs.
+    pub pricing: PricingParams,
+    /// Signed quantity in contracts (negative = short).
+    pub quantity: Decimal,
+}
+
+/// A point in time the portfolio is projected to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForecastHorizon {
+    /// Label shown in reports, e.g. `+2h` or `EOD`.
+    pub label: String,
+    /// Hours from now.
+    pub hours_ahead: Decimal,
+}
+
+impl ForecastHorizon {
+    /// Creates a horizon.
+    #[must_use]
+    pub fn new(label: impl Into<String>, hours_ahead: Decimal) -> Self {
+        Self {
+            label: label.into(),
+            hours_ahead,
+        }
+    }
+}
+
+/// Portfolio Greeks at one horizon.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GreekProjection {
+    /// Horizon label.
+    pub label: String,
+    /// Hours from now.
+    pub hours_ahead: Decimal,
+    /// Projected portfolio Greeks, including the delta of linear hedges.
+    pub greeks: Greeks,
+    /// Projected delta minus current delta.
+    pub delta_drift: Decimal,
+    /// Contracts expired by this horizon.
+    pub expired: usize,
+    /// True if the projected delta is outside the hedge band.
+    pub outside_band: bool,
+}
+
+/// Projected Greeks over a set of horizons.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GreekForecast {
+    /// Current portfolio Greeks, including the delta of linear hedges.
+    pub now: Greeks,
+    /// True if the current delta is outside the hedge band.
+    pub outside_band_now: bool,
+    /// Projections by increasing horizon.
+    pub projections: Vec<GreekProjection>,
+}
+
+impl GreekForecast {
+    /// Returns the first horizon at which delta drifts out of the band
+    /// while it is inside the band now.
+    #[must_use]
+    pub fn first_breach(&self) -> Option<&GreekProjection> {
+        if self.outside_band_now {
+            return None;
+        }
+        self.projections.iter().find(|p| p.outside_band)
+    }
+}
+
+/// Forward projection of portfolio Greeks under time decay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GreekForecaster {
+    /// Hedge band the projections are checked against.
+    params: HedgeParams,
+}
+
+impl GreekForecaster {
+    /// Creates a forecaster checking against a hedger's band.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the hedge parameters are
+    /// invalid.
+    pub fn new(params: HedgeParams) -> Result<Self> {
+        params.validate()?;
+        Ok(Self { params })
+    }
+
+    /// Returns the hedge parameters.
+    #[must_use]
+    pub const fn params(&self) -> &HedgeParams {
+        &self.params
+    }
+
+    /// Projects the portfolio to each horizon.
+    ///
+    /// Spot, volatility and rates stay at their current values; only the
+    /// time to expiry shrinks. A contract that expires before a horizon
+    /// contributes its exercise delta (one if in the money, else zero) and
+    /// no gamma or vega.
+    ///
+    /// # Arguments
+    ///
+    /// * `positions` - Option positions
+    /// * `linear_delta` - Delta of linear hedge positions, constant in time
+    /// * `horizons` - Horizons to project to, in any order
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if a horizon is negative, or
+    /// `Error::PricingError` if a position cannot be priced.
+    pub fn forecast(
+        &self,
+        positions: &[ForecastPosition],
+        linear_delta: Decimal,
+        horizons: &[ForecastHorizon],
+    ) -> Result<GreekForecast> {
+        if let Some(h) = horizons.iter().find(|h| h.hours_ahead < Decimal::ZERO) {
+            return Err(Error::validation(format!(
+                "forecast horizon {} is in the past",
+                h.label
+            )));
+        }
+        let (mut now, _) = project(positions, Decimal::ZERO)?;
+        now.delta += linear_delta;
+
+        let mut horizons: Vec<&ForecastHorizon> = horizons.iter().collect();
+        horizons.sort_by_key(|h| h.hours_ahead);
+        let mut projections = Vec::with_capacity(horizons.len());
+        for horizon in horizons {
+            let (mut greeks, expired) = project(positions, horizon.hours_ahead / HOURS_PER_DAY)?;
+            greeks.delta += linear_delta;
+            projections.push(GreekProjection {
+                label: horizon.label.clone(),
+                hours_ahead: horizon.hours_ahead,
+                greeks,
+                delta_drift: greeks.delta - now.delta,
+                expired,
+                outside_band: self.outside_band(greeks.delta),
+            });
+        }
+        Ok(GreekForecast {
+            now,
+            outside_band_now: self.outside_band(now.delta),
+            projections,
+        })
+    }
+
+    fn outside_band(&self, delta: Decimal) -> bool {
+        (delta - self.params.target_delta).abs() > self.params.threshold
+    }
+}
+
+/// Returns the portfolio Greeks after `elapsed_days` of decay and the
+/// number of contracts expired by then.
+fn project(positions: &[ForecastPosition], elapsed_days: Decimal) -> Result<(Greeks, usize)> {
+    let mut total = Greeks::zero();
+    let mut expired = 0;
+    for position in positions {
+        let days = position.pricing.days_to_expiry - elapsed_days;
+        let unit = if days <= Decimal::ZERO {
+            expired += 1;
+            Greeks {
+                delta: exercise_delta(&position.pricing),
+                ..Greeks::zero()
+            }
+        } else {
+            PricingParams {
+                days_to_expiry: days,
+                ..position.pricing
+            }
+            .greeks()?
+        };
+        total += unit.scaled(position.quantity);
+    }
+    Ok((total, expired))
+}
+
+/// Returns the delta of a contract at expiry.
+fn exercise_delta(pricing: &PricingParams) -> Decimal {
+    match pricing.style {
+        OptionStyle::Call if pricing.spot > pricing.strike => Decimal::ONE,
+        OptionStyle::Put if pricing.spot < pricing.strike => -Decimal::ONE,
+        _ => Decimal::ZERO,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(strike: Decimal, days: Decimal, quantity: Decimal) -> ForecastPosition {
+        ForecastPosition {
+            symbol: format!("C{strike}"),
+            pricing: PricingParams::new(dec!(100), strike, days, dec!(0.3), OptionStyle::Call),
+            quantity,
+        }
+    }
+
+    fn horizons() -> Vec<ForecastHorizon> {
+        vec![
+            ForecastHorizon::new("EOD", dec!(8)),
+            ForecastHorizon::new("+2h", dec!(2)),
+            ForecastHorizon::new("expiry", dec!(24)),
+        ]
+    }
+
+    #[test]
+    fn test_charm_breach_flagged_ahead() {
+        // Long 100 in-the-money calls expiring tomorrow, delta hedged now.
+        let positions = vec![call(dec!(99), Decimal::ONE, dec!(100))];
+        let forecaster = GreekForecaster::new(HedgeParams::new("FUT", dec!(5))).unwrap();
+        let current = forecaster
+            .forecast(&positions, Decimal::ZERO, &[])
+            .unwrap()
+            .now
+            .delta;
+        let forecast = forecaster
+            .forecast(&positions, -current.round(), &horizons())
+            .unwrap();
+
+        assert!(!forecast.outside_band_now);
+        let labels: Vec<&str> = forecast
+            .projections
+            .iter()
+            .map(|p| p.label.as_str())
+            .collect();
+        assert_eq!(labels, vec!["+2h", "EOD", "expiry"]);
+        // Delta climbs towards one per contract as time runs out.
+        assert!(
+            forecast
+                .projections
+                .windows(2)
+                .all(|w| w[1].delta_drift > w[0].delta_drift)
+        );
+        let expiry = &forecast.projections[2];
+        assert_eq!(expiry.expired, 1);
+        assert_eq!(expiry.greeks.gamma, Decimal::ZERO);
+        assert_eq!(forecast.first_breach().unwrap().label, "expiry");
+    }
+
+    #[test]
+    fn test_stable_book_stays_inside_band() {
+        let positions = vec![call(dec!(100), dec!(60), dec!(10))];
+        let forecaster = GreekForecaster::new(HedgeParams::new("FUT", dec!(5))).unwrap();
+        let forecast = forecaster
+            .forecast(&positions, dec!(-5), &horizons())
+            .unwrap();
+        assert!(forecast.first_breach().is_none());
+        assert!(
+            forecast
+                .projections
+                .iter()
+                .all(|p| p.delta_drift.abs() < dec!(0.1))
+        );
+
+        let past = [ForecastHorizon::new("yesterday", dec!(-24))];
+        assert!(
+            forecaster
+                .forecast(&positions, Decimal::ZERO, &past)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,450 @@
+//! Hedge internalization module.
+//!
+//! This module provides the [`Internalizer`], which checks whether resting
+//! liquidity in our own option books can absorb part of a hedge more
+//! cheaply than the external hedge instrument, within risk limits and
+//! compliance constraints.
+//!
+//! ## Cost model
+//!
+//! Crossing an option book costs the distance between the execution price
+//! and theo (`ask - theo` when buying, `theo - bid` when selling), which may
+//! be negative when the resting order is mispriced in our favour. Dividing
+//! by the absolute contract delta gives a cost per unit of delta that is
+//! compared with the external cost per unit of delta supplied by the
+//! caller (half-spread plus fees of the hedge instrument).
+
+use super::order::HedgeOrder;
+use crate::error::{Error, Result};
+use crate::orderbook::OptionOrderBook;
+use crate::pricing::Greeks;
+use crate::risk::RiskLimits;
+use orderbook_rs::Side;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Option book that may absorb part of a hedge.
+#[derive(Clone)]
+pub struct InternalCandidate {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Order book of the contract.
+    pub book: Arc<OptionOrderBook>,
+    /// Greeks of one long contract.
+    pub greeks: Greeks,
+    /// Theoretical value of one contract.
+    pub theo: Decimal,
+    /// Price of one book tick.
+    pub tick_size: Decimal,
+}
+
+/// Internalization settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InternalizationConfig {
+    /// Whether internal crossing is considered at all.
+    pub enabled: bool,
+    /// Largest quantity crossed in one candidate book.
+    pub max_contracts_per_candidate: u64,
+    /// Minimum saving per unit of delta required to prefer an internal cross.
+    pub min_savings_per_delta: Decimal,
+}
+
+impl Default for InternalizationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_contracts_per_candidate: u64::MAX,
+            min_savings_per_delta: Decimal::ZERO,
+        }
+    }
+}
+
+/// Compliance gate for internal crosses.
+pub trait CrossingCompliance: Send + Sync {
+    /// Returns true if crossing `quantity` contracts on `side` of `symbol`
+    /// is permitted.
+    fn allows(&self, symbol: &str, side: Side, quantity: u64) -> bool;
+}
+
+/// A planned cross against one of our option books.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InternalCross {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Our side of the cross.
+    pub side: Side,
+    /// Execution price in book ticks.
+    pub price: u128,
+    /// Quantity in contracts.
+    pub quantity: u64,
+    /// Portfolio delta change.
+    pub delta_change: Decimal,
+    /// Cost versus theo (negative is a gain).
+    pub cost: Decimal,
+}
+
+/// Routing decision for a hedge.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HedgeRoute {
+    /// Crosses against our own option books, cheapest first.
+    pub internal: Vec<InternalCross>,
+    /// Remaining order for the external hedge instrument, if any.
+    pub external: Option<HedgeOrder>,
+    /// Total cost of the internal crosses versus theo.
+    pub internal_cost: Decimal,
+    /// Estimated cost of hedging the internalized delta externally instead.
+    pub external_cost_avoided: Decimal,
+}
+
+impl HedgeRoute {
+    /// Returns the delta covered by internal crosses.
+    #[must_use]
+    pub fn internal_delta(&self) -> Decimal {
+        self.internal.iter().map(|c| c.delta_change).sum()
+    }
+
+    /// Returns the estimated saving from internalizing.
+    #[must_use]
+    pub fn savings(&self) -> Decimal {
+        self.external_cost_avoided - self.internal_cost
+    }
+}
+
+/// Hedge internalization step.
+pub struct Internalizer {
+    /// Internalization settings.
+    config: InternalizationConfig,
+    /// Optional compliance gate.
+    compliance: Option<Arc<dyn CrossingCompliance>>,
+}
+
+/// A priced crossing opportunity.
+struct Opportunity<'a> {
+    candidate: &'a InternalCandidate,
+    side: Side,
+    price: u128,
+    available: u64,
+    cost_per_contract: Decimal,
+    cost_per_delta: Decimal,
+}
+
+impl Internalizer {
+    /// Creates an internalizer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the minimum saving is negative.
+    pub fn new(config: InternalizationConfig) -> Result<Self> {
+        if config.min_savings_per_delta < Decimal::ZERO {
+            return Err(Error::configuration(
+                "minimum internalization saving must be non-negative",
+            ));
+        }
+        Ok(Self {
+            config,
+            compliance: None,
+        })
+    }
+
+    /// Attaches a compliance gate consulted before every cross.
+    #[must_use]
+    pub fn with_compliance(mut self, compliance: Arc<dyn CrossingCompliance>) -> Self {
+        self.compliance = Some(compliance);
+        self
+    }
+
+    /// Returns the internalization settings.
+    #[must_use]
+    pub const fn config(&self) -> &InternalizationConfig {
+        &self.config
+    }
+
+    /// Splits a hedge between internal crosses and an external order.
+    ///
+    /// Candidates are used cheapest first while they are cheaper than the
+    /// external instrument, do not overshoot the hedge, keep projected
+    /// gamma, vega and theta within limits (or no worse than now), and pass
+    /// compliance. Whatever delta is left goes to the external order.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - Hedge the delta hedger wants to send externally
+    /// * `external_cost_per_delta` - External cost per unit of delta
+    /// * `candidates` - Option books that may absorb the hedge
+    /// * `portfolio` - Current portfolio Greeks
+    /// * `limits` - Portfolio risk limits
+    #[must_use]
+    pub fn route(
+        &self,
+        order: &HedgeOrder,
+        external_cost_per_delta: Decimal,
+        candidates: &[InternalCandidate],
+        portfolio: &Greeks,
+        limits: &RiskLimits,
+    ) -> HedgeRoute {
+        let target = order.delta_change();
+        let mut route = HedgeRoute {
+            internal: Vec::new(),
+            external: Some(order.clone()),
+            internal_cost: Decimal::ZERO,
+            external_cost_avoided: Decimal::ZERO,
+        };
+        if !self.config.enabled || target.is_zero() {
+            return route;
+        }
+
+        let mut opportunities: Vec<Opportunity<'_>> = candidates
+            .iter()
+            .filter_map(|c| Self::price(c, target))
+            .filter(|o| {
+                o.cost_per_delta + self.config.min_savings_per_delta <= external_cost_per_delta
+            })
+            .collect();
+        opportunities.sort_by_key(|o| o.cost_per_delta);
+
+        let mut remaining = target;
+        let mut projected = *portfolio;
+        for opp in opportunities {
+            let contract_delta = opp.candidate.greeks.delta.abs();
+            let fits = (remaining.abs() / contract_delta)
+                .floor()
+                .to_u64()
+                .unwrap_or(0);
+            let mut quantity = opp
+                .available
+                .min(fits)
+                .min(self.config.max_contracts_per_candidate);
+
+            let signed = |q: u64| match opp.side {
+                Side::Buy => Decimal::from(q),
+                Side::Sell => -Decimal::from(q),
+            };
+            while quantity > 0
+                && !within_limits(
+                    portfolio,
+                    &(projected + opp.candidate.greeks * signed(quantity)),
+                    limits,
+                )
+            {
+                quantity /= 2;
+            }
+            if quantity == 0
+                || self
+                    .compliance
+                    .as_ref()
+                    .is_some_and(|c| !c.allows(&opp.candidate.symbol, opp.side, quantity))
+            {
+                continue;
+            }
+
+            let change = opp.candidate.greeks * signed(quantity);
+            projected += change;
+            remaining -= change.delta;
+            let cost = opp.cost_per_contract * Decimal::from(quantity);
+            route.internal_cost += cost;
+            route.external_cost_avoided += change.delta.abs() * external_cost_per_delta;
+            route.internal.push(InternalCross {
+                symbol: opp.candidate.symbol.clone(),
+                side: opp.side,
+                price: opp.price,
+                quantity,
+                delta_change: change.delta,
+                cost,
+            });
+        }
+
+        let external_quantity = (remaining.abs() / order.delta_per_unit)
+            .round()
+            .to_u64()
+            .unwrap_or(0);
+        route.external = (external_quantity > 0).then(|| HedgeOrder {
+            quantity: external_quantity,
+            ..order.clone()
+        });
+        route
+    }
+
+    /// Prices the side of a candidate that moves delta towards the target.
+    fn price(candidate: &InternalCandidate, target: Decimal) -> Option<Opportunity<'_>> {
+        let delta = candidate.greeks.delta;
+        if delta.is_zero() || candidate.tick_size <= Decimal::ZERO {
+            return None;
+        }
+        // Buying adds the contract delta; selling removes it.
+        let side = if (delta > Decimal::ZERO) == (target > Decimal::ZERO) {
+            Side::Buy
+        } else {
+            Side::Sell
+        };
+        let quote = candidate.book.best_quote();
+        let (price, available) = match side {
+            Side::Buy => (quote.ask_price()?, quote.ask_size()),
+            Side::Sell => (quote.bid_price()?, quote.bid_size()),
+        };
+        let execution = Decimal::from(price) * candidate.tick_size;
+        let cost_per_contract = match side {
+            Side::Buy => execution - candidate.theo,
+            Side::Sell => candidate.theo - execution,
+        };
+        Some(Opportunity {
+            candidate,
+            side,
+            price,
+            available,
+            cost_per_contract,
+            cost_per_delta: cost_per_contract / delta.abs(),
+        })
+    }
+}
+
+/// Returns true if every non-delta Greek is within its limit or no worse
+/// than before.
+fn within_limits(before: &Greeks, after: &Greeks, limits: &RiskLimits) -> bool {
+    let ok = |b: Decimal, a: Decimal, limit: Decimal| a.abs() <= limit || a.abs() <= b.abs();
+    ok(before.gamma, after.gamma, limits.max_gamma)
+        && ok(before.vega, after.vega, limits.max_vega)
+        && ok(before.theta, after.theta, limits.max_theta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::OptionStyle;
+    use orderbook_rs::OrderId;
+    use rust_decimal_macros::dec;
+
+    fn put_candidate(ask: u128, size: u64) -> InternalCandidate {
+        let book = Arc::new(OptionOrderBook::new("BTC-P", OptionStyle::Put));
+        book.add_limit_order(OrderId::new(), Side::Sell, ask, size)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), Side::Buy, ask - 10, size)
+            .unwrap();
+        InternalCandidate {
+            symbol: "BTC-P".to_string(),
+            book,
+            greeks: Greeks::new(dec!(-0.5), dec!(0.01), dec!(-0.02), dec!(0.1), dec!(0)),
+            theo: dec!(1.00),
+            tick_size: dec!(0.01),
+        }
+    }
+
+    fn sell_order(quantity: u64) -> HedgeOrder {
+        HedgeOrder {
+            symbol: "BTC-PERP".to_string(),
+            side: Side::Sell,
+            quantity,
+            delta_per_unit: Decimal::ONE,
+            timestamp_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_cheap_puts_absorb_hedge() {
+        let internalizer = Internalizer::new(InternalizationConfig::default()).unwrap();
+        // Ask 1.01 vs theo 1.00: 0.02 per delta, external costs 0.05.
+        let route = internalizer.route(
+            &sell_order(10),
+            dec!(0.05),
+            &[put_candidate(101, 8)],
+            &Greeks::zero(),
+            &RiskLimits::default(),
+        );
+
+        assert_eq!(route.internal.len(), 1);
+        assert_eq!(route.internal[0].side, Side::Buy);
+        assert_eq!(route.internal[0].quantity, 8);
+        assert_eq!(route.internal_delta(), dec!(-4));
+        assert!(route.savings() > Decimal::ZERO);
+        assert_eq!(route.external.unwrap().quantity, 6);
+    }
+
+    #[test]
+    fn test_expensive_internal_goes_external() {
+        let internalizer = Internalizer::new(InternalizationConfig::default()).unwrap();
+        let route = internalizer.route(
+            &sell_order(10),
+            dec!(0.01),
+            &[put_candidate(110, 8)],
+            &Greeks::zero(),
+            &RiskLimits::default(),
+        );
+
+        assert!(route.internal.is_empty());
+        assert_eq!(route.external.unwrap().quantity, 10);
+    }
+
+    #[test]
+    fn test_does_not_overshoot() {
+        let internalizer = Internalizer::new(InternalizationConfig::default()).unwrap();
+        let route = internalizer.route(
+            &sell_order(2),
+            dec!(0.05),
+            &[put_candidate(101, 100)],
+            &Greeks::zero(),
+            &RiskLimits::default(),
+        );
+
+        assert_eq!(route.internal[0].quantity, 4);
+        assert!(route.external.is_none());
+    }
+
+    #[test]
+    fn test_risk_limits_shrink_cross() {
+        let internalizer = Internalizer::new(InternalizationConfig::default()).unwrap();
+        let limits = RiskLimits {
+            max_vega: dec!(0.25),
+            ..RiskLimits::default()
+        };
+        let route = internalizer.route(
+            &sell_order(10),
+            dec!(0.05),
+            &[put_candidate(101, 8)],
+            &Greeks::zero(),
+            &limits,
+        );
+
+        // 8 -> 4 -> 2 contracts keeps vega at 0.2.
+        assert_eq!(route.internal[0].quantity, 2);
+    }
+
+    #[test]
+    fn test_compliance_blocks_cross() {
+        struct DenyAll;
+        impl CrossingCompliance for DenyAll {
+            fn allows(&self, _: &str, _: Side, _: u64) -> bool {
+                false
+            }
+        }
+
+        let internalizer = Internalizer::new(InternalizationConfig::default())
+            .unwrap()
+            .with_compliance(Arc::new(DenyAll));
+        let route = internalizer.route(
+            &sell_order(10),
+            dec!(0.05),
+            &[put_candidate(101, 8)],
+            &Greeks::zero(),
+            &RiskLimits::default(),
+        );
+        assert!(route.internal.is_empty());
+    }
+
+    #[test]
+    fn test_disabled() {
+        let internalizer = Internalizer::new(InternalizationConfig {
+            enabled: false,
+            ..InternalizationConfig::default()
+        })
+        .unwrap();
+        let route = internalizer.route(
+            &sell_order(10),
+            dec!(0.05),
+            &[put_candidate(101, 8)],
+            &Greeks::zero(),
+            &RiskLimits::default(),
+        );
+        assert_eq!(route.external, Some(sell_order(10)));
+    }
+}
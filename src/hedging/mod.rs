@@ -0,0 +1,37 @@
+//! Hedging module.
+//!
+//! This module provides the delta hedging pipeline: the [`DeltaHedger`]
+//! decides how much delta to hedge, and the [`Internalizer`] checks whether
+//! our own option books can absorb part of it more cheaply before the rest
+//! is sent to the external hedge instrument.
+//!
+//! ## Components
+//!
+//! - [`HedgeParams`]: Hedge instrument, band and order size bounds
+//! - [`DeltaHedger`]: Band-based hedger emitting [`HedgeOrder`]s
+//! - [`HedgeBook`]: Hedge instrument positions with average price, funding and residual delta after [`HedgeFill`]s
+//! - [`ExecutionPlanner`]: Slices hedge orders into [`ChildOrder`]s by [`ExecutionStrategy`] (aggressive, passive, TWAP, iceberg)
+//! - [`Internalizer`]: Routes hedges into internal option books when cheaper
+//! - [`HedgeRoute`]: Internal crosses plus the remaining external order
+//! - [`GreekForecaster`]: Portfolio Greeks projected to later horizons under time decay, flagging charm-driven band breaches
+
+mod book;
+mod delta;
+mod execution;
+mod forecast;
+mod internal;
+mod order;
+
+pub use book::{HedgeBook, HedgeFill, HedgePosition};
+pub use delta::{DeltaHedger, HedgeParams};
+pub use execution::{
+    ChildOrder, ChildPricing, ChildTrigger, ExecutionPlan, ExecutionPlanner, ExecutionStrategy,
+};
+pub use forecast::{
+    ForecastHorizon, ForecastPosition, GreekForecast, GreekForecaster, GreekProjection,
+};
+pub use internal::{
+    CrossingCompliance, HedgeRoute, InternalCandidate, InternalCross, InternalizationConfig,
+    Internalizer,
+};
+pub use order::HedgeOrder;
@@ -0,0 +1,55 @@
+//! Hedge order module.
+//!
+//! This module provides [`HedgeOrder`], an order in a linear hedge
+//! instrument produced by the hedging pipeline.
+
+use orderbook_rs::Side;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// An order in a hedge instrument.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HedgeOrder {
+    /// Hedge instrument symbol.
+    pub symbol: String,
+    /// Order side.
+    pub side: Side,
+    /// Quantity in instrument units.
+    pub quantity: u64,
+    /// Delta of one instrument unit.
+    pub delta_per_unit: Decimal,
+    /// Creation timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+impl HedgeOrder {
+    /// Returns the portfolio delta change if the order fills completely.
+    #[must_use]
+    pub fn delta_change(&self) -> Decimal {
+        let magnitude = Decimal::from(self.quantity) * self.delta_per_unit;
+        match self.side {
+            Side::Buy => magnitude,
+            Side::Sell => -magnitude,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_delta_change_sign() {
+        let mut order = HedgeOrder {
+            symbol: "BTC-PERP".to_string(),
+            side: Side::Sell,
+            quantity: 3,
+            delta_per_unit: dec!(0.5),
+            timestamp_ms: 0,
+        };
+        assert_eq!(order.delta_change(), dec!(-1.5));
+        order.side = Side::Buy;
+        assert_eq!(order.delta_change(), dec!(1.5));
+    }
+}
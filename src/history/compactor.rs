@@ -0,0 +1,260 @@
+//! Compaction scheduler module.
+//!
+//! This module provides the [`CompactionScheduler`], which compacts every
+//! registered [`Compactable`] store once per configured interval, either
+//! from the caller's loop or on a background thread.
+
+use super::store::CompactionStats;
+use crate::clock::Clock;
+use crate::error::{Error, Result};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Sentinel for "no compaction has run yet".
+const NEVER: u64 = u64::MAX;
+
+/// History that can be compacted by the scheduler.
+pub trait Compactable: Send + Sync {
+    /// Returns the name used in reports.
+    fn name(&self) -> &str;
+
+    /// Applies retention at `now_ms`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the compaction could not be completed.
+    fn compact(&self, now_ms: u64) -> Result<CompactionStats>;
+}
+
+/// Outcome of one scheduler run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompactionReport {
+    /// Clock time of the run in milliseconds.
+    pub timestamp_ms: u64,
+    /// Stats of each store that compacted successfully.
+    pub compacted: Vec<(String, CompactionStats)>,
+    /// Error message of each store that failed.
+    pub failed: Vec<(String, String)>,
+}
+
+impl CompactionReport {
+    /// Returns true if every store compacted successfully.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Runs compaction of registered stores once per interval.
+pub struct CompactionScheduler {
+    /// Time source.
+    clock: Arc<dyn Clock>,
+    /// Minimum time between runs in milliseconds.
+    interval_ms: u64,
+    /// Clock time of the last run.
+    last_run_ms: AtomicU64,
+    /// Stores to compact.
+    stores: RwLock<Vec<Arc<dyn Compactable>>>,
+}
+
+impl CompactionScheduler {
+    /// Creates a scheduler.
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - Time source
+    /// * `interval_ms` - Minimum time between runs in milliseconds
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the interval is zero.
+    pub fn new(clock: Arc<dyn Clock>, interval_ms: u64) -> Result<Self> {
+        if interval_ms == 0 {
+            return Err(Error::configuration("compaction interval must be positive"));
+        }
+        Ok(Self {
+            clock,
+            interval_ms,
+            last_run_ms: AtomicU64::new(NEVER),
+            stores: RwLock::new(Vec::new()),
+        })
+    }
+
+    /// Registers a store to compact.
+    pub fn register(&self, store: Arc<dyn Compactable>) {
+        if let Ok(mut stores) = self.stores.write() {
+            stores.push(store);
+        }
+    }
+
+    /// Returns the number of registered stores.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.stores.read().map_or(0, |s| s.len())
+    }
+
+    /// Returns true if no store is registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the run interval in milliseconds.
+    #[must_use]
+    pub const fn interval_ms(&self) -> u64 {
+        self.interval_ms
+    }
+
+    /// Returns the clock time of the last run, if any.
+    #[must_use]
+    pub fn last_run_ms(&self) -> Option<u64> {
+        match self.last_run_ms.load(Ordering::Acquire) {
+            NEVER => None,
+            ms => Some(ms),
+        }
+    }
+
+    /// Runs if at least one interval has passed since the last run.
+    ///
+    /// Returns the report, or `None` if it was too early.
+    pub fn tick(&self) -> Option<CompactionReport> {
+        let now = self.clock.now_ms();
+        let last = self.last_run_ms.load(Ordering::Acquire);
+        if last != NEVER && now.saturating_sub(last) < self.interval_ms {
+            return None;
+        }
+        if self
+            .last_run_ms
+            .compare_exchange(last, now, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            // Another thread ran concurrently.
+            return None;
+        }
+        Some(self.run(now))
+    }
+
+    /// Runs unconditionally.
+    pub fn force_tick(&self) -> CompactionReport {
+        let now = self.clock.now_ms();
+        self.last_run_ms.store(now, Ordering::Release);
+        self.run(now)
+    }
+
+    /// Starts a background thread that calls [`tick`](Self::tick) every
+    /// `poll` until the returned task is stopped. The thread ticks at least
+    /// once.
+    #[must_use]
+    pub fn spawn(self: Arc<Self>, poll: Duration) -> CompactionTask {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop);
+        let handle = std::thread::spawn(move || {
+            loop {
+                self.tick();
+                if flag.load(Ordering::Acquire) {
+                    break;
+                }
+                std::thread::park_timeout(poll);
+            }
+        });
+        CompactionTask {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Compacts every store.
+    fn run(&self, now: u64) -> CompactionReport {
+        let stores = self.stores.read().map(|s| s.clone()).unwrap_or_default();
+        let mut report = CompactionReport {
+            timestamp_ms: now,
+            ..CompactionReport::default()
+        };
+        for store in stores {
+            match store.compact(now) {
+                Ok(stats) => report.compacted.push((store.name().to_string(), stats)),
+                Err(e) => report
+                    .failed
+                    .push((store.name().to_string(), e.to_string())),
+            }
+        }
+        report
+    }
+}
+
+/// Handle to a background compaction thread.
+///
+/// The thread is stopped when the handle is dropped.
+pub struct CompactionTask {
+    /// Stop request flag.
+    stop: Arc<AtomicBool>,
+    /// Thread handle, taken on stop.
+    handle: Option<JoinHandle<()>>,
+}
+
+impl CompactionTask {
+    /// Stops the thread and waits for it to exit.
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            handle.thread().unpark();
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for CompactionTask {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::history::{HistoryStore, RetentionPolicy};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    const DAY_MS: u64 = 86_400_000;
+
+    #[test]
+    fn test_zero_interval_rejected() {
+        assert!(CompactionScheduler::new(Arc::new(ManualClock::new(0)), 0).is_err());
+    }
+
+    #[test]
+    fn test_tick_respects_interval() {
+        let clock = Arc::new(ManualClock::new(100 * DAY_MS));
+        let scheduler = CompactionScheduler::new(clock.clone(), DAY_MS).unwrap();
+        let store = Arc::new(
+            HistoryStore::<Decimal>::new("pnl", RetentionPolicy::from_days(1, 3, None)).unwrap(),
+        );
+        store.record(99 * DAY_MS, dec!(1));
+        scheduler.register(store.clone());
+
+        let report = scheduler.tick().unwrap();
+        assert!(report.is_ok());
+        assert_eq!(report.compacted[0].0, "pnl");
+        assert_eq!(report.compacted[0].1.rolled_to_hourly, 0);
+
+        clock.advance(DAY_MS / 2);
+        assert!(scheduler.tick().is_none());
+
+        clock.advance(DAY_MS);
+        let report = scheduler.tick().unwrap();
+        assert_eq!(report.compacted[0].1.rolled_to_hourly, 1);
+    }
+
+    #[test]
+    fn test_background_task_stops() {
+        let clock = Arc::new(ManualClock::new(0));
+        let scheduler = Arc::new(CompactionScheduler::new(clock, 1).unwrap());
+        let mut task = Arc::clone(&scheduler).spawn(Duration::from_millis(1));
+        task.stop();
+        assert!(scheduler.last_run_ms().is_some());
+    }
+}
@@ -0,0 +1,35 @@
+//! History module.
+//!
+//! This module provides bounded time-series history with retention
+//! policies: raw points are kept for a configured window, then rolled up
+//! into hourly and daily buckets, and finally evicted. Points leaving the
+//! store can be exported through an [`ArchiveSink`] first.
+//!
+//! ## Components
+//!
+//! - [`RetentionPolicy`]: How long each [`Resolution`] is kept
+//! - [`HistoryStore`]: Time series of [`Aggregate`] values with compaction
+//! - [`ArchiveSink`]: Export hook for points removed by compaction
+//! - [`CompactionScheduler`]: Clock-driven compaction of registered [`Compactable`] stores
+//!
+//! ## Compaction
+//!
+//! Compaction runs from [`CompactionScheduler::tick`] in the caller's
+//! periodic loop, or on a background thread started with
+//! [`CompactionScheduler::spawn`].
+//!
+//! Besides [`HistoryStore`], the order book
+//! [`EventJournal`](crate::orderbook::EventJournal) drops the records its
+//! checkpoint covers and the [`EdgeTracker`](crate::pnl::EdgeTracker)
+//! evicts expired contracts and compacts its edge series. The recovery
+//! journal is truncated by
+//! [`JournaledState::checkpoint`](crate::recovery::JournaledState::checkpoint)
+//! instead, since it needs the state store.
+
+mod compactor;
+mod policy;
+mod store;
+
+pub use compactor::{Compactable, CompactionReport, CompactionScheduler, CompactionTask};
+pub use policy::{Resolution, RetentionPolicy};
+pub use store::{Aggregate, ArchiveSink, CompactionStats, HistoryPoint, HistoryStore};
@@ -0,0 +1,133 @@
+//! Retention policy module.
+//!
+//! This module provides [`RetentionPolicy`] and the [`Resolution`] levels
+//! history is rolled up through.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// Milliseconds in an hour.
+pub(crate) const MS_PER_HOUR: u64 = 3_600_000;
+
+/// Milliseconds in a day.
+pub(crate) const MS_PER_DAY: u64 = 86_400_000;
+
+/// Granularity of a history point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Resolution {
+    /// Point as recorded.
+    Raw,
+    /// Aggregate over a clock hour.
+    Hourly,
+    /// Aggregate over a UTC day.
+    Daily,
+}
+
+impl Resolution {
+    /// Returns the start of the bucket containing a timestamp.
+    #[must_use]
+    pub const fn bucket_start(self, timestamp_ms: u64) -> u64 {
+        match self {
+            Self::Raw => timestamp_ms,
+            Self::Hourly => timestamp_ms - timestamp_ms % MS_PER_HOUR,
+            Self::Daily => timestamp_ms - timestamp_ms % MS_PER_DAY,
+        }
+    }
+}
+
+impl std::fmt::Display for Resolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Raw => write!(f, "raw"),
+            Self::Hourly => write!(f, "hourly"),
+            Self::Daily => write!(f, "daily"),
+        }
+    }
+}
+
+/// How long history is kept at each resolution.
+///
+/// Ages are measured from the point's timestamp (bucket start for
+/// aggregates) to the compaction time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Age after which raw points are rolled up into hourly buckets.
+    pub raw_ms: u64,
+    /// Age after which hourly buckets are rolled up into daily buckets.
+    pub hourly_ms: u64,
+    /// Age after which daily buckets are evicted, or `None` to keep them.
+    pub daily_ms: Option<u64>,
+}
+
+impl Default for RetentionPolicy {
+    /// Raw for 7 days, hourly for 90 days, daily forever.
+    fn default() -> Self {
+        Self {
+            raw_ms: 7 * MS_PER_DAY,
+            hourly_ms: 90 * MS_PER_DAY,
+            daily_ms: None,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// Creates a policy from retention windows in days.
+    #[must_use]
+    pub const fn from_days(raw_days: u64, hourly_days: u64, daily_days: Option<u64>) -> Self {
+        Self {
+            raw_ms: raw_days * MS_PER_DAY,
+            hourly_ms: hourly_days * MS_PER_DAY,
+            daily_ms: match daily_days {
+                Some(days) => Some(days * MS_PER_DAY),
+                None => None,
+            },
+        }
+    }
+
+    /// Validates the policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the windows are not
+    /// non-decreasing from raw to daily.
+    pub fn validate(&self) -> Result<()> {
+        if self.hourly_ms < self.raw_ms {
+            return Err(Error::configuration(
+                "hourly retention must not be shorter than raw retention",
+            ));
+        }
+        if self.daily_ms.is_some_and(|daily| daily < self.hourly_ms) {
+            return Err(Error::configuration(
+                "daily retention must not be shorter than hourly retention",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_start() {
+        let ts = 3 * MS_PER_DAY + 5 * MS_PER_HOUR + 1234;
+        assert_eq!(Resolution::Raw.bucket_start(ts), ts);
+        assert_eq!(
+            Resolution::Hourly.bucket_start(ts),
+            3 * MS_PER_DAY + 5 * MS_PER_HOUR
+        );
+        assert_eq!(Resolution::Daily.bucket_start(ts), 3 * MS_PER_DAY);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(RetentionPolicy::default().validate().is_ok());
+        assert!(RetentionPolicy::from_days(7, 1, None).validate().is_err());
+        assert!(
+            RetentionPolicy::from_days(1, 7, Some(3))
+                .validate()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,398 @@
+//! History store module.
+//!
+//! This module provides the [`HistoryStore`], a time series of
+//! [`Aggregate`] values compacted according to a [`RetentionPolicy`].
+
+use super::compactor::Compactable;
+use super::policy::{Resolution, RetentionPolicy};
+use crate::error::Result;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A value that can be rolled up into coarser buckets.
+///
+/// `merge` should be associative and commutative, since rollups merge
+/// points in no guaranteed order.
+pub trait Aggregate: Clone + Send + Sync {
+    /// Folds another value into this one.
+    fn merge(&mut self, other: &Self);
+}
+
+impl Aggregate for Decimal {
+    fn merge(&mut self, other: &Self) {
+        *self += *other;
+    }
+}
+
+/// One point of a history series.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryPoint<T> {
+    /// Record time, or bucket start for aggregates.
+    pub timestamp_ms: u64,
+    /// Granularity of the point.
+    pub resolution: Resolution,
+    /// Number of raw records folded into the point.
+    pub count: u64,
+    /// Value, aggregated for hourly and daily points.
+    pub value: T,
+}
+
+/// Export hook for points removed by compaction.
+///
+/// Receives points as they were stored: raw points before they are rolled
+/// up, hourly buckets before they are rolled into days, and daily buckets
+/// before eviction.
+pub trait ArchiveSink<T>: Send + Sync {
+    /// Exports points removed from a store.
+    ///
+    /// # Errors
+    ///
+    /// An error aborts the compaction and leaves the store unchanged.
+    fn archive(&self, store: &str, points: &[HistoryPoint<T>]) -> Result<()>;
+}
+
+/// Outcome of one compaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionStats {
+    /// Raw points rolled up into hourly buckets.
+    pub rolled_to_hourly: usize,
+    /// Hourly buckets rolled up into daily buckets.
+    pub rolled_to_daily: usize,
+    /// Daily buckets evicted.
+    pub evicted: usize,
+    /// Points passed to the archive sink.
+    pub archived: usize,
+    /// Points left in the store.
+    pub remaining: usize,
+}
+
+/// Time series with retention-driven compaction.
+pub struct HistoryStore<T: Aggregate> {
+    /// Store name, passed to the archive sink and reported by compaction.
+    name: String,
+    /// Retention policy.
+    policy: RetentionPolicy,
+    /// Points ordered by timestamp.
+    points: Mutex<Vec<HistoryPoint<T>>>,
+    /// Export hook for removed points.
+    archive: Option<Arc<dyn ArchiveSink<T>>>,
+}
+
+impl<T: Aggregate> HistoryStore<T> {
+    /// Creates an empty store.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the policy is invalid.
+    pub fn new(name: impl Into<String>, policy: RetentionPolicy) -> Result<Self> {
+        policy.validate()?;
+        Ok(Self {
+            name: name.into(),
+            policy,
+            points: Mutex::new(Vec::new()),
+            archive: None,
+        })
+    }
+
+    /// Sets the archive sink.
+    #[must_use]
+    pub fn with_archive(mut self, archive: Arc<dyn ArchiveSink<T>>) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    /// Returns the store name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the retention policy.
+    #[must_use]
+    pub const fn policy(&self) -> &RetentionPolicy {
+        &self.policy
+    }
+
+    /// Returns the number of stored points.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns true if the store is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Records a raw value.
+    pub fn record(&self, timestamp_ms: u64, value: T) {
+        let mut points = self.lock();
+        let index = points.partition_point(|p| p.timestamp_ms <= timestamp_ms);
+        points.insert(
+            index,
+            HistoryPoint {
+                timestamp_ms,
+                resolution: Resolution::Raw,
+                count: 1,
+                value,
+            },
+        );
+    }
+
+    /// Returns the points with a timestamp in `[from_ms, to_ms)`.
+    #[must_use]
+    pub fn points(&self, from_ms: u64, to_ms: u64) -> Vec<HistoryPoint<T>> {
+        self.lock()
+            .iter()
+            .filter(|p| p.timestamp_ms >= from_ms && p.timestamp_ms < to_ms)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the aggregate of the points with a timestamp in
+    /// `[from_ms, to_ms)`, or `None` if there are none.
+    #[must_use]
+    pub fn total(&self, from_ms: u64, to_ms: u64) -> Option<T> {
+        let points = self.lock();
+        let mut in_range = points
+            .iter()
+            .filter(|p| p.timestamp_ms >= from_ms && p.timestamp_ms < to_ms);
+        let mut total = in_range.next()?.value.clone();
+        for point in in_range {
+            total.merge(&point.value);
+        }
+        Some(total)
+    }
+
+    /// Applies the retention policy at `now_ms`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the archive sink's error, in which case nothing is changed.
+    pub fn compact(&self, now_ms: u64) -> Result<CompactionStats> {
+        let mut points = self.lock();
+        let mut stats = CompactionStats::default();
+        let mut removed = Vec::new();
+        let mut raw = Vec::new();
+        let mut buckets: BTreeMap<(u64, Resolution), HistoryPoint<T>> = BTreeMap::new();
+
+        // Stored points are archived when removed; rollups created during
+        // this pass are not, since they were never stored.
+        let mut work: VecDeque<(HistoryPoint<T>, bool)> =
+            points.iter().cloned().map(|p| (p, true)).collect();
+        while let Some((point, stored)) = work.pop_front() {
+            let age = now_ms.saturating_sub(point.timestamp_ms);
+            let expired = match point.resolution {
+                Resolution::Raw => age > self.policy.raw_ms,
+                Resolution::Hourly => age > self.policy.hourly_ms,
+                Resolution::Daily => self.policy.daily_ms.is_some_and(|d| age > d),
+            };
+            if !expired {
+                match point.resolution {
+                    Resolution::Raw => raw.push(point),
+                    _ => merge_into(&mut buckets, point),
+                }
+                continue;
+            }
+            let next = match point.resolution {
+                Resolution::Raw => {
+                    stats.rolled_to_hourly += 1;
+                    Resolution::Hourly
+                }
+                Resolution::Hourly => {
+                    stats.rolled_to_daily += 1;
+                    Resolution::Daily
+                }
+                Resolution::Daily => {
+                    stats.evicted += 1;
+                    if stored {
+                        removed.push(point);
+                    }
+                    continue;
+                }
+            };
+            let rolled = HistoryPoint {
+                timestamp_ms: next.bucket_start(point.timestamp_ms),
+                resolution: next,
+                count: point.count,
+                value: point.value.clone(),
+            };
+            if stored {
+                removed.push(point);
+            }
+            // Merge with a stored bucket of the same period before the
+            // rolled point is re-evaluated at its new resolution.
+            match buckets.remove(&(rolled.timestamp_ms, next)) {
+                Some(mut existing) => {
+                    existing.count += rolled.count;
+                    existing.value.merge(&rolled.value);
+                    work.push_back((existing, false));
+                }
+                None => work.push_back((rolled, false)),
+            }
+        }
+
+        if let Some(archive) = &self.archive
+            && !removed.is_empty()
+        {
+            archive.archive(&self.name, &removed)?;
+            stats.archived = removed.len();
+        }
+
+        let mut compacted: Vec<HistoryPoint<T>> = buckets.into_values().collect();
+        compacted.extend(raw);
+        compacted.sort_by_key(|p| (p.timestamp_ms, std::cmp::Reverse(p.resolution)));
+        stats.remaining = compacted.len();
+        *points = compacted;
+        Ok(stats)
+    }
+
+    /// Locks the points, recovering from poisoning since every update
+    /// leaves the series ordered.
+    fn lock(&self) -> MutexGuard<'_, Vec<HistoryPoint<T>>> {
+        self.points
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl<T: Aggregate> Compactable for HistoryStore<T> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn compact(&self, now_ms: u64) -> Result<CompactionStats> {
+        HistoryStore::compact(self, now_ms)
+    }
+}
+
+/// Merges a point into the bucket map.
+fn merge_into<T: Aggregate>(
+    buckets: &mut BTreeMap<(u64, Resolution), HistoryPoint<T>>,
+    point: HistoryPoint<T>,
+) {
+    match buckets.get_mut(&(point.timestamp_ms, point.resolution)) {
+        Some(existing) => {
+            existing.count += point.count;
+            existing.value.merge(&point.value);
+        }
+        None => {
+            buckets.insert((point.timestamp_ms, point.resolution), point);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::history::policy::{MS_PER_DAY, MS_PER_HOUR};
+    use rust_decimal_macros::dec;
+
+    struct FailingArchive;
+
+    impl ArchiveSink<Decimal> for FailingArchive {
+        fn archive(&self, _store: &str, _points: &[HistoryPoint<Decimal>]) -> Result<()> {
+            Err(Error::configuration("archive unavailable"))
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<HistoryPoint<Decimal>>>);
+
+    impl ArchiveSink<Decimal> for Recorder {
+        fn archive(&self, _store: &str, points: &[HistoryPoint<Decimal>]) -> Result<()> {
+            self.0.lock().unwrap().extend_from_slice(points);
+            Ok(())
+        }
+    }
+
+    fn store() -> HistoryStore<Decimal> {
+        HistoryStore::new("pnl", RetentionPolicy::from_days(1, 3, Some(10))).unwrap()
+    }
+
+    #[test]
+    fn test_raw_rolls_up_to_hourly() {
+        let store = store();
+        let base = 100 * MS_PER_DAY;
+        store.record(base + 10, dec!(1));
+        store.record(base + 20, dec!(2));
+        store.record(base + MS_PER_HOUR + 5, dec!(4));
+        store.record(base + 2 * MS_PER_DAY, dec!(8));
+
+        let stats = store.compact(base + 2 * MS_PER_DAY).unwrap();
+
+        assert_eq!(stats.rolled_to_hourly, 3);
+        assert_eq!(stats.remaining, 3);
+        let points = store.points(0, u64::MAX);
+        assert_eq!(points[0].resolution, Resolution::Hourly);
+        assert_eq!(points[0].value, dec!(3));
+        assert_eq!(points[0].count, 2);
+        assert_eq!(points[1].timestamp_ms, base + MS_PER_HOUR);
+        assert_eq!(points[2].resolution, Resolution::Raw);
+        assert_eq!(store.total(0, u64::MAX), Some(dec!(15)));
+    }
+
+    #[test]
+    fn test_cascades_and_evicts() {
+        let store = store();
+        let base = 100 * MS_PER_DAY;
+        store.record(base + 1, dec!(1));
+        store.record(base + MS_PER_HOUR, dec!(2));
+
+        // Five days later both roll straight through hourly into one day.
+        let stats = store.compact(base + 5 * MS_PER_DAY).unwrap();
+        assert_eq!(stats.rolled_to_hourly, 2);
+        assert_eq!(stats.rolled_to_daily, 2);
+        let points = store.points(0, u64::MAX);
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].resolution, Resolution::Daily);
+        assert_eq!(points[0].value, dec!(3));
+
+        let stats = store.compact(base + 11 * MS_PER_DAY).unwrap();
+        assert_eq!(stats.evicted, 1);
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_later_rollups_merge_into_existing_bucket() {
+        let store = store();
+        let base = 100 * MS_PER_DAY;
+        store.record(base + 1, dec!(1));
+        store.compact(base + 2 * MS_PER_DAY).unwrap();
+        store.record(base + 2, dec!(2));
+        store.compact(base + 2 * MS_PER_DAY).unwrap();
+
+        let points = store.points(0, u64::MAX);
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].value, dec!(3));
+        assert_eq!(points[0].count, 2);
+    }
+
+    #[test]
+    fn test_archive_receives_stored_points() {
+        let recorder = Arc::new(Recorder::default());
+        let store = store().with_archive(recorder.clone());
+        let base = 100 * MS_PER_DAY;
+        store.record(base + 1, dec!(1));
+
+        let stats = store.compact(base + 5 * MS_PER_DAY).unwrap();
+
+        assert_eq!(stats.archived, 1);
+        let archived = recorder.0.lock().unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].resolution, Resolution::Raw);
+    }
+
+    #[test]
+    fn test_archive_failure_leaves_store_unchanged() {
+        let store = store().with_archive(Arc::new(FailingArchive));
+        store.record(1, dec!(1));
+
+        assert!(store.compact(10 * MS_PER_DAY).is_err());
+        assert_eq!(store.points(0, u64::MAX)[0].resolution, Resolution::Raw);
+    }
+}
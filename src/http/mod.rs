@@ -0,0 +1,47 @@
+//! HTTP control module.
+//!
+//! This module provides a tiny embedded REST server for operational
+//! tooling: health, statistics, risk and positions, plus manual controls to
+//! pause and resume quoting and trigger a hedge. Available with the `http`
+//! feature.
+//!
+//! ## Endpoints
+//!
+//! | Method | Path | Response |
+//! |--------|------|----------|
+//! | GET | `/health` | Status, quoting switch and trading state (no token required) |
+//! | GET | `/stats` | Order book [`GlobalStats`](crate::orderbook::GlobalStats) |
+//! | GET | `/risk` | [`RiskDashboard`](crate::risk::RiskDashboard) |
+//! | GET | `/positions` | Inventory [`Position`](crate::inventory::Position)s |
+//! | POST | `/quoting/pause` | Pauses quoting everywhere, or adds a scoped pause |
+//! | POST | `/quoting/resume` | Resumes quoting everywhere, or removes a scoped pause |
+//! | GET | `/quoting/pauses` | Active [`QuotingPause`](crate::quoting::QuotingPause)s |
+//! | POST | `/hedge` | Runs the configured [`HedgeTrigger`] |
+//!
+//! Every endpoint except `/health` requires an `Authorization: Bearer
+//! <token>` header matching the configured token. Responses are JSON; an
+//! endpoint whose component was not configured answers `503`.
+//!
+//! ## Scoped Pauses
+//!
+//! Both quoting controls act on the configured
+//! [`QuotingPauses`](crate::quoting::QuotingPauses), which the quote engine
+//! consults. Without query parameters, `/quoting/pause` adds a pause with
+//! the global scope and `/quoting/resume` removes it.
+//!
+//! With query parameters, `/quoting/pause` adds a pause in a narrower
+//! scope. The scope is `contract=<symbol>`, `underlying=<symbol>`,
+//! `underlying` with `expiry=<YYYYMMDD>`, or those two with `low` and
+//! `high` strikes. Optional `reason` (a reason code, default `operator`),
+//! `note`, `by` and `ttl_ms` complete the pause. `/quoting/resume?id=<id>`
+//! removes one pause. Names and values are percent-decoded, with `+` for
+//! space.
+//!
+//! The server only reads and changes state the embedding application
+//! owns; share the same `QuotingPauses` with the quote engine.
+
+mod server;
+mod state;
+
+pub use server::{ControlServer, ControlServerHandle};
+pub use state::{ControlState, HedgeTrigger};
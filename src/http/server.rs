@@ -0,0 +1,728 @@
+//! Control server module.
+//!
+//! This module provides the [`ControlServer`], a minimal blocking HTTP/1.1
+//! server on `std::net` that answers one request per connection.
+//!
+//! Each connection is served on its own short-lived thread, up to
+//! [`MAX_CONNECTIONS`] at once, so a slow client cannot hold up the
+//! others. The whole request head must arrive within one deadline, so a
+//! client trickling bytes cannot hold its thread either.
+
+use super::state::ControlState;
+use crate::error::{Error, Result};
+use crate::quoting::{PauseReason, PauseScope, QuotingPause, QuotingPauses};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Longest request head accepted, in bytes.
+const MAX_HEAD_BYTES: usize = 8 * 1024;
+
+/// Default deadline for reading a request head, and write timeout.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Most connections served at once; further connections are closed
+/// unanswered until one finishes.
+const MAX_CONNECTIONS: usize = 64;
+
+/// Sleep between accept attempts while idle.
+const ACCEPT_POLL: Duration = Duration::from_millis(20);
+
+/// A response status and JSON body.
+struct Response {
+    /// HTTP status code.
+    status: u16,
+    /// JSON body.
+    body: String,
+}
+
+impl Response {
+    /// Creates a response with a serialized body.
+    fn json(status: u16, body: &impl Serialize) -> Self {
+        Self {
+            status,
+            body: serde_json::to_string(body).unwrap_or_else(|_| "null".to_string()),
+        }
+    }
+
+    /// Creates an error response.
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(status, &json!({ "error": message }))
+    }
+}
+
+/// Embedded REST server for health, stats and manual controls.
+pub struct ControlServer {
+    /// Bound listener.
+    listener: TcpListener,
+    /// Request handling shared with the connection threads.
+    service: Service,
+}
+
+/// Endpoints and their configuration, shared by connection threads.
+struct Service {
+    /// Exposed components.
+    state: Arc<ControlState>,
+    /// Bearer token required by protected endpoints.
+    token: String,
+    /// Deadline for reading a request head, and write timeout.
+    timeout: Duration,
+}
+
+impl ControlServer {
+    /// Binds the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Address to listen on, e.g. `127.0.0.1:8080`
+    /// * `state` - Components exposed by the endpoints
+    /// * `token` - Bearer token required by every endpoint except `/health`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the token is empty, or
+    /// `Error::IoError` if the address cannot be bound.
+    pub fn bind(
+        addr: impl ToSocketAddrs,
+        state: Arc<ControlState>,
+        token: impl Into<String>,
+    ) -> Result<Self> {
+        let token = token.into();
+        if token.is_empty() {
+            return Err(Error::configuration(
+                "control server token must not be empty",
+            ));
+        }
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            service: Service {
+                state,
+                token,
+                timeout: IO_TIMEOUT,
+            },
+        })
+    }
+
+    /// Sets the deadline for reading a request head, which is also the
+    /// write timeout. Defaults to five seconds; a zero timeout is ignored.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        if !timeout.is_zero() {
+            self.service.timeout = timeout;
+        }
+        self
+    }
+
+    /// Returns the bound address.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::IoError` if the address cannot be read.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serves requests on a background thread until the handle is stopped.
+    ///
+    /// Each accepted connection is answered on its own thread. Stopping
+    /// the handle stops accepting; connections in flight finish within
+    /// their timeouts.
+    #[must_use]
+    pub fn spawn(self) -> ControlServerHandle {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop);
+        let Self { listener, service } = self;
+        let service = Arc::new(service);
+        let active = Arc::new(AtomicUsize::new(0));
+        let handle = std::thread::spawn(move || {
+            while !flag.load(Ordering::Acquire) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        if active.fetch_add(1, Ordering::AcqRel) >= MAX_CONNECTIONS {
+                            active.fetch_sub(1, Ordering::AcqRel);
+                            continue;
+                        }
+                        let service = Arc::clone(&service);
+                        let slot = Arc::clone(&active);
+                        let spawned = std::thread::Builder::new()
+                            .name("control-connection".to_string())
+                            .spawn(move || {
+                                service.serve(stream);
+                                slot.fetch_sub(1, Ordering::AcqRel);
+                            });
+                        if spawned.is_err() {
+                            active.fetch_sub(1, Ordering::AcqRel);
+                        }
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        std::thread::sleep(ACCEPT_POLL);
+                    }
+                    Err(_) => std::thread::sleep(ACCEPT_POLL),
+                }
+            }
+        });
+        ControlServerHandle {
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Service {
+    /// Answers one request on a connection.
+    ///
+    /// A connection whose timeouts cannot be set is dropped unanswered, so
+    /// a stalled client cannot hold its thread.
+    fn serve(&self, stream: TcpStream) {
+        let configured = stream
+            .set_nonblocking(false)
+            .and_then(|()| stream.set_write_timeout(Some(self.timeout)));
+        if configured.is_err() {
+            return;
+        }
+        let deadline = Instant::now() + self.timeout;
+        let response = match read_head(&stream, deadline) {
+            Some(head) => self.route(&head),
+            None => Response::error(400, "malformed request"),
+        };
+        let _ = write_response(stream, &response);
+    }
+
+    /// Dispatches a request to its endpoint.
+    fn route(&self, head: &RequestHead) -> Response {
+        if head.path != "/health" && !self.authorized(head) {
+            return Response::error(401, "missing or invalid token");
+        }
+        let state = &self.state;
+        match (head.method.as_str(), head.path.as_str()) {
+            ("GET", "/health") => Response::json(
+                200,
+                &json!({
+                    "status": "ok",
+                    "quoting_enabled": state.is_quoting_enabled(),
+                    "trading_state": state.risk.as_ref().map(|r| r.state()),
+                }),
+            ),
+            ("GET", "/stats") => match &state.manager {
+                Some(manager) => Response::json(200, &manager.stats()),
+                None => Response::error(503, "order books not configured"),
+            },
+            ("GET", "/risk") => match &state.risk {
+                Some(risk) => Response::json(200, &risk.dashboard()),
+                None => Response::error(503, "risk controller not configured"),
+            },
+            ("GET", "/positions") => match &state.inventory {
+                Some(inventory) => Response::json(200, &inventory.positions()),
+                None => Response::error(503, "inventory not configured"),
+            },
+            ("POST", "/quoting/pause") if !head.query.is_empty() => match &state.pauses {
+                Some(pauses) => match scoped_pause(pauses, &head.query) {
+                    Ok(pause) => Response::json(200, &pause),
+                    Err(e) => Response::error(400, &e.to_string()),
+                },
+                None => Response::error(503, "quoting pauses not configured"),
+            },
+            ("POST", "/quoting/resume") if !head.query.is_empty() => match &state.pauses {
+                Some(pauses) => match head.query.get("id").and_then(|id| id.parse().ok()) {
+                    Some(id) => match pauses.resume(id) {
+                        Some(pause) => Response::json(200, &json!({ "resumed": pause })),
+                        None => Response::error(404, "no active pause with this id"),
+                    },
+                    None => Response::error(400, "missing or invalid pause id"),
+                },
+                None => Response::error(503, "quoting pauses not configured"),
+            },
+            ("POST", "/quoting/pause") | ("POST", "/quoting/resume") => {
+                match state.set_quoting_enabled(head.path.ends_with("resume")) {
+                    Ok(()) => Response::json(
+                        200,
+                        &json!({ "quoting_enabled": state.is_quoting_enabled() }),
+                    ),
+                    Err(e) => Response::error(503, &e.to_string()),
+                }
+            }
+            ("GET", "/quoting/pauses") => match &state.pauses {
+                Some(pauses) => Response::json(200, &pauses.active()),
+                None => Response::error(503, "quoting pauses not configured"),
+            },
+            ("POST", "/hedge") => match &state.hedge {
+                Some(hedge) => match hedge.trigger_hedge() {
+                    Ok(order) => Response::json(200, &json!({ "order": order })),
+                    Err(e) => Response::error(500, &e.to_string()),
+                },
+                None => Response::error(503, "hedge trigger not configured"),
+            },
+            (
+                _,
+                "/health" | "/stats" | "/risk" | "/positions" | "/quoting/pause"
+                | "/quoting/resume" | "/quoting/pauses" | "/hedge",
+            ) => Response::error(405, "method not allowed"),
+            _ => Response::error(404, "not found"),
+        }
+    }
+
+    /// Returns true if the request carries the configured bearer token.
+    fn authorized(&self, head: &RequestHead) -> bool {
+        head.bearer
+            .as_deref()
+            .is_some_and(|token| constant_time_eq(token.as_bytes(), self.token.as_bytes()))
+    }
+}
+
+/// Handle to a running control server.
+///
+/// The server is stopped when the handle is dropped.
+pub struct ControlServerHandle {
+    /// Stop request flag.
+    stop: Arc<AtomicBool>,
+    /// Server thread, taken on stop.
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ControlServerHandle {
+    /// Stops the server and waits for it to exit.
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for ControlServerHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Parsed request line and the headers the server uses.
+struct RequestHead {
+    /// Request method.
+    method: String,
+    /// Request path without query string.
+    path: String,
+    /// Percent-decoded query parameters.
+    query: HashMap<String, String>,
+    /// Bearer token from the `Authorization` header.
+    bearer: Option<String>,
+}
+
+/// Reads a stream until a deadline, however the bytes trickle in.
+struct DeadlineReader<'a> {
+    /// Connection being read.
+    stream: &'a TcpStream,
+    /// Instant after which reads fail.
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(std::io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        let mut stream = self.stream;
+        stream.read(buf)
+    }
+}
+
+/// Reads the request line and headers; the body is ignored.
+///
+/// At most [`MAX_HEAD_BYTES`] are read from the stream, request line
+/// included, and all of them must arrive before `deadline`; a head that
+/// does not end within both limits is rejected.
+fn read_head(stream: &TcpStream, deadline: Instant) -> Option<RequestHead> {
+    let reader = DeadlineReader { stream, deadline };
+    let mut reader = BufReader::new(reader.take(MAX_HEAD_BYTES as u64));
+    let mut line = String::new();
+    if reader.read_line(&mut line).ok()? == 0 || !line.ends_with('\n') {
+        return None;
+    }
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let path = path.to_string();
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            Some((percent_decode(name)?, percent_decode(value)?))
+        })
+        .collect::<Option<_>>()?;
+
+    let mut bearer = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).ok()? == 0 || !header.ends_with('\n') {
+            return None;
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.trim().eq_ignore_ascii_case("authorization")
+        {
+            bearer = value
+                .trim()
+                .strip_prefix("Bearer ")
+                .map(|t| t.trim().to_string());
+        }
+    }
+    Some(RequestHead {
+        method,
+        path,
+        query,
+        bearer,
+    })
+}
+
+/// Decodes a query component: `%XX` escapes and `+` for space.
+///
+/// Returns `None` for a truncated or invalid escape, or if the decoded
+/// bytes are not UTF-8.
+fn percent_decode(text: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        match byte {
+            b'%' => {
+                let hex = tail.get(..2)?;
+                if !hex.iter().all(u8::is_ascii_hexdigit) {
+                    return None;
+                }
+                bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+                rest = &tail[2..];
+                continue;
+            }
+            b'+' => bytes.push(b' '),
+            _ => bytes.push(byte),
+        }
+        rest = tail;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Adds a scoped pause described by query parameters.
+fn scoped_pause(pauses: &QuotingPauses, query: &HashMap<String, String>) -> Result<QuotingPause> {
+    let text = |name: &str| query.get(name).cloned();
+    let number = |name: &str| -> Result<Option<u64>> {
+        query
+            .get(name)
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| Error::validation(format!("invalid {name} '{v}'")))
+            })
+            .transpose()
+    };
+    let scope = match (text("contract"), text("underlying"), text("expiry")) {
+        (Some(symbol), None, None) => PauseScope::Contract(symbol),
+        (None, Some(underlying), None) => PauseScope::Underlying(underlying),
+        (None, Some(underlying), Some(expiry)) => match (number("low")?, number("high")?) {
+            (None, None) => PauseScope::Expiry { underlying, expiry },
+            (Some(low), Some(high)) => PauseScope::StrikeRange {
+                underlying,
+                expiry,
+                low,
+                high,
+            },
+            _ => return Err(Error::validation("strike range needs low and high")),
+        },
+        _ => return Err(Error::validation("missing or ambiguous pause scope")),
+    };
+    let reason = match query.get("reason") {
+        Some(code) => code.parse()?,
+        None => PauseReason::Operator,
+    };
+    pauses.pause(
+        scope,
+        reason,
+        text("note").unwrap_or_default(),
+        text("by").unwrap_or_else(|| "control-server".to_string()),
+        number("ttl_ms")?,
+    )
+}
+
+/// Writes a response and closes the connection.
+fn write_response(mut stream: TcpStream, response: &Response) -> std::io::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+/// Compares two byte strings in time independent of where they differ.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hedging::HedgeOrder;
+    use crate::http::HedgeTrigger;
+    use crate::inventory::{InventoryManager, PositionLimits};
+    use crate::orderbook::UnderlyingOrderBookManager;
+    use orderbook_rs::Side;
+    use rust_decimal_macros::dec;
+    use std::io::Read;
+
+    struct FixedHedge;
+
+    impl HedgeTrigger for FixedHedge {
+        fn trigger_hedge(&self) -> Result<Option<HedgeOrder>> {
+            Ok(Some(HedgeOrder {
+                symbol: "BTC-PERP".to_string(),
+                side: Side::Sell,
+                quantity: 3,
+                delta_per_unit: dec!(1),
+                timestamp_ms: 0,
+            }))
+        }
+    }
+
+    fn request(addr: SocketAddr, method: &str, path: &str, token: Option<&str>) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let auth = token.map_or(String::new(), |t| format!("Authorization: Bearer {t}\r\n"));
+        write!(stream, "{method} {path} HTTP/1.1\r\nHost: x\r\n{auth}\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response.split("\r\n\r\n").nth(1).unwrap().to_string();
+        (status, body)
+    }
+
+    fn server() -> (Arc<ControlState>, ControlServerHandle, SocketAddr) {
+        use crate::clock::ManualClock;
+
+        let inventory = Arc::new(InventoryManager::new("BTC", PositionLimits::default()).unwrap());
+        inventory.record_trade("BTC-C", dec!(2), dec!(100)).unwrap();
+        let state = Arc::new(
+            ControlState::new()
+                .with_manager(Arc::new(UnderlyingOrderBookManager::new()))
+                .with_inventory(inventory)
+                .with_hedge_trigger(Arc::new(FixedHedge))
+                .with_pauses(Arc::new(QuotingPauses::new(Arc::new(ManualClock::new(0))))),
+        );
+        let server = ControlServer::bind("127.0.0.1:0", Arc::clone(&state), "secret").unwrap();
+        let addr = server.local_addr().unwrap();
+        (state, server.spawn(), addr)
+    }
+
+    #[test]
+    fn test_empty_token_rejected() {
+        let state = Arc::new(ControlState::new());
+        assert!(ControlServer::bind("127.0.0.1:0", state, "").is_err());
+    }
+
+    #[test]
+    fn test_health_and_authorization() {
+        let (_, mut handle, addr) = server();
+
+        let (status, body) = request(addr, "GET", "/health", None);
+        assert_eq!(status, 200);
+        assert!(body.contains("\"quoting_enabled\":true"));
+
+        assert_eq!(request(addr, "GET", "/stats", None).0, 401);
+        assert_eq!(request(addr, "GET", "/stats", Some("wrong")).0, 401);
+        // Unknown paths are behind the token too.
+        assert_eq!(request(addr, "GET", "/nope", None).0, 401);
+        handle.stop();
+    }
+
+    #[test]
+    fn test_routing() {
+        let (state, mut handle, addr) = server();
+
+        let (status, body) = request(addr, "GET", "/stats", Some("secret"));
+        assert_eq!(status, 200);
+        assert!(body.contains("underlying_count"));
+
+        let (_, body) = request(addr, "GET", "/positions", Some("secret"));
+        assert!(body.contains("BTC-C"));
+        assert_eq!(request(addr, "GET", "/risk", Some("secret")).0, 503);
+
+        assert_eq!(
+            request(addr, "POST", "/quoting/pause", Some("secret")).0,
+            200
+        );
+        assert!(!state.is_quoting_enabled());
+        // The global pause stops quoting of every contract.
+        let pauses = state.pauses.as_ref().unwrap();
+        assert!(pauses.is_paused("ETH-20240329-3000-P"));
+        request(addr, "POST", "/quoting/resume", Some("secret"));
+        assert!(state.is_quoting_enabled());
+        assert!(pauses.active().is_empty());
+
+        let (status, body) = request(addr, "POST", "/hedge", Some("secret"));
+        assert_eq!(status, 200);
+        assert!(body.contains("BTC-PERP"));
+        handle.stop();
+    }
+
+    #[test]
+    fn test_unknown_path_and_method() {
+        let (_, mut handle, addr) = server();
+
+        let (status, body) = request(addr, "GET", "/nope", Some("secret"));
+        assert_eq!(status, 404);
+        assert!(body.contains("not found"));
+        assert_eq!(request(addr, "GET", "/hedge", Some("secret")).0, 405);
+        assert_eq!(request(addr, "DELETE", "/stats", Some("secret")).0, 405);
+        handle.stop();
+    }
+
+    #[test]
+    fn test_oversized_head_rejected() {
+        let (_, mut handle, addr) = server();
+
+        // A request line that does not end within the head limit is refused
+        // without reading past the limit.
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let line = format!("GET /{}", "x".repeat(MAX_HEAD_BYTES - 5));
+        stream.write_all(line.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 400"));
+
+        // So is a head whose headers run to the limit without ending.
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let line = "GET /health HTTP/1.1\r\nX-Pad: ";
+        let head = format!("{line}{}", "y".repeat(MAX_HEAD_BYTES - line.len()));
+        stream.write_all(head.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 400"));
+        handle.stop();
+    }
+
+    #[test]
+    fn test_slow_client_does_not_block_others() {
+        let state = Arc::new(ControlState::new());
+        let server = ControlServer::bind("127.0.0.1:0", state, "secret")
+            .unwrap()
+            .with_timeout(Duration::from_millis(300));
+        let addr = server.local_addr().unwrap();
+        let mut handle = server.spawn();
+
+        // One client sends nothing, another trickles a byte well within
+        // the read timeout but never finishes its head.
+        let mut idle = TcpStream::connect(addr).unwrap();
+        let mut slow = TcpStream::connect(addr).unwrap();
+        let started = Instant::now();
+        let trickle = std::thread::spawn(move || {
+            for byte in b"GET /health HTTP/1.1\r\nX: " {
+                if slow.write_all(&[*byte]).is_err() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            let mut response = String::new();
+            let _ = slow.read_to_string(&mut response);
+            response
+        });
+
+        // Other clients are answered meanwhile.
+        assert_eq!(request(addr, "GET", "/health", None).0, 200);
+        assert!(started.elapsed() < Duration::from_millis(300));
+
+        // Both stalled clients are cut off at the head deadline.
+        let mut response = String::new();
+        idle.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 400"));
+        assert!(trickle.join().unwrap().starts_with("HTTP/1.1 400"));
+        assert!(started.elapsed() < Duration::from_secs(2));
+        handle.stop();
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("BTC-20240329").unwrap(), "BTC-20240329");
+        assert_eq!(
+            percent_decode("fat%20finger+fix").unwrap(),
+            "fat finger fix"
+        );
+        assert_eq!(percent_decode("%C3%A9").unwrap(), "\u{e9}");
+        assert!(percent_decode("100%").is_none());
+        assert!(percent_decode("%2").is_none());
+        assert!(percent_decode("%+1").is_none());
+        assert!(percent_decode("%FF").is_none());
+    }
+
+    #[test]
+    fn test_scoped_pauses() {
+        use crate::clock::ManualClock;
+
+        let pauses = Arc::new(QuotingPauses::new(Arc::new(ManualClock::new(0))));
+        let state = Arc::new(ControlState::new().with_pauses(Arc::clone(&pauses)));
+        let server = ControlServer::bind("127.0.0.1:0", Arc::clone(&state), "secret").unwrap();
+        let addr = server.local_addr().unwrap();
+        let mut handle = server.spawn();
+
+        let (status, body) = request(
+            addr,
+            "POST",
+            "/quoting/pause?underlying=BTC&expiry=20240329&reason=expiring&ttl_ms=60000",
+            Some("secret"),
+        );
+        assert_eq!(status, 200);
+        assert!(body.contains("\"reason\":\"Expiring\""));
+        assert!(state.is_quoting_enabled());
+
+        // Query values are percent-decoded.
+        let (status, body) = request(
+            addr,
+            "POST",
+            "/quoting/pause?contract=ETH-20240329-3000-P&note=fat%20finger+check&by=desk%2Fops",
+            Some("secret"),
+        );
+        assert_eq!(status, 200);
+        assert!(body.contains("\"note\":\"fat finger check\""));
+        assert!(body.contains("\"paused_by\":\"desk/ops\""));
+        assert_eq!(
+            request(addr, "POST", "/quoting/pause?contract=%ZZ", Some("secret")).0,
+            400
+        );
+        assert!(pauses.is_paused("BTC-20240329-50000-C"));
+
+        let bad = "/quoting/pause?underlying=BTC&expiry=20240329&low=1";
+        assert_eq!(request(addr, "POST", bad, Some("secret")).0, 400);
+        let (_, body) = request(addr, "GET", "/quoting/pauses", Some("secret"));
+        assert!(body.contains("20240329"));
+
+        let resume = "/quoting/resume?id=0";
+        assert_eq!(
+            request(addr, "POST", "/quoting/resume?id=1", Some("secret")).0,
+            200
+        );
+        assert_eq!(request(addr, "POST", resume, Some("secret")).0, 200);
+        assert_eq!(request(addr, "POST", resume, Some("secret")).0, 404);
+        assert!(!pauses.is_paused("BTC-20240329-50000-C"));
+        handle.stop();
+    }
+}
@@ -0,0 +1,117 @@
+//! Control state module.
+//!
+//! This module provides [`ControlState`], the components the control
+//! server reads from and the quoting pauses it adds and removes.
+
+use crate::error::{Error, Result};
+use crate::hedging::HedgeOrder;
+use crate::inventory::InventoryManager;
+use crate::orderbook::UnderlyingOrderBookManager;
+use crate::quoting::{PauseReason, PauseScope, QuotingPauses};
+use crate::risk::RiskController;
+use std::sync::Arc;
+
+/// Manual hedge action invoked by `POST /hedge`.
+pub trait HedgeTrigger: Send + Sync {
+    /// Computes and dispatches a hedge now.
+    ///
+    /// Returns the dispatched order, or `None` if no hedge was needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the hedge could not be dispatched.
+    fn trigger_hedge(&self) -> Result<Option<HedgeOrder>>;
+}
+
+/// Components exposed by the control server.
+#[derive(Default)]
+pub struct ControlState {
+    /// Order book hierarchy for `/stats`.
+    pub(crate) manager: Option<Arc<UnderlyingOrderBookManager>>,
+    /// Risk controller for `/risk` and `/health`.
+    pub(crate) risk: Option<Arc<RiskController>>,
+    /// Inventory for `/positions`.
+    pub(crate) inventory: Option<Arc<InventoryManager>>,
+    /// Manual hedge action for `/hedge`.
+    pub(crate) hedge: Option<Arc<dyn HedgeTrigger>>,
+    /// Quoting pauses for `/quoting/pause` and `/quoting/resume`.
+    pub(crate) pauses: Option<Arc<QuotingPauses>>,
+}
+
+impl ControlState {
+    /// Creates a state with no components and quoting enabled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the order book hierarchy.
+    #[must_use]
+    pub fn with_manager(mut self, manager: Arc<UnderlyingOrderBookManager>) -> Self {
+        self.manager = Some(manager);
+        self
+    }
+
+    /// Sets the risk controller.
+    #[must_use]
+    pub fn with_risk(mut self, risk: Arc<RiskController>) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    /// Sets the inventory.
+    #[must_use]
+    pub fn with_inventory(mut self, inventory: Arc<InventoryManager>) -> Self {
+        self.inventory = Some(inventory);
+        self
+    }
+
+    /// Sets the manual hedge action.
+    #[must_use]
+    pub fn with_hedge_trigger(mut self, hedge: Arc<dyn HedgeTrigger>) -> Self {
+        self.hedge = Some(hedge);
+        self
+    }
+
+    /// Sets the registry of scoped quoting pauses.
+    #[must_use]
+    pub fn with_pauses(mut self, pauses: Arc<QuotingPauses>) -> Self {
+        self.pauses = Some(pauses);
+        self
+    }
+
+    /// Returns true unless a global quoting pause is active.
+    #[must_use]
+    pub fn is_quoting_enabled(&self) -> bool {
+        self.pauses
+            .as_ref()
+            .is_none_or(|pauses| !pauses.is_paused_globally())
+    }
+
+    /// Turns quoting on or off everywhere, by removing or adding a pause
+    /// with the global scope in the configured [`QuotingPauses`], which the
+    /// quote engine consults.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if no quoting pauses are
+    /// configured.
+    pub fn set_quoting_enabled(&self, enabled: bool) -> Result<()> {
+        let pauses = self
+            .pauses
+            .as_ref()
+            .ok_or_else(|| Error::configuration("quoting pauses not configured"))?;
+        if enabled {
+            pauses.resume_scope(&PauseScope::All);
+        } else if !pauses.is_paused_globally() {
+            pauses.pause(
+                PauseScope::All,
+                PauseReason::Operator,
+                "",
+                "control-server",
+                None,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+//! Combo fill module.
+//!
+//! This module provides [`ComboFill`], a fill on a listed combo instrument
+//! (straddle, strangle) decomposed into its leg fills. Combos never hold a
+//! position of their own: the fill is booked on the legs, so the combo's
+//! risk is counted once, in the same positions the leg books trade.
+
+use crate::error::{Error, Result};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// One leg of a decomposed combo fill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComboLegFill {
+    /// Leg contract symbol.
+    pub symbol: String,
+    /// Signed leg quantity (positive buys).
+    pub quantity: Decimal,
+    /// Leg price per contract.
+    pub price: Decimal,
+}
+
+/// A combo fill decomposed into leg fills.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComboFill {
+    /// Fill identifier.
+    pub fill_id: String,
+    /// Combo instrument symbol.
+    pub combo_symbol: String,
+    /// Signed combo quantity (positive buys).
+    pub quantity: Decimal,
+    /// Combo price per unit.
+    pub price: Decimal,
+    /// Leg fills.
+    pub legs: Vec<ComboLegFill>,
+}
+
+impl ComboFill {
+    /// Validates the fill.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the combo quantity is zero, there
+    /// is no leg, a leg quantity is zero, a leg price is negative or a leg
+    /// symbol repeats.
+    pub fn validate(&self) -> Result<()> {
+        if self.quantity.is_zero() {
+            return Err(Error::validation("combo fill quantity is zero"));
+        }
+        if self.legs.is_empty() {
+            return Err(Error::validation("combo fill has no legs"));
+        }
+        for (i, leg) in self.legs.iter().enumerate() {
+            if leg.quantity.is_zero() || leg.price < Decimal::ZERO {
+                return Err(Error::validation(format!(
+                    "combo leg {} needs a non-zero quantity and non-negative price",
+                    leg.symbol
+                )));
+            }
+            if self.legs[..i].iter().any(|l| l.symbol == leg.symbol) {
+                return Err(Error::validation(format!(
+                    "combo leg {} appears twice",
+                    leg.symbol
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the premium paid for the legs (negative when received).
+    #[must_use]
+    pub fn leg_premium(&self) -> Decimal {
+        self.legs.iter().map(|l| l.quantity * l.price).sum()
+    }
+}
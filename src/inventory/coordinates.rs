@@ -0,0 +1,72 @@
+//! Chain coordinates module.
+//!
+//! This module provides [`ChainCoordinates`], where a contract sits in its
+//! underlying's option chain, used to aggregate positions and Greeks by
+//! strike, expiration and option type.
+
+use chrono::NaiveDate;
+use optionstratlib::OptionStyle;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Milliseconds per day.
+const MS_PER_DAY: Decimal = dec!(86400000);
+
+/// Expiration, strike and style of an option contract.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ChainCoordinates {
+    /// Expiration as formatted in contract symbols.
+    pub expiration: String,
+    /// Strike price.
+    pub strike: u64,
+    /// Call or put.
+    pub style: OptionStyle,
+}
+
+impl ChainCoordinates {
+    /// Creates chain coordinates.
+    #[must_use]
+    pub fn new(expiration: impl Into<String>, strike: u64, style: OptionStyle) -> Self {
+        Self {
+            expiration: expiration.into(),
+            strike,
+            style,
+        }
+    }
+
+    /// Parses the coordinates of a symbol of the form
+    /// `UNDERLYING-EXPIRY-STRIKE-C` or `-P`.
+    ///
+    /// Returns `None` for any other symbol, e.g. a hedge instrument.
+    #[must_use]
+    pub fn parse(symbol: &str) -> Option<Self> {
+        let mut parts = symbol.rsplitn(4, '-');
+        let style = match parts.next()? {
+            "C" => OptionStyle::Call,
+            "P" => OptionStyle::Put,
+            _ => return None,
+        };
+        let strike = parts.next()?.parse().ok()?;
+        let expiration = parts.next()?;
+        let underlying = parts.next()?;
+        if expiration.is_empty() || underlying.is_empty() {
+            return None;
+        }
+        Some(Self::new(expiration, strike, style))
+    }
+
+    /// Returns the days from `now_ms` to the start (UTC) of the expiration
+    /// date, negative once it has passed.
+    ///
+    /// Returns `None` if the expiration is not formatted as `YYYYMMDD`.
+    #[must_use]
+    pub fn days_to_expiry(&self, now_ms: u64) -> Option<Decimal> {
+        let expiry = NaiveDate::parse_from_str(&self.expiration, "%Y%m%d")
+            .ok()?
+            .and_hms_opt(0, 0, 0)?
+            .and_utc()
+            .timestamp_millis();
+        Some((Decimal::from(expiry) - Decimal::from(now_ms)) / MS_PER_DAY)
+    }
+}
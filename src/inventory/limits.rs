@@ -0,0 +1,182 @@
+//! Position limits module.
+//!
+//! This module provides [`PositionLimits`], the absolute position caps an
+//! [`InventoryManager`](super::InventoryManager) enforces at each level of
+//! the chain hierarchy, and optional [`ExtendedGreekLimits`] on the
+//! second-order cross Greeks that build up into pin and expiry risk.
+
+use crate::error::{Error, Result};
+use crate::pricing::ExtendedGreeks;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Optional absolute limits on aggregated cross Greeks.
+///
+/// Units follow [`ExtendedGreeks`]; unset limits are not checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtendedGreekLimits {
+    /// Maximum absolute vanna.
+    pub max_vanna: Option<Decimal>,
+    /// Maximum absolute volga.
+    pub max_volga: Option<Decimal>,
+    /// Maximum absolute charm.
+    pub max_charm: Option<Decimal>,
+    /// Maximum absolute speed.
+    pub max_speed: Option<Decimal>,
+}
+
+impl ExtendedGreekLimits {
+    /// Returns the limits as name and value pairs.
+    fn entries(&self) -> [(&'static str, Option<Decimal>); 4] {
+        [
+            ("vanna", self.max_vanna),
+            ("volga", self.max_volga),
+            ("charm", self.max_charm),
+            ("speed", self.max_speed),
+        ]
+    }
+}
+
+/// Absolute position limits in contracts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionLimits {
+    /// Maximum position in a single option contract.
+    pub per_option: Decimal,
+    /// Maximum gross position, the sum of absolute quantities, across the
+    /// call and put of one strike.
+    pub per_strike: Decimal,
+    /// Maximum gross position across one expiration.
+    pub per_expiration: Decimal,
+    /// Maximum gross position across the underlying.
+    pub per_underlying: Decimal,
+    /// Limits on the aggregated cross Greeks.
+    #[serde(default)]
+    pub extended_greeks: ExtendedGreekLimits,
+}
+
+impl Default for PositionLimits {
+    fn default() -> Self {
+        Self {
+            per_option: dec!(1000),
+            per_strike: dec!(2000),
+            per_expiration: dec!(10000),
+            per_underlying: dec!(50000),
+            extended_greeks: ExtendedGreekLimits::default(),
+        }
+    }
+}
+
+impl PositionLimits {
+    /// Validates the limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if any limit is not positive.
+    pub fn validate(&self) -> Result<()> {
+        if self.per_option <= Decimal::ZERO
+            || self.per_strike <= Decimal::ZERO
+            || self.per_expiration <= Decimal::ZERO
+            || self.per_underlying <= Decimal::ZERO
+            || self
+                .extended_greeks
+                .entries()
+                .iter()
+                .any(|(_, limit)| limit.is_some_and(|l| l <= Decimal::ZERO))
+        {
+            return Err(Error::configuration("position limits must be positive"));
+        }
+        Ok(())
+    }
+
+    /// Checks aggregated cross Greeks, e.g. of an expiration or the whole
+    /// book, against the extended Greek limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InventoryLimitExceeded` for the first cross Greek
+    /// whose absolute value exceeds its limit.
+    pub fn check_extended_greeks(&self, greeks: &ExtendedGreeks) -> Result<()> {
+        match self.extended_greek_breach(&ExtendedGreeks::zero(), greeks) {
+            Some(breach) => Err(breach.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the first cross Greek limit that a change of aggregated
+    /// Greeks from `before` to `after` leaves breached and moves further
+    /// from zero; a change towards compliance is not a breach.
+    #[must_use]
+    pub fn extended_greek_breach(
+        &self,
+        before: &ExtendedGreeks,
+        after: &ExtendedGreeks,
+    ) -> Option<PositionBreach> {
+        let values = |g: &ExtendedGreeks| [g.vanna, g.volga, g.charm, g.speed];
+        let limits = self.extended_greeks.entries().into_iter();
+        for (((level, limit), old), new) in limits.zip(values(before)).zip(values(after)) {
+            if let Some(limit) = limit
+                && new.abs() > limit
+                && new.abs() > old.abs()
+            {
+                return Some(PositionBreach {
+                    level,
+                    limit,
+                    current: new.abs(),
+                });
+            }
+        }
+        None
+    }
+}
+
+/// A position limit breached by a trade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PositionBreach {
+    /// Breached level: `per_option`, `per_strike`, `per_expiration` or
+    /// `per_underlying`, or the cross Greek `vanna`, `volga`, `charm` or
+    /// `speed`.
+    pub level: &'static str,
+    /// Limit of the level.
+    pub limit: Decimal,
+    /// Gross position of the level with the trade, or the absolute cross
+    /// Greek.
+    pub current: Decimal,
+}
+
+impl From<PositionBreach> for Error {
+    fn from(breach: PositionBreach) -> Self {
+        Self::inventory_limit_exceeded(breach.level, breach.limit, breach.current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::Greeks;
+
+    #[test]
+    fn test_extended_greek_limits() {
+        let limits = PositionLimits {
+            extended_greeks: ExtendedGreekLimits {
+                max_charm: Some(dec!(5)),
+                ..ExtendedGreekLimits::default()
+            },
+            ..PositionLimits::default()
+        };
+        let mut greeks = ExtendedGreeks::from(Greeks::zero());
+        greeks.vanna = dec!(1000);
+        greeks.charm = dec!(-4);
+        assert!(limits.check_extended_greeks(&greeks).is_ok());
+
+        greeks.charm = dec!(-6);
+        assert!(matches!(
+            limits.check_extended_greeks(&greeks),
+            Err(Error::InventoryLimitExceeded { limit_type, .. }) if limit_type == "charm"
+        ));
+
+        let mut invalid = limits;
+        invalid.extended_greeks.max_speed = Some(Decimal::ZERO);
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -0,0 +1,1518 @@
+//! Inventory manager module.
+//!
+//! This module provides the [`InventoryManager`], which keeps the positions
+//! of one underlying, enforces [`PositionLimits`] on incoming trades and
+//! aggregates position Greeks.
+//!
+//! Positions are keyed by [`ContractId`]: symbols are interned into the
+//! manager's [`ContractRegistry`], which can be shared with the order book
+//! hierarchy, so hot paths holding an id skip hashing symbol strings. The
+//! `&str` methods resolve the symbol once at the edge; the `*_by_id`
+//! methods take an id directly.
+//!
+//! Positions are located in the option chain by their symbol, or by
+//! coordinates registered with [`InventoryManager::register_contract`],
+//! so Greeks can be aggregated per strike, expiration and option type.
+//! Positions without coordinates, such as hedge legs, count only towards
+//! the totals.
+//!
+//! Tied trades and combo fills are booked as one package: every leg is
+//! checked before any is applied, so a rejected package leaves inventory
+//! untouched.
+//!
+//! Trades recorded with [`InventoryManager::record_strategy_trade`] are
+//! also booked into a position of their strategy, so positions and P&L can
+//! be reported per strategy as [`StrategyReport`]s. The aggregate positions,
+//! limits and Greeks cover every trade, tagged or not.
+//!
+//! ## Aggregate Greeks
+//!
+//! The total and per-expiration Greeks are kept up to date as positions
+//! change: every fill or Greeks update adds the change in the position's
+//! Greeks to the aggregates, so [`InventoryManager::total_greeks`] and
+//! [`InventoryManager::expiration_greeks`] are O(1) on the risk-check path.
+//! Registering coordinates that move a contract to another expiration marks
+//! both expirations dirty; they are re-summed on the next read.
+//! [`InventoryManager::rebuild_greeks`] re-sums everything, e.g. to discard
+//! rounding drift after a long session. The aggregates carry the cross
+//! Greeks set with [`InventoryManager::set_extended_greeks`] too.
+//!
+//! ## Aggregate limits
+//!
+//! The per-strike, per-expiration and per-underlying limits cap gross
+//! positions, the sums of absolute quantities, so a long call does not
+//! offset a short put at the same strike. The gross quantities are kept up
+//! to date the same way as the Greeks, so checking a trade against the
+//! limits does not visit the other positions.
+//!
+//! The extended Greek limits cap the absolute vanna, volga, charm and speed
+//! of each expiration and of the whole inventory. A trade is rejected if it
+//! leaves one of them breached and further from zero;
+//! [`InventoryManager::check_trade`] runs the same checks before a quote
+//! side is sent.
+
+use super::combo::ComboFill;
+use super::coordinates::ChainCoordinates;
+use super::limits::{ExtendedGreekLimits, PositionBreach, PositionLimits};
+use super::position::Position;
+use super::strategy::StrategyReport;
+use super::tied::{TiedFill, TiedTrade};
+use crate::error::{Error, Result};
+use crate::orderbook::{ContractId, ContractRegistry};
+use crate::pricing::{ExtendedGreeks, Greeks};
+use crossbeam_skiplist::SkipMap;
+use optionstratlib::OptionStyle;
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Incrementally maintained aggregate Greeks and gross positions.
+#[derive(Debug, Default)]
+struct GreeksCache {
+    /// Greeks of every position.
+    total: ExtendedGreeks,
+    /// Greeks per expiration of the positions with chain coordinates.
+    by_expiration: BTreeMap<String, ExtendedGreeks>,
+    /// Expirations whose aggregate must be re-summed before it is read.
+    dirty: BTreeSet<String>,
+    /// Gross quantity per expiration and strike.
+    gross_by_strike: BTreeMap<(String, u64), Decimal>,
+    /// Gross quantity per expiration.
+    gross_by_expiration: BTreeMap<String, Decimal>,
+    /// Gross quantity of the positions with chain coordinates.
+    gross_underlying: Decimal,
+}
+
+impl GreeksCache {
+    /// Adds a change in gross quantity at a contract's coordinates.
+    fn add_gross(&mut self, coordinates: &ChainCoordinates, change: Decimal) {
+        *self
+            .gross_by_strike
+            .entry((coordinates.expiration.clone(), coordinates.strike))
+            .or_default() += change;
+        *self
+            .gross_by_expiration
+            .entry(coordinates.expiration.clone())
+            .or_default() += change;
+        self.gross_underlying += change;
+    }
+}
+
+/// Positions and limits for one underlying.
+///
+/// Uses `SkipMap` for thread-safe concurrent access. Trades are booked
+/// under a single lock so packages apply atomically.
+pub struct InventoryManager {
+    /// Underlying symbol.
+    underlying: String,
+    /// Position limits.
+    limits: PositionLimits,
+    /// Interns contract symbols.
+    registry: Arc<ContractRegistry>,
+    /// Positions by contract.
+    positions: SkipMap<ContractId, Mutex<Position>>,
+    /// Chain coordinates registered for symbols that cannot be parsed.
+    coordinates: SkipMap<ContractId, Arc<ChainCoordinates>>,
+    /// Serializes trade booking.
+    booking: Mutex<()>,
+    /// Aggregate Greeks.
+    cache: Mutex<GreeksCache>,
+    /// Positions by strategy tag, then contract.
+    strategies: Mutex<BTreeMap<String, BTreeMap<ContractId, Position>>>,
+}
+
+impl InventoryManager {
+    /// Creates an empty inventory for an underlying.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the limits are invalid.
+    pub fn new(underlying: impl Into<String>, limits: PositionLimits) -> Result<Self> {
+        Self::with_registry(underlying, limits, Arc::new(ContractRegistry::new()))
+    }
+
+    /// Creates an empty inventory sharing a contract registry, e.g. the
+    /// one of the order book hierarchy, so positions and books use the
+    /// same [`ContractId`]s.
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - Underlying symbol
+    /// * `limits` - Position limits
+    /// * `registry` - The registry used to intern contract symbols
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the limits are invalid.
+    pub fn with_registry(
+        underlying: impl Into<String>,
+        limits: PositionLimits,
+        registry: Arc<ContractRegistry>,
+    ) -> Result<Self> {
+        limits.validate()?;
+        Ok(Self {
+            underlying: underlying.into(),
+            limits,
+            registry,
+            positions: SkipMap::new(),
+            coordinates: SkipMap::new(),
+            booking: Mutex::new(()),
+            cache: Mutex::new(GreeksCache::default()),
+            strategies: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    /// Returns the underlying symbol.
+    #[must_use]
+    pub fn underlying(&self) -> &str {
+        &self.underlying
+    }
+
+    /// Returns the position limits.
+    #[must_use]
+    pub const fn limits(&self) -> &PositionLimits {
+        &self.limits
+    }
+
+    /// Returns a reference to the contract registry.
+    #[must_use]
+    pub fn registry(&self) -> &ContractRegistry {
+        &self.registry
+    }
+
+    /// Interns a contract symbol, returning its id.
+    pub fn contract_id(&self, symbol: &str) -> ContractId {
+        self.registry.intern(symbol)
+    }
+
+    /// Registers a position, e.g. to set its settlement or contract size
+    /// before the first trade. Replaces any existing position.
+    pub fn add_position(&self, position: Position) {
+        let id = self.contract_id(position.symbol());
+        let _booking = self.booking.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut cache = self.cache();
+        let before = self.position_by_id(id);
+        let change = position.extended_greeks()
+            - before
+                .as_ref()
+                .map_or_else(ExtendedGreeks::zero, Position::extended_greeks);
+        let gross =
+            position.quantity().abs() - before.map_or(Decimal::ZERO, |p| p.quantity().abs());
+        let coordinates = self.contract_coordinates(id);
+        record_change(&mut cache, coordinates.as_deref(), change);
+        record_gross(&mut cache, coordinates.as_deref(), gross);
+        self.positions.insert(id, Mutex::new(position));
+    }
+
+    /// Returns a copy of a position.
+    #[must_use]
+    pub fn position(&self, symbol: &str) -> Option<Position> {
+        self.registry
+            .id_of(symbol)
+            .and_then(|id| self.position_by_id(id))
+    }
+
+    /// Returns a copy of the position in a contract, by id.
+    #[must_use]
+    pub fn position_by_id(&self, id: ContractId) -> Option<Position> {
+        self.positions
+            .get(&id)
+            .and_then(|e| e.value().lock().ok().map(|p| p.clone()))
+    }
+
+    /// Returns copies of all positions, sorted by symbol.
+    #[must_use]
+    pub fn positions(&self) -> Vec<Position> {
+        let mut positions: Vec<Position> = self
+            .positions
+            .iter()
+            .filter_map(|e| e.value().lock().ok().map(|p| p.clone()))
+            .collect();
+        positions.sort_by(|a, b| a.symbol().cmp(b.symbol()));
+        positions
+    }
+
+    /// Returns the number of positions.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns true if there are no positions.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Sets the Greeks of one long contract of a position.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContractNotFound` if there is no position in `symbol`.
+    pub fn set_greeks(&self, symbol: &str, greeks: Greeks) -> Result<()> {
+        let id = self
+            .registry
+            .id_of(symbol)
+            .ok_or_else(|| Error::contract_not_found(symbol))?;
+        self.set_greeks_by_id(id, greeks)
+    }
+
+    /// Sets the Greeks of one long contract of a position, by id.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContractNotFound` if there is no position in the
+    /// contract.
+    pub fn set_greeks_by_id(&self, id: ContractId, greeks: Greeks) -> Result<()> {
+        self.update_greeks(id, |position| position.set_greeks(greeks))
+            .map(|_| ())
+    }
+
+    /// Sets the Greeks of one long contract of a position with its cross
+    /// Greeks, e.g. from [`crate::pricing::PricingParams::extended_greeks`].
+    ///
+    /// The Greeks describe the market, so they are set even if the
+    /// aggregates then breach an extended Greek limit; the breach is
+    /// returned for the caller to report.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContractNotFound` if there is no position in `symbol`.
+    pub fn set_extended_greeks(
+        &self,
+        symbol: &str,
+        greeks: ExtendedGreeks,
+    ) -> Result<Option<PositionBreach>> {
+        let id = self
+            .registry
+            .id_of(symbol)
+            .ok_or_else(|| Error::contract_not_found(symbol))?;
+        self.set_extended_greeks_by_id(id, greeks)
+    }
+
+    /// Sets the Greeks of one long contract of a position with its cross
+    /// Greeks, by id.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContractNotFound` if there is no position in the
+    /// contract.
+    pub fn set_extended_greeks_by_id(
+        &self,
+        id: ContractId,
+        greeks: ExtendedGreeks,
+    ) -> Result<Option<PositionBreach>> {
+        self.update_greeks(id, |position| position.set_extended_greeks(greeks))
+    }
+
+    /// Calls `f` with every position, in contract id order, without
+    /// copying them.
+    pub fn for_each_position<F: FnMut(&Position)>(&self, mut f: F) {
+        for entry in self.positions.iter() {
+            if let Ok(position) = entry.value().lock() {
+                f(&position);
+            }
+        }
+    }
+
+    /// Folds the position Greeks of every position, in contract id order,
+    /// without collecting them first.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - Initial accumulator
+    /// * `f` - Combines the accumulator with a symbol and its position Greeks
+    pub fn fold_greeks<B, F: FnMut(B, &str, Greeks) -> B>(&self, init: B, mut f: F) -> B {
+        let mut acc = init;
+        for entry in self.positions.iter() {
+            if let Ok(position) = entry.value().lock() {
+                acc = f(acc, position.symbol(), position.greeks());
+            }
+        }
+        acc
+    }
+
+    /// Returns the Greeks aggregated across all positions.
+    ///
+    /// Maintained incrementally, so this does not visit the positions.
+    #[must_use]
+    pub fn total_greeks(&self) -> Greeks {
+        self.cache().total.greeks
+    }
+
+    /// Returns the Greeks aggregated across all positions with their cross
+    /// Greeks.
+    #[must_use]
+    pub fn total_extended_greeks(&self) -> ExtendedGreeks {
+        self.cache().total
+    }
+
+    /// Returns the Greeks aggregated across the positions of one
+    /// expiration, re-summing it first if it is dirty.
+    #[must_use]
+    pub fn expiration_greeks(&self, expiration: &str) -> Greeks {
+        self.expiration_extended_greeks(expiration).greeks
+    }
+
+    /// Returns the Greeks aggregated across the positions of one
+    /// expiration with their cross Greeks.
+    #[must_use]
+    pub fn expiration_extended_greeks(&self, expiration: &str) -> ExtendedGreeks {
+        let mut cache = self.cache();
+        self.expiration_aggregate(&mut cache, expiration)
+    }
+
+    /// Re-sums the aggregate Greeks and gross positions from every
+    /// position, discarding any rounding drift of the incremental updates.
+    pub fn rebuild_greeks(&self) {
+        let mut cache = self.cache();
+        let mut rebuilt = GreeksCache::default();
+        self.for_each_entry(|id, position| {
+            let greeks = position.extended_greeks();
+            rebuilt.total += greeks;
+            if let Some(coordinates) = self.contract_coordinates(id) {
+                *rebuilt
+                    .by_expiration
+                    .entry(coordinates.expiration.clone())
+                    .or_insert_with(ExtendedGreeks::zero) += greeks;
+                rebuilt.add_gross(&coordinates, position.quantity().abs());
+            }
+        });
+        *cache = rebuilt;
+    }
+
+    /// Registers the chain coordinates of a contract, overriding those
+    /// parsed from its symbol.
+    pub fn register_contract(&self, symbol: impl Into<String>, coordinates: ChainCoordinates) {
+        let symbol = symbol.into();
+        let id = self.contract_id(&symbol);
+        let previous = self.contract_coordinates(id);
+        let mut cache = self.cache();
+        let gross = self
+            .position_by_id(id)
+            .map_or(Decimal::ZERO, |p| p.quantity().abs());
+        if let Some(previous) = previous {
+            cache.add_gross(&previous, -gross);
+            cache.dirty.insert(previous.expiration.clone());
+        }
+        cache.add_gross(&coordinates, gross);
+        cache.dirty.insert(coordinates.expiration.clone());
+        self.coordinates.insert(id, Arc::new(coordinates));
+    }
+
+    /// Returns the chain coordinates of a contract: registered ones, or
+    /// else those parsed from its symbol.
+    #[must_use]
+    pub fn coordinates(&self, symbol: &str) -> Option<ChainCoordinates> {
+        match self.registry.id_of(symbol) {
+            Some(id) => self
+                .contract_coordinates(id)
+                .map(|coordinates| (*coordinates).clone()),
+            None => ChainCoordinates::parse(symbol),
+        }
+    }
+
+    /// Returns position Greeks per expiration and strike.
+    #[must_use]
+    pub fn greeks_by_strike(&self) -> BTreeMap<(String, u64), Greeks> {
+        self.greeks_by(|c| (c.expiration.clone(), c.strike))
+    }
+
+    /// Returns position Greeks per expiration, from the incrementally
+    /// maintained aggregates.
+    #[must_use]
+    pub fn greeks_by_expiration(&self) -> BTreeMap<String, Greeks> {
+        let mut cache = self.cache();
+        if !cache.dirty.is_empty() {
+            self.resum_dirty(&mut cache);
+        }
+        cache
+            .by_expiration
+            .iter()
+            .map(|(expiration, greeks)| (expiration.clone(), greeks.greeks))
+            .collect()
+    }
+
+    /// Returns position Greeks of calls and of puts.
+    #[must_use]
+    pub fn greeks_by_option_type(&self) -> BTreeMap<OptionStyle, Greeks> {
+        self.greeks_by(|c| c.style)
+    }
+
+    /// Sums position Greeks by a key of the contracts' coordinates.
+    fn greeks_by<K: Ord, F: Fn(&ChainCoordinates) -> K>(&self, key: F) -> BTreeMap<K, Greeks> {
+        let mut acc = BTreeMap::new();
+        self.for_each_entry(|id, position| {
+            if let Some(coordinates) = self.contract_coordinates(id) {
+                *acc.entry(key(&coordinates)).or_insert_with(Greeks::zero) += position.greeks();
+            }
+        });
+        acc
+    }
+
+    /// Records an option trade.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - Option contract symbol
+    /// * `quantity` - Signed quantity (positive buys)
+    /// * `price` - Trade price
+    ///
+    /// Returns the P&L realized by the trade.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InventoryLimitExceeded` if the resulting position
+    /// breaches the per-option, per-strike, per-expiration or
+    /// per-underlying limit or an extended Greek limit, or
+    /// `Error::ValidationError` if the price is negative.
+    pub fn record_trade(&self, symbol: &str, quantity: Decimal, price: Decimal) -> Result<Decimal> {
+        self.record_trade_by_id(self.contract_id(symbol), quantity, price)
+    }
+
+    /// Records an option trade in a contract, by id.
+    ///
+    /// Returns the P&L realized by the trade.
+    ///
+    /// # Errors
+    ///
+    /// As [`Self::record_trade`], and `Error::ContractNotFound` if the id
+    /// was not interned by this manager's registry.
+    pub fn record_trade_by_id(
+        &self,
+        id: ContractId,
+        quantity: Decimal,
+        price: Decimal,
+    ) -> Result<Decimal> {
+        let _booking = self.booking()?;
+        self.check_limits(&[(id, quantity)])?;
+        self.apply(id, quantity, price, None)
+    }
+
+    /// Checks a prospective trade against every limit without booking it,
+    /// e.g. a full fill of a quote side before the quote is sent.
+    ///
+    /// A trade in a contract without a position adds no Greeks.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InventoryLimitExceeded` for the first limit the
+    /// trade would breach, as [`Self::record_trade`] would.
+    pub fn check_trade(&self, symbol: &str, quantity: Decimal) -> Result<()> {
+        self.check_limits(&[(self.contract_id(symbol), quantity)])
+    }
+
+    /// Records an option trade made by a strategy.
+    ///
+    /// The trade is booked into the aggregate position, as by
+    /// [`Self::record_trade`], and into the strategy's own position in the
+    /// contract, which starts with the aggregate position's Greeks,
+    /// settlement and contract size.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - Strategy tag, e.g. `"mm"`, `"hedge"` or `"rfq"`
+    /// * `symbol` - Option contract symbol
+    /// * `quantity` - Signed quantity (positive buys)
+    /// * `price` - Trade price
+    ///
+    /// Returns the P&L realized by the trade on the aggregate position; the
+    /// strategy's realized P&L is in its [`StrategyReport`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InventoryLimitExceeded` if the resulting aggregate
+    /// position breaches a limit, or `Error::ValidationError` if the price
+    /// is negative. Nothing is booked on error.
+    pub fn record_strategy_trade(
+        &self,
+        strategy: &str,
+        symbol: &str,
+        quantity: Decimal,
+        price: Decimal,
+    ) -> Result<Decimal> {
+        let id = self.contract_id(symbol);
+        let _booking = self.booking()?;
+        self.check_limits(&[(id, quantity)])?;
+        let realized = self.apply(id, quantity, price, None)?;
+        self.apply_strategy(strategy, id, quantity, price)?;
+        Ok(realized)
+    }
+
+    /// Records an execution that has already happened, e.g. a fill of one
+    /// of our orders, under its strategy tag if any.
+    ///
+    /// Unlike [`Self::record_trade`], the execution is booked even if it
+    /// breaches a position limit, so inventory stays in step with the
+    /// venue; the breach is returned for the caller to report.
+    ///
+    /// Returns the P&L realized on the aggregate position, and the limit
+    /// the execution breached, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the price is negative.
+    pub fn record_execution(
+        &self,
+        strategy: Option<&str>,
+        symbol: &str,
+        quantity: Decimal,
+        price: Decimal,
+    ) -> Result<(Decimal, Option<PositionBreach>)> {
+        let id = self.contract_id(symbol);
+        let _booking = self.booking()?;
+        let breach = self.find_breach(&[(id, quantity)]);
+        let realized = self.apply(id, quantity, price, None)?;
+        if let Some(strategy) = strategy {
+            self.apply_strategy(strategy, id, quantity, price)?;
+        }
+        Ok((realized, breach))
+    }
+
+    /// Returns the strategy tags with booked trades, sorted.
+    #[must_use]
+    pub fn strategy_tags(&self) -> Vec<String> {
+        self.strategies().keys().cloned().collect()
+    }
+
+    /// Returns a copy of a strategy's position in a contract.
+    #[must_use]
+    pub fn strategy_position(&self, strategy: &str, symbol: &str) -> Option<Position> {
+        let id = self.registry.id_of(symbol)?;
+        self.strategies()
+            .get(strategy)
+            .and_then(|positions| positions.get(&id))
+            .cloned()
+    }
+
+    /// Returns the positions and P&L of a strategy.
+    #[must_use]
+    pub fn strategy_report(&self, strategy: &str) -> Option<StrategyReport> {
+        self.strategies()
+            .get(strategy)
+            .map(|positions| StrategyReport::new(strategy, positions.values().cloned().collect()))
+    }
+
+    /// Returns the report of every strategy, sorted by tag.
+    #[must_use]
+    pub fn strategy_reports(&self) -> Vec<StrategyReport> {
+        self.strategies()
+            .iter()
+            .map(|(strategy, positions)| {
+                StrategyReport::new(strategy.as_str(), positions.values().cloned().collect())
+            })
+            .collect()
+    }
+
+    /// Records a tied trade: the option fill and its hedge leg at the
+    /// reference price, as one package.
+    ///
+    /// A hedge position created by this call gets a delta of one per unit.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the trade is invalid, or
+    /// `Error::InventoryLimitExceeded` if the option leg breaches a
+    /// position limit. Neither leg is booked on error.
+    pub fn record_tied_trade(&self, trade: &TiedTrade) -> Result<TiedFill> {
+        trade.validate()?;
+        let option = self.contract_id(&trade.option_symbol);
+        let hedge = self.contract_id(&trade.hedge_symbol);
+        let _booking = self.booking()?;
+        self.check_limits(&[(option, trade.option_quantity)])?;
+
+        let option_realized =
+            self.apply(option, trade.option_quantity, trade.option_price, None)?;
+        let hedge_greeks = Greeks {
+            delta: Decimal::ONE,
+            ..Greeks::zero()
+        };
+        let hedge_realized = self.apply(
+            hedge,
+            trade.hedge_quantity,
+            trade.reference_price,
+            Some(hedge_greeks),
+        )?;
+        Ok(TiedFill {
+            option_realized,
+            hedge_realized,
+        })
+    }
+
+    /// Records a combo fill on its legs, as one package.
+    ///
+    /// No position is kept for the combo itself.
+    ///
+    /// Returns the P&L realized across the legs.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the fill is invalid, or
+    /// `Error::InventoryLimitExceeded` if the legs breach a position
+    /// limit. No leg is booked on error.
+    pub fn record_combo_fill(&self, fill: &ComboFill) -> Result<Decimal> {
+        fill.validate()?;
+        let ids: Vec<ContractId> = fill
+            .legs
+            .iter()
+            .map(|leg| self.contract_id(&leg.symbol))
+            .collect();
+        let _booking = self.booking()?;
+        let legs: Vec<(ContractId, Decimal)> = ids
+            .iter()
+            .zip(&fill.legs)
+            .map(|(&id, leg)| (id, leg.quantity))
+            .collect();
+        self.check_limits(&legs)?;
+        let mut realized = Decimal::ZERO;
+        for (&id, leg) in ids.iter().zip(&fill.legs) {
+            realized += self.apply(id, leg.quantity, leg.price, None)?;
+        }
+        Ok(realized)
+    }
+
+    /// Records a delivery of the underlying from an exercise or assignment.
+    ///
+    /// Deliveries are not optional, so they are not checked against the
+    /// position limits. A position created by this call gets a delta of one
+    /// per unit, like a hedge leg.
+    ///
+    /// Returns the P&L realized on the delivery symbol.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the price is negative.
+    pub fn record_delivery(
+        &self,
+        symbol: &str,
+        quantity: Decimal,
+        price: Decimal,
+    ) -> Result<Decimal> {
+        let id = self.contract_id(symbol);
+        let _booking = self.booking()?;
+        let greeks = Greeks {
+            delta: Decimal::ONE,
+            ..Greeks::zero()
+        };
+        self.apply(id, quantity, price, Some(greeks))
+    }
+
+    /// Moves a hedge position to a new benchmark instrument, e.g. from the
+    /// June to the September future on a reference roll.
+    ///
+    /// Closes the whole `from` position at `from_price` and opens the same
+    /// quantity in `to` at `to_price` as one package. A `to` position
+    /// created by this call inherits the per-contract Greeks, settlement
+    /// and contract size of `from`.
+    ///
+    /// Returns the P&L realized on the closed leg.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContractNotFound` if there is no `from` position, or
+    /// `Error::ValidationError` if either price is negative. Neither leg is
+    /// booked on error.
+    pub fn roll_position(
+        &self,
+        from: &str,
+        to: &str,
+        from_price: Decimal,
+        to_price: Decimal,
+    ) -> Result<Decimal> {
+        if from_price < Decimal::ZERO || to_price < Decimal::ZERO {
+            return Err(Error::validation("roll prices must be non-negative"));
+        }
+        let source_id = self
+            .registry
+            .id_of(from)
+            .ok_or_else(|| Error::contract_not_found(from))?;
+        let target_id = self.contract_id(to);
+        let _booking = self.booking()?;
+        let source = self
+            .position_by_id(source_id)
+            .ok_or_else(|| Error::contract_not_found(from))?;
+        let quantity = source.quantity();
+        let realized = self.apply(source_id, -quantity, from_price, None)?;
+        self.positions.get_or_insert_with(target_id, || {
+            let mut position = Position::new(to)
+                .with_settlement(source.settlement())
+                .with_contract_size(source.contract_size());
+            position.set_extended_greeks(source.unit_extended_greeks());
+            Mutex::new(position)
+        });
+        self.apply(target_id, quantity, to_price, None)?;
+        Ok(realized)
+    }
+
+    /// Checks every limit level for a prospective package of option legs.
+    ///
+    /// The per-strike, per-expiration and per-underlying limits cap the
+    /// gross position of the contracts with chain coordinates. A level is
+    /// only checked if the package grows it, so trades that reduce a
+    /// position are always accepted. The extended Greek limits are checked
+    /// per expiration and in total, from the unit Greeks of the legs'
+    /// positions.
+    fn check_limits(&self, legs: &[(ContractId, Decimal)]) -> Result<()> {
+        match self.find_breach(legs) {
+            Some(breach) => Err(breach.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the first limit a prospective package of legs breaches.
+    fn find_breach(&self, legs: &[(ContractId, Decimal)]) -> Option<PositionBreach> {
+        let mut package: BTreeMap<ContractId, Decimal> = BTreeMap::new();
+        for &(id, quantity) in legs {
+            *package.entry(id).or_default() += quantity;
+        }
+        let mut by_strike: BTreeMap<(String, u64), Decimal> = BTreeMap::new();
+        let mut by_expiration: BTreeMap<String, Decimal> = BTreeMap::new();
+        let mut underlying = Decimal::ZERO;
+        let mut greeks_by_expiration: BTreeMap<String, ExtendedGreeks> = BTreeMap::new();
+        let mut greeks = ExtendedGreeks::zero();
+        for (id, quantity) in package {
+            let position = self.position_by_id(id);
+            let current = position.as_ref().map_or(Decimal::ZERO, Position::quantity);
+            let change_greeks = position.as_ref().map_or_else(ExtendedGreeks::zero, |p| {
+                p.unit_extended_greeks()
+                    .scaled(quantity * p.contract_size())
+            });
+            greeks += change_greeks;
+            let resulting = (current + quantity).abs();
+            let change = resulting - current.abs();
+            if change > Decimal::ZERO && resulting > self.limits.per_option {
+                return Some(PositionBreach {
+                    level: "per_option",
+                    limit: self.limits.per_option,
+                    current: resulting,
+                });
+            }
+            if let Some(coordinates) = self.contract_coordinates(id) {
+                *by_strike
+                    .entry((coordinates.expiration.clone(), coordinates.strike))
+                    .or_default() += change;
+                *by_expiration
+                    .entry(coordinates.expiration.clone())
+                    .or_default() += change;
+                *greeks_by_expiration
+                    .entry(coordinates.expiration.clone())
+                    .or_insert_with(ExtendedGreeks::zero) += change_greeks;
+                underlying += change;
+            }
+        }
+
+        let mut cache = self.cache();
+        for (key, change) in by_strike {
+            let resulting = cache.gross_by_strike.get(&key).copied().unwrap_or_default() + change;
+            if change > Decimal::ZERO && resulting > self.limits.per_strike {
+                return Some(PositionBreach {
+                    level: "per_strike",
+                    limit: self.limits.per_strike,
+                    current: resulting,
+                });
+            }
+        }
+        for (expiration, change) in by_expiration {
+            let resulting = cache
+                .gross_by_expiration
+                .get(&expiration)
+                .copied()
+                .unwrap_or_default()
+                + change;
+            if change > Decimal::ZERO && resulting > self.limits.per_expiration {
+                return Some(PositionBreach {
+                    level: "per_expiration",
+                    limit: self.limits.per_expiration,
+                    current: resulting,
+                });
+            }
+        }
+        let resulting = cache.gross_underlying + underlying;
+        if underlying > Decimal::ZERO && resulting > self.limits.per_underlying {
+            return Some(PositionBreach {
+                level: "per_underlying",
+                limit: self.limits.per_underlying,
+                current: resulting,
+            });
+        }
+        if self.limits.extended_greeks == ExtendedGreekLimits::default() {
+            return None;
+        }
+        for (expiration, change) in greeks_by_expiration {
+            let before = self.expiration_aggregate(&mut cache, &expiration);
+            let breach = self
+                .limits
+                .extended_greek_breach(&before, &(before + change));
+            if breach.is_some() {
+                return breach;
+            }
+        }
+        self.limits
+            .extended_greek_breach(&cache.total, &(cache.total + greeks))
+    }
+
+    /// Applies a change to a position's unit Greeks, keeping the
+    /// aggregates and the strategy positions in step.
+    ///
+    /// Returns the extended Greek limit the aggregates breach after the
+    /// change, if any.
+    fn update_greeks<F: Fn(&mut Position)>(
+        &self,
+        id: ContractId,
+        update: F,
+    ) -> Result<Option<PositionBreach>> {
+        let entry = self.positions.get(&id).ok_or_else(|| self.not_found(id))?;
+        let coordinates = self.contract_coordinates(id);
+        let mut cache = self.cache();
+        let expiration = coordinates.as_ref().map(|c| c.expiration.as_str());
+        let before_expiration =
+            expiration.map(|expiration| self.expiration_aggregate(&mut cache, expiration));
+        let before_total = cache.total;
+        let mut position = lock(entry.value())?;
+        let before = position.extended_greeks();
+        update(&mut position);
+        let change = position.extended_greeks() - before;
+        drop(position);
+        record_change(&mut cache, coordinates.as_deref(), change);
+        let breach = before_expiration
+            .and_then(|before| {
+                self.limits
+                    .extended_greek_breach(&before, &(before + change))
+            })
+            .or_else(|| {
+                self.limits
+                    .extended_greek_breach(&before_total, &cache.total)
+            });
+        drop(cache);
+        for positions in self.strategies().values_mut() {
+            if let Some(position) = positions.get_mut(&id) {
+                update(position);
+            }
+        }
+        Ok(breach)
+    }
+
+    /// Applies a fill to a strategy's position, creating it from the
+    /// aggregate position if needed.
+    fn apply_strategy(
+        &self,
+        strategy: &str,
+        id: ContractId,
+        quantity: Decimal,
+        price: Decimal,
+    ) -> Result<Decimal> {
+        let aggregate = self.position_by_id(id).ok_or_else(|| self.not_found(id))?;
+        self.strategies()
+            .entry(strategy.to_string())
+            .or_default()
+            .entry(id)
+            .or_insert_with(|| {
+                let mut position = Position::new(aggregate.symbol())
+                    .with_settlement(aggregate.settlement())
+                    .with_contract_size(aggregate.contract_size());
+                position.set_extended_greeks(aggregate.unit_extended_greeks());
+                position
+            })
+            .apply_fill(quantity, price)
+    }
+
+    /// Applies a fill, creating the position if needed.
+    fn apply(
+        &self,
+        id: ContractId,
+        quantity: Decimal,
+        price: Decimal,
+        new_greeks: Option<Greeks>,
+    ) -> Result<Decimal> {
+        let entry = match self.positions.get(&id) {
+            Some(entry) => entry,
+            None => {
+                let symbol = self
+                    .registry
+                    .symbol_of(id)
+                    .ok_or_else(|| self.not_found(id))?;
+                self.positions.get_or_insert_with(id, || {
+                    let mut position = Position::new(symbol);
+                    if let Some(greeks) = new_greeks {
+                        position.set_greeks(greeks);
+                    }
+                    Mutex::new(position)
+                })
+            }
+        };
+        let coordinates = self.contract_coordinates(id);
+        let mut cache = self.cache();
+        let mut position = lock(entry.value())?;
+        let before = position.extended_greeks();
+        let gross = position.quantity().abs();
+        let realized = position.apply_fill(quantity, price)?;
+        record_change(
+            &mut cache,
+            coordinates.as_deref(),
+            position.extended_greeks() - before,
+        );
+        record_gross(
+            &mut cache,
+            coordinates.as_deref(),
+            position.quantity().abs() - gross,
+        );
+        Ok(realized)
+    }
+
+    /// Returns the chain coordinates of a contract by id: registered ones,
+    /// or else those parsed from its symbol, which are remembered so the
+    /// symbol is parsed once. Shared, so booking a fill does not copy them.
+    fn contract_coordinates(&self, id: ContractId) -> Option<Arc<ChainCoordinates>> {
+        if let Some(entry) = self.coordinates.get(&id) {
+            return Some(Arc::clone(entry.value()));
+        }
+        let parsed = ChainCoordinates::parse(&self.registry.symbol_of(id)?)?;
+        Some(Arc::clone(
+            self.coordinates.get_or_insert(id, Arc::new(parsed)).value(),
+        ))
+    }
+
+    /// Returns the error for a contract without a position, naming its
+    /// symbol if it is interned.
+    fn not_found(&self, id: ContractId) -> Error {
+        Error::contract_not_found(
+            self.registry
+                .symbol_of(id)
+                .unwrap_or_else(|| id.to_string()),
+        )
+    }
+
+    /// Calls `f` with every position and its contract id.
+    fn for_each_entry<F: FnMut(ContractId, &Position)>(&self, mut f: F) {
+        for entry in self.positions.iter() {
+            if let Ok(position) = entry.value().lock() {
+                f(*entry.key(), &position);
+            }
+        }
+    }
+
+    /// Returns the aggregate Greeks of one expiration, re-summing the
+    /// dirty expirations first if it is one of them.
+    fn expiration_aggregate(&self, cache: &mut GreeksCache, expiration: &str) -> ExtendedGreeks {
+        if cache.dirty.contains(expiration) {
+            self.resum_dirty(cache);
+        }
+        cache
+            .by_expiration
+            .get(expiration)
+            .copied()
+            .unwrap_or_else(ExtendedGreeks::zero)
+    }
+
+    /// Re-sums the dirty expirations' aggregates.
+    fn resum_dirty(&self, cache: &mut GreeksCache) {
+        let dirty = std::mem::take(&mut cache.dirty);
+        for expiration in &dirty {
+            cache.by_expiration.remove(expiration);
+        }
+        self.for_each_entry(|id, position| {
+            if let Some(coordinates) = self.contract_coordinates(id)
+                && dirty.contains(&coordinates.expiration)
+            {
+                *cache
+                    .by_expiration
+                    .entry(coordinates.expiration.clone())
+                    .or_insert_with(ExtendedGreeks::zero) += position.extended_greeks();
+            }
+        });
+    }
+
+    /// Locks the aggregate Greeks.
+    fn cache(&self) -> MutexGuard<'_, GreeksCache> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn strategies(&self) -> MutexGuard<'_, BTreeMap<String, BTreeMap<ContractId, Position>>> {
+        self.strategies
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Takes the booking lock.
+    fn booking(&self) -> Result<std::sync::MutexGuard<'_, ()>> {
+        self.booking
+            .lock()
+            .map_err(|_| Error::validation("inventory booking lock poisoned"))
+    }
+}
+
+/// Adds the change in a position's Greeks to the aggregates.
+///
+/// Position updates hold the cache lock across the update, taken before
+/// the position's lock as re-summing does, so every change is counted
+/// exactly once.
+fn record_change(
+    cache: &mut GreeksCache,
+    coordinates: Option<&ChainCoordinates>,
+    change: ExtendedGreeks,
+) {
+    cache.total += change;
+    if let Some(coordinates) = coordinates
+        && !cache.dirty.contains(&coordinates.expiration)
+    {
+        *cache
+            .by_expiration
+            .entry(coordinates.expiration.clone())
+            .or_insert_with(ExtendedGreeks::zero) += change;
+    }
+}
+
+/// Adds the change in a position's gross quantity to the aggregates of
+/// its chain coordinates.
+fn record_gross(cache: &mut GreeksCache, coordinates: Option<&ChainCoordinates>, change: Decimal) {
+    if change.is_zero() {
+        return;
+    }
+    if let Some(coordinates) = coordinates {
+        cache.add_gross(coordinates, change);
+    }
+}
+
+/// Locks a position.
+fn lock(position: &Mutex<Position>) -> Result<std::sync::MutexGuard<'_, Position>> {
+    position
+        .lock()
+        .map_err(|_| Error::validation("position lock poisoned"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn manager() -> InventoryManager {
+        let limits = PositionLimits {
+            per_option: dec!(20),
+            ..PositionLimits::default()
+        };
+        InventoryManager::new("SPX", limits).unwrap()
+    }
+
+    fn tied(quantity: Decimal) -> TiedTrade {
+        TiedTrade::new(
+            "T1",
+            "SPX-C-5000",
+            quantity,
+            dec!(42),
+            dec!(0.3),
+            "SPX-FUT",
+            dec!(5010),
+        )
+    }
+
+    #[test]
+    fn test_greeks_by_chain_coordinates() {
+        let manager = manager();
+        let unit = Greeks::new(dec!(0.5), dec!(0.01), dec!(-1), dec!(4), Decimal::ZERO);
+        for (symbol, quantity) in [
+            ("SPX-20250620-5000-C", dec!(10)),
+            ("SPX-20250620-5000-P", dec!(-4)),
+            ("SPX-20250620-5100-C", dec!(2)),
+            ("SPX-20250919-5000-C", dec!(1)),
+            ("custom-leg", dec!(3)),
+        ] {
+            manager.record_trade(symbol, quantity, dec!(10)).unwrap();
+            manager.set_greeks(symbol, unit).unwrap();
+        }
+        manager.register_contract(
+            "custom-leg",
+            ChainCoordinates::new("20250919", 5000, OptionStyle::Put),
+        );
+
+        let by_strike = manager.greeks_by_strike();
+        assert_eq!(by_strike.len(), 3);
+        assert_eq!(by_strike[&("20250620".to_string(), 5000)].vega, dec!(24));
+        assert_eq!(manager.greeks_by_expiration()["20250919"].gamma, dec!(0.04));
+        let by_type = manager.greeks_by_option_type();
+        assert_eq!(by_type[&OptionStyle::Call].delta, dec!(6.5));
+        assert_eq!(by_type[&OptionStyle::Put].delta, dec!(-0.5));
+        assert!(manager.coordinates("SPX-FUT").is_none());
+    }
+
+    #[test]
+    fn test_incremental_greeks_follow_changes() {
+        let manager = manager();
+        let unit = Greeks::new(dec!(0.5), dec!(0.01), dec!(-1), dec!(4), Decimal::ZERO);
+        manager
+            .record_trade("SPX-20250620-5000-C", dec!(10), dec!(10))
+            .unwrap();
+        manager.set_greeks("SPX-20250620-5000-C", unit).unwrap();
+        manager.record_trade("moved", dec!(2), dec!(10)).unwrap();
+        manager.set_greeks("moved", unit).unwrap();
+        manager
+            .record_trade("SPX-20250620-5000-C", dec!(-4), dec!(12))
+            .unwrap();
+        assert_eq!(manager.total_greeks().delta, dec!(4));
+        assert_eq!(manager.expiration_greeks("20250620").delta, dec!(3));
+
+        // Coordinates registered after the trades move the contract into
+        // the expiration's aggregate.
+        manager.register_contract(
+            "moved",
+            ChainCoordinates::new("20250620", 5100, OptionStyle::Call),
+        );
+        assert_eq!(manager.expiration_greeks("20250620").delta, dec!(4));
+        manager.set_greeks("moved", Greeks::zero()).unwrap();
+        assert_eq!(manager.expiration_greeks("20250620").delta, dec!(3));
+
+        let total = manager.fold_greeks(Greeks::zero(), |acc, _, g| acc + g);
+        assert_eq!(manager.total_greeks(), total);
+        manager.rebuild_greeks();
+        assert_eq!(manager.total_greeks(), total);
+        assert_eq!(manager.greeks_by_expiration().len(), 1);
+    }
+
+    #[test]
+    fn test_aggregate_limits() {
+        let limits = PositionLimits {
+            per_option: dec!(20),
+            per_strike: dec!(25),
+            per_expiration: dec!(40),
+            per_underlying: dec!(50),
+            ..PositionLimits::default()
+        };
+        let manager = InventoryManager::new("SPX", limits).unwrap();
+        manager
+            .record_trade("SPX-20250620-5000-C", dec!(20), dec!(1))
+            .unwrap();
+        // Short puts do not offset long calls at the same strike.
+        let err = manager
+            .record_trade("SPX-20250620-5000-P", dec!(-10), dec!(1))
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::InventoryLimitExceeded { ref limit_type, ref current, .. } if limit_type == "per_strike" && *current == dec!(30))
+        );
+        manager
+            .record_trade("SPX-20250620-5000-P", dec!(-5), dec!(1))
+            .unwrap();
+
+        let err = manager
+            .record_trade("SPX-20250620-5100-C", dec!(20), dec!(1))
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::InventoryLimitExceeded { ref limit_type, .. } if limit_type == "per_expiration")
+        );
+        manager
+            .record_trade("SPX-20250620-5100-C", dec!(15), dec!(1))
+            .unwrap();
+        // Reducing a position is accepted and frees room at every level.
+        manager
+            .record_trade("SPX-20250620-5000-C", dec!(-5), dec!(1))
+            .unwrap();
+
+        manager
+            .record_trade("SPX-20250919-5000-C", dec!(15), dec!(1))
+            .unwrap();
+        let err = manager
+            .record_trade("SPX-20250919-5100-C", dec!(1), dec!(1))
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::InventoryLimitExceeded { ref limit_type, .. } if limit_type == "per_underlying")
+        );
+        assert!(manager.position("SPX-20250919-5100-C").is_none());
+    }
+
+    #[test]
+    fn test_invalid_limits() {
+        let limits = PositionLimits {
+            per_strike: Decimal::ZERO,
+            ..PositionLimits::default()
+        };
+        assert!(InventoryManager::new("SPX", limits).is_err());
+    }
+
+    #[test]
+    fn test_record_trade_and_limit() {
+        let manager = manager();
+        manager
+            .record_trade("SPX-C-5000", dec!(15), dec!(40))
+            .unwrap();
+
+        let err = manager
+            .record_trade("SPX-C-5000", dec!(10), dec!(40))
+            .unwrap_err();
+        assert!(matches!(err, Error::InventoryLimitExceeded { .. }));
+        assert_eq!(manager.position("SPX-C-5000").unwrap().quantity(), dec!(15));
+
+        let realized = manager
+            .record_trade("SPX-C-5000", dec!(-5), dec!(44))
+            .unwrap();
+        assert_eq!(realized, dec!(20));
+    }
+
+    #[test]
+    fn test_extended_greek_limits() {
+        use crate::inventory::ExtendedGreekLimits;
+
+        let limits = PositionLimits {
+            extended_greeks: ExtendedGreekLimits {
+                max_vanna: Some(dec!(10)),
+                ..ExtendedGreekLimits::default()
+            },
+            ..PositionLimits::default()
+        };
+        let manager = InventoryManager::new("SPX", limits).unwrap();
+        let (near, far) = ("SPX-20250620-5000-C", "SPX-20250919-5000-C");
+        let mut unit = ExtendedGreeks::from(Greeks::zero());
+        unit.vanna = dec!(1);
+        for symbol in [near, far] {
+            manager.record_trade(symbol, dec!(1), dec!(10)).unwrap();
+            assert_eq!(manager.set_extended_greeks(symbol, unit).unwrap(), None);
+        }
+        manager.record_trade(near, dec!(7), dec!(10)).unwrap();
+        assert_eq!(
+            manager.expiration_extended_greeks("20250620").vanna,
+            dec!(8)
+        );
+        assert_eq!(manager.total_extended_greeks().vanna, dec!(9));
+
+        // A quote side whose fill takes the total past the limit is refused
+        // before it is sent, and the fill itself is rejected.
+        assert!(manager.check_trade(far, dec!(1)).is_ok());
+        assert!(matches!(
+            manager.check_trade(far, dec!(2)),
+            Err(Error::InventoryLimitExceeded { limit_type, .. }) if limit_type == "vanna"
+        ));
+        assert!(manager.record_trade(far, dec!(2), dec!(10)).is_err());
+        assert_eq!(manager.position(far).unwrap().quantity(), dec!(1));
+        // A fill reducing the exposure is accepted.
+        assert!(manager.record_trade(far, dec!(-1), dec!(10)).is_ok());
+
+        // Greeks are set even when they breach; the breach is reported.
+        unit.vanna = dec!(2);
+        let breach = manager.set_extended_greeks(near, unit).unwrap().unwrap();
+        assert_eq!((breach.level, breach.current), ("vanna", dec!(16)));
+        assert_eq!(manager.total_extended_greeks().vanna, dec!(16));
+        // First-order updates keep the cross Greeks in the aggregates.
+        manager.set_greeks(near, Greeks::zero()).unwrap();
+        assert_eq!(manager.total_extended_greeks().vanna, dec!(16));
+        manager.rebuild_greeks();
+        assert_eq!(manager.total_extended_greeks().vanna, dec!(16));
+    }
+
+    #[test]
+    fn test_tied_trade_books_both_legs() {
+        let manager = manager();
+        manager.record_tied_trade(&tied(dec!(10))).unwrap();
+        manager
+            .set_greeks(
+                "SPX-C-5000",
+                Greeks::new(dec!(0.3), dec!(0.01), dec!(-2), dec!(5), Decimal::ZERO),
+            )
+            .unwrap();
+
+        let hedge = manager.position("SPX-FUT").unwrap();
+        assert_eq!(hedge.quantity(), dec!(-3));
+        assert_eq!(hedge.average_price(), dec!(5010));
+
+        let greeks = manager.total_greeks();
+        assert_eq!(greeks.delta, Decimal::ZERO);
+        assert_eq!(greeks.gamma, dec!(0.1));
+    }
+
+    #[test]
+    fn test_combo_fill_books_legs_only() {
+        use crate::inventory::ComboLegFill;
+
+        let manager = manager();
+        let leg = |symbol: &str, quantity, price| ComboLegFill {
+            symbol: symbol.to_string(),
+            quantity,
+            price,
+        };
+        let fill = ComboFill {
+            fill_id: "F1".to_string(),
+            combo_symbol: "SPX-STRADDLE-5000".to_string(),
+            quantity: dec!(-5),
+            price: dec!(100),
+            legs: vec![
+                leg("SPX-C-5000", dec!(-5), dec!(55)),
+                leg("SPX-P-5000", dec!(-5), dec!(45)),
+            ],
+        };
+        manager.record_combo_fill(&fill).unwrap();
+        assert_eq!(manager.len(), 2);
+        assert!(manager.position("SPX-STRADDLE-5000").is_none());
+        assert_eq!(fill.leg_premium(), dec!(-500));
+
+        // A second fill would breach the per-option limit on both legs.
+        let mut big = fill.clone();
+        for leg in &mut big.legs {
+            leg.quantity = dec!(-16);
+        }
+        assert!(manager.record_combo_fill(&big).is_err());
+        assert_eq!(manager.position("SPX-P-5000").unwrap().quantity(), dec!(-5));
+    }
+
+    #[test]
+    fn test_rejected_tied_trade_books_nothing() {
+        let manager = manager();
+        assert!(manager.record_tied_trade(&tied(dec!(25))).is_err());
+        assert!(manager.is_empty());
+    }
+
+    #[test]
+    fn test_tied_trade_realizes_package_pnl() {
+        let manager = manager();
+        manager.record_tied_trade(&tied(dec!(10))).unwrap();
+
+        let unwind = TiedTrade::new(
+            "T2",
+            "SPX-C-5000",
+            dec!(-10),
+            dec!(45),
+            dec!(0.3),
+            "SPX-FUT",
+            dec!(5020),
+        );
+        let fill = manager.record_tied_trade(&unwind).unwrap();
+
+        assert_eq!(fill.option_realized, dec!(30));
+        assert_eq!(fill.hedge_realized, dec!(-30));
+        assert_eq!(fill.realized(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_fold_greeks_matches_total() {
+        let manager = manager();
+        manager.record_tied_trade(&tied(dec!(10))).unwrap();
+        let total = manager.fold_greeks(Greeks::zero(), |acc, _, g| acc + g);
+        assert_eq!(total, manager.total_greeks());
+
+        let mut symbols = 0;
+        manager.for_each_position(|_| symbols += 1);
+        assert_eq!(symbols, 2);
+    }
+
+    #[test]
+    fn test_roll_position_migrates_hedge() {
+        let manager = manager();
+        manager.record_tied_trade(&tied(dec!(10))).unwrap();
+
+        let realized = manager
+            .roll_position("SPX-FUT", "SPX-FUT-SEP", dec!(5000), dec!(5030))
+            .unwrap();
+        assert_eq!(realized, dec!(30));
+        assert!(manager.position("SPX-FUT").unwrap().is_flat());
+        let rolled = manager.position("SPX-FUT-SEP").unwrap();
+        assert_eq!(rolled.quantity(), dec!(-3));
+        assert_eq!(rolled.greeks().delta, dec!(-3));
+        assert!(manager.roll_position("X", "Y", dec!(1), dec!(1)).is_err());
+    }
+
+    #[test]
+    fn test_set_greeks_unknown_symbol() {
+        assert!(manager().set_greeks("X", Greeks::zero()).is_err());
+    }
+
+    #[test]
+    fn test_strategy_positions_segregated() {
+        let manager = manager();
+        manager
+            .record_strategy_trade("mm", "SPX-C-5000", dec!(5), dec!(10))
+            .unwrap();
+        manager
+            .record_strategy_trade("rfq", "SPX-C-5000", dec!(-5), dec!(12))
+            .unwrap();
+        manager
+            .record_trade("SPX-P-5000", dec!(2), dec!(8))
+            .unwrap();
+        manager
+            .set_greeks(
+                "SPX-C-5000",
+                Greeks::new(dec!(0.5), dec!(0.01), dec!(-1), dec!(2), Decimal::ZERO),
+            )
+            .unwrap();
+
+        // The aggregate nets the two strategies and realizes their spread.
+        let aggregate = manager.position("SPX-C-5000").unwrap();
+        assert!(aggregate.is_flat());
+        assert_eq!(aggregate.realized_pnl(), dec!(10));
+        assert_eq!(manager.strategy_tags(), vec!["mm", "rfq"]);
+
+        let mm = manager.strategy_report("mm").unwrap();
+        assert_eq!(mm.open_positions(), 1);
+        assert_eq!(mm.realized_pnl, Decimal::ZERO);
+        assert_eq!(mm.greeks.delta, dec!(2.5));
+        assert_eq!(mm.total_pnl(|_| Some(dec!(11))), dec!(5));
+        let rfq = manager.strategy_position("rfq", "SPX-C-5000").unwrap();
+        assert_eq!(rfq.quantity(), dec!(-5));
+        assert!(manager.strategy_report("hedge").is_none());
+
+        // Strategy limits are the aggregate ones.
+        assert!(
+            manager
+                .record_strategy_trade("mm", "SPX-C-5000", dec!(21), dec!(10))
+                .is_err()
+        );
+        assert_eq!(manager.strategy_reports().len(), 2);
+    }
+
+    #[test]
+    fn test_positions_keyed_by_contract_id() {
+        let manager = manager();
+        let id = manager.contract_id("SPX-C-5000");
+
+        manager.record_trade_by_id(id, dec!(3), dec!(10)).unwrap();
+        manager
+            .record_trade("SPX-C-5000", dec!(2), dec!(10))
+            .unwrap();
+
+        // Both edges book the same position.
+        assert_eq!(manager.len(), 1);
+        assert_eq!(manager.position_by_id(id).unwrap().quantity(), dec!(5));
+        assert_eq!(manager.position("SPX-C-5000").unwrap().quantity(), dec!(5));
+        manager
+            .set_greeks_by_id(
+                id,
+                Greeks::new(
+                    dec!(0.5),
+                    Decimal::ZERO,
+                    Decimal::ZERO,
+                    Decimal::ZERO,
+                    Decimal::ZERO,
+                ),
+            )
+            .unwrap();
+        assert_eq!(manager.total_greeks().delta, dec!(2.5));
+
+        // Limits apply to id-based trades too.
+        assert!(manager.record_trade_by_id(id, dec!(16), dec!(10)).is_err());
+        // Ids the registry never assigned are rejected.
+        assert!(matches!(
+            manager.record_trade_by_id(ContractId::new(99), dec!(1), dec!(10)),
+            Err(Error::ContractNotFound { .. })
+        ));
+        assert!(manager.position("SPX-C-5100").is_none());
+    }
+
+    #[test]
+    fn test_registry_shared_with_order_books() {
+        use crate::orderbook::UnderlyingOrderBookManager;
+        use optionstratlib::prelude::{ExpirationDate, Positive};
+
+        let books = UnderlyingOrderBookManager::new();
+        let strike = books
+            .get_or_create("BTC")
+            .get_or_create_expiration(ExpirationDate::Days(Positive::THIRTY))
+            .get_or_create_strike(50000);
+        let (call, _put) = strike.contract_ids();
+        let call = call.unwrap();
+        let inventory =
+            InventoryManager::with_registry("BTC", PositionLimits::default(), books.registry_arc())
+                .unwrap();
+
+        inventory
+            .record_trade_by_id(call, dec!(1), dec!(100))
+            .unwrap();
+
+        let position = inventory.position(strike.call().symbol()).unwrap();
+        assert_eq!(position.symbol(), strike.call().symbol());
+        assert_eq!(inventory.contract_id(strike.call().symbol()), call);
+    }
+}
@@ -0,0 +1,41 @@
+//! Inventory module.
+//!
+//! This module provides position keeping for option and hedge contracts.
+//!
+//! ## Components
+//!
+//! - [`Position`]: Signed holding with average-cost P&L for linear and inverse contracts
+//! - [`InventoryManager`]: Positions of one underlying with limit checks and Greeks aggregation
+//! - [`PortfolioManager`]: Inventories of several underlyings with dollar Greeks, [`PortfolioLimits`] and beta-weighted delta
+//! - [`ChainCoordinates`]: Expiration, strike and style of a contract, for per-strike and per-expiration views
+//! - [`PositionLimits`]: Per-option, per-strike, per-expiration and per-underlying caps, with optional [`ExtendedGreekLimits`]
+//! - [`StrategyReport`]: Positions, realized P&L and Greeks of the trades booked under one strategy tag
+//! - [`TiedTrade`]: Option fill booked with its underlying hedge leg at an agreed delta
+//! - [`ComboFill`]: Fill on a listed combo instrument, booked on its legs only
+//! - [`SettlementLedger`]: Fills with trade and settlement dates, dated position views and their reconciliation
+//! - [`GreeksUpdater`]: Reprices the contracts affected by spot and surface updates and pushes their Greeks
+
+mod combo;
+mod coordinates;
+mod limits;
+mod manager;
+mod portfolio;
+mod position;
+mod settlement;
+mod strategy;
+mod tied;
+mod updater;
+
+pub use combo::{ComboFill, ComboLegFill};
+pub use coordinates::ChainCoordinates;
+pub use limits::{ExtendedGreekLimits, PositionBreach, PositionLimits};
+pub use manager::InventoryManager;
+pub use portfolio::{PortfolioLimits, PortfolioManager};
+pub use position::Position;
+pub use settlement::{
+    DateBasis, DatedFill, ReconciliationLine, SettlementCalendar, SettlementLedger,
+    SettlementReconciliation,
+};
+pub use strategy::StrategyReport;
+pub use tied::{TiedFill, TiedTrade};
+pub use updater::{GreeksUpdater, GreeksUpdaterStats};
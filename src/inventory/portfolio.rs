@@ -0,0 +1,530 @@
+//! Portfolio manager module.
+//!
+//! This module provides the [`PortfolioManager`], which owns one
+//! [`InventoryManager`] per underlying and aggregates their risk across
+//! underlyings.
+//!
+//! Greeks of different underlyings cannot be added as they are: a delta of
+//! one in BTC and one in ETH are very different exposures. The portfolio
+//! converts every position to [`DollarGreeks`] at its underlying's spot
+//! before summing, and expresses the total delta in units of a reference
+//! index with per-underlying betas:
+//!
+//! `beta_weighted_delta = sum(beta_i * dollar_delta_i) / index_spot`
+//!
+//! [`PortfolioLimits`] cap the dollar Greeks and the beta-weighted delta of
+//! the whole book on top of each inventory's [`PositionLimits`].
+
+use super::limits::PositionLimits;
+use super::manager::InventoryManager;
+use crate::error::{Error, Result};
+use crate::pricing::{DollarGreeks, dollar_greeks};
+use crossbeam_skiplist::SkipMap;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Absolute caps on the risk of the whole portfolio, in quote currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortfolioLimits {
+    /// Maximum net dollar delta.
+    pub dollar_delta: Decimal,
+    /// Maximum net dollar gamma per 1% move.
+    pub dollar_gamma: Decimal,
+    /// Maximum net vega per vol point.
+    pub dollar_vega: Decimal,
+    /// Maximum beta-weighted delta, in units of the reference index.
+    pub beta_weighted_delta: Decimal,
+}
+
+impl Default for PortfolioLimits {
+    fn default() -> Self {
+        Self {
+            dollar_delta: dec!(10000000),
+            dollar_gamma: dec!(1000000),
+            dollar_vega: dec!(1000000),
+            beta_weighted_delta: dec!(1000),
+        }
+    }
+}
+
+impl PortfolioLimits {
+    /// Validates the limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if any limit is not positive.
+    pub fn validate(&self) -> Result<()> {
+        if self.dollar_delta <= Decimal::ZERO
+            || self.dollar_gamma <= Decimal::ZERO
+            || self.dollar_vega <= Decimal::ZERO
+            || self.beta_weighted_delta <= Decimal::ZERO
+        {
+            return Err(Error::configuration("portfolio limits must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// Inventories of several underlyings with cross-underlying risk.
+///
+/// Trades booked through the portfolio are serialized so the portfolio
+/// limits are checked against a consistent view of every inventory.
+pub struct PortfolioManager {
+    /// Symbol of the index deltas are beta-weighted against.
+    reference: String,
+    /// Portfolio limits.
+    limits: PortfolioLimits,
+    /// Inventories by underlying.
+    inventories: SkipMap<String, Arc<InventoryManager>>,
+    /// Spot prices by underlying, including the reference index.
+    spots: SkipMap<String, Decimal>,
+    /// Betas to the reference index by underlying.
+    betas: SkipMap<String, Decimal>,
+    /// Serializes trade booking.
+    booking: Mutex<()>,
+}
+
+impl PortfolioManager {
+    /// Creates an empty portfolio.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference` - Symbol of the index deltas are beta-weighted against
+    /// * `limits` - Portfolio limits
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the limits are invalid.
+    pub fn new(reference: impl Into<String>, limits: PortfolioLimits) -> Result<Self> {
+        limits.validate()?;
+        Ok(Self {
+            reference: reference.into(),
+            limits,
+            inventories: SkipMap::new(),
+            spots: SkipMap::new(),
+            betas: SkipMap::new(),
+            booking: Mutex::new(()),
+        })
+    }
+
+    /// Returns the reference index symbol.
+    #[must_use]
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+
+    /// Returns the portfolio limits.
+    #[must_use]
+    pub const fn limits(&self) -> &PortfolioLimits {
+        &self.limits
+    }
+
+    /// Adds an inventory, replacing any inventory of the same underlying.
+    pub fn insert(&self, inventory: Arc<InventoryManager>) {
+        self.inventories
+            .insert(inventory.underlying().to_string(), inventory);
+    }
+
+    /// Returns the inventory of an underlying, creating it with `limits` if
+    /// it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if a new inventory's limits are
+    /// invalid.
+    pub fn get_or_create(
+        &self,
+        underlying: &str,
+        limits: PositionLimits,
+    ) -> Result<Arc<InventoryManager>> {
+        if let Some(entry) = self.inventories.get(underlying) {
+            return Ok(Arc::clone(entry.value()));
+        }
+        let inventory = Arc::new(InventoryManager::new(underlying, limits)?);
+        Ok(Arc::clone(
+            self.inventories
+                .get_or_insert(underlying.to_string(), inventory)
+                .value(),
+        ))
+    }
+
+    /// Returns the inventory of an underlying.
+    #[must_use]
+    pub fn inventory(&self, underlying: &str) -> Option<Arc<InventoryManager>> {
+        self.inventories
+            .get(underlying)
+            .map(|e| Arc::clone(e.value()))
+    }
+
+    /// Removes the inventory of an underlying.
+    pub fn remove(&self, underlying: &str) -> Option<Arc<InventoryManager>> {
+        self.inventories
+            .remove(underlying)
+            .map(|e| Arc::clone(e.value()))
+    }
+
+    /// Returns the underlyings with an inventory, in order.
+    #[must_use]
+    pub fn underlyings(&self) -> Vec<String> {
+        self.inventories.iter().map(|e| e.key().clone()).collect()
+    }
+
+    /// Returns the number of inventories.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inventories.len()
+    }
+
+    /// Returns true if there are no inventories.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inventories.is_empty()
+    }
+
+    /// Sets the spot price of an underlying or of the reference index.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the spot is not positive.
+    pub fn set_spot(&self, symbol: &str, spot: Decimal) -> Result<()> {
+        if spot <= Decimal::ZERO {
+            return Err(Error::validation(format!(
+                "spot of {symbol} must be positive"
+            )));
+        }
+        self.spots.insert(symbol.to_string(), spot);
+        Ok(())
+    }
+
+    /// Returns the spot price of an underlying or of the reference index.
+    #[must_use]
+    pub fn spot(&self, symbol: &str) -> Option<Decimal> {
+        self.spots.get(symbol).map(|e| *e.value())
+    }
+
+    /// Sets the beta of an underlying to the reference index.
+    pub fn set_beta(&self, underlying: &str, beta: Decimal) {
+        self.betas.insert(underlying.to_string(), beta);
+    }
+
+    /// Returns the beta of an underlying to the reference index, one if
+    /// none was set.
+    #[must_use]
+    pub fn beta(&self, underlying: &str) -> Decimal {
+        self.betas
+            .get(underlying)
+            .map_or(Decimal::ONE, |e| *e.value())
+    }
+
+    /// Returns the dollar Greeks of one underlying's positions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnderlyingNotFound` if there is no inventory, or
+    /// `Error::NoDataAvailable` if it holds positions but has no spot.
+    pub fn dollar_greeks(&self, underlying: &str) -> Result<DollarGreeks> {
+        let inventory = self
+            .inventory(underlying)
+            .ok_or_else(|| Error::underlying_not_found(underlying))?;
+        self.inventory_dollar_greeks(&inventory)
+    }
+
+    /// Returns the dollar Greeks of every underlying.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if an underlying with positions has
+    /// no spot.
+    pub fn dollar_greeks_by_underlying(&self) -> Result<BTreeMap<String, DollarGreeks>> {
+        self.inventories
+            .iter()
+            .map(|e| Ok((e.key().clone(), self.inventory_dollar_greeks(e.value())?)))
+            .collect()
+    }
+
+    /// Returns the dollar Greeks summed across underlyings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if an underlying with positions has
+    /// no spot.
+    pub fn total_dollar_greeks(&self) -> Result<DollarGreeks> {
+        let mut total = DollarGreeks::default();
+        for entry in self.inventories.iter() {
+            add(&mut total, &self.inventory_dollar_greeks(entry.value())?);
+        }
+        Ok(total)
+    }
+
+    /// Returns the portfolio delta in units of the reference index.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if the reference index or an
+    /// underlying with positions has no spot.
+    pub fn beta_weighted_delta(&self) -> Result<Decimal> {
+        let by_underlying = self.dollar_greeks_by_underlying()?;
+        self.beta_weight(
+            by_underlying
+                .iter()
+                .map(|(underlying, greeks)| (underlying.as_str(), greeks.delta)),
+        )
+    }
+
+    /// Checks the current portfolio against the limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InventoryLimitExceeded` naming the first limit
+    /// breached, or `Error::NoDataAvailable` if a spot is missing.
+    pub fn check_limits(&self) -> Result<()> {
+        let by_underlying = self.dollar_greeks_by_underlying()?;
+        self.check(&by_underlying, None)
+    }
+
+    /// Records an option trade in an underlying's inventory after checking
+    /// the portfolio limits.
+    ///
+    /// The trade's risk is taken from the unit Greeks of the position; a
+    /// trade in a contract without a position adds no Greeks. A trade that
+    /// brings a breached limit closer to compliance is accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - Underlying of the contract
+    /// * `symbol` - Option contract symbol
+    /// * `quantity` - Signed quantity (positive buys)
+    /// * `price` - Trade price
+    ///
+    /// Returns the P&L realized by the trade.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnderlyingNotFound` if there is no inventory,
+    /// `Error::NoDataAvailable` if a spot is missing,
+    /// `Error::InventoryLimitExceeded` if the trade breaches a portfolio or
+    /// position limit, or `Error::ValidationError` if the price is
+    /// negative.
+    pub fn record_trade(
+        &self,
+        underlying: &str,
+        symbol: &str,
+        quantity: Decimal,
+        price: Decimal,
+    ) -> Result<Decimal> {
+        let _booking = self.booking();
+        let inventory = self
+            .inventory(underlying)
+            .ok_or_else(|| Error::underlying_not_found(underlying))?;
+        let by_underlying = self.dollar_greeks_by_underlying()?;
+        if let Some(position) = inventory.position(symbol) {
+            let spot = self.require_spot(underlying)?;
+            let greeks = position.unit_greeks() * (quantity * position.contract_size());
+            let trade = dollar_greeks(&greeks, spot, position.settlement());
+            self.check(&by_underlying, Some((underlying, &trade)))?;
+        }
+        inventory.record_trade(symbol, quantity, price)
+    }
+
+    /// Checks the limits, optionally with a trade added to one underlying.
+    ///
+    /// Without a trade every breach is reported; with a trade only breaches
+    /// the trade worsens are.
+    fn check(
+        &self,
+        by_underlying: &BTreeMap<String, DollarGreeks>,
+        trade: Option<(&str, &DollarGreeks)>,
+    ) -> Result<()> {
+        let mut current = DollarGreeks::default();
+        for greeks in by_underlying.values() {
+            add(&mut current, greeks);
+        }
+        let mut projected = current;
+        let mut projected_deltas: BTreeMap<&str, Decimal> = by_underlying
+            .iter()
+            .map(|(underlying, greeks)| (underlying.as_str(), greeks.delta))
+            .collect();
+        if let Some((underlying, greeks)) = trade {
+            add(&mut projected, greeks);
+            *projected_deltas.entry(underlying).or_default() += greeks.delta;
+        }
+        let current_beta = self.beta_weight(
+            by_underlying
+                .iter()
+                .map(|(underlying, greeks)| (underlying.as_str(), greeks.delta)),
+        )?;
+        let projected_beta = self.beta_weight(projected_deltas.into_iter())?;
+
+        let checks = [
+            (
+                "portfolio_dollar_delta",
+                self.limits.dollar_delta,
+                current.delta,
+                projected.delta,
+            ),
+            (
+                "portfolio_dollar_gamma",
+                self.limits.dollar_gamma,
+                current.gamma,
+                projected.gamma,
+            ),
+            (
+                "portfolio_dollar_vega",
+                self.limits.dollar_vega,
+                current.vega,
+                projected.vega,
+            ),
+            (
+                "portfolio_beta_weighted_delta",
+                self.limits.beta_weighted_delta,
+                current_beta,
+                projected_beta,
+            ),
+        ];
+        for (name, limit, before, after) in checks {
+            let worsened = trade.is_none() || after.abs() > before.abs();
+            if after.abs() > limit && worsened {
+                return Err(Error::inventory_limit_exceeded(name, limit, after.abs()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the sum of beta-weighted dollar deltas in index units.
+    fn beta_weight<'a>(&self, deltas: impl Iterator<Item = (&'a str, Decimal)>) -> Result<Decimal> {
+        let mut weighted = Decimal::ZERO;
+        for (underlying, delta) in deltas {
+            weighted += self.beta(underlying) * delta;
+        }
+        if weighted.is_zero() {
+            return Ok(Decimal::ZERO);
+        }
+        Ok(weighted / self.require_spot(&self.reference)?)
+    }
+
+    /// Returns the dollar Greeks of an inventory at its spot.
+    fn inventory_dollar_greeks(&self, inventory: &InventoryManager) -> Result<DollarGreeks> {
+        let mut total = DollarGreeks::default();
+        if inventory.is_empty() {
+            return Ok(total);
+        }
+        let spot = self.require_spot(inventory.underlying())?;
+        inventory.for_each_position(|position| {
+            add(
+                &mut total,
+                &dollar_greeks(&position.greeks(), spot, position.settlement()),
+            );
+        });
+        Ok(total)
+    }
+
+    /// Returns the spot of a symbol or an error if none was set.
+    fn require_spot(&self, symbol: &str) -> Result<Decimal> {
+        self.spot(symbol)
+            .ok_or_else(|| Error::no_data(format!("no spot price for {symbol}")))
+    }
+
+    /// Acquires the booking lock.
+    fn booking(&self) -> MutexGuard<'_, ()> {
+        self.booking
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Adds dollar Greeks into an accumulator.
+fn add(total: &mut DollarGreeks, greeks: &DollarGreeks) {
+    total.delta += greeks.delta;
+    total.gamma += greeks.gamma;
+    total.theta += greeks.theta;
+    total.vega += greeks.vega;
+    total.rho += greeks.rho;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::Position;
+    use crate::pricing::Greeks;
+
+    fn portfolio(limits: PortfolioLimits) -> PortfolioManager {
+        let portfolio = PortfolioManager::new("SPX", limits).unwrap();
+        for (underlying, symbol, delta, spot) in [
+            ("BTC", "BTC-20240329-50000-C", dec!(0.5), dec!(50000)),
+            ("ETH", "ETH-20240329-3000-P", dec!(-0.4), dec!(3000)),
+        ] {
+            let inventory = portfolio
+                .get_or_create(underlying, PositionLimits::default())
+                .unwrap();
+            inventory.add_position(Position::new(symbol));
+            inventory
+                .set_greeks(
+                    symbol,
+                    Greeks::new(delta, dec!(0.00001), dec!(-5), dec!(20), Decimal::ZERO),
+                )
+                .unwrap();
+            inventory.record_trade(symbol, dec!(10), dec!(100)).unwrap();
+            portfolio.set_spot(underlying, spot).unwrap();
+        }
+        portfolio.set_spot("SPX", dec!(5000)).unwrap();
+        portfolio
+    }
+
+    #[test]
+    fn test_dollar_greeks_and_beta_weighted_delta() {
+        let portfolio = portfolio(PortfolioLimits::default());
+        assert_eq!(portfolio.underlyings(), vec!["BTC", "ETH"]);
+
+        let btc = portfolio.dollar_greeks("BTC").unwrap();
+        assert_eq!(btc.delta, dec!(250000));
+        assert_eq!(btc.vega, dec!(200));
+        let total = portfolio.total_dollar_greeks().unwrap();
+        assert_eq!(total.delta, dec!(250000) - dec!(12000));
+        assert_eq!(total.theta, dec!(-100));
+
+        assert_eq!(portfolio.beta_weighted_delta().unwrap(), dec!(47.6));
+        portfolio.set_beta("BTC", dec!(2));
+        portfolio.set_beta("ETH", dec!(1.5));
+        assert_eq!(portfolio.beta_weighted_delta().unwrap(), dec!(96.4));
+
+        let missing = PortfolioManager::new("SPX", PortfolioLimits::default()).unwrap();
+        missing.insert(portfolio.inventory("BTC").unwrap());
+        assert!(missing.total_dollar_greeks().is_err());
+        assert!(portfolio.dollar_greeks("SOL").is_err());
+    }
+
+    #[test]
+    fn test_portfolio_limits_gate_trades() {
+        let portfolio = portfolio(PortfolioLimits {
+            beta_weighted_delta: dec!(50),
+            ..PortfolioLimits::default()
+        });
+        assert!(portfolio.check_limits().is_ok());
+
+        // Ten more BTC calls add 50 index units of delta.
+        let err = portfolio
+            .record_trade("BTC", "BTC-20240329-50000-C", dec!(10), dec!(100))
+            .unwrap_err();
+        assert!(err.to_string().contains("portfolio_beta_weighted_delta"));
+        assert_eq!(
+            portfolio
+                .inventory("BTC")
+                .unwrap()
+                .position("BTC-20240329-50000-C")
+                .unwrap()
+                .quantity(),
+            dec!(10)
+        );
+
+        // Risk-reducing trades pass, even while breached.
+        portfolio.set_beta("BTC", dec!(2));
+        assert!(portfolio.check_limits().is_err());
+        portfolio
+            .record_trade("BTC", "BTC-20240329-50000-C", dec!(-5), dec!(100))
+            .unwrap();
+        assert!(portfolio.check_limits().is_ok());
+    }
+}
@@ -0,0 +1,314 @@
+//! Position module.
+//!
+//! This module provides [`Position`], a signed holding in one contract with
+//! average-cost accounting of realized and unrealized P&L for linear and
+//! inverse contracts.
+//!
+//! For linear contracts prices and P&L are in the quote currency. For
+//! inverse contracts prices are coin premiums and P&L accrues in coin; its
+//! USD value therefore depends on spot at the time it is measured.
+
+use crate::error::{Error, Result};
+use crate::pricing::{ContractSettlement, ExtendedGreeks, Greeks, coin_to_usd};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// A signed position in one contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    /// Contract symbol.
+    symbol: String,
+    /// Signed quantity in contracts (negative for short).
+    quantity: Decimal,
+    /// Average entry price of the open quantity.
+    average_price: Decimal,
+    /// Realized P&L in the settlement currency.
+    realized_pnl: Decimal,
+    /// Underlying amount per contract.
+    contract_size: Decimal,
+    /// Premium and P&L denomination.
+    settlement: ContractSettlement,
+    /// Greeks of one long contract.
+    greeks: Greeks,
+    /// Cross Greeks of one long contract; the first-order part is unused.
+    #[serde(default)]
+    cross: ExtendedGreeks,
+}
+
+impl Position {
+    /// Creates a flat linear position with a contract size of one.
+    #[must_use]
+    pub fn new(symbol: impl Into<String>) -> Self {
+        Self {
+            symbol: symbol.into(),
+            quantity: Decimal::ZERO,
+            average_price: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            contract_size: Decimal::ONE,
+            settlement: ContractSettlement::Linear,
+            greeks: Greeks::zero(),
+            cross: ExtendedGreeks::zero(),
+        }
+    }
+
+    /// Sets the settlement denomination.
+    #[must_use]
+    pub const fn with_settlement(mut self, settlement: ContractSettlement) -> Self {
+        self.settlement = settlement;
+        self
+    }
+
+    /// Sets the underlying amount per contract.
+    #[must_use]
+    pub const fn with_contract_size(mut self, contract_size: Decimal) -> Self {
+        self.contract_size = contract_size;
+        self
+    }
+
+    /// Returns the contract symbol.
+    #[must_use]
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Returns the signed quantity.
+    #[must_use]
+    pub const fn quantity(&self) -> Decimal {
+        self.quantity
+    }
+
+    /// Returns the average entry price of the open quantity.
+    #[must_use]
+    pub const fn average_price(&self) -> Decimal {
+        self.average_price
+    }
+
+    /// Returns the realized P&L in the settlement currency.
+    #[must_use]
+    pub const fn realized_pnl(&self) -> Decimal {
+        self.realized_pnl
+    }
+
+    /// Returns the underlying amount per contract.
+    #[must_use]
+    pub const fn contract_size(&self) -> Decimal {
+        self.contract_size
+    }
+
+    /// Returns the settlement denomination.
+    #[must_use]
+    pub const fn settlement(&self) -> ContractSettlement {
+        self.settlement
+    }
+
+    /// Returns true if the position is flat.
+    #[must_use]
+    pub fn is_flat(&self) -> bool {
+        self.quantity.is_zero()
+    }
+
+    /// Sets the Greeks of one long contract, keeping its cross Greeks.
+    pub fn set_greeks(&mut self, greeks: Greeks) {
+        self.greeks = greeks;
+    }
+
+    /// Sets the Greeks of one long contract with its cross Greeks.
+    pub fn set_extended_greeks(&mut self, greeks: ExtendedGreeks) {
+        self.greeks = greeks.greeks;
+        self.cross = ExtendedGreeks {
+            greeks: Greeks::zero(),
+            ..greeks
+        };
+    }
+
+    /// Returns the Greeks of one long contract.
+    #[must_use]
+    pub const fn unit_greeks(&self) -> Greeks {
+        self.greeks
+    }
+
+    /// Returns the Greeks of the whole position.
+    #[must_use]
+    pub fn greeks(&self) -> Greeks {
+        self.greeks * (self.quantity * self.contract_size)
+    }
+
+    /// Returns the Greeks of one long contract with its cross Greeks.
+    #[must_use]
+    pub fn unit_extended_greeks(&self) -> ExtendedGreeks {
+        ExtendedGreeks {
+            greeks: self.greeks,
+            ..self.cross
+        }
+    }
+
+    /// Returns the Greeks of the whole position with its cross Greeks.
+    #[must_use]
+    pub fn extended_greeks(&self) -> ExtendedGreeks {
+        self.unit_extended_greeks()
+            .scaled(self.quantity * self.contract_size)
+    }
+
+    /// Applies a fill with average-cost accounting.
+    ///
+    /// Reducing or flipping the position realizes P&L on the closed
+    /// quantity; any remainder opens at the fill price.
+    ///
+    /// # Arguments
+    ///
+    /// * `quantity` - Signed fill quantity (positive buys, negative sells)
+    /// * `price` - Fill price (coin premium for inverse contracts)
+    ///
+    /// Returns the P&L realized by this fill.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the price is negative.
+    pub fn apply_fill(&mut self, quantity: Decimal, price: Decimal) -> Result<Decimal> {
+        if price < Decimal::ZERO {
+            return Err(Error::validation("fill price must be non-negative"));
+        }
+        if quantity.is_zero() {
+            return Ok(Decimal::ZERO);
+        }
+
+        let same_direction = self.quantity.is_zero()
+            || (self.quantity > Decimal::ZERO) == (quantity > Decimal::ZERO);
+        if same_direction {
+            let total = self.quantity + quantity;
+            self.average_price = (self.average_price * self.quantity + price * quantity) / total;
+            self.quantity = total;
+            return Ok(Decimal::ZERO);
+        }
+
+        let closed = quantity.abs().min(self.quantity.abs());
+        let was_long = self.quantity > Decimal::ZERO;
+        let direction = if was_long {
+            Decimal::ONE
+        } else {
+            -Decimal::ONE
+        };
+        let realized = (price - self.average_price) * closed * direction * self.contract_size;
+        self.realized_pnl += realized;
+        self.quantity += quantity;
+        if self.quantity.is_zero() {
+            self.average_price = Decimal::ZERO;
+        } else if (self.quantity > Decimal::ZERO) != was_long {
+            self.average_price = price;
+        }
+        Ok(realized)
+    }
+
+    /// Returns the unrealized P&L in the settlement currency.
+    ///
+    /// # Arguments
+    ///
+    /// * `mark` - Mark price (coin premium for inverse contracts)
+    #[must_use]
+    pub fn unrealized_pnl(&self, mark: Decimal) -> Decimal {
+        (mark - self.average_price) * self.quantity * self.contract_size
+    }
+
+    /// Returns realized plus unrealized P&L in the settlement currency.
+    #[must_use]
+    pub fn total_pnl(&self, mark: Decimal) -> Decimal {
+        self.realized_pnl + self.unrealized_pnl(mark)
+    }
+
+    /// Returns total P&L in USD.
+    ///
+    /// Linear P&L is already in USD. Inverse P&L accrues in coin and is
+    /// valued at the given spot, so the same coin P&L is worth more or less
+    /// USD as spot moves.
+    #[must_use]
+    pub fn total_pnl_usd(&self, mark: Decimal, spot: Decimal) -> Decimal {
+        let pnl = self.total_pnl(mark);
+        match self.settlement {
+            ContractSettlement::Linear => pnl,
+            ContractSettlement::Inverse => coin_to_usd(pnl, spot),
+        }
+    }
+
+    /// Returns the market value in the settlement currency.
+    #[must_use]
+    pub fn market_value(&self, mark: Decimal) -> Decimal {
+        mark * self.quantity * self.contract_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_average_cost() {
+        let mut position = Position::new("BTC-C");
+        position.apply_fill(dec!(2), dec!(10)).unwrap();
+        position.apply_fill(dec!(2), dec!(12)).unwrap();
+
+        assert_eq!(position.quantity(), dec!(4));
+        assert_eq!(position.average_price(), dec!(11));
+        assert_eq!(position.unrealized_pnl(dec!(13)), dec!(8));
+    }
+
+    #[test]
+    fn test_reduce_and_flip() {
+        let mut position = Position::new("BTC-C");
+        position.apply_fill(dec!(3), dec!(10)).unwrap();
+
+        assert_eq!(position.apply_fill(dec!(-1), dec!(14)).unwrap(), dec!(4));
+        assert_eq!(position.average_price(), dec!(10));
+
+        assert_eq!(position.apply_fill(dec!(-4), dec!(9)).unwrap(), dec!(-2));
+        assert_eq!(position.quantity(), dec!(-2));
+        assert_eq!(position.average_price(), dec!(9));
+        assert_eq!(position.realized_pnl(), dec!(2));
+    }
+
+    #[test]
+    fn test_rejects_negative_price() {
+        assert!(Position::new("X").apply_fill(dec!(1), dec!(-1)).is_err());
+    }
+
+    #[test]
+    fn test_inverse_pnl_in_coin() {
+        // Buy 1 BTC call at 0.05 BTC, sell at 0.08 BTC: 0.03 BTC profit,
+        // worth 360 USD with BTC at 12000 but 240 USD at 8000.
+        let mut position =
+            Position::new("BTC-27DEC24-60000-C").with_settlement(ContractSettlement::Inverse);
+        position.apply_fill(dec!(1), dec!(0.05)).unwrap();
+        position.apply_fill(dec!(-1), dec!(0.08)).unwrap();
+
+        assert_eq!(position.realized_pnl(), dec!(0.03));
+        assert_eq!(
+            position.total_pnl_usd(Decimal::ZERO, dec!(12000)),
+            dec!(360)
+        );
+        assert_eq!(position.total_pnl_usd(Decimal::ZERO, dec!(8000)), dec!(240));
+    }
+
+    #[test]
+    fn test_position_greeks_scale_with_size() {
+        let mut position = Position::new("ETH-C").with_contract_size(dec!(10));
+        position.apply_fill(dec!(-2), dec!(1)).unwrap();
+        position.set_greeks(Greeks::new(
+            dec!(0.5),
+            dec!(0.1),
+            dec!(-1),
+            dec!(2),
+            dec!(0),
+        ));
+
+        assert_eq!(position.greeks().delta, dec!(-10));
+        assert_eq!(position.market_value(dec!(1.5)), dec!(-30));
+
+        // First-order updates keep the cross Greeks.
+        let mut unit = ExtendedGreeks::from(position.unit_greeks());
+        unit.vanna = dec!(0.2);
+        position.set_extended_greeks(unit);
+        position.set_greeks(Greeks::zero());
+        assert_eq!(position.extended_greeks().vanna, dec!(-4));
+        assert!(position.greeks().is_zero());
+    }
+}
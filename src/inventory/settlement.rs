@@ -0,0 +1,444 @@
+//! Settlement date module.
+//!
+//! This module provides effective-date handling for fills. Each fill gets
+//! a trade date, rolled to the next trading day when it arrives after the
+//! clearing cut-off, and a settlement date a fixed number of trading days
+//! later from a [`SettlementCalendar`]. The [`SettlementLedger`] then shows
+//! positions and premium cash "as of trade date" or "as of settlement"
+//! and reconciles the two for back-office systems.
+
+use crate::error::{Error, Result};
+use crate::pnl::TradingCalendar;
+use chrono::{DateTime, Days, NaiveDate, NaiveTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Longest run of non-trading days searched before giving up.
+const MAX_CLOSED_DAYS: u32 = 366;
+
+/// Trade and settlement date rules of a clearing venue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettlementCalendar {
+    /// Trading days of the venue.
+    calendar: TradingCalendar,
+    /// Clearing cut-off (UTC); later fills take the next trade date.
+    cutoff: NaiveTime,
+    /// Trading days from trade date to settlement date.
+    settlement_lag: u32,
+}
+
+impl SettlementCalendar {
+    /// Creates settlement rules.
+    ///
+    /// # Arguments
+    ///
+    /// * `calendar` - Trading days of the venue
+    /// * `cutoff` - Clearing cut-off (UTC)
+    /// * `settlement_lag` - Trading days from trade to settlement (e.g. 1 for T+1)
+    #[must_use]
+    pub const fn new(calendar: TradingCalendar, cutoff: NaiveTime, settlement_lag: u32) -> Self {
+        Self {
+            calendar,
+            cutoff,
+            settlement_lag,
+        }
+    }
+
+    /// Returns the settlement lag in trading days.
+    #[must_use]
+    pub const fn settlement_lag(&self) -> u32 {
+        self.settlement_lag
+    }
+
+    /// Returns the trade date of a fill.
+    ///
+    /// Fills at or after the cut-off, or on a non-trading day, take the
+    /// next trading day.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the timestamp is out of range, or
+    /// `Error::ConfigurationError` if the calendar has no trading day within
+    /// a year.
+    pub fn trade_date(&self, timestamp_ms: u64) -> Result<NaiveDate> {
+        let time = i64::try_from(timestamp_ms)
+            .ok()
+            .and_then(DateTime::from_timestamp_millis)
+            .ok_or_else(|| Error::validation(format!("timestamp {timestamp_ms} out of range")))?;
+        let date = time.date_naive();
+        if time.time() >= self.cutoff || !self.calendar.is_trading_day(date) {
+            self.next_trading_day(date)
+        } else {
+            Ok(date)
+        }
+    }
+
+    /// Returns the settlement date of a trade date.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the calendar has no trading
+    /// day within a year.
+    pub fn settle_date(&self, trade_date: NaiveDate) -> Result<NaiveDate> {
+        (0..self.settlement_lag).try_fold(trade_date, |date, _| self.next_trading_day(date))
+    }
+
+    /// Returns the first trading day after a date.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the calendar has no trading
+    /// day within a year.
+    pub fn next_trading_day(&self, date: NaiveDate) -> Result<NaiveDate> {
+        let mut next = date;
+        for _ in 0..MAX_CLOSED_DAYS {
+            next = next
+                .checked_add_days(Days::new(1))
+                .ok_or_else(|| Error::validation("date out of range"))?;
+            if self.calendar.is_trading_day(next) {
+                return Ok(next);
+            }
+        }
+        Err(Error::configuration(
+            "settlement calendar has no trading day within a year",
+        ))
+    }
+}
+
+/// Which date a position view is taken on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DateBasis {
+    /// Fills count from their trade date.
+    TradeDate,
+    /// Fills count from their settlement date.
+    SettleDate,
+}
+
+/// A fill with its effective dates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatedFill {
+    /// Fill identifier.
+    pub fill_id: String,
+    /// Contract symbol.
+    pub symbol: String,
+    /// Signed quantity (positive buys).
+    pub quantity: Decimal,
+    /// Fill price.
+    pub price: Decimal,
+    /// Execution timestamp in milliseconds.
+    pub timestamp_ms: u64,
+    /// Trade date after the clearing cut-off.
+    pub trade_date: NaiveDate,
+    /// Settlement date.
+    pub settle_date: NaiveDate,
+}
+
+impl DatedFill {
+    /// Returns the date the fill counts from on a basis.
+    #[must_use]
+    pub const fn effective_date(&self, basis: DateBasis) -> NaiveDate {
+        match basis {
+            DateBasis::TradeDate => self.trade_date,
+            DateBasis::SettleDate => self.settle_date,
+        }
+    }
+
+    /// Returns the premium cash flow of the fill (negative when paid).
+    #[must_use]
+    pub fn cash(&self) -> Decimal {
+        -self.quantity * self.price
+    }
+}
+
+/// Trade-date versus settled quantity of one contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReconciliationLine {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Position as of trade date.
+    pub trade_date_quantity: Decimal,
+    /// Position as of settlement.
+    pub settled_quantity: Decimal,
+    /// Fills traded but not yet settled.
+    pub unsettled_fills: Vec<String>,
+}
+
+/// Reconciliation of trade-date and settled views on a date.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementReconciliation {
+    /// Reconciliation date.
+    pub date: NaiveDate,
+    /// Lines by symbol, only where the views differ.
+    pub lines: Vec<ReconciliationLine>,
+    /// Premium cash as of trade date.
+    pub trade_date_cash: Decimal,
+    /// Premium cash as of settlement.
+    pub settled_cash: Decimal,
+}
+
+impl SettlementReconciliation {
+    /// Returns the premium cash traded but not yet settled.
+    #[must_use]
+    pub fn unsettled_cash(&self) -> Decimal {
+        self.trade_date_cash - self.settled_cash
+    }
+
+    /// Returns true if both views agree.
+    #[must_use]
+    pub fn is_settled(&self) -> bool {
+        self.lines.is_empty() && self.unsettled_cash().is_zero()
+    }
+}
+
+/// Fills with effective dates and dated position views.
+pub struct SettlementLedger {
+    /// Trade and settlement date rules.
+    calendar: SettlementCalendar,
+    /// Fills in recording order.
+    fills: Mutex<Vec<DatedFill>>,
+}
+
+impl SettlementLedger {
+    /// Creates an empty ledger.
+    #[must_use]
+    pub const fn new(calendar: SettlementCalendar) -> Self {
+        Self {
+            calendar,
+            fills: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the settlement rules.
+    #[must_use]
+    pub const fn calendar(&self) -> &SettlementCalendar {
+        &self.calendar
+    }
+
+    /// Records a fill and assigns its trade and settlement dates.
+    ///
+    /// # Arguments
+    ///
+    /// * `fill_id` - Fill identifier
+    /// * `symbol` - Contract symbol
+    /// * `quantity` - Signed quantity (positive buys)
+    /// * `price` - Fill price
+    /// * `timestamp_ms` - Execution timestamp in milliseconds
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the quantity is zero, the price
+    /// is negative, the fill id is already recorded or the timestamp is out
+    /// of range.
+    pub fn record(
+        &self,
+        fill_id: impl Into<String>,
+        symbol: impl Into<String>,
+        quantity: Decimal,
+        price: Decimal,
+        timestamp_ms: u64,
+    ) -> Result<DatedFill> {
+        let fill_id = fill_id.into();
+        if quantity.is_zero() || price < Decimal::ZERO {
+            return Err(Error::validation(format!(
+                "fill {fill_id} needs a non-zero quantity and non-negative price"
+            )));
+        }
+        let trade_date = self.calendar.trade_date(timestamp_ms)?;
+        let fill = DatedFill {
+            fill_id,
+            symbol: symbol.into(),
+            quantity,
+            price,
+            timestamp_ms,
+            trade_date,
+            settle_date: self.calendar.settle_date(trade_date)?,
+        };
+        let mut fills = self.lock();
+        if fills.iter().any(|f| f.fill_id == fill.fill_id) {
+            return Err(Error::validation(format!(
+                "fill {} already recorded",
+                fill.fill_id
+            )));
+        }
+        fills.push(fill.clone());
+        Ok(fill)
+    }
+
+    /// Returns every fill in recording order.
+    #[must_use]
+    pub fn fills(&self) -> Vec<DatedFill> {
+        self.lock().clone()
+    }
+
+    /// Returns the position of a contract as of a date.
+    #[must_use]
+    pub fn position_as_of(&self, symbol: &str, date: NaiveDate, basis: DateBasis) -> Decimal {
+        self.lock()
+            .iter()
+            .filter(|f| f.symbol == symbol && f.effective_date(basis) <= date)
+            .map(|f| f.quantity)
+            .sum()
+    }
+
+    /// Returns every non-flat position as of a date.
+    #[must_use]
+    pub fn positions_as_of(&self, date: NaiveDate, basis: DateBasis) -> BTreeMap<String, Decimal> {
+        let mut positions = BTreeMap::new();
+        for fill in self
+            .lock()
+            .iter()
+            .filter(|f| f.effective_date(basis) <= date)
+        {
+            *positions
+                .entry(fill.symbol.clone())
+                .or_insert(Decimal::ZERO) += fill.quantity;
+        }
+        positions.retain(|_, q| !q.is_zero());
+        positions
+    }
+
+    /// Returns the premium cash as of a date.
+    #[must_use]
+    pub fn cash_as_of(&self, date: NaiveDate, basis: DateBasis) -> Decimal {
+        self.lock()
+            .iter()
+            .filter(|f| f.effective_date(basis) <= date)
+            .map(DatedFill::cash)
+            .sum()
+    }
+
+    /// Reconciles the trade-date and settled views on a date.
+    #[must_use]
+    pub fn reconcile(&self, date: NaiveDate) -> SettlementReconciliation {
+        let fills = self.lock();
+        let mut lines: BTreeMap<&str, ReconciliationLine> = BTreeMap::new();
+        let mut trade_date_cash = Decimal::ZERO;
+        let mut settled_cash = Decimal::ZERO;
+        for fill in fills.iter().filter(|f| f.trade_date <= date) {
+            let line = lines
+                .entry(&fill.symbol)
+                .or_insert_with(|| ReconciliationLine {
+                    symbol: fill.symbol.clone(),
+                    trade_date_quantity: Decimal::ZERO,
+                    settled_quantity: Decimal::ZERO,
+                    unsettled_fills: Vec::new(),
+                });
+            line.trade_date_quantity += fill.quantity;
+            trade_date_cash += fill.cash();
+            if fill.settle_date <= date {
+                line.settled_quantity += fill.quantity;
+                settled_cash += fill.cash();
+            } else {
+                line.unsettled_fills.push(fill.fill_id.clone());
+            }
+        }
+        SettlementReconciliation {
+            date,
+            lines: lines
+                .into_values()
+                .filter(|l| !l.unsettled_fills.is_empty())
+                .collect(),
+            trade_date_cash,
+            settled_cash,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<DatedFill>> {
+        self.fills
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn ms(day: u32, hour: u32) -> u64 {
+        Utc.with_ymd_and_hms(2024, 3, day, hour, 0, 0)
+            .unwrap()
+            .timestamp_millis() as u64
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn ledger() -> SettlementLedger {
+        // T+1 with a 20:00 UTC cut-off; Monday 11 March is a holiday.
+        let calendar = TradingCalendar::new(
+            NaiveTime::from_hms_opt(14, 30, 0).unwrap(),
+            NaiveTime::from_hms_opt(21, 0, 0).unwrap(),
+        )
+        .with_holiday(date(11));
+        SettlementLedger::new(SettlementCalendar::new(
+            calendar,
+            NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+            1,
+        ))
+    }
+
+    #[test]
+    fn test_cutoff_and_calendar_dates() {
+        let ledger = ledger();
+        // Thursday before the cut-off: trade Thu 7, settle Fri 8.
+        let fill = ledger
+            .record("F1", "SPX-C", dec!(10), dec!(5), ms(7, 15))
+            .unwrap();
+        assert_eq!((fill.trade_date, fill.settle_date), (date(7), date(8)));
+        // Friday after the cut-off: trade Tue 12 (Mon is a holiday), settle Wed 13.
+        let fill = ledger
+            .record("F2", "SPX-C", dec!(-4), dec!(6), ms(8, 20))
+            .unwrap();
+        assert_eq!((fill.trade_date, fill.settle_date), (date(12), date(13)));
+        // Saturday: next trading day.
+        let fill = ledger
+            .record("F3", "SPX-P", dec!(2), dec!(3), ms(9, 10))
+            .unwrap();
+        assert_eq!(fill.trade_date, date(12));
+        assert!(
+            ledger
+                .record("F3", "SPX-P", dec!(2), dec!(3), ms(9, 10))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_trade_date_versus_settled_views() {
+        let ledger = ledger();
+        ledger
+            .record("F1", "SPX-C", dec!(10), dec!(5), ms(7, 15))
+            .unwrap();
+        ledger
+            .record("F2", "SPX-C", dec!(-4), dec!(6), ms(12, 15))
+            .unwrap();
+
+        assert_eq!(
+            ledger.position_as_of("SPX-C", date(7), DateBasis::TradeDate),
+            dec!(10)
+        );
+        assert_eq!(
+            ledger.position_as_of("SPX-C", date(7), DateBasis::SettleDate),
+            Decimal::ZERO
+        );
+        assert_eq!(
+            ledger.positions_as_of(date(12), DateBasis::TradeDate)["SPX-C"],
+            dec!(6)
+        );
+        assert_eq!(
+            ledger.cash_as_of(date(12), DateBasis::SettleDate),
+            dec!(-50)
+        );
+
+        let recon = ledger.reconcile(date(12));
+        assert_eq!(recon.lines.len(), 1);
+        assert_eq!(recon.lines[0].settled_quantity, dec!(10));
+        assert_eq!(recon.lines[0].unsettled_fills, vec!["F2".to_string()]);
+        assert_eq!(recon.unsettled_cash(), dec!(24));
+        assert!(ledger.reconcile(date(13)).is_settled());
+    }
+}
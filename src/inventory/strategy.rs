@@ -0,0 +1,60 @@
+//! Strategy report module.
+//!
+//! This module provides [`StrategyReport`], the positions, realized P&L
+//! and Greeks of the trades booked under one strategy tag, e.g. `"mm"`,
+//! `"hedge"` or `"rfq"`, by
+//! [`InventoryManager::record_strategy_trade`](super::InventoryManager::record_strategy_trade).
+//!
+//! Strategy positions are kept beside the aggregate ones: each is booked
+//! with its own average cost, so a contract bought by one strategy and sold
+//! by another nets to flat in the aggregate while both strategies keep an
+//! open position and their own P&L.
+
+use super::position::Position;
+use crate::pricing::Greeks;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Positions and P&L of one strategy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StrategyReport {
+    /// Strategy tag.
+    pub strategy: String,
+    /// Positions by symbol, flat ones included.
+    pub positions: Vec<Position>,
+    /// Realized P&L over every position.
+    pub realized_pnl: Decimal,
+    /// Greeks of every position.
+    pub greeks: Greeks,
+}
+
+impl StrategyReport {
+    /// Builds the report of a strategy's positions.
+    pub(crate) fn new(strategy: impl Into<String>, positions: Vec<Position>) -> Self {
+        Self {
+            strategy: strategy.into(),
+            realized_pnl: positions.iter().map(Position::realized_pnl).sum(),
+            greeks: positions.iter().map(Position::greeks).sum(),
+            positions,
+        }
+    }
+
+    /// Returns the total P&L of the strategy, each position marked by
+    /// `mark`; positions without a mark count their realized P&L only.
+    #[must_use]
+    pub fn total_pnl(&self, mark: impl Fn(&str) -> Option<Decimal>) -> Decimal {
+        self.positions
+            .iter()
+            .map(|position| match mark(position.symbol()) {
+                Some(price) => position.total_pnl(price),
+                None => position.realized_pnl(),
+            })
+            .sum()
+    }
+
+    /// Returns the number of open positions.
+    #[must_use]
+    pub fn open_positions(&self) -> usize {
+        self.positions.iter().filter(|p| !p.is_flat()).count()
+    }
+}
@@ -0,0 +1,208 @@
+//! Tied trade module.
+//!
+//! This module provides [`TiedTrade`], an option fill booked together with
+//! its underlying hedge leg at an agreed delta and reference price (a
+//! delta-exchange or tied-to-stock trade).
+//!
+//! The hedge leg quantity is `-option_quantity * delta`, so the package is
+//! delta-neutral at the reference price when it is booked.
+
+use crate::error::{Error, Result};
+use crate::pricing::Greeks;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// An option trade tied to an underlying hedge leg.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TiedTrade {
+    /// Package identifier.
+    pub trade_id: String,
+    /// Option contract symbol.
+    pub option_symbol: String,
+    /// Signed option quantity (positive buys).
+    pub option_quantity: Decimal,
+    /// Option premium per contract.
+    pub option_price: Decimal,
+    /// Agreed delta per option contract.
+    pub delta: Decimal,
+    /// Hedge instrument symbol (stock, future or perpetual).
+    pub hedge_symbol: String,
+    /// Signed hedge quantity.
+    pub hedge_quantity: Decimal,
+    /// Agreed reference price of the hedge leg.
+    pub reference_price: Decimal,
+    /// Trade timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+impl TiedTrade {
+    /// Creates a tied trade with a hedge leg that neutralizes the agreed delta.
+    ///
+    /// # Arguments
+    ///
+    /// * `trade_id` - Package identifier
+    /// * `option_symbol` - Option contract symbol
+    /// * `option_quantity` - Signed option quantity (positive buys)
+    /// * `option_price` - Option premium per contract
+    /// * `delta` - Agreed delta per option contract
+    /// * `hedge_symbol` - Hedge instrument symbol
+    /// * `reference_price` - Agreed reference price of the hedge leg
+    #[must_use]
+    pub fn new(
+        trade_id: impl Into<String>,
+        option_symbol: impl Into<String>,
+        option_quantity: Decimal,
+        option_price: Decimal,
+        delta: Decimal,
+        hedge_symbol: impl Into<String>,
+        reference_price: Decimal,
+    ) -> Self {
+        Self {
+            trade_id: trade_id.into(),
+            option_symbol: option_symbol.into(),
+            option_quantity,
+            option_price,
+            delta,
+            hedge_symbol: hedge_symbol.into(),
+            hedge_quantity: -(option_quantity * delta),
+            reference_price,
+            timestamp_ms: 0,
+        }
+    }
+
+    /// Sets the trade timestamp.
+    #[must_use]
+    pub const fn with_timestamp(mut self, timestamp_ms: u64) -> Self {
+        self.timestamp_ms = timestamp_ms;
+        self
+    }
+
+    /// Overrides the hedge quantity, e.g. after rounding to a lot size.
+    #[must_use]
+    pub fn with_hedge_quantity(mut self, hedge_quantity: Decimal) -> Self {
+        self.hedge_quantity = hedge_quantity;
+        self
+    }
+
+    /// Validates the trade.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the option quantity is zero, a
+    /// price is negative, the reference price is not positive, or the hedge
+    /// leg is on the same side as the option delta.
+    pub fn validate(&self) -> Result<()> {
+        if self.option_quantity.is_zero() {
+            return Err(Error::validation("tied trade option quantity is zero"));
+        }
+        if self.option_price < Decimal::ZERO {
+            return Err(Error::validation("tied trade option price is negative"));
+        }
+        if self.reference_price <= Decimal::ZERO {
+            return Err(Error::validation(
+                "tied trade reference price must be positive",
+            ));
+        }
+        if self.option_delta() * self.hedge_quantity > Decimal::ZERO {
+            return Err(Error::validation(
+                "tied trade hedge leg must offset the option delta",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the agreed delta of the option leg.
+    #[must_use]
+    pub fn option_delta(&self) -> Decimal {
+        self.option_quantity * self.delta
+    }
+
+    /// Returns the delta left after the hedge leg at the agreed delta.
+    #[must_use]
+    pub fn residual_delta(&self) -> Decimal {
+        self.option_delta() + self.hedge_quantity
+    }
+
+    /// Returns the Greeks of the hedge leg.
+    #[must_use]
+    pub fn hedge_greeks(&self) -> Greeks {
+        Greeks {
+            delta: self.hedge_quantity,
+            ..Greeks::zero()
+        }
+    }
+
+    /// Returns the Greeks of the whole package.
+    ///
+    /// # Arguments
+    ///
+    /// * `option_greeks` - Greeks of one long option contract
+    #[must_use]
+    pub fn package_greeks(&self, option_greeks: &Greeks) -> Greeks {
+        *option_greeks * self.option_quantity + self.hedge_greeks()
+    }
+}
+
+/// Realized P&L from booking a tied trade.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TiedFill {
+    /// P&L realized on the option leg.
+    pub option_realized: Decimal,
+    /// P&L realized on the hedge leg.
+    pub hedge_realized: Decimal,
+}
+
+impl TiedFill {
+    /// Returns the P&L realized by the package.
+    #[must_use]
+    pub fn realized(&self) -> Decimal {
+        self.option_realized + self.hedge_realized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn trade() -> TiedTrade {
+        TiedTrade::new(
+            "T1",
+            "SPX-C-5000",
+            dec!(10),
+            dec!(42),
+            dec!(0.3),
+            "SPX-FUT",
+            dec!(5010),
+        )
+    }
+
+    #[test]
+    fn test_hedge_leg_offsets_delta() {
+        let trade = trade();
+        assert_eq!(trade.hedge_quantity, dec!(-3));
+        assert_eq!(trade.residual_delta(), Decimal::ZERO);
+        assert!(trade.validate().is_ok());
+
+        let rounded = trade.with_hedge_quantity(dec!(-2));
+        assert_eq!(rounded.residual_delta(), dec!(1));
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(trade().with_hedge_quantity(dec!(3)).validate().is_err());
+        let mut bad = trade();
+        bad.reference_price = Decimal::ZERO;
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_package_greeks() {
+        let option = Greeks::new(dec!(0.3), dec!(0.01), dec!(-2), dec!(5), dec!(1));
+        let package = trade().package_greeks(&option);
+
+        assert_eq!(package.delta, Decimal::ZERO);
+        assert_eq!(package.gamma, dec!(0.1));
+        assert_eq!(package.vega, dec!(50));
+    }
+}
@@ -0,0 +1,358 @@
+//! Greeks updater module.
+//!
+//! This module provides the [`GreeksUpdater`], which keeps the unit Greeks
+//! of an [`InventoryManager`]'s positions in line with market data: on a
+//! spot or volatility surface update it reprices the affected contracts
+//! off the surface and pushes their Greeks, with vanna, volga, charm and
+//! speed, into the inventory.
+//!
+//! ## Dirty tracking
+//!
+//! - A spot move affects every contract
+//! - A surface update affects the expirations whose interpolated smile
+//!   changed; the rest keep their Greeks
+//! - Contracts never priced, e.g. new positions, are priced on the next
+//!   update, and [`GreeksUpdater::mark_dirty`] queues one explicitly
+//!
+//! Time decay between updates only reaches contracts that are repriced;
+//! [`GreeksUpdater::mark_all_dirty`] refreshes everything, e.g. on a
+//! periodic timer. Contracts without chain coordinates, such as hedge
+//! instruments, and expired contracts are left alone.
+
+use super::coordinates::ChainCoordinates;
+use super::manager::InventoryManager;
+use crate::clock::Clock;
+use crate::error::{Error, Result};
+use crate::market_data::{MarketDataHandler, MarketDataUpdate, TickData};
+use crate::pricing::VolatilitySurface;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Counters of a [`GreeksUpdater`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GreeksUpdaterStats {
+    /// Update passes run.
+    pub updates: u64,
+    /// Contracts repriced.
+    pub repriced: u64,
+    /// Contracts that could not be priced.
+    pub failed: u64,
+    /// Repricings that left an extended Greek limit breached.
+    #[serde(default)]
+    pub breaches: u64,
+}
+
+/// Mutable state of a [`GreeksUpdater`].
+#[derive(Debug, Default)]
+struct UpdaterState {
+    /// Last spot price.
+    spot: Option<Decimal>,
+    /// Last volatility surface.
+    surface: Option<VolatilitySurface>,
+    /// Whether every contract needs repricing.
+    all_dirty: bool,
+    /// Expirations needing repricing.
+    dirty_expirations: BTreeSet<String>,
+    /// Contracts needing repricing.
+    dirty_symbols: BTreeSet<String>,
+    /// Contracts priced at least once.
+    priced: HashSet<String>,
+    /// Counters.
+    stats: GreeksUpdaterStats,
+}
+
+/// Reprices positions on market data updates.
+pub struct GreeksUpdater {
+    /// Inventory whose Greeks are kept up to date.
+    inventory: Arc<InventoryManager>,
+    /// Time source for days to expiry.
+    clock: Arc<dyn Clock>,
+    /// Risk-free rate used for pricing.
+    rate: Decimal,
+    /// Market inputs and dirty sets.
+    state: Mutex<UpdaterState>,
+}
+
+impl GreeksUpdater {
+    /// Creates an updater with a zero rate.
+    #[must_use]
+    pub fn new(inventory: Arc<InventoryManager>, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            inventory,
+            clock,
+            rate: Decimal::ZERO,
+            state: Mutex::new(UpdaterState::default()),
+        }
+    }
+
+    /// Sets the risk-free rate used for pricing.
+    #[must_use]
+    pub fn with_rate(mut self, rate: Decimal) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    /// Returns the last spot price.
+    #[must_use]
+    pub fn spot(&self) -> Option<Decimal> {
+        self.lock().spot
+    }
+
+    /// Records a spot price and reprices every contract.
+    ///
+    /// Returns the number of contracts repriced.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the spot is not positive, or
+    /// `Error::NoDataAvailable` if no surface has been set yet.
+    pub fn on_spot(&self, spot: Decimal) -> Result<usize> {
+        if spot <= Decimal::ZERO {
+            return Err(Error::validation("spot must be positive"));
+        }
+        {
+            let mut state = self.lock();
+            if state.spot != Some(spot) {
+                state.spot = Some(spot);
+                state.all_dirty = true;
+            }
+        }
+        self.update()
+    }
+
+    /// Records a volatility surface and reprices the contracts of the
+    /// expirations whose smile changed.
+    ///
+    /// Returns the number of contracts repriced.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if no spot has been set yet.
+    pub fn on_surface(&self, surface: VolatilitySurface) -> Result<usize> {
+        let now_ms = self.clock.now_ms();
+        {
+            let mut state = self.lock();
+            let state = &mut *state;
+            if let Some(previous) = &state.surface {
+                let mut changed = BTreeSet::new();
+                self.inventory.for_each_position(|position| {
+                    let Some(coordinates) = self.inventory.coordinates(position.symbol()) else {
+                        return;
+                    };
+                    if changed.contains(&coordinates.expiration) {
+                        return;
+                    }
+                    let Some(days) = coordinates.days_to_expiry(now_ms) else {
+                        return;
+                    };
+                    if previous.smile(days).ok() != surface.smile(days).ok() {
+                        changed.insert(coordinates.expiration);
+                    }
+                });
+                state.dirty_expirations.extend(changed);
+            } else {
+                state.all_dirty = true;
+            }
+            state.surface = Some(surface);
+        }
+        self.update()
+    }
+
+    /// Queues a contract for repricing on the next update.
+    pub fn mark_dirty(&self, symbol: &str) {
+        self.lock().dirty_symbols.insert(symbol.to_string());
+    }
+
+    /// Queues every contract for repricing on the next update.
+    pub fn mark_all_dirty(&self) {
+        self.lock().all_dirty = true;
+    }
+
+    /// Reprices the queued contracts and the contracts never priced.
+    ///
+    /// Returns the number of contracts repriced. Contracts that cannot be
+    /// priced keep their Greeks and are retried on the next update.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if the spot or surface is missing.
+    pub fn update(&self) -> Result<usize> {
+        let now_ms = self.clock.now_ms();
+        let mut state = self.lock();
+        let (Some(spot), Some(surface)) = (state.spot, state.surface.clone()) else {
+            return Err(Error::no_data("greeks updater needs a spot and a surface"));
+        };
+        state.stats.updates += 1;
+
+        let mut dirty: Vec<(String, ChainCoordinates)> = Vec::new();
+        self.inventory.for_each_position(|position| {
+            let symbol = position.symbol();
+            let Some(coordinates) = self.inventory.coordinates(symbol) else {
+                return;
+            };
+            if state.all_dirty
+                || !state.priced.contains(symbol)
+                || state.dirty_symbols.contains(symbol)
+                || state.dirty_expirations.contains(&coordinates.expiration)
+            {
+                dirty.push((symbol.to_string(), coordinates));
+            }
+        });
+        state.all_dirty = false;
+        state.dirty_expirations.clear();
+        state.dirty_symbols.clear();
+
+        let mut repriced = 0;
+        for (symbol, coordinates) in dirty {
+            let Some(days) = coordinates
+                .days_to_expiry(now_ms)
+                .filter(|d| *d > Decimal::ZERO)
+            else {
+                continue;
+            };
+            let greeks = surface
+                .pricing_params(
+                    spot,
+                    Decimal::from(coordinates.strike),
+                    days,
+                    coordinates.style,
+                )
+                .and_then(|params| params.with_rate(self.rate).extended_greeks())
+                .and_then(|greeks| self.inventory.set_extended_greeks(&symbol, greeks));
+            match greeks {
+                Ok(breach) => {
+                    state.priced.insert(symbol);
+                    state.stats.breaches += u64::from(breach.is_some());
+                    repriced += 1;
+                }
+                Err(_) => {
+                    state.priced.remove(&symbol);
+                    state.stats.failed += 1;
+                }
+            }
+        }
+        state.stats.repriced += repriced as u64;
+        Ok(repriced)
+    }
+
+    /// Returns the updater's counters.
+    #[must_use]
+    pub fn stats(&self) -> GreeksUpdaterStats {
+        self.lock().stats
+    }
+
+    fn lock(&self) -> MutexGuard<'_, UpdaterState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl MarketDataHandler for GreeksUpdater {
+    /// Reprices on spot ticks of the inventory's underlying; other ticks,
+    /// and spot ticks before a surface is set, are ignored.
+    fn on_tick(&mut self, tick: &TickData) -> Result<()> {
+        match &tick.update {
+            MarketDataUpdate::Spot { underlying, price }
+                if underlying == self.inventory.underlying() =>
+            {
+                match self.on_spot(*price) {
+                    Err(Error::NoDataAvailable { .. }) => Ok(()),
+                    other => other.map(|_| ()),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::inventory::PositionLimits;
+    use crate::pricing::SmileParams;
+    use rust_decimal_macros::dec;
+
+    /// 2024-01-01T00:00:00Z.
+    const NOW_MS: u64 = 1_704_067_200_000;
+    const NEAR: &str = "BTC-20240131-100-C";
+    const FAR: &str = "BTC-20240401-100-P";
+
+    fn surface(near_vol: Decimal, far_vol: Decimal) -> VolatilitySurface {
+        VolatilitySurface::new()
+            .with_pillar(
+                dec!(30),
+                SmileParams::new(near_vol, Decimal::ZERO, Decimal::ZERO),
+            )
+            .with_pillar(
+                dec!(91),
+                SmileParams::new(far_vol, Decimal::ZERO, Decimal::ZERO),
+            )
+    }
+
+    fn setup() -> (Arc<InventoryManager>, GreeksUpdater) {
+        let inventory = Arc::new(InventoryManager::new("BTC", PositionLimits::default()).unwrap());
+        for symbol in [NEAR, FAR, "BTC-PERP"] {
+            inventory.record_trade(symbol, dec!(1), dec!(1)).unwrap();
+        }
+        let updater =
+            GreeksUpdater::new(Arc::clone(&inventory), Arc::new(ManualClock::new(NOW_MS)));
+        (inventory, updater)
+    }
+
+    #[test]
+    fn test_spot_reprices_every_option() {
+        let (inventory, updater) = setup();
+        assert!(updater.on_spot(dec!(100)).is_err());
+        assert_eq!(
+            updater.on_surface(surface(dec!(0.5), dec!(0.5))).unwrap(),
+            2
+        );
+        let call_delta = inventory.position(NEAR).unwrap().unit_greeks().delta;
+        assert!(call_delta > dec!(0.5));
+        assert!(
+            inventory
+                .position("BTC-PERP")
+                .unwrap()
+                .unit_greeks()
+                .delta
+                .is_zero()
+        );
+
+        assert_eq!(updater.on_spot(dec!(110)).unwrap(), 2);
+        assert!(inventory.position(NEAR).unwrap().unit_greeks().delta > call_delta);
+        // Cross Greeks reach the inventory's aggregates too.
+        assert!(!inventory.total_extended_greeks().charm.is_zero());
+        // An unchanged spot with nothing dirty reprices nothing.
+        assert_eq!(updater.on_spot(dec!(110)).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_surface_update_reprices_changed_expirations_only() {
+        let (inventory, updater) = setup();
+        updater.on_spot(dec!(100)).unwrap_err();
+        updater.on_surface(surface(dec!(0.5), dec!(0.5))).unwrap();
+        let far_vega = inventory.position(FAR).unwrap().unit_greeks().vega;
+
+        // The near pillar moves; the far expiration sits on its own pillar.
+        assert_eq!(
+            updater.on_surface(surface(dec!(0.6), dec!(0.5))).unwrap(),
+            1
+        );
+        assert_eq!(
+            inventory.position(FAR).unwrap().unit_greeks().vega,
+            far_vega
+        );
+
+        // A new position is priced on the next update.
+        inventory
+            .record_trade("BTC-20240131-120-C", dec!(1), dec!(1))
+            .unwrap();
+        updater.mark_dirty(FAR);
+        assert_eq!(updater.update().unwrap(), 2);
+        assert_eq!(updater.stats().repriced, 5);
+    }
+}
@@ -52,11 +52,36 @@
 //! - ATM strike lookup at any level
 //! - Statistics aggregation across the hierarchy
 //!
+//! Linear instruments used for hedging (futures, perpetuals, spot pairs) are
+//! held by the `UnderlyingOrderBookManager` next to the option hierarchy as
+//! `LinearOrderBook`s, with their own quotes and mark prices.
+//!
 //! ## Module Structure
 //!
 //! | Module | Description |
 //! |--------|-------------|
 //! | [`orderbook`] | Hierarchical order book structure with all managers |
+//! | [`adapters`] | Order entry types, routing with idempotency protection and optimistic order tracking |
+//! | [`quoting`] | Quote generation, parity consistency and market-maker program coverage |
+//! | [`inventory`] | Positions, limits and tied trades with linear and inverse (coin-settled) P&L |
+//! | [`pricing`] | Greeks, Black-Scholes pricing (with an `f64` batch path for quoting), volatility surface, vega ladder, forward reference rolls and parity-implied rates |
+//! | [`market_maker`] | Quoting pipeline facade with user hooks for signals, logging and vetoes |
+//! | [`perf`] | Named performance budgets with a machine-readable pass/fail report (`cargo perf`) |
+//! | [`pnl`] | P&L attribution, intraday theta accrual, round-trip explain, mark overrides and return on capital |
+//! | [`hedging`] | Delta hedging with internalization into our own option books |
+//! | [`risk`] | Risk limits, controller, dashboard snapshot and historical stress scenarios |
+//! | [`account`] | Per-account inventory, P&L, risk limits and own orders with a consolidated view |
+//! | [`alerting`] | Expression-based alert rules over exposed metrics |
+//! | [`recovery`] | Journaled state with pluggable journal and snapshot stores, and a snapshot-and-replay restart drill |
+//! | [`history`] | Bounded history with retention, rollups, archival and scheduled compaction |
+//! | `shm` | Shared-memory hot data for multi-process deployments (`shm` feature) |
+//! | `http` | Embedded REST endpoint for health, stats and manual controls (`http` feature) |
+//! | `metrics` | Latency histograms and fill, cancel and reject counters with Prometheus text exposition (`metrics` feature) |
+//! | [`clock`] | Clock abstraction and batched time-to-expiry updates |
+//! | [`config`] | Market maker configuration with validated hot reload |
+//! | [`backtest`] | Fill simulation for backtests on public trade prints |
+//! | [`market_data`] | Market data ticks and a paced historical replay engine |
+//! | `test_support` | Reusable position, P&L and limit invariants with a seeded case generator (`test-support` feature) |
 //! | [`error`] | Error types and `Result` type alias |
 //! | [`utils`] | Utility functions (e.g., date formatting) |
 //!
@@ -74,6 +99,7 @@
 //! - [`orderbook::OptionOrderBook`]: Single option order book
 //! - [`orderbook::Quote`]: Two-sided market representation
 //! - [`orderbook::QuoteUpdate`]: Quote change tracking
+//! - [`orderbook::ContractRegistry`]: Interns contract symbols into compact `ContractId`s
 //!
 //! ## Example Usage
 //!
@@ -166,6 +192,8 @@
 //! | `04_expiration_orderbook` | Expiration level with term structure |
 //! | `05_underlying_orderbook` | Underlying level (all expirations) |
 //! | `06_full_hierarchy` | Complete hierarchy with trading scenarios |
+//! | `07_shared_memory` | Cross-process reads of contract hot data (`--features shm`) |
+//! | `market_maker_sim` | Multi-hour simulated session across quoting, fills, hedging, risk and P&L, also run by the integration tests |
 //!
 //! Run examples with:
 //! ```bash
@@ -183,6 +211,9 @@
 //! - **expiration_bench**: Expiration order book operations
 //! - **underlying_bench**: Underlying order book operations
 //! - **hierarchy_bench**: Full hierarchy traversal and trading scenarios
+//! - **registry_bench**: Contract id interning and id-based lookups
+//! - **aggregation_bench**: Allocation-free quote and Greeks aggregation
+//! - **pricing_bench**: Fast `f64` batch pricing versus the OptionStratLib reference
 //!
 //! Run benchmarks with:
 //! ```bash
@@ -199,6 +230,7 @@
 //! - **Best Quote Lookup**: O(1) with caching
 //! - **Thread Safety**: Lock-free operations for concurrent access
 //! - **Hierarchy Traversal**: O(1) access via `DashMap`
+//! - **Contract Lookup**: Direct book access by interned `ContractId`, no string hashing
 //!
 //! ## Dependencies
 //!
@@ -209,8 +241,33 @@
 //! - **thiserror** (2.0): Error handling
 //! - **serde** (1.0): Serialization support
 
+pub mod account;
+pub mod adapters;
+pub mod alerting;
+pub mod backtest;
+pub mod clock;
+pub mod config;
 pub mod error;
+pub mod hedging;
+pub mod history;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod inventory;
+pub mod market_data;
+pub mod market_maker;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod orderbook;
+pub mod perf;
+pub mod pnl;
+pub mod pricing;
+pub mod quoting;
+pub mod recovery;
+pub mod risk;
+#[cfg(feature = "shm")]
+pub mod shm;
+#[cfg(feature = "test-support")]
+pub mod test_support;
 pub mod utils;
 
 pub use error::{Error, Result};
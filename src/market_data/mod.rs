@@ -0,0 +1,36 @@
+//! Market data module.
+//!
+//! This module provides the market data representation shared by live
+//! feeds and historical replay, and the [`ReplayEngine`] that drives a
+//! [`MarketDataHandler`] from recorded ticks so the quoting, hedging and
+//! risk pipeline can be backtested.
+//!
+//! ## Components
+//!
+//! - [`TickData`]: A timestamped [`MarketDataUpdate`] (quote, trade, spot or implied vol)
+//! - [`MarketDataHandler`]: Callbacks receiving ticks in time order
+//! - [`Normalizer`]: Decodes raw venue payloads through [`FeedParser`]s, with [`SequenceGap`] detection
+//! - [`DeribitParser`], [`OkxParser`], [`CmeParser`]: Venue parsers mapping instruments to contract symbols
+//! - [`ReplayEngine`]: Replays ticks from a file or iterator at a [`ReplaySpeed`]
+//!
+//! ## Recorded Files
+//!
+//! Recordings are JSON lines, one [`TickData`] per line:
+//!
+//! ```text
+//! {"timestamp_ms":1000,"update":{"spot":{"underlying":"BTC","price":"50000"}}}
+//! {"timestamp_ms":1250,"update":{"trade":{"symbol":"BTC-20240329-50000-C","price":105,"quantity":2,"aggressor":"BUY"}}}
+//! ```
+
+mod normalize;
+mod replay;
+mod tick;
+mod venues;
+
+pub use normalize::{
+    FeedParser, FeedScale, NormalizedBatch, Normalizer, NormalizerStats, ParsedMessage,
+    SequenceGap, SequenceRange, contract_symbol,
+};
+pub use replay::{ReplayEngine, ReplaySpeed, ReplayStats, read_ticks};
+pub use tick::{MarketDataHandler, MarketDataUpdate, TickData};
+pub use venues::{CmeParser, DeribitParser, OkxParser};
@@ -0,0 +1,503 @@
+//! Feed normalization module.
+//!
+//! This module provides the [`Normalizer`], which turns raw venue payloads
+//! into [`TickData`] through pluggable [`FeedParser`]s and checks each
+//! stream's sequence numbers for gaps.
+//!
+//! ## Symbols
+//!
+//! Parsers map venue instrument names to the chain's contract symbols,
+//! `UNDERLYING-YYYYMMDD-STRIKE-C|P`, so normalized updates can be routed to
+//! the books without further lookup.
+//!
+//! ## Sequencing
+//!
+//! A parsed message may carry the first and last sequence numbers it covers
+//! and, for venues that chain messages, the sequence of the previous one.
+//! Per venue and stream the normalizer remembers the last sequence seen:
+//!
+//! - a message at or before it is stale and dropped
+//! - a message that does not follow it is delivered and reported as a
+//!   [`SequenceGap`], so the caller can resubscribe or request a snapshot
+
+use super::tick::{MarketDataUpdate, TickData};
+use crate::error::{Error, Result};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Converts venue prices and sizes to book units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedScale {
+    /// Price of one book tick.
+    pub tick_size: Decimal,
+    /// Venue size of one book lot.
+    pub lot_size: Decimal,
+}
+
+impl Default for FeedScale {
+    fn default() -> Self {
+        Self {
+            tick_size: Decimal::ONE,
+            lot_size: Decimal::ONE,
+        }
+    }
+}
+
+impl FeedScale {
+    /// Creates a scale.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the tick or lot size is not
+    /// positive.
+    pub fn new(tick_size: Decimal, lot_size: Decimal) -> Result<Self> {
+        let scale = Self {
+            tick_size,
+            lot_size,
+        };
+        scale.validate()?;
+        Ok(scale)
+    }
+
+    /// Validates a scale built from its public fields or deserialized.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the tick or lot size is not
+    /// positive.
+    pub fn validate(&self) -> Result<()> {
+        if self.tick_size <= Decimal::ZERO || self.lot_size <= Decimal::ZERO {
+            return Err(Error::configuration(
+                "feed tick and lot sizes must be positive",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Converts a venue price to book ticks, rounding to the nearest tick.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MarketDataError` if the price is negative or the
+    /// tick size is zero.
+    pub fn price(&self, price: Decimal) -> Result<u128> {
+        price
+            .checked_div(self.tick_size)
+            .and_then(|ticks| ticks.round().to_u128())
+            .ok_or_else(|| Error::market_data(format!("invalid feed price {price}")))
+    }
+
+    /// Converts a venue size to book lots, rounding to the nearest lot.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MarketDataError` if the size is negative or the lot
+    /// size is zero.
+    pub fn quantity(&self, size: Decimal) -> Result<u64> {
+        size.checked_div(self.lot_size)
+            .and_then(|lots| lots.round().to_u64())
+            .ok_or_else(|| Error::market_data(format!("invalid feed size {size}")))
+    }
+}
+
+/// Sequence numbers covered by a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SequenceRange {
+    /// First sequence in the message.
+    pub first: u64,
+    /// Last sequence in the message.
+    pub last: u64,
+    /// Sequence of the previous message, for venues that chain messages
+    /// instead of numbering them contiguously.
+    pub previous: Option<u64>,
+}
+
+impl SequenceRange {
+    /// Creates a contiguous range.
+    #[must_use]
+    pub const fn new(first: u64, last: u64) -> Self {
+        Self {
+            first,
+            last,
+            previous: None,
+        }
+    }
+
+    /// Creates a single sequence chained to the previous one.
+    #[must_use]
+    pub const fn chained(sequence: u64, previous: u64) -> Self {
+        Self {
+            first: sequence,
+            last: sequence,
+            previous: Some(previous),
+        }
+    }
+}
+
+/// A venue payload decoded into updates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedMessage {
+    /// Stream the sequence numbers belong to, e.g. a channel.
+    pub stream: String,
+    /// Sequence numbers covered, if the stream is sequenced.
+    pub sequence: Option<SequenceRange>,
+    /// Normalized ticks, with contract symbols.
+    pub ticks: Vec<TickData>,
+}
+
+/// Decodes one venue's payloads.
+pub trait FeedParser: Send + Sync {
+    /// Returns the venue name the parser is registered under.
+    fn venue(&self) -> &str;
+
+    /// Decodes a raw payload.
+    ///
+    /// Payloads that carry no market data, e.g. heartbeats, decode to a
+    /// message without ticks.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MarketDataError` if the payload is malformed.
+    fn parse(&self, payload: &str) -> Result<ParsedMessage>;
+}
+
+/// A break in a stream's sequence numbers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SequenceGap {
+    /// Venue name.
+    pub venue: String,
+    /// Stream name.
+    pub stream: String,
+    /// Last sequence seen before the gap.
+    pub last_seen: u64,
+    /// First sequence received after it.
+    pub received: u64,
+}
+
+/// Ticks decoded from one payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedBatch {
+    /// Normalized ticks, empty if the message was stale.
+    pub ticks: Vec<TickData>,
+    /// Gap detected before this message.
+    pub gap: Option<SequenceGap>,
+}
+
+/// Counters of a [`Normalizer`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NormalizerStats {
+    /// Payloads decoded.
+    pub messages: u64,
+    /// Ticks delivered.
+    pub ticks: u64,
+    /// Sequence gaps detected.
+    pub gaps: u64,
+    /// Stale or duplicate messages dropped.
+    pub stale: u64,
+    /// Payloads that failed to decode.
+    pub errors: u64,
+}
+
+/// Normalizes raw feeds from several venues.
+#[derive(Default)]
+pub struct Normalizer {
+    /// Parsers by venue.
+    parsers: HashMap<String, Arc<dyn FeedParser>>,
+    /// Last sequence per venue and stream.
+    sequences: HashMap<(String, String), u64>,
+    /// Counters.
+    stats: NormalizerStats,
+}
+
+impl Normalizer {
+    /// Creates a normalizer without parsers.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a parser under its venue name, replacing any previous one.
+    #[must_use]
+    pub fn with_parser(mut self, parser: Arc<dyn FeedParser>) -> Self {
+        self.register(parser);
+        self
+    }
+
+    /// Registers a parser under its venue name, replacing any previous one.
+    pub fn register(&mut self, parser: Arc<dyn FeedParser>) {
+        self.parsers.insert(parser.venue().to_string(), parser);
+    }
+
+    /// Returns the registered venue names, sorted.
+    #[must_use]
+    pub fn venues(&self) -> Vec<String> {
+        let mut venues: Vec<String> = self.parsers.keys().cloned().collect();
+        venues.sort();
+        venues
+    }
+
+    /// Returns the last sequence seen on a stream.
+    #[must_use]
+    pub fn last_sequence(&self, venue: &str, stream: &str) -> Option<u64> {
+        self.sequences
+            .get(&(venue.to_string(), stream.to_string()))
+            .copied()
+    }
+
+    /// Forgets a stream's sequence, e.g. after resubscribing.
+    pub fn reset_sequence(&mut self, venue: &str, stream: &str) -> bool {
+        self.sequences
+            .remove(&(venue.to_string(), stream.to_string()))
+            .is_some()
+    }
+
+    /// Returns the counters.
+    #[must_use]
+    pub const fn stats(&self) -> NormalizerStats {
+        self.stats
+    }
+
+    /// Decodes a venue payload and checks its sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `venue` - Venue the payload came from
+    /// * `payload` - Raw payload
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MarketDataError` if no parser is registered for the
+    /// venue or the payload is malformed.
+    pub fn normalize(&mut self, venue: &str, payload: &str) -> Result<NormalizedBatch> {
+        let parser = self
+            .parsers
+            .get(venue)
+            .ok_or_else(|| Error::market_data(format!("no feed parser for venue {venue}")))?;
+        let message = match parser.parse(payload) {
+            Ok(message) => message,
+            Err(e) => {
+                self.stats.errors += 1;
+                return Err(e);
+            }
+        };
+        self.stats.messages += 1;
+
+        let mut gap = None;
+        if let Some(range) = message.sequence {
+            let key = (venue.to_string(), message.stream.clone());
+            if let Some(&last_seen) = self.sequences.get(&key) {
+                if range.last <= last_seen {
+                    self.stats.stale += 1;
+                    return Ok(NormalizedBatch {
+                        ticks: Vec::new(),
+                        gap: None,
+                    });
+                }
+                let follows = range
+                    .previous
+                    .map_or(range.first == last_seen + 1, |p| p == last_seen);
+                if !follows {
+                    self.stats.gaps += 1;
+                    gap = Some(SequenceGap {
+                        venue: venue.to_string(),
+                        stream: message.stream.clone(),
+                        last_seen,
+                        received: range.first,
+                    });
+                }
+            }
+            self.sequences.insert(key, range.last);
+        }
+
+        self.stats.ticks += message.ticks.len() as u64;
+        Ok(NormalizedBatch {
+            ticks: message.ticks,
+            gap,
+        })
+    }
+}
+
+/// Builds a contract symbol from its parts.
+///
+/// # Arguments
+///
+/// * `underlying` - Underlying symbol
+/// * `expiration` - Expiration as `YYYYMMDD`
+/// * `strike` - Strike price
+/// * `is_call` - True for a call, false for a put
+#[must_use]
+pub fn contract_symbol(underlying: &str, expiration: &str, strike: u64, is_call: bool) -> String {
+    let style = if is_call { 'C' } else { 'P' };
+    format!("{underlying}-{expiration}-{strike}-{style}")
+}
+
+/// Returns the quote update of a contract.
+pub(crate) fn quote_update(
+    symbol: String,
+    scale: &FeedScale,
+    bid: Option<(Decimal, Decimal)>,
+    ask: Option<(Decimal, Decimal)>,
+) -> Result<MarketDataUpdate> {
+    let side = |level: Option<(Decimal, Decimal)>| -> Result<(Option<u128>, u64)> {
+        match level {
+            Some((price, size)) if size > Decimal::ZERO => {
+                Ok((Some(scale.price(price)?), scale.quantity(size)?))
+            }
+            _ => Ok((None, 0)),
+        }
+    };
+    let (bid_price, bid_size) = side(bid)?;
+    let (ask_price, ask_size) = side(ask)?;
+    Ok(MarketDataUpdate::Quote {
+        symbol,
+        bid_price,
+        bid_size,
+        ask_price,
+        ask_size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    struct Numbered;
+
+    impl FeedParser for Numbered {
+        fn venue(&self) -> &str {
+            "test"
+        }
+
+        // Payload: "<stream> <first> <last>".
+        fn parse(&self, payload: &str) -> Result<ParsedMessage> {
+            let parts: Vec<&str> = payload.split(' ').collect();
+            let number = |i: usize| {
+                parts
+                    .get(i)
+                    .and_then(|p| p.parse().ok())
+                    .ok_or_else(|| Error::market_data("bad payload"))
+            };
+            Ok(ParsedMessage {
+                stream: parts[0].to_string(),
+                sequence: Some(SequenceRange::new(number(1)?, number(2)?)),
+                ticks: vec![TickData::new(
+                    0,
+                    MarketDataUpdate::Spot {
+                        underlying: "BTC".to_string(),
+                        price: dec!(1),
+                    },
+                )],
+            })
+        }
+    }
+
+    #[test]
+    fn test_sequence_gaps_and_stale_messages() {
+        let mut normalizer = Normalizer::new().with_parser(Arc::new(Numbered));
+        assert!(normalizer.normalize("test", "a 1 2").unwrap().gap.is_none());
+        assert!(normalizer.normalize("test", "a 3 3").unwrap().gap.is_none());
+        // Another stream is sequenced separately.
+        assert!(
+            normalizer
+                .normalize("test", "b 40 40")
+                .unwrap()
+                .gap
+                .is_none()
+        );
+
+        let batch = normalizer.normalize("test", "a 6 7").unwrap();
+        let gap = batch.gap.unwrap();
+        assert_eq!((gap.last_seen, gap.received), (3, 6));
+        assert_eq!(batch.ticks.len(), 1);
+
+        assert!(
+            normalizer
+                .normalize("test", "a 5 5")
+                .unwrap()
+                .ticks
+                .is_empty()
+        );
+        assert_eq!(normalizer.last_sequence("test", "a"), Some(7));
+        assert!(normalizer.normalize("test", "a x").is_err());
+        assert!(normalizer.normalize("other", "a 1 1").is_err());
+
+        let stats = normalizer.stats();
+        assert_eq!(
+            (
+                stats.messages,
+                stats.ticks,
+                stats.gaps,
+                stats.stale,
+                stats.errors
+            ),
+            (5, 4, 1, 1, 1)
+        );
+    }
+
+    #[test]
+    fn test_chained_sequences() {
+        struct Chained;
+        impl FeedParser for Chained {
+            fn venue(&self) -> &str {
+                "chained"
+            }
+            fn parse(&self, payload: &str) -> Result<ParsedMessage> {
+                let (previous, sequence) = payload.split_once(' ').unwrap();
+                Ok(ParsedMessage {
+                    stream: "books".to_string(),
+                    sequence: Some(SequenceRange::chained(
+                        sequence.parse().unwrap(),
+                        previous.parse().unwrap(),
+                    )),
+                    ticks: Vec::new(),
+                })
+            }
+        }
+        let mut normalizer = Normalizer::new().with_parser(Arc::new(Chained));
+        assert!(
+            normalizer
+                .normalize("chained", "0 100")
+                .unwrap()
+                .gap
+                .is_none()
+        );
+        assert!(
+            normalizer
+                .normalize("chained", "100 250")
+                .unwrap()
+                .gap
+                .is_none()
+        );
+        assert!(
+            normalizer
+                .normalize("chained", "300 320")
+                .unwrap()
+                .gap
+                .is_some()
+        );
+        assert!(normalizer.reset_sequence("chained", "books"));
+        assert_eq!(
+            FeedScale::new(dec!(0.5), dec!(0.1))
+                .unwrap()
+                .price(dec!(1.26))
+                .unwrap(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_unvalidated_zero_scale_is_an_error() {
+        let scale = FeedScale {
+            tick_size: Decimal::ZERO,
+            lot_size: Decimal::ZERO,
+        };
+        assert!(scale.validate().is_err());
+        assert!(scale.price(dec!(1.25)).is_err());
+        assert!(scale.quantity(dec!(2)).is_err());
+        assert!(FeedScale::new(dec!(0.5), Decimal::ZERO).is_err());
+    }
+}
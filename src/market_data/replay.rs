@@ -0,0 +1,307 @@
+//! Market data replay module.
+//!
+//! This module provides the [`ReplayEngine`], which delivers recorded
+//! [`TickData`] to a [`MarketDataHandler`] in time order, paced in real
+//! time, accelerated, or as fast as possible.
+//!
+//! When a [`ManualClock`] is attached it is set to each tick's timestamp
+//! before the handler runs, so components reading the clock see replay
+//! time rather than wall time.
+
+use super::tick::{MarketDataHandler, TickData};
+use crate::clock::ManualClock;
+use crate::error::{Error, Result};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Pace at which recorded ticks are delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplaySpeed {
+    /// Gaps between ticks are waited out in wall time.
+    RealTime,
+    /// Gaps between ticks are divided by the factor.
+    Accelerated(Decimal),
+    /// Ticks are delivered without waiting.
+    AsFastAsPossible,
+}
+
+impl ReplaySpeed {
+    /// Returns the wall time to wait for a gap in event time, if any.
+    fn wall_delay(&self, event_ms: u64) -> Option<Duration> {
+        let factor = match self {
+            Self::RealTime => Decimal::ONE,
+            Self::Accelerated(factor) => *factor,
+            Self::AsFastAsPossible => return None,
+        };
+        let micros = (Decimal::from(event_ms) * Decimal::ONE_THOUSAND / factor).to_u64()?;
+        Some(Duration::from_micros(micros))
+    }
+}
+
+/// Outcome of a replay.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayStats {
+    /// Ticks delivered to the handler.
+    pub delivered: u64,
+    /// Ticks skipped because they were older than the last delivered tick.
+    pub out_of_order: u64,
+    /// Timestamp of the first delivered tick.
+    pub first_timestamp_ms: Option<u64>,
+    /// Timestamp of the last delivered tick.
+    pub last_timestamp_ms: Option<u64>,
+    /// Wall time the replay took, in milliseconds.
+    pub wall_time_ms: u64,
+}
+
+impl ReplayStats {
+    /// Returns the event time covered by the replay, in milliseconds.
+    #[must_use]
+    pub fn event_span_ms(&self) -> u64 {
+        match (self.first_timestamp_ms, self.last_timestamp_ms) {
+            (Some(first), Some(last)) => last - first,
+            _ => 0,
+        }
+    }
+}
+
+/// Replays recorded market data into a handler.
+#[derive(Debug, Clone)]
+pub struct ReplayEngine {
+    /// Delivery pace.
+    speed: ReplaySpeed,
+    /// Clock moved to each tick's timestamp.
+    clock: Option<Arc<ManualClock>>,
+}
+
+impl ReplayEngine {
+    /// Creates a replay engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if an accelerated factor is not
+    /// positive.
+    pub fn new(speed: ReplaySpeed) -> Result<Self> {
+        if let ReplaySpeed::Accelerated(factor) = speed
+            && factor <= Decimal::ZERO
+        {
+            return Err(Error::configuration(
+                "replay acceleration factor must be positive",
+            ));
+        }
+        Ok(Self { speed, clock: None })
+    }
+
+    /// Sets a clock that is moved to each tick's timestamp before dispatch.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<ManualClock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Returns the delivery pace.
+    #[must_use]
+    pub const fn speed(&self) -> ReplaySpeed {
+        self.speed
+    }
+
+    /// Replays ticks from an iterator.
+    ///
+    /// # Arguments
+    ///
+    /// * `ticks` - Ticks in time order; older ticks are skipped and counted
+    /// * `handler` - Receives every delivered tick
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error raised by the handler; the replay stops there.
+    pub fn replay<I, H>(&self, ticks: I, handler: &mut H) -> Result<ReplayStats>
+    where
+        I: IntoIterator<Item = TickData>,
+        H: MarketDataHandler + ?Sized,
+    {
+        self.run(ticks.into_iter().map(Ok), handler)
+    }
+
+    /// Replays a JSON lines recording, one [`TickData`] per line.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::IoError` if the file cannot be read,
+    /// `Error::SerializationError` if a line is not a tick, or the first
+    /// error raised by the handler.
+    pub fn replay_file<H>(&self, path: impl AsRef<Path>, handler: &mut H) -> Result<ReplayStats>
+    where
+        H: MarketDataHandler + ?Sized,
+    {
+        self.run(read_ticks(path)?, handler)
+    }
+
+    fn run<I, H>(&self, ticks: I, handler: &mut H) -> Result<ReplayStats>
+    where
+        I: Iterator<Item = Result<TickData>>,
+        H: MarketDataHandler + ?Sized,
+    {
+        let started = Instant::now();
+        let mut stats = ReplayStats::default();
+        for tick in ticks {
+            let tick = tick?;
+            if stats
+                .last_timestamp_ms
+                .is_some_and(|last| tick.timestamp_ms < last)
+            {
+                stats.out_of_order += 1;
+                continue;
+            }
+            let first = *stats.first_timestamp_ms.get_or_insert(tick.timestamp_ms);
+            if let Some(delay) = self.speed.wall_delay(tick.timestamp_ms - first) {
+                let elapsed = started.elapsed();
+                if delay > elapsed {
+                    std::thread::sleep(delay - elapsed);
+                }
+            }
+            if let Some(clock) = &self.clock {
+                clock.set(tick.timestamp_ms);
+            }
+            handler.on_tick(&tick)?;
+            stats.delivered += 1;
+            stats.last_timestamp_ms = Some(tick.timestamp_ms);
+        }
+        stats.wall_time_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        handler.on_replay_end(&stats);
+        Ok(stats)
+    }
+}
+
+/// Reads a JSON lines recording lazily, one [`TickData`] per line.
+///
+/// Blank lines are ignored.
+///
+/// # Errors
+///
+/// Returns `Error::IoError` if the file cannot be opened. Read and parse
+/// errors are yielded per line.
+pub fn read_ticks(path: impl AsRef<Path>) -> Result<impl Iterator<Item = Result<TickData>>> {
+    let reader = BufReader::new(File::open(path)?);
+    Ok(reader.lines().filter_map(|line| match line {
+        Ok(line) if line.trim().is_empty() => None,
+        Ok(line) => Some(serde_json::from_str(&line).map_err(Error::from)),
+        Err(e) => Some(Err(Error::from(e))),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::Clock;
+    use crate::market_data::MarketDataUpdate;
+    use orderbook_rs::Side;
+    use rust_decimal_macros::dec;
+    use std::io::Write;
+
+    struct Recorder {
+        clock: Arc<ManualClock>,
+        seen: Vec<(u64, u64)>,
+        ended: bool,
+    }
+
+    impl MarketDataHandler for Recorder {
+        fn on_tick(&mut self, tick: &TickData) -> Result<()> {
+            self.seen.push((tick.timestamp_ms, self.clock.now_ms()));
+            Ok(())
+        }
+
+        fn on_replay_end(&mut self, _stats: &ReplayStats) {
+            self.ended = true;
+        }
+    }
+
+    fn spot(timestamp_ms: u64, price: Decimal) -> TickData {
+        TickData::new(
+            timestamp_ms,
+            MarketDataUpdate::Spot {
+                underlying: "BTC".to_string(),
+                price,
+            },
+        )
+    }
+
+    #[test]
+    fn test_replay_drives_handler_and_clock() {
+        let clock = Arc::new(ManualClock::new(0));
+        let engine = ReplayEngine::new(ReplaySpeed::AsFastAsPossible)
+            .unwrap()
+            .with_clock(Arc::clone(&clock));
+        let mut recorder = Recorder {
+            clock: Arc::clone(&clock),
+            seen: Vec::new(),
+            ended: false,
+        };
+        let ticks = [
+            spot(1_000, dec!(50000)),
+            spot(2_000, dec!(50100)),
+            spot(1_500, dec!(49900)),
+            spot(60_000, dec!(50200)),
+        ];
+
+        let stats = engine.replay(ticks, &mut recorder).unwrap();
+        assert_eq!(
+            recorder.seen,
+            vec![(1_000, 1_000), (2_000, 2_000), (60_000, 60_000)]
+        );
+        assert!(recorder.ended);
+        assert_eq!((stats.delivered, stats.out_of_order), (3, 1));
+        assert_eq!(stats.event_span_ms(), 59_000);
+        assert!(ReplayEngine::new(ReplaySpeed::Accelerated(dec!(0))).is_err());
+    }
+
+    #[test]
+    fn test_accelerated_replay_from_file() {
+        let trade = TickData::new(
+            1_200,
+            MarketDataUpdate::Trade {
+                symbol: "BTC-C".to_string(),
+                price: 105,
+                quantity: 2,
+                aggressor: Side::Buy,
+            },
+        );
+        let path = std::env::temp_dir().join(format!("replay-{}.jsonl", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        for tick in [spot(1_000, dec!(50000)), trade.clone()] {
+            writeln!(file, "{}", serde_json::to_string(&tick).unwrap()).unwrap();
+        }
+        writeln!(file).unwrap();
+        drop(file);
+
+        let clock = Arc::new(ManualClock::new(0));
+        let mut recorder = Recorder {
+            clock: Arc::clone(&clock),
+            seen: Vec::new(),
+            ended: false,
+        };
+        // 200ms of event time at 10x takes at least 20ms.
+        let stats = ReplayEngine::new(ReplaySpeed::Accelerated(dec!(10)))
+            .unwrap()
+            .replay_file(&path, &mut recorder)
+            .unwrap();
+        assert_eq!(stats.delivered, 2);
+        assert!(stats.wall_time_ms >= 20);
+        let print = trade.public_trade().unwrap();
+        assert_eq!((print.price, print.timestamp_ms), (105, 1_200));
+
+        std::fs::write(&path, "not a tick\n").unwrap();
+        assert!(
+            ReplayEngine::new(ReplaySpeed::AsFastAsPossible)
+                .unwrap()
+                .replay_file(&path, &mut recorder)
+                .is_err()
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,114 @@
+//! Tick data module.
+//!
+//! This module provides [`TickData`], a timestamped [`MarketDataUpdate`],
+//! and the [`MarketDataHandler`] trait that consumes ticks.
+
+use super::replay::ReplayStats;
+use crate::backtest::PublicTrade;
+use crate::error::Result;
+use orderbook_rs::Side;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// One market data event.
+///
+/// Book prices and quantities are in smallest units, matching
+/// `OptionOrderBook`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketDataUpdate {
+    /// Top of book of a contract.
+    Quote {
+        /// Contract symbol.
+        symbol: String,
+        /// Best bid price, if any.
+        bid_price: Option<u128>,
+        /// Size at the best bid.
+        bid_size: u64,
+        /// Best ask price, if any.
+        ask_price: Option<u128>,
+        /// Size at the best ask.
+        ask_size: u64,
+    },
+    /// Public trade print.
+    Trade {
+        /// Contract symbol.
+        symbol: String,
+        /// Trade price.
+        price: u128,
+        /// Trade quantity.
+        quantity: u64,
+        /// Side of the aggressor.
+        aggressor: Side,
+    },
+    /// Spot price of an underlying.
+    Spot {
+        /// Underlying symbol.
+        underlying: String,
+        /// Spot price.
+        price: Decimal,
+    },
+    /// Implied volatility of a contract.
+    ImpliedVol {
+        /// Contract symbol.
+        symbol: String,
+        /// Implied volatility.
+        iv: Decimal,
+    },
+}
+
+/// A market data update with its event time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickData {
+    /// Event time in milliseconds.
+    pub timestamp_ms: u64,
+    /// The update.
+    pub update: MarketDataUpdate,
+}
+
+impl TickData {
+    /// Creates a tick.
+    #[must_use]
+    pub const fn new(timestamp_ms: u64, update: MarketDataUpdate) -> Self {
+        Self {
+            timestamp_ms,
+            update,
+        }
+    }
+
+    /// Returns the trade print of a trade tick, for the backtest fill model.
+    #[must_use]
+    pub fn public_trade(&self) -> Option<PublicTrade> {
+        match &self.update {
+            MarketDataUpdate::Trade {
+                symbol,
+                price,
+                quantity,
+                aggressor,
+            } => Some(PublicTrade {
+                symbol: symbol.clone(),
+                price: *price,
+                quantity: *quantity,
+                aggressor: *aggressor,
+                timestamp_ms: self.timestamp_ms,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Consumer of market data ticks.
+///
+/// Live feeds and the [`super::ReplayEngine`] call the same handler, so a
+/// pipeline wired for production can be backtested unchanged.
+pub trait MarketDataHandler {
+    /// Called for every tick, in time order.
+    ///
+    /// # Errors
+    ///
+    /// An error stops a replay and is returned to its caller.
+    fn on_tick(&mut self, tick: &TickData) -> Result<()>;
+
+    /// Called once when a replay has delivered every tick.
+    fn on_replay_end(&mut self, _stats: &ReplayStats) {}
+}
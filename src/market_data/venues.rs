@@ -0,0 +1,581 @@
+//! Venue parser module.
+//!
+//! This module provides [`FeedParser`]s for Deribit and OKX JSON feeds and
+//! for CME-style FIX market data messages. Each maps the venue's instrument
+//! naming to the chain's contract symbols and its prices and sizes to book
+//! units through a [`FeedScale`].
+//!
+//! ## Supported Messages
+//!
+//! | Venue | Messages | Sequenced by |
+//! |-------|----------|--------------|
+//! | Deribit | `ticker.*` and `trades.*` subscriptions | `trade_seq` on trades |
+//! | OKX | `tickers` and `trades` channels | `seqId` / `prevSeqId` when present |
+//! | CME | FIX `35=W` / `35=X` with `269` entries (bid, offer, trade) | `34` (MsgSeqNum) per sender |
+
+use super::normalize::{
+    FeedParser, FeedScale, ParsedMessage, SequenceRange, contract_symbol, quote_update,
+};
+use super::tick::{MarketDataUpdate, TickData};
+use crate::error::{Error, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+use orderbook_rs::Side;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::FromPrimitive;
+use serde_json::Value;
+use std::str::FromStr;
+
+/// Parses Deribit JSON-RPC subscription notifications.
+///
+/// Instruments are named `BTC-29MAR24-50000-C`. `mark_iv` is quoted in
+/// percent and normalized to a fraction; `index_price` becomes a spot
+/// update for the currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DeribitParser {
+    /// Price and size conversion.
+    scale: FeedScale,
+}
+
+impl DeribitParser {
+    /// Creates a Deribit parser.
+    #[must_use]
+    pub const fn new(scale: FeedScale) -> Self {
+        Self { scale }
+    }
+
+    /// Maps a Deribit instrument name to a contract symbol.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MarketDataError` if the name is not an option.
+    pub fn symbol(instrument: &str) -> Result<String> {
+        let parts: Vec<&str> = instrument.split('-').collect();
+        let [underlying, expiry, strike, style] = parts[..] else {
+            return Err(Error::market_data(format!(
+                "not a Deribit option: {instrument}"
+            )));
+        };
+        let expiration = NaiveDate::parse_from_str(expiry, "%d%b%y")
+            .map_err(|_| Error::market_data(format!("bad Deribit expiry in {instrument}")))?;
+        option_symbol(
+            underlying,
+            &expiration.format("%Y%m%d").to_string(),
+            strike,
+            style,
+        )
+        .ok_or_else(|| Error::market_data(format!("bad Deribit option: {instrument}")))
+    }
+}
+
+impl FeedParser for DeribitParser {
+    fn venue(&self) -> &str {
+        "deribit"
+    }
+
+    fn parse(&self, payload: &str) -> Result<ParsedMessage> {
+        let value: Value = serde_json::from_str(payload)
+            .map_err(|e| Error::market_data(format!("bad Deribit payload: {e}")))?;
+        let Some(params) = value.get("params") else {
+            return Ok(empty_message());
+        };
+        let channel = text(params, "channel")?;
+        let data = field(params, "data")?;
+
+        if channel.starts_with("ticker.") {
+            let instrument = text(data, "instrument_name")?;
+            let symbol = Self::symbol(instrument)?;
+            let timestamp_ms = integer(data, "timestamp")?;
+            let level = |price: &str, size: &str| {
+                Some((decimal(data.get(price)?)?, decimal(data.get(size)?)?))
+            };
+            let mut updates = vec![quote_update(
+                symbol.clone(),
+                &self.scale,
+                level("best_bid_price", "best_bid_amount"),
+                level("best_ask_price", "best_ask_amount"),
+            )?];
+            if let Some(iv) = data.get("mark_iv").and_then(decimal) {
+                updates.push(MarketDataUpdate::ImpliedVol {
+                    symbol,
+                    iv: iv / Decimal::ONE_HUNDRED,
+                });
+            }
+            if let Some(price) = data.get("index_price").and_then(decimal) {
+                let underlying = instrument.split('-').next().unwrap_or_default();
+                updates.push(MarketDataUpdate::Spot {
+                    underlying: underlying.to_string(),
+                    price,
+                });
+            }
+            return Ok(ParsedMessage {
+                stream: channel.to_string(),
+                sequence: None,
+                ticks: stamp(timestamp_ms, updates),
+            });
+        }
+
+        if channel.starts_with("trades.") {
+            let trades = data
+                .as_array()
+                .ok_or_else(|| Error::market_data("Deribit trades must be an array"))?;
+            let mut ticks = Vec::with_capacity(trades.len());
+            let mut sequences = Vec::with_capacity(trades.len());
+            for trade in trades {
+                ticks.push(TickData::new(
+                    integer(trade, "timestamp")?,
+                    MarketDataUpdate::Trade {
+                        symbol: Self::symbol(text(trade, "instrument_name")?)?,
+                        price: self.scale.price(number(trade, "price")?)?,
+                        quantity: self.scale.quantity(number(trade, "amount")?)?,
+                        aggressor: side(text(trade, "direction")?)?,
+                    },
+                ));
+                sequences.extend(trade.get("trade_seq").and_then(Value::as_u64));
+            }
+            let sequence = sequences
+                .iter()
+                .min()
+                .zip(sequences.iter().max())
+                .map(|(first, last)| SequenceRange::new(*first, *last));
+            return Ok(ParsedMessage {
+                stream: channel.to_string(),
+                sequence,
+                ticks,
+            });
+        }
+
+        Ok(empty_message())
+    }
+}
+
+/// Parses OKX public WebSocket pushes.
+///
+/// Instruments are named `BTC-USD-240329-50000-C`; the contract symbol uses
+/// the base currency as underlying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OkxParser {
+    /// Price and size conversion.
+    scale: FeedScale,
+}
+
+impl OkxParser {
+    /// Creates an OKX parser.
+    #[must_use]
+    pub const fn new(scale: FeedScale) -> Self {
+        Self { scale }
+    }
+
+    /// Maps an OKX instrument id to a contract symbol.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MarketDataError` if the id is not an option.
+    pub fn symbol(instrument: &str) -> Result<String> {
+        let parts: Vec<&str> = instrument.split('-').collect();
+        let [underlying, _quote, expiry, strike, style] = parts[..] else {
+            return Err(Error::market_data(format!(
+                "not an OKX option: {instrument}"
+            )));
+        };
+        let expiration = NaiveDate::parse_from_str(expiry, "%y%m%d")
+            .map_err(|_| Error::market_data(format!("bad OKX expiry in {instrument}")))?;
+        option_symbol(
+            underlying,
+            &expiration.format("%Y%m%d").to_string(),
+            strike,
+            style,
+        )
+        .ok_or_else(|| Error::market_data(format!("bad OKX option: {instrument}")))
+    }
+}
+
+impl FeedParser for OkxParser {
+    fn venue(&self) -> &str {
+        "okx"
+    }
+
+    fn parse(&self, payload: &str) -> Result<ParsedMessage> {
+        let value: Value = serde_json::from_str(payload)
+            .map_err(|e| Error::market_data(format!("bad OKX payload: {e}")))?;
+        let (Some(arg), Some(data)) = (value.get("arg"), value.get("data")) else {
+            return Ok(empty_message());
+        };
+        let channel = text(arg, "channel")?;
+        let items = data
+            .as_array()
+            .ok_or_else(|| Error::market_data("OKX data must be an array"))?;
+
+        let mut ticks = Vec::with_capacity(items.len());
+        let mut sequence = None;
+        for item in items {
+            let symbol = Self::symbol(text(item, "instId")?)?;
+            let timestamp_ms = integer(item, "ts")?;
+            let update = match channel {
+                "tickers" => {
+                    let level = |price: &str, size: &str| {
+                        Some((decimal(item.get(price)?)?, decimal(item.get(size)?)?))
+                    };
+                    quote_update(
+                        symbol,
+                        &self.scale,
+                        level("bidPx", "bidSz"),
+                        level("askPx", "askSz"),
+                    )?
+                }
+                "trades" => MarketDataUpdate::Trade {
+                    symbol,
+                    price: self.scale.price(number(item, "px")?)?,
+                    quantity: self.scale.quantity(number(item, "sz")?)?,
+                    aggressor: side(text(item, "side")?)?,
+                },
+                _ => continue,
+            };
+            ticks.push(TickData::new(timestamp_ms, update));
+            if let (Ok(seq), Ok(previous)) = (integer(item, "seqId"), integer(item, "prevSeqId")) {
+                sequence = Some(SequenceRange::chained(seq, previous));
+            }
+        }
+        let stream = arg
+            .get("instId")
+            .and_then(Value::as_str)
+            .map_or_else(|| channel.to_string(), |id| format!("{channel}:{id}"));
+        Ok(ParsedMessage {
+            stream,
+            sequence,
+            ticks,
+        })
+    }
+}
+
+/// Parses CME-style FIX market data messages.
+///
+/// Fields are `tag=value` pairs separated by SOH or `|`. The contract is
+/// identified by `55` (underlying), `541` (maturity date, `YYYYMMDD`),
+/// `202` (strike) and `201` (`1` call, `0` put). Each `269` entry is a bid
+/// (`0`), offer (`1`) or trade (`2`) with price `270` and size `271`;
+/// trades take their aggressor from `5797` (`1` buy, `2` sell). Bid and
+/// offer entries describe the full top of book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CmeParser {
+    /// Price and size conversion.
+    scale: FeedScale,
+}
+
+impl CmeParser {
+    /// Creates a CME parser.
+    #[must_use]
+    pub const fn new(scale: FeedScale) -> Self {
+        Self { scale }
+    }
+}
+
+/// One `269` entry of a FIX message.
+#[derive(Debug, Default)]
+struct FixEntry<'a> {
+    kind: &'a str,
+    price: Option<&'a str>,
+    size: Option<&'a str>,
+    aggressor: Option<&'a str>,
+}
+
+impl FeedParser for CmeParser {
+    fn venue(&self) -> &str {
+        "cme"
+    }
+
+    fn parse(&self, payload: &str) -> Result<ParsedMessage> {
+        let mut tags: Vec<(&str, &str)> = Vec::new();
+        for field in payload.split(['\x01', '|']).filter(|f| !f.is_empty()) {
+            let (tag, value) = field
+                .split_once('=')
+                .ok_or_else(|| Error::market_data(format!("bad FIX field: {field}")))?;
+            tags.push((tag, value));
+        }
+        let get = |tag: &str| tags.iter().find(|(t, _)| *t == tag).map(|(_, v)| *v);
+        let require = |tag: &str| {
+            get(tag).ok_or_else(|| Error::market_data(format!("FIX tag {tag} missing")))
+        };
+
+        if !matches!(get("35"), Some("W" | "X")) {
+            return Ok(empty_message());
+        }
+        let stream = get("49").unwrap_or("cme").to_string();
+        let sequence = get("34")
+            .map(|s| {
+                s.parse::<u64>()
+                    .map_err(|_| Error::market_data(format!("bad FIX sequence {s}")))
+            })
+            .transpose()?
+            .map(|s| SequenceRange::new(s, s));
+        let sent = require("52")?;
+        let timestamp_ms = NaiveDateTime::parse_from_str(sent, "%Y%m%d-%H:%M:%S%.f")
+            .map_err(|_| Error::market_data(format!("bad FIX sending time {sent}")))?
+            .and_utc()
+            .timestamp_millis()
+            .max(0) as u64;
+        let style = match require("201")? {
+            "1" => "C",
+            "0" => "P",
+            other => return Err(Error::market_data(format!("bad FIX put/call {other}"))),
+        };
+        let symbol = option_symbol(require("55")?, require("541")?, require("202")?, style)
+            .ok_or_else(|| Error::market_data("bad FIX contract"))?;
+
+        let mut entries: Vec<FixEntry<'_>> = Vec::new();
+        for (tag, value) in &tags {
+            match (*tag, entries.last_mut()) {
+                ("269", _) => entries.push(FixEntry {
+                    kind: value,
+                    ..FixEntry::default()
+                }),
+                ("270", Some(entry)) => entry.price = Some(value),
+                ("271", Some(entry)) => entry.size = Some(value),
+                ("5797", Some(entry)) => entry.aggressor = Some(value),
+                _ => {}
+            }
+        }
+
+        let parse = |value: Option<&str>| {
+            value
+                .and_then(|v| Decimal::from_str(v).ok())
+                .ok_or_else(|| Error::market_data("bad FIX entry price or size"))
+        };
+        let (mut bid, mut ask, mut updates) = (None, None, Vec::new());
+        for entry in &entries {
+            match entry.kind {
+                "0" => bid = Some((parse(entry.price)?, parse(entry.size)?)),
+                "1" => ask = Some((parse(entry.price)?, parse(entry.size)?)),
+                "2" => updates.push(MarketDataUpdate::Trade {
+                    symbol: symbol.clone(),
+                    price: self.scale.price(parse(entry.price)?)?,
+                    quantity: self.scale.quantity(parse(entry.size)?)?,
+                    aggressor: match entry.aggressor {
+                        Some("1") => Side::Buy,
+                        Some("2") => Side::Sell,
+                        _ => return Err(Error::market_data("FIX trade without aggressor")),
+                    },
+                }),
+                _ => {}
+            }
+        }
+        if bid.is_some() || ask.is_some() {
+            updates.insert(0, quote_update(symbol, &self.scale, bid, ask)?);
+        }
+        Ok(ParsedMessage {
+            stream,
+            sequence,
+            ticks: stamp(timestamp_ms, updates),
+        })
+    }
+}
+
+/// Builds a contract symbol from venue strike and style fields.
+fn option_symbol(underlying: &str, expiration: &str, strike: &str, style: &str) -> Option<String> {
+    let strike = strike.parse::<u64>().ok()?;
+    let is_call = match style {
+        "C" | "c" => true,
+        "P" | "p" => false,
+        _ => return None,
+    };
+    Some(contract_symbol(underlying, expiration, strike, is_call))
+}
+
+/// Returns a message without market data.
+fn empty_message() -> ParsedMessage {
+    ParsedMessage {
+        stream: String::new(),
+        sequence: None,
+        ticks: Vec::new(),
+    }
+}
+
+/// Timestamps updates.
+fn stamp(timestamp_ms: u64, updates: Vec<MarketDataUpdate>) -> Vec<TickData> {
+    updates
+        .into_iter()
+        .map(|update| TickData::new(timestamp_ms, update))
+        .collect()
+}
+
+/// Returns a JSON field.
+fn field<'a>(value: &'a Value, key: &str) -> Result<&'a Value> {
+    value
+        .get(key)
+        .ok_or_else(|| Error::market_data(format!("field {key} missing")))
+}
+
+/// Returns a JSON string field.
+fn text<'a>(value: &'a Value, key: &str) -> Result<&'a str> {
+    field(value, key)?
+        .as_str()
+        .ok_or_else(|| Error::market_data(format!("field {key} must be a string")))
+}
+
+/// Returns a JSON number or numeric string as a decimal.
+fn decimal(value: &Value) -> Option<Decimal> {
+    match value {
+        Value::String(s) => Decimal::from_str(s).ok(),
+        Value::Number(n) => n
+            .as_i64()
+            .map(Decimal::from)
+            .or_else(|| n.as_f64().and_then(Decimal::from_f64)),
+        _ => None,
+    }
+}
+
+/// Returns a decimal field.
+fn number(value: &Value, key: &str) -> Result<Decimal> {
+    decimal(field(value, key)?)
+        .ok_or_else(|| Error::market_data(format!("field {key} must be numeric")))
+}
+
+/// Returns a non-negative integer field, given as a number or a string.
+fn integer(value: &Value, key: &str) -> Result<u64> {
+    let value = field(value, key)?;
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+        .ok_or_else(|| Error::market_data(format!("field {key} must be an integer")))
+}
+
+/// Parses a venue trade direction.
+fn side(direction: &str) -> Result<Side> {
+    match direction {
+        "buy" | "BUY" | "Buy" => Ok(Side::Buy),
+        "sell" | "SELL" | "Sell" => Ok(Side::Sell),
+        other => Err(Error::market_data(format!("bad trade direction {other}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::Normalizer;
+    use rust_decimal_macros::dec;
+    use std::sync::Arc;
+
+    fn scale() -> FeedScale {
+        FeedScale::new(dec!(0.0005), dec!(0.1)).unwrap()
+    }
+
+    #[test]
+    fn test_deribit_ticker_and_trades() {
+        let mut normalizer = Normalizer::new().with_parser(Arc::new(DeribitParser::new(scale())));
+        let ticker = r#"{"jsonrpc":"2.0","method":"subscription","params":{
+            "channel":"ticker.BTC-29MAR24-50000-C.100ms",
+            "data":{"instrument_name":"BTC-29MAR24-50000-C","timestamp":1700000000000,
+                "best_bid_price":0.0425,"best_bid_amount":12.5,
+                "best_ask_price":0.044,"best_ask_amount":3,
+                "mark_iv":55.2,"index_price":43000.5}}}"#;
+        let batch = normalizer.normalize("deribit", ticker).unwrap();
+        assert_eq!(batch.ticks.len(), 3);
+        assert_eq!(
+            batch.ticks[0].update,
+            MarketDataUpdate::Quote {
+                symbol: "BTC-20240329-50000-C".to_string(),
+                bid_price: Some(85),
+                bid_size: 125,
+                ask_price: Some(88),
+                ask_size: 30,
+            }
+        );
+        assert_eq!(
+            batch.ticks[1].update,
+            MarketDataUpdate::ImpliedVol {
+                symbol: "BTC-20240329-50000-C".to_string(),
+                iv: dec!(0.552),
+            }
+        );
+
+        let trades = |first: u64| {
+            format!(
+                r#"{{"params":{{"channel":"trades.BTC-5APR24-45000-P.raw","data":[
+                {{"instrument_name":"BTC-5APR24-45000-P","timestamp":1,"price":0.01,"amount":1,"direction":"sell","trade_seq":{first}}},
+                {{"instrument_name":"BTC-5APR24-45000-P","timestamp":2,"price":0.0105,"amount":0.5,"direction":"buy","trade_seq":{}}}]}}}}"#,
+                first + 1
+            )
+        };
+        let batch = normalizer.normalize("deribit", &trades(10)).unwrap();
+        assert!(batch.gap.is_none());
+        assert_eq!(
+            batch.ticks[0].update,
+            MarketDataUpdate::Trade {
+                symbol: "BTC-20240405-45000-P".to_string(),
+                price: 20,
+                quantity: 10,
+                aggressor: Side::Sell,
+            }
+        );
+        let gap = normalizer
+            .normalize("deribit", &trades(14))
+            .unwrap()
+            .gap
+            .unwrap();
+        assert_eq!((gap.last_seen, gap.received), (11, 14));
+        assert!(
+            normalizer
+                .normalize("deribit", r#"{"jsonrpc":"2.0","result":"ok"}"#)
+                .unwrap()
+                .ticks
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_okx_tickers() {
+        let parser = OkxParser::new(FeedScale::new(dec!(0.0005), Decimal::ONE).unwrap());
+        let payload = r#"{"arg":{"channel":"tickers","instId":"BTC-USD-240329-50000-C"},
+            "data":[{"instId":"BTC-USD-240329-50000-C","bidPx":"0.042","bidSz":"20",
+            "askPx":"0.0435","askSz":"0","ts":"1700000000000"}]}"#;
+        let message = parser.parse(payload).unwrap();
+        assert_eq!(message.stream, "tickers:BTC-USD-240329-50000-C");
+        assert_eq!(message.ticks[0].timestamp_ms, 1_700_000_000_000);
+        assert_eq!(
+            message.ticks[0].update,
+            MarketDataUpdate::Quote {
+                symbol: "BTC-20240329-50000-C".to_string(),
+                bid_price: Some(84),
+                bid_size: 20,
+                ask_price: None,
+                ask_size: 0,
+            }
+        );
+        assert!(OkxParser::symbol("BTC-USD-SWAP").is_err());
+    }
+
+    #[test]
+    fn test_cme_fix_message() {
+        let parser = CmeParser::new(FeedScale::new(dec!(0.25), Decimal::ONE).unwrap());
+        let message = parser
+            .parse(
+                "8=FIX.4.4|35=X|49=CME|34=7|52=20240315-14:30:00.250|55=ES|541=20240621|\
+                 202=5000|201=0|269=0|270=41.25|271=12|269=1|270=41.75|271=8|\
+                 269=2|270=41.5|271=3|5797=2|",
+            )
+            .unwrap();
+        assert_eq!(message.stream, "CME");
+        assert_eq!(message.sequence, Some(SequenceRange::new(7, 7)));
+        assert_eq!(message.ticks.len(), 2);
+        assert_eq!(
+            message.ticks[0].update,
+            MarketDataUpdate::Quote {
+                symbol: "ES-20240621-5000-P".to_string(),
+                bid_price: Some(165),
+                bid_size: 12,
+                ask_price: Some(167),
+                ask_size: 8,
+            }
+        );
+        assert_eq!(
+            message.ticks[1].update,
+            MarketDataUpdate::Trade {
+                symbol: "ES-20240621-5000-P".to_string(),
+                price: 166,
+                quantity: 3,
+                aggressor: Side::Sell,
+            }
+        );
+        assert!(parser.parse("35=0|34=8|").unwrap().ticks.is_empty());
+        assert!(parser.parse("35=X|34=9|52=bad|").is_err());
+    }
+}
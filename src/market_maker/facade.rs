@@ -0,0 +1,336 @@
+//! Market maker facade module.
+//!
+//! This module provides the [`MarketMaker`], which ties the quote engine,
+//! order router, inventory and risk controller into one pipeline and calls
+//! the registered [`Hooks`] at each step.
+
+use super::hooks::{HookDecision, Hooks};
+use crate::adapters::{OrderRequest, OrderResponse, OrderRouter};
+use crate::error::Result;
+use crate::inventory::InventoryManager;
+use crate::quoting::{GeneratedQuote, QuoteEngine, QuoteParams};
+use crate::risk::{RiskController, TradingState};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counters of hook activity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct HookStats {
+    /// Quotes withheld by a hook veto.
+    pub quotes_vetoed: u64,
+    /// Orders dropped by a hook veto.
+    pub orders_vetoed: u64,
+    /// Fills reported to hooks.
+    pub fills: u64,
+    /// Trading state changes reported to hooks.
+    pub state_changes: u64,
+}
+
+/// Quoting pipeline facade with user hooks.
+pub struct MarketMaker {
+    /// Quote generation.
+    engine: QuoteEngine,
+    /// Order entry.
+    router: OrderRouter,
+    /// Position book.
+    inventory: Arc<InventoryManager>,
+    /// Risk limits and trading state.
+    risk: Arc<RiskController>,
+    /// Hooks in registration order.
+    hooks: Vec<Arc<dyn Hooks>>,
+    /// Quotes withheld by a veto.
+    quotes_vetoed: AtomicU64,
+    /// Orders dropped by a veto.
+    orders_vetoed: AtomicU64,
+    /// Fills reported to hooks.
+    fills: AtomicU64,
+    /// Trading state changes reported to hooks.
+    state_changes: AtomicU64,
+}
+
+impl MarketMaker {
+    /// Creates a market maker without hooks.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - Quote engine
+    /// * `router` - Order router
+    /// * `inventory` - Position book fills are booked on
+    /// * `risk` - Risk controller holding the trading state
+    #[must_use]
+    pub fn new(
+        engine: QuoteEngine,
+        router: OrderRouter,
+        inventory: Arc<InventoryManager>,
+        risk: Arc<RiskController>,
+    ) -> Self {
+        Self {
+            engine,
+            router,
+            inventory,
+            risk,
+            hooks: Vec::new(),
+            quotes_vetoed: AtomicU64::new(0),
+            orders_vetoed: AtomicU64::new(0),
+            fills: AtomicU64::new(0),
+            state_changes: AtomicU64::new(0),
+        }
+    }
+
+    /// Registers hooks, called after those already registered.
+    #[must_use]
+    pub fn with_hooks(mut self, hooks: Arc<dyn Hooks>) -> Self {
+        self.hooks.push(hooks);
+        self
+    }
+
+    /// Returns the number of registered hooks.
+    #[must_use]
+    pub fn hook_count(&self) -> usize {
+        self.hooks.len()
+    }
+
+    /// Returns the quote engine.
+    #[must_use]
+    pub const fn engine(&self) -> &QuoteEngine {
+        &self.engine
+    }
+
+    /// Returns the order router.
+    #[must_use]
+    pub const fn router(&self) -> &OrderRouter {
+        &self.router
+    }
+
+    /// Returns the position book.
+    #[must_use]
+    pub fn inventory(&self) -> &InventoryManager {
+        &self.inventory
+    }
+
+    /// Returns the risk controller.
+    #[must_use]
+    pub fn risk(&self) -> &RiskController {
+        &self.risk
+    }
+
+    /// Generates a quote for a contract.
+    ///
+    /// Hooks may adjust the parameters once the theo is known, then veto the
+    /// generated quote.
+    ///
+    /// Returns `None` if a hook vetoed the quote.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::QuotingError` if the adjusted parameters are invalid.
+    pub fn quote(&self, symbol: &str, params: &QuoteParams) -> Result<Option<GeneratedQuote>> {
+        let mut params = *params;
+        for hooks in &self.hooks {
+            hooks.on_theo_computed(symbol, &mut params);
+        }
+        let quote = self.engine.generate(&params)?;
+        if self.gate(|h| h.on_quote_generated(symbol, &quote)) {
+            self.quotes_vetoed.fetch_add(1, Ordering::Relaxed);
+            return Ok(None);
+        }
+        Ok(Some(quote))
+    }
+
+    /// Places an order unless a hook vetoes it.
+    ///
+    /// Returns `None` if a hook vetoed the order.
+    ///
+    /// # Errors
+    ///
+    /// Returns the router's error if the order cannot be placed.
+    pub fn place(&self, request: &OrderRequest) -> Result<Option<OrderResponse>> {
+        if self.gate(|h| h.pre_order_place(request)) {
+            self.orders_vetoed.fetch_add(1, Ordering::Relaxed);
+            return Ok(None);
+        }
+        self.router.submit(request).map(Some)
+    }
+
+    /// Books a fill on the inventory and reports it to the hooks.
+    ///
+    /// Returns the P&L realized by the fill.
+    ///
+    /// # Errors
+    ///
+    /// Returns the inventory's error if the fill cannot be booked; hooks are
+    /// not called in that case.
+    pub fn on_fill(&self, symbol: &str, quantity: Decimal, price: Decimal) -> Result<Decimal> {
+        let realized = self.inventory.record_trade(symbol, quantity, price)?;
+        self.fills.fetch_add(1, Ordering::Relaxed);
+        for hooks in &self.hooks {
+            hooks.on_fill(symbol, quantity, price);
+        }
+        Ok(realized)
+    }
+
+    /// Sets the trading state, reporting a change to the hooks.
+    ///
+    /// Returns true if the state changed.
+    pub fn set_trading_state(&self, state: TradingState) -> bool {
+        let previous = self.risk.state();
+        if previous == state {
+            return false;
+        }
+        self.risk.set_state(state);
+        if self.risk.state() != state {
+            return false;
+        }
+        self.state_changes.fetch_add(1, Ordering::Relaxed);
+        for hooks in &self.hooks {
+            hooks.on_risk_state_change(previous, state);
+        }
+        true
+    }
+
+    /// Returns the hook activity counters.
+    #[must_use]
+    pub fn hook_stats(&self) -> HookStats {
+        HookStats {
+            quotes_vetoed: self.quotes_vetoed.load(Ordering::Relaxed),
+            orders_vetoed: self.orders_vetoed.load(Ordering::Relaxed),
+            fills: self.fills.load(Ordering::Relaxed),
+            state_changes: self.state_changes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns true if any hook vetoes a step.
+    fn gate<F: Fn(&dyn Hooks) -> HookDecision>(&self, decide: F) -> bool {
+        self.hooks.iter().any(|h| decide(h.as_ref()).is_veto())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::PositionLimits;
+    use crate::orderbook::UnderlyingOrderBookManager;
+    use crate::quoting::SpreadCalculator;
+    use crate::risk::RiskLimits;
+    use optionstratlib::ExpirationDate;
+    use optionstratlib::prelude::pos_or_panic;
+    use orderbook_rs::Side;
+    use rust_decimal_macros::dec;
+    use std::sync::Mutex;
+
+    /// Skews the theo up, vetoes sells and records every event.
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl Recorder {
+        fn push(&self, event: String) {
+            self.events.lock().unwrap().push(event);
+        }
+    }
+
+    impl Hooks for Recorder {
+        fn on_theo_computed(&self, symbol: &str, params: &mut QuoteParams) {
+            params.theo += dec!(1);
+            self.push(format!("theo {symbol}"));
+        }
+
+        fn on_quote_generated(&self, symbol: &str, _quote: &GeneratedQuote) -> HookDecision {
+            self.push(format!("quote {symbol}"));
+            HookDecision::Allow
+        }
+
+        fn pre_order_place(&self, request: &OrderRequest) -> HookDecision {
+            if request.side == Side::Sell {
+                return HookDecision::veto("no selling");
+            }
+            HookDecision::Allow
+        }
+
+        fn on_fill(&self, symbol: &str, quantity: Decimal, _price: Decimal) {
+            self.push(format!("fill {symbol} {quantity}"));
+        }
+
+        fn on_risk_state_change(&self, previous: TradingState, current: TradingState) {
+            self.push(format!("state {previous}->{current}"));
+        }
+    }
+
+    /// Vetoes every quote.
+    struct QuietHours;
+
+    impl Hooks for QuietHours {
+        fn on_quote_generated(&self, _symbol: &str, _quote: &GeneratedQuote) -> HookDecision {
+            HookDecision::veto("quiet hours")
+        }
+    }
+
+    fn market_maker() -> (MarketMaker, String) {
+        let manager = Arc::new(UnderlyingOrderBookManager::new());
+        let symbol = manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(30.0)))
+            .get_or_create_strike(50000)
+            .call()
+            .symbol()
+            .to_string();
+        let inventory = InventoryManager::new("BTC", PositionLimits::default()).unwrap();
+        let mm = MarketMaker::new(
+            QuoteEngine::new(SpreadCalculator::new(10)),
+            OrderRouter::new(manager),
+            Arc::new(inventory),
+            Arc::new(RiskController::new(RiskLimits::default()).unwrap()),
+        );
+        (mm, symbol)
+    }
+
+    #[test]
+    fn test_hooks_called_along_pipeline() {
+        let recorder = Arc::new(Recorder::default());
+        let (mm, symbol) = market_maker();
+        let mm = mm.with_hooks(recorder.clone());
+        let params = QuoteParams::new(dec!(10), dec!(0.3));
+
+        let quote = mm.quote(&symbol, &params).unwrap().unwrap();
+        let plain = mm.engine().generate(&params).unwrap();
+        assert_eq!(quote.mid(), plain.mid() + dec!(1));
+
+        let buy = OrderRequest::new(symbol.as_str(), Side::Buy, 100, 10);
+        assert!(mm.place(&buy).unwrap().is_some());
+        let sell = OrderRequest::new(symbol.as_str(), Side::Sell, 110, 10);
+        assert!(mm.place(&sell).unwrap().is_none());
+
+        mm.on_fill(&symbol, dec!(5), dec!(11)).unwrap();
+        assert!(mm.set_trading_state(TradingState::ReducedRisk));
+        assert!(!mm.set_trading_state(TradingState::ReducedRisk));
+
+        let events = recorder.events.lock().unwrap().clone();
+        assert_eq!(
+            events,
+            vec![
+                format!("theo {symbol}"),
+                format!("quote {symbol}"),
+                format!("fill {symbol} 5"),
+                "state Active->ReducedRisk".to_string(),
+            ]
+        );
+        let stats = mm.hook_stats();
+        assert_eq!(
+            (stats.orders_vetoed, stats.fills, stats.state_changes),
+            (1, 1, 1)
+        );
+    }
+
+    #[test]
+    fn test_quote_veto_withholds_quote() {
+        let (mm, symbol) = market_maker();
+        let mm = mm.with_hooks(Arc::new(QuietHours));
+        assert_eq!(mm.hook_count(), 1);
+        let params = QuoteParams::new(dec!(10), dec!(0.3));
+        assert!(mm.quote(&symbol, &params).unwrap().is_none());
+        assert_eq!(mm.hook_stats().quotes_vetoed, 1);
+    }
+}
@@ -0,0 +1,62 @@
+//! Strategy hooks module.
+//!
+//! This module provides the [`Hooks`] trait. Every callback has a no-op
+//! default, so an implementation only overrides the points it cares about.
+//! Gating callbacks return a [`HookDecision`]; the first veto wins.
+
+use crate::adapters::OrderRequest;
+use crate::quoting::{GeneratedQuote, QuoteParams};
+use crate::risk::TradingState;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a gating hook.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HookDecision {
+    /// Let the pipeline continue.
+    #[default]
+    Allow,
+    /// Stop this step, with a reason for logs.
+    Veto(String),
+}
+
+impl HookDecision {
+    /// Creates a veto.
+    #[must_use]
+    pub fn veto(reason: impl Into<String>) -> Self {
+        Self::Veto(reason.into())
+    }
+
+    /// Returns true if the step is vetoed.
+    #[must_use]
+    pub const fn is_veto(&self) -> bool {
+        matches!(self, Self::Veto(_))
+    }
+}
+
+/// User callbacks at defined points of the quoting pipeline.
+///
+/// Hooks run synchronously on the quoting path, in registration order, and
+/// must be cheap.
+pub trait Hooks: Send + Sync {
+    /// Called after the theo is computed and before the quote is built.
+    ///
+    /// The parameters may be adjusted, e.g. to skew the theo on a signal.
+    fn on_theo_computed(&self, _symbol: &str, _params: &mut QuoteParams) {}
+
+    /// Called after a quote is generated; a veto withholds the quote.
+    fn on_quote_generated(&self, _symbol: &str, _quote: &GeneratedQuote) -> HookDecision {
+        HookDecision::Allow
+    }
+
+    /// Called before an order is sent; a veto drops the order.
+    fn pre_order_place(&self, _request: &OrderRequest) -> HookDecision {
+        HookDecision::Allow
+    }
+
+    /// Called after a fill is booked.
+    fn on_fill(&self, _symbol: &str, _quantity: Decimal, _price: Decimal) {}
+
+    /// Called when the trading state changes.
+    fn on_risk_state_change(&self, _previous: TradingState, _current: TradingState) {}
+}
@@ -0,0 +1,18 @@
+//! Market maker module.
+//!
+//! This module provides the [`MarketMaker`] facade, which drives one pass
+//! of the quoting pipeline (theo, quote, order placement, fill booking and
+//! risk state) and calls user [`Hooks`] at each step. Hooks let a desk add
+//! signals, logging or vetoes without forking the pipeline.
+//!
+//! ## Components
+//!
+//! - [`MarketMaker`]: Quoting pipeline facade with registered hooks
+//! - [`Hooks`]: Extension callbacks invoked at defined pipeline points
+//! - [`HookDecision`]: Allow or veto returned by gating hooks
+
+mod facade;
+mod hooks;
+
+pub use facade::{HookStats, MarketMaker};
+pub use hooks::{HookDecision, Hooks};
@@ -0,0 +1,204 @@
+//! Engine metrics module.
+//!
+//! This module provides [`EngineMetrics`], the standard metric set of the
+//! engine registered on a [`MetricsRegistry`]:
+//!
+//! | Metric | Type | Recorded by |
+//! |--------|------|-------------|
+//! | `ocob_order_add_latency_seconds` | histogram | [`EngineMetrics::add_limit_order`] |
+//! | `ocob_quote_generation_seconds` | histogram | [`EngineMetrics::time_quote_generation`] |
+//! | `ocob_greeks_aggregation_seconds` | histogram | [`EngineMetrics::time_greeks_aggregation`] |
+//! | `ocob_fills_total` | counter | Book executions, as a [`BookListener`] |
+//! | `ocob_cancels_total` | counter | [`EngineMetrics::cancel_order`] |
+//! | `ocob_rejects_total` | counter | Orders a book refused in [`EngineMetrics::add_limit_order`] |
+
+use super::registry::{Counter, Histogram, MetricsRegistry};
+use crate::error::Result;
+use crate::orderbook::{BookListener, OptionOrderBook};
+use orderbook_rs::{OrderId, Side, TimeInForce, TradeResult};
+use std::sync::Arc;
+
+/// Standard engine metrics.
+#[derive(Debug, Clone)]
+pub struct EngineMetrics {
+    /// Time to add a limit order to a book.
+    order_add_latency: Arc<Histogram>,
+    /// Time to generate quotes.
+    quote_generation: Arc<Histogram>,
+    /// Time to aggregate portfolio Greeks.
+    greeks_aggregation: Arc<Histogram>,
+    /// Executions.
+    fills: Arc<Counter>,
+    /// Orders cancelled.
+    cancels: Arc<Counter>,
+    /// Orders rejected.
+    rejects: Arc<Counter>,
+}
+
+impl EngineMetrics {
+    /// Registers the engine metrics, or picks up those already registered.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if a name is taken by a metric
+    /// of another type.
+    pub fn register(registry: &MetricsRegistry) -> Result<Self> {
+        Ok(Self {
+            order_add_latency: registry.histogram(
+                "ocob_order_add_latency_seconds",
+                "Time to add a limit order to an option book.",
+            )?,
+            quote_generation: registry
+                .histogram("ocob_quote_generation_seconds", "Time to generate quotes.")?,
+            greeks_aggregation: registry.histogram(
+                "ocob_greeks_aggregation_seconds",
+                "Time to aggregate portfolio Greeks.",
+            )?,
+            fills: registry.counter("ocob_fills_total", "Executions in option books.")?,
+            cancels: registry.counter("ocob_cancels_total", "Orders cancelled.")?,
+            rejects: registry.counter("ocob_rejects_total", "Orders rejected by a book.")?,
+        })
+    }
+
+    /// Adds a limit order to a book, recording its latency, or a reject if
+    /// the book refuses it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the book's error.
+    pub fn add_limit_order(
+        &self,
+        book: &OptionOrderBook,
+        order_id: OrderId,
+        side: Side,
+        price: u128,
+        quantity: u64,
+        time_in_force: TimeInForce,
+    ) -> Result<()> {
+        let added = self
+            .order_add_latency
+            .time(|| book.add_limit_order_with_tif(order_id, side, price, quantity, time_in_force));
+        if added.is_err() {
+            self.rejects.inc();
+        }
+        added
+    }
+
+    /// Cancels an order in a book, counting it if the book accepted the
+    /// cancel.
+    ///
+    /// # Errors
+    ///
+    /// Returns the book's error.
+    pub fn cancel_order(&self, book: &OptionOrderBook, order_id: OrderId) -> Result<bool> {
+        let cancelled = book.cancel_order(order_id)?;
+        if cancelled {
+            self.cancels.inc();
+        }
+        Ok(cancelled)
+    }
+
+    /// Runs a quote generation, recording its duration.
+    pub fn time_quote_generation<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.quote_generation.time(f)
+    }
+
+    /// Runs a Greeks aggregation, recording its duration.
+    pub fn time_greeks_aggregation<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.greeks_aggregation.time(f)
+    }
+
+    /// Counts a rejected order, e.g. one refused before reaching a book.
+    pub fn record_reject(&self) {
+        self.rejects.inc();
+    }
+
+    /// Returns the order add latency histogram.
+    #[must_use]
+    pub fn order_add_latency(&self) -> &Histogram {
+        &self.order_add_latency
+    }
+
+    /// Returns the quote generation histogram.
+    #[must_use]
+    pub fn quote_generation(&self) -> &Histogram {
+        &self.quote_generation
+    }
+
+    /// Returns the Greeks aggregation histogram.
+    #[must_use]
+    pub fn greeks_aggregation(&self) -> &Histogram {
+        &self.greeks_aggregation
+    }
+
+    /// Returns the fill counter.
+    #[must_use]
+    pub fn fills(&self) -> &Counter {
+        &self.fills
+    }
+
+    /// Returns the cancel counter.
+    #[must_use]
+    pub fn cancels(&self) -> &Counter {
+        &self.cancels
+    }
+
+    /// Returns the reject counter.
+    #[must_use]
+    pub fn rejects(&self) -> &Counter {
+        &self.rejects
+    }
+}
+
+impl BookListener for EngineMetrics {
+    /// Counts every execution of the trade.
+    fn on_trade(&self, _symbol: &str, trade: &TradeResult) {
+        self.fills
+            .add(trade.match_result.transactions.as_vec().len() as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::encode_prometheus;
+    use crate::orderbook::OptionOrderBook;
+    use optionstratlib::OptionStyle;
+
+    #[test]
+    fn test_book_operations_are_recorded() {
+        let registry = MetricsRegistry::new();
+        let metrics = Arc::new(EngineMetrics::register(&registry).unwrap());
+        let book = OptionOrderBook::new("BTC-20240329-50000-C", OptionStyle::Call);
+        book.add_book_listener(metrics.clone());
+
+        let resting = OrderId::new();
+        metrics
+            .add_limit_order(&book, resting, Side::Sell, 500, 10, TimeInForce::Gtc)
+            .unwrap();
+        metrics
+            .add_limit_order(&book, OrderId::new(), Side::Buy, 500, 4, TimeInForce::Gtc)
+            .unwrap();
+        // A fill-or-kill order larger than the book is rejected.
+        assert!(
+            metrics
+                .add_limit_order(&book, OrderId::new(), Side::Buy, 500, 50, TimeInForce::Fok)
+                .is_err()
+        );
+        assert!(metrics.cancel_order(&book, resting).unwrap());
+        metrics.time_quote_generation(|| ());
+
+        assert_eq!(metrics.order_add_latency().count(), 3);
+        assert_eq!(metrics.quote_generation().count(), 1);
+        assert_eq!(metrics.fills().get(), 1);
+        assert_eq!(metrics.cancels().get(), 1);
+        assert_eq!(metrics.rejects().get(), 1);
+
+        // Registering again shares the same metrics.
+        let again = EngineMetrics::register(&registry).unwrap();
+        assert_eq!(again.fills().get(), 1);
+        let text = encode_prometheus(&registry);
+        assert!(text.contains("ocob_fills_total 1\n"));
+        assert!(text.contains("ocob_order_add_latency_seconds_count 3\n"));
+    }
+}
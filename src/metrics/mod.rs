@@ -0,0 +1,37 @@
+//! Metrics module.
+//!
+//! This module provides internal metrics for monitoring the engine: latency
+//! histograms and event counters kept in a [`MetricsRegistry`] and exposed
+//! in the Prometheus text format. Available with the `metrics` feature.
+//!
+//! ## Components
+//!
+//! - [`MetricsRegistry`]: Named [`Counter`]s and latency [`Histogram`]s, registered once and shared
+//! - [`EngineMetrics`]: Order add, quote generation and Greeks aggregation latencies, and fill, cancel and reject counts
+//! - [`encode_prometheus`]: Prometheus text exposition of a registry
+//!
+//! ## Example
+//!
+//! ```rust
+//! use option_chain_orderbook::metrics::{EngineMetrics, MetricsRegistry, encode_prometheus};
+//!
+//! let registry = MetricsRegistry::new();
+//! let metrics = EngineMetrics::register(&registry).unwrap();
+//! metrics.time_quote_generation(|| {
+//!     // generate quotes
+//! });
+//!
+//! let text = encode_prometheus(&registry);
+//! assert!(text.contains("ocob_quote_generation_seconds_count 1"));
+//! ```
+
+mod engine;
+mod prometheus;
+mod registry;
+
+pub use engine::EngineMetrics;
+pub use prometheus::{PROMETHEUS_CONTENT_TYPE, encode_prometheus};
+pub use registry::{
+    Counter, DEFAULT_LATENCY_BUCKETS, Histogram, HistogramSnapshot, Metric, MetricsRegistry,
+    RegisteredMetric,
+};
@@ -0,0 +1,87 @@
+//! Prometheus exposition module.
+//!
+//! This module encodes a [`MetricsRegistry`] in the Prometheus text
+//! exposition format (version 0.0.4), to be served on a scrape endpoint.
+//! Histograms are exposed in seconds.
+
+use super::registry::{Metric, MetricsRegistry};
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Content type of the exposition.
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Encodes every metric of a registry in the Prometheus text format.
+#[must_use]
+pub fn encode_prometheus(registry: &MetricsRegistry) -> String {
+    let mut out = String::new();
+    for registered in registry.metrics() {
+        let name = &registered.name;
+        if !registered.help.is_empty() {
+            let _ = writeln!(out, "# HELP {name} {}", escape_help(&registered.help));
+        }
+        match &registered.metric {
+            Metric::Counter(counter) => {
+                let _ = writeln!(out, "# TYPE {name} counter");
+                let _ = writeln!(out, "{name} {}", counter.get());
+            }
+            Metric::Histogram(histogram) => {
+                let snapshot = histogram.snapshot();
+                let _ = writeln!(out, "# TYPE {name} histogram");
+                for (bound, count) in snapshot.bounds.iter().zip(&snapshot.cumulative) {
+                    let _ = writeln!(out, "{name}_bucket{{le=\"{}\"}} {count}", seconds(*bound));
+                }
+                let _ = writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {}", snapshot.count);
+                let _ = writeln!(out, "{name}_sum {}", seconds(snapshot.sum));
+                let _ = writeln!(out, "{name}_count {}", snapshot.count);
+            }
+        }
+    }
+    out
+}
+
+fn seconds(duration: Duration) -> f64 {
+    duration.as_secs_f64()
+}
+
+/// Escapes backslashes and line feeds in help text.
+fn escape_help(help: &str) -> String {
+    help.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_counter_and_histogram() {
+        let registry = MetricsRegistry::new();
+        registry
+            .counter("ocob_rejects_total", "Orders rejected\nby books")
+            .unwrap()
+            .add(2);
+        let latency = registry
+            .histogram_with_buckets(
+                "ocob_order_add_latency_seconds",
+                "Order add latency",
+                &[Duration::from_micros(1), Duration::from_millis(1)],
+            )
+            .unwrap();
+        latency.observe(Duration::from_micros(500));
+
+        let text = encode_prometheus(&registry);
+        let expected = "\
+# HELP ocob_order_add_latency_seconds Order add latency
+# TYPE ocob_order_add_latency_seconds histogram
+ocob_order_add_latency_seconds_bucket{le=\"0.000001\"} 0
+ocob_order_add_latency_seconds_bucket{le=\"0.001\"} 1
+ocob_order_add_latency_seconds_bucket{le=\"+Inf\"} 1
+ocob_order_add_latency_seconds_sum 0.0005
+ocob_order_add_latency_seconds_count 1
+# HELP ocob_rejects_total Orders rejected\\nby books
+# TYPE ocob_rejects_total counter
+ocob_rejects_total 2
+";
+        assert_eq!(text, expected);
+    }
+}
@@ -0,0 +1,396 @@
+//! Metrics registry module.
+//!
+//! This module provides the [`MetricsRegistry`] of named [`Counter`]s and
+//! latency [`Histogram`]s. Handles are shared `Arc`s updated with relaxed
+//! atomics, so recording on a hot path never takes the registry's lock.
+//! Histograms time work by the monotonic clock of a [`TimeSource`].
+
+use crate::clock::{SystemClock, TimeSource};
+use crate::error::{Error, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Default latency bucket bounds, from one microsecond to one second.
+pub const DEFAULT_LATENCY_BUCKETS: [Duration; 12] = [
+    Duration::from_micros(1),
+    Duration::from_micros(5),
+    Duration::from_micros(10),
+    Duration::from_micros(50),
+    Duration::from_micros(100),
+    Duration::from_micros(500),
+    Duration::from_millis(1),
+    Duration::from_millis(5),
+    Duration::from_millis(10),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+];
+
+/// A monotonically increasing count.
+#[derive(Debug, Default)]
+pub struct Counter {
+    /// Current count.
+    value: AtomicU64,
+}
+
+impl Counter {
+    /// Adds one.
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    /// Adds to the count.
+    pub fn add(&self, n: u64) {
+        self.value.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Returns the count.
+    #[must_use]
+    pub fn get(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+}
+
+/// Point-in-time view of a [`Histogram`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistogramSnapshot {
+    /// Upper bounds of the buckets, ascending.
+    pub bounds: Vec<Duration>,
+    /// Observations at or below each bound, cumulative.
+    pub cumulative: Vec<u64>,
+    /// Total observations, including those above the last bound.
+    pub count: u64,
+    /// Sum of the observations.
+    pub sum: Duration,
+}
+
+/// A latency distribution over fixed buckets.
+pub struct Histogram {
+    /// Upper bounds of the buckets in nanoseconds, ascending.
+    bounds: Vec<u64>,
+    /// Observations per bucket; the last one is above every bound.
+    buckets: Vec<AtomicU64>,
+    /// Sum of the observations in nanoseconds.
+    sum_nanos: AtomicU64,
+    /// Total observations.
+    count: AtomicU64,
+    /// Time source [`Self::time`] measures with.
+    source: Arc<dyn TimeSource>,
+}
+
+impl fmt::Debug for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Histogram")
+            .field("bounds", &self.bounds)
+            .field("buckets", &self.buckets)
+            .field("sum_nanos", &self.sum_nanos)
+            .field("count", &self.count)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Histogram {
+    /// Creates a histogram with the given bucket bounds.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if there are no bounds or they are
+    /// not strictly ascending.
+    pub fn new(bounds: &[Duration]) -> Result<Self> {
+        if bounds.is_empty() {
+            return Err(Error::configuration("histogram needs at least one bucket"));
+        }
+        if bounds.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(Error::configuration(
+                "histogram buckets must be strictly ascending",
+            ));
+        }
+        Ok(Self {
+            bounds: bounds.iter().map(|b| saturating_nanos(*b)).collect(),
+            buckets: (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect(),
+            sum_nanos: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+            source: Arc::new(SystemClock),
+        })
+    }
+
+    /// Sets the time source [`Self::time`] measures with, e.g. a manual
+    /// clock in tests and replays.
+    #[must_use]
+    pub fn with_time_source(mut self, source: Arc<dyn TimeSource>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Records one observation.
+    pub fn observe(&self, elapsed: Duration) {
+        let nanos = saturating_nanos(elapsed);
+        let bucket = self.bounds.partition_point(|bound| *bound < nanos);
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum_nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Runs `f` and records how long it took.
+    pub fn time<T>(&self, f: impl FnOnce() -> T) -> T {
+        let start = self.source.monotonic_ns();
+        let result = f();
+        self.observe(Duration::from_nanos(
+            self.source.monotonic_ns().saturating_sub(start),
+        ));
+        result
+    }
+
+    /// Returns the number of observations.
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Returns the current distribution.
+    #[must_use]
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let mut total = 0;
+        let cumulative = self.buckets[..self.bounds.len()]
+            .iter()
+            .map(|bucket| {
+                total += bucket.load(Ordering::Relaxed);
+                total
+            })
+            .collect();
+        HistogramSnapshot {
+            bounds: self
+                .bounds
+                .iter()
+                .map(|b| Duration::from_nanos(*b))
+                .collect(),
+            cumulative,
+            count: self.count(),
+            sum: Duration::from_nanos(self.sum_nanos.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// A registered metric.
+#[derive(Debug, Clone)]
+pub enum Metric {
+    /// A counter.
+    Counter(Arc<Counter>),
+    /// A latency histogram.
+    Histogram(Arc<Histogram>),
+}
+
+/// A metric with its name and description.
+#[derive(Debug, Clone)]
+pub struct RegisteredMetric {
+    /// Metric name.
+    pub name: String,
+    /// Description.
+    pub help: String,
+    /// The metric.
+    pub metric: Metric,
+}
+
+/// Named metrics of the engine.
+pub struct MetricsRegistry {
+    /// Metrics by name.
+    metrics: Mutex<BTreeMap<String, RegisteredMetric>>,
+    /// Time source of the histograms it registers.
+    source: Arc<dyn TimeSource>,
+}
+
+impl Default for MetricsRegistry {
+    fn default() -> Self {
+        Self {
+            metrics: Mutex::new(BTreeMap::new()),
+            source: Arc::new(SystemClock),
+        }
+    }
+}
+
+impl fmt::Debug for MetricsRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MetricsRegistry")
+            .field("metrics", &self.metrics)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MetricsRegistry {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time source of the histograms registered from now on.
+    #[must_use]
+    pub fn with_time_source(mut self, source: Arc<dyn TimeSource>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Returns the counter with a name, registering it if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the name is invalid or taken by
+    /// a histogram.
+    pub fn counter(&self, name: &str, help: &str) -> Result<Arc<Counter>> {
+        let metric = self.get_or_register(name, help, || {
+            Ok(Metric::Counter(Arc::new(Counter::default())))
+        })?;
+        match metric {
+            Metric::Counter(counter) => Ok(counter),
+            Metric::Histogram(_) => Err(Error::configuration(format!(
+                "metric {name} is a histogram"
+            ))),
+        }
+    }
+
+    /// Returns the histogram with a name, registering it with
+    /// [`DEFAULT_LATENCY_BUCKETS`] if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the name is invalid or taken by
+    /// a counter.
+    pub fn histogram(&self, name: &str, help: &str) -> Result<Arc<Histogram>> {
+        self.histogram_with_buckets(name, help, &DEFAULT_LATENCY_BUCKETS)
+    }
+
+    /// Returns the histogram with a name, registering it with the given
+    /// buckets if needed; an existing histogram keeps its buckets.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the name is invalid, taken by
+    /// a counter, or the buckets are invalid.
+    pub fn histogram_with_buckets(
+        &self,
+        name: &str,
+        help: &str,
+        bounds: &[Duration],
+    ) -> Result<Arc<Histogram>> {
+        let metric = self.get_or_register(name, help, || {
+            Ok(Metric::Histogram(Arc::new(
+                Histogram::new(bounds)?.with_time_source(Arc::clone(&self.source)),
+            )))
+        })?;
+        match metric {
+            Metric::Histogram(histogram) => Ok(histogram),
+            Metric::Counter(_) => Err(Error::configuration(format!("metric {name} is a counter"))),
+        }
+    }
+
+    /// Returns the registered metrics sorted by name.
+    #[must_use]
+    pub fn metrics(&self) -> Vec<RegisteredMetric> {
+        self.lock().values().cloned().collect()
+    }
+
+    /// Returns the number of registered metrics.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns true if no metric is registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn get_or_register(
+        &self,
+        name: &str,
+        help: &str,
+        create: impl FnOnce() -> Result<Metric>,
+    ) -> Result<Metric> {
+        if !is_valid_name(name) {
+            return Err(Error::configuration(format!("invalid metric name: {name}")));
+        }
+        let mut metrics = self.lock();
+        if let Some(existing) = metrics.get(name) {
+            return Ok(existing.metric.clone());
+        }
+        let metric = create()?;
+        metrics.insert(
+            name.to_string(),
+            RegisteredMetric {
+                name: name.to_string(),
+                help: help.to_string(),
+                metric: metric.clone(),
+            },
+        );
+        Ok(metric)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, RegisteredMetric>> {
+        self.metrics
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Returns true if a name matches `[a-zA-Z_:][a-zA-Z0-9_:]*`.
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == ':')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+}
+
+fn saturating_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let histogram =
+            Histogram::new(&[Duration::from_micros(10), Duration::from_micros(100)]).unwrap();
+        histogram.observe(Duration::from_micros(10));
+        histogram.observe(Duration::from_micros(50));
+        histogram.observe(Duration::from_millis(1));
+
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.cumulative, vec![1, 2]);
+        assert_eq!(snapshot.count, 3);
+        assert_eq!(snapshot.sum, Duration::from_micros(1060));
+        assert!(Histogram::new(&[Duration::from_micros(10), Duration::from_micros(10)]).is_err());
+    }
+
+    #[test]
+    fn test_registry_returns_shared_handles() {
+        let registry = MetricsRegistry::new();
+        let fills = registry.counter("fills_total", "Fills").unwrap();
+        fills.inc();
+        registry.counter("fills_total", "Fills").unwrap().add(2);
+        assert_eq!(fills.get(), 3);
+
+        assert!(registry.histogram("fills_total", "Fills").is_err());
+        assert!(registry.counter("9fills", "Fills").is_err());
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn test_histogram_times_by_time_source() {
+        let clock = Arc::new(crate::clock::ManualClock::new(1_000));
+        let registry = MetricsRegistry::new().with_time_source(clock.clone());
+        let histogram = registry.histogram("work_seconds", "Work").unwrap();
+
+        histogram.time(|| clock.advance(5));
+
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.count, 1);
+        assert_eq!(snapshot.sum, Duration::from_millis(5));
+    }
+}
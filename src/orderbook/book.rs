@@ -3,13 +3,36 @@
 //! This module provides the [`OptionOrderBook`] structure that wraps the
 //! OrderBook-rs `OrderBook<T>` implementation with option-specific functionality.
 
+use super::events::{BookListener, BookObservers, TouchedLevel};
+use super::modify::ModifyOutcome;
 use super::quote::Quote;
+use super::registry::ContractId;
+use super::stp::{SelfTradeGuard, SelfTradePrevention};
+use super::timers::{MassCancelScope, OrderTimers, ScheduledCancel, TimerReport};
 use crate::Result;
+use crate::clock::Clock;
 use optionstratlib::OptionStyle;
-use orderbook_rs::{DefaultOrderBook, OrderBookSnapshot, OrderId, Side, TimeInForce};
+use orderbook_rs::{
+    DefaultOrderBook, OrderBookSnapshot, OrderId, Side, TimeInForce, TradeListener, TradeResult,
+};
+use pricelevel::OrderUpdate;
+use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// What an [`OptionOrderBook`] tracks for a resting order beyond the inner
+/// book, which snapshots do not carry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderTags {
+    /// Order id.
+    pub order_id: OrderId,
+    /// Good-Till-Date expiry in milliseconds by the book's clock.
+    pub expire_at_ms: Option<u64>,
+    /// Self-trade participant the order was added for.
+    pub participant: Option<String>,
+}
 
 /// Order book for a single option contract.
 ///
@@ -43,6 +66,18 @@ pub struct OptionOrderBook {
     option_style: OptionStyle,
     /// Unique identifier for this order book.
     id: OrderId,
+    /// Interned contract identifier, if registered in a `ContractRegistry`.
+    contract_id: Option<ContractId>,
+    /// Open interest as last reported by market data.
+    open_interest: AtomicU64,
+    /// Receiver of executions, replaceable after construction.
+    trade_listener: Arc<RwLock<Option<TradeListener>>>,
+    /// Listeners of quote, trade and depth changes.
+    observers: Arc<BookObservers>,
+    /// Participant tags of resting orders and the self-trade policy.
+    self_trade: Arc<SelfTradeGuard>,
+    /// Clock, Good-Till-Date expiries and scheduled mass cancels.
+    timers: Arc<OrderTimers>,
 }
 
 impl OptionOrderBook {
@@ -57,16 +92,130 @@ impl OptionOrderBook {
         let symbol = symbol.into();
         let symbol_hash = Self::hash_symbol(&symbol);
 
+        // The inner book takes its listener at construction, so it forwards
+        // to a slot that can be filled once the book is shared.
+        let trade_listener: Arc<RwLock<Option<TradeListener>>> = Arc::new(RwLock::new(None));
+        let observers = Arc::new(BookObservers::default());
+        let self_trade = Arc::new(SelfTradeGuard::default());
+        let timers = Arc::new(OrderTimers::default());
+        let slot = Arc::clone(&trade_listener);
+        let trade_observers = Arc::clone(&observers);
+        let trade_owners = Arc::clone(&self_trade);
+        let trade_timers = Arc::clone(&timers);
+        let forward: TradeListener = Arc::new(move |trade: &TradeResult| {
+            // Resting orders filled away no longer count for self-trades
+            // and no longer expire.
+            for order_id in &trade.match_result.filled_order_ids {
+                trade_owners.remove(*order_id);
+                trade_timers.remove_expiry(*order_id);
+            }
+            let listener = slot.read().unwrap_or_else(PoisonError::into_inner).clone();
+            if let Some(listener) = listener {
+                listener(trade);
+            }
+            trade_observers.trade(trade);
+        });
+
         Self {
             symbol: symbol.clone(),
             symbol_hash,
-            book: Arc::new(DefaultOrderBook::new(&symbol)),
+            book: Arc::new(DefaultOrderBook::with_trade_listener(&symbol, forward)),
             last_quote: Arc::new(Quote::empty(0)),
             option_style,
             id: OrderId::new(),
+            contract_id: None,
+            open_interest: AtomicU64::new(0),
+            trade_listener,
+            observers,
+            self_trade,
+            timers,
+        }
+    }
+
+    /// Creates a new option order book bound to an interned contract id.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The option contract symbol (e.g., "BTC-20240329-50000-C")
+    /// * `option_style` - The option style (Call or Put)
+    /// * `contract_id` - The id assigned by a `ContractRegistry`
+    #[must_use]
+    pub fn with_contract_id(
+        symbol: impl Into<String>,
+        option_style: OptionStyle,
+        contract_id: ContractId,
+    ) -> Self {
+        Self {
+            contract_id: Some(contract_id),
+            ..Self::new(symbol, option_style)
         }
     }
 
+    /// Returns the interned contract id, if this book was registered.
+    #[must_use]
+    pub const fn contract_id(&self) -> Option<ContractId> {
+        self.contract_id
+    }
+
+    /// Returns the open interest as last reported by market data.
+    #[must_use]
+    pub fn open_interest(&self) -> u64 {
+        self.open_interest.load(Ordering::Relaxed)
+    }
+
+    /// Sets the open interest reported by market data.
+    pub fn set_open_interest(&self, open_interest: u64) {
+        self.open_interest.store(open_interest, Ordering::Relaxed);
+    }
+
+    /// Sets the listener called with every execution in this book,
+    /// replacing any previous one.
+    ///
+    /// The listener runs on the thread that submitted the aggressive order.
+    pub fn set_trade_listener(&self, listener: TradeListener) {
+        *self
+            .trade_listener
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Some(listener);
+    }
+
+    /// Removes the trade listener, returning whether one was set.
+    pub fn clear_trade_listener(&self) -> bool {
+        self.trade_listener
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+            .is_some()
+    }
+
+    /// Returns whether a trade listener is set.
+    #[must_use]
+    pub fn has_trade_listener(&self) -> bool {
+        self.trade_listener
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_some()
+    }
+
+    /// Registers a listener of quote, trade and depth changes.
+    ///
+    /// Listeners see changes made through this wrapper, on the thread
+    /// making them.
+    pub fn add_book_listener(&self, listener: Arc<dyn BookListener>) {
+        self.observers.subscribe(listener, self.best_quote());
+    }
+
+    /// Removes a book listener, returning whether it was registered.
+    pub fn remove_book_listener(&self, listener: &Arc<dyn BookListener>) -> bool {
+        self.observers.unsubscribe(listener)
+    }
+
+    /// Returns the number of registered book listeners.
+    #[must_use]
+    pub fn book_listener_count(&self) -> usize {
+        self.observers.len()
+    }
+
     /// Returns the option style (Call or Put).
     #[must_use]
     pub const fn option_style(&self) -> OptionStyle {
@@ -125,10 +274,7 @@ impl OptionOrderBook {
         price: u128,
         quantity: u64,
     ) -> Result<()> {
-        self.book
-            .add_limit_order(order_id, price, quantity, side, TimeInForce::Gtc, None)
-            .map_err(|e| crate::Error::orderbook(e.to_string()))?;
-        Ok(())
+        self.add_limit_order_with_tif(order_id, side, price, quantity, TimeInForce::Gtc)
     }
 
     /// Adds a limit order with time-in-force specification.
@@ -140,6 +286,16 @@ impl OptionOrderBook {
     /// * `price` - Limit price in smallest units (u128)
     /// * `quantity` - Order quantity in smallest units (u64)
     /// * `tif` - Time-in-force (GTC, IOC, FOK, etc.)
+    ///
+    /// A Good-Till-Date order carries its expiry in Unix seconds, as in
+    /// pricelevel. It expires by the book's clock and is cancelled by the
+    /// first [`Self::run_timers`] at or after its expiry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if a Good-Till-Date order has
+    /// already expired, or `Error::OrderBookError` if the book rejects the
+    /// order.
     pub fn add_limit_order_with_tif(
         &self,
         order_id: OrderId,
@@ -148,12 +304,288 @@ impl OptionOrderBook {
         quantity: u64,
         tif: TimeInForce,
     ) -> Result<()> {
-        self.book
+        self.add_limit_order_at(order_id, side, price, quantity, tif, self.timers.now_ms())
+    }
+
+    /// Adds a limit order, checking a Good-Till-Date expiry against `now_ms`
+    /// rather than the book's clock, e.g. the time a journaled add was made.
+    pub(crate) fn add_limit_order_at(
+        &self,
+        order_id: OrderId,
+        side: Side,
+        price: u128,
+        quantity: u64,
+        tif: TimeInForce,
+        now_ms: u64,
+    ) -> Result<()> {
+        let (tif, expire_at_ms) = match tif {
+            TimeInForce::Gtd(expire_at_s) => {
+                let expire_at_ms = expire_at_s.saturating_mul(1000);
+                if expire_at_ms <= now_ms {
+                    return Err(crate::Error::validation(format!(
+                        "order {order_id} expired at {expire_at_s}s"
+                    )));
+                }
+                (TimeInForce::Gtc, Some(expire_at_ms))
+            }
+            tif => (tif, None),
+        };
+        let touched = self.observers.is_active().then(|| TouchedLevel {
+            side,
+            price,
+            was_empty: self.depth_at_price(side, price) == 0,
+        });
+        let added = self
+            .book
             .add_limit_order(order_id, price, quantity, side, tif, None)
-            .map_err(|e| crate::Error::orderbook(e.to_string()))?;
+            .map_err(|e| crate::Error::orderbook(e.to_string()));
+        self.observers.publish(self, touched);
+        added?;
+        if let Some(expire_at_ms) = expire_at_ms
+            && self.book.get_order(order_id).is_some()
+        {
+            self.timers.insert_expiry(expire_at_ms, order_id);
+        }
         Ok(())
     }
 
+    /// Adds a limit order on behalf of a participant, enforcing the book's
+    /// [`SelfTradePrevention`] policy against the participant's resting
+    /// orders.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_id` - Unique order identifier
+    /// * `side` - Buy or Sell
+    /// * `price` - Limit price in smallest units
+    /// * `quantity` - Order quantity in smallest units
+    /// * `tif` - Time-in-force
+    /// * `participant` - Tag shared by the orders that must not trade with
+    ///   each other, e.g. our quoter and hedger
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::OrderBookError` if the policy rejects the order as a
+    /// self-trade or the book rejects it.
+    pub fn add_limit_order_as(
+        &self,
+        order_id: OrderId,
+        side: Side,
+        price: u128,
+        quantity: u64,
+        tif: TimeInForce,
+        participant: &str,
+    ) -> Result<()> {
+        let now_ms = self.timers.now_ms();
+        self.add_limit_order_as_at(order_id, side, price, quantity, tif, participant, now_ms)
+    }
+
+    /// Adds a limit order on behalf of a participant like
+    /// [`Self::add_limit_order_as`], checking a Good-Till-Date expiry
+    /// against `now_ms`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn add_limit_order_as_at(
+        &self,
+        order_id: OrderId,
+        side: Side,
+        price: u128,
+        quantity: u64,
+        tif: TimeInForce,
+        participant: &str,
+        now_ms: u64,
+    ) -> Result<()> {
+        self.prevent_self_trade(order_id, side, price, participant)?;
+        self.add_limit_order_at(order_id, side, price, quantity, tif, now_ms)?;
+        if self.book.get_order(order_id).is_some() {
+            self.self_trade.insert(order_id, participant, side, price);
+        }
+        Ok(())
+    }
+
+    /// Adds an iceberg order on behalf of a participant, enforcing the
+    /// book's [`SelfTradePrevention`] policy like
+    /// [`Self::add_limit_order_as`].
+    ///
+    /// # Arguments
+    ///
+    /// * `order_id` - Unique order identifier
+    /// * `side` - Buy or Sell
+    /// * `price` - Limit price in smallest units
+    /// * `visible` - Displayed quantity
+    /// * `hidden` - Hidden reserve that refills the display
+    /// * `tif` - Time-in-force
+    /// * `participant` - Tag shared by the orders that must not trade with
+    ///   each other
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::OrderBookError` if the policy rejects the order as a
+    /// self-trade or the book rejects it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_iceberg_order_as(
+        &self,
+        order_id: OrderId,
+        side: Side,
+        price: u128,
+        visible: u64,
+        hidden: u64,
+        tif: TimeInForce,
+        participant: &str,
+    ) -> Result<()> {
+        self.prevent_self_trade(order_id, side, price, participant)?;
+        self.add_iceberg(order_id, side, price, visible, hidden, tif)?;
+        if self.book.get_order(order_id).is_some() {
+            self.self_trade.insert(order_id, participant, side, price);
+        }
+        Ok(())
+    }
+
+    /// Applies the self-trade prevention policy to an incoming order of a
+    /// participant, cancelling the resting orders it would cross if the
+    /// policy says so.
+    fn prevent_self_trade(
+        &self,
+        order_id: OrderId,
+        side: Side,
+        price: u128,
+        participant: &str,
+    ) -> Result<()> {
+        let Some(policy) = self.self_trade.policy() else {
+            return Ok(());
+        };
+        let crossed = self.self_trade.crossed(participant, side, price, |id| {
+            self.book.get_order(id).is_some()
+        });
+        if crossed.is_empty() {
+            return Ok(());
+        }
+        self.self_trade.record_prevented();
+        if policy.cancels_resting() {
+            for id in &crossed {
+                self.cancel_order(*id)?;
+            }
+        }
+        if policy.cancels_incoming() {
+            return Err(crate::Error::orderbook(format!(
+                "self-trade prevented ({policy}): order {order_id} of {participant} \
+                 would cross {} resting order(s)",
+                crossed.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Sets the self-trade prevention policy applied to orders added with
+    /// [`Self::add_limit_order_as`]; `None` disables it.
+    pub fn set_self_trade_prevention(&self, policy: Option<SelfTradePrevention>) {
+        self.self_trade.set_policy(policy);
+    }
+
+    /// Returns the self-trade prevention policy, if enabled.
+    #[must_use]
+    pub fn self_trade_prevention(&self) -> Option<SelfTradePrevention> {
+        self.self_trade.policy()
+    }
+
+    /// Returns the number of orders that triggered self-trade prevention.
+    #[must_use]
+    pub fn self_trades_prevented(&self) -> u64 {
+        self.self_trade.prevented()
+    }
+
+    /// Returns the participant a resting order was added for, if tagged.
+    #[must_use]
+    pub fn participant(&self, order_id: OrderId) -> Option<String> {
+        self.self_trade.participant(order_id)
+    }
+
+    /// Sets the clock that stamps quotes and times Good-Till-Date orders and
+    /// scheduled mass cancels, e.g. a simulated clock in tests and replays.
+    pub fn set_clock(&self, clock: Arc<dyn Clock>) {
+        self.timers.set_clock(clock);
+    }
+
+    /// Returns the expiry of a resting Good-Till-Date order, in milliseconds
+    /// by the book's clock.
+    #[must_use]
+    pub fn order_expiry(&self, order_id: OrderId) -> Option<u64> {
+        self.book
+            .get_order(order_id)
+            .and_then(|_| self.timers.expiry(order_id))
+    }
+
+    /// Schedules a mass cancel, run by the first [`Self::run_timers`] at or
+    /// after its time.
+    pub fn schedule_mass_cancel(&self, cancel: ScheduledCancel) {
+        self.timers.schedule(cancel);
+    }
+
+    /// Returns the pending scheduled mass cancels in time order.
+    #[must_use]
+    pub fn scheduled_mass_cancels(&self) -> Vec<ScheduledCancel> {
+        self.timers.scheduled()
+    }
+
+    /// Drops every pending scheduled mass cancel, returning how many there
+    /// were.
+    pub fn clear_scheduled_mass_cancels(&self) -> usize {
+        self.timers.clear_scheduled()
+    }
+
+    /// Cancels the orders in a scope.
+    ///
+    /// Returns the number of orders cancelled.
+    pub fn mass_cancel(&self, scope: &MassCancelScope) -> usize {
+        self.cancel_scope(scope).len()
+    }
+
+    /// Cancels the orders in a scope, returning the ids cancelled.
+    fn cancel_scope(&self, scope: &MassCancelScope) -> Vec<OrderId> {
+        let ids: Vec<OrderId> = match scope {
+            MassCancelScope::All => self
+                .book
+                .get_all_orders()
+                .iter()
+                .map(|order| order.id())
+                .collect(),
+            MassCancelScope::Participant(participant) => self.self_trade.orders_of(participant),
+        };
+        ids.into_iter()
+            .filter(|id| self.cancel_resting(*id))
+            .collect()
+    }
+
+    /// Cancels the Good-Till-Date orders past their expiry and runs the
+    /// scheduled mass cancels that fell due, by the book's clock.
+    ///
+    /// Book listeners are told which orders were cancelled, so a journal
+    /// can record them.
+    pub fn run_timers(&self) -> TimerReport {
+        let due = self.timers.take_due(self.timers.now_ms());
+        let mut cancelled: Vec<OrderId> = due
+            .expired
+            .into_iter()
+            .filter(|id| self.cancel_resting(*id))
+            .collect();
+        let mut report = TimerReport {
+            expired: cancelled.len(),
+            ..TimerReport::default()
+        };
+        for cancel in &due.cancels {
+            let ids = self.cancel_scope(&cancel.scope);
+            report.mass_cancels += 1;
+            report.mass_cancelled += ids.len();
+            cancelled.extend(ids);
+        }
+        self.observers.timer_cancel(self, &cancelled);
+        report
+    }
+
+    /// Cancels an order if it is still resting.
+    fn cancel_resting(&self, order_id: OrderId) -> bool {
+        self.book.get_order(order_id).is_some() && self.cancel_order(order_id).unwrap_or(false)
+    }
+
     /// Cancels an order by its ID.
     ///
     /// # Arguments
@@ -164,16 +596,170 @@ impl OptionOrderBook {
     ///
     /// `Ok(true)` if the order was found and cancelled, `Ok(false)` if not found.
     pub fn cancel_order(&self, order_id: OrderId) -> Result<bool> {
+        let touched = if self.observers.is_active() {
+            self.book.get_order(order_id).map(|order| TouchedLevel {
+                side: order.side(),
+                price: order.price(),
+                was_empty: false,
+            })
+        } else {
+            None
+        };
+        self.self_trade.remove(order_id);
+        self.timers.remove_expiry(order_id);
         match self.book.cancel_order(order_id) {
-            Ok(_) => Ok(true),
+            Ok(_) => {
+                self.observers.publish(self, touched);
+                Ok(true)
+            }
             Err(_) => Ok(false),
         }
     }
 
+    /// Modifies a resting order's price and quantity.
+    ///
+    /// A lower quantity at the same price is amended in place and keeps
+    /// the order's queue priority. A price change or a size increase
+    /// cancels the order and re-adds it with the same id, side and
+    /// time-in-force; it may trade on re-entry, its Good-Till-Date expiry
+    /// carries over, and an order added under a participant is re-checked
+    /// for self-trades. A quantity of zero cancels the order.
+    ///
+    /// Price levels queue orders by id, and a cancel leaves the id in
+    /// place until it is reached. A re-added order joins the back of a
+    /// level it has not rested on, but at its own level, or one it left
+    /// while other orders still rest there, it takes its earlier place.
+    ///
+    /// Quantities are totals of what remains, so a partially filled order
+    /// is compared by its unfilled size. An iceberg order is reduced from
+    /// its displayed part, in place, while the hidden reserve covers the
+    /// rest; a reduction into the reserve, or any other change, re-adds it
+    /// as an iceberg showing at most its current display size.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_id` - The ID of the order to modify
+    /// * `new_price` - New limit price in smallest units
+    /// * `new_quantity` - New total resting quantity
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::OrderBookError` if the book or the self-trade policy
+    /// rejects the re-added order, in which case the order stays cancelled.
+    pub fn modify_order(
+        &self,
+        order_id: OrderId,
+        new_price: u128,
+        new_quantity: u64,
+    ) -> Result<ModifyOutcome> {
+        let Some(order) = self.book.get_order(order_id) else {
+            return Ok(ModifyOutcome::NotFound);
+        };
+        if new_quantity == 0 {
+            self.cancel_order(order_id)?;
+            return Ok(ModifyOutcome::Cancelled);
+        }
+        let (side, price) = (order.side(), order.price());
+        let quantity = order.visible_quantity() + order.hidden_quantity();
+        if new_price == price && new_quantity == quantity {
+            return Ok(ModifyOutcome::Unchanged);
+        }
+        let hidden = order.hidden_quantity();
+        if new_price == price && new_quantity < quantity && new_quantity > hidden {
+            // The book amends the displayed part and keeps the reserve.
+            let amended = self
+                .book
+                .update_order(OrderUpdate::UpdateQuantity {
+                    order_id,
+                    new_quantity: new_quantity - hidden,
+                })
+                .map_err(|e| crate::Error::orderbook(e.to_string()))?;
+            if amended.is_some() {
+                self.observers.publish(
+                    self,
+                    Some(TouchedLevel {
+                        side,
+                        price,
+                        was_empty: false,
+                    }),
+                );
+                return Ok(ModifyOutcome::Amended {
+                    quantity: new_quantity,
+                });
+            }
+            return Ok(ModifyOutcome::NotFound);
+        }
+
+        let participant = self.self_trade.participant(order_id);
+        let expiry = self.timers.expiry(order_id);
+        if !self.cancel_resting(order_id) {
+            return Ok(ModifyOutcome::NotFound);
+        }
+        let tif = order.time_in_force();
+        let visible = order.visible_quantity().clamp(1, new_quantity);
+        match (participant, hidden) {
+            (Some(participant), 0) => {
+                self.add_limit_order_as(order_id, side, new_price, new_quantity, tif, &participant)?
+            }
+            (Some(participant), _) => self.add_iceberg_order_as(
+                order_id,
+                side,
+                new_price,
+                visible,
+                new_quantity - visible,
+                tif,
+                &participant,
+            )?,
+            (None, 0) => {
+                self.add_limit_order_with_tif(order_id, side, new_price, new_quantity, tif)?
+            }
+            (None, _) => self.add_iceberg(
+                order_id,
+                side,
+                new_price,
+                visible,
+                new_quantity - visible,
+                tif,
+            )?,
+        }
+        if let Some(expire_at_ms) = expiry
+            && self.book.get_order(order_id).is_some()
+        {
+            self.timers.insert_expiry(expire_at_ms, order_id);
+        }
+        Ok(ModifyOutcome::Replaced {
+            price: new_price,
+            quantity: new_quantity,
+        })
+    }
+
+    /// Adds an iceberg order, notifying book listeners.
+    fn add_iceberg(
+        &self,
+        order_id: OrderId,
+        side: Side,
+        price: u128,
+        visible: u64,
+        hidden: u64,
+        tif: TimeInForce,
+    ) -> Result<()> {
+        let touched = self.observers.is_active().then(|| TouchedLevel {
+            side,
+            price,
+            was_empty: self.depth_at_price(side, price) == 0,
+        });
+        let added = self
+            .book
+            .add_iceberg_order(order_id, price, visible, hidden, side, tif, None)
+            .map_err(|e| crate::Error::orderbook(e.to_string()));
+        self.observers.publish(self, touched);
+        added.map(|_| ())
+    }
+
     /// Returns the current best quote.
     #[must_use]
     pub fn best_quote(&self) -> Quote {
-        let timestamp_ms = orderbook_rs::current_time_millis();
+        let timestamp_ms = self.timers.now_ms();
 
         let (bid_price, bid_size) = self
             .book
@@ -230,6 +816,67 @@ impl OptionOrderBook {
         self.book.create_snapshot(depth)
     }
 
+    /// Returns the expiry and participant of every resting order that has
+    /// either, to be saved with a [`Self::snapshot`].
+    #[must_use]
+    pub fn order_tags(&self) -> Vec<OrderTags> {
+        self.book
+            .get_all_orders()
+            .iter()
+            .map(|order| OrderTags {
+                order_id: order.id(),
+                expire_at_ms: self.timers.expiry(order.id()),
+                participant: self.self_trade.participant(order.id()),
+            })
+            .filter(|tags| tags.expire_at_ms.is_some() || tags.participant.is_some())
+            .collect()
+    }
+
+    /// Replaces the book's contents with a snapshot.
+    ///
+    /// Expiries and participants of the previous contents are dropped; see
+    /// [`Self::restore_with_tags`] to restore them from the snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::OrderBookError` if the snapshot was taken from a book
+    /// with another symbol.
+    pub fn restore(&self, snapshot: OrderBookSnapshot) -> Result<()> {
+        self.restore_with_tags(snapshot, &[])
+    }
+
+    /// Replaces the book's contents with a snapshot and re-arms the
+    /// expiries and participants saved with it by [`Self::order_tags`].
+    ///
+    /// Tags of orders not in the snapshot are ignored. An expiry that has
+    /// passed cancels its order on the next [`Self::run_timers`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::OrderBookError` if the snapshot was taken from a book
+    /// with another symbol.
+    pub fn restore_with_tags(&self, snapshot: OrderBookSnapshot, tags: &[OrderTags]) -> Result<()> {
+        self.book
+            .restore_from_snapshot(snapshot)
+            .map_err(|e| crate::Error::orderbook(e.to_string()))?;
+        self.self_trade.clear();
+        self.timers.clear_expiries();
+        for tags in tags {
+            let Some(order) = self.book.get_order(tags.order_id) else {
+                continue;
+            };
+            if let Some(expire_at_ms) = tags.expire_at_ms {
+                self.timers.insert_expiry(expire_at_ms, tags.order_id);
+            }
+            if let Some(participant) = &tags.participant {
+                self.self_trade
+                    .insert(tags.order_id, participant, order.side(), order.price());
+            }
+        }
+        self.observers.publish(self, None);
+        Ok(())
+    }
+
     /// Returns the total bid depth (sum of all bid quantities).
     #[must_use]
     pub fn total_bid_depth(&self) -> u64 {
@@ -270,11 +917,14 @@ impl OptionOrderBook {
     pub fn clear(&self) {
         let empty_snapshot = OrderBookSnapshot {
             symbol: self.symbol.clone(),
-            timestamp: orderbook_rs::current_time_millis(),
+            timestamp: self.timers.now_ms(),
             bids: vec![],
             asks: vec![],
         };
         let _ = self.book.restore_from_snapshot(empty_snapshot);
+        self.self_trade.clear();
+        self.timers.clear_expiries();
+        self.observers.publish(self, None);
     }
 
     /// Returns the order book imbalance for top N levels.
@@ -310,18 +960,24 @@ impl OptionOrderBook {
         Arc::clone(&self.last_quote)
     }
 
+    /// Returns depth at a specific price level on one side.
+    fn depth_at_price(&self, side: Side, price: u128) -> u64 {
+        match side {
+            Side::Buy => self.bid_depth_at_price(price),
+            Side::Sell => self.ask_depth_at_price(price),
+        }
+    }
+
     /// Returns depth at a specific price level on the bid side.
     #[must_use]
     pub fn bid_depth_at_price(&self, price: u128) -> u64 {
-        let (bid_volumes, _) = self.book.get_volume_by_price();
-        bid_volumes.get(&price).copied().unwrap_or(0)
+        self.book.liquidity_in_range(price, price, Side::Buy)
     }
 
     /// Returns depth at a specific price level on the ask side.
     #[must_use]
     pub fn ask_depth_at_price(&self, price: u128) -> u64 {
-        let (_, ask_volumes) = self.book.get_volume_by_price();
-        ask_volumes.get(&price).copied().unwrap_or(0)
+        self.book.liquidity_in_range(price, price, Side::Sell)
     }
 
     /// Calculates VWAP for a given quantity.
@@ -360,6 +1016,28 @@ mod tests {
         assert_eq!(book.option_style(), OptionStyle::Call);
         assert!(book.is_empty());
         assert_eq!(book.order_count(), 0);
+        assert!(book.contract_id().is_none());
+    }
+
+    #[test]
+    fn test_option_order_book_with_contract_id() {
+        let book = OptionOrderBook::with_contract_id(
+            "BTC-20240329-50000-C",
+            OptionStyle::Call,
+            ContractId::new(42),
+        );
+
+        assert_eq!(book.symbol(), "BTC-20240329-50000-C");
+        assert_eq!(book.contract_id(), Some(ContractId::new(42)));
+    }
+
+    #[test]
+    fn test_open_interest() {
+        let book = OptionOrderBook::new("BTC-20240329-50000-C", OptionStyle::Call);
+        assert_eq!(book.open_interest(), 0);
+
+        book.set_open_interest(1250);
+        assert_eq!(book.open_interest(), 1250);
     }
 
     #[test]
@@ -615,4 +1293,412 @@ mod tests {
         // avg_price is f64, just verify it's a valid number
         assert!(impact.avg_price >= 0.0 || impact.avg_price < 0.0);
     }
+
+    /// Returns a book with our ask of 10 at 105 and its id.
+    fn book_with_our_ask() -> (OptionOrderBook, OrderId) {
+        let book = OptionOrderBook::new("BTC-20240329-50000-C", OptionStyle::Call);
+        let quote = OrderId::new();
+        book.add_limit_order_as(quote, Side::Sell, 105, 10, TimeInForce::Gtc, "mm")
+            .unwrap();
+        (book, quote)
+    }
+
+    #[test]
+    fn test_self_trade_without_policy_trades() {
+        let (book, quote) = book_with_our_ask();
+        assert_eq!(book.participant(quote).as_deref(), Some("mm"));
+        assert_eq!(book.self_trade_prevention(), None);
+
+        book.add_limit_order_as(OrderId::new(), Side::Buy, 105, 2, TimeInForce::Gtc, "mm")
+            .unwrap();
+        assert_eq!(book.ask_depth_at_price(105), 8);
+        assert_eq!(book.self_trades_prevented(), 0);
+    }
+
+    #[test]
+    fn test_self_trade_cancel_newest() {
+        let (book, quote) = book_with_our_ask();
+        book.set_self_trade_prevention(Some(SelfTradePrevention::CancelNewest));
+
+        let err = book
+            .add_limit_order_as(OrderId::new(), Side::Buy, 106, 2, TimeInForce::Gtc, "mm")
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::OrderBookError { .. }));
+        assert_eq!(book.ask_depth_at_price(105), 10);
+        assert_eq!(book.participant(quote).as_deref(), Some("mm"));
+
+        // A limit short of our quote, or another participant, is not checked.
+        book.add_limit_order_as(OrderId::new(), Side::Buy, 104, 2, TimeInForce::Gtc, "mm")
+            .unwrap();
+        book.add_limit_order_as(
+            OrderId::new(),
+            Side::Buy,
+            105,
+            1,
+            TimeInForce::Gtc,
+            "client",
+        )
+        .unwrap();
+        assert_eq!(book.ask_depth_at_price(105), 9);
+        assert_eq!(book.self_trades_prevented(), 1);
+    }
+
+    #[test]
+    fn test_self_trade_cancel_oldest() {
+        let (book, quote) = book_with_our_ask();
+        book.set_self_trade_prevention(Some(SelfTradePrevention::CancelOldest));
+
+        let hedge = OrderId::new();
+        book.add_limit_order_as(hedge, Side::Buy, 105, 3, TimeInForce::Gtc, "mm")
+            .unwrap();
+        assert_eq!(book.best_ask(), None);
+        assert_eq!(book.best_bid(), Some(105));
+        assert_eq!(book.bid_depth_at_price(105), 3);
+        assert_eq!(book.participant(quote), None);
+        assert_eq!(book.participant(hedge).as_deref(), Some("mm"));
+        assert_eq!(book.self_trades_prevented(), 1);
+    }
+
+    #[test]
+    fn test_self_trade_cancel_both() {
+        let (book, quote) = book_with_our_ask();
+        book.set_self_trade_prevention(Some(SelfTradePrevention::CancelBoth));
+
+        assert!(
+            book.add_limit_order_as(OrderId::new(), Side::Buy, 110, 1, TimeInForce::Gtc, "mm")
+                .is_err()
+        );
+        assert!(book.is_empty());
+        assert_eq!(book.participant(quote), None);
+        assert_eq!(book.self_trades_prevented(), 1);
+    }
+
+    #[test]
+    fn test_participant_tags_pruned_on_fill_and_cancel() {
+        let (book, quote) = book_with_our_ask();
+        let bid = OrderId::new();
+        book.add_limit_order_as(bid, Side::Buy, 90, 5, TimeInForce::Gtc, "mm")
+            .unwrap();
+
+        // Without a policy, a client filling our ask and our cancel of the
+        // bid both drop the tags.
+        book.add_limit_order(OrderId::new(), Side::Buy, 105, 10)
+            .unwrap();
+        assert_eq!(book.participant(quote), None);
+        assert!(book.cancel_order(bid).unwrap());
+        assert_eq!(book.participant(bid), None);
+        assert!(book.is_empty());
+    }
+
+    /// Returns a book on a manual clock at 1s.
+    fn book_on_clock() -> (OptionOrderBook, Arc<crate::clock::ManualClock>) {
+        let book = OptionOrderBook::new("BTC-20240329-50000-C", OptionStyle::Call);
+        let clock = Arc::new(crate::clock::ManualClock::new(1_000));
+        book.set_clock(clock.clone());
+        (book, clock)
+    }
+
+    #[test]
+    fn test_good_till_date_expiry() {
+        let (book, clock) = book_on_clock();
+        assert!(
+            book.add_limit_order_with_tif(OrderId::new(), Side::Buy, 100, 1, TimeInForce::Gtd(1))
+                .is_err()
+        );
+        let gtd = OrderId::new();
+        book.add_limit_order_with_tif(gtd, Side::Buy, 100, 5, TimeInForce::Gtd(5))
+            .unwrap();
+        assert_eq!(book.order_expiry(gtd), Some(5_000));
+        assert_eq!(book.best_quote().timestamp_ms(), 1_000);
+
+        clock.set(4_999);
+        assert_eq!(book.run_timers(), TimerReport::default());
+        clock.set(5_000);
+        assert_eq!(book.run_timers().expired, 1);
+        assert!(book.is_empty());
+        assert_eq!(book.order_expiry(gtd), None);
+        assert_eq!(book.timers.expiry_count(), 0);
+    }
+
+    #[test]
+    fn test_good_till_date_after_modify() {
+        let (book, clock) = book_on_clock();
+        let gtd = OrderId::new();
+        book.add_limit_order_with_tif(gtd, Side::Sell, 110, 5, TimeInForce::Gtd(5))
+            .unwrap();
+
+        // Both an amend and a re-add keep the expiry, under the same id.
+        book.modify_order(gtd, 110, 4).unwrap();
+        assert_eq!(book.order_expiry(gtd), Some(5_000));
+        book.modify_order(gtd, 111, 4).unwrap();
+        assert_eq!(book.order_expiry(gtd), Some(5_000));
+        assert_eq!(book.timers.expiry_count(), 1);
+
+        clock.set(5_000);
+        assert_eq!(book.run_timers().expired, 1);
+        assert!(book.is_empty());
+    }
+
+    #[test]
+    fn test_expiries_dropped_on_fill_and_cancel() {
+        let (book, _clock) = book_on_clock();
+        let filled = OrderId::new();
+        let cancelled = OrderId::new();
+        book.add_limit_order_with_tif(filled, Side::Sell, 110, 5, TimeInForce::Gtd(5))
+            .unwrap();
+        book.add_limit_order_with_tif(cancelled, Side::Sell, 120, 5, TimeInForce::Gtd(5))
+            .unwrap();
+        assert_eq!(book.timers.expiry_count(), 2);
+
+        book.add_limit_order(OrderId::new(), Side::Buy, 110, 5)
+            .unwrap();
+        assert_eq!(book.timers.expiry_count(), 1);
+        book.cancel_order(cancelled).unwrap();
+        assert_eq!(book.timers.expiry_count(), 0);
+    }
+
+    #[test]
+    fn test_scheduled_mass_cancel_all() {
+        let (book, clock) = book_on_clock();
+        book.add_limit_order_as(OrderId::new(), Side::Sell, 110, 5, TimeInForce::Gtc, "mm")
+            .unwrap();
+        book.add_limit_order(OrderId::new(), Side::Sell, 120, 5)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), Side::Buy, 90, 5)
+            .unwrap();
+        book.schedule_mass_cancel(ScheduledCancel::new(2_000, MassCancelScope::All));
+        assert_eq!(book.scheduled_mass_cancels().len(), 1);
+
+        clock.set(2_000);
+        let report = book.run_timers();
+        assert_eq!((report.mass_cancels, report.mass_cancelled), (1, 3));
+        assert!(book.is_empty());
+        assert!(book.scheduled_mass_cancels().is_empty());
+        assert_eq!(book.run_timers(), TimerReport::default());
+    }
+
+    #[test]
+    fn test_scheduled_mass_cancel_of_participant() {
+        let (book, clock) = book_on_clock();
+        book.add_limit_order_as(OrderId::new(), Side::Sell, 110, 5, TimeInForce::Gtc, "mm")
+            .unwrap();
+        book.add_limit_order(OrderId::new(), Side::Sell, 120, 5)
+            .unwrap();
+        // Pull our orders one minute before a 70s expiry.
+        book.schedule_mass_cancel(ScheduledCancel::before(
+            70_000,
+            60_000,
+            MassCancelScope::Participant("mm".to_string()),
+        ));
+
+        clock.set(10_000);
+        assert_eq!(book.run_timers().mass_cancelled, 1);
+        assert_eq!(book.best_ask(), Some(120));
+    }
+
+    /// A book with two 5-lot asks at 100, `first` ahead of `second`.
+    fn book_with_two_asks() -> (OptionOrderBook, OrderId, OrderId) {
+        let book = OptionOrderBook::new("BTC-20240329-50000-C", OptionStyle::Call);
+        book.set_clock(Arc::new(crate::clock::ManualClock::new(1_000)));
+        let first = OrderId::new();
+        let second = OrderId::new();
+        book.add_limit_order_with_tif(first, Side::Sell, 100, 5, TimeInForce::Gtd(9))
+            .unwrap();
+        book.add_limit_order(second, Side::Sell, 100, 5).unwrap();
+        (book, first, second)
+    }
+
+    /// Sends an IOC buy at 100 and returns the quantities left resting.
+    ///
+    /// A partial fill sends the maker to the back of its level, so queue
+    /// order is probed with lifts that fill the front order completely.
+    fn lift(book: &OptionOrderBook, quantity: u64, orders: &[OrderId]) -> Vec<u64> {
+        book.add_limit_order_with_tif(OrderId::new(), Side::Buy, 100, quantity, TimeInForce::Ioc)
+            .unwrap();
+        orders
+            .iter()
+            .map(|id| {
+                book.inner()
+                    .get_order(*id)
+                    .map_or(0, |o| o.visible_quantity() + o.hidden_quantity())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_modify_size_reduction_keeps_priority() {
+        let (book, first, second) = book_with_two_asks();
+
+        let outcome = book.modify_order(first, 100, 3).unwrap();
+        assert_eq!(outcome, ModifyOutcome::Amended { quantity: 3 });
+        assert!(outcome.keeps_priority());
+        assert_eq!(book.ask_depth_at_price(100), 8);
+        assert_eq!(book.order_expiry(first), Some(9_000));
+
+        // The amended order still trades first.
+        assert_eq!(lift(&book, 1, &[first, second]), vec![2, 5]);
+        assert_eq!(
+            book.modify_order(first, 100, 2).unwrap(),
+            ModifyOutcome::Unchanged
+        );
+    }
+
+    #[test]
+    fn test_modify_price_change_replaces_order() {
+        let (book, first, second) = book_with_two_asks();
+        let third = OrderId::new();
+        book.add_limit_order(third, Side::Sell, 101, 5).unwrap();
+
+        let outcome = book.modify_order(first, 101, 5).unwrap();
+        assert_eq!(
+            outcome,
+            ModifyOutcome::Replaced {
+                price: 101,
+                quantity: 5
+            }
+        );
+        assert!(!outcome.keeps_priority());
+        assert_eq!(book.best_ask(), Some(100));
+        // The re-added order keeps its id and expiry.
+        assert_eq!(book.inner().get_order(first).unwrap().price(), 101);
+        assert_eq!(book.order_expiry(first), Some(9_000));
+
+        // At its new level it queues behind `third`.
+        book.cancel_order(second).unwrap();
+        book.add_limit_order_with_tif(OrderId::new(), Side::Buy, 101, 5, TimeInForce::Ioc)
+            .unwrap();
+        assert!(book.inner().get_order(third).is_none());
+        assert_eq!(book.ask_depth_at_price(101), 5);
+    }
+
+    #[test]
+    fn test_modify_back_to_earlier_level_keeps_place() {
+        let (book, first, second) = book_with_two_asks();
+
+        // The level still queues `first` by id, ahead of `second`.
+        book.modify_order(first, 101, 5).unwrap();
+        book.modify_order(first, 100, 5).unwrap();
+        assert_eq!(lift(&book, 5, &[first, second]), vec![0, 5]);
+    }
+
+    #[test]
+    fn test_modify_size_increase_replaces_order() {
+        let (book, first, second) = book_with_two_asks();
+
+        assert!(matches!(
+            book.modify_order(first, 100, 6).unwrap(),
+            ModifyOutcome::Replaced { quantity: 6, .. }
+        ));
+        assert_eq!(book.ask_depth_at_price(100), 11);
+        assert_eq!(lift(&book, 5, &[first, second]), vec![1, 5]);
+    }
+
+    #[test]
+    fn test_modify_partially_filled_order() {
+        let (book, first, second) = book_with_two_asks();
+        assert_eq!(lift(&book, 2, &[first, second]), vec![3, 5]);
+
+        // Quantities compare against what is left unfilled.
+        assert_eq!(
+            book.modify_order(first, 100, 3).unwrap(),
+            ModifyOutcome::Unchanged
+        );
+        assert_eq!(
+            book.modify_order(first, 100, 1).unwrap(),
+            ModifyOutcome::Amended { quantity: 1 }
+        );
+        assert_eq!(book.ask_depth_at_price(100), 6);
+        assert!(matches!(
+            book.modify_order(first, 100, 4).unwrap(),
+            ModifyOutcome::Replaced { quantity: 4, .. }
+        ));
+        assert_eq!(book.ask_depth_at_price(100), 9);
+
+        assert_eq!(lift(&book, 9, &[first, second]), vec![0, 0]);
+        assert_eq!(
+            book.modify_order(first, 100, 4).unwrap(),
+            ModifyOutcome::NotFound
+        );
+    }
+
+    #[test]
+    fn test_modify_iceberg_order() {
+        let book = OptionOrderBook::new("BTC-20240329-50000-C", OptionStyle::Call);
+        let iceberg = OrderId::new();
+        let behind = OrderId::new();
+        book.inner()
+            .add_iceberg_order(iceberg, 100, 4, 8, Side::Sell, TimeInForce::Gtc, None)
+            .unwrap();
+        book.add_limit_order(behind, Side::Sell, 100, 5).unwrap();
+        let shown = |id| {
+            let order = book.inner().get_order(id).unwrap();
+            (order.visible_quantity(), order.hidden_quantity())
+        };
+
+        // A reduction the reserve covers comes off the display in place.
+        assert_eq!(
+            book.modify_order(iceberg, 100, 10).unwrap(),
+            ModifyOutcome::Amended { quantity: 10 }
+        );
+        assert_eq!(shown(iceberg), (2, 8));
+        assert_eq!(lift(&book, 1, &[iceberg, behind])[1], 5);
+
+        // Reducing into the reserve re-adds it, still as an iceberg.
+        assert!(matches!(
+            book.modify_order(iceberg, 100, 5).unwrap(),
+            ModifyOutcome::Replaced { quantity: 5, .. }
+        ));
+        let (visible, hidden) = shown(iceberg);
+        assert_eq!(visible + hidden, 5);
+        assert!(hidden > 0 && visible <= 2);
+
+        let outcome = book.modify_order(iceberg, 101, 5).unwrap();
+        assert!(!outcome.keeps_priority());
+        assert_eq!(book.inner().get_order(iceberg).unwrap().price(), 101);
+        assert_eq!(shown(iceberg), (visible, hidden));
+    }
+
+    #[test]
+    fn test_modify_tagged_iceberg_order() {
+        let book = OptionOrderBook::new("BTC-20240329-50000-C", OptionStyle::Call);
+        book.set_self_trade_prevention(Some(SelfTradePrevention::CancelNewest));
+        let iceberg = OrderId::new();
+        book.add_iceberg_order_as(iceberg, Side::Sell, 100, 4, 8, TimeInForce::Gtc, "mm")
+            .unwrap();
+
+        // The replace keeps both the reserve and the participant tag.
+        assert!(matches!(
+            book.modify_order(iceberg, 101, 9).unwrap(),
+            ModifyOutcome::Replaced { quantity: 9, .. }
+        ));
+        let order = book.inner().get_order(iceberg).unwrap();
+        assert_eq!((order.visible_quantity(), order.hidden_quantity()), (4, 5));
+        assert_eq!(book.ask_depth_at_price(101), 9);
+        assert_eq!(book.participant(iceberg).as_deref(), Some("mm"));
+        assert!(
+            book.add_limit_order_as(OrderId::new(), Side::Buy, 101, 1, TimeInForce::Gtc, "mm")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_modify_unknown_or_cancelled_order() {
+        let (book, first, _) = book_with_two_asks();
+
+        assert_eq!(
+            book.modify_order(OrderId::new(), 100, 1).unwrap(),
+            ModifyOutcome::NotFound
+        );
+        assert!(!ModifyOutcome::NotFound.is_applied());
+        assert_eq!(
+            book.modify_order(first, 100, 0).unwrap(),
+            ModifyOutcome::Cancelled
+        );
+        assert_eq!(book.order_expiry(first), None);
+        assert_eq!(
+            book.modify_order(first, 100, 1).unwrap(),
+            ModifyOutcome::NotFound
+        );
+        assert_eq!(book.order_count(), 1);
+    }
 }
@@ -3,12 +3,28 @@
 //! This module provides the [`OptionChainOrderBook`] and [`OptionChainOrderBookManager`]
 //! for managing all strikes within a single expiration.
 
+use super::book::OptionOrderBook;
+use super::filter::{ChainFilter, ChainView};
+use super::listing::ListingRules;
+use super::quote::Quote;
+use super::registry::ContractRegistry;
 use super::strike::{StrikeOrderBook, StrikeOrderBookManager};
+use super::timers::{ScheduledCancel, TimerReport};
+use crate::clock::Clock;
 use crate::error::{Error, Result};
 use crossbeam_skiplist::SkipMap;
 use optionstratlib::ExpirationDate;
 use orderbook_rs::OrderId;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Sentinel stored while no time to expiry has been published.
+const TAU_UNSET: u64 = u64::MAX;
+
+/// Milliseconds in a 365-day year.
+const MS_PER_YEAR: Decimal = dec!(31_536_000_000);
 
 /// Option chain order book for a single expiration.
 ///
@@ -32,6 +48,8 @@ pub struct OptionChainOrderBook {
     strikes: Arc<StrikeOrderBookManager>,
     /// Unique identifier for this option chain order book.
     id: OrderId,
+    /// Time to expiry in milliseconds, as of the last clock tick.
+    time_to_expiry_ms: AtomicU64,
 }
 
 impl OptionChainOrderBook {
@@ -43,13 +61,34 @@ impl OptionChainOrderBook {
     /// * `expiration` - The expiration date
     #[must_use]
     pub fn new(underlying: impl Into<String>, expiration: ExpirationDate) -> Self {
+        Self::with_registry(underlying, expiration, Arc::new(ContractRegistry::new()))
+    }
+
+    /// Creates a new option chain order book sharing a contract registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - The underlying asset symbol (e.g., "BTC")
+    /// * `expiration` - The expiration date
+    /// * `registry` - The registry used to intern contract symbols
+    #[must_use]
+    pub fn with_registry(
+        underlying: impl Into<String>,
+        expiration: ExpirationDate,
+        registry: Arc<ContractRegistry>,
+    ) -> Self {
         let underlying = underlying.into();
 
         Self {
-            strikes: Arc::new(StrikeOrderBookManager::new(&underlying, expiration)),
+            strikes: Arc::new(StrikeOrderBookManager::with_registry(
+                &underlying,
+                expiration,
+                registry,
+            )),
             underlying,
             expiration,
             id: OrderId::new(),
+            time_to_expiry_ms: AtomicU64::new(TAU_UNSET),
         }
     }
 
@@ -65,6 +104,39 @@ impl OptionChainOrderBook {
         &self.expiration
     }
 
+    /// Returns the time to expiry in milliseconds published by the last
+    /// clock tick, or `None` if no tick has run yet.
+    #[must_use]
+    pub fn time_to_expiry_ms(&self) -> Option<u64> {
+        match self.time_to_expiry_ms.load(Ordering::Acquire) {
+            TAU_UNSET => None,
+            ms => Some(ms),
+        }
+    }
+
+    /// Returns the time to expiry in years (365-day basis) published by the
+    /// last clock tick, or `None` if no tick has run yet.
+    ///
+    /// Derived from integer milliseconds, so every reader of the same tick
+    /// sees exactly the same value.
+    #[must_use]
+    pub fn tau(&self) -> Option<Decimal> {
+        self.time_to_expiry_ms()
+            .map(|ms| Decimal::from(ms) / MS_PER_YEAR)
+    }
+
+    /// Publishes the time to expiry for every contract in this chain.
+    ///
+    /// Normally called by [`crate::clock::ExpiryClock`] once per tick.
+    ///
+    /// # Arguments
+    ///
+    /// * `ms` - Milliseconds until expiry, zero once expired
+    pub fn set_time_to_expiry_ms(&self, ms: u64) {
+        self.time_to_expiry_ms
+            .store(ms.min(TAU_UNSET - 1), Ordering::Release);
+    }
+
     /// Returns the unique identifier for this option chain order book.
     #[must_use]
     pub const fn id(&self) -> OrderId {
@@ -120,6 +192,67 @@ impl OptionChainOrderBook {
         self.strikes.total_order_count()
     }
 
+    /// Lists the strike ladder of the listing rules around spot and
+    /// returns the listed strikes, ascending.
+    ///
+    /// Strikes already listed are kept as they are. With a tiering policy
+    /// in the rules, cold strikes are listed as placeholders.
+    ///
+    /// # Arguments
+    ///
+    /// * `spot` - Spot price in the same units as strikes
+    /// * `rules` - Strike intervals, counts and rounding
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`ListingRules::strikes`].
+    pub fn generate_strikes(&self, spot: u64, rules: &ListingRules) -> Result<Vec<u64>> {
+        let ladder = rules.strikes(spot)?;
+        for &strike in &ladder {
+            match &rules.tiering {
+                Some(policy) => {
+                    self.strikes.list(strike, spot, policy);
+                }
+                None => {
+                    self.strikes.get_or_create(strike);
+                }
+            }
+        }
+        Ok(ladder)
+    }
+
+    /// Calls `f` with every option book, by ascending strike, call first.
+    pub fn for_each_book<F: FnMut(&OptionOrderBook)>(&self, f: F) {
+        self.strikes.for_each_book(f);
+    }
+
+    /// Sets the clock of every book of the chain, including strikes listed
+    /// later.
+    pub fn set_clock(&self, clock: Arc<dyn Clock>) {
+        self.strikes.set_clock(clock);
+    }
+
+    /// Schedules a mass cancel in every book of the chain, e.g. pulling our
+    /// orders a minute before expiry or at the session close.
+    ///
+    /// Strikes listed before the cancel falls due are covered too.
+    pub fn schedule_mass_cancel(&self, cancel: &ScheduledCancel) {
+        self.strikes.schedule_mass_cancel(cancel);
+    }
+
+    /// Runs the timers of every book of the chain, by each book's clock.
+    pub fn run_timers(&self) -> TimerReport {
+        let mut report = TimerReport::default();
+        self.for_each_book(|book| report += book.run_timers());
+        report
+    }
+
+    /// Calls `f` with every option book and its best quote, without
+    /// collecting them first.
+    pub fn for_each_quote<F: FnMut(&OptionOrderBook, Quote)>(&self, f: F) {
+        self.strikes.for_each_quote(f);
+    }
+
     /// Returns the ATM strike closest to the given spot price.
     ///
     /// # Errors
@@ -129,6 +262,16 @@ impl OptionChainOrderBook {
         self.strikes.atm_strike(spot)
     }
 
+    /// Returns a view over the contracts matching a filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Tenor, moneyness and liquidity criteria
+    #[must_use]
+    pub fn filter(&self, filter: &ChainFilter) -> ChainView {
+        ChainView::from_chain(self, filter)
+    }
+
     /// Returns statistics about this option chain.
     #[must_use]
     pub fn stats(&self) -> OptionChainStats {
@@ -169,6 +312,8 @@ pub struct OptionChainOrderBookManager {
     chains: SkipMap<ExpirationDate, Arc<OptionChainOrderBook>>,
     /// The underlying asset symbol.
     underlying: String,
+    /// Registry shared by all chains of this manager.
+    registry: Arc<ContractRegistry>,
 }
 
 impl OptionChainOrderBookManager {
@@ -179,9 +324,21 @@ impl OptionChainOrderBookManager {
     /// * `underlying` - The underlying asset symbol
     #[must_use]
     pub fn new(underlying: impl Into<String>) -> Self {
+        Self::with_registry(underlying, Arc::new(ContractRegistry::new()))
+    }
+
+    /// Creates a new option chain manager sharing a contract registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - The underlying asset symbol
+    /// * `registry` - The registry used to intern contract symbols
+    #[must_use]
+    pub fn with_registry(underlying: impl Into<String>, registry: Arc<ContractRegistry>) -> Self {
         Self {
             chains: SkipMap::new(),
             underlying: underlying.into(),
+            registry,
         }
     }
 
@@ -208,7 +365,11 @@ impl OptionChainOrderBookManager {
         if let Some(entry) = self.chains.get(&expiration) {
             return Arc::clone(entry.value());
         }
-        let chain = Arc::new(OptionChainOrderBook::new(&self.underlying, expiration));
+        let chain = Arc::new(OptionChainOrderBook::with_registry(
+            &self.underlying,
+            expiration,
+            Arc::clone(&self.registry),
+        ));
         self.chains.insert(expiration, Arc::clone(&chain));
         chain
     }
@@ -240,9 +401,15 @@ impl OptionChainOrderBookManager {
         self.chains.iter()
     }
 
-    /// Removes an option chain.
+    /// Removes an option chain, releasing its contracts from the registry.
     pub fn remove(&self, expiration: &ExpirationDate) -> bool {
-        self.chains.remove(expiration).is_some()
+        match self.chains.remove(expiration) {
+            Some(entry) => {
+                entry.value().strikes().release_all_contracts();
+                true
+            }
+            None => false,
+        }
     }
 
     /// Returns the total order count across all chains.
@@ -273,6 +440,49 @@ mod tests {
         assert!(chain.is_empty());
     }
 
+    #[test]
+    fn test_option_chain_time_to_expiry() {
+        let chain = OptionChainOrderBook::new("BTC", test_expiration());
+        assert_eq!(chain.tau(), None);
+
+        chain.set_time_to_expiry_ms(15_768_000_000);
+
+        assert_eq!(chain.time_to_expiry_ms(), Some(15_768_000_000));
+        assert_eq!(chain.tau(), Some(rust_decimal_macros::dec!(0.5)));
+    }
+
+    #[test]
+    fn test_generate_strikes_lists_ladder() {
+        use crate::orderbook::{BookTier, ListingRules, StrikeBand, TieringPolicy};
+        use rust_decimal_macros::dec;
+
+        let chain = OptionChainOrderBook::new("BTC", test_expiration());
+        let rules = ListingRules {
+            bands: vec![
+                StrikeBand::new(dec!(0.04), 1000),
+                StrikeBand::new(dec!(0.5), 5000),
+            ],
+            strikes_above: 4,
+            strikes_below: 4,
+            tiering: Some(TieringPolicy {
+                min_moneyness: dec!(0.9),
+                max_moneyness: dec!(1.1),
+                max_days: None,
+            }),
+            ..ListingRules::default()
+        };
+        let ladder = chain.generate_strikes(50_400, &rules).unwrap();
+        assert_eq!(
+            ladder,
+            vec![
+                40_000, 45_000, 48_000, 49_000, 50_000, 51_000, 52_000, 53_000, 55_000
+            ]
+        );
+        assert_eq!(chain.strikes().listed_strikes(), ladder);
+        assert_eq!(chain.strikes().tier(50_000), Some(BookTier::Materialized));
+        assert_eq!(chain.strikes().tier(45_000), Some(BookTier::Placeholder));
+    }
+
     #[test]
     fn test_option_chain_strikes() {
         let chain = OptionChainOrderBook::new("BTC", test_expiration());
@@ -0,0 +1,505 @@
+//! Combo book module.
+//!
+//! This module provides synthetic order books for option strategies traded
+//! as one unit, such as straddles, strangles and verticals:
+//!
+//! - [`ComboDefinition`] names the combo and its legs with signed ratios
+//! - [`ComboBook::implied_quote`] derives the combo's bid and ask from the
+//!   leg books (implied out)
+//! - [`ComboBook::implied_legs`] turns a combo order into the leg orders
+//!   that would complete it against the other legs' touch (implied in)
+//! - [`ComboBook::decompose`] splits a combo fill into leg fills for
+//!   [`crate::inventory::InventoryManager::record_combo_fill`]
+//!
+//! Combo prices are in the ticks of the leg books and may be negative,
+//! e.g. for a vertical sold as a credit, so they are signed. Buying one
+//! combo unit buys `ratio` contracts of every leg with a positive ratio
+//! and sells `|ratio|` of every leg with a negative one.
+
+use super::book::OptionOrderBook;
+use super::underlying::UnderlyingOrderBookManager;
+use crate::error::{Error, Result};
+use crate::inventory::{ComboFill, ComboLegFill};
+use orderbook_rs::Side;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// One leg of a combo definition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComboDefinitionLeg {
+    /// Leg contract symbol.
+    pub symbol: String,
+    /// Signed contracts per combo unit (negative = sold).
+    pub ratio: i64,
+}
+
+impl ComboDefinitionLeg {
+    /// Creates a leg.
+    #[must_use]
+    pub fn new(symbol: impl Into<String>, ratio: i64) -> Self {
+        Self {
+            symbol: symbol.into(),
+            ratio,
+        }
+    }
+
+    /// Returns the leg's side when the combo is bought or sold.
+    #[must_use]
+    pub const fn side(&self, combo_side: Side) -> Side {
+        match (combo_side, self.ratio > 0) {
+            (Side::Buy, true) | (Side::Sell, false) => Side::Buy,
+            _ => Side::Sell,
+        }
+    }
+}
+
+/// A combo made of legs with ratios.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComboDefinition {
+    /// Combo symbol.
+    pub symbol: String,
+    /// Legs.
+    pub legs: Vec<ComboDefinitionLeg>,
+}
+
+impl ComboDefinition {
+    /// Creates a combo definition.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if there is no leg, a ratio is
+    /// zero or a leg symbol repeats.
+    pub fn new(symbol: impl Into<String>, legs: Vec<ComboDefinitionLeg>) -> Result<Self> {
+        let symbol = symbol.into();
+        if legs.is_empty() {
+            return Err(Error::configuration(format!("combo {symbol} has no legs")));
+        }
+        for (i, leg) in legs.iter().enumerate() {
+            if leg.ratio == 0 {
+                return Err(Error::configuration(format!(
+                    "combo {symbol} leg {} has a zero ratio",
+                    leg.symbol
+                )));
+            }
+            if legs[..i].iter().any(|l| l.symbol == leg.symbol) {
+                return Err(Error::configuration(format!(
+                    "combo {symbol} lists leg {} twice",
+                    leg.symbol
+                )));
+            }
+        }
+        Ok(Self { symbol, legs })
+    }
+
+    /// Creates a long straddle: one call and one put at the same strike.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if both legs are the same.
+    pub fn straddle(
+        symbol: impl Into<String>,
+        call_symbol: impl Into<String>,
+        put_symbol: impl Into<String>,
+    ) -> Result<Self> {
+        Self::new(
+            symbol,
+            vec![
+                ComboDefinitionLeg::new(call_symbol, 1),
+                ComboDefinitionLeg::new(put_symbol, 1),
+            ],
+        )
+    }
+
+    /// Creates a long strangle: one put below and one call above.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if both legs are the same.
+    pub fn strangle(
+        symbol: impl Into<String>,
+        put_symbol: impl Into<String>,
+        call_symbol: impl Into<String>,
+    ) -> Result<Self> {
+        Self::new(
+            symbol,
+            vec![
+                ComboDefinitionLeg::new(put_symbol, 1),
+                ComboDefinitionLeg::new(call_symbol, 1),
+            ],
+        )
+    }
+
+    /// Creates a vertical spread: one contract bought and one sold, of the
+    /// same style and expiration at different strikes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if both legs are the same.
+    pub fn vertical(
+        symbol: impl Into<String>,
+        long_symbol: impl Into<String>,
+        short_symbol: impl Into<String>,
+    ) -> Result<Self> {
+        Self::new(
+            symbol,
+            vec![
+                ComboDefinitionLeg::new(long_symbol, 1),
+                ComboDefinitionLeg::new(short_symbol, -1),
+            ],
+        )
+    }
+}
+
+/// Combo bid and ask implied by the leg books.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyntheticQuote {
+    /// Price at which the combo can be sold into the legs.
+    pub bid_price: Option<i128>,
+    /// Combo units available at the bid.
+    pub bid_size: u64,
+    /// Price at which the combo can be bought from the legs.
+    pub ask_price: Option<i128>,
+    /// Combo units available at the ask.
+    pub ask_size: u64,
+}
+
+/// A leg order implied by a combo order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImpliedLegOrder {
+    /// Leg contract symbol.
+    pub symbol: String,
+    /// Leg side.
+    pub side: Side,
+    /// Leg price in ticks.
+    pub price: u128,
+    /// Leg quantity.
+    pub quantity: u64,
+}
+
+/// Synthetic order book of a combo over its leg books.
+pub struct ComboBook {
+    /// Combo legs.
+    definition: ComboDefinition,
+    /// Leg books, in leg order.
+    books: Vec<Arc<OptionOrderBook>>,
+}
+
+impl ComboBook {
+    /// Creates a combo book over the given leg books.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the books do not match the
+    /// legs one to one, in order.
+    pub fn new(definition: ComboDefinition, books: Vec<Arc<OptionOrderBook>>) -> Result<Self> {
+        let matches = books.len() == definition.legs.len()
+            && books
+                .iter()
+                .zip(&definition.legs)
+                .all(|(book, leg)| book.symbol() == leg.symbol);
+        if !matches {
+            return Err(Error::configuration(format!(
+                "leg books do not match the legs of combo {}",
+                definition.symbol
+            )));
+        }
+        Ok(Self { definition, books })
+    }
+
+    /// Creates a combo book over the leg books of a manager.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContractNotFound` if a leg has no book.
+    pub fn from_manager(
+        definition: ComboDefinition,
+        manager: &UnderlyingOrderBookManager,
+    ) -> Result<Self> {
+        let books = definition
+            .legs
+            .iter()
+            .map(|leg| manager.book(manager.contract_id(&leg.symbol)?))
+            .collect::<Result<Vec<_>>>()?;
+        Self::new(definition, books)
+    }
+
+    /// Returns the combo definition.
+    #[must_use]
+    pub const fn definition(&self) -> &ComboDefinition {
+        &self.definition
+    }
+
+    /// Returns the combo quote implied by the leg books' touch.
+    ///
+    /// A side is empty when a leg has no price on the side it would trade.
+    #[must_use]
+    pub fn implied_quote(&self) -> SyntheticQuote {
+        let (bid_price, bid_size) = self.implied_side(Side::Sell, None).unzip();
+        let (ask_price, ask_size) = self.implied_side(Side::Buy, None).unzip();
+        SyntheticQuote {
+            bid_price,
+            bid_size: bid_size.unwrap_or(0),
+            ask_price,
+            ask_size: ask_size.unwrap_or(0),
+        }
+    }
+
+    /// Returns the leg orders implied by a combo order.
+    ///
+    /// For each leg, the implied order is the leg price at which the combo
+    /// order completes when the other legs trade at their touch, rounded
+    /// in the combo order's favour. Legs whose implied price would be
+    /// negative, or whose other legs have no liquidity, are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `combo_side` - Side of the combo order
+    /// * `combo_price` - Combo price in ticks
+    /// * `quantity` - Combo units
+    #[must_use]
+    pub fn implied_legs(
+        &self,
+        combo_side: Side,
+        combo_price: i128,
+        quantity: u64,
+    ) -> Vec<ImpliedLegOrder> {
+        self.definition
+            .legs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, leg)| {
+                let (others, size) = self.implied_side(combo_side, Some(i))?;
+                let quantity = quantity.min(size).checked_mul(leg.ratio.unsigned_abs())?;
+                let side = leg.side(combo_side);
+                let price = divide(combo_price - others, i128::from(leg.ratio), side);
+                let price = u128::try_from(price).ok()?;
+                (quantity > 0).then(|| ImpliedLegOrder {
+                    symbol: leg.symbol.clone(),
+                    side,
+                    price,
+                    quantity,
+                })
+            })
+            .collect()
+    }
+
+    /// Decomposes a combo fill into leg fills.
+    ///
+    /// Leg prices are the leg mids, or the one-sided touch, scaled so that
+    /// the legs add up to the combo price; if the legs are worth zero
+    /// together, the difference goes to the first leg.
+    ///
+    /// # Arguments
+    ///
+    /// * `fill_id` - Fill identifier
+    /// * `side` - Our side of the combo
+    /// * `quantity` - Combo units filled
+    /// * `price` - Combo price in ticks
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if a leg book is empty, or
+    /// `Error::ValidationError` if the resulting fill is invalid.
+    pub fn decompose(
+        &self,
+        fill_id: impl Into<String>,
+        side: Side,
+        quantity: u64,
+        price: i128,
+    ) -> Result<ComboFill> {
+        let mut references = self
+            .books
+            .iter()
+            .map(|book| {
+                let bid = book.best_bid().map(Decimal::from);
+                let ask = book.best_ask().map(Decimal::from);
+                match (bid, ask) {
+                    (Some(bid), Some(ask)) => Ok((bid + ask) / Decimal::TWO),
+                    (Some(touch), None) | (None, Some(touch)) => Ok(touch),
+                    (None, None) => Err(Error::no_data(format!(
+                        "leg book {} is empty",
+                        book.symbol()
+                    ))),
+                }
+            })
+            .collect::<Result<Vec<Decimal>>>()?;
+        let price = Decimal::from(price);
+        let reference: Decimal = references
+            .iter()
+            .zip(&self.definition.legs)
+            .map(|(r, leg)| r * Decimal::from(leg.ratio))
+            .sum();
+        if reference.is_zero() {
+            references[0] += price / Decimal::from(self.definition.legs[0].ratio);
+        } else {
+            let scale = price / reference;
+            references.iter_mut().for_each(|r| *r *= scale);
+        }
+
+        let quantity = match side {
+            Side::Buy => Decimal::from(quantity),
+            Side::Sell => -Decimal::from(quantity),
+        };
+        let fill = ComboFill {
+            fill_id: fill_id.into(),
+            combo_symbol: self.definition.symbol.clone(),
+            quantity,
+            price,
+            legs: self
+                .definition
+                .legs
+                .iter()
+                .zip(references)
+                .map(|(leg, leg_price)| ComboLegFill {
+                    symbol: leg.symbol.clone(),
+                    quantity: quantity * Decimal::from(leg.ratio),
+                    price: leg_price,
+                })
+                .collect(),
+        };
+        fill.validate()?;
+        Ok(fill)
+    }
+
+    /// Returns the combo price and size of taking the legs' touch for a
+    /// combo side, leaving out leg `skip`.
+    fn implied_side(&self, combo_side: Side, skip: Option<usize>) -> Option<(i128, u64)> {
+        let mut price = 0i128;
+        let mut size = u64::MAX;
+        for (i, (leg, book)) in self.definition.legs.iter().zip(&self.books).enumerate() {
+            if skip == Some(i) {
+                continue;
+            }
+            let (touch, depth) = match leg.side(combo_side) {
+                Side::Buy => {
+                    let ask = book.best_ask()?;
+                    (ask, book.ask_depth_at_price(ask))
+                }
+                Side::Sell => {
+                    let bid = book.best_bid()?;
+                    (bid, book.bid_depth_at_price(bid))
+                }
+            };
+            price = i128::try_from(touch)
+                .ok()?
+                .checked_mul(i128::from(leg.ratio))?
+                .checked_add(price)?;
+            size = size.min(depth / leg.ratio.unsigned_abs());
+        }
+        Some((price, size))
+    }
+}
+
+/// Divides a combo amount by a leg ratio, rounding down for leg bids and
+/// up for leg asks.
+fn divide(amount: i128, ratio: i128, side: Side) -> i128 {
+    let (amount, ratio) = if ratio < 0 {
+        (-amount, -ratio)
+    } else {
+        (amount, ratio)
+    };
+    match side {
+        Side::Buy => amount.div_euclid(ratio),
+        Side::Sell => -(-amount).div_euclid(ratio),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::OptionStyle;
+    use orderbook_rs::OrderId;
+    use rust_decimal_macros::dec;
+
+    fn leg(
+        symbol: &str,
+        style: OptionStyle,
+        bid: (u128, u64),
+        ask: (u128, u64),
+    ) -> Arc<OptionOrderBook> {
+        let book = OptionOrderBook::new(symbol, style);
+        book.add_limit_order(OrderId::new(), Side::Buy, bid.0, bid.1)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), Side::Sell, ask.0, ask.1)
+            .unwrap();
+        Arc::new(book)
+    }
+
+    fn straddle() -> ComboBook {
+        let call = leg(
+            "BTC-20240329-50000-C",
+            OptionStyle::Call,
+            (100, 10),
+            (110, 10),
+        );
+        let put = leg("BTC-20240329-50000-P", OptionStyle::Put, (50, 4), (60, 6));
+        let definition =
+            ComboDefinition::straddle("BTC-20240329-50000-STRADDLE", call.symbol(), put.symbol())
+                .unwrap();
+        ComboBook::new(definition, vec![call, put]).unwrap()
+    }
+
+    #[test]
+    fn test_implied_quote() {
+        let quote = straddle().implied_quote();
+        assert_eq!(quote.bid_price, Some(150));
+        assert_eq!(quote.bid_size, 4);
+        assert_eq!(quote.ask_price, Some(170));
+        assert_eq!(quote.ask_size, 6);
+
+        let long = leg(
+            "BTC-20240329-50000-C",
+            OptionStyle::Call,
+            (100, 10),
+            (110, 10),
+        );
+        let short = leg("BTC-20240329-55000-C", OptionStyle::Call, (40, 8), (45, 3));
+        let definition =
+            ComboDefinition::vertical("CALL-SPREAD", long.symbol(), short.symbol()).unwrap();
+        let vertical = ComboBook::new(definition, vec![long, short]).unwrap();
+        let quote = vertical.implied_quote();
+        // Buy the spread: pay the long ask, receive the short bid.
+        assert_eq!(quote.ask_price, Some(70));
+        assert_eq!(quote.ask_size, 8);
+        assert_eq!(quote.bid_price, Some(55));
+        assert_eq!(quote.bid_size, 3);
+    }
+
+    #[test]
+    fn test_implied_legs() {
+        let book = straddle();
+        let legs = book.implied_legs(Side::Buy, 165, 10);
+        assert_eq!(legs.len(), 2);
+        assert_eq!(legs[0].side, Side::Buy);
+        assert_eq!(legs[0].price, 105);
+        assert_eq!(legs[0].quantity, 6);
+        assert_eq!(legs[1].price, 55);
+        assert_eq!(legs[1].quantity, 10);
+
+        // Selling the straddle below the call bid implies no put order.
+        let legs = book.implied_legs(Side::Sell, 90, 1);
+        assert_eq!(legs.len(), 1);
+        assert_eq!(legs[0].symbol, "BTC-20240329-50000-C");
+        assert_eq!(legs[0].side, Side::Sell);
+        assert_eq!(legs[0].price, 40);
+
+        assert_eq!(divide(7, -2, Side::Sell), -3);
+        assert_eq!(divide(7, 2, Side::Buy), 3);
+    }
+
+    #[test]
+    fn test_decompose_and_validation() {
+        let book = straddle();
+        let fill = book.decompose("F1", Side::Sell, 2, 168).unwrap();
+        assert_eq!(fill.quantity, dec!(-2));
+        assert_eq!(fill.legs[0].quantity, dec!(-2));
+        assert_eq!(fill.legs[0].price, dec!(110.25));
+        assert_eq!(fill.legs[1].price, dec!(57.75));
+        assert_eq!(fill.leg_premium(), dec!(-336));
+
+        assert!(ComboDefinition::straddle("S", "C", "C").is_err());
+        let definition = ComboDefinition::straddle("S", "C", "P").unwrap();
+        assert!(ComboBook::new(definition, Vec::new()).is_err());
+    }
+}
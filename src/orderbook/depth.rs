@@ -0,0 +1,417 @@
+//! Level-2 depth streaming module.
+//!
+//! This module provides incremental L2 updates for option books:
+//!
+//! - [`DepthTracker`]: Diffs the top price levels of a book against the
+//!   last state it saw, producing a [`DepthUpdate`] of levels added,
+//!   changed or removed
+//! - [`DepthFeed`]: Per-symbol subscriptions over an
+//!   [`UnderlyingOrderBookManager`], publishing each contract's updates to
+//!   its [`DepthListener`]s when polled
+//!
+//! The first update of a contract lists every tracked level as added, so a
+//! consumer can build its book from the stream alone. Updates carry a
+//! per-contract sequence number starting at one; a gap means the consumer
+//! missed an update and should resubscribe. A book removed from the
+//! hierarchy, e.g. at expiry, publishes the removal of all its levels.
+
+use super::book::OptionOrderBook;
+use super::underlying::UnderlyingOrderBookManager;
+use crate::error::{Error, Result};
+use orderbook_rs::Side;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+
+/// Kind of change to a price level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LevelChangeKind {
+    /// The level is new.
+    Added,
+    /// The level's quantity changed.
+    Changed,
+    /// The level is gone.
+    Removed,
+}
+
+/// A change to one price level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LevelChange {
+    /// Side of the level.
+    pub side: Side,
+    /// Price of the level.
+    pub price: u128,
+    /// Quantity at the level after the change, zero when removed.
+    pub quantity: u64,
+    /// Kind of change.
+    pub kind: LevelChangeKind,
+}
+
+/// Incremental L2 update of one contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepthUpdate {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Per-contract sequence number, starting at one.
+    pub sequence: u64,
+    /// Changed levels, bids from best to worst, then asks from best to
+    /// worst.
+    pub changes: Vec<LevelChange>,
+}
+
+/// Last published levels of a contract.
+#[derive(Debug, Default)]
+struct TrackedDepth {
+    bids: BTreeMap<u128, u64>,
+    asks: BTreeMap<u128, u64>,
+    sequence: u64,
+}
+
+/// Computes incremental L2 updates between book states.
+pub struct DepthTracker {
+    /// Price levels tracked per side.
+    depth: usize,
+    /// Last state per contract.
+    books: Mutex<HashMap<String, TrackedDepth>>,
+}
+
+impl DepthTracker {
+    /// Creates a tracker.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - Price levels tracked on each side
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the depth is zero.
+    pub fn new(depth: usize) -> Result<Self> {
+        if depth == 0 {
+            return Err(Error::configuration("depth must be positive"));
+        }
+        Ok(Self {
+            depth,
+            books: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Returns the price levels tracked on each side.
+    #[must_use]
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Diffs a book against its last state, returning the changes if any.
+    pub fn update(&self, book: &OptionOrderBook) -> Option<DepthUpdate> {
+        let bids = self.levels(book, Side::Buy);
+        let asks = self.levels(book, Side::Sell);
+        let mut books = self.lock();
+        let tracked = books.entry(book.symbol().to_string()).or_default();
+        let mut changes = diff(Side::Buy, &tracked.bids, &bids);
+        changes.extend(diff(Side::Sell, &tracked.asks, &asks));
+        if changes.is_empty() {
+            return None;
+        }
+        tracked.bids = bids;
+        tracked.asks = asks;
+        tracked.sequence += 1;
+        Some(DepthUpdate {
+            symbol: book.symbol().to_string(),
+            sequence: tracked.sequence,
+            changes,
+        })
+    }
+
+    /// Stops tracking a contract, returning the removal of its levels if
+    /// any were published.
+    pub fn remove(&self, symbol: &str) -> Option<DepthUpdate> {
+        let tracked = self.lock().remove(symbol)?;
+        let empty = BTreeMap::new();
+        let mut changes = diff(Side::Buy, &tracked.bids, &empty);
+        changes.extend(diff(Side::Sell, &tracked.asks, &empty));
+        if changes.is_empty() {
+            return None;
+        }
+        Some(DepthUpdate {
+            symbol: symbol.to_string(),
+            sequence: tracked.sequence + 1,
+            changes,
+        })
+    }
+
+    /// Returns the number of tracked contracts.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns true if no contract is tracked.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Returns the top levels of one side of a book.
+    fn levels(&self, book: &OptionOrderBook, side: Side) -> BTreeMap<u128, u64> {
+        book.inner()
+            .levels_with_cumulative_depth(side)
+            .take(self.depth)
+            .filter(|level| level.quantity > 0)
+            .map(|level| (level.price, level.quantity))
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, TrackedDepth>> {
+        self.books
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Returns the changes from one side's old levels to its new levels, best
+/// price first.
+fn diff(side: Side, old: &BTreeMap<u128, u64>, new: &BTreeMap<u128, u64>) -> Vec<LevelChange> {
+    let mut changes = Vec::new();
+    for (&price, &quantity) in new {
+        let kind = match old.get(&price) {
+            None => LevelChangeKind::Added,
+            Some(&previous) if previous != quantity => LevelChangeKind::Changed,
+            Some(_) => continue,
+        };
+        changes.push(LevelChange {
+            side,
+            price,
+            quantity,
+            kind,
+        });
+    }
+    for &price in old.keys().filter(|price| !new.contains_key(price)) {
+        changes.push(LevelChange {
+            side,
+            price,
+            quantity: 0,
+            kind: LevelChangeKind::Removed,
+        });
+    }
+    match side {
+        Side::Buy => changes.sort_by_key(|c| std::cmp::Reverse(c.price)),
+        Side::Sell => changes.sort_by_key(|c| c.price),
+    }
+    changes
+}
+
+/// Receiver of a contract's depth updates, e.g. a GUI or a downstream feed.
+pub trait DepthListener: Send + Sync {
+    /// Called with each update of a subscribed contract.
+    fn on_depth_update(&self, update: &DepthUpdate);
+}
+
+/// Depth subscriptions per contract over the order book hierarchy.
+pub struct DepthFeed {
+    /// The order book hierarchy.
+    manager: Arc<UnderlyingOrderBookManager>,
+    /// Diff state.
+    tracker: DepthTracker,
+    /// Listeners per contract symbol.
+    subscriptions: RwLock<HashMap<String, Vec<Arc<dyn DepthListener>>>>,
+}
+
+impl DepthFeed {
+    /// Creates a depth feed.
+    ///
+    /// # Arguments
+    ///
+    /// * `manager` - The order book hierarchy
+    /// * `depth` - Price levels streamed on each side
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the depth is zero.
+    pub fn new(manager: Arc<UnderlyingOrderBookManager>, depth: usize) -> Result<Self> {
+        Ok(Self {
+            manager,
+            tracker: DepthTracker::new(depth)?,
+            subscriptions: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Returns the diff state.
+    #[must_use]
+    pub const fn tracker(&self) -> &DepthTracker {
+        &self.tracker
+    }
+
+    /// Subscribes a listener to a contract's depth updates.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContractNotFound` if the contract is not listed.
+    pub fn subscribe(&self, symbol: &str, listener: Arc<dyn DepthListener>) -> Result<()> {
+        self.manager.contract_id(symbol)?;
+        if let Ok(mut subscriptions) = self.subscriptions.write() {
+            subscriptions
+                .entry(symbol.to_string())
+                .or_default()
+                .push(listener);
+        }
+        Ok(())
+    }
+
+    /// Drops every subscription of a contract.
+    pub fn unsubscribe(&self, symbol: &str) -> bool {
+        self.tracker.remove(symbol);
+        self.subscriptions
+            .write()
+            .is_ok_and(|mut subscriptions| subscriptions.remove(symbol).is_some())
+    }
+
+    /// Returns the subscribed contract symbols.
+    #[must_use]
+    pub fn symbols(&self) -> Vec<String> {
+        self.subscriptions
+            .read()
+            .map(|subscriptions| subscriptions.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Diffs every subscribed contract and publishes the updates.
+    ///
+    /// Contracts no longer in the hierarchy publish the removal of their
+    /// levels and are unsubscribed.
+    ///
+    /// Returns the number of updates published.
+    pub fn poll(&self) -> usize {
+        let subscriptions: Vec<(String, Vec<Arc<dyn DepthListener>>)> =
+            match self.subscriptions.read() {
+                Ok(subscriptions) => subscriptions
+                    .iter()
+                    .map(|(symbol, listeners)| (symbol.clone(), listeners.clone()))
+                    .collect(),
+                Err(_) => return 0,
+            };
+
+        let mut published = 0;
+        for (symbol, listeners) in subscriptions {
+            let book = self
+                .manager
+                .contract_id(&symbol)
+                .and_then(|id| self.manager.book(id));
+            let update = match book {
+                Ok(book) => self.tracker.update(&book),
+                Err(_) => {
+                    if let Ok(mut subscriptions) = self.subscriptions.write() {
+                        subscriptions.remove(&symbol);
+                    }
+                    self.tracker.remove(&symbol)
+                }
+            };
+            if let Some(update) = update {
+                for listener in &listeners {
+                    listener.on_depth_update(&update);
+                }
+                published += 1;
+            }
+        }
+        published
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use optionstratlib::ExpirationDate;
+    use orderbook_rs::OrderId;
+
+    #[derive(Default)]
+    struct Collector(Mutex<Vec<DepthUpdate>>);
+
+    impl DepthListener for Collector {
+        fn on_depth_update(&self, update: &DepthUpdate) {
+            self.0.lock().unwrap().push(update.clone());
+        }
+    }
+
+    #[test]
+    fn test_tracker_diffs_levels() {
+        let book = OptionOrderBook::new("C", optionstratlib::OptionStyle::Call);
+        let tracker = DepthTracker::new(2).unwrap();
+        let bid = OrderId::new();
+        book.add_limit_order(bid, Side::Buy, 100, 5).unwrap();
+        book.add_limit_order(OrderId::new(), Side::Buy, 99, 3)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), Side::Buy, 98, 1)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), Side::Sell, 105, 2)
+            .unwrap();
+
+        let first = tracker.update(&book).unwrap();
+        assert_eq!(first.sequence, 1);
+        // Only the top two bids are tracked.
+        assert_eq!(first.changes.len(), 3);
+        assert!(
+            first
+                .changes
+                .iter()
+                .all(|c| c.kind == LevelChangeKind::Added)
+        );
+        assert!(tracker.update(&book).is_none());
+
+        book.cancel_order(bid).unwrap();
+        book.add_limit_order(OrderId::new(), Side::Sell, 105, 4)
+            .unwrap();
+        let second = tracker.update(&book).unwrap();
+        assert_eq!(second.sequence, 2);
+        let kinds: Vec<(u128, LevelChangeKind)> =
+            second.changes.iter().map(|c| (c.price, c.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (100, LevelChangeKind::Removed),
+                (98, LevelChangeKind::Added),
+                (105, LevelChangeKind::Changed),
+            ]
+        );
+        assert_eq!(second.changes[2].quantity, 6);
+        assert!(DepthTracker::new(0).is_err());
+    }
+
+    #[test]
+    fn test_feed_publishes_to_subscribers() {
+        let manager = Arc::new(UnderlyingOrderBookManager::new());
+        let expiration =
+            ExpirationDate::DateTime(Utc.with_ymd_and_hms(2030, 6, 21, 0, 0, 0).unwrap());
+        let strike = manager
+            .get_or_create("SPX")
+            .get_or_create_expiration(expiration)
+            .get_or_create_strike(5000);
+        let symbol = strike.call().symbol().to_string();
+        let feed = DepthFeed::new(Arc::clone(&manager), 5).unwrap();
+        let collector = Arc::new(Collector::default());
+        feed.subscribe(&symbol, collector.clone()).unwrap();
+        assert!(feed.subscribe("SPX-UNKNOWN", collector.clone()).is_err());
+
+        strike
+            .call()
+            .add_limit_order(OrderId::new(), Side::Buy, 120, 5)
+            .unwrap();
+        // Other contracts are not published.
+        strike
+            .put()
+            .add_limit_order(OrderId::new(), Side::Buy, 80, 5)
+            .unwrap();
+        assert_eq!(feed.poll(), 1);
+        assert_eq!(feed.poll(), 0);
+
+        manager
+            .get("SPX")
+            .unwrap()
+            .expirations()
+            .remove(&expiration);
+        assert_eq!(feed.poll(), 1);
+        let updates = collector.0.lock().unwrap();
+        assert_eq!(updates[1].changes[0].kind, LevelChangeKind::Removed);
+        assert_eq!(updates[1].sequence, 2);
+        assert!(feed.symbols().is_empty());
+    }
+}
@@ -0,0 +1,390 @@
+//! Book events module.
+//!
+//! This module provides observer hooks on an [`OptionOrderBook`]:
+//! [`BookListener`]s registered on a book are told of changes to its best
+//! quote, of its executions and of changes to its price levels. The
+//! [`ChannelListener`] forwards the same notifications as [`BookEvent`]s
+//! over a channel, for consumers running on another thread.
+//!
+//! Listeners run on the thread that changed the book. Trades are notified
+//! as they execute; level and quote changes once the order has been added
+//! or cancelled; cancels made by the book's timers once they have run. Clearing or restoring the book only notifies the quote
+//! change, and changes made directly on the inner book are not observed.
+//! A book without listeners skips all of this work.
+
+use super::book::OptionOrderBook;
+use super::depth::{LevelChange, LevelChangeKind};
+use super::quote::Quote;
+use orderbook_rs::{OrderId, Side, TradeResult};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+
+/// Receives the events of an option order book.
+///
+/// Every method defaults to doing nothing, so a listener implements only
+/// the events it needs.
+pub trait BookListener: Send + Sync {
+    /// Called when the best bid or ask price or size changes.
+    fn on_quote_change(&self, _symbol: &str, _quote: &Quote) {}
+
+    /// Called with every execution in the book.
+    fn on_trade(&self, _symbol: &str, _trade: &TradeResult) {}
+
+    /// Called when the quantity at a price level changes.
+    fn on_depth_change(&self, _symbol: &str, _change: &LevelChange) {}
+
+    /// Called with the orders cancelled by Good-Till-Date expiries and
+    /// scheduled mass cancels when the book runs its timers.
+    fn on_timer_cancel(&self, _symbol: &str, _order_ids: &[OrderId]) {}
+}
+
+/// An order book event, as sent by a [`ChannelListener`].
+#[derive(Debug, Clone)]
+pub enum BookEvent {
+    /// The best quote changed.
+    Quote {
+        /// Contract symbol.
+        symbol: String,
+        /// New best quote.
+        quote: Quote,
+    },
+    /// An order executed.
+    Trade {
+        /// Contract symbol.
+        symbol: String,
+        /// The execution.
+        trade: TradeResult,
+    },
+    /// A price level changed.
+    Depth {
+        /// Contract symbol.
+        symbol: String,
+        /// The level change.
+        change: LevelChange,
+    },
+    /// The book's timers cancelled orders.
+    TimerCancel {
+        /// Contract symbol.
+        symbol: String,
+        /// Ids of the cancelled orders.
+        order_ids: Vec<OrderId>,
+    },
+}
+
+/// Forwards book events over a channel.
+///
+/// Events are dropped once the receiver is gone.
+pub struct ChannelListener {
+    /// Sending half of the channel.
+    sender: Sender<BookEvent>,
+}
+
+impl ChannelListener {
+    /// Creates a listener and the receiver of its events.
+    #[must_use]
+    pub fn channel() -> (Arc<Self>, Receiver<BookEvent>) {
+        let (sender, receiver) = mpsc::channel();
+        (Arc::new(Self { sender }), receiver)
+    }
+
+    fn send(&self, event: BookEvent) {
+        let _ = self.sender.send(event);
+    }
+}
+
+impl BookListener for ChannelListener {
+    fn on_quote_change(&self, symbol: &str, quote: &Quote) {
+        self.send(BookEvent::Quote {
+            symbol: symbol.to_string(),
+            quote: *quote,
+        });
+    }
+
+    fn on_trade(&self, symbol: &str, trade: &TradeResult) {
+        self.send(BookEvent::Trade {
+            symbol: symbol.to_string(),
+            trade: trade.clone(),
+        });
+    }
+
+    fn on_depth_change(&self, symbol: &str, change: &LevelChange) {
+        self.send(BookEvent::Depth {
+            symbol: symbol.to_string(),
+            change: *change,
+        });
+    }
+
+    fn on_timer_cancel(&self, symbol: &str, order_ids: &[OrderId]) {
+        self.send(BookEvent::TimerCancel {
+            symbol: symbol.to_string(),
+            order_ids: order_ids.to_vec(),
+        });
+    }
+}
+
+/// A price level touched by an operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TouchedLevel {
+    /// Side of the level.
+    pub side: Side,
+    /// Price of the level.
+    pub price: u128,
+    /// Whether the level was empty before the operation.
+    pub was_empty: bool,
+}
+
+/// Listeners of one book and the state needed to notify them.
+#[derive(Default)]
+pub(crate) struct BookObservers {
+    /// Registered listeners.
+    listeners: RwLock<Vec<Arc<dyn BookListener>>>,
+    /// Whether any listener is registered.
+    active: AtomicBool,
+    /// Maker levels hit by trades since the last publication.
+    traded: Mutex<Vec<TouchedLevel>>,
+    /// Last quote notified.
+    last_quote: Mutex<Quote>,
+}
+
+impl BookObservers {
+    /// Returns whether any listener is registered.
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Acquire)
+    }
+
+    /// Registers a listener; `quote` is the book's current quote, against
+    /// which the next change is detected.
+    pub fn subscribe(&self, listener: Arc<dyn BookListener>, quote: Quote) {
+        let mut listeners = self
+            .listeners
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if listeners.is_empty() {
+            *lock(&self.last_quote) = quote;
+        }
+        listeners.push(listener);
+        self.active.store(true, Ordering::Release);
+    }
+
+    /// Removes a listener, returning whether it was registered.
+    pub fn unsubscribe(&self, listener: &Arc<dyn BookListener>) -> bool {
+        let mut listeners = self
+            .listeners
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let before = listeners.len();
+        listeners.retain(|l| !Arc::ptr_eq(l, listener));
+        self.active.store(!listeners.is_empty(), Ordering::Release);
+        listeners.len() != before
+    }
+
+    /// Returns the number of registered listeners.
+    pub fn len(&self) -> usize {
+        self.listeners().len()
+    }
+
+    /// Notifies a trade and remembers the maker levels it hit.
+    pub fn trade(&self, trade: &TradeResult) {
+        if !self.is_active() {
+            return;
+        }
+        {
+            let mut traded = lock(&self.traded);
+            for transaction in trade.match_result.transactions.as_vec() {
+                let level = TouchedLevel {
+                    side: transaction.maker_side(),
+                    price: transaction.price,
+                    was_empty: false,
+                };
+                if !traded.contains(&level) {
+                    traded.push(level);
+                }
+            }
+        }
+        for listener in self.listeners().iter() {
+            listener.on_trade(&trade.symbol, trade);
+        }
+    }
+
+    /// Notifies the level changes of an operation, including the levels
+    /// hit by its trades, then the quote change if any.
+    pub fn publish(&self, book: &OptionOrderBook, touched: Option<TouchedLevel>) {
+        if !self.is_active() {
+            return;
+        }
+        let mut levels: Vec<TouchedLevel> = touched.into_iter().collect();
+        levels.append(&mut lock(&self.traded));
+        let changes: Vec<LevelChange> = levels
+            .into_iter()
+            .filter_map(|level| {
+                let quantity = match level.side {
+                    Side::Buy => book.bid_depth_at_price(level.price),
+                    Side::Sell => book.ask_depth_at_price(level.price),
+                };
+                let kind = match (level.was_empty, quantity) {
+                    (true, 0) => return None,
+                    (_, 0) => LevelChangeKind::Removed,
+                    (true, _) => LevelChangeKind::Added,
+                    (false, _) => LevelChangeKind::Changed,
+                };
+                Some(LevelChange {
+                    side: level.side,
+                    price: level.price,
+                    quantity,
+                    kind,
+                })
+            })
+            .collect();
+
+        let quote = book.best_quote();
+        let quote_changed = {
+            let mut last = lock(&self.last_quote);
+            let changed = *last != quote;
+            *last = quote;
+            changed
+        };
+
+        let listeners = self.listeners();
+        for change in &changes {
+            for listener in listeners.iter() {
+                listener.on_depth_change(book.symbol(), change);
+            }
+        }
+        if quote_changed {
+            for listener in listeners.iter() {
+                listener.on_quote_change(book.symbol(), &quote);
+            }
+        }
+    }
+
+    /// Notifies the orders cancelled by the book's timers.
+    pub fn timer_cancel(&self, book: &OptionOrderBook, order_ids: &[OrderId]) {
+        if !self.is_active() || order_ids.is_empty() {
+            return;
+        }
+        for listener in self.listeners().iter() {
+            listener.on_timer_cancel(book.symbol(), order_ids);
+        }
+    }
+
+    fn listeners(&self) -> Vec<Arc<dyn BookListener>> {
+        self.listeners
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::OptionStyle;
+    use orderbook_rs::OrderId;
+
+    fn book() -> OptionOrderBook {
+        OptionOrderBook::new("BTC-20240329-50000-C", OptionStyle::Call)
+    }
+
+    #[test]
+    fn test_add_and_cancel_notify_depth_and_quote() {
+        let book = book();
+        let (listener, events) = ChannelListener::channel();
+        book.add_book_listener(listener);
+
+        let bid = OrderId::new();
+        book.add_limit_order(bid, Side::Buy, 100, 10).unwrap();
+        let received: Vec<BookEvent> = events.try_iter().collect();
+        assert_eq!(received.len(), 2);
+        assert!(matches!(
+            received[0],
+            BookEvent::Depth {
+                change: LevelChange {
+                    side: Side::Buy,
+                    price: 100,
+                    quantity: 10,
+                    kind: LevelChangeKind::Added,
+                },
+                ..
+            }
+        ));
+        assert!(matches!(
+            received[1],
+            BookEvent::Quote { quote, .. } if quote.bid_price() == Some(100)
+        ));
+
+        // A deeper bid changes the depth but not the quote.
+        book.add_limit_order(OrderId::new(), Side::Buy, 90, 5)
+            .unwrap();
+        assert_eq!(events.try_iter().count(), 1);
+
+        assert!(book.cancel_order(bid).unwrap());
+        let received: Vec<BookEvent> = events.try_iter().collect();
+        assert!(matches!(
+            received[0],
+            BookEvent::Depth {
+                change: LevelChange {
+                    kind: LevelChangeKind::Removed,
+                    ..
+                },
+                ..
+            }
+        ));
+        assert!(matches!(
+            received[1],
+            BookEvent::Quote { quote, .. } if quote.bid_price() == Some(90)
+        ));
+    }
+
+    #[test]
+    fn test_trade_notifies_maker_level() {
+        let book = book();
+        book.add_limit_order(OrderId::new(), Side::Sell, 110, 10)
+            .unwrap();
+        let (listener, events) = ChannelListener::channel();
+        book.add_book_listener(listener);
+
+        book.add_limit_order(OrderId::new(), Side::Buy, 110, 4)
+            .unwrap();
+        let received: Vec<BookEvent> = events.try_iter().collect();
+        assert!(matches!(received[0], BookEvent::Trade { .. }));
+        assert!(matches!(
+            received[1],
+            BookEvent::Depth {
+                change: LevelChange {
+                    side: Side::Sell,
+                    quantity: 6,
+                    kind: LevelChangeKind::Changed,
+                    ..
+                },
+                ..
+            }
+        ));
+        assert!(matches!(
+            received[2],
+            BookEvent::Quote { quote, .. } if quote.ask_size() == 6
+        ));
+        assert_eq!(received.len(), 3);
+    }
+
+    #[test]
+    fn test_remove_listener() {
+        let book = book();
+        let (listener, events) = ChannelListener::channel();
+        let listener: Arc<dyn BookListener> = listener;
+        book.add_book_listener(Arc::clone(&listener));
+        assert_eq!(book.book_listener_count(), 1);
+        assert!(book.remove_book_listener(&listener));
+        assert!(!book.remove_book_listener(&listener));
+
+        book.add_limit_order(OrderId::new(), Side::Buy, 100, 10)
+            .unwrap();
+        assert_eq!(events.try_iter().count(), 0);
+    }
+}
@@ -3,8 +3,14 @@
 //! This module provides the [`ExpirationOrderBook`] and [`ExpirationOrderBookManager`]
 //! for managing all expirations for a single underlying asset.
 
+use super::book::OptionOrderBook;
 use super::chain::OptionChainOrderBook;
+use super::filter::{ChainFilter, ChainView};
+use super::listing::ListingRules;
+use super::registry::ContractRegistry;
 use super::strike::StrikeOrderBook;
+use super::timers::{ScheduledCancel, TimerDefaults, TimerReport};
+use crate::clock::Clock;
 use crate::error::{Error, Result};
 use crossbeam_skiplist::SkipMap;
 use optionstratlib::ExpirationDate;
@@ -43,10 +49,30 @@ impl ExpirationOrderBook {
     /// * `expiration` - The expiration date
     #[must_use]
     pub fn new(underlying: impl Into<String>, expiration: ExpirationDate) -> Self {
+        Self::with_registry(underlying, expiration, Arc::new(ContractRegistry::new()))
+    }
+
+    /// Creates a new expiration order book sharing a contract registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - The underlying asset symbol (e.g., "BTC")
+    /// * `expiration` - The expiration date
+    /// * `registry` - The registry used to intern contract symbols
+    #[must_use]
+    pub fn with_registry(
+        underlying: impl Into<String>,
+        expiration: ExpirationDate,
+        registry: Arc<ContractRegistry>,
+    ) -> Self {
         let underlying = underlying.into();
 
         Self {
-            chain: Arc::new(OptionChainOrderBook::new(&underlying, expiration)),
+            chain: Arc::new(OptionChainOrderBook::with_registry(
+                &underlying,
+                expiration,
+                registry,
+            )),
             underlying,
             expiration,
             id: OrderId::new(),
@@ -97,6 +123,17 @@ impl ExpirationOrderBook {
         self.chain.get_strike(strike)
     }
 
+    /// Lists the strike ladder of the listing rules around spot.
+    ///
+    /// See [`OptionChainOrderBook::generate_strikes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`ListingRules::strikes`].
+    pub fn generate_strikes(&self, spot: u64, rules: &ListingRules) -> Result<Vec<u64>> {
+        self.chain.generate_strikes(spot, rules)
+    }
+
     /// Returns the number of strikes.
     #[must_use]
     pub fn strike_count(&self) -> usize {
@@ -120,6 +157,42 @@ impl ExpirationOrderBook {
         self.chain.total_order_count()
     }
 
+    /// Calls `f` with every option book of the chain.
+    pub fn for_each_book<F: FnMut(&OptionOrderBook)>(&self, f: F) {
+        self.chain.for_each_book(f);
+    }
+
+    /// Sets the clock of every book of the expiration, including strikes
+    /// listed later.
+    pub fn set_clock(&self, clock: Arc<dyn Clock>) {
+        self.chain.set_clock(clock);
+    }
+
+    /// Schedules a mass cancel in every book of the expiration, e.g. pulling our
+    /// orders a minute before expiry or at the session close.
+    ///
+    /// Strikes listed before the cancel falls due are covered too.
+    pub fn schedule_mass_cancel(&self, cancel: &ScheduledCancel) {
+        self.chain.schedule_mass_cancel(cancel);
+    }
+
+    /// Runs the timers of every book of the expiration, by each book's clock.
+    pub fn run_timers(&self) -> TimerReport {
+        let mut report = TimerReport::default();
+        self.for_each_book(|book| report += book.run_timers());
+        report
+    }
+
+    /// Returns a view over the contracts matching a filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Tenor, moneyness and liquidity criteria
+    #[must_use]
+    pub fn filter(&self, filter: &ChainFilter) -> ChainView {
+        self.chain.filter(filter)
+    }
+
     /// Returns the ATM strike closest to the given spot price.
     ///
     /// # Errors
@@ -139,6 +212,10 @@ pub struct ExpirationOrderBookManager {
     expirations: SkipMap<ExpirationDate, Arc<ExpirationOrderBook>>,
     /// The underlying asset symbol.
     underlying: String,
+    /// Registry shared by all expirations of this manager.
+    registry: Arc<ContractRegistry>,
+    /// Clock and mass cancels applied to expirations listed later.
+    timers: TimerDefaults,
 }
 
 impl ExpirationOrderBookManager {
@@ -149,9 +226,22 @@ impl ExpirationOrderBookManager {
     /// * `underlying` - The underlying asset symbol
     #[must_use]
     pub fn new(underlying: impl Into<String>) -> Self {
+        Self::with_registry(underlying, Arc::new(ContractRegistry::new()))
+    }
+
+    /// Creates a new expiration order book manager sharing a contract registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - The underlying asset symbol
+    /// * `registry` - The registry used to intern contract symbols
+    #[must_use]
+    pub fn with_registry(underlying: impl Into<String>, registry: Arc<ContractRegistry>) -> Self {
         Self {
             expirations: SkipMap::new(),
             underlying: underlying.into(),
+            registry,
+            timers: TimerDefaults::default(),
         }
     }
 
@@ -178,7 +268,15 @@ impl ExpirationOrderBookManager {
         if let Some(entry) = self.expirations.get(&expiration) {
             return Arc::clone(entry.value());
         }
-        let book = Arc::new(ExpirationOrderBook::new(&self.underlying, expiration));
+        let book = Arc::new(ExpirationOrderBook::with_registry(
+            &self.underlying,
+            expiration,
+            Arc::clone(&self.registry),
+        ));
+        self.timers.apply(
+            |clock| book.set_clock(clock),
+            |cancel| book.schedule_mass_cancel(cancel),
+        );
         self.expirations.insert(expiration, Arc::clone(&book));
         book
     }
@@ -209,9 +307,22 @@ impl ExpirationOrderBookManager {
         self.expirations.iter()
     }
 
-    /// Removes an expiration order book.
+    /// Removes an expiration order book, releasing its contracts from the registry.
     pub fn remove(&self, expiration: &ExpirationDate) -> bool {
-        self.expirations.remove(expiration).is_some()
+        match self.expirations.remove(expiration) {
+            Some(entry) => {
+                entry.value().chain().strikes().release_all_contracts();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Releases the contracts of every expiration from the registry.
+    pub(crate) fn release_all_contracts(&self) {
+        for entry in self.expirations.iter() {
+            entry.value().chain().strikes().release_all_contracts();
+        }
     }
 
     /// Returns the total order count across all expirations.
@@ -223,6 +334,31 @@ impl ExpirationOrderBookManager {
             .sum()
     }
 
+    /// Calls `f` with every option book, by expiration.
+    pub fn for_each_book<F: FnMut(&OptionOrderBook)>(&self, mut f: F) {
+        for entry in self.expirations.iter() {
+            entry.value().for_each_book(&mut f);
+        }
+    }
+
+    /// Sets the clock of every book, including expirations and strikes
+    /// listed later.
+    pub fn set_clock(&self, clock: Arc<dyn Clock>) {
+        self.timers.set_clock(Arc::clone(&clock));
+        for entry in self.expirations.iter() {
+            entry.value().set_clock(Arc::clone(&clock));
+        }
+    }
+
+    /// Schedules a mass cancel in every book, including expirations and
+    /// strikes listed before it falls due.
+    pub fn schedule_mass_cancel(&self, cancel: &ScheduledCancel) {
+        self.timers.schedule(cancel);
+        for entry in self.expirations.iter() {
+            entry.value().schedule_mass_cancel(cancel);
+        }
+    }
+
     /// Returns the total strike count across all expirations.
     #[must_use]
     pub fn total_strike_count(&self) -> usize {
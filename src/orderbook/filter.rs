@@ -0,0 +1,386 @@
+//! Chain filter module.
+//!
+//! This module provides [`ChainFilter`], a declarative selection of contracts
+//! by tenor, moneyness and liquidity, and the [`ChainView`] it produces: a
+//! lightweight list of matching contracts with aggregate statistics that
+//! quoting, risk reports and exports can share.
+
+use super::book::OptionOrderBook;
+use super::chain::OptionChainOrderBook;
+use optionstratlib::{ExpirationDate, OptionStyle};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+/// Moneyness bounds expressed as `strike / spot`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoneynessRange {
+    /// Reference spot price in the same units as strikes.
+    pub spot: u64,
+    /// Minimum `strike / spot` (inclusive).
+    pub min: Decimal,
+    /// Maximum `strike / spot` (inclusive).
+    pub max: Decimal,
+}
+
+impl MoneynessRange {
+    /// Creates a moneyness range.
+    ///
+    /// # Arguments
+    ///
+    /// * `spot` - Reference spot price in the same units as strikes
+    /// * `min` - Minimum `strike / spot` (inclusive)
+    /// * `max` - Maximum `strike / spot` (inclusive)
+    #[must_use]
+    pub const fn new(spot: u64, min: Decimal, max: Decimal) -> Self {
+        Self { spot, min, max }
+    }
+
+    /// Returns true if the strike lies within the range.
+    #[must_use]
+    pub fn contains(&self, strike: u64) -> bool {
+        if self.spot == 0 {
+            return false;
+        }
+        let moneyness = Decimal::from(strike) / Decimal::from(self.spot);
+        moneyness >= self.min && moneyness <= self.max
+    }
+}
+
+/// Declarative contract selection.
+///
+/// Every criterion is optional; the default filter matches every contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ChainFilter {
+    /// Maximum days to expiration (inclusive).
+    pub max_days: Option<Decimal>,
+    /// Moneyness bounds.
+    pub moneyness_range: Option<MoneynessRange>,
+    /// Minimum open interest.
+    pub min_open_interest: Option<u64>,
+    /// Only keep contracts with both a bid and an ask.
+    pub two_sided_only: bool,
+    /// Only keep one option style.
+    pub option_style: Option<OptionStyle>,
+}
+
+impl ChainFilter {
+    /// Returns true if an expiration passes the tenor criterion.
+    #[must_use]
+    pub fn matches_expiration(&self, expiration: &ExpirationDate) -> bool {
+        match (self.max_days, expiration.get_days()) {
+            (None, _) => true,
+            (Some(max), Ok(days)) => days.to_dec() <= max,
+            (Some(_), Err(_)) => false,
+        }
+    }
+
+    /// Returns true if a contract passes the strike, style and book criteria.
+    #[must_use]
+    pub fn matches_contract(&self, strike: u64, book: &OptionOrderBook) -> bool {
+        if self
+            .option_style
+            .is_some_and(|style| style != book.option_style())
+        {
+            return false;
+        }
+        if self
+            .moneyness_range
+            .is_some_and(|range| !range.contains(strike))
+        {
+            return false;
+        }
+        if self
+            .min_open_interest
+            .is_some_and(|min| book.open_interest() < min)
+        {
+            return false;
+        }
+        !self.two_sided_only || (book.best_bid().is_some() && book.best_ask().is_some())
+    }
+}
+
+/// A contract selected by a [`ChainFilter`].
+#[derive(Clone)]
+pub struct ChainContract {
+    /// Expiration of the contract.
+    pub expiration: ExpirationDate,
+    /// Strike price.
+    pub strike: u64,
+    /// Order book of the contract.
+    pub book: Arc<OptionOrderBook>,
+}
+
+impl ChainContract {
+    /// Returns the option style of the contract.
+    #[must_use]
+    pub fn option_style(&self) -> OptionStyle {
+        self.book.option_style()
+    }
+
+    /// Returns the contract symbol.
+    #[must_use]
+    pub fn symbol(&self) -> &str {
+        self.book.symbol()
+    }
+}
+
+impl std::fmt::Debug for ChainContract {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChainContract")
+            .field("symbol", &self.book.symbol())
+            .field("strike", &self.strike)
+            .finish()
+    }
+}
+
+/// Contracts matching a [`ChainFilter`], ordered by expiration then strike,
+/// with calls before puts.
+#[derive(Debug, Clone, Default)]
+pub struct ChainView {
+    /// Matching contracts.
+    contracts: Vec<ChainContract>,
+}
+
+impl ChainView {
+    /// Builds a view over one option chain.
+    #[must_use]
+    pub fn from_chain(chain: &OptionChainOrderBook, filter: &ChainFilter) -> Self {
+        let mut view = Self::default();
+        view.extend_from_chain(chain, filter);
+        view
+    }
+
+    /// Appends the contracts of a chain that match the filter.
+    pub fn extend_from_chain(&mut self, chain: &OptionChainOrderBook, filter: &ChainFilter) {
+        if !filter.matches_expiration(chain.expiration()) {
+            return;
+        }
+        for entry in chain.strikes().iter() {
+            let strike = entry.value();
+            for book in [strike.call_arc(), strike.put_arc()] {
+                if filter.matches_contract(strike.strike(), &book) {
+                    self.contracts.push(ChainContract {
+                        expiration: *chain.expiration(),
+                        strike: strike.strike(),
+                        book,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Returns the number of matching contracts.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.contracts.len()
+    }
+
+    /// Returns true if no contracts match.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.contracts.is_empty()
+    }
+
+    /// Returns an iterator over the matching contracts.
+    pub fn iter(&self) -> impl Iterator<Item = &ChainContract> {
+        self.contracts.iter()
+    }
+
+    /// Returns the matching contracts.
+    #[must_use]
+    pub fn contracts(&self) -> &[ChainContract] {
+        &self.contracts
+    }
+
+    /// Returns aggregate statistics over the matching contracts.
+    #[must_use]
+    pub fn stats(&self) -> ChainViewStats {
+        let mut stats = ChainViewStats::default();
+        let mut spread_sum = 0u128;
+        for contract in &self.contracts {
+            let book = &contract.book;
+            match book.option_style() {
+                OptionStyle::Call => stats.call_count += 1,
+                OptionStyle::Put => stats.put_count += 1,
+            }
+            if let Some(spread) = book.spread() {
+                stats.two_sided_count += 1;
+                spread_sum += spread;
+            }
+            stats.total_bid_depth += book.total_bid_depth();
+            stats.total_ask_depth += book.total_ask_depth();
+            stats.total_open_interest += book.open_interest();
+        }
+        stats.contract_count = self.contracts.len();
+        if stats.two_sided_count > 0 {
+            stats.average_spread =
+                Some(Decimal::from(spread_sum) / Decimal::from(stats.two_sided_count));
+        }
+        stats
+    }
+}
+
+impl IntoIterator for ChainView {
+    type Item = ChainContract;
+    type IntoIter = std::vec::IntoIter<ChainContract>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.contracts.into_iter()
+    }
+}
+
+/// Aggregate statistics over a [`ChainView`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChainViewStats {
+    /// Number of matching contracts.
+    pub contract_count: usize,
+    /// Number of matching calls.
+    pub call_count: usize,
+    /// Number of matching puts.
+    pub put_count: usize,
+    /// Number of contracts with both a bid and an ask.
+    pub two_sided_count: usize,
+    /// Total bid depth across contracts.
+    pub total_bid_depth: u64,
+    /// Total ask depth across contracts.
+    pub total_ask_depth: u64,
+    /// Total open interest across contracts.
+    pub total_open_interest: u64,
+    /// Average spread of two-sided contracts in smallest units.
+    pub average_spread: Option<Decimal>,
+}
+
+impl std::fmt::Display for ChainViewStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} contracts ({} calls, {} puts), {} two-sided, OI {}",
+            self.contract_count,
+            self.call_count,
+            self.put_count,
+            self.two_sided_count,
+            self.total_open_interest
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::UnderlyingOrderBook;
+    use optionstratlib::prelude::pos_or_panic;
+    use orderbook_rs::{OrderId, Side};
+    use rust_decimal_macros::dec;
+
+    fn populated_chain() -> OptionChainOrderBook {
+        let chain = OptionChainOrderBook::new("BTC", ExpirationDate::Days(pos_or_panic!(30.0)));
+        for strike in [40000, 50000, 60000] {
+            drop(chain.get_or_create_strike(strike));
+        }
+        let atm = chain.get_strike(50000).unwrap();
+        atm.call()
+            .add_limit_order(OrderId::new(), Side::Buy, 100, 5)
+            .unwrap();
+        atm.call()
+            .add_limit_order(OrderId::new(), Side::Sell, 110, 3)
+            .unwrap();
+        atm.call().set_open_interest(500);
+        atm.put().set_open_interest(50);
+        chain
+    }
+
+    #[test]
+    fn test_default_filter_matches_all() {
+        let view = populated_chain().filter(&ChainFilter::default());
+
+        assert_eq!(view.len(), 6);
+        let stats = view.stats();
+        assert_eq!(stats.call_count, 3);
+        assert_eq!(stats.put_count, 3);
+        assert_eq!(stats.two_sided_count, 1);
+        assert_eq!(stats.total_open_interest, 550);
+        assert_eq!(stats.average_spread, Some(dec!(10)));
+    }
+
+    #[test]
+    fn test_filter_by_moneyness() {
+        let filter = ChainFilter {
+            moneyness_range: Some(MoneynessRange::new(50000, dec!(0.9), dec!(1.1))),
+            ..ChainFilter::default()
+        };
+
+        let view = populated_chain().filter(&filter);
+
+        assert_eq!(view.len(), 2);
+        assert!(view.iter().all(|c| c.strike == 50000));
+    }
+
+    #[test]
+    fn test_filter_by_liquidity() {
+        let chain = populated_chain();
+
+        let two_sided = chain.filter(&ChainFilter {
+            two_sided_only: true,
+            ..ChainFilter::default()
+        });
+        assert_eq!(two_sided.len(), 1);
+        assert_eq!(two_sided.contracts()[0].option_style(), OptionStyle::Call);
+
+        let open_interest = chain.filter(&ChainFilter {
+            min_open_interest: Some(100),
+            ..ChainFilter::default()
+        });
+        assert_eq!(open_interest.len(), 1);
+    }
+
+    #[test]
+    fn test_filter_by_style() {
+        let view = populated_chain().filter(&ChainFilter {
+            option_style: Some(OptionStyle::Put),
+            ..ChainFilter::default()
+        });
+
+        assert_eq!(view.len(), 3);
+        assert!(
+            view.into_iter()
+                .all(|c| c.option_style() == OptionStyle::Put)
+        );
+    }
+
+    #[test]
+    fn test_filter_by_tenor_across_expirations() {
+        let underlying = UnderlyingOrderBook::new("BTC");
+        drop(
+            underlying
+                .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(7.0)))
+                .get_or_create_strike(50000),
+        );
+        drop(
+            underlying
+                .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(90.0)))
+                .get_or_create_strike(50000),
+        );
+
+        let filter = ChainFilter {
+            max_days: Some(dec!(30)),
+            ..ChainFilter::default()
+        };
+
+        assert_eq!(underlying.filter(&ChainFilter::default()).len(), 4);
+        assert_eq!(underlying.filter(&filter).len(), 2);
+    }
+
+    #[test]
+    fn test_moneyness_zero_spot() {
+        assert!(!MoneynessRange::new(0, dec!(0), dec!(10)).contains(100));
+    }
+
+    #[test]
+    fn test_view_stats_display() {
+        let display = populated_chain()
+            .filter(&ChainFilter::default())
+            .stats()
+            .to_string();
+        assert!(display.contains("6 contracts"));
+    }
+}
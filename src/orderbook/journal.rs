@@ -0,0 +1,692 @@
+//! Event journal module.
+//!
+//! This module provides the [`EventJournal`], a write-ahead log of order
+//! book activity. Every add, cancel and modify made through the journal is
+//! appended before it is applied to the book, and fills are appended as
+//! the book reports them, so a book or a whole manager can be rebuilt by
+//! replaying the log and every execution can be audited.
+//!
+//! ## Storage
+//!
+//! Records go through a [`JournalStore`], by default a
+//! [`SegmentedJournalStore`] of checksummed segment files that repairs a
+//! torn tail on open. Once a snapshot covers the log up to a sequence,
+//! [`EventJournal::truncate_through`] drops the records it covers.
+//!
+//! The journal is also [`Compactable`]: recording the snapshot with
+//! [`EventJournal::set_checkpoint`] lets a
+//! [`CompactionScheduler`](crate::history::CompactionScheduler) drop the
+//! covered records on its next run.
+//!
+//! ## Replay
+//!
+//! Matching is deterministic, so replaying the adds, cancels and modifies
+//! in order reproduces the fills; recorded fills are for audit and are not
+//! applied. Adds carry their self-trade participant and are replayed as of
+//! the time they were journaled, so a Good-Till-Date order is accepted
+//! again and keeps its expiry. Cancels made by the book's timers are
+//! journaled as the book reports them. Operations the book rejected live
+//! are rejected again on replay and counted. Books being rebuilt must not
+//! have the journal subscribed, or the replayed fills and timer cancels are
+//! journaled a second time.
+
+use super::book::OptionOrderBook;
+use super::events::BookListener;
+use super::underlying::UnderlyingOrderBookManager;
+use crate::clock::Clock;
+use crate::error::Result;
+use crate::history::{Compactable, CompactionStats};
+use crate::recovery::{JournalStore, SegmentConfig, SegmentedJournalStore, SequencedRecord};
+use orderbook_rs::{OrderId, Side, TimeInForce, TradeResult};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// An order book operation or execution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JournalEvent {
+    /// A limit order was submitted.
+    Add {
+        /// Contract symbol.
+        symbol: String,
+        /// Order id.
+        order_id: OrderId,
+        /// Buy or Sell side.
+        side: Side,
+        /// Limit price in smallest units.
+        price: u128,
+        /// Quantity in smallest units.
+        quantity: u64,
+        /// Time in force.
+        time_in_force: TimeInForce,
+        /// Self-trade participant the order was added for.
+        #[serde(default)]
+        participant: Option<String>,
+    },
+    /// An order was cancelled.
+    Cancel {
+        /// Contract symbol.
+        symbol: String,
+        /// Order id.
+        order_id: OrderId,
+    },
+    /// A resting order was replaced at a new price and quantity.
+    Modify {
+        /// Contract symbol.
+        symbol: String,
+        /// Order id.
+        order_id: OrderId,
+        /// New limit price in smallest units.
+        price: u128,
+        /// New quantity in smallest units.
+        quantity: u64,
+    },
+    /// A resting order was filled.
+    Fill {
+        /// Contract symbol.
+        symbol: String,
+        /// Id of the resting order.
+        maker_order_id: OrderId,
+        /// Execution price in smallest units.
+        price: u128,
+        /// Executed quantity in smallest units.
+        quantity: u64,
+        /// Side of the incoming order.
+        taker_side: Side,
+    },
+}
+
+impl JournalEvent {
+    /// Returns the contract symbol of the event.
+    #[must_use]
+    pub fn symbol(&self) -> &str {
+        match self {
+            Self::Add { symbol, .. }
+            | Self::Cancel { symbol, .. }
+            | Self::Modify { symbol, .. }
+            | Self::Fill { symbol, .. } => symbol,
+        }
+    }
+}
+
+/// A journal event with its position in the log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEventRecord {
+    /// Sequence number, starting at one.
+    pub sequence: u64,
+    /// Time the event was journaled in milliseconds.
+    pub timestamp_ms: u64,
+    /// The event.
+    pub event: JournalEvent,
+}
+
+impl SequencedRecord for JournalEventRecord {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+}
+
+/// Counters of an [`EventJournal`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventJournalStats {
+    /// Records appended since open.
+    pub appended: u64,
+    /// Fills that could not be journaled.
+    pub failed_fills: u64,
+    /// Cancels made by book timers that could not be journaled.
+    #[serde(default)]
+    pub failed_cancels: u64,
+}
+
+/// Outcome of replaying a journal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalReplay {
+    /// Operations applied.
+    pub applied: usize,
+    /// Operations the book rejected, as it did live.
+    pub rejected: usize,
+    /// Recorded fills, not applied.
+    pub fills: usize,
+}
+
+/// Mutable state of an [`EventJournal`].
+#[derive(Default)]
+struct JournalState {
+    /// Sequence of the next record.
+    next_sequence: u64,
+    /// Last sequence covered by a snapshot.
+    checkpoint: u64,
+    /// Counters.
+    stats: EventJournalStats,
+}
+
+/// Append-only log of order book events.
+pub struct EventJournal {
+    /// Storage of the records.
+    store: Arc<dyn JournalStore<JournalEventRecord>>,
+    /// Time source for record timestamps.
+    clock: Arc<dyn Clock>,
+    /// Serializes journaled operations so the log order is the book order.
+    operations: Mutex<()>,
+    /// Write position and counters.
+    state: Mutex<JournalState>,
+}
+
+impl EventJournal {
+    /// Opens the journal in a [`SegmentedJournalStore`] in `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of [`SegmentedJournalStore::open`] or
+    /// [`Self::with_store`].
+    pub fn open(
+        dir: impl Into<PathBuf>,
+        config: SegmentConfig,
+        clock: Arc<dyn Clock>,
+    ) -> Result<Self> {
+        Self::with_store(Arc::new(SegmentedJournalStore::open(dir, config)?), clock)
+    }
+
+    /// Creates a journal appending to a store, continuing after its last
+    /// record.
+    ///
+    /// # Errors
+    ///
+    /// Returns the store's error if its last sequence cannot be read.
+    pub fn with_store(
+        store: Arc<dyn JournalStore<JournalEventRecord>>,
+        clock: Arc<dyn Clock>,
+    ) -> Result<Self> {
+        let next_sequence = store.last_sequence()? + 1;
+        Ok(Self {
+            store,
+            clock,
+            operations: Mutex::new(()),
+            state: Mutex::new(JournalState {
+                next_sequence,
+                ..JournalState::default()
+            }),
+        })
+    }
+
+    /// Returns the store the records go through.
+    #[must_use]
+    pub fn store(&self) -> &Arc<dyn JournalStore<JournalEventRecord>> {
+        &self.store
+    }
+
+    /// Returns the sequence of the last record, or zero if none.
+    #[must_use]
+    pub fn last_sequence(&self) -> u64 {
+        self.lock().next_sequence - 1
+    }
+
+    /// Returns the journal's counters.
+    #[must_use]
+    pub fn stats(&self) -> EventJournalStats {
+        self.lock().stats
+    }
+
+    /// Appends an event, returning its sequence.
+    ///
+    /// # Errors
+    ///
+    /// Returns the store's error if the record cannot be written.
+    pub fn append(&self, event: JournalEvent) -> Result<u64> {
+        let timestamp_ms = self.clock.now_ms();
+        let mut state = self.lock();
+        let record = JournalEventRecord {
+            sequence: state.next_sequence,
+            timestamp_ms,
+            event,
+        };
+        self.store.append(std::slice::from_ref(&record))?;
+        state.next_sequence += 1;
+        state.stats.appended += 1;
+        Ok(record.sequence)
+    }
+
+    /// Drops the records up to and including `sequence`, e.g. once a
+    /// snapshot covers them. Sequences keep counting from the last record.
+    ///
+    /// # Errors
+    ///
+    /// Returns the store's error.
+    pub fn truncate_through(&self, sequence: u64) -> Result<()> {
+        let _operation = self.operation();
+        let _state = self.lock();
+        self.store.truncate_through(sequence)
+    }
+
+    /// Records that a snapshot covers the log up to `sequence`, capped at
+    /// the last record; the next compaction drops the covered records.
+    /// A checkpoint never moves back.
+    pub fn set_checkpoint(&self, sequence: u64) {
+        let mut state = self.lock();
+        let sequence = sequence.min(state.next_sequence - 1);
+        state.checkpoint = state.checkpoint.max(sequence);
+    }
+
+    /// Returns the last sequence covered by a snapshot, or zero if none.
+    #[must_use]
+    pub fn checkpoint(&self) -> u64 {
+        self.lock().checkpoint
+    }
+
+    /// Journals and adds a limit order.
+    ///
+    /// # Errors
+    ///
+    /// Returns the journal's write error, in which case the book is
+    /// unchanged, or the book's error.
+    pub fn add_limit_order(
+        &self,
+        book: &OptionOrderBook,
+        order_id: OrderId,
+        side: Side,
+        price: u128,
+        quantity: u64,
+        time_in_force: TimeInForce,
+    ) -> Result<()> {
+        let _operation = self.operation();
+        self.append(JournalEvent::Add {
+            symbol: book.symbol().to_string(),
+            order_id,
+            side,
+            price,
+            quantity,
+            time_in_force,
+            participant: None,
+        })?;
+        book.add_limit_order_with_tif(order_id, side, price, quantity, time_in_force)
+    }
+
+    /// Journals and adds a limit order on behalf of a self-trade
+    /// participant; see [`OptionOrderBook::add_limit_order_as`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the journal's write error, in which case the book is
+    /// unchanged, or the book's error.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_limit_order_as(
+        &self,
+        book: &OptionOrderBook,
+        order_id: OrderId,
+        side: Side,
+        price: u128,
+        quantity: u64,
+        time_in_force: TimeInForce,
+        participant: &str,
+    ) -> Result<()> {
+        let _operation = self.operation();
+        self.append(JournalEvent::Add {
+            symbol: book.symbol().to_string(),
+            order_id,
+            side,
+            price,
+            quantity,
+            time_in_force,
+            participant: Some(participant.to_string()),
+        })?;
+        book.add_limit_order_as(order_id, side, price, quantity, time_in_force, participant)
+    }
+
+    /// Journals and cancels an order, returning whether it was resting.
+    ///
+    /// # Errors
+    ///
+    /// Returns the journal's write error, in which case the book is
+    /// unchanged.
+    pub fn cancel_order(&self, book: &OptionOrderBook, order_id: OrderId) -> Result<bool> {
+        let _operation = self.operation();
+        self.append(JournalEvent::Cancel {
+            symbol: book.symbol().to_string(),
+            order_id,
+        })?;
+        book.cancel_order(order_id)
+    }
+
+    /// Journals and modifies a resting order's price and quantity; see
+    /// [`OptionOrderBook::modify_order`] for when it keeps its queue
+    /// priority.
+    ///
+    /// Returns whether the order was resting.
+    ///
+    /// # Errors
+    ///
+    /// Returns the journal's write error, in which case the book is
+    /// unchanged, or the book's error on re-adding the order.
+    pub fn modify_order(
+        &self,
+        book: &OptionOrderBook,
+        order_id: OrderId,
+        price: u128,
+        quantity: u64,
+    ) -> Result<bool> {
+        let _operation = self.operation();
+        self.append(JournalEvent::Modify {
+            symbol: book.symbol().to_string(),
+            order_id,
+            price,
+            quantity,
+        })?;
+        Ok(book.modify_order(order_id, price, quantity)?.is_applied())
+    }
+
+    /// Calls `f` on every record after `sequence`, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns the store's error, or the first error returned by `f`.
+    pub fn replay(
+        &self,
+        sequence: u64,
+        f: &mut dyn FnMut(JournalEventRecord) -> Result<()>,
+    ) -> Result<()> {
+        self.store.iterate(sequence, f)
+    }
+
+    /// Rebuilds a book by replaying its events.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of [`Self::replay`].
+    pub fn rebuild_book(&self, book: &OptionOrderBook) -> Result<JournalReplay> {
+        let mut report = JournalReplay::default();
+        self.replay(0, &mut |record| {
+            if record.event.symbol() == book.symbol() {
+                apply(book, &record, &mut report);
+            }
+            Ok(())
+        })?;
+        Ok(report)
+    }
+
+    /// Rebuilds every book of a manager by replaying the log.
+    ///
+    /// The chains must already be listed, e.g. restored from a
+    /// [`SystemSnapshot`](crate::recovery::SystemSnapshot) taken before
+    /// the journal started; books are found by symbol.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContractNotFound` if an event names a contract the
+    /// manager does not list, or the error of [`Self::replay`].
+    pub fn rebuild_manager(&self, manager: &UnderlyingOrderBookManager) -> Result<JournalReplay> {
+        let mut report = JournalReplay::default();
+        self.replay(0, &mut |record| {
+            let book = manager.book(manager.contract_id(record.event.symbol())?)?;
+            apply(&book, &record, &mut report);
+            Ok(())
+        })?;
+        Ok(report)
+    }
+
+    fn operation(&self) -> MutexGuard<'_, ()> {
+        self.operations
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, JournalState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Compactable for EventJournal {
+    fn name(&self) -> &str {
+        "event_journal"
+    }
+
+    /// Drops the records up to the checkpoint, reporting them as evicted.
+    fn compact(&self, _now_ms: u64) -> Result<CompactionStats> {
+        let _operation = self.operation();
+        let state = self.lock();
+        let last = state.next_sequence - 1;
+        let Some(first) = self.store.first_sequence()? else {
+            return Ok(CompactionStats::default());
+        };
+        // Sequences are contiguous, so the store holds `first..=last`.
+        let kept_from = first.max(state.checkpoint + 1);
+        if kept_from > first {
+            self.store.truncate_through(state.checkpoint)?;
+        }
+        let to_count = |n: u64| usize::try_from(n).unwrap_or(usize::MAX);
+        Ok(CompactionStats {
+            evicted: to_count(kept_from - first),
+            remaining: to_count((last + 1).saturating_sub(kept_from)),
+            ..CompactionStats::default()
+        })
+    }
+}
+
+impl BookListener for EventJournal {
+    /// Journals each maker fill of an execution.
+    fn on_trade(&self, symbol: &str, trade: &TradeResult) {
+        for transaction in trade.match_result.transactions.as_vec() {
+            let appended = self.append(JournalEvent::Fill {
+                symbol: symbol.to_string(),
+                maker_order_id: transaction.maker_order_id,
+                price: transaction.price,
+                quantity: transaction.quantity,
+                taker_side: transaction.taker_side,
+            });
+            if appended.is_err() {
+                self.lock().stats.failed_fills += 1;
+            }
+        }
+    }
+
+    /// Journals each order cancelled by an expiry or scheduled mass cancel.
+    fn on_timer_cancel(&self, symbol: &str, order_ids: &[OrderId]) {
+        for order_id in order_ids {
+            let appended = self.append(JournalEvent::Cancel {
+                symbol: symbol.to_string(),
+                order_id: *order_id,
+            });
+            if appended.is_err() {
+                self.lock().stats.failed_cancels += 1;
+            }
+        }
+    }
+}
+
+/// Applies one event to a book, adds as of the time they were journaled.
+fn apply(book: &OptionOrderBook, record: &JournalEventRecord, report: &mut JournalReplay) {
+    let now_ms = record.timestamp_ms;
+    let applied = match record.event {
+        JournalEvent::Add {
+            order_id,
+            side,
+            price,
+            quantity,
+            time_in_force,
+            ref participant,
+            ..
+        } => match participant {
+            Some(participant) => book.add_limit_order_as_at(
+                order_id,
+                side,
+                price,
+                quantity,
+                time_in_force,
+                participant,
+                now_ms,
+            ),
+            None => book.add_limit_order_at(order_id, side, price, quantity, time_in_force, now_ms),
+        }
+        .is_ok(),
+        JournalEvent::Cancel { order_id, .. } => book.cancel_order(order_id).unwrap_or(false),
+        JournalEvent::Modify {
+            order_id,
+            price,
+            quantity,
+            ..
+        } => book
+            .modify_order(order_id, price, quantity)
+            .is_ok_and(|outcome| outcome.is_applied()),
+        JournalEvent::Fill { .. } => {
+            report.fills += 1;
+            return;
+        }
+    };
+    if applied {
+        report.applied += 1;
+    } else {
+        report.rejected += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use optionstratlib::OptionStyle;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+    use std::path::Path;
+
+    const SYMBOL: &str = "BTC-20240329-50000-C";
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ocob-wal-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn journal(
+        dir: &Path,
+        max_segment_bytes: u64,
+    ) -> (Arc<EventJournal>, Arc<SegmentedJournalStore>) {
+        let config = SegmentConfig {
+            max_segment_bytes,
+            sync_writes: false,
+        };
+        let store = Arc::new(SegmentedJournalStore::open(dir, config).unwrap());
+        let clock = Arc::new(ManualClock::new(1_000));
+        let journal = EventJournal::with_store(store.clone(), clock).unwrap();
+        (Arc::new(journal), store)
+    }
+
+    #[test]
+    fn test_rebuild_book_from_rotated_segments() {
+        let dir = temp_dir("rebuild");
+        let (journal, store) = journal(&dir, 512);
+        let book = OptionOrderBook::new(SYMBOL, OptionStyle::Call);
+        book.add_book_listener(journal.clone());
+
+        let ask = OrderId::new();
+        let bid = OrderId::new();
+        journal
+            .add_limit_order(&book, ask, Side::Sell, 110, 10, TimeInForce::Gtc)
+            .unwrap();
+        journal
+            .add_limit_order(&book, bid, Side::Buy, 100, 5, TimeInForce::Gtc)
+            .unwrap();
+        assert!(journal.modify_order(&book, bid, 102, 7).unwrap());
+        journal
+            .add_limit_order(&book, OrderId::new(), Side::Buy, 110, 4, TimeInForce::Ioc)
+            .unwrap();
+        assert!(!journal.modify_order(&book, OrderId::new(), 90, 1).unwrap());
+        assert_eq!(journal.last_sequence(), 6);
+        assert!(store.segment_count().unwrap() > 1);
+
+        let rebuilt = OptionOrderBook::new(SYMBOL, OptionStyle::Call);
+        let report = journal.rebuild_book(&rebuilt).unwrap();
+        assert_eq!(report.fills, 1);
+        assert_eq!(report.rejected, 1);
+        assert_eq!(rebuilt.best_quote(), book.best_quote());
+        assert_eq!(rebuilt.ask_depth_at_price(110), 6);
+        assert_eq!(rebuilt.bid_depth_at_price(102), 7);
+
+        // Compaction drops what the checkpoint covers, once.
+        journal.set_checkpoint(4);
+        let stats = journal.compact(0).unwrap();
+        assert_eq!((stats.evicted, stats.remaining), (4, 2));
+        assert_eq!(journal.compact(0).unwrap().evicted, 0);
+        let first = JournalStore::<JournalEventRecord>::first_sequence(store.as_ref());
+        assert_eq!(first.unwrap(), Some(5));
+
+        // A snapshot covering the log lets it be truncated; numbering goes on.
+        journal.truncate_through(journal.last_sequence()).unwrap();
+        assert_eq!(store.segment_count().unwrap(), 1);
+        let empty = OptionOrderBook::new(SYMBOL, OptionStyle::Call);
+        assert_eq!(journal.rebuild_book(&empty).unwrap().applied, 0);
+        assert!(journal.cancel_order(&book, bid).unwrap());
+        assert_eq!(journal.last_sequence(), 7);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_replay_good_till_date_and_timer_cancels() {
+        let dir = temp_dir("gtd");
+        let store = Arc::new(SegmentedJournalStore::open(&dir, SegmentConfig::default()).unwrap());
+        let clock = Arc::new(ManualClock::new(1_000_000));
+        let journal = Arc::new(EventJournal::with_store(store, clock.clone()).unwrap());
+        let book = OptionOrderBook::new(SYMBOL, OptionStyle::Call);
+        book.set_clock(clock.clone());
+        book.add_book_listener(journal.clone());
+
+        let expiring = OrderId::new();
+        let resting = OrderId::new();
+        journal
+            .add_limit_order(&book, expiring, Side::Sell, 110, 5, TimeInForce::Gtd(1_060))
+            .unwrap();
+        journal
+            .add_limit_order_as(
+                &book,
+                resting,
+                Side::Sell,
+                120,
+                5,
+                TimeInForce::Gtd(9_000),
+                "mm",
+            )
+            .unwrap();
+        clock.set(1_060_000);
+        assert_eq!(book.run_timers().expired, 1);
+        assert_eq!(journal.last_sequence(), 3);
+
+        // Replayed long after the first expiry, both adds are accepted as
+        // of their journal time and the timer cancel is replayed.
+        let rebuilt = OptionOrderBook::new(SYMBOL, OptionStyle::Call);
+        rebuilt.set_clock(Arc::new(ManualClock::new(2_000_000)));
+        let report = journal.rebuild_book(&rebuilt).unwrap();
+        assert_eq!((report.applied, report.rejected), (3, 0));
+        assert_eq!(rebuilt.best_ask(), Some(120));
+        assert_eq!(rebuilt.order_expiry(resting), Some(9_000_000));
+        assert_eq!(rebuilt.participant(resting).as_deref(), Some("mm"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_open_truncates_torn_tail() {
+        let dir = temp_dir("torn");
+        let book = OptionOrderBook::new(SYMBOL, OptionStyle::Call);
+        {
+            let (journal, _) = journal(&dir, 1 << 20);
+            journal
+                .add_limit_order(&book, OrderId::new(), Side::Buy, 100, 5, TimeInForce::Gtc)
+                .unwrap();
+        }
+        let path = fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[42, 0, 0, 0, 1, 2]).unwrap();
+
+        let (journal, _) = journal(&dir, 1 << 20);
+        assert_eq!(journal.last_sequence(), 1);
+        journal.cancel_order(&book, OrderId::new()).unwrap();
+        let mut sequences = Vec::new();
+        journal
+            .replay(0, &mut |record| {
+                sequences.push(record.sequence);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(sequences, vec![1, 2]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
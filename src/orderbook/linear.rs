@@ -0,0 +1,265 @@
+//! Linear instrument order book module.
+//!
+//! This module provides the [`LinearOrderBook`] for strike-less instruments
+//! used for hedging and basis calculations: futures, perpetual swaps and spot
+//! pairs. These books live alongside the option hierarchy in the
+//! [`UnderlyingOrderBookManager`](super::UnderlyingOrderBookManager).
+
+use super::quote::Quote;
+use crate::Result;
+use crate::clock::{Clock, SystemClock};
+use optionstratlib::ExpirationDate;
+use orderbook_rs::{DefaultOrderBook, OrderId, Side, TimeInForce};
+use std::sync::{Arc, RwLock};
+
+/// Kind of linear instrument.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinearKind {
+    /// Dated future.
+    Future(ExpirationDate),
+    /// Perpetual swap.
+    Perpetual,
+    /// Spot pair.
+    Spot,
+}
+
+impl std::fmt::Display for LinearKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Future(_) => write!(f, "future"),
+            Self::Perpetual => write!(f, "perpetual"),
+            Self::Spot => write!(f, "spot"),
+        }
+    }
+}
+
+/// Order book for a single linear instrument.
+///
+/// Wraps the `OrderBook<T>` from OrderBook-rs like [`OptionOrderBook`](super::OptionOrderBook)
+/// and additionally carries a mark price, which falls back to the mid price
+/// when no explicit mark has been set.
+pub struct LinearOrderBook {
+    /// The instrument symbol.
+    symbol: String,
+    /// The underlying asset symbol.
+    underlying: String,
+    /// The instrument kind.
+    kind: LinearKind,
+    /// The underlying order book from OrderBook-rs.
+    book: Arc<DefaultOrderBook>,
+    /// Explicit mark price, if set.
+    mark: RwLock<Option<u128>>,
+    /// Clock stamping quotes.
+    clock: Arc<dyn Clock>,
+}
+
+impl LinearOrderBook {
+    /// Creates a new linear instrument order book.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The instrument symbol (e.g., "BTC-PERP")
+    /// * `underlying` - The underlying asset symbol (e.g., "BTC")
+    /// * `kind` - The instrument kind
+    #[must_use]
+    pub fn new(symbol: impl Into<String>, underlying: impl Into<String>, kind: LinearKind) -> Self {
+        let symbol = symbol.into();
+        Self {
+            book: Arc::new(DefaultOrderBook::new(&symbol)),
+            symbol,
+            underlying: underlying.into(),
+            kind,
+            mark: RwLock::new(None),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Sets the clock stamping quotes, e.g. a simulated clock in tests and
+    /// replays.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns the instrument symbol.
+    #[must_use]
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Returns the underlying asset symbol.
+    #[must_use]
+    pub fn underlying(&self) -> &str {
+        &self.underlying
+    }
+
+    /// Returns the instrument kind.
+    #[must_use]
+    pub const fn kind(&self) -> &LinearKind {
+        &self.kind
+    }
+
+    /// Returns a reference to the underlying OrderBook from OrderBook-rs.
+    #[must_use]
+    pub fn inner(&self) -> &DefaultOrderBook {
+        &self.book
+    }
+
+    /// Adds a limit order to the book.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_id` - Unique identifier for the order
+    /// * `side` - Buy or Sell side
+    /// * `price` - Limit price in smallest units (u128)
+    /// * `quantity` - Order quantity in smallest units (u64)
+    pub fn add_limit_order(
+        &self,
+        order_id: OrderId,
+        side: Side,
+        price: u128,
+        quantity: u64,
+    ) -> Result<()> {
+        self.book
+            .add_limit_order(order_id, price, quantity, side, TimeInForce::Gtc, None)
+            .map_err(|e| crate::Error::orderbook(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Cancels an order by its ID.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(true)` if the order was found and cancelled, `Ok(false)` if not found.
+    pub fn cancel_order(&self, order_id: OrderId) -> Result<bool> {
+        Ok(matches!(self.book.cancel_order(order_id), Ok(Some(_))))
+    }
+
+    /// Returns the current best quote.
+    #[must_use]
+    pub fn best_quote(&self) -> Quote {
+        let (bids, asks) = self.book.get_volume_by_price();
+        let bid_price = self.book.best_bid();
+        let ask_price = self.book.best_ask();
+        Quote::new(
+            bid_price,
+            bid_price.and_then(|p| bids.get(&p).copied()).unwrap_or(0),
+            ask_price,
+            ask_price.and_then(|p| asks.get(&p).copied()).unwrap_or(0),
+            self.clock.now_ms(),
+        )
+    }
+
+    /// Returns the best bid price.
+    #[must_use]
+    pub fn best_bid(&self) -> Option<u128> {
+        self.book.best_bid()
+    }
+
+    /// Returns the best ask price.
+    #[must_use]
+    pub fn best_ask(&self) -> Option<u128> {
+        self.book.best_ask()
+    }
+
+    /// Returns the total number of orders in the book.
+    #[must_use]
+    pub fn order_count(&self) -> usize {
+        self.book.get_all_orders().len()
+    }
+
+    /// Returns true if the order book is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.book.best_bid().is_none() && self.book.best_ask().is_none()
+    }
+
+    /// Sets an explicit mark price.
+    pub fn set_mark(&self, price: u128) {
+        if let Ok(mut mark) = self.mark.write() {
+            *mark = Some(price);
+        }
+    }
+
+    /// Clears the explicit mark price so the mid price is used again.
+    pub fn clear_mark(&self) {
+        if let Ok(mut mark) = self.mark.write() {
+            *mark = None;
+        }
+    }
+
+    /// Returns the mark price.
+    ///
+    /// Uses the explicit mark if set, otherwise the mid of the best bid and
+    /// ask (rounded down). Returns `None` if neither is available.
+    #[must_use]
+    pub fn mark_price(&self) -> Option<u128> {
+        if let Some(mark) = self.mark.read().ok().and_then(|m| *m) {
+            return Some(mark);
+        }
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => Some(bid / 2 + ask / 2 + (bid % 2 + ask % 2) / 2),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_book_creation() {
+        let book = LinearOrderBook::new("BTC-PERP", "BTC", LinearKind::Perpetual);
+
+        assert_eq!(book.symbol(), "BTC-PERP");
+        assert_eq!(book.underlying(), "BTC");
+        assert_eq!(book.kind(), &LinearKind::Perpetual);
+        assert!(book.is_empty());
+        assert!(book.mark_price().is_none());
+    }
+
+    #[test]
+    fn test_linear_book_orders_and_quote() {
+        let book = LinearOrderBook::new("BTC-USD", "BTC", LinearKind::Spot)
+            .with_clock(Arc::new(crate::clock::ManualClock::new(7_000)));
+        let bid_id = OrderId::new();
+        book.add_limit_order(bid_id, Side::Buy, 50000, 3).unwrap();
+        book.add_limit_order(OrderId::new(), Side::Sell, 50011, 2)
+            .unwrap();
+
+        let quote = book.best_quote();
+        assert!(quote.is_two_sided());
+        assert_eq!(quote.bid_size(), 3);
+        assert_eq!(quote.timestamp_ms(), 7_000);
+        assert_eq!(book.order_count(), 2);
+
+        assert!(book.cancel_order(bid_id).unwrap());
+        assert!(!book.cancel_order(bid_id).unwrap());
+        assert_eq!(book.best_bid(), None);
+    }
+
+    #[test]
+    fn test_mark_price_falls_back_to_mid() {
+        let book = LinearOrderBook::new("BTC-PERP", "BTC", LinearKind::Perpetual);
+        book.add_limit_order(OrderId::new(), Side::Buy, 101, 1)
+            .unwrap();
+        book.add_limit_order(OrderId::new(), Side::Sell, 105, 1)
+            .unwrap();
+
+        assert_eq!(book.mark_price(), Some(103));
+
+        book.set_mark(104);
+        assert_eq!(book.mark_price(), Some(104));
+
+        book.clear_mark();
+        assert_eq!(book.mark_price(), Some(103));
+    }
+
+    #[test]
+    fn test_linear_kind_display() {
+        assert_eq!(LinearKind::Spot.to_string(), "spot");
+        assert_eq!(LinearKind::Perpetual.to_string(), "perpetual");
+    }
+}
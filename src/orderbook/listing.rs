@@ -0,0 +1,239 @@
+//! Strike listing module.
+//!
+//! This module provides the [`ListingRules`] used by
+//! [`super::OptionChainOrderBook::generate_strikes`] to lay out the strike
+//! ladder of a new expiration around spot.
+//!
+//! ## Ladder
+//!
+//! The at-the-money strike is spot rounded onto the grid of the innermost
+//! band. From there the ladder walks outwards one strike at a time, each
+//! step landing on the next multiple of the interval of the band the
+//! current strike falls in, so intervals widen with distance from spot:
+//!
+//! ```text
+//! spot 103, bands [5% -> 1, 20% -> 5, beyond -> 25]
+//! ... 85 90 95 97 98 99 100 101 102 103 104 ... 108 109 110 115 120 125 150 175 ...
+//! ```
+
+use super::tiering::TieringPolicy;
+use crate::error::{Error, Result};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Strike interval used up to a distance from spot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StrikeBand {
+    /// Largest `|strike / spot - 1|` the band covers (inclusive).
+    pub max_distance: Decimal,
+    /// Interval between strikes in the band.
+    pub interval: u64,
+}
+
+impl StrikeBand {
+    /// Creates a strike band.
+    #[must_use]
+    pub const fn new(max_distance: Decimal, interval: u64) -> Self {
+        Self {
+            max_distance,
+            interval,
+        }
+    }
+}
+
+/// How spot is rounded onto the strike grid to give the ATM strike.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StrikeRounding {
+    /// Nearest strike, halves rounded up.
+    #[default]
+    Nearest,
+    /// Highest strike at or below spot.
+    Down,
+    /// Lowest strike at or above spot.
+    Up,
+}
+
+impl StrikeRounding {
+    /// Rounds a price onto a grid.
+    #[must_use]
+    pub const fn round(&self, price: u64, interval: u64) -> u64 {
+        let below = price / interval * interval;
+        match self {
+            Self::Down => below,
+            Self::Up if below == price => below,
+            Self::Up => below + interval,
+            Self::Nearest if price - below >= interval.div_ceil(2) => below + interval,
+            Self::Nearest => below,
+        }
+    }
+}
+
+/// Listing rules of a strike ladder.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListingRules {
+    /// Intervals by distance from spot, innermost first; strikes beyond
+    /// the last band use its interval.
+    pub bands: Vec<StrikeBand>,
+    /// Strikes listed above the ATM strike.
+    pub strikes_above: usize,
+    /// Strikes listed below the ATM strike, fewer if the ladder reaches
+    /// the minimum strike.
+    pub strikes_below: usize,
+    /// Rounding of spot to the ATM strike.
+    pub rounding: StrikeRounding,
+    /// Lowest strike listed.
+    pub min_strike: u64,
+    /// Warm/cold tiering of the listed strikes; every strike gets order
+    /// books if unset.
+    pub tiering: Option<TieringPolicy>,
+}
+
+impl Default for ListingRules {
+    fn default() -> Self {
+        Self {
+            bands: vec![
+                StrikeBand::new(dec!(0.05), 1),
+                StrikeBand::new(dec!(0.2), 5),
+                StrikeBand::new(dec!(1), 25),
+            ],
+            strikes_above: 20,
+            strikes_below: 20,
+            rounding: StrikeRounding::Nearest,
+            min_strike: 1,
+            tiering: None,
+        }
+    }
+}
+
+impl ListingRules {
+    /// Validates the rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if there are no bands, an
+    /// interval is zero, the band distances are not strictly ascending
+    /// and positive, or the minimum strike is zero.
+    pub fn validate(&self) -> Result<()> {
+        if self.bands.is_empty() {
+            return Err(Error::configuration("listing rules need a strike band"));
+        }
+        if self.bands.iter().any(|band| band.interval == 0) {
+            return Err(Error::configuration("strike intervals must be positive"));
+        }
+        if self.bands[0].max_distance <= Decimal::ZERO
+            || self
+                .bands
+                .windows(2)
+                .any(|w| w[0].max_distance >= w[1].max_distance)
+        {
+            return Err(Error::configuration(
+                "strike band distances must be positive and strictly ascending",
+            ));
+        }
+        if self.min_strike == 0 {
+            return Err(Error::configuration("minimum strike must be positive"));
+        }
+        Ok(())
+    }
+
+    /// Returns the ascending strike ladder around a spot price.
+    ///
+    /// # Arguments
+    ///
+    /// * `spot` - Spot price in the same units as strikes
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the rules are invalid, or
+    /// `Error::ValidationError` if spot is zero.
+    pub fn strikes(&self, spot: u64) -> Result<Vec<u64>> {
+        self.validate()?;
+        if spot == 0 {
+            return Err(Error::validation("spot must be positive"));
+        }
+        let atm = self
+            .rounding
+            .round(spot, self.bands[0].interval)
+            .max(self.min_strike);
+
+        let mut below = Vec::with_capacity(self.strikes_below);
+        let mut strike = atm;
+        while below.len() < self.strikes_below {
+            let interval = self.interval(strike, spot);
+            let next = (strike - 1) / interval * interval;
+            if next < self.min_strike {
+                break;
+            }
+            below.push(next);
+            strike = next;
+        }
+
+        let mut ladder: Vec<u64> = below.into_iter().rev().collect();
+        ladder.push(atm);
+        let mut strike = atm;
+        for _ in 0..self.strikes_above {
+            let interval = self.interval(strike, spot);
+            strike = (strike / interval + 1).saturating_mul(interval);
+            ladder.push(strike);
+        }
+        Ok(ladder)
+    }
+
+    /// Returns the interval of the band a strike falls in.
+    fn interval(&self, strike: u64, spot: u64) -> u64 {
+        let distance = (Decimal::from(strike) / Decimal::from(spot) - Decimal::ONE).abs();
+        self.bands
+            .iter()
+            .find(|band| distance <= band.max_distance)
+            .unwrap_or(&self.bands[self.bands.len() - 1])
+            .interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ladder_widens_with_distance() {
+        let rules = ListingRules {
+            strikes_above: 12,
+            strikes_below: 10,
+            ..ListingRules::default()
+        };
+        let ladder = rules.strikes(103).unwrap();
+        assert_eq!(
+            ladder,
+            vec![
+                80, 85, 90, 95, 97, 98, 99, 100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110,
+                115, 120, 125, 150, 175,
+            ]
+        );
+
+        assert_eq!(StrikeRounding::Nearest.round(1049, 100), 1000);
+        assert_eq!(StrikeRounding::Nearest.round(1050, 100), 1100);
+        assert_eq!(StrikeRounding::Down.round(1099, 100), 1000);
+        assert_eq!(StrikeRounding::Up.round(1001, 100), 1100);
+        assert_eq!(StrikeRounding::Up.round(1000, 100), 1000);
+    }
+
+    #[test]
+    fn test_ladder_stops_at_min_strike() {
+        let rules = ListingRules {
+            bands: vec![StrikeBand::new(dec!(0.5), 10)],
+            strikes_above: 1,
+            strikes_below: 10,
+            min_strike: 20,
+            ..ListingRules::default()
+        };
+        assert_eq!(rules.strikes(42).unwrap(), vec![20, 30, 40, 50]);
+        assert!(rules.strikes(0).is_err());
+
+        let unordered = ListingRules {
+            bands: vec![StrikeBand::new(dec!(0.2), 5), StrikeBand::new(dec!(0.1), 1)],
+            ..ListingRules::default()
+        };
+        assert!(unordered.strikes(100).is_err());
+    }
+}
@@ -26,7 +26,25 @@
 //! - [`StrikeOrderBookManager`]: Manages strikes for an expiration
 //! - [`StrikeOrderBook`]: Call/put pair at a strike price
 //! - [`OptionOrderBook`]: Single option order book (call or put)
+//! - [`OrderTags`]: Good-Till-Date expiry and self-trade participant of a resting order, saved alongside book snapshots
+//! - [`ModifyOutcome`]: Result of a modify, amended in place with queue priority kept or cancelled and replaced
+//! - [`SelfTradePrevention`]: Cancel-newest, cancel-oldest or cancel-both policy between orders of one participant
+//! - [`ScheduledCancel`]: Mass cancel of all or one participant's orders at a time, run with Good-Till-Date expiries by the book's clock, at any level of the hierarchy
 //! - [`Quote`]: Represents a two-sided quote (bid and ask)
+//! - [`ContractRegistry`]: Interns contract symbols into compact [`ContractId`]s
+//! - [`ChainFilter`]: Selects contracts by tenor, moneyness and liquidity into a [`ChainView`]
+//! - [`ComboBook`]: Straddles, strangles and verticals priced from their leg books, per a [`ComboDefinition`]
+//! - [`LinearOrderBook`]: Futures, perpetuals and spot pairs used for hedging
+//! - [`DepthTracker`]: Incremental L2 level changes between book states, streamed per symbol by a [`DepthFeed`]
+//! - [`BookListener`]: Observer hooks on an [`OptionOrderBook`] for quote, trade and depth changes, or a channel of [`BookEvent`]s
+//! - [`EventJournal`]: Write-ahead log of adds, cancels, modifies and fills over a journal store, replayable into a book or manager and truncatable after a snapshot
+//! - [`QueuePositionTracker`]: Estimates the queue ahead of our resting orders and their fill probability
+//! - [`scan_parity`]: Put-call parity violations tradable against the books, as [`ArbitrageOpportunity`]s net of fees
+//! - [`smile_metrics`]: ATM vol, 25-delta risk reversal and butterfly, and skew slope per expiration, as [`SmileMetrics`]
+//! - [`SettlementEngine`]: Expiry settlement of held contracts in cash or into the underlying, as [`SettlementEvent`]s
+//! - [`ListingRules`]: Strike ladder of a new expiration by moneyness [`StrikeBand`]s, strike counts around spot and [`StrikeRounding`]
+//! - [`ChainBookSynchronizer`]: Creates books for [`ListedContract`]s and closes and archives them on expiry or delisting, with a consistency check
+//! - [`TieringPolicy`]: Warm/cold listing of strikes, with far strikes kept as [`StrikePlaceholder`]s until used
 //!
 //! ## Example
 //!
@@ -49,17 +67,57 @@
 
 mod book;
 mod chain;
+mod combo;
+mod depth;
+mod events;
 mod expiration;
+mod filter;
+mod journal;
+mod linear;
+mod listing;
+mod modify;
+mod parity;
+mod queue;
 mod quote;
+mod registry;
+mod settlement;
+mod smile;
+mod stp;
 mod strike;
+mod sync;
+mod tiering;
+mod timers;
 mod underlying;
 
 // Re-export all public types
-pub use book::OptionOrderBook;
+pub use book::{OptionOrderBook, OrderTags};
 pub use chain::{OptionChainOrderBook, OptionChainOrderBookManager, OptionChainStats};
+pub use combo::{ComboBook, ComboDefinition, ComboDefinitionLeg, ImpliedLegOrder, SyntheticQuote};
+pub use depth::{
+    DepthFeed, DepthListener, DepthTracker, DepthUpdate, LevelChange, LevelChangeKind,
+};
+pub use events::{BookEvent, BookListener, ChannelListener};
 pub use expiration::{ExpirationManagerStats, ExpirationOrderBook, ExpirationOrderBookManager};
+pub use filter::{ChainContract, ChainFilter, ChainView, ChainViewStats, MoneynessRange};
+pub use journal::{
+    EventJournal, EventJournalStats, JournalEvent, JournalEventRecord, JournalReplay,
+};
+pub use linear::{LinearKind, LinearOrderBook};
+pub use listing::{ListingRules, StrikeBand, StrikeRounding};
+pub use modify::ModifyOutcome;
+pub use parity::{
+    ArbitrageOpportunity, ParityDirection, ParityScanConfig, scan_parity, scan_underlying_parity,
+};
+pub use queue::{QueuePosition, QueuePositionTracker};
 pub use quote::{Quote, QuoteUpdate};
+pub use registry::{ContractId, ContractRegistry};
+pub use settlement::{Delivery, SettlementEngine, SettlementEvent, SettlementStyle};
+pub use smile::{SmileMetrics, SmileMetricsConfig, smile_metrics, underlying_smile_metrics};
+pub use stp::SelfTradePrevention;
 pub use strike::{StrikeOrderBook, StrikeOrderBookManager};
+pub use sync::{ArchivedContract, ChainBookSynchronizer, CloseReason, ListedContract, SyncReport};
+pub use tiering::{BookTier, StrikePlaceholder, TierStats, TieringPolicy};
+pub use timers::{MassCancelScope, ScheduledCancel, TimerReport};
 pub use underlying::{
     GlobalStats, UnderlyingOrderBook, UnderlyingOrderBookManager, UnderlyingStats,
 };
@@ -0,0 +1,61 @@
+//! Order modification module.
+//!
+//! This module provides the [`ModifyOutcome`] of
+//! [`super::OptionOrderBook::modify_order`]. A modify that only reduces the
+//! size of a resting order is amended in place and keeps the order's place
+//! in its price level queue; any other change of price or size cancels the
+//! order and re-adds it under the same id, which joins the back of the
+//! queue unless the level still holds the order's earlier place.
+
+use serde::{Deserialize, Serialize};
+
+/// What a modify did to the order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ModifyOutcome {
+    /// The order was not resting in the book.
+    NotFound,
+    /// The new price and quantity equal the resting ones.
+    Unchanged,
+    /// The quantity was reduced in place; the order kept its queue priority.
+    Amended {
+        /// Quantity now resting.
+        quantity: u64,
+    },
+    /// The order was cancelled and re-added; it may have traded on
+    /// re-entry, and only keeps its queue place at a level that still holds
+    /// it.
+    Replaced {
+        /// New limit price.
+        price: u128,
+        /// New quantity.
+        quantity: u64,
+    },
+    /// A new quantity of zero cancelled the order.
+    Cancelled,
+}
+
+impl ModifyOutcome {
+    /// Returns true if the order was resting and the modify took effect.
+    #[must_use]
+    pub const fn is_applied(&self) -> bool {
+        !matches!(self, Self::NotFound)
+    }
+
+    /// Returns true if the order is still resting with its queue priority.
+    #[must_use]
+    pub const fn keeps_priority(&self) -> bool {
+        matches!(self, Self::Unchanged | Self::Amended { .. })
+    }
+}
+
+impl std::fmt::Display for ModifyOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "not found"),
+            Self::Unchanged => write!(f, "unchanged"),
+            Self::Amended { quantity } => write!(f, "amended to {quantity}"),
+            Self::Replaced { price, quantity } => write!(f, "replaced at {quantity}@{price}"),
+            Self::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
@@ -0,0 +1,337 @@
+//! Put-call parity scanner module.
+//!
+//! This module scans an option chain's books for put-call parity violations
+//! that can be locked in against the resting quotes, net of fees. It is used
+//! both to check the market and to keep our own quotes inside the parity
+//! bounds.
+//!
+//! ## Bounds
+//!
+//! With spot `S`, dividend yield `q`, rate `r`, strike `K` and `T` years to
+//! expiry, parity values the synthetic forward at
+//!
+//! ```text
+//! PV = S * e^(-qT) - K * e^(-rT)
+//! ```
+//!
+//! - A conversion sells the call at its bid, buys the put at its ask and
+//!   buys the underlying; it earns `C_bid - P_ask - PV`
+//! - A reversal buys the call at its ask, sells the put at its bid and
+//!   sells the underlying; it earns `PV - (C_ask - P_bid)`
+//!
+//! Each opportunity is charged two option fees and one underlying fee.
+
+use super::chain::OptionChainOrderBook;
+use super::underlying::UnderlyingOrderBook;
+use crate::error::{Error, Result};
+use crate::pricing::DAYS_PER_YEAR;
+use rust_decimal::{Decimal, MathematicalOps};
+use serde::{Deserialize, Serialize};
+
+/// Direction of a parity trade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ParityDirection {
+    /// Sell call, buy put, buy underlying.
+    Conversion,
+    /// Buy call, sell put, sell underlying.
+    Reversal,
+}
+
+/// Settings of a parity scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParityScanConfig {
+    /// Price of one book tick, in strike units.
+    pub tick_size: Decimal,
+    /// Annualized risk-free rate.
+    pub rate: Decimal,
+    /// Annualized dividend (or borrow) yield of the underlying.
+    pub dividend_yield: Decimal,
+    /// Fee per option contract traded.
+    pub option_fee: Decimal,
+    /// Fee per unit of underlying traded.
+    pub underlying_fee: Decimal,
+    /// Profit after fees an opportunity must exceed to be reported.
+    pub min_profit: Decimal,
+}
+
+impl Default for ParityScanConfig {
+    fn default() -> Self {
+        Self {
+            tick_size: Decimal::ONE,
+            rate: Decimal::ZERO,
+            dividend_yield: Decimal::ZERO,
+            option_fee: Decimal::ZERO,
+            underlying_fee: Decimal::ZERO,
+            min_profit: Decimal::ZERO,
+        }
+    }
+}
+
+impl ParityScanConfig {
+    /// Validates the settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the tick size is not positive
+    /// or a fee is negative.
+    pub fn validate(&self) -> Result<()> {
+        if self.tick_size <= Decimal::ZERO {
+            return Err(Error::configuration("parity tick size must be positive"));
+        }
+        if self.option_fee < Decimal::ZERO || self.underlying_fee < Decimal::ZERO {
+            return Err(Error::configuration("parity fees must be non-negative"));
+        }
+        Ok(())
+    }
+
+    /// Returns the fees charged on one parity trade.
+    #[must_use]
+    pub fn trade_fees(&self) -> Decimal {
+        self.option_fee * Decimal::TWO + self.underlying_fee
+    }
+}
+
+/// A parity violation tradable against resting quotes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArbitrageOpportunity {
+    /// Call contract symbol.
+    pub call_symbol: String,
+    /// Put contract symbol.
+    pub put_symbol: String,
+    /// Strike price.
+    pub strike: u64,
+    /// Years to expiry used for discounting.
+    pub years_to_expiry: Decimal,
+    /// Trade direction.
+    pub direction: ParityDirection,
+    /// Call price traded against.
+    pub call_price: Decimal,
+    /// Put price traded against.
+    pub put_price: Decimal,
+    /// Parity value of the synthetic forward.
+    pub parity_value: Decimal,
+    /// Profit per unit before fees.
+    pub gross_profit: Decimal,
+    /// Fees per unit.
+    pub fees: Decimal,
+    /// Profit per unit after fees.
+    pub profit: Decimal,
+    /// Units available at both quotes.
+    pub quantity: u64,
+}
+
+/// Scans every strike of one expiration for parity violations.
+///
+/// The time to expiry is taken from the chain's last clock tick, or else
+/// from its expiration date. Expired chains yield nothing. Opportunities are
+/// returned by decreasing profit.
+///
+/// # Arguments
+///
+/// * `chain` - Books of one expiration
+/// * `spot` - Spot price of the underlying, in strike units
+/// * `config` - Rates, fees and tick size
+///
+/// # Errors
+///
+/// Returns `Error::ValidationError` if spot is not positive, or
+/// `Error::ConfigurationError` if the settings are invalid.
+pub fn scan_parity(
+    chain: &OptionChainOrderBook,
+    spot: Decimal,
+    config: &ParityScanConfig,
+) -> Result<Vec<ArbitrageOpportunity>> {
+    if spot <= Decimal::ZERO {
+        return Err(Error::validation("spot must be positive"));
+    }
+    config.validate()?;
+
+    let mut found = Vec::new();
+    scan_chain(chain, spot, config, &mut found);
+    found.sort_by_key(|o| std::cmp::Reverse(o.profit));
+    Ok(found)
+}
+
+/// Scans every expiration of an underlying for parity violations.
+///
+/// Opportunities are returned by decreasing profit.
+///
+/// # Errors
+///
+/// Returns `Error::ValidationError` if spot is not positive, or
+/// `Error::ConfigurationError` if the settings are invalid.
+pub fn scan_underlying_parity(
+    underlying: &UnderlyingOrderBook,
+    spot: Decimal,
+    config: &ParityScanConfig,
+) -> Result<Vec<ArbitrageOpportunity>> {
+    if spot <= Decimal::ZERO {
+        return Err(Error::validation("spot must be positive"));
+    }
+    config.validate()?;
+
+    let mut found = Vec::new();
+    for expiration in underlying.expirations().iter() {
+        scan_chain(expiration.value().chain(), spot, config, &mut found);
+    }
+    found.sort_by_key(|o| std::cmp::Reverse(o.profit));
+    Ok(found)
+}
+
+/// Appends the violations of one chain.
+fn scan_chain(
+    chain: &OptionChainOrderBook,
+    spot: Decimal,
+    config: &ParityScanConfig,
+    found: &mut Vec<ArbitrageOpportunity>,
+) {
+    let years = chain.tau().or_else(|| {
+        chain
+            .expiration()
+            .get_days()
+            .ok()
+            .map(|d| d.to_dec() / DAYS_PER_YEAR)
+    });
+    let Some(years) = years.filter(|t| *t > Decimal::ZERO) else {
+        return;
+    };
+    let carry = spot * (-config.dividend_yield * years).exp();
+    let discount = (-config.rate * years).exp();
+    let fees = config.trade_fees();
+    let price = |ticks: u128| Decimal::from(ticks) * config.tick_size;
+
+    for entry in chain.strikes().iter() {
+        let strike_book = entry.value();
+        let strike = *entry.key();
+        let parity_value = carry - Decimal::from(strike) * discount;
+        let (call, put) = (strike_book.call_quote(), strike_book.put_quote());
+
+        let legs = [
+            (
+                ParityDirection::Conversion,
+                call.bid_price().zip(put.ask_price()),
+                call.bid_size().min(put.ask_size()),
+            ),
+            (
+                ParityDirection::Reversal,
+                call.ask_price().zip(put.bid_price()),
+                call.ask_size().min(put.bid_size()),
+            ),
+        ];
+        for (direction, prices, quantity) in legs {
+            let Some((call_ticks, put_ticks)) = prices else {
+                continue;
+            };
+            let (call_price, put_price) = (price(call_ticks), price(put_ticks));
+            let synthetic = call_price - put_price;
+            let gross_profit = match direction {
+                ParityDirection::Conversion => synthetic - parity_value,
+                ParityDirection::Reversal => parity_value - synthetic,
+            };
+            let profit = gross_profit - fees;
+            if profit <= config.min_profit || quantity == 0 {
+                continue;
+            }
+            found.push(ArbitrageOpportunity {
+                call_symbol: strike_book.call().symbol().to_string(),
+                put_symbol: strike_book.put().symbol().to_string(),
+                strike,
+                years_to_expiry: years,
+                direction,
+                call_price,
+                put_price,
+                parity_value,
+                gross_profit,
+                fees,
+                profit,
+                quantity,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::ExpirationDate;
+    use optionstratlib::prelude::pos_or_panic;
+    use orderbook_rs::{OrderId, Side};
+    use rust_decimal_macros::dec;
+
+    fn chain() -> OptionChainOrderBook {
+        let chain = OptionChainOrderBook::new("SPX", ExpirationDate::Days(pos_or_panic!(30.0)));
+        // Zero rates: parity value is 100 - K.
+        let at_parity = chain.get_or_create_strike(95);
+        at_parity
+            .call()
+            .add_limit_order(OrderId::new(), Side::Buy, 600, 10)
+            .unwrap();
+        at_parity
+            .call()
+            .add_limit_order(OrderId::new(), Side::Sell, 620, 10)
+            .unwrap();
+        at_parity
+            .put()
+            .add_limit_order(OrderId::new(), Side::Buy, 100, 10)
+            .unwrap();
+        at_parity
+            .put()
+            .add_limit_order(OrderId::new(), Side::Sell, 120, 10)
+            .unwrap();
+
+        // Call bid 3.50 against put ask 0.50 at K = 100: conversion earns 3.
+        let rich = chain.get_or_create_strike(100);
+        rich.call()
+            .add_limit_order(OrderId::new(), Side::Buy, 350, 4)
+            .unwrap();
+        rich.put()
+            .add_limit_order(OrderId::new(), Side::Sell, 50, 7)
+            .unwrap();
+        chain
+    }
+
+    #[test]
+    fn test_finds_conversion_after_fees() {
+        let chain = chain();
+        let config = ParityScanConfig {
+            tick_size: dec!(0.01),
+            option_fee: dec!(0.5),
+            underlying_fee: dec!(0.25),
+            ..ParityScanConfig::default()
+        };
+        let found = scan_parity(&chain, dec!(100), &config).unwrap();
+        assert_eq!(found.len(), 1);
+        let opportunity = &found[0];
+        assert_eq!(opportunity.strike, 100);
+        assert_eq!(opportunity.direction, ParityDirection::Conversion);
+        assert_eq!(opportunity.gross_profit, dec!(3));
+        assert_eq!(opportunity.profit, dec!(1.75));
+        assert_eq!(opportunity.quantity, 4);
+
+        // Fees above the edge leave nothing to trade.
+        let costly = ParityScanConfig {
+            option_fee: dec!(2),
+            ..config
+        };
+        assert!(scan_parity(&chain, dec!(100), &costly).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rejects_invalid_inputs() {
+        let chain = chain();
+        let config = ParityScanConfig::default();
+        assert!(scan_parity(&chain, Decimal::ZERO, &config).is_err());
+        let bad = ParityScanConfig {
+            tick_size: Decimal::ZERO,
+            ..config
+        };
+        assert!(scan_parity(&chain, dec!(100), &bad).is_err());
+
+        let underlying = UnderlyingOrderBook::new("SPX");
+        assert!(
+            scan_underlying_parity(&underlying, dec!(100), &config)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
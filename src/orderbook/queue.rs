@@ -0,0 +1,427 @@
+//! Queue position module.
+//!
+//! This module provides the [`QueuePositionTracker`], which estimates for
+//! each of our resting orders how much quantity is ahead of it at its price
+//! level, updated from the book events observed since the order entered.
+//!
+//! Other participants' adds join behind us. Trades at our level consume
+//! the queue from the front. Cancels at our level are assumed to come from
+//! anywhere in the queue, so they reduce the quantity ahead and behind us
+//! in proportion.
+//!
+//! Registered as a [`BookListener`] on a book, the tracker follows these
+//! events by itself: trades and fills of our orders come from the book's
+//! executions, and adds and cancels of other participants from the level
+//! quantities differing from our estimate of the level.
+//!
+//! Fill probabilities assume the volume traded at our level before we
+//! requote is exponentially distributed with a given mean, so an order
+//! with `q` ahead of it fills completely with probability
+//! `exp(-(q + quantity) / expected_volume)`.
+
+use super::book::OptionOrderBook;
+use super::depth::LevelChange;
+use super::events::BookListener;
+use orderbook_rs::{OrderId, Side, TradeResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Estimated queue state of one of our orders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueuePosition {
+    /// Our side.
+    pub side: Side,
+    /// Our price in ticks.
+    pub price: u128,
+    /// Our remaining quantity.
+    pub quantity: u64,
+    /// Estimated quantity ahead of us.
+    pub queue_ahead: u64,
+    /// Estimated quantity behind us.
+    pub queue_behind: u64,
+    /// Entry timestamp in milliseconds.
+    pub entered_at_ms: u64,
+}
+
+impl QueuePosition {
+    /// Returns our position as a fraction of the level, where zero is the
+    /// front of the queue.
+    #[must_use]
+    pub fn relative_position(&self) -> f64 {
+        let level = self.queue_ahead + self.quantity + self.queue_behind;
+        if level == 0 {
+            0.0
+        } else {
+            self.queue_ahead as f64 / level as f64
+        }
+    }
+
+    /// Returns the estimated quantity at our level, ours included.
+    #[must_use]
+    pub const fn level_quantity(&self) -> u64 {
+        self.queue_ahead + self.quantity + self.queue_behind
+    }
+
+    /// Returns the probability that our remaining quantity fills
+    /// completely.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected_volume` - Mean volume expected to trade at our level
+    ///   before we requote
+    #[must_use]
+    pub fn fill_probability(&self, expected_volume: f64) -> f64 {
+        self.probability_beyond(self.queue_ahead + self.quantity, expected_volume)
+    }
+
+    /// Returns the probability that our order starts filling.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected_volume` - Mean volume expected to trade at our level
+    ///   before we requote
+    #[must_use]
+    pub fn first_fill_probability(&self, expected_volume: f64) -> f64 {
+        self.probability_beyond(self.queue_ahead, expected_volume)
+    }
+
+    /// Returns the probability that more than `quantity` trades at our
+    /// level.
+    fn probability_beyond(&self, quantity: u64, expected_volume: f64) -> f64 {
+        if self.quantity == 0 || quantity == 0 {
+            return 1.0;
+        }
+        if expected_volume <= 0.0 || !expected_volume.is_finite() {
+            return 0.0;
+        }
+        (-(quantity as f64) / expected_volume).exp()
+    }
+}
+
+/// Queue position estimator for our resting orders.
+///
+/// `OrderId` has no ordering, so orders are kept in a mutex-guarded map.
+#[derive(Default)]
+pub struct QueuePositionTracker {
+    /// Tracked orders.
+    orders: Mutex<HashMap<OrderId, QueuePosition>>,
+}
+
+impl QueuePositionTracker {
+    /// Creates an empty tracker.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts tracking an order with a known queue ahead of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_id` - Our order identifier
+    /// * `side` - Our side
+    /// * `price` - Our price in ticks
+    /// * `quantity` - Our quantity
+    /// * `queue_ahead` - Quantity resting at our price when we joined
+    /// * `entered_at_ms` - Entry timestamp in milliseconds
+    pub fn track(
+        &self,
+        order_id: OrderId,
+        side: Side,
+        price: u128,
+        quantity: u64,
+        queue_ahead: u64,
+        entered_at_ms: u64,
+    ) {
+        self.lock().insert(
+            order_id,
+            QueuePosition {
+                side,
+                price,
+                quantity,
+                queue_ahead,
+                queue_behind: 0,
+                entered_at_ms,
+            },
+        );
+    }
+
+    /// Starts tracking an order that has just been added to a book, taking
+    /// the rest of its price level as the queue ahead.
+    pub fn track_in_book(
+        &self,
+        book: &OptionOrderBook,
+        order_id: OrderId,
+        side: Side,
+        price: u128,
+        quantity: u64,
+        entered_at_ms: u64,
+    ) {
+        let depth = match side {
+            Side::Buy => book.bid_depth_at_price(price),
+            Side::Sell => book.ask_depth_at_price(price),
+        };
+        self.track(
+            order_id,
+            side,
+            price,
+            quantity,
+            depth.saturating_sub(quantity),
+            entered_at_ms,
+        );
+    }
+
+    /// Stops tracking an order.
+    pub fn untrack(&self, order_id: OrderId) -> Option<QueuePosition> {
+        self.lock().remove(&order_id)
+    }
+
+    /// Returns the estimated queue state of an order.
+    #[must_use]
+    pub fn position(&self, order_id: OrderId) -> Option<QueuePosition> {
+        self.lock().get(&order_id).copied()
+    }
+
+    /// Returns the estimated quantity ahead of an order.
+    #[must_use]
+    pub fn queue_ahead(&self, order_id: OrderId) -> Option<u64> {
+        self.position(order_id).map(|p| p.queue_ahead)
+    }
+
+    /// Returns the probability that an order fills completely, as
+    /// [`QueuePosition::fill_probability`].
+    #[must_use]
+    pub fn fill_probability(&self, order_id: OrderId, expected_volume: f64) -> Option<f64> {
+        self.position(order_id)
+            .map(|p| p.fill_probability(expected_volume))
+    }
+
+    /// Returns the number of tracked orders.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns true if no order is tracked.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Records another participant's order joining a level.
+    pub fn on_add(&self, side: Side, price: u128, quantity: u64) {
+        self.update_level(side, price, |p| p.queue_behind += quantity);
+    }
+
+    /// Records another participant's cancel at a level.
+    pub fn on_cancel(&self, side: Side, price: u128, quantity: u64) {
+        self.update_level(side, price, |p| {
+            let others = p.queue_ahead + p.queue_behind;
+            if others == 0 {
+                return;
+            }
+            let quantity = quantity.min(others);
+            let from_ahead =
+                (u128::from(quantity) * u128::from(p.queue_ahead) / u128::from(others)) as u64;
+            p.queue_ahead -= from_ahead;
+            p.queue_behind = p.queue_behind.saturating_sub(quantity - from_ahead);
+        });
+    }
+
+    /// Records a trade against resting orders on `side` at `price`.
+    ///
+    /// Trades through our price also clear the queue ahead of us.
+    pub fn on_trade(&self, side: Side, price: u128, quantity: u64) {
+        for p in self.lock().values_mut() {
+            if p.side != side {
+                continue;
+            }
+            let through = match side {
+                Side::Buy => price < p.price,
+                Side::Sell => price > p.price,
+            };
+            if through {
+                p.queue_ahead = 0;
+            } else if price == p.price {
+                p.queue_ahead = p.queue_ahead.saturating_sub(quantity);
+            }
+        }
+    }
+
+    /// Records a partial fill of one of our orders.
+    pub fn on_own_fill(&self, order_id: OrderId, quantity: u64) {
+        if let Some(p) = self.lock().get_mut(&order_id) {
+            p.queue_ahead = 0;
+            p.quantity = p.quantity.saturating_sub(quantity);
+        }
+    }
+
+    /// Reconciles our estimate of a level with its observed quantity:
+    /// growth is taken as adds and shrinkage as cancels.
+    pub fn on_level(&self, side: Side, price: u128, quantity: u64) {
+        let estimate = self
+            .lock()
+            .values()
+            .find(|p| p.side == side && p.price == price)
+            .map(QueuePosition::level_quantity);
+        match estimate {
+            Some(estimate) if quantity > estimate => {
+                self.on_add(side, price, quantity - estimate);
+            }
+            Some(estimate) if quantity < estimate => {
+                self.on_cancel(side, price, estimate - quantity);
+            }
+            _ => {}
+        }
+    }
+
+    /// Applies an update to every tracked order at a level.
+    fn update_level(&self, side: Side, price: u128, mut f: impl FnMut(&mut QueuePosition)) {
+        for p in self.lock().values_mut() {
+            if p.side == side && p.price == price {
+                f(p);
+            }
+        }
+    }
+
+    /// Locks the order map, recovering from poisoning since every update
+    /// leaves positions consistent.
+    fn lock(&self) -> MutexGuard<'_, HashMap<OrderId, QueuePosition>> {
+        self.orders
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl BookListener for QueuePositionTracker {
+    fn on_trade(&self, _symbol: &str, trade: &TradeResult) {
+        for transaction in trade.match_result.transactions.as_vec() {
+            self.on_trade(
+                transaction.maker_side(),
+                transaction.price,
+                transaction.quantity,
+            );
+            self.on_own_fill(transaction.maker_order_id, transaction.quantity);
+        }
+    }
+
+    fn on_depth_change(&self, _symbol: &str, change: &LevelChange) {
+        self.on_level(change.side, change.price, change.quantity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::OptionStyle;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_track_in_book() {
+        let book = OptionOrderBook::new("BTC-C", OptionStyle::Call);
+        book.add_limit_order(OrderId::new(), Side::Buy, 100, 30)
+            .unwrap();
+        let ours = OrderId::new();
+        book.add_limit_order(ours, Side::Buy, 100, 5).unwrap();
+
+        let tracker = QueuePositionTracker::new();
+        tracker.track_in_book(&book, ours, Side::Buy, 100, 5, 0);
+
+        assert_eq!(tracker.queue_ahead(ours), Some(30));
+    }
+
+    #[test]
+    fn test_trades_consume_queue() {
+        let tracker = QueuePositionTracker::new();
+        let ours = OrderId::new();
+        tracker.track(ours, Side::Sell, 105, 5, 20, 0);
+
+        tracker.on_trade(Side::Sell, 105, 8);
+        assert_eq!(tracker.queue_ahead(ours), Some(12));
+
+        // Other side, or better asks than ours, leave our queue alone.
+        tracker.on_trade(Side::Buy, 105, 100);
+        tracker.on_trade(Side::Sell, 104, 100);
+        assert_eq!(tracker.queue_ahead(ours), Some(12));
+
+        // Trading through our price clears everything ahead of us.
+        tracker.on_trade(Side::Sell, 106, 1);
+        assert_eq!(tracker.queue_ahead(ours), Some(0));
+    }
+
+    #[test]
+    fn test_cancels_are_pro_rata() {
+        let tracker = QueuePositionTracker::new();
+        let ours = OrderId::new();
+        tracker.track(ours, Side::Buy, 100, 5, 30, 0);
+        tracker.on_add(Side::Buy, 100, 10);
+
+        tracker.on_cancel(Side::Buy, 100, 20);
+
+        let position = tracker.position(ours).unwrap();
+        assert_eq!(position.queue_ahead, 15);
+        assert_eq!(position.queue_behind, 5);
+        assert!((position.relative_position() - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_follows_book_events() {
+        let book = OptionOrderBook::new("BTC-C", OptionStyle::Call);
+        let front = OrderId::new();
+        book.add_limit_order(front, Side::Sell, 105, 20).unwrap();
+        let ours = OrderId::new();
+        book.add_limit_order(ours, Side::Sell, 105, 5).unwrap();
+        let tracker = Arc::new(QueuePositionTracker::new());
+        tracker.track_in_book(&book, ours, Side::Sell, 105, 5, 0);
+        book.add_book_listener(Arc::clone(&tracker) as Arc<dyn BookListener>);
+
+        book.add_limit_order(OrderId::new(), Side::Sell, 105, 10)
+            .unwrap();
+        assert_eq!(tracker.position(ours).unwrap().queue_behind, 10);
+
+        book.add_limit_order(OrderId::new(), Side::Buy, 105, 8)
+            .unwrap();
+        assert_eq!(tracker.queue_ahead(ours), Some(12));
+
+        // The rest of the front order leaves; two thirds of it is assumed
+        // to have been ahead of us.
+        assert!(book.cancel_order(front).unwrap());
+        let position = tracker.position(ours).unwrap();
+        assert_eq!(position.queue_ahead + position.queue_behind, 10);
+        assert_eq!(position.level_quantity(), 15);
+
+        book.add_limit_order(OrderId::new(), Side::Buy, 105, 100)
+            .unwrap();
+        let position = tracker.position(ours).unwrap();
+        assert_eq!(position.quantity, 0);
+        assert_eq!(position.fill_probability(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_fill_probability() {
+        let tracker = QueuePositionTracker::new();
+        let ours = OrderId::new();
+        tracker.track(ours, Side::Buy, 100, 10, 10, 0);
+
+        let position = tracker.position(ours).unwrap();
+        assert!((position.first_fill_probability(10.0) - (-1.0f64).exp()).abs() < 1e-12);
+        let full = tracker.fill_probability(ours, 10.0).unwrap();
+        assert!((full - (-2.0f64).exp()).abs() < 1e-12);
+        assert_eq!(position.fill_probability(0.0), 0.0);
+        assert_eq!(tracker.fill_probability(OrderId::new(), 10.0), None);
+    }
+
+    #[test]
+    fn test_own_fill_and_untrack() {
+        let tracker = QueuePositionTracker::new();
+        let ours = OrderId::new();
+        tracker.track(ours, Side::Buy, 100, 5, 30, 0);
+
+        tracker.on_own_fill(ours, 2);
+        assert_eq!(tracker.position(ours).unwrap().quantity, 3);
+        assert_eq!(tracker.queue_ahead(ours), Some(0));
+        assert!(tracker.untrack(ours).is_some());
+        assert!(tracker.is_empty());
+    }
+}
@@ -0,0 +1,226 @@
+//! Contract registry module.
+//!
+//! This module provides [`ContractId`], a compact integer identifier for option
+//! contracts, and the [`ContractRegistry`] that interns contract symbols once at
+//! listing time. Hot paths can then address order books by `ContractId`
+//! instead of hashing and comparing symbol strings on every access.
+
+use super::book::OptionOrderBook;
+use crossbeam_skiplist::SkipMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Compact integer identifier for an option contract.
+///
+/// Identifiers are assigned by a [`ContractRegistry`] the first time a symbol
+/// is interned and are never reused within that registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ContractId(u32);
+
+impl ContractId {
+    /// Creates a contract identifier from its raw value.
+    #[must_use]
+    pub const fn new(raw: u32) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the raw integer value.
+    #[must_use]
+    pub const fn as_u32(&self) -> u32 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for ContractId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Interns contract symbols into [`ContractId`]s and indexes order books by id.
+///
+/// The symbol-to-id mapping is append-only: removing a book releases the book
+/// but keeps the id, so relisting the same symbol yields the same `ContractId`.
+/// Uses `SkipMap` for thread-safe concurrent access.
+pub struct ContractRegistry {
+    /// Contract identifiers indexed by symbol.
+    ids: SkipMap<String, ContractId>,
+    /// Symbols indexed by contract identifier.
+    symbols: SkipMap<ContractId, String>,
+    /// Live order books indexed by contract identifier.
+    books: SkipMap<ContractId, Arc<OptionOrderBook>>,
+    /// Next identifier to assign.
+    next_id: AtomicU32,
+}
+
+impl Default for ContractRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContractRegistry {
+    /// Creates a new, empty contract registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            ids: SkipMap::new(),
+            symbols: SkipMap::new(),
+            books: SkipMap::new(),
+            next_id: AtomicU32::new(0),
+        }
+    }
+
+    /// Returns the number of interned symbols.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns true if no symbols have been interned.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Returns the number of live order books indexed by id.
+    #[must_use]
+    pub fn book_count(&self) -> usize {
+        self.books.len()
+    }
+
+    /// Interns a symbol, returning its existing id or assigning a new one.
+    pub fn intern(&self, symbol: &str) -> ContractId {
+        if let Some(entry) = self.ids.get(symbol) {
+            return *entry.value();
+        }
+        let entry = self.ids.get_or_insert_with(symbol.to_string(), || {
+            ContractId(self.next_id.fetch_add(1, Ordering::Relaxed))
+        });
+        let id = *entry.value();
+        self.symbols.get_or_insert(id, symbol.to_string());
+        id
+    }
+
+    /// Returns the id for a symbol if it has been interned.
+    #[must_use]
+    pub fn id_of(&self, symbol: &str) -> Option<ContractId> {
+        self.ids.get(symbol).map(|e| *e.value())
+    }
+
+    /// Returns the symbol for an id if it exists.
+    #[must_use]
+    pub fn symbol_of(&self, id: ContractId) -> Option<String> {
+        self.symbols.get(&id).map(|e| e.value().clone())
+    }
+
+    /// Indexes an order book under its contract id.
+    ///
+    /// Books without a contract id are ignored.
+    pub fn insert_book(&self, book: Arc<OptionOrderBook>) {
+        if let Some(id) = book.contract_id() {
+            self.books.insert(id, book);
+        }
+    }
+
+    /// Returns the order book for an id if it is live.
+    #[must_use]
+    pub fn book(&self, id: ContractId) -> Option<Arc<OptionOrderBook>> {
+        self.books.get(&id).map(|e| Arc::clone(e.value()))
+    }
+
+    /// Returns every live order book, ordered by contract id.
+    #[must_use]
+    pub fn books(&self) -> Vec<Arc<OptionOrderBook>> {
+        self.books.iter().map(|e| Arc::clone(e.value())).collect()
+    }
+
+    /// Releases the order book indexed under an id.
+    ///
+    /// The symbol stays interned. Returns true if a book was removed.
+    pub fn remove_book(&self, id: ContractId) -> bool {
+        self.books.remove(&id).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::OptionStyle;
+
+    #[test]
+    fn test_intern_assigns_sequential_ids() {
+        let registry = ContractRegistry::new();
+
+        let a = registry.intern("BTC-20240329-50000-C");
+        let b = registry.intern("BTC-20240329-50000-P");
+
+        assert_eq!(a, ContractId::new(0));
+        assert_eq!(b, ContractId::new(1));
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn test_intern_is_idempotent() {
+        let registry = ContractRegistry::new();
+
+        let first = registry.intern("BTC-20240329-50000-C");
+        let second = registry.intern("BTC-20240329-50000-C");
+
+        assert_eq!(first, second);
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn test_symbol_lookup_both_ways() {
+        let registry = ContractRegistry::new();
+        let id = registry.intern("ETH-20240329-3000-P");
+
+        assert_eq!(registry.id_of("ETH-20240329-3000-P"), Some(id));
+        assert_eq!(
+            registry.symbol_of(id).as_deref(),
+            Some("ETH-20240329-3000-P")
+        );
+        assert!(registry.id_of("missing").is_none());
+        assert!(registry.symbol_of(ContractId::new(99)).is_none());
+    }
+
+    #[test]
+    fn test_book_index() {
+        let registry = ContractRegistry::new();
+        let id = registry.intern("BTC-20240329-50000-C");
+        let book = Arc::new(OptionOrderBook::with_contract_id(
+            "BTC-20240329-50000-C",
+            OptionStyle::Call,
+            id,
+        ));
+
+        registry.insert_book(Arc::clone(&book));
+        assert_eq!(registry.book_count(), 1);
+        assert_eq!(registry.book(id).unwrap().symbol(), "BTC-20240329-50000-C");
+        assert_eq!(registry.books().len(), 1);
+
+        assert!(registry.remove_book(id));
+        assert!(registry.book(id).is_none());
+        assert_eq!(registry.id_of("BTC-20240329-50000-C"), Some(id));
+    }
+
+    #[test]
+    fn test_insert_book_without_id_is_ignored() {
+        let registry = ContractRegistry::new();
+        let book = Arc::new(OptionOrderBook::new(
+            "BTC-20240329-50000-C",
+            OptionStyle::Call,
+        ));
+
+        registry.insert_book(book);
+        assert_eq!(registry.book_count(), 0);
+    }
+
+    #[test]
+    fn test_contract_id_display() {
+        assert_eq!(ContractId::new(7).to_string(), "#7");
+        assert_eq!(ContractId::new(7).as_u32(), 7);
+    }
+}
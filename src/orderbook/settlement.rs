@@ -0,0 +1,398 @@
+//! Expiration settlement module.
+//!
+//! This module provides the [`SettlementEngine`], which settles an expiring
+//! option chain against a settlement price of its underlying:
+//!
+//! - every held contract is classified in or out of the money
+//! - out-of-the-money positions expire worthless, realizing their premium
+//! - in-the-money cash-settled positions are closed at intrinsic value
+//! - in-the-money physically settled positions are closed at zero and
+//!   replaced by an underlying position bought or sold at the strike
+//! - the expiration is removed from the chain, releasing its contracts
+//!
+//! Each settled position produces a [`SettlementEvent`] for P&L and
+//! inventory consumers. Prices and strikes share the strike's units.
+
+use super::underlying::{UnderlyingOrderBook, UnderlyingOrderBookManager};
+use crate::error::{Error, Result};
+use crate::inventory::InventoryManager;
+use chrono::{DateTime, Utc};
+use optionstratlib::{ExpirationDate, OptionStyle};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How an in-the-money contract is settled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SettlementStyle {
+    /// Closed at intrinsic value in cash.
+    Cash,
+    /// Exercised or assigned into the underlying at the strike.
+    Physical,
+}
+
+/// Settlement of one held contract at expiry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementEvent {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Settled expiration.
+    pub expiration: ExpirationDate,
+    /// Contract symbol.
+    pub symbol: String,
+    /// Strike price.
+    pub strike: u64,
+    /// Call or put.
+    pub style: OptionStyle,
+    /// Settlement price of the underlying.
+    pub settlement_price: Decimal,
+    /// True if the contract finished in the money.
+    pub in_the_money: bool,
+    /// How the contract was settled.
+    pub settlement: SettlementStyle,
+    /// Signed option position settled.
+    pub quantity: Decimal,
+    /// Intrinsic value of one contract.
+    pub intrinsic: Decimal,
+    /// P&L realized on the option position.
+    pub realized_pnl: Decimal,
+    /// Underlying position delivered, if physically settled in the money.
+    pub delivery: Option<Delivery>,
+}
+
+/// Underlying delivered by an exercise or assignment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Delivery {
+    /// Symbol the underlying position is booked under.
+    pub symbol: String,
+    /// Signed quantity delivered (positive when received).
+    pub quantity: Decimal,
+    /// Delivery price, the strike.
+    pub price: Decimal,
+}
+
+/// Settles expiring option chains into inventory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettlementEngine {
+    /// Settlement style of contracts without an override.
+    default_style: SettlementStyle,
+    /// Settlement style per contract symbol.
+    contract_styles: HashMap<String, SettlementStyle>,
+    /// Symbol deliveries are booked under, per underlying.
+    delivery_symbols: HashMap<String, String>,
+}
+
+impl SettlementEngine {
+    /// Creates an engine settling every contract in one style.
+    ///
+    /// Deliveries are booked under the underlying symbol itself.
+    #[must_use]
+    pub fn new(default_style: SettlementStyle) -> Self {
+        Self {
+            default_style,
+            contract_styles: HashMap::new(),
+            delivery_symbols: HashMap::new(),
+        }
+    }
+
+    /// Overrides the settlement style of a contract.
+    #[must_use]
+    pub fn with_contract_style(
+        mut self,
+        symbol: impl Into<String>,
+        style: SettlementStyle,
+    ) -> Self {
+        self.contract_styles.insert(symbol.into(), style);
+        self
+    }
+
+    /// Sets the symbol deliveries of an underlying are booked under.
+    #[must_use]
+    pub fn with_delivery_symbol(
+        mut self,
+        underlying: impl Into<String>,
+        symbol: impl Into<String>,
+    ) -> Self {
+        self.delivery_symbols
+            .insert(underlying.into(), symbol.into());
+        self
+    }
+
+    /// Returns the settlement style of a contract.
+    #[must_use]
+    pub fn style_of(&self, symbol: &str) -> SettlementStyle {
+        self.contract_styles
+            .get(symbol)
+            .copied()
+            .unwrap_or(self.default_style)
+    }
+
+    /// Returns the symbol deliveries of an underlying are booked under.
+    #[must_use]
+    pub fn delivery_symbol<'a>(&'a self, underlying: &'a str) -> &'a str {
+        self.delivery_symbols
+            .get(underlying)
+            .map_or(underlying, String::as_str)
+    }
+
+    /// Settles one expiration of an underlying and removes it from the
+    /// chain.
+    ///
+    /// Returns one event per non-flat position; contracts without a
+    /// position are removed without an event.
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - Books of the underlying
+    /// * `expiration` - Expiration to settle
+    /// * `settlement_price` - Settlement price of the underlying
+    /// * `inventory` - Positions of the underlying
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the settlement price is negative,
+    /// or `Error::ExpirationNotFound` if the chain has no such expiration.
+    /// Positions already settled stay settled if booking fails part way.
+    pub fn settle_expiration(
+        &self,
+        underlying: &UnderlyingOrderBook,
+        expiration: &ExpirationDate,
+        settlement_price: Decimal,
+        inventory: &InventoryManager,
+    ) -> Result<Vec<SettlementEvent>> {
+        if settlement_price < Decimal::ZERO {
+            return Err(Error::validation(format!(
+                "negative settlement price {settlement_price} for {}",
+                underlying.underlying()
+            )));
+        }
+        let books = underlying.get_expiration(expiration)?;
+        let mut strikes: Vec<_> = books
+            .chain()
+            .strikes()
+            .iter()
+            .map(|e| e.value().clone())
+            .collect();
+        strikes.sort_by_key(|s| s.strike());
+
+        let mut events = Vec::new();
+        for strike_book in strikes {
+            for style in [OptionStyle::Call, OptionStyle::Put] {
+                let symbol = strike_book.get(style).symbol();
+                let Some(position) = inventory.position(symbol) else {
+                    continue;
+                };
+                if position.is_flat() {
+                    continue;
+                }
+                let strike = strike_book.strike();
+                let strike_price = Decimal::from(strike);
+                let intrinsic = match style {
+                    OptionStyle::Call => settlement_price - strike_price,
+                    OptionStyle::Put => strike_price - settlement_price,
+                }
+                .max(Decimal::ZERO);
+                let event = SettlementEvent {
+                    underlying: underlying.underlying().to_string(),
+                    expiration: *expiration,
+                    symbol: symbol.to_string(),
+                    strike,
+                    style,
+                    settlement_price,
+                    in_the_money: intrinsic > Decimal::ZERO,
+                    settlement: self.style_of(symbol),
+                    quantity: position.quantity(),
+                    intrinsic,
+                    realized_pnl: Decimal::ZERO,
+                    delivery: None,
+                };
+                events.push(self.book(event, inventory)?);
+            }
+        }
+        underlying.expirations().remove(expiration);
+        Ok(events)
+    }
+
+    /// Settles every expiration at or before `as_of` of the underlyings with
+    /// a settlement price and an inventory.
+    ///
+    /// # Arguments
+    ///
+    /// * `books` - Order books of every underlying
+    /// * `settlement_prices` - Settlement price per underlying
+    /// * `inventories` - Inventories, matched to underlyings by name
+    /// * `as_of` - Expirations at or before this instant are settled
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if a settlement price is negative,
+    /// or an error if an expiration date cannot be resolved.
+    pub fn settle_expired(
+        &self,
+        books: &UnderlyingOrderBookManager,
+        settlement_prices: &HashMap<String, Decimal>,
+        inventories: &[&InventoryManager],
+        as_of: DateTime<Utc>,
+    ) -> Result<Vec<SettlementEvent>> {
+        let mut events = Vec::new();
+        for (name, price) in settlement_prices {
+            let Ok(underlying) = books.get(name) else {
+                continue;
+            };
+            let Some(inventory) = inventories.iter().find(|i| i.underlying() == name) else {
+                continue;
+            };
+            let mut expiring = Vec::new();
+            for entry in underlying.expirations().iter() {
+                if entry.key().get_date()? <= as_of {
+                    expiring.push(*entry.key());
+                }
+            }
+            for expiration in &expiring {
+                events.extend(self.settle_expiration(
+                    &underlying,
+                    expiration,
+                    *price,
+                    inventory,
+                )?);
+            }
+        }
+        Ok(events)
+    }
+
+    /// Books a settlement into inventory, filling in its realized P&L and
+    /// delivery.
+    fn book(
+        &self,
+        mut event: SettlementEvent,
+        inventory: &InventoryManager,
+    ) -> Result<SettlementEvent> {
+        if event.in_the_money && event.settlement == SettlementStyle::Physical {
+            event.realized_pnl =
+                inventory.record_trade(&event.symbol, -event.quantity, Decimal::ZERO)?;
+            let delivery = Delivery {
+                symbol: self.delivery_symbol(&event.underlying).to_string(),
+                quantity: match event.style {
+                    OptionStyle::Call => event.quantity,
+                    OptionStyle::Put => -event.quantity,
+                },
+                price: Decimal::from(event.strike),
+            };
+            inventory.record_delivery(&delivery.symbol, delivery.quantity, delivery.price)?;
+            event.delivery = Some(delivery);
+        } else {
+            event.realized_pnl =
+                inventory.record_trade(&event.symbol, -event.quantity, event.intrinsic)?;
+        }
+        Ok(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::PositionLimits;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn expiration() -> ExpirationDate {
+        ExpirationDate::DateTime(Utc.with_ymd_and_hms(2030, 6, 21, 0, 0, 0).unwrap())
+    }
+
+    fn setup() -> (UnderlyingOrderBookManager, InventoryManager, [String; 4]) {
+        let books = UnderlyingOrderBookManager::new();
+        let chain = books
+            .get_or_create("SPX")
+            .get_or_create_expiration(expiration());
+        let (k90, k110) = (
+            chain.get_or_create_strike(90),
+            chain.get_or_create_strike(110),
+        );
+        let symbols = [
+            k90.call().symbol().to_string(),
+            k90.put().symbol().to_string(),
+            k110.call().symbol().to_string(),
+            k110.put().symbol().to_string(),
+        ];
+        let inventory = InventoryManager::new("SPX", PositionLimits::default()).unwrap();
+        inventory
+            .record_trade(&symbols[0], dec!(2), dec!(12))
+            .unwrap();
+        inventory
+            .record_trade(&symbols[1], dec!(-3), dec!(1))
+            .unwrap();
+        inventory
+            .record_trade(&symbols[3], dec!(-1), dec!(9))
+            .unwrap();
+        (books, inventory, symbols)
+    }
+
+    #[test]
+    fn test_cash_settlement_and_expiration_removal() {
+        let (books, inventory, symbols) = setup();
+        let engine = SettlementEngine::new(SettlementStyle::Cash);
+        let underlying = books.get("SPX").unwrap();
+        let events = engine
+            .settle_expiration(&underlying, &expiration(), dec!(100), &inventory)
+            .unwrap();
+
+        assert_eq!(events.len(), 3);
+        // Long 90 call: closed at intrinsic 10, bought at 12.
+        assert!(events[0].in_the_money);
+        assert_eq!(events[0].realized_pnl, dec!(-4));
+        // Short 90 put expires worthless, keeping the premium.
+        assert!(!events[1].in_the_money);
+        assert_eq!(events[1].realized_pnl, dec!(3));
+        // Short 110 put: sold at 9, settled at 10.
+        assert_eq!(events[2].symbol, symbols[3]);
+        assert_eq!(events[2].realized_pnl, dec!(-1));
+        assert!(events.iter().all(|e| e.delivery.is_none()));
+        assert!(inventory.positions().iter().all(|p| p.is_flat()));
+        assert!(!underlying.expirations().contains(&expiration()));
+        assert!(
+            engine
+                .settle_expiration(&underlying, &expiration(), dec!(100), &inventory)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_physical_settlement_delivers_underlying() {
+        let (books, inventory, symbols) = setup();
+        let engine = SettlementEngine::new(SettlementStyle::Physical)
+            .with_contract_style(symbols[1].clone(), SettlementStyle::Cash)
+            .with_delivery_symbol("SPX", "SPX-FUT");
+        let as_of = Utc.with_ymd_and_hms(2030, 6, 22, 0, 0, 0).unwrap();
+        let prices = HashMap::from([("SPX".to_string(), dec!(100))]);
+        let events = engine
+            .settle_expired(&books, &prices, &[&inventory], as_of)
+            .unwrap();
+
+        assert_eq!(events[0].settlement, SettlementStyle::Physical);
+        // Long 90 call: premium lost, 2 units bought at 90.
+        assert_eq!(events[0].realized_pnl, dec!(-24));
+        // Short 110 put assigned: 1 unit bought at 110.
+        assert_eq!(events[1].settlement, SettlementStyle::Cash);
+        let hedge = inventory.position("SPX-FUT").unwrap();
+        assert_eq!(hedge.quantity(), dec!(3));
+        assert_eq!(events[2].delivery.as_ref().unwrap().quantity, dec!(1));
+        assert_eq!(inventory.total_greeks().delta, dec!(3));
+
+        // Expirations after `as_of` are left alone.
+        let later = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let (books, inventory, _) = setup();
+        assert!(
+            engine
+                .settle_expired(&books, &prices, &[&inventory], later)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            books
+                .get("SPX")
+                .unwrap()
+                .expirations()
+                .contains(&expiration())
+        );
+    }
+}
@@ -0,0 +1,368 @@
+//! Smile metrics module.
+//!
+//! This module measures the shape of each expiration's volatility smile
+//! from the books, for surface monitoring and as quoting inputs:
+//!
+//! - ATM volatility, at the forward
+//! - 25-delta risk reversal: `vol(25Δ call) - vol(25Δ put)`
+//! - 25-delta butterfly: `(vol(25Δ call) + vol(25Δ put)) / 2 - ATM vol`
+//! - Skew slope: least-squares slope of implied volatility in
+//!   log-moneyness `ln(K / F)`
+//!
+//! Each strike contributes the implied volatility of the mid of its
+//! out-of-the-money option, the call at or above the forward and the put
+//! below it, falling back to the other option when that one has no
+//! two-sided quote. Deltas are taken at each strike's own implied
+//! volatility, and metrics between strikes are interpolated linearly, so
+//! an expiration whose quoted strikes do not bracket the forward and both
+//! 25-delta points has no metrics.
+
+use super::chain::OptionChainOrderBook;
+use super::quote::Quote;
+use super::underlying::UnderlyingOrderBook;
+use crate::error::{Error, Result};
+use crate::pricing::{DAYS_PER_YEAR, FastOption, to_decimal};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+/// Absolute delta of the risk reversal and butterfly wings.
+const WING_DELTA: f64 = 0.25;
+
+/// Settings of the smile measurement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmileMetricsConfig {
+    /// Price of one book tick, in strike units.
+    pub tick_size: Decimal,
+    /// Annualized risk-free rate.
+    pub rate: Decimal,
+    /// Annualized dividend (or borrow) yield of the underlying.
+    pub dividend_yield: Decimal,
+}
+
+impl Default for SmileMetricsConfig {
+    fn default() -> Self {
+        Self {
+            tick_size: Decimal::ONE,
+            rate: Decimal::ZERO,
+            dividend_yield: Decimal::ZERO,
+        }
+    }
+}
+
+impl SmileMetricsConfig {
+    /// Validates the settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the tick size is not positive.
+    pub fn validate(&self) -> Result<()> {
+        if self.tick_size <= Decimal::ZERO {
+            return Err(Error::configuration("smile tick size must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// Shape of one expiration's volatility smile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmileMetrics {
+    /// Years to expiry.
+    pub years_to_expiry: Decimal,
+    /// Forward price the smile is measured against.
+    pub forward: Decimal,
+    /// Implied volatility at the forward.
+    pub atm_vol: Decimal,
+    /// Implied volatility of the 25-delta call.
+    pub call_25d_vol: Decimal,
+    /// Implied volatility of the 25-delta put.
+    pub put_25d_vol: Decimal,
+    /// 25-delta risk reversal.
+    pub risk_reversal_25d: Decimal,
+    /// 25-delta butterfly.
+    pub butterfly_25d: Decimal,
+    /// Slope of implied volatility in log-moneyness.
+    pub skew_slope: Decimal,
+    /// Number of strikes with an implied volatility.
+    pub strikes: usize,
+}
+
+/// Implied volatility of one strike.
+#[derive(Debug, Clone, Copy)]
+struct SmilePoint {
+    /// Log-moneyness `ln(K / F)`.
+    log_moneyness: f64,
+    /// Implied volatility.
+    iv: f64,
+    /// Call delta at the implied volatility.
+    call_delta: f64,
+}
+
+/// Measures the volatility smile of one chain.
+///
+/// # Errors
+///
+/// Returns `Error::ValidationError` if spot is not positive,
+/// `Error::ConfigurationError` if the settings are invalid, or
+/// `Error::NoDataAvailable` if the chain has expired or its quotes do not
+/// bracket the forward and both 25-delta points.
+pub fn smile_metrics(
+    chain: &OptionChainOrderBook,
+    spot: Decimal,
+    config: &SmileMetricsConfig,
+) -> Result<SmileMetrics> {
+    if spot <= Decimal::ZERO {
+        return Err(Error::validation("spot must be positive"));
+    }
+    config.validate()?;
+    measure(chain, spot, config)
+}
+
+/// Measures the volatility smile of every expiration of an underlying.
+///
+/// Expirations without metrics are left out; the rest are returned by
+/// increasing expiry.
+///
+/// # Errors
+///
+/// Returns `Error::ValidationError` if spot is not positive, or
+/// `Error::ConfigurationError` if the settings are invalid.
+pub fn underlying_smile_metrics(
+    underlying: &UnderlyingOrderBook,
+    spot: Decimal,
+    config: &SmileMetricsConfig,
+) -> Result<Vec<SmileMetrics>> {
+    if spot <= Decimal::ZERO {
+        return Err(Error::validation("spot must be positive"));
+    }
+    config.validate()?;
+
+    let mut metrics: Vec<SmileMetrics> = underlying
+        .expirations()
+        .iter()
+        .filter_map(|expiration| measure(expiration.value().chain(), spot, config).ok())
+        .collect();
+    metrics.sort_by_key(|m| m.years_to_expiry);
+    Ok(metrics)
+}
+
+/// Measures a chain with validated inputs.
+fn measure(
+    chain: &OptionChainOrderBook,
+    spot: Decimal,
+    config: &SmileMetricsConfig,
+) -> Result<SmileMetrics> {
+    let years = chain.tau().or_else(|| {
+        chain
+            .expiration()
+            .get_days()
+            .ok()
+            .map(|d| d.to_dec() / DAYS_PER_YEAR)
+    });
+    let Some(years) = years.filter(|t| *t > Decimal::ZERO) else {
+        return Err(Error::no_data("expiration has no time left"));
+    };
+    let to_f64 = |value: Decimal| {
+        value
+            .to_f64()
+            .ok_or_else(|| Error::pricing(format!("{value} does not fit in f64")))
+    };
+    let template = FastOption {
+        spot: to_f64(spot)?,
+        strike: 0.0,
+        tau: to_f64(years)?,
+        volatility: 0.0,
+        rate: to_f64(config.rate)?,
+        dividend_yield: to_f64(config.dividend_yield)?,
+        is_call: true,
+    };
+    let forward = template.spot * ((template.rate - template.dividend_yield) * template.tau).exp();
+    let carry = (-template.dividend_yield * template.tau).exp();
+    let tick_size = to_f64(config.tick_size)?;
+
+    let points: Vec<SmilePoint> = chain
+        .strikes()
+        .iter()
+        .filter_map(|entry| {
+            let strike = *entry.key() as f64;
+            let book = entry.value();
+            let (call, put) = (book.call_quote(), book.put_quote());
+            let mid = |quote: Quote| {
+                let (bid, ask) = quote.bid_price().zip(quote.ask_price())?;
+                Some((bid as f64 + ask as f64) / 2.0 * tick_size)
+            };
+            let quotes = if strike >= forward {
+                [(true, mid(call)), (false, mid(put))]
+            } else {
+                [(false, mid(put)), (true, mid(call))]
+            };
+            let iv = quotes.into_iter().find_map(|(is_call, price)| {
+                FastOption {
+                    strike,
+                    is_call,
+                    ..template
+                }
+                .implied_volatility(price?)
+            })?;
+            let (_, call_delta) = FastOption {
+                strike,
+                volatility: iv,
+                ..template
+            }
+            .price_and_delta();
+            Some(SmilePoint {
+                log_moneyness: (strike / forward).ln(),
+                iv,
+                call_delta,
+            })
+        })
+        .collect();
+
+    let by_moneyness: Vec<(f64, f64)> = points.iter().map(|p| (p.log_moneyness, p.iv)).collect();
+    let by_delta: Vec<(f64, f64)> = points.iter().map(|p| (p.call_delta, p.iv)).collect();
+    let missing = || Error::no_data("quotes do not bracket the ATM and 25-delta strikes");
+    let atm_vol = interpolate(&by_moneyness, 0.0).ok_or_else(missing)?;
+    let call_vol = interpolate(&by_delta, WING_DELTA).ok_or_else(missing)?;
+    let put_vol = interpolate(&by_delta, carry - WING_DELTA).ok_or_else(missing)?;
+    let slope = slope(&by_moneyness).ok_or_else(missing)?;
+
+    let decimal =
+        |value: f64| to_decimal(value).ok_or_else(|| Error::pricing("smile metric is not finite"));
+    Ok(SmileMetrics {
+        years_to_expiry: years,
+        forward: decimal(forward)?,
+        atm_vol: decimal(atm_vol)?,
+        call_25d_vol: decimal(call_vol)?,
+        put_25d_vol: decimal(put_vol)?,
+        risk_reversal_25d: decimal(call_vol - put_vol)?,
+        butterfly_25d: decimal((call_vol + put_vol) / 2.0 - atm_vol)?,
+        skew_slope: decimal(slope)?,
+        strikes: points.len(),
+    })
+}
+
+/// Interpolates `(key, value)` pairs linearly at `at`, using the first
+/// pair of neighbours whose keys bracket it.
+fn interpolate(points: &[(f64, f64)], at: f64) -> Option<f64> {
+    points.windows(2).find_map(|pair| {
+        let ((k0, v0), (k1, v1)) = (pair[0], pair[1]);
+        let (low, high) = if k0 <= k1 { (k0, k1) } else { (k1, k0) };
+        if at < low || at > high {
+            return None;
+        }
+        if k0 == k1 {
+            return Some((v0 + v1) / 2.0);
+        }
+        Some(v0 + (v1 - v0) * (at - k0) / (k1 - k0))
+    })
+}
+
+/// Returns the least-squares slope of `(x, y)` pairs.
+fn slope(points: &[(f64, f64)]) -> Option<f64> {
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let (cov, var) = points.iter().fold((0.0, 0.0), |(cov, var), (x, y)| {
+        (
+            cov + (x - mean_x) * (y - mean_y),
+            var + (x - mean_x) * (x - mean_x),
+        )
+    });
+    (var > 0.0).then(|| cov / var)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::ExpirationDate;
+    use optionstratlib::prelude::pos_or_panic;
+    use orderbook_rs::{OrderId, Side};
+    use rust_decimal_macros::dec;
+
+    /// Quotes a chain one tick either side of the prices of a smile with
+    /// negative skew and positive curvature, at zero rates.
+    fn chain() -> OptionChainOrderBook {
+        let chain = OptionChainOrderBook::new("SPX", ExpirationDate::Days(pos_or_panic!(30.0)));
+        for strike in (60..=140).step_by(5) {
+            let book = chain.get_or_create_strike(strike);
+            let x = (strike as f64 / 100.0).ln();
+            let volatility = 0.5 - 0.2 * x + 0.5 * x * x;
+            for (is_call, side_book) in [(true, book.call()), (false, book.put())] {
+                let option = FastOption {
+                    spot: 100.0,
+                    strike: strike as f64,
+                    tau: 30.0 / 365.0,
+                    volatility,
+                    rate: 0.0,
+                    dividend_yield: 0.0,
+                    is_call,
+                };
+                let ticks = (option.price() * 10_000.0).round() as u128;
+                side_book
+                    .add_limit_order(OrderId::new(), Side::Buy, ticks - 1, 1)
+                    .unwrap();
+                side_book
+                    .add_limit_order(OrderId::new(), Side::Sell, ticks + 1, 1)
+                    .unwrap();
+            }
+        }
+        chain
+    }
+
+    fn config() -> SmileMetricsConfig {
+        SmileMetricsConfig {
+            tick_size: dec!(0.0001),
+            ..SmileMetricsConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_metrics_recover_smile_shape() {
+        let metrics = smile_metrics(&chain(), dec!(100), &config()).unwrap();
+        assert_eq!(metrics.strikes, 17);
+        assert_eq!(metrics.forward, dec!(100));
+        assert!((metrics.atm_vol - dec!(0.5)).abs() < dec!(0.001));
+        // Negative skew: the put wing is richer than the call wing.
+        assert!(metrics.risk_reversal_25d < Decimal::ZERO);
+        assert!(metrics.put_25d_vol > metrics.atm_vol);
+        assert!(metrics.butterfly_25d > Decimal::ZERO);
+        // The strikes reach further into the puts in log-moneyness, so the
+        // curvature steepens the fitted slope beyond the smile's own skew.
+        let generated: Vec<(f64, f64)> = (60..=140)
+            .step_by(5)
+            .map(|k| {
+                let x = (k as f64 / 100.0).ln();
+                (x, 0.5 - 0.2 * x + 0.5 * x * x)
+            })
+            .collect();
+        let expected = to_decimal(slope(&generated).unwrap()).unwrap();
+        assert!(expected < dec!(-0.2));
+        assert!((metrics.skew_slope - expected).abs() < dec!(0.001));
+    }
+
+    #[test]
+    fn test_missing_wings_and_invalid_inputs() {
+        let chain = OptionChainOrderBook::new("SPX", ExpirationDate::Days(pos_or_panic!(30.0)));
+        let book = chain.get_or_create_strike(100);
+        book.call()
+            .add_limit_order(OrderId::new(), Side::Buy, 500, 1)
+            .unwrap();
+        book.call()
+            .add_limit_order(OrderId::new(), Side::Sell, 520, 1)
+            .unwrap();
+        assert!(matches!(
+            smile_metrics(&chain, dec!(100), &config()),
+            Err(Error::NoDataAvailable { .. })
+        ));
+
+        assert!(smile_metrics(&chain, Decimal::ZERO, &config()).is_err());
+        let bad = SmileMetricsConfig {
+            tick_size: Decimal::ZERO,
+            ..config()
+        };
+        assert!(smile_metrics(&chain, dec!(100), &bad).is_err());
+
+        assert_eq!(interpolate(&[(0.8, 1.0), (0.2, 2.0)], 0.5), Some(1.5));
+        assert_eq!(slope(&[(1.0, 1.0)]), None);
+    }
+}
@@ -0,0 +1,158 @@
+//! Self-trade prevention module.
+//!
+//! This module provides the [`SelfTradePrevention`] policy of an
+//! [`super::OptionOrderBook`]. Orders added with a participant tag, e.g.
+//! `"mm"` for both our quoter and our hedger, are remembered while they
+//! rest; a new order of the same participant whose limit reaches one of
+//! them would trade against ourselves, and the policy decides which side
+//! is cancelled instead.
+//!
+//! The check is on price: every resting order of the participant on the
+//! other side at or through the new order's limit counts, whether or not
+//! better-priced liquidity would have filled the new order first. Orders
+//! added without a tag are never checked. A tag is forgotten once its order
+//! is filled or cancelled, whether or not a policy is set.
+
+use orderbook_rs::{OrderId, Side};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError, RwLock};
+
+/// Which order is cancelled when a participant would trade with itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SelfTradePrevention {
+    /// The new order is rejected; resting orders stay.
+    CancelNewest,
+    /// The crossed resting orders are cancelled; the new order is added.
+    CancelOldest,
+    /// Both the crossed resting orders and the new order are cancelled.
+    CancelBoth,
+}
+
+impl SelfTradePrevention {
+    /// Returns true if the crossed resting orders are cancelled.
+    #[must_use]
+    pub const fn cancels_resting(&self) -> bool {
+        matches!(self, Self::CancelOldest | Self::CancelBoth)
+    }
+
+    /// Returns true if the new order is rejected.
+    #[must_use]
+    pub const fn cancels_incoming(&self) -> bool {
+        matches!(self, Self::CancelNewest | Self::CancelBoth)
+    }
+}
+
+impl std::fmt::Display for SelfTradePrevention {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CancelNewest => write!(f, "cancel-newest"),
+            Self::CancelOldest => write!(f, "cancel-oldest"),
+            Self::CancelBoth => write!(f, "cancel-both"),
+        }
+    }
+}
+
+/// A resting order added with a participant tag.
+#[derive(Debug, Clone)]
+struct OwnedOrder {
+    /// Participant the order belongs to.
+    participant: String,
+    /// Side of the order.
+    side: Side,
+    /// Limit price in ticks.
+    price: u128,
+}
+
+/// Participant tags and policy of one book.
+#[derive(Debug, Default)]
+pub(crate) struct SelfTradeGuard {
+    /// Policy, if prevention is enabled.
+    policy: RwLock<Option<SelfTradePrevention>>,
+    /// Tagged resting orders by id; entries are removed when the order is
+    /// filled away or cancelled.
+    owners: Mutex<HashMap<OrderId, OwnedOrder>>,
+    /// New orders that triggered the policy.
+    prevented: AtomicU64,
+}
+
+impl SelfTradeGuard {
+    pub(crate) fn policy(&self) -> Option<SelfTradePrevention> {
+        *self.policy.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn set_policy(&self, policy: Option<SelfTradePrevention>) {
+        *self.policy.write().unwrap_or_else(PoisonError::into_inner) = policy;
+    }
+
+    pub(crate) fn prevented(&self) -> u64 {
+        self.prevented.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_prevented(&self) {
+        self.prevented.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the participant's resting orders a new order would cross,
+    /// dropping the entries `is_live` rejects.
+    pub(crate) fn crossed(
+        &self,
+        participant: &str,
+        side: Side,
+        price: u128,
+        is_live: impl Fn(OrderId) -> bool,
+    ) -> Vec<OrderId> {
+        let mut owners = self.lock();
+        owners.retain(|id, _| is_live(*id));
+        owners
+            .iter()
+            .filter(|(_, order)| {
+                order.participant == participant
+                    && order.side != side
+                    && match side {
+                        Side::Buy => order.price <= price,
+                        Side::Sell => order.price >= price,
+                    }
+            })
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    pub(crate) fn insert(&self, order_id: OrderId, participant: &str, side: Side, price: u128) {
+        self.lock().insert(
+            order_id,
+            OwnedOrder {
+                participant: participant.to_string(),
+                side,
+                price,
+            },
+        );
+    }
+
+    pub(crate) fn remove(&self, order_id: OrderId) {
+        self.lock().remove(&order_id);
+    }
+
+    pub(crate) fn participant(&self, order_id: OrderId) -> Option<String> {
+        self.lock()
+            .get(&order_id)
+            .map(|order| order.participant.clone())
+    }
+
+    pub(crate) fn orders_of(&self, participant: &str) -> Vec<OrderId> {
+        self.lock()
+            .iter()
+            .filter(|(_, order)| order.participant == participant)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    pub(crate) fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<OrderId, OwnedOrder>> {
+        self.owners.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
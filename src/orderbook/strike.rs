@@ -1,10 +1,16 @@
 //! Strike order book module.
 //!
 //! This module provides the [`StrikeOrderBook`] and [`StrikeOrderBookManager`]
-//! for managing call/put pairs at a specific strike price.
+//! for managing call/put pairs at a specific strike price. The manager
+//! keeps far out-of-the-money and long-dated strikes as placeholders until
+//! their books are needed (see [`super::TieringPolicy`]).
 
 use super::book::OptionOrderBook;
 use super::quote::Quote;
+use super::registry::{ContractId, ContractRegistry};
+use super::tiering::{BookTier, StrikePlaceholder, TierStats, TieringPolicy};
+use super::timers::{ScheduledCancel, TimerDefaults};
+use crate::clock::Clock;
 use crate::error::{Error, Result};
 use crate::utils::format_expiration_yyyymmdd;
 use crossbeam_skiplist::SkipMap;
@@ -12,6 +18,7 @@ use optionstratlib::greeks::Greek;
 use optionstratlib::{ExpirationDate, OptionStyle};
 use orderbook_rs::OrderId;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Order book for a single strike price containing both call and put.
 ///
@@ -56,13 +63,7 @@ impl StrikeOrderBook {
     #[must_use]
     pub fn new(underlying: impl Into<String>, expiration: ExpirationDate, strike: u64) -> Self {
         let underlying = underlying.into();
-
-        // Format expiration as YYYYMMDD, fallback to Display if formatting fails
-        let exp_str =
-            format_expiration_yyyymmdd(&expiration).unwrap_or_else(|_| expiration.to_string());
-
-        let call_symbol = format!("{}-{}-{}-C", underlying, exp_str, strike);
-        let put_symbol = format!("{}-{}-{}-P", underlying, exp_str, strike);
+        let (call_symbol, put_symbol) = Self::symbols(&underlying, &expiration, strike);
 
         Self {
             underlying,
@@ -76,6 +77,66 @@ impl StrikeOrderBook {
         }
     }
 
+    /// Creates a new strike order book whose call and put are interned in a registry.
+    ///
+    /// Both books receive a `ContractId` and are indexed by the registry so
+    /// they can be looked up without going through the hierarchy.
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - The underlying asset symbol (e.g., "BTC")
+    /// * `expiration` - The expiration date
+    /// * `strike` - The strike price
+    /// * `registry` - The registry used to intern the contract symbols
+    #[must_use]
+    pub fn with_registry(
+        underlying: impl Into<String>,
+        expiration: ExpirationDate,
+        strike: u64,
+        registry: &ContractRegistry,
+    ) -> Self {
+        let underlying = underlying.into();
+        let (call_symbol, put_symbol) = Self::symbols(&underlying, &expiration, strike);
+        let call_id = registry.intern(&call_symbol);
+        let put_id = registry.intern(&put_symbol);
+
+        let call = Arc::new(OptionOrderBook::with_contract_id(
+            call_symbol,
+            OptionStyle::Call,
+            call_id,
+        ));
+        let put = Arc::new(OptionOrderBook::with_contract_id(
+            put_symbol,
+            OptionStyle::Put,
+            put_id,
+        ));
+        registry.insert_book(Arc::clone(&call));
+        registry.insert_book(Arc::clone(&put));
+
+        Self {
+            underlying,
+            expiration,
+            strike,
+            call,
+            put,
+            call_greeks: None,
+            put_greeks: None,
+            id: OrderId::new(),
+        }
+    }
+
+    /// Builds the call and put contract symbols for a strike.
+    fn symbols(underlying: &str, expiration: &ExpirationDate, strike: u64) -> (String, String) {
+        // Format expiration as YYYYMMDD, fallback to Display if formatting fails
+        let exp_str =
+            format_expiration_yyyymmdd(expiration).unwrap_or_else(|_| expiration.to_string());
+
+        (
+            format!("{}-{}-{}-C", underlying, exp_str, strike),
+            format!("{}-{}-{}-P", underlying, exp_str, strike),
+        )
+    }
+
     /// Returns the underlying asset symbol.
     #[must_use]
     pub fn underlying(&self) -> &str {
@@ -142,6 +203,12 @@ impl StrikeOrderBook {
         }
     }
 
+    /// Returns the contract ids of the call and put, if they were registered.
+    #[must_use]
+    pub fn contract_ids(&self) -> (Option<ContractId>, Option<ContractId>) {
+        (self.call.contract_id(), self.put.contract_id())
+    }
+
     /// Returns the best quote for the call option.
     #[must_use]
     pub fn call_quote(&self) -> Quote {
@@ -154,6 +221,12 @@ impl StrikeOrderBook {
         self.put.best_quote()
     }
 
+    /// Calls `f` with the call book, then the put book.
+    pub fn for_each_book<F: FnMut(&OptionOrderBook)>(&self, mut f: F) {
+        f(&self.call);
+        f(&self.put);
+    }
+
     /// Returns true if both call and put have two-sided quotes.
     #[must_use]
     pub fn is_fully_quoted(&self) -> bool {
@@ -212,6 +285,16 @@ pub struct StrikeOrderBookManager {
     underlying: String,
     /// The expiration date.
     expiration: ExpirationDate,
+    /// Registry interning the contracts created by this manager.
+    registry: Arc<ContractRegistry>,
+    /// Strikes listed without materialized books.
+    placeholders: SkipMap<u64, StrikePlaceholder>,
+    /// Placeholders materialized since creation.
+    materializations: AtomicU64,
+    /// Books demoted to placeholders since creation.
+    demotions: AtomicU64,
+    /// Clock and mass cancels applied to strikes listed later.
+    timers: TimerDefaults,
 }
 
 impl StrikeOrderBookManager {
@@ -223,13 +306,40 @@ impl StrikeOrderBookManager {
     /// * `expiration` - The expiration date
     #[must_use]
     pub fn new(underlying: impl Into<String>, expiration: ExpirationDate) -> Self {
+        Self::with_registry(underlying, expiration, Arc::new(ContractRegistry::new()))
+    }
+
+    /// Creates a new strike order book manager sharing a contract registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - The underlying asset symbol
+    /// * `expiration` - The expiration date
+    /// * `registry` - The registry used to intern contract symbols
+    #[must_use]
+    pub fn with_registry(
+        underlying: impl Into<String>,
+        expiration: ExpirationDate,
+        registry: Arc<ContractRegistry>,
+    ) -> Self {
         Self {
             strikes: SkipMap::new(),
             underlying: underlying.into(),
             expiration,
+            registry,
+            placeholders: SkipMap::new(),
+            materializations: AtomicU64::new(0),
+            demotions: AtomicU64::new(0),
+            timers: TimerDefaults::default(),
         }
     }
 
+    /// Returns a reference to the contract registry.
+    #[must_use]
+    pub fn registry(&self) -> &ContractRegistry {
+        &self.registry
+    }
+
     /// Returns the underlying asset symbol.
     #[must_use]
     pub fn underlying(&self) -> &str {
@@ -242,7 +352,7 @@ impl StrikeOrderBookManager {
         &self.expiration
     }
 
-    /// Returns the number of strikes.
+    /// Returns the number of strikes with materialized books.
     #[must_use]
     pub fn len(&self) -> usize {
         self.strikes.len()
@@ -255,19 +365,176 @@ impl StrikeOrderBookManager {
     }
 
     /// Gets or creates a strike order book, returning an Arc reference.
+    ///
+    /// A placeholder for the strike is materialized into real books; this
+    /// is the path every order and quote takes, so placeholders come alive
+    /// on first use.
     pub fn get_or_create(&self, strike: u64) -> Arc<StrikeOrderBook> {
         if let Some(entry) = self.strikes.get(&strike) {
             return Arc::clone(entry.value());
         }
-        let book = Arc::new(StrikeOrderBook::new(
+        let book = Arc::new(StrikeOrderBook::with_registry(
             &self.underlying,
             self.expiration,
             strike,
+            &self.registry,
         ));
+        self.timers.apply(
+            |clock| book.for_each_book(|b| b.set_clock(Arc::clone(&clock))),
+            |cancel| book.for_each_book(|b| b.schedule_mass_cancel(cancel.clone())),
+        );
         self.strikes.insert(strike, Arc::clone(&book));
+        if self.placeholders.remove(&strike).is_some() {
+            self.materializations.fetch_add(1, Ordering::Relaxed);
+        }
         book
     }
 
+    /// Lists a strike, materializing its books if the policy deems it warm
+    /// and keeping a placeholder otherwise.
+    ///
+    /// Strikes that are already listed keep their tier.
+    ///
+    /// # Arguments
+    ///
+    /// * `strike` - The strike price
+    /// * `spot` - Reference spot price in the same units as strikes
+    /// * `policy` - Warm/cold tiering rule
+    pub fn list(&self, strike: u64, spot: u64, policy: &TieringPolicy) -> BookTier {
+        if let Some(tier) = self.tier(strike) {
+            return tier;
+        }
+        if policy.is_warm(strike, spot, &self.expiration) {
+            self.get_or_create(strike);
+            BookTier::Materialized
+        } else {
+            self.add_placeholder(strike, None, None);
+            BookTier::Placeholder
+        }
+    }
+
+    /// Lists a strike as a placeholder with indicative marks.
+    ///
+    /// Returns false, and does nothing, if the strike's books are already
+    /// materialized. An existing placeholder has its marks replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `strike` - The strike price
+    /// * `call_mark` - Indicative call mark, in book price units
+    /// * `put_mark` - Indicative put mark, in book price units
+    pub fn add_placeholder(
+        &self,
+        strike: u64,
+        call_mark: Option<u128>,
+        put_mark: Option<u128>,
+    ) -> bool {
+        if self.strikes.contains_key(&strike) {
+            return false;
+        }
+        let (call_symbol, put_symbol) =
+            StrikeOrderBook::symbols(&self.underlying, &self.expiration, strike);
+        self.placeholders.insert(
+            strike,
+            StrikePlaceholder {
+                strike,
+                call_symbol,
+                put_symbol,
+                call_mark,
+                put_mark,
+            },
+        );
+        true
+    }
+
+    /// Updates the indicative mark of a placeholder.
+    ///
+    /// Returns false if the strike has no placeholder.
+    pub fn update_mark(&self, strike: u64, option_style: OptionStyle, mark: Option<u128>) -> bool {
+        let Some(entry) = self.placeholders.get(&strike) else {
+            return false;
+        };
+        let mut placeholder = entry.value().clone();
+        match option_style {
+            OptionStyle::Call => placeholder.call_mark = mark,
+            OptionStyle::Put => placeholder.put_mark = mark,
+        }
+        self.placeholders.insert(strike, placeholder);
+        true
+    }
+
+    /// Returns the placeholder of a strike, if it is not materialized.
+    #[must_use]
+    pub fn placeholder(&self, strike: u64) -> Option<StrikePlaceholder> {
+        self.placeholders.get(&strike).map(|e| e.value().clone())
+    }
+
+    /// Returns the tier of a strike, or `None` if it is not listed.
+    #[must_use]
+    pub fn tier(&self, strike: u64) -> Option<BookTier> {
+        if self.strikes.contains_key(&strike) {
+            Some(BookTier::Materialized)
+        } else if self.placeholders.contains_key(&strike) {
+            Some(BookTier::Placeholder)
+        } else {
+            None
+        }
+    }
+
+    /// Demotes a strike with no resting orders back to a placeholder,
+    /// releasing its books.
+    ///
+    /// Returns false if the strike is not materialized or has orders.
+    ///
+    /// # Arguments
+    ///
+    /// * `strike` - The strike price
+    /// * `call_mark` - Indicative call mark kept by the placeholder
+    /// * `put_mark` - Indicative put mark kept by the placeholder
+    pub fn demote(&self, strike: u64, call_mark: Option<u128>, put_mark: Option<u128>) -> bool {
+        match self.strikes.get(&strike) {
+            Some(entry) if entry.value().is_empty() => {}
+            _ => return false,
+        }
+        if !self.remove(strike) {
+            return false;
+        }
+        self.add_placeholder(strike, call_mark, put_mark);
+        self.demotions.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    /// Returns the number of placeholder strikes.
+    #[must_use]
+    pub fn placeholder_count(&self) -> usize {
+        self.placeholders.len()
+    }
+
+    /// Returns every listed strike price, materialized or not (sorted).
+    #[must_use]
+    pub fn listed_strikes(&self) -> Vec<u64> {
+        let mut strikes: Vec<u64> = self
+            .strikes
+            .iter()
+            .map(|e| *e.key())
+            .chain(self.placeholders.iter().map(|e| *e.key()))
+            .collect();
+        strikes.sort_unstable();
+        strikes.dedup();
+        strikes
+    }
+
+    /// Returns materialized versus placeholder counts.
+    #[must_use]
+    pub fn tier_stats(&self) -> TierStats {
+        TierStats {
+            materialized: self.strikes.len(),
+            placeholders: self.placeholders.len(),
+            materializations: self.materializations.load(Ordering::Relaxed),
+            demotions: self.demotions.load(Ordering::Relaxed),
+        }
+    }
+
     /// Gets a strike order book by strike price.
     ///
     /// # Errors
@@ -296,8 +563,32 @@ impl StrikeOrderBookManager {
     /// Removes a strike order book.
     ///
     /// Note: Returns true if the strike was removed, false if it didn't exist.
+    /// The call and put books are released from the contract registry, and
+    /// a placeholder for the strike is dropped.
     pub fn remove(&self, strike: u64) -> bool {
-        self.strikes.remove(&strike).is_some()
+        let placeholder = self.placeholders.remove(&strike).is_some();
+        match self.strikes.remove(&strike) {
+            Some(entry) => {
+                self.release_contracts(entry.value());
+                true
+            }
+            None => placeholder,
+        }
+    }
+
+    /// Releases every strike's books from the contract registry.
+    pub(crate) fn release_all_contracts(&self) {
+        for entry in self.strikes.iter() {
+            self.release_contracts(entry.value());
+        }
+    }
+
+    /// Releases a strike's call and put books from the contract registry.
+    fn release_contracts(&self, strike: &StrikeOrderBook) {
+        let (call_id, put_id) = strike.contract_ids();
+        for id in [call_id, put_id].into_iter().flatten() {
+            self.registry.remove_book(id);
+        }
     }
 
     /// Returns all strike prices (sorted).
@@ -312,6 +603,32 @@ impl StrikeOrderBookManager {
         self.strikes.iter().map(|e| e.value().order_count()).sum()
     }
 
+    /// Calls `f` with every option book, by ascending strike, call first.
+    pub fn for_each_book<F: FnMut(&OptionOrderBook)>(&self, mut f: F) {
+        for entry in self.strikes.iter() {
+            entry.value().for_each_book(&mut f);
+        }
+    }
+
+    /// Sets the clock of every book, including strikes listed later.
+    pub fn set_clock(&self, clock: Arc<dyn Clock>) {
+        self.timers.set_clock(Arc::clone(&clock));
+        self.for_each_book(|book| book.set_clock(Arc::clone(&clock)));
+    }
+
+    /// Schedules a mass cancel in every book, including strikes listed
+    /// before it falls due.
+    pub fn schedule_mass_cancel(&self, cancel: &ScheduledCancel) {
+        self.timers.schedule(cancel);
+        self.for_each_book(|book| book.schedule_mass_cancel(cancel.clone()));
+    }
+
+    /// Calls `f` with every option book and its best quote, without
+    /// collecting them first.
+    pub fn for_each_quote<F: FnMut(&OptionOrderBook, Quote)>(&self, mut f: F) {
+        self.for_each_book(|book| f(book, book.best_quote()));
+    }
+
     /// Returns the ATM (at-the-money) strike closest to the given spot price.
     ///
     /// # Errors
@@ -625,6 +942,39 @@ mod tests {
         assert!(!manager.remove(50000));
     }
 
+    #[test]
+    fn test_strike_with_registry_interns_contracts() {
+        let registry = ContractRegistry::new();
+        let strike = StrikeOrderBook::with_registry("BTC", test_expiration(), 50000, &registry);
+
+        let (call_id, put_id) = strike.contract_ids();
+        let call_id = call_id.unwrap();
+        let put_id = put_id.unwrap();
+
+        assert_ne!(call_id, put_id);
+        assert_eq!(registry.id_of(strike.call().symbol()), Some(call_id));
+        assert_eq!(
+            registry.book(put_id).unwrap().symbol(),
+            strike.put().symbol()
+        );
+    }
+
+    #[test]
+    fn test_strike_manager_remove_releases_contracts() {
+        let manager = StrikeOrderBookManager::new("BTC", test_expiration());
+
+        let (call_id, _) = manager.get_or_create(50000).contract_ids();
+        let call_id = call_id.unwrap();
+        assert!(manager.registry().book(call_id).is_some());
+
+        assert!(manager.remove(50000));
+        assert!(manager.registry().book(call_id).is_none());
+
+        // Relisting the same strike reuses the interned id
+        let (relisted, _) = manager.get_or_create(50000).contract_ids();
+        assert_eq!(relisted, Some(call_id));
+    }
+
     #[test]
     fn test_strike_manager_total_order_count() {
         let manager = StrikeOrderBookManager::new("BTC", test_expiration());
@@ -645,4 +995,37 @@ mod tests {
 
         assert_eq!(manager.total_order_count(), 2);
     }
+
+    #[test]
+    fn test_strike_manager_lazy_materialization() {
+        let manager = StrikeOrderBookManager::new("BTC", test_expiration());
+        let policy = TieringPolicy::default();
+
+        assert_eq!(manager.list(50000, 50000, &policy), BookTier::Materialized);
+        assert_eq!(manager.list(90000, 50000, &policy), BookTier::Placeholder);
+        assert!(!manager.contains(90000));
+        assert!(manager.update_mark(90000, OptionStyle::Call, Some(5)));
+        let placeholder = manager.placeholder(90000).unwrap();
+        assert!(placeholder.call_symbol.ends_with("-90000-C"));
+        assert_eq!(placeholder.call_mark, Some(5));
+        assert_eq!(manager.listed_strikes(), vec![50000, 90000]);
+
+        // First order on the far strike materializes its books.
+        manager
+            .get_or_create(90000)
+            .call()
+            .add_limit_order(OrderId::new(), Side::Sell, 6, 1)
+            .unwrap();
+        assert_eq!(manager.tier(90000), Some(BookTier::Materialized));
+        assert!(manager.placeholder(90000).is_none());
+        assert!(!manager.demote(90000, Some(5), None));
+
+        assert!(manager.demote(50000, Some(1200), Some(1100)));
+        assert_eq!(manager.tier(50000), Some(BookTier::Placeholder));
+        let stats = manager.tier_stats();
+        assert_eq!(stats.materialized, 1);
+        assert_eq!(stats.placeholders, 1);
+        assert_eq!(stats.materializations, 1);
+        assert_eq!(stats.demotions, 1);
+    }
 }
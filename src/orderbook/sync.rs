@@ -0,0 +1,358 @@
+//! Chain-to-order-book synchronization module.
+//!
+//! This module provides the [`ChainBookSynchronizer`], which keeps the books
+//! of an [`UnderlyingOrderBookManager`] in step with the set of listed
+//! contracts. Listing a [`ListedContract`] creates its call and put books;
+//! delisting it, or its expiration passing, cancels their resting orders,
+//! archives their final state as an [`ArchivedContract`] and removes them
+//! from the hierarchy, releasing their contract ids.
+//!
+//! ## Consistency
+//!
+//! [`ChainBookSynchronizer::check`] compares the listed contracts with the
+//! books in the hierarchy: a listed contract without books is missing, and
+//! books of a contract that is not listed, e.g. created directly through the
+//! manager, are orphaned. [`ChainBookSynchronizer::repair`] creates the
+//! missing books; orphaned books are left to the caller to list or delist.
+
+use super::strike::StrikeOrderBook;
+use super::timers::MassCancelScope;
+use super::underlying::UnderlyingOrderBookManager;
+use crate::clock::{Clock, SystemClock};
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use optionstratlib::ExpirationDate;
+use orderbook_rs::OrderBookSnapshot;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Depth of the snapshots kept of closed books.
+const ARCHIVE_DEPTH: usize = 50;
+
+/// A listed call/put pair at one strike of an expiration.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ListedContract {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Expiration date.
+    pub expiration: ExpirationDate,
+    /// Strike price.
+    pub strike: u64,
+}
+
+impl ListedContract {
+    /// Creates a listed contract.
+    #[must_use]
+    pub fn new(underlying: impl Into<String>, expiration: ExpirationDate, strike: u64) -> Self {
+        Self {
+            underlying: underlying.into(),
+            expiration,
+            strike,
+        }
+    }
+}
+
+impl std::fmt::Display for ListedContract {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}-{}", self.underlying, self.expiration, self.strike)
+    }
+}
+
+/// Why a contract's books were closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CloseReason {
+    /// The expiration passed.
+    Expired,
+    /// The contract was delisted.
+    Delisted,
+}
+
+/// Final state of a closed contract's books.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedContract {
+    /// The closed contract.
+    pub contract: ListedContract,
+    /// Why it was closed.
+    pub reason: CloseReason,
+    /// Call book before its orders were cancelled.
+    pub call: OrderBookSnapshot,
+    /// Put book before its orders were cancelled.
+    pub put: OrderBookSnapshot,
+    /// Resting orders cancelled on close.
+    pub cancelled_orders: usize,
+    /// Close time in milliseconds.
+    pub closed_at_ms: u64,
+}
+
+/// Result of a consistency check between listed contracts and books.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncReport {
+    /// Listed contracts without books.
+    pub missing: Vec<ListedContract>,
+    /// Books of contracts that are not listed.
+    pub orphaned: Vec<ListedContract>,
+}
+
+impl SyncReport {
+    /// Returns true if every listed contract has books and every book is
+    /// listed.
+    #[must_use]
+    pub fn is_consistent(&self) -> bool {
+        self.missing.is_empty() && self.orphaned.is_empty()
+    }
+}
+
+/// Keeps the books of a manager in step with the listed contracts.
+pub struct ChainBookSynchronizer {
+    /// Books kept in step.
+    books: Arc<UnderlyingOrderBookManager>,
+    /// Listed contracts.
+    listed: Mutex<BTreeSet<ListedContract>>,
+    /// Closed contracts, oldest first.
+    archive: Mutex<Vec<ArchivedContract>>,
+    /// Clock stamping closes.
+    clock: Arc<dyn Clock>,
+}
+
+impl ChainBookSynchronizer {
+    /// Creates a synchronizer over a book hierarchy with no listed
+    /// contracts.
+    #[must_use]
+    pub fn new(books: Arc<UnderlyingOrderBookManager>) -> Self {
+        Self {
+            books,
+            listed: Mutex::new(BTreeSet::new()),
+            archive: Mutex::new(Vec::new()),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Sets the clock stamping closes.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns the book hierarchy.
+    #[must_use]
+    pub fn books(&self) -> &Arc<UnderlyingOrderBookManager> {
+        &self.books
+    }
+
+    /// Lists a contract, creating its books if needed.
+    pub fn list(&self, contract: ListedContract) -> Arc<StrikeOrderBook> {
+        let strike = self.create_books(&contract);
+        self.listed().insert(contract);
+        strike
+    }
+
+    /// Delists a contract, closing its books.
+    ///
+    /// Returns the archived books, or `None` if the contract had none.
+    pub fn delist(&self, contract: &ListedContract) -> Option<ArchivedContract> {
+        self.listed().remove(contract);
+        self.close(contract, CloseReason::Delisted)
+    }
+
+    /// Closes the books of every listed contract expired by `as_of`.
+    ///
+    /// Returns the archived books.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an expiration date cannot be resolved; nothing
+    /// is closed in that case.
+    pub fn expire(&self, as_of: DateTime<Utc>) -> Result<Vec<ArchivedContract>> {
+        let mut expired = Vec::new();
+        for contract in self.listed().iter() {
+            if contract.expiration.get_date()? <= as_of {
+                expired.push(contract.clone());
+            }
+        }
+        let mut closed = Vec::with_capacity(expired.len());
+        for contract in &expired {
+            self.listed().remove(contract);
+            closed.extend(self.close(contract, CloseReason::Expired));
+        }
+        Ok(closed)
+    }
+
+    /// Returns true if a contract is listed.
+    #[must_use]
+    pub fn is_listed(&self, contract: &ListedContract) -> bool {
+        self.listed().contains(contract)
+    }
+
+    /// Returns the listed contracts, sorted.
+    #[must_use]
+    pub fn listed_contracts(&self) -> Vec<ListedContract> {
+        self.listed().iter().cloned().collect()
+    }
+
+    /// Returns the closed contracts, oldest first.
+    #[must_use]
+    pub fn archived(&self) -> Vec<ArchivedContract> {
+        self.archive().clone()
+    }
+
+    /// Removes and returns the closed contracts, oldest first.
+    pub fn take_archived(&self) -> Vec<ArchivedContract> {
+        std::mem::take(&mut *self.archive())
+    }
+
+    /// Compares the listed contracts with the books in the hierarchy.
+    #[must_use]
+    pub fn check(&self) -> SyncReport {
+        let listed = self.listed().clone();
+        let mut books = BTreeSet::new();
+        for underlying in self.books.iter() {
+            for expiration in underlying.value().expirations().iter() {
+                for strike in expiration.value().strike_prices() {
+                    books.insert(ListedContract::new(
+                        underlying.key().as_str(),
+                        *expiration.key(),
+                        strike,
+                    ));
+                }
+            }
+        }
+        SyncReport {
+            missing: listed.difference(&books).cloned().collect(),
+            orphaned: books.difference(&listed).cloned().collect(),
+        }
+    }
+
+    /// Creates the books of listed contracts that have none.
+    ///
+    /// Returns the report from before the repair.
+    pub fn repair(&self) -> SyncReport {
+        let report = self.check();
+        for contract in &report.missing {
+            self.create_books(contract);
+        }
+        report
+    }
+
+    /// Gets or creates the books of a contract.
+    fn create_books(&self, contract: &ListedContract) -> Arc<StrikeOrderBook> {
+        self.books
+            .get_or_create(contract.underlying.as_str())
+            .get_or_create_expiration(contract.expiration)
+            .get_or_create_strike(contract.strike)
+    }
+
+    /// Cancels, archives and removes a contract's books, dropping its
+    /// expiration once no strikes remain.
+    fn close(&self, contract: &ListedContract, reason: CloseReason) -> Option<ArchivedContract> {
+        let underlying = self.books.get(&contract.underlying).ok()?;
+        let expiration = underlying.get_expiration(&contract.expiration).ok()?;
+        let strike = expiration.get_strike(contract.strike).ok()?;
+        let (call, put) = (
+            strike.call().snapshot(ARCHIVE_DEPTH),
+            strike.put().snapshot(ARCHIVE_DEPTH),
+        );
+        let cancelled_orders = strike.call().mass_cancel(&MassCancelScope::All)
+            + strike.put().mass_cancel(&MassCancelScope::All);
+        expiration.chain().strikes().remove(contract.strike);
+        if expiration.is_empty() {
+            underlying.expirations().remove(&contract.expiration);
+        }
+        let archived = ArchivedContract {
+            contract: contract.clone(),
+            reason,
+            call,
+            put,
+            cancelled_orders,
+            closed_at_ms: self.clock.now_ms(),
+        };
+        self.archive().push(archived.clone());
+        Some(archived)
+    }
+
+    fn listed(&self) -> MutexGuard<'_, BTreeSet<ListedContract>> {
+        self.listed.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn archive(&self) -> MutexGuard<'_, Vec<ArchivedContract>> {
+        self.archive.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use chrono::TimeZone;
+    use orderbook_rs::{OrderId, Side};
+
+    fn expiration(day: u32) -> ExpirationDate {
+        ExpirationDate::DateTime(Utc.with_ymd_and_hms(2030, 6, day, 8, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn test_list_delist_and_expire() {
+        let sync = ChainBookSynchronizer::new(Arc::new(UnderlyingOrderBookManager::new()))
+            .with_clock(Arc::new(ManualClock::new(7)));
+        let weekly = ListedContract::new("BTC", expiration(14), 50000);
+        let monthly = ListedContract::new("BTC", expiration(28), 50000);
+        let strike = sync.list(weekly.clone());
+        sync.list(monthly.clone());
+        let id = sync.books().contract_id(strike.call().symbol()).unwrap();
+        strike
+            .call()
+            .add_limit_order(OrderId::new(), Side::Buy, 100, 5)
+            .unwrap();
+        assert!(sync.check().is_consistent());
+
+        let archived = sync.delist(&weekly).unwrap();
+        assert_eq!(archived.reason, CloseReason::Delisted);
+        assert_eq!(archived.cancelled_orders, 1);
+        assert_eq!(archived.call.bids.len(), 1);
+        assert_eq!(archived.closed_at_ms, 7);
+        assert!(sync.books().book(id).is_err());
+        assert_eq!(sync.books().get("BTC").unwrap().expiration_count(), 1);
+        assert!(sync.delist(&weekly).is_none());
+
+        let as_of = Utc.with_ymd_and_hms(2030, 6, 28, 8, 0, 0).unwrap();
+        let expired = sync.expire(as_of).unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].reason, CloseReason::Expired);
+        assert!(sync.listed_contracts().is_empty());
+        assert_eq!(sync.take_archived().len(), 2);
+        assert!(sync.archived().is_empty());
+    }
+
+    #[test]
+    fn test_check_and_repair() {
+        let sync = ChainBookSynchronizer::new(Arc::new(UnderlyingOrderBookManager::new()));
+        let listed = ListedContract::new("ETH", expiration(14), 3000);
+        sync.list(listed.clone());
+        // Books created around the synchronizer are orphaned, books removed
+        // around it are missing.
+        sync.books()
+            .get_or_create("ETH")
+            .get_or_create_expiration(expiration(14))
+            .get_or_create_strike(3100);
+        sync.books()
+            .get("ETH")
+            .unwrap()
+            .get_expiration(&expiration(14))
+            .unwrap()
+            .chain()
+            .strikes()
+            .remove(3000);
+
+        let report = sync.repair();
+        assert_eq!(report.missing, vec![listed.clone()]);
+        assert_eq!(
+            report.orphaned,
+            vec![ListedContract::new("ETH", expiration(14), 3100)]
+        );
+        let report = sync.check();
+        assert!(report.missing.is_empty());
+        assert!(!report.is_consistent());
+        assert!(sync.is_listed(&listed));
+    }
+}
@@ -0,0 +1,123 @@
+//! Book tiering module.
+//!
+//! This module provides the types behind warm/cold tiering of strikes in a
+//! [`super::StrikeOrderBookManager`]. Listing a full chain creates order
+//! books only for warm strikes; far out-of-the-money or long-dated strikes
+//! are kept as a [`StrikePlaceholder`] holding the contract symbols and an
+//! indicative mark, and their books are materialized when the first order
+//! or quote arrives. Empty books can be demoted back to placeholders.
+
+use optionstratlib::ExpirationDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Storage tier of a strike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BookTier {
+    /// Call and put order books exist.
+    Materialized,
+    /// Only a placeholder exists.
+    Placeholder,
+}
+
+/// Lightweight stand-in for a strike whose books are not materialized.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StrikePlaceholder {
+    /// The strike price.
+    pub strike: u64,
+    /// Call contract symbol.
+    pub call_symbol: String,
+    /// Put contract symbol.
+    pub put_symbol: String,
+    /// Indicative call mark, in book price units.
+    pub call_mark: Option<u128>,
+    /// Indicative put mark, in book price units.
+    pub put_mark: Option<u128>,
+}
+
+/// Rule deciding which strikes are listed warm.
+///
+/// A strike is warm when `strike / spot` lies within the moneyness bounds
+/// and the expiration is within `max_days`; every other strike is listed
+/// as a placeholder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TieringPolicy {
+    /// Minimum `strike / spot` of a warm strike (inclusive).
+    pub min_moneyness: Decimal,
+    /// Maximum `strike / spot` of a warm strike (inclusive).
+    pub max_moneyness: Decimal,
+    /// Maximum days to expiration of a warm strike (inclusive), if any.
+    pub max_days: Option<Decimal>,
+}
+
+impl Default for TieringPolicy {
+    fn default() -> Self {
+        Self {
+            min_moneyness: dec!(0.7),
+            max_moneyness: dec!(1.3),
+            max_days: Some(dec!(90)),
+        }
+    }
+}
+
+impl TieringPolicy {
+    /// Returns true if a strike should be listed with materialized books.
+    ///
+    /// Strikes are warm when the spot is zero or the expiration cannot be
+    /// read, so missing data never hides a book.
+    ///
+    /// # Arguments
+    ///
+    /// * `strike` - The strike price
+    /// * `spot` - Reference spot price in the same units as strikes
+    /// * `expiration` - The expiration date
+    #[must_use]
+    pub fn is_warm(&self, strike: u64, spot: u64, expiration: &ExpirationDate) -> bool {
+        if spot == 0 {
+            return true;
+        }
+        let moneyness = Decimal::from(strike) / Decimal::from(spot);
+        if moneyness < self.min_moneyness || moneyness > self.max_moneyness {
+            return false;
+        }
+        match (self.max_days, expiration.get_days()) {
+            (Some(max), Ok(days)) => days.to_dec() <= max,
+            _ => true,
+        }
+    }
+}
+
+/// Materialized versus placeholder strike counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TierStats {
+    /// Strikes with materialized books.
+    pub materialized: usize,
+    /// Strikes held as placeholders.
+    pub placeholders: usize,
+    /// Placeholders materialized since creation.
+    pub materializations: u64,
+    /// Books demoted to placeholders since creation.
+    pub demotions: u64,
+}
+
+impl TierStats {
+    /// Returns the share of listed strikes with materialized books, or
+    /// `None` if nothing is listed.
+    #[must_use]
+    pub fn materialized_ratio(&self) -> Option<Decimal> {
+        let listed = self.materialized + self.placeholders;
+        (listed > 0).then(|| Decimal::from(self.materialized) / Decimal::from(listed))
+    }
+
+    /// Adds another manager's counts.
+    #[must_use]
+    pub(crate) const fn merge(self, other: Self) -> Self {
+        Self {
+            materialized: self.materialized + other.materialized,
+            placeholders: self.placeholders + other.placeholders,
+            materializations: self.materializations + other.materializations,
+            demotions: self.demotions + other.demotions,
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Order timers module.
+//!
+//! This module provides the time-based order features of an
+//! [`super::OptionOrderBook`]: Good-Till-Date orders and scheduled mass
+//! cancels, such as pulling our orders a minute before expiry or at the
+//! session close.
+//!
+//! Time is read from the book's [`Clock`], so simulated time drives both
+//! features in tests and replays. Nothing runs on its own: the owner calls
+//! `run_timers` on its timer or clock tick, and orders and schedules fall
+//! due at the first call at or after their time. Every level of the
+//! hierarchy, from [`super::OptionChainOrderBook`] up to
+//! [`super::UnderlyingOrderBookManager`], can set the clock, schedule a
+//! mass cancel and run the timers of all its books at once. A level keeps
+//! its clock and pending cancels and applies them to the books it lists
+//! later. [`crate::clock::ExpiryClock::schedule_expiry_cancels`] schedules
+//! a cancel a lead time before each expiration.
+//!
+//! Good-Till-Date orders are held as good-till-cancel in the inner book,
+//! whose own expiry checks read the wall clock. Their expiries are keyed by
+//! order id and dropped when the order is filled or cancelled; a modify
+//! that re-adds the order carries its expiry over.
+
+use crate::clock::{Clock, SystemClock};
+use orderbook_rs::OrderId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock};
+
+/// Orders a scheduled mass cancel applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MassCancelScope {
+    /// Every order in the book.
+    All,
+    /// Orders added for a participant, e.g. all of ours.
+    Participant(String),
+}
+
+/// A mass cancel scheduled at a time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledCancel {
+    /// Time the cancel falls due, in milliseconds.
+    pub at_ms: u64,
+    /// Orders cancelled.
+    pub scope: MassCancelScope,
+}
+
+impl ScheduledCancel {
+    /// Creates a scheduled mass cancel.
+    #[must_use]
+    pub const fn new(at_ms: u64, scope: MassCancelScope) -> Self {
+        Self { at_ms, scope }
+    }
+
+    /// Creates a mass cancel a lead time before an instant, e.g. one
+    /// minute before expiry or the session close.
+    #[must_use]
+    pub const fn before(instant_ms: u64, lead_ms: u64, scope: MassCancelScope) -> Self {
+        Self::new(instant_ms.saturating_sub(lead_ms), scope)
+    }
+}
+
+/// Outcome of one run of a book's timers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimerReport {
+    /// Good-Till-Date orders cancelled at their expiry.
+    pub expired: usize,
+    /// Scheduled mass cancels that fell due.
+    pub mass_cancels: usize,
+    /// Orders cancelled by those mass cancels.
+    pub mass_cancelled: usize,
+}
+
+impl std::ops::AddAssign for TimerReport {
+    fn add_assign(&mut self, other: Self) {
+        self.expired += other.expired;
+        self.mass_cancels += other.mass_cancels;
+        self.mass_cancelled += other.mass_cancelled;
+    }
+}
+
+/// Work due at a run of the timers.
+#[derive(Debug, Default)]
+pub(crate) struct DueTimers {
+    /// Good-Till-Date orders past their expiry.
+    pub(crate) expired: Vec<OrderId>,
+    /// Mass cancels past their time, in time order.
+    pub(crate) cancels: Vec<ScheduledCancel>,
+}
+
+/// Mutable state of [`OrderTimers`].
+#[derive(Debug, Default)]
+struct TimersState {
+    /// Expiry of each resting Good-Till-Date order.
+    expiries: HashMap<OrderId, u64>,
+    /// Good-Till-Date orders by expiry.
+    by_expiry: BTreeMap<u64, HashSet<OrderId>>,
+    /// Pending mass cancels, sorted by time.
+    scheduled: Vec<ScheduledCancel>,
+}
+
+impl TimersState {
+    fn remove_expiry(&mut self, order_id: OrderId) -> Option<u64> {
+        let expire_at_ms = self.expiries.remove(&order_id)?;
+        if let Some(ids) = self.by_expiry.get_mut(&expire_at_ms) {
+            ids.remove(&order_id);
+            if ids.is_empty() {
+                self.by_expiry.remove(&expire_at_ms);
+            }
+        }
+        Some(expire_at_ms)
+    }
+}
+
+/// Clock, Good-Till-Date orders and scheduled cancels of one book.
+pub(crate) struct OrderTimers {
+    /// Time source.
+    clock: RwLock<Arc<dyn Clock>>,
+    /// Expiries and schedules.
+    state: Mutex<TimersState>,
+}
+
+impl Default for OrderTimers {
+    fn default() -> Self {
+        Self {
+            clock: RwLock::new(Arc::new(SystemClock)),
+            state: Mutex::new(TimersState::default()),
+        }
+    }
+}
+
+impl OrderTimers {
+    pub(crate) fn now_ms(&self) -> u64 {
+        self.clock
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .now_ms()
+    }
+
+    pub(crate) fn set_clock(&self, clock: Arc<dyn Clock>) {
+        *self.clock.write().unwrap_or_else(PoisonError::into_inner) = clock;
+    }
+
+    pub(crate) fn insert_expiry(&self, expire_at_ms: u64, order_id: OrderId) {
+        let mut state = self.lock();
+        state.remove_expiry(order_id);
+        state.expiries.insert(order_id, expire_at_ms);
+        state
+            .by_expiry
+            .entry(expire_at_ms)
+            .or_default()
+            .insert(order_id);
+    }
+
+    pub(crate) fn remove_expiry(&self, order_id: OrderId) -> Option<u64> {
+        self.lock().remove_expiry(order_id)
+    }
+
+    pub(crate) fn expiry(&self, order_id: OrderId) -> Option<u64> {
+        self.lock().expiries.get(&order_id).copied()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn expiry_count(&self) -> usize {
+        self.lock().expiries.len()
+    }
+
+    pub(crate) fn schedule(&self, cancel: ScheduledCancel) {
+        let mut state = self.lock();
+        let at = state.scheduled.partition_point(|c| c.at_ms <= cancel.at_ms);
+        state.scheduled.insert(at, cancel);
+    }
+
+    pub(crate) fn scheduled(&self) -> Vec<ScheduledCancel> {
+        self.lock().scheduled.clone()
+    }
+
+    pub(crate) fn clear_scheduled(&self) -> usize {
+        std::mem::take(&mut self.lock().scheduled).len()
+    }
+
+    /// Removes and returns the work due at `now_ms`.
+    pub(crate) fn take_due(&self, now_ms: u64) -> DueTimers {
+        let mut state = self.lock();
+        let pending = state.by_expiry.split_off(&(now_ms.saturating_add(1)));
+        let expired: Vec<OrderId> = std::mem::replace(&mut state.by_expiry, pending)
+            .into_values()
+            .flatten()
+            .collect();
+        for order_id in &expired {
+            state.expiries.remove(order_id);
+        }
+        let due = state.scheduled.partition_point(|c| c.at_ms <= now_ms);
+        DueTimers {
+            expired,
+            cancels: state.scheduled.drain(..due).collect(),
+        }
+    }
+
+    pub(crate) fn clear_expiries(&self) {
+        let mut state = self.lock();
+        state.expiries.clear();
+        state.by_expiry.clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TimersState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Clock and mass cancels set at a level of the hierarchy, kept so that the
+/// books it lists later get them too.
+#[derive(Default)]
+pub(crate) struct TimerDefaults {
+    /// Clock set at this level, if any.
+    clock: RwLock<Option<Arc<dyn Clock>>>,
+    /// Mass cancels scheduled at this level.
+    cancels: Mutex<Vec<ScheduledCancel>>,
+}
+
+impl TimerDefaults {
+    pub(crate) fn set_clock(&self, clock: Arc<dyn Clock>) {
+        *self.clock.write().unwrap_or_else(PoisonError::into_inner) = Some(clock);
+    }
+
+    pub(crate) fn schedule(&self, cancel: &ScheduledCancel) {
+        self.cancels
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(cancel.clone());
+    }
+
+    /// Applies the clock and the cancels not yet due by it to a newly
+    /// listed child, dropping the cancels that fell due.
+    pub(crate) fn apply(
+        &self,
+        set_clock: impl FnOnce(Arc<dyn Clock>),
+        mut schedule: impl FnMut(&ScheduledCancel),
+    ) {
+        let clock = self
+            .clock
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let now_ms = clock
+            .as_ref()
+            .map_or_else(|| SystemClock.now_ms(), |c| c.now_ms());
+        if let Some(clock) = clock {
+            set_clock(clock);
+        }
+        let mut cancels = self.cancels.lock().unwrap_or_else(PoisonError::into_inner);
+        cancels.retain(|cancel| cancel.at_ms > now_ms);
+        cancels.iter().for_each(&mut schedule);
+    }
+}
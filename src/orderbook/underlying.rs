@@ -3,10 +3,19 @@
 //! This module provides the [`UnderlyingOrderBook`] and [`UnderlyingOrderBookManager`]
 //! for managing all underlyings in the system.
 
+use super::book::OptionOrderBook;
 use super::expiration::{ExpirationOrderBook, ExpirationOrderBookManager};
+use super::filter::{ChainFilter, ChainView};
+use super::linear::{LinearKind, LinearOrderBook};
+use super::quote::Quote;
+use super::registry::{ContractId, ContractRegistry};
+use super::tiering::TierStats;
+use super::timers::{ScheduledCancel, TimerDefaults, TimerReport};
+use crate::clock::Clock;
 use crate::error::{Error, Result};
 use crossbeam_skiplist::SkipMap;
 use optionstratlib::ExpirationDate;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 /// Order book for a single underlying asset.
@@ -37,10 +46,21 @@ impl UnderlyingOrderBook {
     /// * `underlying` - The underlying asset symbol (e.g., "BTC")
     #[must_use]
     pub fn new(underlying: impl Into<String>) -> Self {
+        Self::with_registry(underlying, Arc::new(ContractRegistry::new()))
+    }
+
+    /// Creates a new underlying order book sharing a contract registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - The underlying asset symbol (e.g., "BTC")
+    /// * `registry` - The registry used to intern contract symbols
+    #[must_use]
+    pub fn with_registry(underlying: impl Into<String>, registry: Arc<ContractRegistry>) -> Self {
         let underlying = underlying.into();
 
         Self {
-            expirations: ExpirationOrderBookManager::new(&underlying),
+            expirations: ExpirationOrderBookManager::with_registry(&underlying, registry),
             underlying,
         }
     }
@@ -95,6 +115,62 @@ impl UnderlyingOrderBook {
         self.expirations.total_strike_count()
     }
 
+    /// Calls `f` with every option book, by expiration.
+    pub fn for_each_book<F: FnMut(&OptionOrderBook)>(&self, f: F) {
+        self.expirations.for_each_book(f);
+    }
+
+    /// Sets the clock of every book of the underlying, including books
+    /// listed later.
+    pub fn set_clock(&self, clock: Arc<dyn Clock>) {
+        self.expirations.set_clock(clock);
+    }
+
+    /// Schedules a mass cancel in every book of the underlying, e.g. pulling our
+    /// orders a minute before expiry or at the session close.
+    ///
+    /// Books listed before the cancel falls due are covered too.
+    pub fn schedule_mass_cancel(&self, cancel: &ScheduledCancel) {
+        self.expirations.schedule_mass_cancel(cancel);
+    }
+
+    /// Runs the timers of every book of the underlying, by each book's clock.
+    pub fn run_timers(&self) -> TimerReport {
+        let mut report = TimerReport::default();
+        self.for_each_book(|book| report += book.run_timers());
+        report
+    }
+
+    /// Calls `f` with every option book and its best quote, without
+    /// collecting them first.
+    pub fn for_each_quote<F: FnMut(&OptionOrderBook, Quote)>(&self, mut f: F) {
+        self.for_each_book(|book| f(book, book.best_quote()));
+    }
+
+    /// Returns a view over the contracts matching a filter across all expirations.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Tenor, moneyness and liquidity criteria
+    #[must_use]
+    pub fn filter(&self, filter: &ChainFilter) -> ChainView {
+        let mut view = ChainView::default();
+        for entry in self.expirations.iter() {
+            view.extend_from_chain(entry.value().chain(), filter);
+        }
+        view
+    }
+
+    /// Returns materialized versus placeholder strike counts across all
+    /// expirations.
+    #[must_use]
+    pub fn tier_stats(&self) -> TierStats {
+        self.expirations
+            .iter()
+            .map(|e| e.value().chain().strikes().tier_stats())
+            .fold(TierStats::default(), TierStats::merge)
+    }
+
     /// Returns statistics about this underlying.
     #[must_use]
     pub fn stats(&self) -> UnderlyingStats {
@@ -150,6 +226,12 @@ impl std::fmt::Display for UnderlyingStats {
 pub struct UnderlyingOrderBookManager {
     /// Underlying order books indexed by symbol.
     underlyings: SkipMap<String, Arc<UnderlyingOrderBook>>,
+    /// Registry interning every contract in the hierarchy.
+    registry: Arc<ContractRegistry>,
+    /// Linear instrument order books indexed by symbol.
+    linear: SkipMap<String, Arc<LinearOrderBook>>,
+    /// Clock and mass cancels applied to underlyings listed later.
+    timers: TimerDefaults,
 }
 
 impl Default for UnderlyingOrderBookManager {
@@ -164,9 +246,58 @@ impl UnderlyingOrderBookManager {
     pub fn new() -> Self {
         Self {
             underlyings: SkipMap::new(),
+            registry: Arc::new(ContractRegistry::new()),
+            linear: SkipMap::new(),
+            timers: TimerDefaults::default(),
         }
     }
 
+    /// Returns a reference to the contract registry shared by the hierarchy.
+    #[must_use]
+    pub fn registry(&self) -> &ContractRegistry {
+        &self.registry
+    }
+
+    /// Returns an Arc reference to the contract registry.
+    #[must_use]
+    pub fn registry_arc(&self) -> Arc<ContractRegistry> {
+        Arc::clone(&self.registry)
+    }
+
+    /// Resolves a contract symbol to its interned id.
+    ///
+    /// This is the string edge of the API; hot paths should keep the returned
+    /// `ContractId` and use the id-based accessors.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContractNotFound` if the symbol was never listed.
+    pub fn contract_id(&self, symbol: &str) -> Result<ContractId> {
+        self.registry
+            .id_of(symbol)
+            .ok_or_else(|| Error::contract_not_found(symbol))
+    }
+
+    /// Gets an option order book by contract id.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContractNotFound` if no live book exists for the id.
+    pub fn book(&self, id: ContractId) -> Result<Arc<OptionOrderBook>> {
+        self.registry
+            .book(id)
+            .ok_or_else(|| Error::contract_not_found(id.to_string()))
+    }
+
+    /// Returns the best quote for a contract id.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContractNotFound` if no live book exists for the id.
+    pub fn best_quote(&self, id: ContractId) -> Result<Quote> {
+        Ok(self.book(id)?.best_quote())
+    }
+
     /// Returns the number of underlyings.
     #[must_use]
     pub fn len(&self) -> usize {
@@ -185,7 +316,14 @@ impl UnderlyingOrderBookManager {
         if let Some(entry) = self.underlyings.get(&underlying) {
             return Arc::clone(entry.value());
         }
-        let book = Arc::new(UnderlyingOrderBook::new(&underlying));
+        let book = Arc::new(UnderlyingOrderBook::with_registry(
+            &underlying,
+            Arc::clone(&self.registry),
+        ));
+        self.timers.apply(
+            |clock| book.set_clock(clock),
+            |cancel| book.schedule_mass_cancel(cancel),
+        );
         self.underlyings.insert(underlying, Arc::clone(&book));
         book
     }
@@ -216,9 +354,130 @@ impl UnderlyingOrderBookManager {
         self.underlyings.iter()
     }
 
-    /// Removes an underlying order book.
+    /// Calls `f` with every option book of every underlying.
+    pub fn for_each_book<F: FnMut(&OptionOrderBook)>(&self, mut f: F) {
+        for entry in self.underlyings.iter() {
+            entry.value().for_each_book(&mut f);
+        }
+    }
+
+    /// Sets the clock of every book of the manager, including books listed
+    /// later.
+    pub fn set_clock(&self, clock: Arc<dyn Clock>) {
+        self.timers.set_clock(Arc::clone(&clock));
+        for entry in self.underlyings.iter() {
+            entry.value().set_clock(Arc::clone(&clock));
+        }
+    }
+
+    /// Schedules a mass cancel in every book of the manager, e.g. pulling our
+    /// orders a minute before expiry or at the session close.
+    ///
+    /// Books listed before the cancel falls due are covered too.
+    pub fn schedule_mass_cancel(&self, cancel: &ScheduledCancel) {
+        self.timers.schedule(cancel);
+        for entry in self.underlyings.iter() {
+            entry.value().schedule_mass_cancel(cancel);
+        }
+    }
+
+    /// Runs the timers of every book of the manager, by each book's clock.
+    pub fn run_timers(&self) -> TimerReport {
+        let mut report = TimerReport::default();
+        self.for_each_book(|book| report += book.run_timers());
+        report
+    }
+
+    /// Calls `f` with every option book and its best quote, without
+    /// collecting them first.
+    pub fn for_each_quote<F: FnMut(&OptionOrderBook, Quote)>(&self, mut f: F) {
+        self.for_each_book(|book| f(book, book.best_quote()));
+    }
+
+    /// Removes an underlying order book, releasing its contracts from the registry.
     pub fn remove(&self, underlying: &str) -> bool {
-        self.underlyings.remove(underlying).is_some()
+        match self.underlyings.remove(underlying) {
+            Some(entry) => {
+                entry.value().expirations().release_all_contracts();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Gets or creates a linear instrument order book.
+    ///
+    /// If a book already exists for the symbol it is returned unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - The instrument symbol (e.g., "BTC-PERP")
+    /// * `underlying` - The underlying asset symbol (e.g., "BTC")
+    /// * `kind` - The instrument kind
+    pub fn get_or_create_linear(
+        &self,
+        symbol: impl Into<String>,
+        underlying: impl Into<String>,
+        kind: LinearKind,
+    ) -> Arc<LinearOrderBook> {
+        let symbol = symbol.into();
+        if let Some(entry) = self.linear.get(&symbol) {
+            return Arc::clone(entry.value());
+        }
+        let entry = self.linear.get_or_insert_with(symbol.clone(), || {
+            Arc::new(LinearOrderBook::new(symbol, underlying, kind))
+        });
+        Arc::clone(entry.value())
+    }
+
+    /// Gets a linear instrument order book.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContractNotFound` if the instrument does not exist.
+    pub fn linear(&self, symbol: &str) -> Result<Arc<LinearOrderBook>> {
+        self.linear
+            .get(symbol)
+            .map(|e| Arc::clone(e.value()))
+            .ok_or_else(|| Error::contract_not_found(symbol))
+    }
+
+    /// Returns all linear instruments on an underlying, sorted by symbol.
+    #[must_use]
+    pub fn linear_for_underlying(&self, underlying: &str) -> Vec<Arc<LinearOrderBook>> {
+        self.linear
+            .iter()
+            .filter(|e| e.value().underlying() == underlying)
+            .map(|e| Arc::clone(e.value()))
+            .collect()
+    }
+
+    /// Removes a linear instrument order book.
+    pub fn remove_linear(&self, symbol: &str) -> bool {
+        self.linear.remove(symbol).is_some()
+    }
+
+    /// Returns the number of linear instruments.
+    #[must_use]
+    pub fn linear_count(&self) -> usize {
+        self.linear.len()
+    }
+
+    /// Returns the basis between two linear instruments in smallest units.
+    ///
+    /// Calculated as `mark(instrument) - mark(reference)`, e.g. a future
+    /// against spot. Returns `None` if either mark is unavailable.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ContractNotFound` if either instrument does not exist.
+    pub fn basis(&self, instrument: &str, reference: &str) -> Result<Option<i128>> {
+        let instrument = self.linear(instrument)?;
+        let reference = self.linear(reference)?;
+        Ok(instrument
+            .mark_price()
+            .zip(reference.mark_price())
+            .map(|(a, b)| a as i128 - b as i128))
     }
 
     /// Returns all underlying symbols (sorted).
@@ -233,7 +492,12 @@ impl UnderlyingOrderBookManager {
         self.underlyings
             .iter()
             .map(|e| e.value().total_order_count())
-            .sum()
+            .sum::<usize>()
+            + self
+                .linear
+                .iter()
+                .map(|e| e.value().order_count())
+                .sum::<usize>()
     }
 
     /// Returns the total expiration count across all underlyings.
@@ -254,6 +518,16 @@ impl UnderlyingOrderBookManager {
             .sum()
     }
 
+    /// Returns materialized versus placeholder strike counts across all
+    /// underlyings.
+    #[must_use]
+    pub fn tier_stats(&self) -> TierStats {
+        self.underlyings
+            .iter()
+            .map(|e| e.value().tier_stats())
+            .fold(TierStats::default(), TierStats::merge)
+    }
+
     /// Returns statistics about the entire order book system.
     #[must_use]
     pub fn stats(&self) -> GlobalStats {
@@ -262,12 +536,13 @@ impl UnderlyingOrderBookManager {
             total_expirations: self.total_expiration_count(),
             total_strikes: self.total_strike_count(),
             total_orders: self.total_order_count(),
+            linear_instruments: self.linear_count(),
         }
     }
 }
 
 /// Global statistics about the order book system.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GlobalStats {
     /// Number of underlyings.
     pub underlying_count: usize,
@@ -277,14 +552,20 @@ pub struct GlobalStats {
     pub total_strikes: usize,
     /// Total number of orders.
     pub total_orders: usize,
+    /// Number of linear instruments.
+    pub linear_instruments: usize,
 }
 
 impl std::fmt::Display for GlobalStats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{} underlyings, {} expirations, {} strikes, {} orders",
-            self.underlying_count, self.total_expirations, self.total_strikes, self.total_orders
+            "{} underlyings, {} expirations, {} strikes, {} linear instruments, {} orders",
+            self.underlying_count,
+            self.total_expirations,
+            self.total_strikes,
+            self.linear_instruments,
+            self.total_orders
         )
     }
 }
@@ -488,6 +769,106 @@ mod tests {
         assert_eq!(manager.total_order_count(), 1);
     }
 
+    #[test]
+    fn test_underlying_manager_contract_id_lookup() {
+        let manager = UnderlyingOrderBookManager::new();
+
+        let strike = manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(test_expiration())
+            .get_or_create_strike(50000);
+        strike
+            .call()
+            .add_limit_order(OrderId::new(), Side::Buy, 100, 10)
+            .unwrap();
+        strike
+            .call()
+            .add_limit_order(OrderId::new(), Side::Sell, 105, 5)
+            .unwrap();
+
+        let id = manager.contract_id(strike.call().symbol()).unwrap();
+        assert_eq!(manager.book(id).unwrap().symbol(), strike.call().symbol());
+        assert!(manager.best_quote(id).unwrap().is_two_sided());
+        assert!(manager.contract_id("BTC-19700101-1-C").is_err());
+    }
+
+    #[test]
+    fn test_underlying_manager_shares_registry() {
+        let manager = UnderlyingOrderBookManager::new();
+        let exp = test_expiration();
+
+        drop(
+            manager
+                .get_or_create("BTC")
+                .get_or_create_expiration(exp)
+                .get_or_create_strike(50000),
+        );
+        drop(
+            manager
+                .get_or_create("ETH")
+                .get_or_create_expiration(exp)
+                .get_or_create_strike(3000),
+        );
+
+        assert_eq!(manager.registry().len(), 4);
+        assert_eq!(manager.registry().book_count(), 4);
+    }
+
+    #[test]
+    fn test_for_each_quote_visits_every_book() {
+        let manager = UnderlyingOrderBookManager::new();
+        let exp = test_expiration();
+        for strike in [50000, 51000] {
+            let strike = manager
+                .get_or_create("BTC")
+                .get_or_create_expiration(exp)
+                .get_or_create_strike(strike);
+            strike
+                .put()
+                .add_limit_order(OrderId::new(), Side::Buy, 100, 10)
+                .unwrap();
+        }
+        drop(
+            manager
+                .get_or_create("ETH")
+                .get_or_create_expiration(exp)
+                .get_or_create_strike(3000),
+        );
+
+        let mut books = 0;
+        let mut bid_size = 0;
+        manager.for_each_quote(|_, quote| {
+            books += 1;
+            bid_size += quote.bid_size();
+        });
+        assert_eq!(books, 6);
+        assert_eq!(bid_size, 20);
+
+        let mut symbols = Vec::new();
+        manager
+            .get("BTC")
+            .unwrap()
+            .for_each_book(|book| symbols.push(book.symbol().to_string()));
+        assert_eq!(symbols.len(), 4);
+        assert!(symbols[0].ends_with("-C"));
+    }
+
+    #[test]
+    fn test_underlying_manager_remove_releases_contracts() {
+        let manager = UnderlyingOrderBookManager::new();
+
+        let strike = manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(test_expiration())
+            .get_or_create_strike(50000);
+        let id = manager.contract_id(strike.put().symbol()).unwrap();
+        drop(strike);
+
+        assert!(manager.remove("BTC"));
+        assert!(manager.book(id).is_err());
+        assert_eq!(manager.registry().book_count(), 0);
+    }
+
     #[test]
     fn test_global_stats_display() {
         let manager = UnderlyingOrderBookManager::new();
@@ -504,4 +885,104 @@ mod tests {
         assert!(display.contains("1 expirations"));
         assert!(display.contains("1 strikes"));
     }
+
+    #[test]
+    fn test_linear_instruments() {
+        let manager = UnderlyingOrderBookManager::new();
+
+        let perp = manager.get_or_create_linear("BTC-PERP", "BTC", LinearKind::Perpetual);
+        let again = manager.get_or_create_linear("BTC-PERP", "BTC", LinearKind::Spot);
+        drop(manager.get_or_create_linear("ETH-PERP", "ETH", LinearKind::Perpetual));
+
+        assert_eq!(again.kind(), &LinearKind::Perpetual);
+        assert_eq!(manager.linear_count(), 2);
+        assert_eq!(manager.linear_for_underlying("BTC").len(), 1);
+        assert!(manager.linear("SOL-PERP").is_err());
+
+        perp.add_limit_order(OrderId::new(), Side::Buy, 50000, 1)
+            .unwrap();
+        assert_eq!(manager.total_order_count(), 1);
+        assert_eq!(manager.stats().linear_instruments, 2);
+
+        assert!(manager.remove_linear("ETH-PERP"));
+        assert!(!manager.remove_linear("ETH-PERP"));
+    }
+
+    #[test]
+    fn test_linear_basis() {
+        let manager = UnderlyingOrderBookManager::new();
+        let future = manager.get_or_create_linear(
+            "BTC-20240329",
+            "BTC",
+            LinearKind::Future(test_expiration()),
+        );
+        let spot = manager.get_or_create_linear("BTC-USD", "BTC", LinearKind::Spot);
+
+        assert_eq!(manager.basis("BTC-20240329", "BTC-USD").unwrap(), None);
+
+        future.set_mark(50500);
+        spot.set_mark(50000);
+        assert_eq!(manager.basis("BTC-20240329", "BTC-USD").unwrap(), Some(500));
+        assert_eq!(
+            manager.basis("BTC-USD", "BTC-20240329").unwrap(),
+            Some(-500)
+        );
+        assert!(manager.basis("BTC-USD", "missing").is_err());
+    }
+
+    #[test]
+    fn test_manager_tier_stats() {
+        let manager = UnderlyingOrderBookManager::new();
+        let exp = manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(test_expiration());
+        exp.get_or_create_strike(50000);
+        exp.chain().strikes().add_placeholder(100000, None, None);
+        manager
+            .get_or_create("ETH")
+            .get_or_create_expiration(test_expiration())
+            .chain()
+            .strikes()
+            .add_placeholder(9000, Some(1), Some(2));
+
+        let stats = manager.tier_stats();
+        assert_eq!(stats.materialized, 1);
+        assert_eq!(stats.placeholders, 2);
+        assert_eq!(
+            stats.materialized_ratio(),
+            Some(rust_decimal::Decimal::ONE / rust_decimal::Decimal::from(3))
+        );
+    }
+
+    #[test]
+    fn test_books_listed_later_get_clock_and_cancels() {
+        use crate::orderbook::{MassCancelScope, ScheduledCancel};
+        use orderbook_rs::TimeInForce;
+
+        let manager = UnderlyingOrderBookManager::new();
+        let clock = Arc::new(crate::clock::ManualClock::new(1_000_000));
+        manager.set_clock(clock.clone());
+        manager.schedule_mass_cancel(&ScheduledCancel::new(500_000, MassCancelScope::All));
+        manager.schedule_mass_cancel(&ScheduledCancel::new(1_060_000, MassCancelScope::All));
+
+        let strike = manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(test_expiration())
+            .get_or_create_strike(50000);
+        strike
+            .call()
+            .add_limit_order_with_tif(OrderId::new(), Side::Sell, 100, 1, TimeInForce::Gtd(1_120))
+            .unwrap();
+        strike
+            .put()
+            .add_limit_order(OrderId::new(), Side::Sell, 100, 1)
+            .unwrap();
+
+        // The cancel that fell due before the strike was listed is dropped.
+        assert_eq!(manager.run_timers(), TimerReport::default());
+        clock.set(1_060_000);
+        let report = manager.run_timers();
+        assert_eq!((report.mass_cancels, report.mass_cancelled), (2, 2));
+        assert_eq!(manager.total_order_count(), 0);
+    }
 }
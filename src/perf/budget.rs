@@ -0,0 +1,208 @@
+//! Budget harness module.
+//!
+//! This module provides the [`PerfHarness`]. Each operation is run a
+//! number of warm-up passes, then timed over a fixed number of passes; the
+//! median pass is held against the budget so a single preempted pass does
+//! not fail the run, while the 99th percentile is reported alongside it.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Timings of one budgeted operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BudgetResult {
+    /// Budget name.
+    pub name: String,
+    /// Budget per pass, in nanoseconds.
+    pub budget_ns: u64,
+    /// Timed passes.
+    pub iterations: u32,
+    /// Median pass, in nanoseconds.
+    pub median_ns: u64,
+    /// 99th percentile pass, in nanoseconds.
+    pub p99_ns: u64,
+    /// Fastest pass, in nanoseconds.
+    pub min_ns: u64,
+    /// True if the median pass is within the budget.
+    pub passed: bool,
+}
+
+impl BudgetResult {
+    /// Returns the median pass as a fraction of the budget.
+    #[must_use]
+    pub fn utilization(&self) -> f64 {
+        self.median_ns as f64 / self.budget_ns.max(1) as f64
+    }
+}
+
+/// Results of a harness run.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PerfReport {
+    /// Results in measurement order.
+    pub results: Vec<BudgetResult>,
+}
+
+impl PerfReport {
+    /// Returns true if every budget passed.
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|r| r.passed)
+    }
+
+    /// Returns the budgets that failed.
+    #[must_use]
+    pub fn failures(&self) -> Vec<&BudgetResult> {
+        self.results.iter().filter(|r| !r.passed).collect()
+    }
+
+    /// Serializes the report to pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::SerializationError` if serialization fails.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Times operations against named budgets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerfHarness {
+    /// Untimed passes before measuring.
+    warmup: u32,
+    /// Timed passes.
+    iterations: u32,
+    /// Factor applied to every budget, for slower or faster hardware.
+    scale: f64,
+    /// Results so far.
+    report: PerfReport,
+}
+
+impl PerfHarness {
+    /// Creates a harness.
+    ///
+    /// # Arguments
+    ///
+    /// * `warmup` - Untimed passes before measuring
+    /// * `iterations` - Timed passes per operation
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if `iterations` is zero.
+    pub fn new(warmup: u32, iterations: u32) -> Result<Self> {
+        if iterations == 0 {
+            return Err(Error::configuration("perf harness needs at least one pass"));
+        }
+        Ok(Self {
+            warmup,
+            iterations,
+            scale: 1.0,
+            report: PerfReport::default(),
+        })
+    }
+
+    /// Scales every budget, e.g. 2.0 to allow twice the time on slower
+    /// hardware.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the scale is not positive.
+    pub fn with_scale(mut self, scale: f64) -> Result<Self> {
+        if !(scale.is_finite() && scale > 0.0) {
+            return Err(Error::configuration("perf budget scale must be positive"));
+        }
+        self.scale = scale;
+        Ok(self)
+    }
+
+    /// Times an operation and checks its median pass against a budget.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Budget name shown in the report
+    /// * `budget` - Allowed time per pass, before scaling
+    /// * `pass` - One pass of the operation
+    pub fn measure<F: FnMut()>(
+        &mut self,
+        name: impl Into<String>,
+        budget: Duration,
+        mut pass: F,
+    ) -> &BudgetResult {
+        for _ in 0..self.warmup {
+            pass();
+        }
+        let mut samples: Vec<u64> = (0..self.iterations)
+            .map(|_| {
+                let start = Instant::now();
+                pass();
+                duration_ns(start.elapsed())
+            })
+            .collect();
+        samples.sort_unstable();
+
+        let budget_ns = (duration_ns(budget) as f64 * self.scale) as u64;
+        let median_ns = samples[samples.len() / 2];
+        let p99 = (samples.len() * 99).div_ceil(100).max(1) - 1;
+        self.report.results.push(BudgetResult {
+            name: name.into(),
+            budget_ns,
+            iterations: self.iterations,
+            median_ns,
+            p99_ns: samples[p99],
+            min_ns: samples[0],
+            passed: median_ns <= budget_ns,
+        });
+        &self.report.results[self.report.results.len() - 1]
+    }
+
+    /// Returns the report of the operations measured so far.
+    #[must_use]
+    pub const fn report(&self) -> &PerfReport {
+        &self.report
+    }
+
+    /// Consumes the harness and returns its report.
+    #[must_use]
+    pub fn into_report(self) -> PerfReport {
+        self.report
+    }
+}
+
+fn duration_ns(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_verdicts() {
+        let mut harness = PerfHarness::new(1, 5).unwrap();
+        let fast = harness.measure("noop", Duration::from_secs(1), || {});
+        assert!(fast.passed);
+        assert_eq!(fast.iterations, 5);
+        assert!(fast.min_ns <= fast.median_ns && fast.median_ns <= fast.p99_ns);
+
+        let slow = harness.measure("sleep", Duration::from_nanos(1), || {
+            std::thread::sleep(Duration::from_micros(50));
+        });
+        assert!(!slow.passed);
+
+        let report = harness.into_report();
+        assert!(!report.passed());
+        assert_eq!(report.failures()[0].name, "sleep");
+        let json: PerfReport = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json, report);
+    }
+
+    #[test]
+    fn test_scale_and_validation() {
+        assert!(PerfHarness::new(0, 0).is_err());
+        assert!(PerfHarness::new(0, 1).unwrap().with_scale(0.0).is_err());
+        let mut harness = PerfHarness::new(0, 1).unwrap().with_scale(2.5).unwrap();
+        let result = harness.measure("scaled", Duration::from_millis(2), || {});
+        assert_eq!(result.budget_ns, 5_000_000);
+    }
+}
@@ -0,0 +1,20 @@
+//! Performance budget module.
+//!
+//! This module provides the [`PerfHarness`], which times named operations
+//! against fixed budgets (e.g. quote generation for 500 contracts under
+//! 2ms) and produces a machine-readable [`PerfReport`] with a pass/fail
+//! verdict per budget.
+//!
+//! The crate's own budgets live in the `perf_budget` bench and run with
+//! `cargo perf`; downstream users can run them on their hardware, or build
+//! a harness over their own hot paths, to catch regressions when upgrading.
+//!
+//! ## Components
+//!
+//! - [`PerfHarness`]: Times operations against named budgets
+//! - [`BudgetResult`]: Measured timings and verdict of one budget
+//! - [`PerfReport`]: All results with an overall verdict, serializable to JSON
+
+mod budget;
+
+pub use budget::{BudgetResult, PerfHarness, PerfReport};
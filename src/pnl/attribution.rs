@@ -0,0 +1,469 @@
+//! P&L attribution module.
+//!
+//! This module provides the [`PnLCalculator`] which decomposes the P&L of a
+//! position over a time window into Greek-driven components using a
+//! second-order Taylor expansion in spot and first-order terms elsewhere.
+
+use super::calendar::ThetaAccrual;
+use super::explain::{ExplainLedger, ExplainMark, ExplainTrade, PnLExplainReport};
+use crate::error::Result;
+use crate::history::Aggregate;
+use crate::inventory::TiedTrade;
+use crate::pricing::Greeks;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Seconds in a calendar day.
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Market changes over an attribution window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarketMove {
+    /// Change in the underlying price.
+    pub spot_change: Decimal,
+    /// Absolute change in implied volatility (0.01 = one vol point).
+    pub vol_change: Decimal,
+    /// Absolute change in the risk-free rate (0.01 = one percentage point).
+    pub rate_change: Decimal,
+}
+
+impl MarketMove {
+    /// Creates a new market move.
+    #[must_use]
+    pub const fn new(spot_change: Decimal, vol_change: Decimal, rate_change: Decimal) -> Self {
+        Self {
+            spot_change,
+            vol_change,
+            rate_change,
+        }
+    }
+}
+
+/// Decomposition of P&L into Greek-driven components.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PnLAttribution {
+    /// P&L explained by delta.
+    pub delta_pnl: Decimal,
+    /// P&L explained by gamma.
+    pub gamma_pnl: Decimal,
+    /// P&L explained by time decay.
+    pub theta_pnl: Decimal,
+    /// P&L explained by volatility changes.
+    pub vega_pnl: Decimal,
+    /// P&L explained by rate changes.
+    pub rho_pnl: Decimal,
+    /// P&L not explained by any Greek.
+    pub unexplained: Decimal,
+    /// Actual P&L over the window.
+    pub total: Decimal,
+}
+
+impl PnLAttribution {
+    /// Returns the sum of the Greek-explained components.
+    #[must_use]
+    pub fn explained(&self) -> Decimal {
+        self.delta_pnl + self.gamma_pnl + self.theta_pnl + self.vega_pnl + self.rho_pnl
+    }
+}
+
+impl Aggregate for PnLAttribution {
+    fn merge(&mut self, other: &Self) {
+        self.delta_pnl += other.delta_pnl;
+        self.gamma_pnl += other.gamma_pnl;
+        self.theta_pnl += other.theta_pnl;
+        self.vega_pnl += other.vega_pnl;
+        self.rho_pnl += other.rho_pnl;
+        self.unexplained += other.unexplained;
+        self.total += other.total;
+    }
+}
+
+impl std::fmt::Display for PnLAttribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "P&L {}: delta={} gamma={} theta={} vega={} rho={} unexplained={}",
+            self.total,
+            self.delta_pnl,
+            self.gamma_pnl,
+            self.theta_pnl,
+            self.vega_pnl,
+            self.rho_pnl,
+            self.unexplained
+        )
+    }
+}
+
+/// Attribution of a tied trade as one package.
+///
+/// The package is attributed with its combined Greeks; the delta P&L of
+/// each leg is reported separately so the offset is visible.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TiedAttribution {
+    /// Attribution of the whole package.
+    pub package: PnLAttribution,
+    /// Delta P&L of the option leg.
+    pub option_delta_pnl: Decimal,
+    /// Delta P&L of the hedge leg.
+    pub hedge_delta_pnl: Decimal,
+}
+
+impl std::fmt::Display for TiedAttribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (option delta={} hedge delta={})",
+            self.package, self.option_delta_pnl, self.hedge_delta_pnl
+        )
+    }
+}
+
+/// Attribution of an option position together with its hedge book.
+///
+/// The options are attributed to their Greeks; the hedge P&L, e.g. the
+/// change in [`HedgeBook::total_pnl`](crate::hedging::HedgeBook::total_pnl)
+/// over the window, is split into its delta P&L and the rest (funding,
+/// fees and trading inside the window).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HedgedAttribution {
+    /// Attribution of the option positions.
+    pub options: PnLAttribution,
+    /// Hedge P&L over the window.
+    pub hedge_pnl: Decimal,
+    /// Hedge P&L explained by the hedge delta held at the start.
+    pub hedge_delta_pnl: Decimal,
+    /// Hedge P&L not explained by its delta.
+    pub hedge_unexplained: Decimal,
+}
+
+impl HedgedAttribution {
+    /// Returns the delta P&L of the options and hedges together.
+    #[must_use]
+    pub fn net_delta_pnl(&self) -> Decimal {
+        self.options.delta_pnl + self.hedge_delta_pnl
+    }
+
+    /// Returns the P&L of the options and hedges together.
+    #[must_use]
+    pub fn total(&self) -> Decimal {
+        self.options.total + self.hedge_pnl
+    }
+}
+
+impl std::fmt::Display for HedgedAttribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (hedge P&L {}: delta={} unexplained={})",
+            self.options, self.hedge_pnl, self.hedge_delta_pnl, self.hedge_unexplained
+        )
+    }
+}
+
+/// Attributes position P&L to Greeks.
+///
+/// Without a [`ThetaAccrual`], theta accrues uniformly in calendar time.
+///
+/// Marks and trades recorded for [`PnLCalculator::explain`] are shared by
+/// clones of the calculator.
+#[derive(Debug, Clone, Default)]
+pub struct PnLCalculator {
+    /// Intraday theta accrual, if configured.
+    theta_accrual: Option<ThetaAccrual>,
+    /// Marks and trades for P&L explain.
+    ledger: Arc<ExplainLedger>,
+}
+
+impl PnLCalculator {
+    /// Creates a calculator that accrues theta in calendar time.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a calculator that accrues theta using a trading calendar.
+    #[must_use]
+    pub fn with_theta_accrual(theta_accrual: ThetaAccrual) -> Self {
+        Self {
+            theta_accrual: Some(theta_accrual),
+            ledger: Arc::default(),
+        }
+    }
+
+    /// Returns the theta accrual, if configured.
+    #[must_use]
+    pub const fn theta_accrual(&self) -> Option<&ThetaAccrual> {
+        self.theta_accrual.as_ref()
+    }
+
+    /// Returns the number of theta days elapsed between two instants.
+    #[must_use]
+    pub fn theta_days(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Decimal {
+        match &self.theta_accrual {
+            Some(accrual) => accrual.accrued_days(from, to),
+            None => {
+                let seconds = (to - from).num_seconds().max(0);
+                Decimal::from(seconds) / Decimal::from(SECONDS_PER_DAY)
+            }
+        }
+    }
+
+    /// Attributes P&L over a window to the position's Greeks.
+    ///
+    /// # Arguments
+    ///
+    /// * `greeks` - Position Greeks at the start of the window
+    /// * `market_move` - Market changes over the window
+    /// * `from` - Start of the window
+    /// * `to` - End of the window
+    /// * `actual_pnl` - Realized P&L over the window
+    #[must_use]
+    pub fn attribute(
+        &self,
+        greeks: &Greeks,
+        market_move: &MarketMove,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        actual_pnl: Decimal,
+    ) -> PnLAttribution {
+        let spot = market_move.spot_change;
+        let mut attribution = PnLAttribution {
+            delta_pnl: greeks.delta * spot,
+            gamma_pnl: dec!(0.5) * greeks.gamma * spot * spot,
+            theta_pnl: greeks.theta * self.theta_days(from, to),
+            vega_pnl: greeks.vega * market_move.vol_change * dec!(100),
+            rho_pnl: greeks.rho * market_move.rate_change * dec!(100),
+            unexplained: Decimal::ZERO,
+            total: actual_pnl,
+        };
+        attribution.unexplained = actual_pnl - attribution.explained();
+        attribution
+    }
+
+    /// Attributes the P&L of a tied trade as one package.
+    ///
+    /// The spot change in `market_move` is measured from the trade's
+    /// reference price, since that is where the hedge leg was booked.
+    ///
+    /// # Arguments
+    ///
+    /// * `trade` - The tied trade
+    /// * `option_greeks` - Greeks of one long option contract at the start of the window
+    /// * `market_move` - Market changes over the window
+    /// * `from` - Start of the window
+    /// * `to` - End of the window
+    /// * `actual_pnl` - Realized P&L of the package over the window
+    #[must_use]
+    pub fn attribute_tied(
+        &self,
+        trade: &TiedTrade,
+        option_greeks: &Greeks,
+        market_move: &MarketMove,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        actual_pnl: Decimal,
+    ) -> TiedAttribution {
+        let package = trade.package_greeks(option_greeks);
+        TiedAttribution {
+            package: self.attribute(&package, market_move, from, to, actual_pnl),
+            option_delta_pnl: option_greeks.delta * trade.option_quantity * market_move.spot_change,
+            hedge_delta_pnl: trade.hedge_quantity * market_move.spot_change,
+        }
+    }
+
+    /// Attributes the P&L of option positions and their hedges.
+    ///
+    /// # Arguments
+    ///
+    /// * `greeks` - Option position Greeks at the start of the window
+    /// * `market_move` - Market changes over the window
+    /// * `from` - Start of the window
+    /// * `to` - End of the window
+    /// * `option_pnl` - Realized P&L of the options over the window
+    /// * `hedge_delta` - Delta of the hedge positions at the start of the window
+    /// * `hedge_pnl` - P&L of the hedge positions over the window
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn attribute_hedged(
+        &self,
+        greeks: &Greeks,
+        market_move: &MarketMove,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        option_pnl: Decimal,
+        hedge_delta: Decimal,
+        hedge_pnl: Decimal,
+    ) -> HedgedAttribution {
+        let hedge_delta_pnl = hedge_delta * market_move.spot_change;
+        HedgedAttribution {
+            options: self.attribute(greeks, market_move, from, to, option_pnl),
+            hedge_pnl,
+            hedge_delta_pnl,
+            hedge_unexplained: hedge_pnl - hedge_delta_pnl,
+        }
+    }
+
+    /// Records the position and market state of a contract for P&L explain.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the mark is older than the
+    /// contract's last mark.
+    pub fn record_mark(&self, mark: ExplainMark) -> Result<()> {
+        self.ledger.record_mark(mark)
+    }
+
+    /// Records one of our trades for P&L explain.
+    pub fn record_trade(&self, trade: ExplainTrade) {
+        self.ledger.record_trade(trade);
+    }
+
+    /// Explains P&L over a window from the recorded marks and trades.
+    ///
+    /// Contracts with fewer than two marks in the window are omitted.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_ms` - Window start in milliseconds
+    /// * `end_ms` - Window end in milliseconds
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the window ends before it starts.
+    pub fn explain(&self, start_ms: u64, end_ms: u64) -> Result<PnLExplainReport> {
+        self.ledger.explain(self, start_ms, end_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pnl::{ThetaAccrualConfig, TradingCalendar};
+    use chrono::{NaiveTime, TimeZone};
+
+    fn at(d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, d, h, min, 0).unwrap()
+    }
+
+    fn greeks() -> Greeks {
+        Greeks::new(dec!(0.5), dec!(0.02), dec!(-1.44), dec!(0.3), dec!(0.1))
+    }
+
+    #[test]
+    fn test_attribute_components() {
+        let calculator = PnLCalculator::new();
+        let market_move = MarketMove::new(dec!(2), dec!(0.01), dec!(0.0025));
+
+        let attribution =
+            calculator.attribute(&greeks(), &market_move, at(4, 0, 0), at(5, 0, 0), dec!(0));
+
+        assert_eq!(attribution.delta_pnl, dec!(1.0));
+        assert_eq!(attribution.gamma_pnl, dec!(0.04));
+        assert_eq!(attribution.theta_pnl, dec!(-1.44));
+        assert_eq!(attribution.vega_pnl, dec!(0.3));
+        assert_eq!(attribution.rho_pnl, dec!(0.025));
+        assert_eq!(attribution.explained() + attribution.unexplained, dec!(0));
+    }
+
+    #[test]
+    fn test_attribute_tied_trade() {
+        let calculator = PnLCalculator::new();
+        let trade = TiedTrade::new(
+            "T1",
+            "SPX-C-5000",
+            dec!(10),
+            dec!(42),
+            dec!(0.5),
+            "SPX-FUT",
+            dec!(5000),
+        );
+        let market_move = MarketMove::new(dec!(2), Decimal::ZERO, Decimal::ZERO);
+
+        let attribution = calculator.attribute_tied(
+            &trade,
+            &greeks(),
+            &market_move,
+            at(4, 0, 0),
+            at(4, 0, 0),
+            dec!(0.4),
+        );
+
+        assert_eq!(attribution.option_delta_pnl, dec!(10));
+        assert_eq!(attribution.hedge_delta_pnl, dec!(-10));
+        assert_eq!(attribution.package.delta_pnl, Decimal::ZERO);
+        assert_eq!(attribution.package.gamma_pnl, dec!(0.4));
+        assert_eq!(attribution.package.unexplained, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_calendar_time_theta_is_intraday() {
+        let calculator = PnLCalculator::new();
+
+        let attribution = calculator.attribute(
+            &greeks(),
+            &MarketMove::default(),
+            at(4, 12, 0),
+            at(4, 12, 10),
+            dec!(-0.01),
+        );
+
+        assert_eq!(attribution.theta_pnl.round_dp(10), dec!(-0.01));
+        assert_eq!(attribution.unexplained.round_dp(10), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_theta_accrual_spreads_decay() {
+        let calendar = TradingCalendar::new(
+            NaiveTime::from_hms_opt(14, 30, 0).unwrap(),
+            NaiveTime::from_hms_opt(21, 0, 0).unwrap(),
+        );
+        let config = ThetaAccrualConfig {
+            overnight_weight: Decimal::ZERO,
+            weekend_weight: Decimal::ZERO,
+            ..ThetaAccrualConfig::default()
+        };
+        let calculator =
+            PnLCalculator::with_theta_accrual(ThetaAccrual::new(calendar, config).unwrap());
+
+        let overnight = calculator.theta_days(at(4, 22, 0), at(5, 14, 0));
+        let session = calculator.theta_days(at(5, 14, 30), at(5, 21, 0));
+
+        assert_eq!(overnight, Decimal::ZERO);
+        assert_eq!(session.round_dp(10), dec!(1.4));
+        assert!(calculator.theta_accrual().is_some());
+    }
+
+    #[test]
+    fn test_hedged_attribution_nets_delta() {
+        let calculator = PnLCalculator::new();
+        let greeks = Greeks {
+            delta: dec!(10),
+            ..Greeks::zero()
+        };
+        // Short 10 units of the underlying, with 0.5 of funding received.
+        let attribution = calculator.attribute_hedged(
+            &greeks,
+            &MarketMove::new(dec!(2), Decimal::ZERO, Decimal::ZERO),
+            at(4, 12, 0),
+            at(4, 12, 0),
+            dec!(20),
+            dec!(-10),
+            dec!(-19.5),
+        );
+        assert_eq!(attribution.net_delta_pnl(), Decimal::ZERO);
+        assert_eq!(attribution.hedge_unexplained, dec!(0.5));
+        assert_eq!(attribution.total(), dec!(0.5));
+    }
+
+    #[test]
+    fn test_attribution_display() {
+        let attribution = PnLAttribution {
+            total: dec!(5),
+            ..PnLAttribution::default()
+        };
+        assert!(attribution.to_string().contains("P&L 5"));
+    }
+}
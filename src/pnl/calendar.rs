@@ -0,0 +1,471 @@
+//! Trading calendar and theta accrual module.
+//!
+//! This module provides the [`TradingCalendar`] used to classify time as
+//! session, overnight or non-trading, and [`ThetaAccrual`] which converts a
+//! time window into a weighted number of theta days.
+
+use crate::error::{Error, Result};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, Timelike, Utc, Weekday};
+use rust_decimal::Decimal;
+use std::collections::BTreeSet;
+
+/// Minutes in a calendar day.
+const MINUTES_PER_DAY: i64 = 1440;
+
+/// Trading calendar describing session hours, weekend days and holidays.
+///
+/// Session hours are expressed in UTC and may not cross midnight; a close at or
+/// before the open runs the session to midnight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TradingCalendar {
+    /// Session open as minutes after midnight UTC.
+    open_minute: u32,
+    /// Session close as minutes after midnight UTC.
+    close_minute: u32,
+    /// Days of the week without a session.
+    weekend: Vec<Weekday>,
+    /// Dates without a session.
+    holidays: BTreeSet<NaiveDate>,
+}
+
+impl TradingCalendar {
+    /// Creates a calendar with the given session hours and a Saturday/Sunday weekend.
+    ///
+    /// # Arguments
+    ///
+    /// * `open` - Session open time (UTC)
+    /// * `close` - Session close time (UTC)
+    #[must_use]
+    pub fn new(open: NaiveTime, close: NaiveTime) -> Self {
+        let open_minute = open.num_seconds_from_midnight() / 60;
+        let mut close_minute = close.num_seconds_from_midnight() / 60;
+        if close_minute <= open_minute {
+            close_minute = MINUTES_PER_DAY as u32;
+        }
+        Self {
+            open_minute,
+            close_minute,
+            weekend: vec![Weekday::Sat, Weekday::Sun],
+            holidays: BTreeSet::new(),
+        }
+    }
+
+    /// Creates a calendar that trades around the clock every day.
+    #[must_use]
+    pub fn continuous() -> Self {
+        Self {
+            open_minute: 0,
+            close_minute: MINUTES_PER_DAY as u32,
+            weekend: Vec::new(),
+            holidays: BTreeSet::new(),
+        }
+    }
+
+    /// Adds a holiday to the calendar.
+    #[must_use]
+    pub fn with_holiday(mut self, date: NaiveDate) -> Self {
+        self.holidays.insert(date);
+        self
+    }
+
+    /// Replaces the days of the week without a session.
+    #[must_use]
+    pub fn with_weekend(mut self, days: Vec<Weekday>) -> Self {
+        self.weekend = days;
+        self
+    }
+
+    /// Returns the number of session minutes in a trading day.
+    #[must_use]
+    pub const fn session_minutes(&self) -> u32 {
+        self.close_minute - self.open_minute
+    }
+
+    /// Returns true if the date has a session.
+    #[must_use]
+    pub fn is_trading_day(&self, date: NaiveDate) -> bool {
+        !self.weekend.contains(&date.weekday()) && !self.holidays.contains(&date)
+    }
+
+    /// Returns the number of distinct weekdays without a session.
+    fn weekend_days(&self) -> usize {
+        [
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+            Weekday::Sat,
+            Weekday::Sun,
+        ]
+        .iter()
+        .filter(|day| self.weekend.contains(day))
+        .count()
+    }
+
+    /// Returns the number of session minutes within `[start, end)` minutes of a day.
+    fn session_overlap(&self, start: i64, end: i64) -> i64 {
+        let lo = start.max(i64::from(self.open_minute));
+        let hi = end.min(i64::from(self.close_minute));
+        (hi - lo).max(0)
+    }
+}
+
+/// Time slice at which theta is accrued.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AccrualGranularity {
+    /// Accrue every minute.
+    #[default]
+    Minute,
+    /// Accrue every hour.
+    Hour,
+}
+
+impl AccrualGranularity {
+    /// Returns the slice length in minutes.
+    #[must_use]
+    pub const fn minutes(&self) -> i64 {
+        match self {
+            Self::Minute => 1,
+            Self::Hour => 60,
+        }
+    }
+}
+
+/// Weighting configuration for intraday theta accrual.
+///
+/// Session time always has weight one. The defaults weight every minute
+/// equally, which reproduces plain calendar-time accrual.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThetaAccrualConfig {
+    /// Weight of time outside the session on trading days.
+    pub overnight_weight: Decimal,
+    /// Weight of time on weekends and holidays.
+    pub weekend_weight: Decimal,
+    /// Slice at which time is accrued.
+    pub granularity: AccrualGranularity,
+}
+
+impl Default for ThetaAccrualConfig {
+    fn default() -> Self {
+        Self {
+            overnight_weight: Decimal::ONE,
+            weekend_weight: Decimal::ONE,
+            granularity: AccrualGranularity::Minute,
+        }
+    }
+}
+
+impl ThetaAccrualConfig {
+    /// Validates the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if a weight is negative.
+    pub fn validate(&self) -> Result<()> {
+        if self.overnight_weight.is_sign_negative() {
+            return Err(Error::configuration(
+                "overnight_weight must be non-negative",
+            ));
+        }
+        if self.weekend_weight.is_sign_negative() {
+            return Err(Error::configuration("weekend_weight must be non-negative"));
+        }
+        Ok(())
+    }
+}
+
+/// Converts time windows into weighted theta days.
+///
+/// Weights are normalized so that a standard week (seven calendar days without
+/// holidays) accrues exactly seven days of theta, whatever the weights. The
+/// distribution within the week follows the weights: with a zero weekend
+/// weight, the weekend's decay is spread across the trading days.
+#[derive(Debug, Clone)]
+pub struct ThetaAccrual {
+    /// Calendar used to classify time.
+    calendar: TradingCalendar,
+    /// Weighting configuration.
+    config: ThetaAccrualConfig,
+    /// Weighted minutes in an average calendar day.
+    weighted_minutes_per_day: Decimal,
+}
+
+impl ThetaAccrual {
+    /// Creates a new theta accrual.
+    ///
+    /// # Arguments
+    ///
+    /// * `calendar` - Trading calendar
+    /// * `config` - Weighting configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the configuration is invalid or
+    /// gives a standard week zero total weight.
+    pub fn new(calendar: TradingCalendar, config: ThetaAccrualConfig) -> Result<Self> {
+        config.validate()?;
+
+        let session = Decimal::from(calendar.session_minutes());
+        let off_session = Decimal::from(MINUTES_PER_DAY) - session;
+        let trading_day = session + off_session * config.overnight_weight;
+        let non_trading_day = Decimal::from(MINUTES_PER_DAY) * config.weekend_weight;
+        let non_trading_days = calendar.weekend_days();
+        let week = trading_day * Decimal::from(7 - non_trading_days)
+            + non_trading_day * Decimal::from(non_trading_days);
+
+        if week.is_zero() {
+            return Err(Error::configuration(
+                "theta accrual weights give a standard week zero weight",
+            ));
+        }
+
+        Ok(Self {
+            calendar,
+            config,
+            weighted_minutes_per_day: week / Decimal::from(7),
+        })
+    }
+
+    /// Returns the trading calendar.
+    #[must_use]
+    pub const fn calendar(&self) -> &TradingCalendar {
+        &self.calendar
+    }
+
+    /// Returns the weighting configuration.
+    #[must_use]
+    pub const fn config(&self) -> &ThetaAccrualConfig {
+        &self.config
+    }
+
+    /// Returns the weighted number of theta days between two instants.
+    ///
+    /// Both instants are floored to the configured granularity. Returns zero
+    /// if `to` is not after `from`.
+    #[must_use]
+    pub fn accrued_days(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Decimal {
+        let slice = self.config.granularity.minutes();
+        let from_min = floor_minutes(from, slice);
+        let to_min = floor_minutes(to, slice);
+        if to_min <= from_min {
+            return Decimal::ZERO;
+        }
+
+        let mut weighted = Decimal::ZERO;
+        let first_day = from_min.div_euclid(MINUTES_PER_DAY);
+        let last_day = (to_min - 1).div_euclid(MINUTES_PER_DAY);
+
+        for day in first_day..=last_day {
+            let day_start = day * MINUTES_PER_DAY;
+            let start = from_min.max(day_start) - day_start;
+            let end = to_min.min(day_start + MINUTES_PER_DAY) - day_start;
+            let Some(date) = DateTime::from_timestamp(day * 86_400, 0).map(|d| d.date_naive())
+            else {
+                continue;
+            };
+
+            if self.calendar.is_trading_day(date) {
+                let session = self.calendar.session_overlap(start, end);
+                let overnight = (end - start) - session;
+                weighted += Decimal::from(session)
+                    + Decimal::from(overnight) * self.config.overnight_weight;
+            } else {
+                weighted += Decimal::from(end - start) * self.config.weekend_weight;
+            }
+        }
+
+        weighted / self.weighted_minutes_per_day
+    }
+
+    /// Returns the theta accrued between two instants.
+    ///
+    /// # Arguments
+    ///
+    /// * `theta_per_day` - Theta per calendar day
+    /// * `from` - Start of the window
+    /// * `to` - End of the window
+    #[must_use]
+    pub fn accrue(
+        &self,
+        theta_per_day: Decimal,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Decimal {
+        theta_per_day * self.accrued_days(from, to)
+    }
+}
+
+/// Returns minutes since the Unix epoch floored to a slice length.
+fn floor_minutes(instant: DateTime<Utc>, slice: i64) -> i64 {
+    let minutes = instant.timestamp().div_euclid(60);
+    minutes - minutes.rem_euclid(slice)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn equity_calendar() -> TradingCalendar {
+        TradingCalendar::new(
+            NaiveTime::from_hms_opt(14, 30, 0).unwrap(),
+            NaiveTime::from_hms_opt(21, 0, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_default_config_is_calendar_time() {
+        let accrual = ThetaAccrual::new(equity_calendar(), ThetaAccrualConfig::default()).unwrap();
+
+        assert_eq!(
+            accrual.accrued_days(at(2024, 3, 4, 0, 0), at(2024, 3, 5, 0, 0)),
+            Decimal::ONE
+        );
+        assert_eq!(
+            accrual.accrued_days(at(2024, 3, 4, 0, 0), at(2024, 3, 4, 6, 0)),
+            dec!(0.25)
+        );
+    }
+
+    #[test]
+    fn test_week_accrues_seven_days() {
+        let config = ThetaAccrualConfig {
+            overnight_weight: dec!(0.2),
+            weekend_weight: Decimal::ZERO,
+            granularity: AccrualGranularity::Minute,
+        };
+        let accrual = ThetaAccrual::new(equity_calendar(), config).unwrap();
+
+        let days = accrual.accrued_days(at(2024, 3, 4, 0, 0), at(2024, 3, 11, 0, 0));
+        assert_eq!(days.round_dp(10), dec!(7));
+    }
+
+    #[test]
+    fn test_weekend_weighting() {
+        let config = ThetaAccrualConfig {
+            weekend_weight: Decimal::ZERO,
+            ..ThetaAccrualConfig::default()
+        };
+        let accrual = ThetaAccrual::new(equity_calendar(), config).unwrap();
+
+        // 2024-03-09 is a Saturday.
+        let weekend = accrual.accrued_days(at(2024, 3, 9, 0, 0), at(2024, 3, 11, 0, 0));
+        let monday = accrual.accrued_days(at(2024, 3, 11, 0, 0), at(2024, 3, 12, 0, 0));
+
+        assert_eq!(weekend, Decimal::ZERO);
+        assert_eq!(monday.round_dp(10), dec!(1.4));
+    }
+
+    #[test]
+    fn test_holiday_uses_weekend_weight() {
+        let holiday = NaiveDate::from_ymd_opt(2024, 3, 6).unwrap();
+        let config = ThetaAccrualConfig {
+            weekend_weight: Decimal::ZERO,
+            ..ThetaAccrualConfig::default()
+        };
+        let accrual = ThetaAccrual::new(equity_calendar().with_holiday(holiday), config).unwrap();
+
+        assert!(!accrual.calendar().is_trading_day(holiday));
+        assert_eq!(
+            accrual.accrued_days(at(2024, 3, 6, 0, 0), at(2024, 3, 7, 0, 0)),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn test_no_jump_at_daily_boundary() {
+        let config = ThetaAccrualConfig {
+            overnight_weight: dec!(0.5),
+            ..ThetaAccrualConfig::default()
+        };
+        let accrual = ThetaAccrual::new(equity_calendar(), config).unwrap();
+
+        let before = accrual.accrued_days(at(2024, 3, 4, 23, 58), at(2024, 3, 4, 23, 59));
+        let across = accrual.accrued_days(at(2024, 3, 4, 23, 59), at(2024, 3, 5, 0, 0));
+        let after = accrual.accrued_days(at(2024, 3, 5, 0, 0), at(2024, 3, 5, 0, 1));
+
+        assert_eq!(before, across);
+        assert_eq!(across, after);
+    }
+
+    #[test]
+    fn test_intraday_windows_sum_to_whole() {
+        let config = ThetaAccrualConfig {
+            overnight_weight: dec!(0.3),
+            ..ThetaAccrualConfig::default()
+        };
+        let accrual = ThetaAccrual::new(equity_calendar(), config).unwrap();
+
+        let whole = accrual.accrued_days(at(2024, 3, 4, 10, 0), at(2024, 3, 5, 18, 0));
+        let parts = accrual.accrued_days(at(2024, 3, 4, 10, 0), at(2024, 3, 4, 15, 7))
+            + accrual.accrued_days(at(2024, 3, 4, 15, 7), at(2024, 3, 5, 18, 0));
+
+        assert_eq!(whole, parts);
+    }
+
+    #[test]
+    fn test_hour_granularity_floors() {
+        let config = ThetaAccrualConfig {
+            granularity: AccrualGranularity::Hour,
+            ..ThetaAccrualConfig::default()
+        };
+        let accrual = ThetaAccrual::new(TradingCalendar::continuous(), config).unwrap();
+
+        assert_eq!(
+            accrual.accrued_days(at(2024, 3, 4, 10, 5), at(2024, 3, 4, 10, 55)),
+            Decimal::ZERO
+        );
+        assert_eq!(
+            accrual.accrued_days(at(2024, 3, 4, 10, 55), at(2024, 3, 4, 11, 5)),
+            Decimal::ONE / Decimal::from(24)
+        );
+    }
+
+    #[test]
+    fn test_accrue_scales_theta() {
+        let accrual =
+            ThetaAccrual::new(TradingCalendar::continuous(), ThetaAccrualConfig::default())
+                .unwrap();
+
+        assert_eq!(
+            accrual
+                .accrue(dec!(-24), at(2024, 3, 4, 0, 0), at(2024, 3, 4, 1, 0))
+                .round_dp(10),
+            dec!(-1)
+        );
+        assert_eq!(
+            accrual.accrue(dec!(-24), at(2024, 3, 4, 1, 0), at(2024, 3, 4, 0, 0)),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let negative = ThetaAccrualConfig {
+            overnight_weight: dec!(-0.1),
+            ..ThetaAccrualConfig::default()
+        };
+        assert!(ThetaAccrual::new(equity_calendar(), negative).is_err());
+
+        let zero_week = ThetaAccrualConfig {
+            weekend_weight: Decimal::ZERO,
+            ..ThetaAccrualConfig::default()
+        };
+        let all_weekend = TradingCalendar::continuous().with_weekend(vec![
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+            Weekday::Sat,
+            Weekday::Sun,
+        ]);
+        assert!(ThetaAccrual::new(all_weekend, zero_week).is_err());
+    }
+}
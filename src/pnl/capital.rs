@@ -0,0 +1,350 @@
+//! Capital attribution module.
+//!
+//! This module provides the [`CapitalTracker`], which records margin
+//! estimates and cumulative P&L per underlying and strategy tag over time
+//! and reports capital usage and return on capital, so limits can be
+//! allocated to where risk-adjusted returns are best.
+//!
+//! ## Metrics
+//!
+//! Margin is held from one sample to the next, so capital usage over a
+//! window is the time-weighted average margin:
+//!
+//! ```text
+//! average capital   = sum(margin_i * (t_{i+1} - t_i)) / (t_last - t_first)
+//! P&L               = cumulative P&L at t_last - cumulative P&L at t_first
+//! return on capital = P&L / average capital
+//! return on peak    = P&L / peak margin
+//! ```
+
+use crate::error::{Error, Result};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Underlying and strategy tag capital is attributed to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CapitalKey {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Strategy tag.
+    pub strategy: String,
+}
+
+impl CapitalKey {
+    /// Creates a key.
+    #[must_use]
+    pub fn new(underlying: impl Into<String>, strategy: impl Into<String>) -> Self {
+        Self {
+            underlying: underlying.into(),
+            strategy: strategy.into(),
+        }
+    }
+}
+
+/// Margin and cumulative P&L of a key at one time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapitalSample {
+    /// Sample time in milliseconds.
+    pub timestamp_ms: u64,
+    /// Estimated margin requirement.
+    pub margin: Decimal,
+    /// Cumulative P&L.
+    pub cumulative_pnl: Decimal,
+}
+
+/// Capital usage and return of one key, or of a rollup of keys.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapitalUsage {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Strategy tag, `None` for an underlying rollup.
+    pub strategy: Option<String>,
+    /// Time-weighted average margin.
+    pub average_capital: Decimal,
+    /// Highest margin sampled; summed over keys in a rollup.
+    pub peak_capital: Decimal,
+    /// P&L over the window.
+    pub pnl: Decimal,
+}
+
+impl CapitalUsage {
+    /// Returns P&L per unit of average capital, if any capital was used.
+    #[must_use]
+    pub fn return_on_capital(&self) -> Option<Decimal> {
+        (self.average_capital > Decimal::ZERO).then(|| self.pnl / self.average_capital)
+    }
+
+    /// Returns P&L per unit of peak capital, if any capital was used.
+    #[must_use]
+    pub fn return_on_peak(&self) -> Option<Decimal> {
+        (self.peak_capital > Decimal::ZERO).then(|| self.pnl / self.peak_capital)
+    }
+
+    fn absorb(&mut self, other: &Self) {
+        self.average_capital += other.average_capital;
+        self.peak_capital += other.peak_capital;
+        self.pnl += other.pnl;
+    }
+}
+
+/// Capital usage over a window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapitalReport {
+    /// Window start in milliseconds.
+    pub from_ms: u64,
+    /// Window end in milliseconds.
+    pub to_ms: u64,
+    /// Usage per underlying and strategy.
+    pub strategies: Vec<CapitalUsage>,
+    /// Usage per underlying, summed over strategies.
+    pub underlyings: Vec<CapitalUsage>,
+}
+
+impl CapitalReport {
+    /// Returns the strategies by decreasing return on capital; those
+    /// without capital come last.
+    #[must_use]
+    pub fn ranked(&self) -> Vec<&CapitalUsage> {
+        let mut ranked: Vec<&CapitalUsage> = self.strategies.iter().collect();
+        ranked.sort_by_key(|u| std::cmp::Reverse(u.return_on_capital()));
+        ranked
+    }
+
+    /// Returns the usage summed over every key.
+    #[must_use]
+    pub fn total(&self) -> CapitalUsage {
+        let mut total = CapitalUsage {
+            underlying: String::new(),
+            strategy: None,
+            average_capital: Decimal::ZERO,
+            peak_capital: Decimal::ZERO,
+            pnl: Decimal::ZERO,
+        };
+        for usage in &self.underlyings {
+            total.absorb(usage);
+        }
+        total
+    }
+}
+
+/// Margin and P&L history per underlying and strategy.
+#[derive(Default)]
+pub struct CapitalTracker {
+    /// Samples per key, in time order.
+    samples: Mutex<BTreeMap<CapitalKey, Vec<CapitalSample>>>,
+}
+
+impl CapitalTracker {
+    /// Creates an empty tracker.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the margin estimate and cumulative P&L of a key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Underlying and strategy tag
+    /// * `timestamp_ms` - Sample time in milliseconds
+    /// * `margin` - Estimated margin requirement
+    /// * `cumulative_pnl` - Cumulative P&L of the key
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the margin is negative or the
+    /// sample is older than the key's last sample.
+    pub fn record(
+        &self,
+        key: CapitalKey,
+        timestamp_ms: u64,
+        margin: Decimal,
+        cumulative_pnl: Decimal,
+    ) -> Result<()> {
+        if margin < Decimal::ZERO {
+            return Err(Error::validation(format!(
+                "negative margin {margin} for {}/{}",
+                key.underlying, key.strategy
+            )));
+        }
+        let mut samples = self.lock();
+        let history = samples.entry(key).or_default();
+        if history
+            .last()
+            .is_some_and(|s| s.timestamp_ms > timestamp_ms)
+        {
+            return Err(Error::validation(format!(
+                "capital sample at {timestamp_ms} is out of order"
+            )));
+        }
+        history.push(CapitalSample {
+            timestamp_ms,
+            margin,
+            cumulative_pnl,
+        });
+        Ok(())
+    }
+
+    /// Returns the recorded keys.
+    #[must_use]
+    pub fn keys(&self) -> Vec<CapitalKey> {
+        self.lock().keys().cloned().collect()
+    }
+
+    /// Returns the samples of a key.
+    #[must_use]
+    pub fn samples(&self, key: &CapitalKey) -> Vec<CapitalSample> {
+        self.lock().get(key).cloned().unwrap_or_default()
+    }
+
+    /// Reports capital usage and return over a window.
+    ///
+    /// Keys with no sample in the window are omitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the window ends before it starts.
+    pub fn report(&self, from_ms: u64, to_ms: u64) -> Result<CapitalReport> {
+        if to_ms < from_ms {
+            return Err(Error::validation(
+                "capital report window ends before it starts",
+            ));
+        }
+        let mut strategies = Vec::new();
+        let mut underlyings: BTreeMap<&str, CapitalUsage> = BTreeMap::new();
+        let samples = self.lock();
+        for (key, history) in samples.iter() {
+            let window: Vec<&CapitalSample> = history
+                .iter()
+                .filter(|s| (from_ms..=to_ms).contains(&s.timestamp_ms))
+                .collect();
+            let Some(usage) = usage(key, &window) else {
+                continue;
+            };
+            underlyings
+                .entry(&key.underlying)
+                .or_insert_with(|| CapitalUsage {
+                    underlying: key.underlying.clone(),
+                    strategy: None,
+                    average_capital: Decimal::ZERO,
+                    peak_capital: Decimal::ZERO,
+                    pnl: Decimal::ZERO,
+                })
+                .absorb(&usage);
+            strategies.push(usage);
+        }
+        Ok(CapitalReport {
+            from_ms,
+            to_ms,
+            strategies,
+            underlyings: underlyings.into_values().collect(),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<CapitalKey, Vec<CapitalSample>>> {
+        self.samples
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Returns the usage of one key from its samples in a window.
+fn usage(key: &CapitalKey, window: &[&CapitalSample]) -> Option<CapitalUsage> {
+    let (first, last) = (window.first()?, window.last()?);
+    let span = last.timestamp_ms - first.timestamp_ms;
+    let average_capital = if span == 0 {
+        last.margin
+    } else {
+        window
+            .windows(2)
+            .map(|w| w[0].margin * Decimal::from(w[1].timestamp_ms - w[0].timestamp_ms))
+            .sum::<Decimal>()
+            / Decimal::from(span)
+    };
+    Some(CapitalUsage {
+        underlying: key.underlying.clone(),
+        strategy: Some(key.strategy.clone()),
+        average_capital,
+        peak_capital: window.iter().map(|s| s.margin).max().unwrap_or_default(),
+        pnl: last.cumulative_pnl - first.cumulative_pnl,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_time_weighted_capital_and_return() {
+        let tracker = CapitalTracker::new();
+        let key = CapitalKey::new("BTC", "vol-selling");
+        // 100 held for 1h, then 300 for 3h.
+        tracker.record(key.clone(), 0, dec!(100), dec!(0)).unwrap();
+        tracker
+            .record(key.clone(), 3_600_000, dec!(300), dec!(10))
+            .unwrap();
+        tracker
+            .record(key.clone(), 14_400_000, dec!(50), dec!(50))
+            .unwrap();
+
+        let report = tracker.report(0, 14_400_000).unwrap();
+        let usage = &report.strategies[0];
+        assert_eq!(usage.average_capital, dec!(250));
+        assert_eq!(usage.peak_capital, dec!(300));
+        assert_eq!(usage.pnl, dec!(50));
+        assert_eq!(usage.return_on_capital(), Some(dec!(0.2)));
+
+        assert!(tracker.record(key, 1_000, dec!(10), dec!(0)).is_err());
+        assert!(
+            tracker
+                .record(CapitalKey::new("BTC", "x"), 0, dec!(-1), dec!(0))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_rollup_and_ranking() {
+        let tracker = CapitalTracker::new();
+        let samples = [
+            ("BTC", "dispersion", dec!(100), dec!(5)),
+            ("BTC", "vol-selling", dec!(200), dec!(30)),
+            ("ETH", "vol-selling", dec!(50), dec!(-2)),
+        ];
+        for (underlying, strategy, margin, pnl) in samples {
+            let key = CapitalKey::new(underlying, strategy);
+            tracker
+                .record(key.clone(), 0, margin, Decimal::ZERO)
+                .unwrap();
+            tracker.record(key, 1_000, margin, pnl).unwrap();
+        }
+
+        let report = tracker.report(0, 1_000).unwrap();
+        let ranked: Vec<(&str, Option<&str>)> = report
+            .ranked()
+            .iter()
+            .map(|u| (u.underlying.as_str(), u.strategy.as_deref()))
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![
+                ("BTC", Some("vol-selling")),
+                ("BTC", Some("dispersion")),
+                ("ETH", Some("vol-selling")),
+            ]
+        );
+        let btc = &report.underlyings[0];
+        assert_eq!((btc.average_capital, btc.pnl), (dec!(300), dec!(35)));
+        assert_eq!(report.total().pnl, dec!(33));
+        assert!(
+            tracker
+                .report(0, 500)
+                .unwrap()
+                .strategies
+                .iter()
+                .all(|u| u.pnl.is_zero())
+        );
+    }
+}
@@ -0,0 +1,363 @@
+//! Theoretical edge module.
+//!
+//! This module provides the [`EdgeTracker`], which measures the edge
+//! captured on each of our fills: how far the fill price was from the
+//! theoretical value at execution time, in our favour.
+//!
+//! ```text
+//! edge = (theo - price) * quantity    for our buys
+//! edge = (price - theo) * quantity    for our sells
+//! ```
+//!
+//! Edge is accumulated per contract, per strike bucket and per side of the
+//! counterparty, so a desk can see whether it earns its spread and where it
+//! gives it back.
+//!
+//! ## Retention
+//!
+//! The tracker is [`Compactable`]. Compaction drops the per-contract totals
+//! of contracts a day past their expiration date, whose edge stays in the
+//! other groupings, and compacts the optional edge time series kept with
+//! [`EdgeTracker::with_history`].
+
+use crate::error::{Error, Result};
+use crate::history::{Aggregate, Compactable, CompactionStats, HistoryStore, RetentionPolicy};
+use crate::inventory::ChainCoordinates;
+use orderbook_rs::Side;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// One of our fills with the theo at the time of execution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeFill {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Our side.
+    pub side: Side,
+    /// Filled quantity, positive.
+    pub quantity: Decimal,
+    /// Fill price.
+    pub price: Decimal,
+    /// Theoretical value at execution time.
+    pub theo: Decimal,
+    /// Fill time in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+impl EdgeFill {
+    /// Returns the edge captured by the fill.
+    #[must_use]
+    pub fn edge(&self) -> Decimal {
+        let per_contract = match self.side {
+            Side::Buy => self.theo - self.price,
+            Side::Sell => self.price - self.theo,
+        };
+        per_contract * self.quantity
+    }
+}
+
+/// Edge accumulated over a set of fills.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeSummary {
+    /// Number of fills.
+    pub fills: u64,
+    /// Contracts filled.
+    pub quantity: Decimal,
+    /// Edge captured.
+    pub edge: Decimal,
+    /// Notional traded at theo.
+    pub theo_notional: Decimal,
+}
+
+impl EdgeSummary {
+    /// Adds a fill.
+    pub fn add(&mut self, fill: &EdgeFill) {
+        self.fills += 1;
+        self.quantity += fill.quantity;
+        self.edge += fill.edge();
+        self.theo_notional += fill.theo.abs() * fill.quantity;
+    }
+
+    /// Returns the edge per contract filled, if any.
+    #[must_use]
+    pub fn edge_per_contract(&self) -> Option<Decimal> {
+        (!self.quantity.is_zero()).then(|| self.edge / self.quantity)
+    }
+
+    /// Returns the edge as a fraction of the notional at theo, if any.
+    #[must_use]
+    pub fn edge_ratio(&self) -> Option<Decimal> {
+        (!self.theo_notional.is_zero()).then(|| self.edge / self.theo_notional)
+    }
+}
+
+impl Aggregate for EdgeSummary {
+    fn merge(&mut self, other: &Self) {
+        self.fills += other.fills;
+        self.quantity += other.quantity;
+        self.edge += other.edge;
+        self.theo_notional += other.theo_notional;
+    }
+}
+
+/// Edge totals by grouping.
+#[derive(Debug, Default)]
+struct Ledger {
+    /// All fills.
+    total: EdgeSummary,
+    /// By contract symbol.
+    by_symbol: BTreeMap<String, EdgeSummary>,
+    /// By lower bound of the strike bucket.
+    by_strike_bucket: BTreeMap<u64, EdgeSummary>,
+    /// Fills where the counterparty bought (we sold).
+    counterparty_buys: EdgeSummary,
+    /// Fills where the counterparty sold (we bought).
+    counterparty_sells: EdgeSummary,
+}
+
+/// Accumulates edge captured versus theo per fill.
+pub struct EdgeTracker {
+    /// Width of a strike bucket.
+    strike_bucket_width: u64,
+    /// Accumulated edge.
+    ledger: Mutex<Ledger>,
+    /// Edge of each fill over time, if kept.
+    history: Option<HistoryStore<EdgeSummary>>,
+}
+
+impl EdgeTracker {
+    /// Creates an empty tracker.
+    ///
+    /// # Arguments
+    ///
+    /// * `strike_bucket_width` - Width of the strike buckets, in strike
+    ///   units; a width of one buckets by strike
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the width is zero.
+    pub fn new(strike_bucket_width: u64) -> Result<Self> {
+        if strike_bucket_width == 0 {
+            return Err(Error::configuration("strike bucket width must be positive"));
+        }
+        Ok(Self {
+            strike_bucket_width,
+            ledger: Mutex::new(Ledger::default()),
+            history: None,
+        })
+    }
+
+    /// Keeps the edge of each fill as a time series, rolled up and evicted
+    /// by `policy` on compaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the policy is invalid.
+    pub fn with_history(mut self, policy: RetentionPolicy) -> Result<Self> {
+        self.history = Some(HistoryStore::new("edge", policy)?);
+        Ok(self)
+    }
+
+    /// Returns the edge time series, if kept.
+    #[must_use]
+    pub fn history(&self) -> Option<&HistoryStore<EdgeSummary>> {
+        self.history.as_ref()
+    }
+
+    /// Returns the width of the strike buckets.
+    #[must_use]
+    pub const fn strike_bucket_width(&self) -> u64 {
+        self.strike_bucket_width
+    }
+
+    /// Records a fill and returns the edge it captured.
+    ///
+    /// Fills whose symbol has no strike, e.g. hedge instruments, count in
+    /// every grouping except the strike buckets. Fills without a positive
+    /// quantity are ignored.
+    pub fn record(&self, fill: &EdgeFill) -> Decimal {
+        if fill.quantity <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+        let bucket = ChainCoordinates::parse(&fill.symbol)
+            .map(|c| c.strike / self.strike_bucket_width * self.strike_bucket_width);
+        let mut ledger = self.lock();
+        ledger.total.add(fill);
+        ledger
+            .by_symbol
+            .entry(fill.symbol.clone())
+            .or_default()
+            .add(fill);
+        if let Some(bucket) = bucket {
+            ledger.by_strike_bucket.entry(bucket).or_default().add(fill);
+        }
+        match fill.side {
+            Side::Buy => ledger.counterparty_sells.add(fill),
+            Side::Sell => ledger.counterparty_buys.add(fill),
+        }
+        if let Some(history) = &self.history {
+            let mut summary = EdgeSummary::default();
+            summary.add(fill);
+            history.record(fill.timestamp_ms, summary);
+        }
+        fill.edge()
+    }
+
+    /// Returns the edge of every fill.
+    #[must_use]
+    pub fn total(&self) -> EdgeSummary {
+        self.lock().total
+    }
+
+    /// Returns the edge per contract symbol.
+    #[must_use]
+    pub fn by_symbol(&self) -> BTreeMap<String, EdgeSummary> {
+        self.lock().by_symbol.clone()
+    }
+
+    /// Returns the edge per strike bucket, keyed by the bucket's lowest
+    /// strike.
+    #[must_use]
+    pub fn by_strike_bucket(&self) -> BTreeMap<u64, EdgeSummary> {
+        self.lock().by_strike_bucket.clone()
+    }
+
+    /// Returns the edge of fills where the counterparty was on `side`.
+    #[must_use]
+    pub fn by_counterparty_side(&self, side: Side) -> EdgeSummary {
+        let ledger = self.lock();
+        match side {
+            Side::Buy => ledger.counterparty_buys,
+            Side::Sell => ledger.counterparty_sells,
+        }
+    }
+
+    /// Clears every total.
+    pub fn reset(&self) {
+        *self.lock() = Ledger::default();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Ledger> {
+        self.ledger
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Compactable for EdgeTracker {
+    fn name(&self) -> &str {
+        "edge"
+    }
+
+    /// Compacts the time series, then evicts the per-contract totals of
+    /// expired contracts; `evicted` and `remaining` count both points and
+    /// contracts.
+    fn compact(&self, now_ms: u64) -> Result<CompactionStats> {
+        let mut stats = match &self.history {
+            Some(history) => history.compact(now_ms)?,
+            None => CompactionStats::default(),
+        };
+        let mut ledger = self.lock();
+        let before = ledger.by_symbol.len();
+        ledger.by_symbol.retain(|symbol, _| {
+            ChainCoordinates::parse(symbol)
+                .and_then(|c| c.days_to_expiry(now_ms))
+                .is_none_or(|days| days > Decimal::NEGATIVE_ONE)
+        });
+        stats.evicted += before - ledger.by_symbol.len();
+        stats.remaining += ledger.by_symbol.len();
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn fill(symbol: &str, side: Side, price: Decimal, theo: Decimal) -> EdgeFill {
+        EdgeFill {
+            symbol: symbol.to_string(),
+            side,
+            quantity: dec!(10),
+            price,
+            theo,
+            timestamp_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_edge_by_symbol_bucket_and_side() {
+        let tracker = EdgeTracker::new(500).unwrap();
+        let call = "BTC-20240329-50000-C";
+        let put = "BTC-20240329-50250-P";
+        assert_eq!(
+            tracker.record(&fill(call, Side::Buy, dec!(4.9), dec!(5))),
+            dec!(1)
+        );
+        assert_eq!(
+            tracker.record(&fill(put, Side::Sell, dec!(3.2), dec!(3))),
+            dec!(2)
+        );
+        // Picked off: sold below theo.
+        tracker.record(&fill(call, Side::Sell, dec!(4.8), dec!(5)));
+        tracker.record(&fill("BTC-PERP", Side::Buy, dec!(100), dec!(100)));
+
+        let total = tracker.total();
+        assert_eq!(total.fills, 4);
+        assert_eq!(total.edge, dec!(1));
+        assert_eq!(tracker.by_symbol()[call].edge, dec!(-1));
+
+        let buckets = tracker.by_strike_bucket();
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(buckets[&50000].fills, 3);
+
+        assert_eq!(tracker.by_counterparty_side(Side::Buy).edge, Decimal::ZERO);
+        assert_eq!(tracker.by_counterparty_side(Side::Sell).edge, dec!(1));
+        assert_eq!(
+            tracker.by_counterparty_side(Side::Buy).edge_per_contract(),
+            Some(Decimal::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert!(EdgeTracker::new(0).is_err());
+        let tracker = EdgeTracker::new(1).unwrap();
+        let mut empty = fill("C", Side::Buy, dec!(1), dec!(2));
+        empty.quantity = Decimal::ZERO;
+        assert_eq!(tracker.record(&empty), Decimal::ZERO);
+        assert_eq!(tracker.total().fills, 0);
+        assert_eq!(tracker.total().edge_ratio(), None);
+    }
+
+    #[test]
+    fn test_compaction_evicts_expired_contracts() {
+        const MARCH_29: u64 = 1_711_670_400_000;
+        const HOUR: u64 = 3_600_000;
+        let tracker = EdgeTracker::new(1)
+            .unwrap()
+            .with_history(RetentionPolicy::from_days(1, 30, None))
+            .unwrap();
+        for symbol in ["BTC-20240329-50000-C", "BTC-20240426-50000-C", "BTC-PERP"] {
+            let mut recorded = fill(symbol, Side::Buy, dec!(4.9), dec!(5));
+            recorded.timestamp_ms = MARCH_29;
+            tracker.record(&recorded);
+        }
+        assert_eq!(tracker.history().unwrap().len(), 3);
+
+        // Contracts are kept through their expiration date.
+        assert_eq!(tracker.compact(MARCH_29 + HOUR).unwrap().evicted, 0);
+
+        let stats = tracker.compact(MARCH_29 + 48 * HOUR).unwrap();
+        assert_eq!(stats.evicted, 1);
+        assert_eq!(stats.rolled_to_hourly, 3);
+        // One hourly bucket and two contracts.
+        assert_eq!(stats.remaining, 3);
+        assert!(!tracker.by_symbol().contains_key("BTC-20240329-50000-C"));
+        assert_eq!(tracker.total().fills, 3);
+        assert_eq!(tracker.by_strike_bucket()[&50000].fills, 2);
+    }
+}
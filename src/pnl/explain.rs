@@ -0,0 +1,408 @@
+//! P&L explain module.
+//!
+//! This module provides the [`PnLExplainReport`] produced by
+//! [`PnLCalculator::explain`](super::PnLCalculator::explain): P&L over a
+//! window split into Greek components, new trades, fees and an unexplained
+//! residual, per contract and aggregated per expiration.
+//!
+//! ## Method
+//!
+//! Positions are explained between consecutive [`ExplainMark`]s of a
+//! contract inside the window. Over each interval:
+//!
+//! ```text
+//! carried P&L = q0 * (P1 - P0)          attributed with q0 * Greeks at the first mark
+//! new trades  = sum(q * (P1 - price))   trades after the first mark, valued at the second
+//! fees        = -sum(fee)
+//! total       = carried P&L + new trades + fees
+//! ```
+//!
+//! The Greek components and the unexplained residual sum to the carried
+//! P&L, so every line of the report sums to its total.
+
+use super::attribution::{MarketMove, PnLCalculator};
+use crate::error::{Error, Result};
+use crate::history::Aggregate;
+use crate::pricing::Greeks;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Position and market state of a contract at one time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExplainMark {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Expiration the contract is reported under.
+    pub expiration: String,
+    /// Mark time in milliseconds.
+    pub timestamp_ms: u64,
+    /// Signed position in contracts.
+    pub quantity: Decimal,
+    /// Mark price of one contract.
+    pub price: Decimal,
+    /// Greeks of one long contract.
+    pub greeks: Greeks,
+    /// Underlying price.
+    pub spot: Decimal,
+    /// Implied volatility of the contract.
+    pub volatility: Decimal,
+    /// Risk-free rate.
+    pub rate: Decimal,
+}
+
+/// One of our trades in a contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExplainTrade {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Trade time in milliseconds.
+    pub timestamp_ms: u64,
+    /// Signed quantity, positive when bought.
+    pub quantity: Decimal,
+    /// Trade price.
+    pub price: Decimal,
+    /// Fees paid on the trade.
+    pub fee: Decimal,
+}
+
+/// P&L components of a contract or a group of contracts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExplainLine {
+    /// P&L explained by delta.
+    pub delta: Decimal,
+    /// P&L explained by gamma.
+    pub gamma: Decimal,
+    /// P&L explained by volatility changes.
+    pub vega: Decimal,
+    /// P&L explained by time decay.
+    pub theta: Decimal,
+    /// P&L explained by rate changes.
+    pub rho: Decimal,
+    /// P&L of trades done in the window, marked at the next mark.
+    pub new_trades: Decimal,
+    /// Fees paid, as a negative P&L.
+    pub fees: Decimal,
+    /// Carried P&L not explained by any Greek.
+    pub unexplained: Decimal,
+    /// Actual P&L.
+    pub total: Decimal,
+}
+
+impl ExplainLine {
+    /// Returns the sum of the Greek components.
+    #[must_use]
+    pub fn greeks(&self) -> Decimal {
+        self.delta + self.gamma + self.vega + self.theta + self.rho
+    }
+}
+
+impl Aggregate for ExplainLine {
+    fn merge(&mut self, other: &Self) {
+        self.delta += other.delta;
+        self.gamma += other.gamma;
+        self.vega += other.vega;
+        self.theta += other.theta;
+        self.rho += other.rho;
+        self.new_trades += other.new_trades;
+        self.fees += other.fees;
+        self.unexplained += other.unexplained;
+        self.total += other.total;
+    }
+}
+
+/// Explained P&L of one contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolExplain {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Expiration the contract is reported under.
+    pub expiration: String,
+    /// P&L components.
+    pub line: ExplainLine,
+}
+
+/// Explained P&L of every contract of one expiration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpirationExplain {
+    /// Expiration.
+    pub expiration: String,
+    /// Summed P&L components.
+    pub line: ExplainLine,
+}
+
+/// P&L explain over a window, per contract and per expiration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PnLExplainReport {
+    /// Window start in milliseconds.
+    pub from_ms: u64,
+    /// Window end in milliseconds.
+    pub to_ms: u64,
+    /// Contracts with at least two marks in the window, sorted by symbol.
+    pub symbols: Vec<SymbolExplain>,
+    /// Expirations, sorted.
+    pub expirations: Vec<ExpirationExplain>,
+    /// Sum of every contract.
+    pub total: ExplainLine,
+}
+
+impl PnLExplainReport {
+    /// Serializes the report to JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::SerializationError` if serialization fails.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+/// Marks and trades recorded for P&L explain.
+#[derive(Debug, Default)]
+pub(crate) struct ExplainLedger {
+    /// Marks and trades per contract, in time order.
+    contracts: Mutex<BTreeMap<String, ContractHistory>>,
+}
+
+#[derive(Debug, Default)]
+struct ContractHistory {
+    marks: Vec<ExplainMark>,
+    trades: Vec<ExplainTrade>,
+}
+
+impl ExplainLedger {
+    pub(crate) fn record_mark(&self, mark: ExplainMark) -> Result<()> {
+        let mut contracts = self.lock();
+        let history = contracts.entry(mark.symbol.clone()).or_default();
+        if history
+            .marks
+            .last()
+            .is_some_and(|m| m.timestamp_ms > mark.timestamp_ms)
+        {
+            return Err(Error::validation(format!(
+                "mark of {} at {} is out of order",
+                mark.symbol, mark.timestamp_ms
+            )));
+        }
+        history.marks.push(mark);
+        Ok(())
+    }
+
+    pub(crate) fn record_trade(&self, trade: ExplainTrade) {
+        let mut contracts = self.lock();
+        let trades = &mut contracts.entry(trade.symbol.clone()).or_default().trades;
+        let at = trades.partition_point(|t| t.timestamp_ms <= trade.timestamp_ms);
+        trades.insert(at, trade);
+    }
+
+    pub(crate) fn explain(
+        &self,
+        calculator: &PnLCalculator,
+        from_ms: u64,
+        to_ms: u64,
+    ) -> Result<PnLExplainReport> {
+        if to_ms < from_ms {
+            return Err(Error::validation(
+                "P&L explain window ends before it starts",
+            ));
+        }
+        let mut symbols = Vec::new();
+        let mut expirations: BTreeMap<String, ExplainLine> = BTreeMap::new();
+        let mut total = ExplainLine::default();
+        let contracts = self.lock();
+        for (symbol, history) in contracts.iter() {
+            let marks: Vec<&ExplainMark> = history
+                .marks
+                .iter()
+                .filter(|m| (from_ms..=to_ms).contains(&m.timestamp_ms))
+                .collect();
+            if marks.len() < 2 {
+                continue;
+            }
+            let mut line = ExplainLine::default();
+            for pair in marks.windows(2) {
+                line.merge(&explain_interval(
+                    calculator,
+                    pair[0],
+                    pair[1],
+                    &history.trades,
+                ));
+            }
+            let expiration = marks[0].expiration.clone();
+            expirations
+                .entry(expiration.clone())
+                .or_default()
+                .merge(&line);
+            total.merge(&line);
+            symbols.push(SymbolExplain {
+                symbol: symbol.clone(),
+                expiration,
+                line,
+            });
+        }
+        Ok(PnLExplainReport {
+            from_ms,
+            to_ms,
+            symbols,
+            expirations: expirations
+                .into_iter()
+                .map(|(expiration, line)| ExpirationExplain { expiration, line })
+                .collect(),
+            total,
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, ContractHistory>> {
+        self.contracts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Explains the P&L of a contract between two consecutive marks.
+fn explain_interval(
+    calculator: &PnLCalculator,
+    start: &ExplainMark,
+    end: &ExplainMark,
+    trades: &[ExplainTrade],
+) -> ExplainLine {
+    let carried = start.quantity * (end.price - start.price);
+    let market_move = MarketMove::new(
+        end.spot - start.spot,
+        end.volatility - start.volatility,
+        end.rate - start.rate,
+    );
+    let attribution = calculator.attribute(
+        &(start.greeks * start.quantity),
+        &market_move,
+        to_datetime(start.timestamp_ms),
+        to_datetime(end.timestamp_ms),
+        carried,
+    );
+    let (mut new_trades, mut fees) = (Decimal::ZERO, Decimal::ZERO);
+    for trade in trades
+        .iter()
+        .filter(|t| t.timestamp_ms > start.timestamp_ms && t.timestamp_ms <= end.timestamp_ms)
+    {
+        new_trades += trade.quantity * (end.price - trade.price);
+        fees -= trade.fee;
+    }
+    ExplainLine {
+        delta: attribution.delta_pnl,
+        gamma: attribution.gamma_pnl,
+        vega: attribution.vega_pnl,
+        theta: attribution.theta_pnl,
+        rho: attribution.rho_pnl,
+        new_trades,
+        fees,
+        unexplained: attribution.unexplained,
+        total: carried + new_trades + fees,
+    }
+}
+
+fn to_datetime(timestamp_ms: u64) -> DateTime<Utc> {
+    i64::try_from(timestamp_ms)
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    const DAY_MS: u64 = 86_400_000;
+
+    fn mark(
+        symbol: &str,
+        ts: u64,
+        quantity: Decimal,
+        price: Decimal,
+        spot: Decimal,
+    ) -> ExplainMark {
+        ExplainMark {
+            symbol: symbol.to_string(),
+            expiration: "20300621".to_string(),
+            timestamp_ms: ts,
+            quantity,
+            price,
+            greeks: Greeks::new(dec!(0.5), dec!(0.02), dec!(-0.1), dec!(0.3), dec!(0.1)),
+            spot,
+            volatility: dec!(0.2),
+            rate: dec!(0.05),
+        }
+    }
+
+    #[test]
+    fn test_explain_splits_components_and_aggregates() {
+        let calculator = PnLCalculator::new();
+        calculator
+            .record_mark(mark("C", 0, dec!(10), dec!(5), dec!(100)))
+            .unwrap();
+        calculator.record_trade(ExplainTrade {
+            symbol: "C".to_string(),
+            timestamp_ms: DAY_MS / 2,
+            quantity: dec!(5),
+            price: dec!(5.5),
+            fee: dec!(0.25),
+        });
+        calculator
+            .record_mark(mark("C", DAY_MS, dec!(15), dec!(6), dec!(102)))
+            .unwrap();
+        calculator
+            .record_mark(mark("P", 0, dec!(-4), dec!(3), dec!(100)))
+            .unwrap();
+        calculator
+            .record_mark(mark("P", DAY_MS, dec!(-4), dec!(2.5), dec!(102)))
+            .unwrap();
+
+        let report = calculator.explain(0, DAY_MS).unwrap();
+        let call = &report.symbols[0].line;
+        // 10 * (0.5 * 2) and 10 * 0.5 * 0.02 * 4.
+        assert_eq!(call.delta, dec!(10));
+        assert_eq!(call.gamma, dec!(0.4));
+        assert_eq!(call.theta, dec!(-1));
+        assert_eq!(call.new_trades, dec!(2.5));
+        assert_eq!(call.fees, dec!(-0.25));
+        assert_eq!(call.total, dec!(12.25));
+        assert_eq!(
+            call.greeks() + call.unexplained + call.new_trades + call.fees,
+            call.total
+        );
+
+        assert_eq!(report.expirations.len(), 1);
+        assert_eq!(report.expirations[0].line, report.total);
+        assert_eq!(report.total.total, dec!(14.25));
+
+        let json = report.to_json().unwrap();
+        let parsed: PnLExplainReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, report);
+    }
+
+    #[test]
+    fn test_window_and_ordering() {
+        let calculator = PnLCalculator::new();
+        calculator
+            .record_mark(mark("C", DAY_MS, dec!(1), dec!(5), dec!(100)))
+            .unwrap();
+        assert!(
+            calculator
+                .record_mark(mark("C", 0, dec!(1), dec!(5), dec!(100)))
+                .is_err()
+        );
+        calculator
+            .record_mark(mark("C", 2 * DAY_MS, dec!(1), dec!(7), dec!(100)))
+            .unwrap();
+
+        // A single mark in the window explains nothing.
+        assert!(calculator.explain(0, DAY_MS).unwrap().symbols.is_empty());
+        assert_eq!(
+            calculator.explain(0, 2 * DAY_MS).unwrap().total.total,
+            dec!(2)
+        );
+        assert!(calculator.explain(DAY_MS, 0).is_err());
+    }
+}
@@ -0,0 +1,361 @@
+//! Mark override module.
+//!
+//! This module provides the [`MarkOverrideRegistry`], which lets risk
+//! force selected contracts to be marked to model (or to a fixed price)
+//! instead of the market while markets are broken.
+//!
+//! Every override carries a reason, the requester and a time to live, and
+//! lapses on its own once the TTL passes, so a forgotten override cannot
+//! keep distorting P&L. Each set, clear and expiry is kept in an audit
+//! trail.
+
+use crate::clock::Clock;
+use crate::error::{Error, Result};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Where a contract's mark comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MarkSource {
+    /// Market price.
+    Market,
+    /// Model (theo) price.
+    Model,
+    /// Fixed price set by risk.
+    Fixed(Decimal),
+}
+
+/// An active mark override.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarkOverride {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Mark source while the override is active.
+    pub source: MarkSource,
+    /// Why the override was set.
+    pub reason: String,
+    /// Who set the override.
+    pub requested_by: String,
+    /// Creation time in milliseconds.
+    pub created_ms: u64,
+    /// Expiry time in milliseconds.
+    pub expires_ms: u64,
+}
+
+impl MarkOverride {
+    /// Returns true if the override has lapsed at `now_ms`.
+    #[must_use]
+    pub const fn is_expired(&self, now_ms: u64) -> bool {
+        now_ms >= self.expires_ms
+    }
+}
+
+/// Kind of mark override audit event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MarkOverrideEventKind {
+    /// An override was set or replaced.
+    Set,
+    /// An override was cleared before its expiry.
+    Cleared {
+        /// Who cleared the override.
+        by: String,
+    },
+    /// An override lapsed.
+    Expired,
+}
+
+/// Audit record of a mark override change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarkOverrideEvent {
+    /// Clock time of the event in milliseconds.
+    pub timestamp_ms: u64,
+    /// What happened.
+    pub kind: MarkOverrideEventKind,
+    /// The override the event refers to.
+    pub mark_override: MarkOverride,
+}
+
+/// A resolved mark and where it came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolvedMark {
+    /// Mark price.
+    pub price: Decimal,
+    /// Source of the price.
+    pub source: MarkSource,
+    /// True if an override selected the source.
+    pub overridden: bool,
+}
+
+/// Overrides and audit trail, guarded together.
+#[derive(Debug, Default)]
+struct Overrides {
+    /// Active overrides by symbol.
+    active: HashMap<String, MarkOverride>,
+    /// Audit trail, oldest first.
+    events: Vec<MarkOverrideEvent>,
+}
+
+/// Registry of mark overrides with automatic expiry.
+pub struct MarkOverrideRegistry {
+    /// Time source for creation, expiry and audit timestamps.
+    clock: Arc<dyn Clock>,
+    /// Overrides and audit trail.
+    overrides: Mutex<Overrides>,
+}
+
+impl MarkOverrideRegistry {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            overrides: Mutex::new(Overrides::default()),
+        }
+    }
+
+    /// Sets or replaces the override of a contract.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - Contract symbol
+    /// * `source` - Mark source while the override is active
+    /// * `ttl_ms` - Time to live in milliseconds
+    /// * `reason` - Why the override is needed
+    /// * `requested_by` - Who requested it
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the TTL is zero, the reason is
+    /// blank, or a fixed price is negative.
+    pub fn set(
+        &self,
+        symbol: impl Into<String>,
+        source: MarkSource,
+        ttl_ms: u64,
+        reason: impl Into<String>,
+        requested_by: impl Into<String>,
+    ) -> Result<MarkOverride> {
+        let reason = reason.into();
+        if ttl_ms == 0 {
+            return Err(Error::validation("mark override needs a positive TTL"));
+        }
+        if reason.trim().is_empty() {
+            return Err(Error::validation("mark override needs a reason"));
+        }
+        if matches!(source, MarkSource::Fixed(price) if price < Decimal::ZERO) {
+            return Err(Error::validation("fixed mark must not be negative"));
+        }
+        let now_ms = self.clock.now_ms();
+        let mark_override = MarkOverride {
+            symbol: symbol.into(),
+            source,
+            reason,
+            requested_by: requested_by.into(),
+            created_ms: now_ms,
+            expires_ms: now_ms.saturating_add(ttl_ms),
+        };
+        let mut overrides = self.lock();
+        overrides
+            .active
+            .insert(mark_override.symbol.clone(), mark_override.clone());
+        overrides.events.push(MarkOverrideEvent {
+            timestamp_ms: now_ms,
+            kind: MarkOverrideEventKind::Set,
+            mark_override: mark_override.clone(),
+        });
+        Ok(mark_override)
+    }
+
+    /// Clears the override of a contract before its expiry.
+    ///
+    /// Returns the cleared override, or `None` if none was active.
+    pub fn clear(&self, symbol: &str, by: impl Into<String>) -> Option<MarkOverride> {
+        let now_ms = self.clock.now_ms();
+        let mut overrides = self.lock();
+        expire_locked(&mut overrides, now_ms);
+        let cleared = overrides.active.remove(symbol)?;
+        overrides.events.push(MarkOverrideEvent {
+            timestamp_ms: now_ms,
+            kind: MarkOverrideEventKind::Cleared { by: by.into() },
+            mark_override: cleared.clone(),
+        });
+        Some(cleared)
+    }
+
+    /// Returns the active override of a contract.
+    #[must_use]
+    pub fn active(&self, symbol: &str) -> Option<MarkOverride> {
+        let now_ms = self.clock.now_ms();
+        let mut overrides = self.lock();
+        expire_locked(&mut overrides, now_ms);
+        overrides.active.get(symbol).cloned()
+    }
+
+    /// Returns every active override sorted by symbol.
+    #[must_use]
+    pub fn overrides(&self) -> Vec<MarkOverride> {
+        let now_ms = self.clock.now_ms();
+        let mut overrides = self.lock();
+        expire_locked(&mut overrides, now_ms);
+        let mut active: Vec<MarkOverride> = overrides.active.values().cloned().collect();
+        active.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        active
+    }
+
+    /// Removes lapsed overrides.
+    ///
+    /// Returns the overrides that expired.
+    pub fn expire(&self) -> Vec<MarkOverride> {
+        let now_ms = self.clock.now_ms();
+        expire_locked(&mut self.lock(), now_ms)
+    }
+
+    /// Returns the audit trail, oldest first.
+    #[must_use]
+    pub fn events(&self) -> Vec<MarkOverrideEvent> {
+        let now_ms = self.clock.now_ms();
+        let mut overrides = self.lock();
+        expire_locked(&mut overrides, now_ms);
+        overrides.events.clone()
+    }
+
+    /// Resolves the mark of a contract.
+    ///
+    /// Without an override the market price is used. An override selects
+    /// its source; `None` is returned if that source has no price.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - Contract symbol
+    /// * `market` - Market price, if any
+    /// * `model` - Model price, if any
+    #[must_use]
+    pub fn resolve(
+        &self,
+        symbol: &str,
+        market: Option<Decimal>,
+        model: Option<Decimal>,
+    ) -> Option<ResolvedMark> {
+        let (source, overridden) = self
+            .active(symbol)
+            .map_or((MarkSource::Market, false), |o| (o.source, true));
+        let price = match source {
+            MarkSource::Market => market,
+            MarkSource::Model => model,
+            MarkSource::Fixed(price) => Some(price),
+        }?;
+        Some(ResolvedMark {
+            price,
+            source,
+            overridden,
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Overrides> {
+        self.overrides
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Removes lapsed overrides and audits their expiry.
+fn expire_locked(overrides: &mut Overrides, now_ms: u64) -> Vec<MarkOverride> {
+    let mut lapsed: Vec<MarkOverride> = overrides
+        .active
+        .values()
+        .filter(|o| o.is_expired(now_ms))
+        .cloned()
+        .collect();
+    lapsed.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    for mark_override in &lapsed {
+        overrides.active.remove(&mark_override.symbol);
+        overrides.events.push(MarkOverrideEvent {
+            timestamp_ms: mark_override.expires_ms,
+            kind: MarkOverrideEventKind::Expired,
+            mark_override: mark_override.clone(),
+        });
+    }
+    lapsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use rust_decimal_macros::dec;
+
+    fn registry() -> (Arc<ManualClock>, MarkOverrideRegistry) {
+        let clock = Arc::new(ManualClock::new(1_000));
+        (Arc::clone(&clock), MarkOverrideRegistry::new(clock))
+    }
+
+    #[test]
+    fn test_override_selects_source_until_expiry() {
+        let (clock, registry) = registry();
+        let (market, model) = (Some(dec!(7)), Some(dec!(5)));
+        assert_eq!(
+            registry.resolve("C1", market, model).unwrap().price,
+            dec!(7)
+        );
+
+        registry
+            .set("C1", MarkSource::Model, 60_000, "crossed market", "risk")
+            .unwrap();
+        let mark = registry.resolve("C1", market, model).unwrap();
+        assert_eq!(mark.price, dec!(5));
+        assert!(mark.overridden);
+        assert!(registry.resolve("C1", market, None).is_none());
+
+        clock.advance(60_000);
+        assert!(registry.active("C1").is_none());
+        assert_eq!(
+            registry.resolve("C1", market, model).unwrap().price,
+            dec!(7)
+        );
+        let events = registry.events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].kind, MarkOverrideEventKind::Expired);
+        assert_eq!(events[1].timestamp_ms, 61_000);
+    }
+
+    #[test]
+    fn test_clear_is_audited() {
+        let (_, registry) = registry();
+        registry
+            .set("C1", MarkSource::Fixed(dec!(3)), 1_000, "halted", "risk")
+            .unwrap();
+        assert_eq!(registry.overrides().len(), 1);
+
+        assert!(registry.clear("C1", "desk").is_some());
+        assert!(registry.clear("C1", "desk").is_none());
+        assert_eq!(
+            registry.events()[1].kind,
+            MarkOverrideEventKind::Cleared {
+                by: "desk".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_overrides_rejected() {
+        let (_, registry) = registry();
+        assert!(
+            registry
+                .set("C1", MarkSource::Model, 0, "x", "risk")
+                .is_err()
+        );
+        assert!(
+            registry
+                .set("C1", MarkSource::Model, 1, " ", "risk")
+                .is_err()
+        );
+        assert!(
+            registry
+                .set("C1", MarkSource::Fixed(dec!(-1)), 1, "x", "risk")
+                .is_err()
+        );
+        assert!(registry.events().is_empty());
+    }
+}
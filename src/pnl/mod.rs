@@ -0,0 +1,47 @@
+//! P&L module.
+//!
+//! This module provides P&L attribution for option positions, decomposing
+//! realized P&L into Greek-driven components over an arbitrary time window.
+//!
+//! ## Components
+//!
+//! - [`PnLCalculator`]: Attributes P&L to delta, gamma, theta, vega and rho
+//! - [`PnLAttribution`]: Result of an attribution with the unexplained residual
+//! - [`PnLExplainReport`]: P&L explain per contract and expiration, with new trades, fees and residual
+//! - [`EdgeTracker`]: Edge captured versus theo per fill, by contract, strike bucket and counterparty side
+//! - [`TiedAttribution`]: Package attribution of a tied (delta-exchange) trade
+//! - [`HedgedAttribution`]: Option attribution with the hedge book's P&L split into delta and funding/fees
+//! - [`ThetaAccrual`]: Intraday theta accrual driven by a [`TradingCalendar`]
+//! - [`MarkOverrideRegistry`]: Audited, expiring mark-to-model overrides per contract
+//! - [`RoundTripTracker`]: Pairs fills into round trips and explains their P&L
+//! - [`CapitalTracker`]: Capital usage and return on capital per underlying and strategy
+//!
+//! ## Intraday Theta
+//!
+//! Theta is quoted per calendar day. Rather than booking a full day of decay at
+//! each close, [`ThetaAccrual`] accrues it minute by minute (or hour by hour)
+//! with configurable weights for session, overnight and non-trading time, so
+//! intraday attribution has no phantom jump at the daily boundary.
+
+mod attribution;
+mod calendar;
+mod capital;
+mod edge;
+mod explain;
+mod marks;
+mod round_trip;
+
+pub use attribution::{
+    HedgedAttribution, MarketMove, PnLAttribution, PnLCalculator, TiedAttribution,
+};
+pub use calendar::{AccrualGranularity, ThetaAccrual, ThetaAccrualConfig, TradingCalendar};
+pub use capital::{CapitalKey, CapitalReport, CapitalSample, CapitalTracker, CapitalUsage};
+pub use edge::{EdgeFill, EdgeSummary, EdgeTracker};
+pub use explain::{
+    ExpirationExplain, ExplainLine, ExplainMark, ExplainTrade, PnLExplainReport, SymbolExplain,
+};
+pub use marks::{
+    MarkOverride, MarkOverrideEvent, MarkOverrideEventKind, MarkOverrideRegistry, MarkSource,
+    ResolvedMark,
+};
+pub use round_trip::{RoundTrip, RoundTripFill, RoundTripSummary, RoundTripTracker};
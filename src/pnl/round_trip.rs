@@ -0,0 +1,448 @@
+//! Round trip module.
+//!
+//! This module provides the [`RoundTripTracker`], which pairs opening and
+//! closing fills per contract first-in first-out and explains the P&L of
+//! each resulting [`RoundTrip`].
+//!
+//! ## Decomposition
+//!
+//! For `q` contracts opened at price `P0` with theo `T0` and closed at `P1`
+//! with theo `T1`, with `s = +1` for a long and `-1` for a short:
+//!
+//! ```text
+//! entry spread capture = s * (T0 - P0) * q
+//! exit spread capture  = s * (P1 - T1) * q
+//! theo drift           = s * (T1 - T0) * q
+//! gross P&L            = s * (P1 - P0) * q   (sum of the three above)
+//! net P&L              = gross - hedge cost - fees
+//! ```
+//!
+//! Hedge costs booked against a contract are spread over its open lots by
+//! quantity and realized with the lot. Fees of each fill are split per
+//! contract, so a partially closed lot carries the rest of its entry fee.
+
+use crate::history::Aggregate;
+use orderbook_rs::Side;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Mutex, MutexGuard};
+
+/// One of our fills, with the theo at the time of the fill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundTripFill {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Segment the contract belongs to (e.g. expiry and moneyness bucket).
+    pub bucket: String,
+    /// Our side.
+    pub side: Side,
+    /// Filled quantity, positive.
+    pub quantity: Decimal,
+    /// Fill price.
+    pub price: Decimal,
+    /// Theo at the time of the fill.
+    pub theo: Decimal,
+    /// Fees paid on the fill.
+    pub fee: Decimal,
+    /// Fill time in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// A matched open and close of the same contracts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundTrip {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Segment of the opening fill.
+    pub bucket: String,
+    /// Side of the opening fill.
+    pub direction: Side,
+    /// Contracts opened and closed.
+    pub quantity: Decimal,
+    /// Opening price.
+    pub entry_price: Decimal,
+    /// Closing price.
+    pub exit_price: Decimal,
+    /// Theo at the open.
+    pub entry_theo: Decimal,
+    /// Theo at the close.
+    pub exit_theo: Decimal,
+    /// Open time in milliseconds.
+    pub opened_ms: u64,
+    /// Close time in milliseconds.
+    pub closed_ms: u64,
+    /// Edge versus theo captured at the open.
+    pub entry_spread_capture: Decimal,
+    /// Edge versus theo captured at the close.
+    pub exit_spread_capture: Decimal,
+    /// P&L from theo moving while the position was open.
+    pub theo_drift: Decimal,
+    /// Hedge costs attributed to the position.
+    pub hedge_cost: Decimal,
+    /// Entry and exit fees.
+    pub fees: Decimal,
+    /// Price P&L before hedge costs and fees.
+    pub gross_pnl: Decimal,
+    /// P&L after hedge costs and fees.
+    pub net_pnl: Decimal,
+}
+
+impl RoundTrip {
+    /// Returns the time the position was held in milliseconds.
+    #[must_use]
+    pub const fn holding_ms(&self) -> u64 {
+        self.closed_ms.saturating_sub(self.opened_ms)
+    }
+}
+
+/// Totals over a set of round trips.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundTripSummary {
+    /// Number of round trips.
+    pub round_trips: u64,
+    /// Contracts round-tripped.
+    pub quantity: Decimal,
+    /// Edge captured at the open.
+    pub entry_spread_capture: Decimal,
+    /// Edge captured at the close.
+    pub exit_spread_capture: Decimal,
+    /// P&L from theo drift.
+    pub theo_drift: Decimal,
+    /// Hedge costs.
+    pub hedge_cost: Decimal,
+    /// Fees.
+    pub fees: Decimal,
+    /// Price P&L before hedge costs and fees.
+    pub gross_pnl: Decimal,
+    /// P&L after hedge costs and fees.
+    pub net_pnl: Decimal,
+}
+
+impl RoundTripSummary {
+    /// Adds a round trip.
+    pub fn add(&mut self, trip: &RoundTrip) {
+        self.round_trips += 1;
+        self.quantity += trip.quantity;
+        self.entry_spread_capture += trip.entry_spread_capture;
+        self.exit_spread_capture += trip.exit_spread_capture;
+        self.theo_drift += trip.theo_drift;
+        self.hedge_cost += trip.hedge_cost;
+        self.fees += trip.fees;
+        self.gross_pnl += trip.gross_pnl;
+        self.net_pnl += trip.net_pnl;
+    }
+
+    /// Returns the net P&L per contract round-tripped, if any.
+    #[must_use]
+    pub fn net_per_contract(&self) -> Option<Decimal> {
+        (!self.quantity.is_zero()).then(|| self.net_pnl / self.quantity)
+    }
+}
+
+impl Aggregate for RoundTripSummary {
+    fn merge(&mut self, other: &Self) {
+        self.round_trips += other.round_trips;
+        self.quantity += other.quantity;
+        self.entry_spread_capture += other.entry_spread_capture;
+        self.exit_spread_capture += other.exit_spread_capture;
+        self.theo_drift += other.theo_drift;
+        self.hedge_cost += other.hedge_cost;
+        self.fees += other.fees;
+        self.gross_pnl += other.gross_pnl;
+        self.net_pnl += other.net_pnl;
+    }
+}
+
+/// Open quantity from one opening fill.
+#[derive(Debug, Clone)]
+struct Lot {
+    /// Opening side.
+    side: Side,
+    /// Remaining quantity.
+    quantity: Decimal,
+    /// Opening price.
+    price: Decimal,
+    /// Theo at the open.
+    theo: Decimal,
+    /// Entry fee per contract.
+    fee_per_unit: Decimal,
+    /// Hedge cost accrued on the remaining quantity.
+    hedge_cost: Decimal,
+    /// Segment of the opening fill.
+    bucket: String,
+    /// Open time in milliseconds.
+    opened_ms: u64,
+}
+
+/// Open lots and completed round trips.
+#[derive(Debug, Default)]
+struct Book {
+    /// Open lots by symbol, oldest first.
+    lots: HashMap<String, VecDeque<Lot>>,
+    /// Completed round trips, oldest first.
+    trips: Vec<RoundTrip>,
+}
+
+/// Pairs fills into round trips and explains their P&L.
+#[derive(Default)]
+pub struct RoundTripTracker {
+    /// Open lots and completed round trips.
+    book: Mutex<Book>,
+}
+
+impl RoundTripTracker {
+    /// Creates an empty tracker.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a fill, closing open lots of the opposite side first.
+    ///
+    /// Returns the round trips completed by the fill. Quantity left after
+    /// closing opens a new lot.
+    pub fn record_fill(&self, fill: &RoundTripFill) -> Vec<RoundTrip> {
+        if fill.quantity <= Decimal::ZERO {
+            return Vec::new();
+        }
+        let exit_fee_per_unit = fill.fee / fill.quantity;
+        let mut book = self.lock();
+        let lots = book.lots.entry(fill.symbol.clone()).or_default();
+        let mut remaining = fill.quantity;
+        let mut completed = Vec::new();
+
+        while remaining > Decimal::ZERO {
+            let Some(lot) = lots.front_mut().filter(|l| l.side != fill.side) else {
+                break;
+            };
+            let quantity = remaining.min(lot.quantity);
+            let hedge_cost = lot.hedge_cost * quantity / lot.quantity;
+            lot.hedge_cost -= hedge_cost;
+            lot.quantity -= quantity;
+            remaining -= quantity;
+            completed.push(close(
+                &fill.symbol,
+                lot,
+                fill,
+                quantity,
+                hedge_cost,
+                exit_fee_per_unit,
+            ));
+            if lot.quantity.is_zero() {
+                lots.pop_front();
+            }
+        }
+        if remaining > Decimal::ZERO {
+            lots.push_back(Lot {
+                side: fill.side,
+                quantity: remaining,
+                price: fill.price,
+                theo: fill.theo,
+                fee_per_unit: exit_fee_per_unit,
+                hedge_cost: Decimal::ZERO,
+                bucket: fill.bucket.clone(),
+                opened_ms: fill.timestamp_ms,
+            });
+        }
+        book.trips.extend(completed.iter().cloned());
+        completed
+    }
+
+    /// Attributes a hedge cost to a contract's open lots by quantity.
+    ///
+    /// Returns false, attributing nothing, if the contract has no open lot.
+    pub fn record_hedge_cost(&self, symbol: &str, cost: Decimal) -> bool {
+        let mut book = self.lock();
+        let Some(lots) = book.lots.get_mut(symbol).filter(|l| !l.is_empty()) else {
+            return false;
+        };
+        let open: Decimal = lots.iter().map(|l| l.quantity).sum();
+        for lot in lots.iter_mut() {
+            lot.hedge_cost += cost * lot.quantity / open;
+        }
+        true
+    }
+
+    /// Returns the signed open quantity of a contract.
+    #[must_use]
+    pub fn open_quantity(&self, symbol: &str) -> Decimal {
+        self.lock().lots.get(symbol).map_or(Decimal::ZERO, |lots| {
+            lots.iter()
+                .map(|l| match l.side {
+                    Side::Buy => l.quantity,
+                    Side::Sell => -l.quantity,
+                })
+                .sum()
+        })
+    }
+
+    /// Returns the completed round trips, oldest first.
+    #[must_use]
+    pub fn round_trips(&self) -> Vec<RoundTrip> {
+        self.lock().trips.clone()
+    }
+
+    /// Returns the totals of every completed round trip.
+    #[must_use]
+    pub fn total(&self) -> RoundTripSummary {
+        let mut summary = RoundTripSummary::default();
+        for trip in &self.lock().trips {
+            summary.add(trip);
+        }
+        summary
+    }
+
+    /// Returns the totals per contract.
+    #[must_use]
+    pub fn by_contract(&self) -> BTreeMap<String, RoundTripSummary> {
+        self.summarize(|t| &t.symbol)
+    }
+
+    /// Returns the totals per bucket.
+    #[must_use]
+    pub fn by_bucket(&self) -> BTreeMap<String, RoundTripSummary> {
+        self.summarize(|t| &t.bucket)
+    }
+
+    fn summarize(&self, key: impl Fn(&RoundTrip) -> &String) -> BTreeMap<String, RoundTripSummary> {
+        let mut summaries: BTreeMap<String, RoundTripSummary> = BTreeMap::new();
+        for trip in &self.lock().trips {
+            summaries.entry(key(trip).clone()).or_default().add(trip);
+        }
+        summaries
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Book> {
+        self.book
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Builds the round trip closing `quantity` of a lot.
+fn close(
+    symbol: &str,
+    lot: &Lot,
+    fill: &RoundTripFill,
+    quantity: Decimal,
+    hedge_cost: Decimal,
+    exit_fee_per_unit: Decimal,
+) -> RoundTrip {
+    let sign = match lot.side {
+        Side::Buy => Decimal::ONE,
+        Side::Sell => Decimal::NEGATIVE_ONE,
+    };
+    let entry_spread_capture = sign * (lot.theo - lot.price) * quantity;
+    let exit_spread_capture = sign * (fill.price - fill.theo) * quantity;
+    let theo_drift = sign * (fill.theo - lot.theo) * quantity;
+    let gross_pnl = entry_spread_capture + exit_spread_capture + theo_drift;
+    let fees = (lot.fee_per_unit + exit_fee_per_unit) * quantity;
+    RoundTrip {
+        symbol: symbol.to_string(),
+        bucket: lot.bucket.clone(),
+        direction: lot.side,
+        quantity,
+        entry_price: lot.price,
+        exit_price: fill.price,
+        entry_theo: lot.theo,
+        exit_theo: fill.theo,
+        opened_ms: lot.opened_ms,
+        closed_ms: fill.timestamp_ms,
+        entry_spread_capture,
+        exit_spread_capture,
+        theo_drift,
+        hedge_cost,
+        fees,
+        gross_pnl,
+        net_pnl: gross_pnl - hedge_cost - fees,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn fill(
+        side: Side,
+        quantity: Decimal,
+        price: Decimal,
+        theo: Decimal,
+        ts: u64,
+    ) -> RoundTripFill {
+        RoundTripFill {
+            symbol: "C100".to_string(),
+            bucket: "atm".to_string(),
+            side,
+            quantity,
+            price,
+            theo,
+            fee: quantity * dec!(0.01),
+            timestamp_ms: ts,
+        }
+    }
+
+    #[test]
+    fn test_round_trip_decomposition() {
+        let tracker = RoundTripTracker::new();
+        assert!(
+            tracker
+                .record_fill(&fill(Side::Buy, dec!(10), dec!(4.9), dec!(5), 0))
+                .is_empty()
+        );
+        assert!(tracker.record_hedge_cost("C100", dec!(0.3)));
+
+        let trips = tracker.record_fill(&fill(Side::Sell, dec!(10), dec!(5.4), dec!(5.3), 60_000));
+        let trip = &trips[0];
+        assert_eq!(trip.entry_spread_capture, dec!(1));
+        assert_eq!(trip.exit_spread_capture, dec!(1));
+        assert_eq!(trip.theo_drift, dec!(3));
+        assert_eq!(trip.gross_pnl, dec!(5));
+        assert_eq!(trip.fees, dec!(0.2));
+        assert_eq!(trip.net_pnl, dec!(4.5));
+        assert_eq!(trip.holding_ms(), 60_000);
+        assert_eq!(tracker.open_quantity("C100"), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_fifo_partial_close_and_flip() {
+        let tracker = RoundTripTracker::new();
+        tracker.record_fill(&fill(Side::Sell, dec!(5), dec!(5.1), dec!(5), 0));
+        tracker.record_fill(&fill(Side::Sell, dec!(5), dec!(5.2), dec!(5), 1));
+        tracker.record_hedge_cost("C100", dec!(1));
+
+        // Buys back the first lot and part of the second, then flips long.
+        let trips = tracker.record_fill(&fill(Side::Buy, dec!(12), dec!(4.9), dec!(5), 2));
+        assert_eq!(trips.len(), 2);
+        assert_eq!(trips[0].quantity, dec!(5));
+        assert_eq!(trips[0].gross_pnl, dec!(1));
+        assert_eq!(trips[0].hedge_cost, dec!(0.5));
+        assert_eq!(trips[1].entry_price, dec!(5.2));
+        assert_eq!(trips[1].quantity, dec!(5));
+        assert_eq!(tracker.open_quantity("C100"), dec!(2));
+        assert!(!tracker.record_hedge_cost("P90", dec!(1)));
+    }
+
+    #[test]
+    fn test_summaries_per_contract_and_bucket() {
+        let tracker = RoundTripTracker::new();
+        tracker.record_fill(&fill(Side::Buy, dec!(10), dec!(4.9), dec!(5), 0));
+        tracker.record_fill(&fill(Side::Sell, dec!(10), dec!(5.1), dec!(5), 1));
+        let mut wing = fill(Side::Sell, dec!(2), dec!(0.6), dec!(0.5), 2);
+        wing.symbol = "C150".to_string();
+        wing.bucket = "wing".to_string();
+        tracker.record_fill(&wing);
+        wing.side = Side::Buy;
+        wing.price = dec!(1.5);
+        wing.theo = dec!(1.4);
+        tracker.record_fill(&wing);
+
+        let buckets = tracker.by_bucket();
+        assert_eq!(buckets["atm"].net_pnl, dec!(1.8));
+        assert_eq!(buckets["wing"].theo_drift, dec!(-1.8));
+        assert_eq!(tracker.by_contract().len(), 2);
+        assert_eq!(tracker.total().round_trips, 2);
+        assert_eq!(buckets["atm"].net_per_contract(), Some(dec!(0.18)));
+    }
+}
@@ -0,0 +1,177 @@
+//! Extended Greeks value type.
+//!
+//! This module provides [`ExtendedGreeks`], the first-order [`Greeks`]
+//! together with the second-order cross sensitivities that drive pin and
+//! expiry risk: vanna, volga, charm and speed. Like [`Greeks`] they scale by
+//! position size and aggregate across a portfolio.
+//!
+//! ## Conventions
+//!
+//! - `vanna` is the change in delta per vol point
+//! - `volga` is the change in vega (per vol point) per vol point
+//! - `charm` is the change in delta per calendar day as time passes
+//! - `speed` is the change in gamma per unit of underlying price
+
+use super::greeks::Greeks;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+/// First-order Greeks with second-order cross sensitivities.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtendedGreeks {
+    /// First-order sensitivities.
+    pub greeks: Greeks,
+    /// Sensitivity of delta to implied volatility per vol point.
+    pub vanna: Decimal,
+    /// Sensitivity of vega to implied volatility per vol point (vomma).
+    pub volga: Decimal,
+    /// Delta drift per calendar day.
+    pub charm: Decimal,
+    /// Sensitivity of gamma to the underlying price.
+    pub speed: Decimal,
+}
+
+impl ExtendedGreeks {
+    /// Creates a set of extended Greeks.
+    #[must_use]
+    pub const fn new(
+        greeks: Greeks,
+        vanna: Decimal,
+        volga: Decimal,
+        charm: Decimal,
+        speed: Decimal,
+    ) -> Self {
+        Self {
+            greeks,
+            vanna,
+            volga,
+            charm,
+            speed,
+        }
+    }
+
+    /// Returns extended Greeks with every sensitivity set to zero.
+    #[must_use]
+    pub const fn zero() -> Self {
+        Self::new(
+            Greeks::zero(),
+            Decimal::ZERO,
+            Decimal::ZERO,
+            Decimal::ZERO,
+            Decimal::ZERO,
+        )
+    }
+
+    /// Returns these Greeks scaled by a signed quantity.
+    #[must_use]
+    pub fn scaled(&self, quantity: Decimal) -> Self {
+        *self * quantity
+    }
+
+    /// Returns true if every sensitivity is zero.
+    #[must_use]
+    pub fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+}
+
+impl From<Greeks> for ExtendedGreeks {
+    /// Extends first-order Greeks with zero cross sensitivities.
+    fn from(greeks: Greeks) -> Self {
+        Self {
+            greeks,
+            ..Self::zero()
+        }
+    }
+}
+
+impl Add for ExtendedGreeks {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(
+            self.greeks + rhs.greeks,
+            self.vanna + rhs.vanna,
+            self.volga + rhs.volga,
+            self.charm + rhs.charm,
+            self.speed + rhs.speed,
+        )
+    }
+}
+
+impl AddAssign for ExtendedGreeks {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for ExtendedGreeks {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Neg for ExtendedGreeks {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self * Decimal::NEGATIVE_ONE
+    }
+}
+
+impl Mul<Decimal> for ExtendedGreeks {
+    type Output = Self;
+
+    fn mul(self, rhs: Decimal) -> Self {
+        Self::new(
+            self.greeks * rhs,
+            self.vanna * rhs,
+            self.volga * rhs,
+            self.charm * rhs,
+            self.speed * rhs,
+        )
+    }
+}
+
+impl Sum for ExtendedGreeks {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl std::fmt::Display for ExtendedGreeks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} vanna={} volga={} charm={} speed={}",
+            self.greeks, self.vanna, self.volga, self.charm, self.speed
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_extended_greeks_aggregate() {
+        let one = ExtendedGreeks::new(
+            Greeks::new(dec!(0.5), dec!(0.01), dec!(-0.05), dec!(0.2), dec!(0.1)),
+            dec!(0.002),
+            dec!(0.01),
+            dec!(-0.003),
+            dec!(-0.0001),
+        );
+        let total: ExtendedGreeks = vec![one, one.scaled(dec!(-3))].into_iter().sum();
+        assert_eq!(total, one.scaled(dec!(-2)));
+        assert_eq!(total.greeks.delta, dec!(-1.0));
+        assert!((one - one).is_zero());
+        assert_eq!(ExtendedGreeks::from(one.greeks).vanna, Decimal::ZERO);
+        assert!(one.to_string().contains("charm=-0.003"));
+    }
+}
@@ -0,0 +1,428 @@
+//! Fast pricing module.
+//!
+//! This module provides an opt-in `f64` Black-Scholes batch pricer for the
+//! quoting hot loop. Risk, P&L and anything that is booked keep using the
+//! `Decimal` reference implementation in [`PricingParams`].
+//!
+//! ## Accuracy
+//!
+//! The normal CDF uses Hart's double-precision rational approximation
+//! (absolute error below `1e-14`). Across strikes from 50% to 200% of spot,
+//! expiries from one day to two years and volatilities from 5% to 200%,
+//! prices agree with [`PricingParams::price`] within
+//! [`FAST_PRICE_TOLERANCE`] times spot. Deltas, gammas and vegas are the
+//! analytic Black-Scholes-Merton Greeks of the same formula, vega per vol
+//! point as in [`Greeks`](super::Greeks).
+//!
+//! ## Use in quoting
+//!
+//! [`ChainQuoter::with_fast_pricing`](crate::quoting::ChainQuoter::with_fast_pricing)
+//! prices each expiry of a chain through [`price_and_delta_batch`] instead
+//! of the `Decimal` reference.
+
+use super::params::PricingParams;
+use optionstratlib::OptionStyle;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+
+/// Documented price tolerance versus the `Decimal` reference, as a
+/// fraction of spot.
+pub const FAST_PRICE_TOLERANCE: f64 = 1e-8;
+
+/// [`DAYS_PER_YEAR`](super::DAYS_PER_YEAR) as an `f64`.
+const DAYS_PER_YEAR: f64 = 365.0;
+
+/// Lowest volatility the implied volatility solver returns.
+pub const MIN_IMPLIED_VOLATILITY: f64 = 0.0001;
+
+/// Highest volatility the implied volatility solver returns.
+pub const MAX_IMPLIED_VOLATILITY: f64 = 5.0;
+
+/// Price tolerance of the implied volatility solver, as a fraction of
+/// spot.
+const IMPLIED_VOLATILITY_TOLERANCE: f64 = 1e-12;
+
+/// Bisection steps of the implied volatility solver.
+const IMPLIED_VOLATILITY_ITERATIONS: usize = 100;
+
+/// Inputs for pricing one European option in `f64`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FastOption {
+    /// Spot price of the underlying.
+    pub spot: f64,
+    /// Strike price.
+    pub strike: f64,
+    /// Time to expiry in years.
+    pub tau: f64,
+    /// Implied volatility.
+    pub volatility: f64,
+    /// Risk-free rate.
+    pub rate: f64,
+    /// Continuous dividend yield.
+    pub dividend_yield: f64,
+    /// True for a call, false for a put.
+    pub is_call: bool,
+}
+
+impl FastOption {
+    /// Converts `Decimal` pricing parameters.
+    ///
+    /// Returns `None` if a value does not fit in `f64`.
+    #[must_use]
+    pub fn from_params(params: &PricingParams) -> Option<Self> {
+        Some(Self {
+            spot: params.spot.to_f64()?,
+            strike: params.strike.to_f64()?,
+            tau: params.days_to_expiry.to_f64()? / DAYS_PER_YEAR,
+            volatility: params.volatility.to_f64()?,
+            rate: params.rate.to_f64()?,
+            dividend_yield: params.dividend_yield.to_f64()?,
+            is_call: params.style == OptionStyle::Call,
+        })
+    }
+
+    /// Returns the price and delta of one contract.
+    #[must_use]
+    pub fn price_and_delta(&self) -> (f64, f64) {
+        let carry = (-self.dividend_yield * self.tau).exp();
+        let discount = (-self.rate * self.tau).exp();
+        let forward_value = self.spot * carry;
+        let strike_value = self.strike * discount;
+
+        let std_dev = self.volatility * self.tau.sqrt();
+        if std_dev <= 0.0 || !std_dev.is_finite() {
+            // No optionality left: discounted intrinsic value.
+            return if self.is_call {
+                let itm = forward_value > strike_value;
+                (
+                    (forward_value - strike_value).max(0.0),
+                    if itm { carry } else { 0.0 },
+                )
+            } else {
+                let itm = strike_value > forward_value;
+                (
+                    (strike_value - forward_value).max(0.0),
+                    if itm { -carry } else { 0.0 },
+                )
+            };
+        }
+
+        let d1 = ((self.spot / self.strike).ln()
+            + (self.rate - self.dividend_yield + 0.5 * self.volatility * self.volatility)
+                * self.tau)
+            / std_dev;
+        let d2 = d1 - std_dev;
+        if self.is_call {
+            let n1 = normal_cdf(d1);
+            (
+                forward_value * n1 - strike_value * normal_cdf(d2),
+                carry * n1,
+            )
+        } else {
+            let n1 = normal_cdf(-d1);
+            (
+                strike_value * normal_cdf(-d2) - forward_value * n1,
+                -carry * n1,
+            )
+        }
+    }
+
+    /// Returns the gamma and the vega per vol point of one contract, zero
+    /// once no optionality is left.
+    #[must_use]
+    pub fn gamma_and_vega(&self) -> (f64, f64) {
+        let sqrt_tau = self.tau.sqrt();
+        let std_dev = self.volatility * sqrt_tau;
+        if std_dev <= 0.0 || !std_dev.is_finite() || self.spot <= 0.0 {
+            return (0.0, 0.0);
+        }
+        let d1 = ((self.spot / self.strike).ln()
+            + (self.rate - self.dividend_yield + 0.5 * self.volatility * self.volatility)
+                * self.tau)
+            / std_dev;
+        let density = (-self.dividend_yield * self.tau).exp() * normal_pdf(d1);
+        (
+            density / (self.spot * std_dev),
+            self.spot * density * sqrt_tau / 100.0,
+        )
+    }
+
+    /// Returns the price of one contract.
+    #[must_use]
+    pub fn price(&self) -> f64 {
+        self.price_and_delta().0
+    }
+
+    /// Returns the volatility at which this option is worth `price`,
+    /// ignoring [`FastOption::volatility`].
+    ///
+    /// Solved by bisection between [`MIN_IMPLIED_VOLATILITY`] and
+    /// [`MAX_IMPLIED_VOLATILITY`]. Returns `None` if the price is outside
+    /// the prices of that range, e.g. below intrinsic value.
+    #[must_use]
+    pub fn implied_volatility(&self, price: f64) -> Option<f64> {
+        let at = |volatility: f64| {
+            Self {
+                volatility,
+                ..*self
+            }
+            .price()
+        };
+        let (mut low, mut high) = (MIN_IMPLIED_VOLATILITY, MAX_IMPLIED_VOLATILITY);
+        if !price.is_finite() || price < at(low) || price > at(high) {
+            return None;
+        }
+        let tolerance = IMPLIED_VOLATILITY_TOLERANCE * self.spot;
+        for _ in 0..IMPLIED_VOLATILITY_ITERATIONS {
+            let mid = (low + high) / 2.0;
+            let diff = at(mid) - price;
+            if diff.abs() <= tolerance {
+                return Some(mid);
+            }
+            if diff > 0.0 {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+        Some((low + high) / 2.0)
+    }
+}
+
+/// Prices a batch of options into `prices`.
+///
+/// Only the first `min(options.len(), prices.len())` entries are written.
+pub fn price_batch(options: &[FastOption], prices: &mut [f64]) {
+    for (option, price) in options.iter().zip(prices.iter_mut()) {
+        *price = option.price();
+    }
+}
+
+/// Returns the price and delta of every option in a batch.
+#[must_use]
+pub fn price_and_delta_batch(options: &[FastOption]) -> Vec<(f64, f64)> {
+    options.iter().map(FastOption::price_and_delta).collect()
+}
+
+/// Converts a fast price to `Decimal` for quote generation.
+///
+/// Returns `None` for non-finite values.
+#[must_use]
+pub fn to_decimal(value: f64) -> Option<Decimal> {
+    Decimal::from_f64(value)
+}
+
+/// Standard normal probability density function.
+fn normal_pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / 2.506_628_274_631
+}
+
+/// Standard normal cumulative distribution function (Hart, 1968).
+fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs();
+    let tail = if z > 37.0 {
+        0.0
+    } else {
+        let e = (-z * z / 2.0).exp();
+        if z < 7.071_067_811_865_47 {
+            let mut n = 3.526_249_659_989_11e-2 * z + 0.700_383_064_443_688;
+            n = n * z + 6.373_962_203_531_65;
+            n = n * z + 33.912_866_078_383;
+            n = n * z + 112.079_291_497_871;
+            n = n * z + 221.213_596_169_931;
+            n = n * z + 220.206_867_912_376;
+            let mut d = 8.838_834_764_831_84e-2 * z + 1.755_667_163_182_64;
+            d = d * z + 16.064_177_579_207;
+            d = d * z + 86.780_732_202_946_1;
+            d = d * z + 296.564_248_779_674;
+            d = d * z + 637.333_633_378_831;
+            d = d * z + 793.826_512_519_948;
+            d = d * z + 440.413_735_824_752;
+            e * n / d
+        } else {
+            let mut d = z + 0.65;
+            d = z + 4.0 / d;
+            d = z + 3.0 / d;
+            d = z + 2.0 / d;
+            d = z + 1.0 / d;
+            e / d / 2.506_628_274_631
+        }
+    };
+    if x > 0.0 { 1.0 - tail } else { tail }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normal_cdf() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-15);
+        assert!((normal_cdf(1.959_963_984_540_054) - 0.975).abs() < 1e-12);
+        assert!((normal_cdf(-1.0) + normal_cdf(1.0) - 1.0).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_matches_decimal_reference() {
+        let spot = Decimal::ONE_HUNDRED;
+        let mut worst = 0.0_f64;
+        for strike in [50, 80, 95, 100, 105, 120, 200] {
+            for days in [1, 7, 30, 90, 365, 730] {
+                for vol in [5, 20, 60, 120, 200] {
+                    for style in [OptionStyle::Call, OptionStyle::Put] {
+                        let params = PricingParams::new(
+                            spot,
+                            Decimal::from(strike),
+                            Decimal::from(days),
+                            Decimal::new(vol, 2),
+                            style,
+                        )
+                        .with_rate(Decimal::new(3, 2))
+                        .with_dividend_yield(Decimal::new(1, 2));
+                        let price = FastOption::from_params(&params).unwrap().price();
+                        let reference = params.price().unwrap().to_f64().unwrap();
+                        worst = worst.max((price - reference).abs() / 100.0);
+                    }
+                }
+            }
+        }
+        assert!(worst < FAST_PRICE_TOLERANCE, "price error {worst}");
+    }
+
+    #[test]
+    fn test_delta_matches_finite_difference() {
+        for is_call in [true, false] {
+            let option = FastOption {
+                spot: 100.0,
+                strike: 110.0,
+                tau: 0.25,
+                volatility: 0.4,
+                rate: 0.03,
+                dividend_yield: 0.01,
+                is_call,
+            };
+            let bump = 1e-4;
+            let up = FastOption {
+                spot: 100.0 + bump,
+                ..option
+            }
+            .price();
+            let down = FastOption {
+                spot: 100.0 - bump,
+                ..option
+            }
+            .price();
+            let numeric = (up - down) / (2.0 * bump);
+            assert!((option.price_and_delta().1 - numeric).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_gamma_and_vega_match_decimal_reference() {
+        for style in [OptionStyle::Call, OptionStyle::Put] {
+            let params = PricingParams::new(
+                Decimal::ONE_HUNDRED,
+                Decimal::from(110),
+                Decimal::from(90),
+                Decimal::new(40, 2),
+                style,
+            )
+            .with_rate(Decimal::new(3, 2));
+            let reference = params.greeks().unwrap();
+            let (gamma, vega) = FastOption::from_params(&params).unwrap().gamma_and_vega();
+            assert!((gamma - reference.gamma.to_f64().unwrap()).abs() < 1e-8);
+            assert!((vega - reference.vega.to_f64().unwrap()).abs() < 1e-8);
+        }
+
+        // With a dividend yield, against finite differences of the price.
+        let option = FastOption {
+            spot: 100.0,
+            strike: 110.0,
+            tau: 0.25,
+            volatility: 0.4,
+            rate: 0.03,
+            dividend_yield: 0.05,
+            is_call: true,
+        };
+        let at = |spot: f64, volatility: f64| {
+            FastOption {
+                spot,
+                volatility,
+                ..option
+            }
+            .price()
+        };
+        let (gamma, vega) = option.gamma_and_vega();
+        let numeric_gamma = (at(100.01, 0.4) - 2.0 * at(100.0, 0.4) + at(99.99, 0.4)) / 1e-4;
+        let numeric_vega = (at(100.0, 0.4001) - at(100.0, 0.3999)) / 0.0002 / 100.0;
+        assert!((gamma - numeric_gamma).abs() < 1e-5);
+        assert!((vega - numeric_vega).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_expired_is_intrinsic() {
+        let option = FastOption {
+            spot: 110.0,
+            strike: 100.0,
+            tau: 0.0,
+            volatility: 0.2,
+            rate: 0.0,
+            dividend_yield: 0.0,
+            is_call: true,
+        };
+        assert_eq!(option.price_and_delta(), (10.0, 1.0));
+        let put = FastOption {
+            is_call: false,
+            ..option
+        };
+        assert_eq!(put.price_and_delta(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_implied_volatility_round_trip() {
+        for (strike, is_call) in [(80.0, false), (100.0, true), (130.0, true)] {
+            let option = FastOption {
+                spot: 100.0,
+                strike,
+                tau: 0.25,
+                volatility: 0.45,
+                rate: 0.03,
+                dividend_yield: 0.0,
+                is_call,
+            };
+            let iv = option.implied_volatility(option.price()).unwrap();
+            assert!((iv - 0.45).abs() < 1e-8);
+        }
+        let call = FastOption {
+            spot: 100.0,
+            strike: 80.0,
+            tau: 0.25,
+            volatility: 0.2,
+            rate: 0.0,
+            dividend_yield: 0.0,
+            is_call: true,
+        };
+        // Below intrinsic value.
+        assert_eq!(call.implied_volatility(15.0), None);
+    }
+
+    #[test]
+    fn test_batch() {
+        let option = FastOption {
+            spot: 100.0,
+            strike: 100.0,
+            tau: 1.0,
+            volatility: 0.2,
+            rate: 0.0,
+            dividend_yield: 0.0,
+            is_call: true,
+        };
+        let options = [option; 4];
+        let mut prices = [0.0; 4];
+        price_batch(&options, &mut prices);
+
+        assert!((prices[3] - 7.965_567_455_405_804).abs() < 1e-9);
+        assert_eq!(price_and_delta_batch(&options).len(), 4);
+        assert!(to_decimal(prices[0]).is_some());
+    }
+}
@@ -0,0 +1,216 @@
+//! Greeks value type.
+//!
+//! This module provides [`Greeks`], a compact `Copy` set of first-order
+//! sensitivities that supports the arithmetic needed to scale per-contract
+//! Greeks by position size and aggregate them across a portfolio.
+
+use optionstratlib::greeks::Greek;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+/// First-order option sensitivities.
+///
+/// Units follow the conventions documented in the [`pricing`](crate::pricing)
+/// module. Values may represent a single contract or an aggregated position.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Greeks {
+    /// Sensitivity to the underlying price.
+    pub delta: Decimal,
+    /// Sensitivity of delta to the underlying price.
+    pub gamma: Decimal,
+    /// Value decay per calendar day.
+    pub theta: Decimal,
+    /// Sensitivity to implied volatility per vol point.
+    pub vega: Decimal,
+    /// Sensitivity to the risk-free rate per percentage point.
+    pub rho: Decimal,
+}
+
+impl Greeks {
+    /// Creates a new set of Greeks.
+    #[must_use]
+    pub const fn new(
+        delta: Decimal,
+        gamma: Decimal,
+        theta: Decimal,
+        vega: Decimal,
+        rho: Decimal,
+    ) -> Self {
+        Self {
+            delta,
+            gamma,
+            theta,
+            vega,
+            rho,
+        }
+    }
+
+    /// Returns Greeks with every sensitivity set to zero.
+    #[must_use]
+    pub const fn zero() -> Self {
+        Self::new(
+            Decimal::ZERO,
+            Decimal::ZERO,
+            Decimal::ZERO,
+            Decimal::ZERO,
+            Decimal::ZERO,
+        )
+    }
+
+    /// Returns these Greeks scaled by a signed quantity.
+    #[must_use]
+    pub fn scaled(&self, quantity: Decimal) -> Self {
+        *self * quantity
+    }
+
+    /// Returns true if every sensitivity is zero.
+    #[must_use]
+    pub fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+}
+
+impl From<&Greek> for Greeks {
+    fn from(greek: &Greek) -> Self {
+        Self::new(greek.delta, greek.gamma, greek.theta, greek.vega, greek.rho)
+    }
+}
+
+impl Add for Greeks {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(
+            self.delta + rhs.delta,
+            self.gamma + rhs.gamma,
+            self.theta + rhs.theta,
+            self.vega + rhs.vega,
+            self.rho + rhs.rho,
+        )
+    }
+}
+
+impl AddAssign for Greeks {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Greeks {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Neg for Greeks {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self * Decimal::NEGATIVE_ONE
+    }
+}
+
+impl Mul<Decimal> for Greeks {
+    type Output = Self;
+
+    fn mul(self, rhs: Decimal) -> Self {
+        Self::new(
+            self.delta * rhs,
+            self.gamma * rhs,
+            self.theta * rhs,
+            self.vega * rhs,
+            self.rho * rhs,
+        )
+    }
+}
+
+impl Sum for Greeks {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl std::fmt::Display for Greeks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "delta={} gamma={} theta={} vega={} rho={}",
+            self.delta, self.gamma, self.theta, self.vega, self.rho
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn sample() -> Greeks {
+        Greeks::new(dec!(0.5), dec!(0.01), dec!(-0.05), dec!(0.2), dec!(0.1))
+    }
+
+    #[test]
+    fn test_greeks_zero() {
+        assert!(Greeks::zero().is_zero());
+        assert_eq!(Greeks::default(), Greeks::zero());
+        assert!(!sample().is_zero());
+    }
+
+    #[test]
+    fn test_greeks_scaled() {
+        let scaled = sample().scaled(dec!(-10));
+        assert_eq!(scaled.delta, dec!(-5.0));
+        assert_eq!(scaled.theta, dec!(0.50));
+    }
+
+    #[test]
+    fn test_greeks_arithmetic() {
+        let a = sample();
+        let b = sample().scaled(dec!(2));
+
+        assert_eq!(a + a, b);
+        assert_eq!(b - a, a);
+        assert!((a - a).is_zero());
+
+        let mut acc = Greeks::zero();
+        acc += a;
+        assert_eq!(acc, a);
+    }
+
+    #[test]
+    fn test_greeks_sum() {
+        let total: Greeks = vec![sample(), sample(), sample()].into_iter().sum();
+        assert_eq!(total.delta, dec!(1.5));
+        assert_eq!(total.vega, dec!(0.6));
+    }
+
+    #[test]
+    fn test_greeks_from_optionstratlib() {
+        let greek = Greek {
+            delta: dec!(0.5),
+            gamma: dec!(0.01),
+            theta: dec!(-0.05),
+            vega: dec!(0.2),
+            rho: dec!(0.1),
+            rho_d: dec!(0.0),
+            alpha: dec!(0.0),
+            vanna: dec!(0.0),
+            vomma: dec!(0.0),
+            veta: dec!(0.0),
+            charm: dec!(0.0),
+            color: dec!(0.0),
+        };
+        assert_eq!(Greeks::from(&greek), sample());
+    }
+
+    #[test]
+    fn test_greeks_display() {
+        let display = sample().to_string();
+        assert!(display.contains("delta=0.5"));
+        assert!(display.contains("rho=0.1"));
+    }
+}
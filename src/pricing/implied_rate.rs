@@ -0,0 +1,448 @@
+//! Implied rate module.
+//!
+//! This module extracts the financing rate and carry implied by put-call
+//! parity at each expiry, and collects them into an [`ImpliedRateCurve`]
+//! the pricer can use instead of a user-supplied rate.
+//!
+//! ## Parity regression
+//!
+//! Parity gives `C - P = D * F - D * K`, linear in the strike. Regressing
+//! the synthetic `C - P` on `K` across strikes yields the discount factor
+//! `D` (minus the slope) and the forward `F` (intercept over `D`), from
+//! which
+//!
+//! - the financing rate is `-ln(D) / T`
+//! - the carry rate is `ln(F / S) / T`, i.e. the futures basis as a rate
+//! - the borrow (or dividend) rate is financing minus carry
+//!
+//! Strikes with stale or crossed quotes are removed as outliers: starting
+//! from a Theil-Sen (median of pairwise slopes) fit, strikes whose residual
+//! exceeds a multiple of the scaled median absolute deviation are dropped
+//! and the line is refitted by least squares on the survivors.
+
+use super::params::{DAYS_PER_YEAR, PricingParams};
+use crate::error::{Error, Result};
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Scale turning a median absolute deviation into a standard deviation.
+const MAD_SCALE: Decimal = dec!(1.4826);
+
+/// Call and put mid prices at one strike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParityQuote {
+    /// Strike price.
+    pub strike: Decimal,
+    /// Call mid price.
+    pub call_mid: Decimal,
+    /// Put mid price.
+    pub put_mid: Decimal,
+}
+
+impl ParityQuote {
+    /// Creates a parity quote.
+    #[must_use]
+    pub const fn new(strike: Decimal, call_mid: Decimal, put_mid: Decimal) -> Self {
+        Self {
+            strike,
+            call_mid,
+            put_mid,
+        }
+    }
+
+    /// Returns the synthetic forward price `C - P`.
+    #[must_use]
+    pub fn synthetic(&self) -> Decimal {
+        self.call_mid - self.put_mid
+    }
+}
+
+/// Settings of the parity regression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImpliedRateConfig {
+    /// Residual, in scaled median absolute deviations, above which a strike
+    /// is an outlier.
+    pub outlier_threshold: Decimal,
+    /// Residual below which a strike is never an outlier, in price units.
+    pub residual_floor: Decimal,
+    /// Maximum refits after removing outliers.
+    pub max_iterations: usize,
+    /// Minimum strikes kept in the fit.
+    pub min_strikes: usize,
+}
+
+impl Default for ImpliedRateConfig {
+    fn default() -> Self {
+        Self {
+            outlier_threshold: dec!(3),
+            residual_floor: dec!(0.0001),
+            max_iterations: 3,
+            min_strikes: 3,
+        }
+    }
+}
+
+/// Financing and carry implied at one expiry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImpliedCarry {
+    /// Days to expiry.
+    pub days_to_expiry: Decimal,
+    /// Implied discount factor.
+    pub discount_factor: Decimal,
+    /// Implied forward price.
+    pub forward: Decimal,
+    /// Forward minus spot.
+    pub basis: Decimal,
+    /// Annualized financing rate.
+    pub financing_rate: Decimal,
+    /// Annualized carry rate of the forward over spot.
+    pub carry_rate: Decimal,
+    /// Strikes kept in the final fit.
+    pub strikes_used: usize,
+    /// Strikes removed as outliers.
+    pub strikes_rejected: usize,
+}
+
+impl ImpliedCarry {
+    /// Returns the annualized borrow (or dividend) rate.
+    #[must_use]
+    pub fn borrow_rate(&self) -> Decimal {
+        self.financing_rate - self.carry_rate
+    }
+}
+
+/// Estimates the financing and carry implied by parity at one expiry.
+///
+/// # Arguments
+///
+/// * `spot` - Spot price of the underlying
+/// * `days_to_expiry` - Days to expiry
+/// * `quotes` - Call and put mids per strike
+/// * `config` - Regression settings
+///
+/// # Errors
+///
+/// Returns `Error::ValidationError` if spot or days to expiry are not
+/// positive, or `Error::PricingError` if fewer than `min_strikes` strikes
+/// are given or the fit implies a non-positive discount factor or forward.
+pub fn implied_carry(
+    spot: Decimal,
+    days_to_expiry: Decimal,
+    quotes: &[ParityQuote],
+    config: &ImpliedRateConfig,
+) -> Result<ImpliedCarry> {
+    if spot <= Decimal::ZERO || days_to_expiry <= Decimal::ZERO {
+        return Err(Error::validation(
+            "implied carry needs positive spot and days to expiry",
+        ));
+    }
+    let min_strikes = config.min_strikes.max(2);
+    if quotes.len() < min_strikes {
+        return Err(Error::pricing(format!(
+            "implied carry needs at least {min_strikes} strikes, got {}",
+            quotes.len()
+        )));
+    }
+
+    let mut kept: Vec<ParityQuote> = quotes.to_vec();
+    let (mut intercept, mut slope) = robust_fit(&kept)?;
+    for _ in 0..config.max_iterations {
+        let residuals: Vec<Decimal> = kept
+            .iter()
+            .map(|q| (q.synthetic() - intercept - slope * q.strike).abs())
+            .collect();
+        let cutoff = (median(residuals.clone()) * MAD_SCALE * config.outlier_threshold)
+            .max(config.residual_floor);
+        let survivors: Vec<ParityQuote> = kept
+            .iter()
+            .zip(&residuals)
+            .filter(|(_, r)| **r <= cutoff)
+            .map(|(q, _)| *q)
+            .collect();
+        if survivors.len() == kept.len() || survivors.len() < min_strikes {
+            break;
+        }
+        kept = survivors;
+        (intercept, slope) = fit(&kept)?;
+    }
+
+    let discount_factor = -slope;
+    if discount_factor <= Decimal::ZERO {
+        return Err(Error::pricing(
+            "parity fit implies a non-positive discount factor",
+        ));
+    }
+    let forward = intercept / discount_factor;
+    if forward <= Decimal::ZERO {
+        return Err(Error::pricing("parity fit implies a non-positive forward"));
+    }
+    let years = days_to_expiry / DAYS_PER_YEAR;
+    Ok(ImpliedCarry {
+        days_to_expiry,
+        discount_factor,
+        forward,
+        basis: forward - spot,
+        financing_rate: -discount_factor.ln() / years,
+        carry_rate: (forward / spot).ln() / years,
+        strikes_used: kept.len(),
+        strikes_rejected: quotes.len() - kept.len(),
+    })
+}
+
+/// Least-squares fit of the synthetic on the strike.
+///
+/// Returns the intercept and slope.
+fn fit(quotes: &[ParityQuote]) -> Result<(Decimal, Decimal)> {
+    let n = Decimal::from(quotes.len());
+    let mean_k = quotes.iter().map(|q| q.strike).sum::<Decimal>() / n;
+    let mean_y = quotes.iter().map(ParityQuote::synthetic).sum::<Decimal>() / n;
+    let (mut sxy, mut sxx) = (Decimal::ZERO, Decimal::ZERO);
+    for q in quotes {
+        let dk = q.strike - mean_k;
+        sxy += dk * (q.synthetic() - mean_y);
+        sxx += dk * dk;
+    }
+    if sxx.is_zero() {
+        return Err(Error::pricing("implied carry needs distinct strikes"));
+    }
+    let slope = sxy / sxx;
+    Ok((mean_y - slope * mean_k, slope))
+}
+
+/// Theil-Sen fit of the synthetic on the strike, which outliers cannot
+/// drag the way they drag least squares.
+///
+/// Returns the intercept and slope.
+fn robust_fit(quotes: &[ParityQuote]) -> Result<(Decimal, Decimal)> {
+    let mut slopes = Vec::with_capacity(quotes.len() * (quotes.len() - 1) / 2);
+    for (i, a) in quotes.iter().enumerate() {
+        for b in &quotes[i + 1..] {
+            if a.strike != b.strike {
+                slopes.push((b.synthetic() - a.synthetic()) / (b.strike - a.strike));
+            }
+        }
+    }
+    if slopes.is_empty() {
+        return Err(Error::pricing("implied carry needs distinct strikes"));
+    }
+    let slope = median(slopes);
+    let intercept = median(
+        quotes
+            .iter()
+            .map(|q| q.synthetic() - slope * q.strike)
+            .collect(),
+    );
+    Ok((intercept, slope))
+}
+
+/// Returns the median of a non-empty sample.
+fn median(mut values: Vec<Decimal>) -> Decimal {
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / Decimal::TWO
+    } else {
+        values[mid]
+    }
+}
+
+/// Implied financing and carry by days to expiry for one underlying.
+///
+/// Between expiries both rates are interpolated linearly in days; outside
+/// the fitted expiries the nearest one applies.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImpliedRateCurve {
+    /// Implied carry by days to expiry.
+    points: BTreeMap<Decimal, ImpliedCarry>,
+}
+
+impl ImpliedRateCurve {
+    /// Creates an empty curve.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the implied carry of an expiry.
+    pub fn insert(&mut self, carry: ImpliedCarry) {
+        self.points.insert(carry.days_to_expiry.normalize(), carry);
+    }
+
+    /// Fits an expiry from parity quotes and stores the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of [`implied_carry`]; the curve is unchanged.
+    pub fn fit_expiry(
+        &mut self,
+        spot: Decimal,
+        days_to_expiry: Decimal,
+        quotes: &[ParityQuote],
+        config: &ImpliedRateConfig,
+    ) -> Result<ImpliedCarry> {
+        let carry = implied_carry(spot, days_to_expiry, quotes, config)?;
+        self.insert(carry);
+        Ok(carry)
+    }
+
+    /// Returns the fitted expiries sorted by days to expiry.
+    #[must_use]
+    pub fn points(&self) -> Vec<ImpliedCarry> {
+        self.points.values().copied().collect()
+    }
+
+    /// Returns the number of fitted expiries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Returns true if no expiry is fitted.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns the financing and carry rates at a days to expiry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataError` if the curve is empty.
+    pub fn rates(&self, days: Decimal) -> Result<(Decimal, Decimal)> {
+        let below = self.points.range(..=days).next_back();
+        let above = self.points.range(days..).next();
+        match (below, above) {
+            (Some((d0, p0)), Some((d1, p1))) if d1 > d0 => {
+                let w = (days - d0) / (d1 - d0);
+                Ok((
+                    p0.financing_rate + (p1.financing_rate - p0.financing_rate) * w,
+                    p0.carry_rate + (p1.carry_rate - p0.carry_rate) * w,
+                ))
+            }
+            (Some((_, p)), _) | (None, Some((_, p))) => Ok((p.financing_rate, p.carry_rate)),
+            (None, None) => Err(Error::no_data("implied rate curve is empty")),
+        }
+    }
+
+    /// Returns pricing parameters using the curve's rates at their expiry.
+    ///
+    /// The rate is the financing rate and the dividend yield the borrow
+    /// rate. A negative borrow rate cannot be priced, so in that case the
+    /// rate is set to the carry rate with no dividend yield, which keeps
+    /// the forward exact at the cost of the discounting.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataError` if the curve is empty.
+    pub fn apply(&self, params: PricingParams) -> Result<PricingParams> {
+        let (financing, carry) = self.rates(params.days_to_expiry)?;
+        let borrow = financing - carry;
+        Ok(if borrow >= Decimal::ZERO {
+            params.with_rate(financing).with_dividend_yield(borrow)
+        } else {
+            params.with_rate(carry).with_dividend_yield(Decimal::ZERO)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::OptionStyle;
+
+    fn assert_close(actual: Decimal, expected: Decimal) {
+        assert!(
+            (actual - expected).abs() < dec!(0.000001),
+            "{actual} != {expected}"
+        );
+    }
+
+    /// Quotes consistent with a 5% financing rate and 1% borrow over a year.
+    fn quotes() -> Vec<ParityQuote> {
+        let discount = (-dec!(0.05)).exp();
+        let forward = dec!(100) * dec!(0.04).exp();
+        [70, 80, 90, 100, 110, 120, 130]
+            .into_iter()
+            .map(|k| {
+                let k = Decimal::from(k);
+                let put = dec!(5) + (k - dec!(100)) / dec!(4);
+                ParityQuote::new(k, put + discount * (forward - k), put)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_implied_carry_recovers_rates() {
+        let carry = implied_carry(
+            dec!(100),
+            dec!(365),
+            &quotes(),
+            &ImpliedRateConfig::default(),
+        )
+        .unwrap();
+
+        assert_close(carry.financing_rate, dec!(0.05));
+        assert_close(carry.carry_rate, dec!(0.04));
+        assert_close(carry.borrow_rate(), dec!(0.01));
+        assert_eq!(carry.strikes_used, 7);
+        assert!(carry.basis > dec!(4));
+    }
+
+    #[test]
+    fn test_outlier_strike_is_rejected() {
+        let mut quotes = quotes();
+        quotes[1].call_mid += dec!(3);
+        quotes[3].put_mid -= dec!(2);
+
+        let carry =
+            implied_carry(dec!(100), dec!(365), &quotes, &ImpliedRateConfig::default()).unwrap();
+
+        assert_eq!(carry.strikes_rejected, 2);
+        assert_close(carry.financing_rate, dec!(0.05));
+        assert!(
+            implied_carry(
+                dec!(100),
+                dec!(365),
+                &quotes[..2],
+                &ImpliedRateConfig::default()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_curve_interpolates_and_applies() {
+        let mut curve = ImpliedRateCurve::new();
+        let params = PricingParams::new(
+            dec!(100),
+            dec!(100),
+            dec!(180),
+            dec!(0.5),
+            OptionStyle::Call,
+        );
+        assert!(curve.apply(params).is_err());
+
+        curve
+            .fit_expiry(
+                dec!(100),
+                dec!(365),
+                &quotes(),
+                &ImpliedRateConfig::default(),
+            )
+            .unwrap();
+        let mut short = curve.points()[0];
+        short.days_to_expiry = dec!(30);
+        short.financing_rate = dec!(0.03);
+        short.carry_rate = dec!(0.02);
+        curve.insert(short);
+
+        let (financing, _) = curve.rates(dec!(197.5)).unwrap();
+        assert_close(financing, dec!(0.04));
+        let applied = curve.apply(params).unwrap();
+        assert!(applied.rate > dec!(0.03) && applied.rate < dec!(0.05));
+        assert_close(applied.dividend_yield, dec!(0.01));
+        assert_eq!(curve.len(), 2);
+    }
+}
@@ -0,0 +1,272 @@
+//! Inverse contract module.
+//!
+//! This module provides the conversions needed for inverse options, whose
+//! contract size, premium and P&L are denominated in the underlying coin
+//! (e.g. Deribit BTC options), and dollar-Greek helpers for both linear and
+//! inverse contracts.
+//!
+//! ## Inverse Greeks
+//!
+//! If `V(S)` is the Black-Scholes value in USD, the coin premium is
+//! `P = V / S`. Expressed as coin exposure, the delta of an inverse option
+//! is `S * dP/dS = delta_usd - P`, which is the delta exchanges such as
+//! Deribit report. Gamma becomes `gamma_usd - (delta_usd - P) / S`, and
+//! theta, vega and rho are divided by spot.
+
+use super::extended::ExtendedGreeks;
+use super::greeks::Greeks;
+use crate::error::{Error, Result};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// How a contract's premium and P&L are denominated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ContractSettlement {
+    /// Premium and P&L in the quote currency (e.g. USD).
+    #[default]
+    Linear,
+    /// Premium and P&L in the underlying coin (e.g. BTC).
+    Inverse,
+}
+
+/// Greeks expressed in quote-currency amounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DollarGreeks {
+    /// Value change for a one unit move in spot, scaled to notional (`delta * S`).
+    pub delta: Decimal,
+    /// Change in dollar delta for a 1% move in spot (`gamma * S^2 / 100`).
+    pub gamma: Decimal,
+    /// Value decay per calendar day.
+    pub theta: Decimal,
+    /// Value change per vol point.
+    pub vega: Decimal,
+    /// Value change per percentage point of rate.
+    pub rho: Decimal,
+}
+
+/// Extended Greeks expressed in quote-currency amounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DollarExtendedGreeks {
+    /// First-order dollar Greeks.
+    pub greeks: DollarGreeks,
+    /// Change in dollar delta per vol point (`vanna * S`).
+    pub vanna: Decimal,
+    /// Change in dollar vega per vol point.
+    pub volga: Decimal,
+    /// Change in dollar delta per calendar day (`charm * S`).
+    pub charm: Decimal,
+    /// Change in dollar gamma for a 1% move in spot (`speed * S^3 / 10000`).
+    pub speed: Decimal,
+}
+
+/// Returns an error unless spot is positive.
+fn check_spot(spot: Decimal) -> Result<()> {
+    if spot <= Decimal::ZERO {
+        return Err(Error::pricing("spot must be positive"));
+    }
+    Ok(())
+}
+
+/// Converts a USD premium to a coin premium.
+///
+/// # Errors
+///
+/// Returns `Error::PricingError` if spot is not positive.
+pub fn usd_to_coin(usd: Decimal, spot: Decimal) -> Result<Decimal> {
+    check_spot(spot)?;
+    Ok(usd / spot)
+}
+
+/// Converts a coin amount to USD.
+#[must_use]
+pub fn coin_to_usd(coin: Decimal, spot: Decimal) -> Decimal {
+    coin * spot
+}
+
+/// Converts Black-Scholes (USD) Greeks of one contract to inverse (coin)
+/// Greeks.
+///
+/// # Arguments
+///
+/// * `usd` - Greeks of the USD-priced option
+/// * `usd_price` - Black-Scholes value in USD
+/// * `spot` - Spot price of the underlying
+///
+/// # Errors
+///
+/// Returns `Error::PricingError` if spot is not positive.
+pub fn inverse_greeks(usd: &Greeks, usd_price: Decimal, spot: Decimal) -> Result<Greeks> {
+    check_spot(spot)?;
+    let coin_price = usd_price / spot;
+    let delta = usd.delta - coin_price;
+    Ok(Greeks {
+        delta,
+        gamma: usd.gamma - delta / spot,
+        theta: usd.theta / spot,
+        vega: usd.vega / spot,
+        rho: usd.rho / spot,
+    })
+}
+
+/// Returns dollar Greeks for a contract.
+///
+/// For linear contracts `greeks` are USD Greeks. For inverse contracts
+/// `greeks` are coin Greeks (as returned by [`inverse_greeks`]): delta is a
+/// coin exposure, gamma is per unit of spot, and theta, vega and rho are in
+/// coin, so the non-delta Greeks are converted at spot.
+#[must_use]
+pub fn dollar_greeks(
+    greeks: &Greeks,
+    spot: Decimal,
+    settlement: ContractSettlement,
+) -> DollarGreeks {
+    let value_factor = match settlement {
+        ContractSettlement::Linear => Decimal::ONE,
+        ContractSettlement::Inverse => spot,
+    };
+    DollarGreeks {
+        delta: greeks.delta * spot,
+        gamma: greeks.gamma * spot * spot / dec!(100),
+        theta: greeks.theta * value_factor,
+        vega: greeks.vega * value_factor,
+        rho: greeks.rho * value_factor,
+    }
+}
+
+/// Returns dollar extended Greeks for a contract.
+///
+/// Vanna and charm convert like delta, speed like gamma, and volga like
+/// vega; see [`dollar_greeks`] for the settlement conventions.
+#[must_use]
+pub fn dollar_extended_greeks(
+    greeks: &ExtendedGreeks,
+    spot: Decimal,
+    settlement: ContractSettlement,
+) -> DollarExtendedGreeks {
+    let value_factor = match settlement {
+        ContractSettlement::Linear => Decimal::ONE,
+        ContractSettlement::Inverse => spot,
+    };
+    DollarExtendedGreeks {
+        greeks: dollar_greeks(&greeks.greeks, spot, settlement),
+        vanna: greeks.vanna * spot,
+        volga: greeks.volga * value_factor,
+        charm: greeks.charm * spot,
+        speed: greeks.speed * spot * spot * spot / dec!(10000),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_premium_conversion() {
+        assert_eq!(usd_to_coin(dec!(500), dec!(10000)).unwrap(), dec!(0.05));
+        assert_eq!(coin_to_usd(dec!(0.05), dec!(10000)), dec!(500));
+        assert!(usd_to_coin(dec!(500), Decimal::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_inverse_delta_subtracts_premium() {
+        // BTC at 10000, call worth 0.05 BTC with Black-Scholes delta 0.6:
+        // the exchange shows a delta of 0.55.
+        let usd = Greeks::new(dec!(0.6), dec!(0.0001), dec!(-20), dec!(30), dec!(5));
+        let coin = inverse_greeks(&usd, dec!(500), dec!(10000)).unwrap();
+
+        assert_eq!(coin.delta, dec!(0.55));
+        assert_eq!(coin.gamma, dec!(0.000045));
+        assert_eq!(coin.theta, dec!(-0.002));
+        assert_eq!(coin.vega, dec!(0.003));
+    }
+
+    #[test]
+    fn test_put_inverse_delta() {
+        let usd = Greeks::new(
+            dec!(-0.4),
+            Decimal::ZERO,
+            Decimal::ZERO,
+            Decimal::ZERO,
+            Decimal::ZERO,
+        );
+        let coin = inverse_greeks(&usd, dec!(300), dec!(10000)).unwrap();
+        assert_eq!(coin.delta, dec!(-0.43));
+    }
+
+    #[test]
+    fn test_btc_worked_example() {
+        // A 30-day BTC option at a 60000 index priced the way Deribit quotes
+        // it: premium in BTC is the USD value over the index, and delta is
+        // the Black-Scholes delta less the BTC premium. Both are checked
+        // against finite differences of the BTC premium itself.
+        use crate::pricing::PricingParams;
+        use optionstratlib::OptionStyle;
+
+        let spot = dec!(60000);
+        let bump = dec!(1);
+        for (strike, style) in [
+            (dec!(65000), OptionStyle::Call),
+            (dec!(55000), OptionStyle::Put),
+        ] {
+            let at = |s: Decimal| PricingParams::new(s, strike, dec!(30), dec!(0.5), style);
+            let coin_premium = |s: Decimal| usd_to_coin(at(s).price().unwrap(), s).unwrap();
+            let usd_price = at(spot).price().unwrap();
+            let coin = inverse_greeks(&at(spot).greeks().unwrap(), usd_price, spot).unwrap();
+
+            let premium = coin_premium(spot);
+            assert!((coin_to_usd(premium, spot) - usd_price).abs() < dec!(0.000001));
+            assert!(premium > dec!(0.005) && premium < dec!(0.05));
+
+            // Coin delta is S * dP/dS and gamma its derivative in spot.
+            let coin_delta = |s: Decimal| {
+                s * (coin_premium(s + bump) - coin_premium(s - bump)) / (bump * Decimal::TWO)
+            };
+            let step = dec!(100);
+            let coin_gamma =
+                (coin_delta(spot + step) - coin_delta(spot - step)) / (step * Decimal::TWO);
+            assert!((coin.delta - coin_delta(spot)).abs() < dec!(0.0001));
+            assert!((coin.gamma - coin_gamma).abs() < dec!(0.0000001));
+
+            // Theta and vega in BTC convert back to the USD Greeks at spot.
+            let dollars = dollar_greeks(&coin, spot, ContractSettlement::Inverse);
+            assert_eq!(dollars.delta, coin.delta * spot);
+            assert!((dollars.vega - at(spot).greeks().unwrap().vega).abs() < dec!(0.000001));
+        }
+    }
+
+    #[test]
+    fn test_dollar_greeks() {
+        let greeks = Greeks::new(
+            dec!(0.5),
+            dec!(0.0002),
+            dec!(-0.001),
+            dec!(0.002),
+            Decimal::ZERO,
+        );
+
+        let linear = dollar_greeks(&greeks, dec!(10000), ContractSettlement::Linear);
+        assert_eq!(linear.delta, dec!(5000));
+        assert_eq!(linear.gamma, dec!(200));
+        assert_eq!(linear.vega, dec!(0.002));
+
+        let inverse = dollar_greeks(&greeks, dec!(10000), ContractSettlement::Inverse);
+        assert_eq!(inverse.delta, dec!(5000));
+        assert_eq!(inverse.theta, dec!(-10));
+        assert_eq!(inverse.vega, dec!(20));
+
+        let extended = ExtendedGreeks::new(
+            greeks,
+            dec!(0.001),
+            dec!(0.0005),
+            dec!(-0.002),
+            dec!(-0.00000001),
+        );
+        let dollars = dollar_extended_greeks(&extended, dec!(10000), ContractSettlement::Inverse);
+        assert_eq!(dollars.greeks, inverse);
+        assert_eq!(dollars.vanna, dec!(10));
+        assert_eq!(dollars.volga, dec!(5));
+        assert_eq!(dollars.charm, dec!(-20));
+        assert_eq!(dollars.speed, dec!(-1));
+    }
+}
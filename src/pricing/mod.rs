@@ -0,0 +1,61 @@
+//! Pricing module.
+//!
+//! This module provides the pricing-side value types shared by the risk,
+//! inventory and P&L layers, Black-Scholes pricing through OptionStratLib,
+//! and a parametric volatility surface with bump-and-reprice sensitivities.
+//!
+//! ## Components
+//!
+//! - [`Greeks`]: First-order option sensitivities that can be scaled and aggregated
+//! - [`ExtendedGreeks`]: Greeks with vanna, volga, charm and speed for pin and expiry risk
+//! - [`PricingParams`]: Inputs for pricing a European option, with days to expiry over [`DAYS_PER_YEAR`]
+//! - [`VolatilitySurface`]: Per-expiry [`SmileParams`] pillars with interpolation
+//! - [`SurfacePoint`]: Observed IVs a surface is fitted to, with calendar and butterfly [`ArbitrageViolation`] checks
+//! - [`vega_ladder`]: P&L of bumping each pillar's ATM vol, skew and curvature
+//! - [`inverse_greeks`], [`dollar_greeks`] and [`dollar_extended_greeks`]: Coin-margined contracts and dollar Greeks
+//! - [`RateCurve`] and [`DividendSchedule`]: Rate, dividend yield, borrow and cash dividend term structures giving the forward per expiry
+//! - [`ImpliedRateCurve`]: Financing and carry implied by put-call parity per expiry
+//! - [`ReferenceMap`]: Per-expiry forward reference instrument and basis, with rolls
+//! - [`FastOption`] and [`price_batch`]: `f64` batch pricing and implied volatility for quote generation only
+//!
+//! ## Conventions
+//!
+//! - `delta` and `gamma` are per unit of underlying price
+//! - `theta` is per calendar day
+//! - `vega` is per vol point (0.01 absolute change in implied volatility)
+//! - `rho` is per percentage point (0.01 absolute change in the rate)
+//! - [`ExtendedGreeks`] document the units of the cross sensitivities
+
+mod extended;
+mod fast;
+mod greeks;
+mod implied_rate;
+mod inverse;
+mod params;
+mod reference;
+mod surface;
+mod surface_fit;
+mod surface_risk;
+mod term_structure;
+
+pub use extended::ExtendedGreeks;
+pub use fast::{
+    FAST_PRICE_TOLERANCE, FastOption, MAX_IMPLIED_VOLATILITY, MIN_IMPLIED_VOLATILITY,
+    price_and_delta_batch, price_batch, to_decimal,
+};
+pub use greeks::Greeks;
+pub use implied_rate::{
+    ImpliedCarry, ImpliedRateConfig, ImpliedRateCurve, ParityQuote, implied_carry,
+};
+pub use inverse::{
+    ContractSettlement, DollarExtendedGreeks, DollarGreeks, coin_to_usd, dollar_extended_greeks,
+    dollar_greeks, inverse_greeks, usd_to_coin,
+};
+pub use params::{DAYS_PER_YEAR, PricingParams};
+pub use reference::{ForwardReference, ReferenceMap, ReferenceRoll};
+pub use surface::{SmileParams, VolatilitySurface};
+pub use surface_fit::{ArbitrageKind, ArbitrageViolation, SurfacePoint};
+pub use surface_risk::{
+    OptionExposure, PillarSensitivity, SurfaceBumpSizes, VegaLadder, vega_ladder,
+};
+pub use term_structure::{DiscreteDividend, DividendSchedule, RateCurve};
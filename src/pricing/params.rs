@@ -0,0 +1,273 @@
+//! Pricing parameters module.
+//!
+//! This module provides [`PricingParams`], the inputs needed to price a
+//! European option, and evaluates price and Greeks with OptionStratLib's
+//! Black-Scholes implementation.
+
+use super::extended::ExtendedGreeks;
+use super::greeks::Greeks;
+use crate::error::{Error, Result};
+use optionstratlib::greeks::{delta, gamma, rho, theta, vega};
+use optionstratlib::prelude::Positive;
+use optionstratlib::pricing::black_scholes;
+use optionstratlib::{ExpirationDate, OptionStyle, OptionType, Options, Side};
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+
+/// Days per year used to convert days to expiry to year fractions.
+pub const DAYS_PER_YEAR: Decimal = dec!(365);
+
+/// Inputs for pricing a single European option.
+///
+/// Rates and volatility are annualized decimals (`0.05` = 5%).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PricingParams {
+    /// Spot price of the underlying.
+    pub spot: Decimal,
+    /// Strike price.
+    pub strike: Decimal,
+    /// Days to expiration.
+    pub days_to_expiry: Decimal,
+    /// Implied volatility.
+    pub volatility: Decimal,
+    /// Risk-free rate.
+    pub rate: Decimal,
+    /// Continuous dividend yield.
+    pub dividend_yield: Decimal,
+    /// Call or put.
+    pub style: OptionStyle,
+}
+
+impl PricingParams {
+    /// Creates pricing parameters with zero rate and dividend yield.
+    ///
+    /// # Arguments
+    ///
+    /// * `spot` - Spot price of the underlying
+    /// * `strike` - Strike price
+    /// * `days_to_expiry` - Days to expiration
+    /// * `volatility` - Implied volatility
+    /// * `style` - Call or put
+    #[must_use]
+    pub const fn new(
+        spot: Decimal,
+        strike: Decimal,
+        days_to_expiry: Decimal,
+        volatility: Decimal,
+        style: OptionStyle,
+    ) -> Self {
+        Self {
+            spot,
+            strike,
+            days_to_expiry,
+            volatility,
+            rate: Decimal::ZERO,
+            dividend_yield: Decimal::ZERO,
+            style,
+        }
+    }
+
+    /// Sets the risk-free rate.
+    #[must_use]
+    pub const fn with_rate(mut self, rate: Decimal) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    /// Sets the continuous dividend yield.
+    #[must_use]
+    pub const fn with_dividend_yield(mut self, dividend_yield: Decimal) -> Self {
+        self.dividend_yield = dividend_yield;
+        self
+    }
+
+    /// Sets the implied volatility.
+    #[must_use]
+    pub const fn with_volatility(mut self, volatility: Decimal) -> Self {
+        self.volatility = volatility;
+        self
+    }
+
+    /// Builds the OptionStratLib option for one long contract.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PricingError` if an input that must be positive is not.
+    pub fn to_options(&self) -> Result<Options> {
+        let positive = |value: Decimal, name: &str| {
+            Positive::new_decimal(value)
+                .map_err(|e| Error::pricing(format!("{name} must be non-negative: {e}")))
+        };
+        Ok(Options::new(
+            OptionType::European,
+            Side::Long,
+            String::new(),
+            positive(self.strike, "strike")?,
+            ExpirationDate::Days(positive(self.days_to_expiry, "days_to_expiry")?),
+            positive(self.volatility, "volatility")?,
+            Positive::ONE,
+            positive(self.spot, "spot")?,
+            self.rate,
+            self.style,
+            positive(self.dividend_yield, "dividend_yield")?,
+            None,
+        ))
+    }
+
+    /// Returns the Black-Scholes price of one contract.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PricingError` if the inputs are invalid or pricing fails.
+    pub fn price(&self) -> Result<Decimal> {
+        black_scholes(&self.to_options()?).map_err(|e| Error::pricing(e.to_string()))
+    }
+
+    /// Returns the Black-Scholes Greeks of one contract.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PricingError` if the inputs are invalid, or
+    /// `Error::GreeksError` if a Greek cannot be computed.
+    pub fn greeks(&self) -> Result<Greeks> {
+        let option = self.to_options()?;
+        let greek = |value: std::result::Result<Decimal, _>| {
+            value.map_err(|e: optionstratlib::error::GreeksError| Error::greeks(e.to_string()))
+        };
+        Ok(Greeks::new(
+            greek(delta(&option))?,
+            greek(gamma(&option))?,
+            greek(theta(&option))?,
+            greek(vega(&option))?,
+            greek(rho(&option))?,
+        ))
+    }
+
+    /// Returns the Black-Scholes Greeks of one contract with vanna, volga,
+    /// charm and speed.
+    ///
+    /// The cross sensitivities are zero at expiry or at zero volatility.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Self::greeks`].
+    pub fn extended_greeks(&self) -> Result<ExtendedGreeks> {
+        let greeks = self.greeks()?;
+        let t = self.days_to_expiry / DAYS_PER_YEAR;
+        let sigma = self.volatility;
+        if t <= Decimal::ZERO
+            || sigma <= Decimal::ZERO
+            || self.spot <= Decimal::ZERO
+            || self.strike <= Decimal::ZERO
+        {
+            return Ok(ExtendedGreeks::from(greeks));
+        }
+        let (s, r, q) = (self.spot, self.rate, self.dividend_yield);
+        let sqrt_t = t.sqrt().unwrap_or(Decimal::ZERO);
+        let sigma_sqrt_t = sigma * sqrt_t;
+        let d1 =
+            ((s / self.strike).ln() + (r - q + sigma * sigma / Decimal::TWO) * t) / sigma_sqrt_t;
+        let d2 = d1 - sigma_sqrt_t;
+        let carry = (-q * t).exp();
+        let pdf = carry * d1.norm_pdf();
+        let gamma = pdf / (s * sigma_sqrt_t);
+        let vega = s * pdf * sqrt_t;
+        let drift = pdf * (Decimal::TWO * (r - q) * t - d2 * sigma_sqrt_t)
+            / (Decimal::TWO * t * sigma_sqrt_t);
+        let charm = match self.style {
+            OptionStyle::Call => q * carry * d1.norm_cdf() - drift,
+            OptionStyle::Put => -q * carry * (-d1).norm_cdf() - drift,
+        };
+        Ok(ExtendedGreeks::new(
+            greeks,
+            -pdf * d2 / sigma / dec!(100),
+            vega * d1 * d2 / sigma / dec!(10000),
+            charm / DAYS_PER_YEAR,
+            -gamma / s * (d1 / sigma_sqrt_t + Decimal::ONE),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::MathematicalOps;
+    use rust_decimal_macros::dec;
+
+    fn atm_call() -> PricingParams {
+        PricingParams::new(
+            dec!(100),
+            dec!(100),
+            dec!(365),
+            dec!(0.2),
+            OptionStyle::Call,
+        )
+        .with_rate(dec!(0.05))
+    }
+
+    #[test]
+    fn test_price_matches_black_scholes() {
+        let price = atm_call().price().unwrap();
+        assert!((price - dec!(10.4506)).abs() < dec!(0.01));
+    }
+
+    #[test]
+    fn test_put_call_parity() {
+        let call = atm_call().price().unwrap();
+        let put = PricingParams {
+            style: OptionStyle::Put,
+            ..atm_call()
+        }
+        .price()
+        .unwrap();
+
+        // C - P = S - K e^{-rT}
+        let forward_value = dec!(100) - dec!(100) * (-dec!(0.05)).exp();
+        assert!((call - put - forward_value).abs() < dec!(0.01));
+    }
+
+    #[test]
+    fn test_greeks_conventions() {
+        let greeks = atm_call().greeks().unwrap();
+
+        assert!((greeks.delta - dec!(0.6368)).abs() < dec!(0.001));
+        assert!(greeks.gamma > Decimal::ZERO);
+        // Per calendar day and per vol point.
+        assert!(greeks.theta < Decimal::ZERO && greeks.theta > dec!(-0.05));
+        assert!((greeks.vega - dec!(0.3752)).abs() < dec!(0.005));
+    }
+
+    #[test]
+    fn test_extended_greeks_match_bumps() {
+        let params = atm_call();
+        let extended = params.extended_greeks().unwrap();
+        // Central differences over one unit of each bumped input.
+        let diff =
+            |down: PricingParams, up: PricingParams| (down.greeks().unwrap(), up.greeks().unwrap());
+
+        let (down, up) = diff(
+            params.with_volatility(dec!(0.195)),
+            params.with_volatility(dec!(0.205)),
+        );
+        assert!((extended.vanna - (up.delta - down.delta)).abs() < dec!(0.00001));
+        assert!((extended.volga - (up.vega - down.vega)).abs() < dec!(0.00001));
+
+        let days = |days_to_expiry| PricingParams {
+            days_to_expiry,
+            ..params
+        };
+        let (later, earlier) = diff(days(dec!(364.5)), days(dec!(365.5)));
+        assert!((extended.charm - (later.delta - earlier.delta)).abs() < dec!(0.00001));
+
+        let spot = |spot| PricingParams { spot, ..params };
+        let (lower, higher) = diff(spot(dec!(99.5)), spot(dec!(100.5)));
+        assert!((extended.speed - (higher.gamma - lower.gamma)).abs() < dec!(0.00001));
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let params = atm_call().with_volatility(dec!(-0.1));
+        assert!(params.price().is_err());
+        assert!(params.greeks().is_err());
+    }
+}
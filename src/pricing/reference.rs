@@ -0,0 +1,268 @@
+//! Forward reference module.
+//!
+//! This module provides the [`ReferenceMap`], which records for each
+//! expiry the instrument its forward is derived from (e.g. the June future)
+//! and the basis between that instrument and the expiry's forward, and the
+//! reference roll that moves one or more expiries to a new instrument.
+//!
+//! A roll keeps the implied forward continuous by default: the new basis
+//! is the old forward minus the new instrument's price, so theos do not
+//! jump at the roll and only start tracking the new instrument afterwards.
+//! Expiries referencing other instruments are not touched.
+
+use crate::error::{Error, Result};
+use crossbeam_skiplist::SkipMap;
+use optionstratlib::ExpirationDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Instrument and basis an expiry's forward is derived from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForwardReference {
+    /// Reference instrument symbol.
+    pub instrument: String,
+    /// Forward minus the reference price.
+    pub basis: Decimal,
+}
+
+impl ForwardReference {
+    /// Creates a reference.
+    #[must_use]
+    pub fn new(instrument: impl Into<String>, basis: Decimal) -> Self {
+        Self {
+            instrument: instrument.into(),
+            basis,
+        }
+    }
+
+    /// Returns the forward implied by a reference price.
+    #[must_use]
+    pub fn forward(&self, reference_price: Decimal) -> Decimal {
+        reference_price + self.basis
+    }
+}
+
+/// Result of rolling one expiry to a new reference instrument.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferenceRoll {
+    /// Expiry that was rolled.
+    pub expiration: ExpirationDate,
+    /// Reference before the roll.
+    pub from: ForwardReference,
+    /// Reference after the roll.
+    pub to: ForwardReference,
+    /// Forward implied by the old reference at the roll.
+    pub old_forward: Decimal,
+    /// Forward implied by the new reference at the roll.
+    pub new_forward: Decimal,
+}
+
+impl ReferenceRoll {
+    /// Returns the change in forward caused by the roll.
+    #[must_use]
+    pub fn forward_change(&self) -> Decimal {
+        self.new_forward - self.old_forward
+    }
+
+    /// Returns a theo re-marked to the new forward to first order.
+    ///
+    /// # Arguments
+    ///
+    /// * `theo` - Theo under the old reference
+    /// * `delta` - Delta of the contract to the forward
+    #[must_use]
+    pub fn adjust_theo(&self, theo: Decimal, delta: Decimal) -> Decimal {
+        theo + delta * self.forward_change()
+    }
+}
+
+/// Forward references by expiry for one underlying.
+///
+/// Uses `SkipMap` for thread-safe concurrent access, so rolling one expiry
+/// does not block readers of the others.
+pub struct ReferenceMap {
+    /// Underlying symbol.
+    underlying: String,
+    /// References by expiry.
+    references: SkipMap<ExpirationDate, ForwardReference>,
+}
+
+impl ReferenceMap {
+    /// Creates an empty map.
+    #[must_use]
+    pub fn new(underlying: impl Into<String>) -> Self {
+        Self {
+            underlying: underlying.into(),
+            references: SkipMap::new(),
+        }
+    }
+
+    /// Returns the underlying symbol.
+    #[must_use]
+    pub fn underlying(&self) -> &str {
+        &self.underlying
+    }
+
+    /// Returns the number of expiries with a reference.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.references.len()
+    }
+
+    /// Returns true if no expiry has a reference.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.references.is_empty()
+    }
+
+    /// Sets the reference of an expiry.
+    pub fn set(&self, expiration: ExpirationDate, reference: ForwardReference) {
+        self.references.insert(expiration, reference);
+    }
+
+    /// Returns the reference of an expiry.
+    #[must_use]
+    pub fn reference(&self, expiration: &ExpirationDate) -> Option<ForwardReference> {
+        self.references.get(expiration).map(|e| e.value().clone())
+    }
+
+    /// Returns the expiries referencing an instrument.
+    #[must_use]
+    pub fn expirations_on(&self, instrument: &str) -> Vec<ExpirationDate> {
+        self.references
+            .iter()
+            .filter(|e| e.value().instrument == instrument)
+            .map(|e| *e.key())
+            .collect()
+    }
+
+    /// Returns the forward of an expiry given its reference price.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MarketDataError` if the expiry has no reference.
+    pub fn forward(
+        &self,
+        expiration: &ExpirationDate,
+        reference_price: Decimal,
+    ) -> Result<Decimal> {
+        self.reference(expiration)
+            .map(|r| r.forward(reference_price))
+            .ok_or_else(|| no_reference(expiration))
+    }
+
+    /// Rolls one expiry to a new reference instrument.
+    ///
+    /// # Arguments
+    ///
+    /// * `expiration` - Expiry to roll
+    /// * `instrument` - New reference instrument
+    /// * `from_price` - Price of the old instrument at the roll
+    /// * `to_price` - Price of the new instrument at the roll
+    /// * `basis` - New basis, or `None` to keep the forward continuous
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MarketDataError` if the expiry has no reference, or
+    /// `Error::ValidationError` if it already references `instrument`.
+    pub fn roll(
+        &self,
+        expiration: &ExpirationDate,
+        instrument: &str,
+        from_price: Decimal,
+        to_price: Decimal,
+        basis: Option<Decimal>,
+    ) -> Result<ReferenceRoll> {
+        let from = self
+            .reference(expiration)
+            .ok_or_else(|| no_reference(expiration))?;
+        if from.instrument == instrument {
+            return Err(Error::validation(format!(
+                "expiry {expiration} already references {instrument}"
+            )));
+        }
+        let old_forward = from.forward(from_price);
+        let to = ForwardReference::new(instrument, basis.unwrap_or(old_forward - to_price));
+        let new_forward = to.forward(to_price);
+        self.references.insert(*expiration, to.clone());
+        Ok(ReferenceRoll {
+            expiration: *expiration,
+            from,
+            to,
+            old_forward,
+            new_forward,
+        })
+    }
+
+    /// Rolls every expiry referencing `from` to `to`, keeping each
+    /// forward continuous.
+    ///
+    /// Returns one roll per expiry moved.
+    pub fn roll_instrument(
+        &self,
+        from: &str,
+        to: &str,
+        from_price: Decimal,
+        to_price: Decimal,
+    ) -> Vec<ReferenceRoll> {
+        self.expirations_on(from)
+            .iter()
+            .filter_map(|expiration| self.roll(expiration, to, from_price, to_price, None).ok())
+            .collect()
+    }
+}
+
+fn no_reference(expiration: &ExpirationDate) -> Error {
+    Error::market_data(format!("no forward reference for expiry {expiration}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::prelude::pos_or_panic;
+    use rust_decimal_macros::dec;
+
+    fn map() -> (ReferenceMap, ExpirationDate, ExpirationDate, ExpirationDate) {
+        let map = ReferenceMap::new("ES");
+        let near = ExpirationDate::Days(pos_or_panic!(10.0));
+        let mid = ExpirationDate::Days(pos_or_panic!(40.0));
+        let far = ExpirationDate::Days(pos_or_panic!(120.0));
+        map.set(near, ForwardReference::new("ESM4", dec!(-2)));
+        map.set(mid, ForwardReference::new("ESM4", dec!(8)));
+        map.set(far, ForwardReference::new("ESU4", dec!(5)));
+        (map, near, mid, far)
+    }
+
+    #[test]
+    fn test_roll_keeps_forward_continuous() {
+        let (map, near, _, _) = map();
+        let roll = map
+            .roll(&near, "ESU4", dec!(5000), dec!(5040), None)
+            .unwrap();
+        assert_eq!(roll.old_forward, dec!(4998));
+        assert_eq!(roll.to.basis, dec!(-42));
+        assert_eq!(roll.forward_change(), Decimal::ZERO);
+        assert_eq!(map.forward(&near, dec!(5050)).unwrap(), dec!(5008));
+    }
+
+    #[test]
+    fn test_roll_with_basis_reprices_theos() {
+        let (map, near, _, _) = map();
+        let roll = map
+            .roll(&near, "ESU4", dec!(5000), dec!(5040), Some(dec!(-40)))
+            .unwrap();
+        assert_eq!(roll.forward_change(), dec!(2));
+        assert_eq!(roll.adjust_theo(dec!(30), dec!(0.5)), dec!(31));
+    }
+
+    #[test]
+    fn test_roll_instrument_leaves_other_expiries() {
+        let (map, near, mid, far) = map();
+        let rolls = map.roll_instrument("ESM4", "ESU4", dec!(5000), dec!(5040));
+        assert_eq!(rolls.len(), 2);
+        assert_eq!(map.reference(&near).unwrap().instrument, "ESU4");
+        assert_eq!(map.reference(&mid).unwrap().basis, dec!(-32));
+        assert_eq!(map.reference(&far).unwrap().basis, dec!(5));
+        assert!(map.roll(&far, "ESU4", dec!(1), dec!(1), None).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+//! Volatility surface module.
+//!
+//! This module provides [`VolatilitySurface`], a parametric implied
+//! volatility surface defined by per-expiry [`SmileParams`] pillars.
+
+use crate::error::{Error, Result};
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Lowest volatility the surface will return.
+const MIN_VOLATILITY: Decimal = dec!(0.01);
+
+/// Smile parameters at a single expiry pillar.
+///
+/// The smile is quadratic in log-moneyness `x = ln(K / S)`:
+/// `vol(x) = atm_vol + skew * x + curvature * x^2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SmileParams {
+    /// At-the-money volatility.
+    pub atm_vol: Decimal,
+    /// Slope of the smile in log-moneyness.
+    pub skew: Decimal,
+    /// Convexity of the smile in log-moneyness.
+    pub curvature: Decimal,
+}
+
+impl SmileParams {
+    /// Creates smile parameters.
+    #[must_use]
+    pub const fn new(atm_vol: Decimal, skew: Decimal, curvature: Decimal) -> Self {
+        Self {
+            atm_vol,
+            skew,
+            curvature,
+        }
+    }
+
+    /// Returns the volatility at a log-moneyness, floored at 1%.
+    #[must_use]
+    pub fn vol_at(&self, log_moneyness: Decimal) -> Decimal {
+        (self.atm_vol + self.skew * log_moneyness + self.curvature * log_moneyness * log_moneyness)
+            .max(MIN_VOLATILITY)
+    }
+}
+
+/// Parametric implied volatility surface.
+///
+/// Pillars are keyed by days to expiry. Between pillars, ATM volatility is
+/// interpolated linearly in total variance and skew and curvature linearly
+/// in time; outside the pillar range the nearest pillar is used.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VolatilitySurface {
+    /// Smile parameters by days to expiry.
+    pillars: BTreeMap<Decimal, SmileParams>,
+}
+
+impl VolatilitySurface {
+    /// Creates an empty surface.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces a pillar, returning the surface.
+    #[must_use]
+    pub fn with_pillar(mut self, days: Decimal, params: SmileParams) -> Self {
+        self.set_pillar(days, params);
+        self
+    }
+
+    /// Adds or replaces a pillar.
+    ///
+    /// # Arguments
+    ///
+    /// * `days` - Days to expiry of the pillar
+    /// * `params` - Smile parameters at the pillar
+    pub fn set_pillar(&mut self, days: Decimal, params: SmileParams) {
+        self.pillars.insert(days.normalize(), params);
+    }
+
+    /// Returns the smile parameters at a pillar.
+    #[must_use]
+    pub fn pillar(&self, days: Decimal) -> Option<SmileParams> {
+        self.pillars.get(&days.normalize()).copied()
+    }
+
+    /// Returns the pillars sorted by days to expiry.
+    #[must_use]
+    pub fn pillars(&self) -> Vec<(Decimal, SmileParams)> {
+        self.pillars.iter().map(|(d, p)| (*d, *p)).collect()
+    }
+
+    /// Returns the number of pillars.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.pillars.len()
+    }
+
+    /// Returns true if the surface has no pillars.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.pillars.is_empty()
+    }
+
+    /// Returns the interpolated smile parameters at an expiry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if the surface has no pillars.
+    pub fn smile(&self, days: Decimal) -> Result<SmileParams> {
+        let below = self.pillars.range(..=days).next_back();
+        let above = self.pillars.range(days..).next();
+
+        match (below, above) {
+            (None, None) => Err(Error::no_data("volatility surface has no pillars")),
+            (Some((_, p)), None) | (None, Some((_, p))) => Ok(*p),
+            (Some((d0, p0)), Some((d1, p1))) => {
+                if d0 == d1 {
+                    return Ok(*p0);
+                }
+                let w = (days - d0) / (d1 - d0);
+                let var0 = p0.atm_vol * p0.atm_vol * d0;
+                let var1 = p1.atm_vol * p1.atm_vol * d1;
+                let variance = var0 + (var1 - var0) * w;
+                let atm_vol = if days.is_zero() || variance <= Decimal::ZERO {
+                    p0.atm_vol
+                } else {
+                    (variance / days).sqrt().unwrap_or(p0.atm_vol)
+                };
+                Ok(SmileParams {
+                    atm_vol,
+                    skew: p0.skew + (p1.skew - p0.skew) * w,
+                    curvature: p0.curvature + (p1.curvature - p0.curvature) * w,
+                })
+            }
+        }
+    }
+
+    /// Returns the implied volatility for a strike and expiry.
+    ///
+    /// # Arguments
+    ///
+    /// * `days` - Days to expiry
+    /// * `strike` - Strike price
+    /// * `spot` - Spot price of the underlying
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if the surface has no pillars, or
+    /// `Error::PricingError` if the strike or spot is not positive.
+    pub fn vol(&self, days: Decimal, strike: Decimal, spot: Decimal) -> Result<Decimal> {
+        if strike <= Decimal::ZERO || spot <= Decimal::ZERO {
+            return Err(Error::pricing("strike and spot must be positive"));
+        }
+        let smile = self.smile(days)?;
+        Ok(smile.vol_at((strike / spot).ln()))
+    }
+
+    /// Returns a copy of the surface with one pillar shifted.
+    ///
+    /// # Arguments
+    ///
+    /// * `days` - Pillar to bump
+    /// * `shift` - Added to the pillar's ATM volatility, skew and curvature
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if the pillar does not exist.
+    pub fn bumped(&self, days: Decimal, shift: SmileParams) -> Result<Self> {
+        let mut surface = self.clone();
+        let params = surface
+            .pillars
+            .get_mut(&days.normalize())
+            .ok_or_else(|| Error::no_data(format!("no pillar at {days} days")))?;
+        params.atm_vol += shift.atm_vol;
+        params.skew += shift.skew;
+        params.curvature += shift.curvature;
+        Ok(surface)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn surface() -> VolatilitySurface {
+        VolatilitySurface::new()
+            .with_pillar(
+                dec!(30),
+                SmileParams::new(dec!(0.20), dec!(-0.10), dec!(0.50)),
+            )
+            .with_pillar(
+                dec!(90),
+                SmileParams::new(dec!(0.30), dec!(-0.20), dec!(0.30)),
+            )
+    }
+
+    #[test]
+    fn test_empty_surface() {
+        assert!(
+            VolatilitySurface::new()
+                .vol(dec!(30), dec!(100), dec!(100))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_atm_vol_at_pillar() {
+        let vol = surface().vol(dec!(30), dec!(100), dec!(100)).unwrap();
+        assert_eq!(vol, dec!(0.20));
+    }
+
+    #[test]
+    fn test_skew_lowers_upside_vol() {
+        let s = surface();
+        let down = s.vol(dec!(30), dec!(90), dec!(100)).unwrap();
+        let up = s.vol(dec!(30), dec!(110), dec!(100)).unwrap();
+        assert!(down > up);
+    }
+
+    #[test]
+    fn test_total_variance_interpolation() {
+        let smile = surface().smile(dec!(60)).unwrap();
+        // (0.04 * 30 + (0.09 * 90 - 0.04 * 30) / 2) / 60 = 0.0775
+        let expected = dec!(0.0775).sqrt().unwrap();
+        assert!((smile.atm_vol - expected).abs() < dec!(0.000001));
+        assert_eq!(smile.skew, dec!(-0.15));
+    }
+
+    #[test]
+    fn test_flat_extrapolation() {
+        let s = surface();
+        assert_eq!(s.smile(dec!(7)).unwrap(), s.pillar(dec!(30)).unwrap());
+        assert_eq!(s.smile(dec!(365)).unwrap(), s.pillar(dec!(90)).unwrap());
+    }
+
+    #[test]
+    fn test_bumped_leaves_original() {
+        let s = surface();
+        let bumped = s
+            .bumped(
+                dec!(30),
+                SmileParams::new(dec!(0.01), Decimal::ZERO, Decimal::ZERO),
+            )
+            .unwrap();
+
+        assert_eq!(bumped.pillar(dec!(30)).unwrap().atm_vol, dec!(0.21));
+        assert_eq!(s.pillar(dec!(30)).unwrap().atm_vol, dec!(0.20));
+        assert!(s.bumped(dec!(45), SmileParams::default()).is_err());
+    }
+}
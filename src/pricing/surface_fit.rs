@@ -0,0 +1,381 @@
+//! Surface fitting module.
+//!
+//! This module builds a [`VolatilitySurface`] from observed
+//! `(expiry, strike, IV)` points, checks the surface for static arbitrage
+//! and turns it into [`PricingParams`] for a whole chain.
+//!
+//! ## Fitting
+//!
+//! Each expiry's points are fitted by least squares to the quadratic smile
+//! of [`SmileParams`] in log-moneyness. With two distinct strikes the smile
+//! is linear, with one it is flat. Interpolation across expiries is the
+//! surface's own (linear in total variance).
+//!
+//! ## Arbitrage
+//!
+//! - Calendar: total variance `vol^2 * T` must not fall with expiry at any
+//!   checked strike
+//! - Butterfly: call prices must be convex in strike at every pillar
+
+use super::params::{DAYS_PER_YEAR, PricingParams};
+use super::surface::{SmileParams, VolatilitySurface};
+use crate::error::{Error, Result};
+use optionstratlib::OptionStyle;
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Violations smaller than this are treated as numerical noise.
+const ARBITRAGE_TOLERANCE: Decimal = dec!(0.000001);
+
+/// An observed implied volatility.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SurfacePoint {
+    /// Days to expiry.
+    pub days: Decimal,
+    /// Strike price.
+    pub strike: Decimal,
+    /// Implied volatility.
+    pub iv: Decimal,
+}
+
+impl SurfacePoint {
+    /// Creates a surface point.
+    #[must_use]
+    pub const fn new(days: Decimal, strike: Decimal, iv: Decimal) -> Self {
+        Self { days, strike, iv }
+    }
+}
+
+/// Kind of static arbitrage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ArbitrageKind {
+    /// Total variance decreases from one expiry to the next.
+    Calendar,
+    /// Call prices are not convex in strike.
+    Butterfly,
+}
+
+/// A static arbitrage found on the surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArbitrageViolation {
+    /// Kind of arbitrage.
+    pub kind: ArbitrageKind,
+    /// Expiry of the violation (the later one for calendar arbitrage).
+    pub days: Decimal,
+    /// Strike of the violation (the middle one for butterfly arbitrage).
+    pub strike: Decimal,
+    /// Size: lost annualized total variance for calendar arbitrage, drop
+    /// in call slope for butterfly arbitrage.
+    pub magnitude: Decimal,
+}
+
+impl VolatilitySurface {
+    /// Fits a surface to observed implied volatilities.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - Observed `(expiry, strike, IV)` points
+    /// * `spot` - Spot price the log-moneyness is taken against
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if there is no point, or a point's
+    /// days, strike or IV is not positive, or `Error::PricingError` if the
+    /// spot is not positive.
+    pub fn from_points(points: &[SurfacePoint], spot: Decimal) -> Result<Self> {
+        if spot <= Decimal::ZERO {
+            return Err(Error::pricing("spot must be positive"));
+        }
+        if points.is_empty() {
+            return Err(Error::validation("no points to fit a surface to"));
+        }
+        let mut by_expiry: BTreeMap<Decimal, Vec<(Decimal, Decimal)>> = BTreeMap::new();
+        for p in points {
+            if p.days <= Decimal::ZERO || p.strike <= Decimal::ZERO || p.iv <= Decimal::ZERO {
+                return Err(Error::validation(format!(
+                    "surface point ({}, {}, {}) must be positive",
+                    p.days, p.strike, p.iv
+                )));
+            }
+            by_expiry
+                .entry(p.days.normalize())
+                .or_default()
+                .push(((p.strike / spot).ln(), p.iv));
+        }
+        let mut surface = Self::new();
+        for (days, smile) in by_expiry {
+            surface.set_pillar(days, fit_smile(&smile));
+        }
+        Ok(surface)
+    }
+
+    /// Checks the surface for calendar and butterfly arbitrage.
+    ///
+    /// # Arguments
+    ///
+    /// * `spot` - Spot price of the underlying
+    /// * `strikes` - Strikes to check at, in any order
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PricingError` if the spot or a strike is not positive
+    /// or a call cannot be priced.
+    pub fn arbitrage(&self, spot: Decimal, strikes: &[Decimal]) -> Result<Vec<ArbitrageViolation>> {
+        let mut strikes = strikes.to_vec();
+        strikes.sort();
+        strikes.dedup();
+        let pillars = self.pillars();
+        let mut violations = Vec::new();
+
+        for pair in pillars.windows(2) {
+            let ((d0, _), (d1, _)) = (pair[0], pair[1]);
+            for &strike in &strikes {
+                let w0 = total_variance(self.vol(d0, strike, spot)?, d0);
+                let w1 = total_variance(self.vol(d1, strike, spot)?, d1);
+                if w0 - w1 > ARBITRAGE_TOLERANCE {
+                    violations.push(ArbitrageViolation {
+                        kind: ArbitrageKind::Calendar,
+                        days: d1,
+                        strike,
+                        magnitude: w0 - w1,
+                    });
+                }
+            }
+        }
+
+        for (days, _) in &pillars {
+            let calls = strikes
+                .iter()
+                .map(|&k| {
+                    Ok((
+                        k,
+                        self.pricing_params(spot, k, *days, OptionStyle::Call)?
+                            .price()?,
+                    ))
+                })
+                .collect::<Result<Vec<(Decimal, Decimal)>>>()?;
+            for w in calls.windows(3) {
+                let left = (w[1].1 - w[0].1) / (w[1].0 - w[0].0);
+                let right = (w[2].1 - w[1].1) / (w[2].0 - w[1].0);
+                if left - right > ARBITRAGE_TOLERANCE {
+                    violations.push(ArbitrageViolation {
+                        kind: ArbitrageKind::Butterfly,
+                        days: *days,
+                        strike: w[1].0,
+                        magnitude: left - right,
+                    });
+                }
+            }
+        }
+        Ok(violations)
+    }
+
+    /// Returns pricing inputs for one contract with the surface volatility.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if the surface has no pillars, or
+    /// `Error::PricingError` if the strike or spot is not positive.
+    pub fn pricing_params(
+        &self,
+        spot: Decimal,
+        strike: Decimal,
+        days: Decimal,
+        style: OptionStyle,
+    ) -> Result<PricingParams> {
+        let vol = self.vol(days, strike, spot)?;
+        Ok(PricingParams::new(spot, strike, days, vol, style))
+    }
+
+    /// Returns pricing inputs for every call and put of a chain.
+    ///
+    /// Contracts are ordered by expiry, then strike, call before put.
+    ///
+    /// # Arguments
+    ///
+    /// * `spot` - Spot price of the underlying
+    /// * `rate` - Risk-free rate applied to every contract
+    /// * `expiries` - Days to expiry of the chain's expirations
+    /// * `strikes` - Strikes listed at every expiration
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if the surface has no pillars, or
+    /// `Error::PricingError` if a strike or the spot is not positive.
+    pub fn chain_params(
+        &self,
+        spot: Decimal,
+        rate: Decimal,
+        expiries: &[Decimal],
+        strikes: &[Decimal],
+    ) -> Result<Vec<PricingParams>> {
+        let mut params = Vec::with_capacity(expiries.len() * strikes.len() * 2);
+        for &days in expiries {
+            for &strike in strikes {
+                for style in [OptionStyle::Call, OptionStyle::Put] {
+                    params.push(
+                        self.pricing_params(spot, strike, days, style)?
+                            .with_rate(rate),
+                    );
+                }
+            }
+        }
+        Ok(params)
+    }
+}
+
+/// Returns annualized total variance.
+fn total_variance(vol: Decimal, days: Decimal) -> Decimal {
+    vol * vol * days / DAYS_PER_YEAR
+}
+
+/// Fits a smile to `(log-moneyness, IV)` pairs by least squares.
+fn fit_smile(points: &[(Decimal, Decimal)]) -> SmileParams {
+    let mut xs: Vec<Decimal> = points.iter().map(|(x, _)| x.round_dp(12)).collect();
+    xs.sort();
+    xs.dedup();
+    let terms = xs.len().min(3);
+
+    // Normal equations for iv = sum(c_j * x^j), j < terms.
+    let mut a = vec![vec![Decimal::ZERO; terms]; terms];
+    let mut b = vec![Decimal::ZERO; terms];
+    for &(x, iv) in points {
+        let powers = [Decimal::ONE, x, x * x, x * x * x, x * x * x * x];
+        for i in 0..terms {
+            b[i] += powers[i] * iv;
+            for j in 0..terms {
+                a[i][j] += powers[i + j];
+            }
+        }
+    }
+    let c = solve(a, b).unwrap_or_else(|| {
+        let mean = points.iter().map(|(_, iv)| *iv).sum::<Decimal>() / Decimal::from(points.len());
+        vec![mean]
+    });
+    SmileParams::new(
+        c[0],
+        c.get(1).copied().unwrap_or_default(),
+        c.get(2).copied().unwrap_or_default(),
+    )
+}
+
+/// Solves a small linear system by Gaussian elimination with pivoting.
+fn solve(mut a: Vec<Vec<Decimal>>, mut b: Vec<Decimal>) -> Option<Vec<Decimal>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by_key(|&r| a[r][col].abs())?;
+        if a[pivot][col].is_zero() {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let pivot_row = a[col].clone();
+        for row in col + 1..n {
+            let factor = a[row][col] / pivot_row[col];
+            for (cell, p) in a[row].iter_mut().zip(&pivot_row).skip(col) {
+                *cell -= factor * p;
+            }
+            let delta = factor * b[col];
+            b[row] -= delta;
+        }
+    }
+    let mut x = vec![Decimal::ZERO; n];
+    for row in (0..n).rev() {
+        let tail: Decimal = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - tail) / a[row][row];
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strikes() -> Vec<Decimal> {
+        (80..=120).step_by(5).map(Decimal::from).collect()
+    }
+
+    #[test]
+    fn test_fit_recovers_smile() {
+        let smile = SmileParams::new(dec!(0.25), dec!(-0.2), dec!(0.4));
+        let points: Vec<SurfacePoint> = strikes()
+            .into_iter()
+            .map(|k| SurfacePoint::new(dec!(30), k, smile.vol_at((k / dec!(100)).ln())))
+            .collect();
+        let fitted = VolatilitySurface::from_points(&points, dec!(100)).unwrap();
+        let pillar = fitted.pillar(dec!(30)).unwrap();
+        assert!((pillar.atm_vol - dec!(0.25)).abs() < dec!(0.0001));
+        assert!((pillar.skew + dec!(0.2)).abs() < dec!(0.001));
+        assert!((pillar.curvature - dec!(0.4)).abs() < dec!(0.01));
+
+        // A single strike gives a flat smile.
+        let flat = VolatilitySurface::from_points(
+            &[SurfacePoint::new(dec!(60), dec!(90), dec!(0.3))],
+            dec!(100),
+        )
+        .unwrap();
+        assert_eq!(
+            flat.pillar(dec!(60)).unwrap(),
+            SmileParams::new(dec!(0.3), Decimal::ZERO, Decimal::ZERO)
+        );
+        assert!(VolatilitySurface::from_points(&[], dec!(100)).is_err());
+    }
+
+    #[test]
+    fn test_arbitrage_detection() {
+        let clean = VolatilitySurface::new()
+            .with_pillar(dec!(30), SmileParams::new(dec!(0.2), dec!(-0.1), dec!(0.2)))
+            .with_pillar(
+                dec!(90),
+                SmileParams::new(dec!(0.22), dec!(-0.1), dec!(0.2)),
+            );
+        assert!(clean.arbitrage(dec!(100), &strikes()).unwrap().is_empty());
+
+        // Far-dated vol collapses: total variance falls with expiry.
+        let calendar = clean
+            .clone()
+            .with_pillar(dec!(90), SmileParams::new(dec!(0.1), dec!(-0.1), dec!(0.2)));
+        let violations = calendar.arbitrage(dec!(100), &strikes()).unwrap();
+        assert!(
+            violations
+                .iter()
+                .any(|v| v.kind == ArbitrageKind::Calendar && v.days == dec!(90))
+        );
+
+        // A smile with a steep negative curvature is concave enough to make
+        // call prices non-convex.
+        let butterfly = VolatilitySurface::new().with_pillar(
+            dec!(30),
+            SmileParams::new(dec!(0.5), Decimal::ZERO, dec!(-20)),
+        );
+        let violations = butterfly.arbitrage(dec!(100), &strikes()).unwrap();
+        assert!(
+            violations
+                .iter()
+                .any(|v| v.kind == ArbitrageKind::Butterfly)
+        );
+    }
+
+    #[test]
+    fn test_chain_params() {
+        let surface = VolatilitySurface::new().with_pillar(
+            dec!(30),
+            SmileParams::new(dec!(0.2), dec!(-0.1), Decimal::ZERO),
+        );
+        let params = surface
+            .chain_params(
+                dec!(100),
+                dec!(0.05),
+                &[dec!(30), dec!(60)],
+                &[dec!(90), dec!(110)],
+            )
+            .unwrap();
+        assert_eq!(params.len(), 8);
+        assert_eq!(params[0].style, OptionStyle::Call);
+        assert_eq!(params[1].style, OptionStyle::Put);
+        assert_eq!(params[0].rate, dec!(0.05));
+        assert!(params[0].volatility > params[2].volatility);
+    }
+}
@@ -0,0 +1,323 @@
+//! Surface risk module.
+//!
+//! This module provides the vega ladder: the P&L impact of bumping each
+//! expiry pillar's ATM volatility, skew and curvature independently,
+//! computed by bump-and-reprice over a set of option exposures.
+
+use super::params::PricingParams;
+use super::surface::{SmileParams, VolatilitySurface};
+use crate::error::{Error, Result};
+use optionstratlib::OptionStyle;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// A signed option position priced off the surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptionExposure {
+    /// Strike price.
+    pub strike: Decimal,
+    /// Days to expiry.
+    pub days_to_expiry: Decimal,
+    /// Call or put.
+    pub style: OptionStyle,
+    /// Signed quantity (negative for short positions).
+    pub quantity: Decimal,
+}
+
+impl OptionExposure {
+    /// Creates an option exposure.
+    ///
+    /// # Arguments
+    ///
+    /// * `strike` - Strike price
+    /// * `days_to_expiry` - Days to expiry
+    /// * `style` - Call or put
+    /// * `quantity` - Signed quantity
+    #[must_use]
+    pub const fn new(
+        strike: Decimal,
+        days_to_expiry: Decimal,
+        style: OptionStyle,
+        quantity: Decimal,
+    ) -> Self {
+        Self {
+            strike,
+            days_to_expiry,
+            style,
+            quantity,
+        }
+    }
+}
+
+/// Bump sizes applied to each smile parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SurfaceBumpSizes {
+    /// Absolute bump to ATM volatility.
+    pub atm_vol: Decimal,
+    /// Absolute bump to skew.
+    pub skew: Decimal,
+    /// Absolute bump to curvature.
+    pub curvature: Decimal,
+}
+
+impl Default for SurfaceBumpSizes {
+    fn default() -> Self {
+        Self {
+            atm_vol: dec!(0.01),
+            skew: dec!(0.01),
+            curvature: dec!(0.01),
+        }
+    }
+}
+
+/// P&L impact of bumping one pillar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PillarSensitivity {
+    /// Days to expiry of the pillar.
+    pub pillar_days: Decimal,
+    /// P&L for the ATM volatility bump.
+    pub atm_vol_pnl: Decimal,
+    /// P&L for the skew bump.
+    pub skew_pnl: Decimal,
+    /// P&L for the curvature bump.
+    pub curvature_pnl: Decimal,
+}
+
+/// Vega ladder by pillar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VegaLadder {
+    /// Bump sizes used to build the ladder.
+    pub bumps: SurfaceBumpSizes,
+    /// Sensitivities sorted by pillar.
+    pub pillars: Vec<PillarSensitivity>,
+}
+
+impl VegaLadder {
+    /// Returns the total P&L for a parallel ATM volatility bump.
+    #[must_use]
+    pub fn total_atm_vol_pnl(&self) -> Decimal {
+        self.pillars.iter().map(|p| p.atm_vol_pnl).sum()
+    }
+
+    /// Returns the total P&L for a parallel skew bump.
+    #[must_use]
+    pub fn total_skew_pnl(&self) -> Decimal {
+        self.pillars.iter().map(|p| p.skew_pnl).sum()
+    }
+
+    /// Returns the total P&L for a parallel curvature bump.
+    #[must_use]
+    pub fn total_curvature_pnl(&self) -> Decimal {
+        self.pillars.iter().map(|p| p.curvature_pnl).sum()
+    }
+
+    /// Returns the largest absolute ATM volatility P&L across pillars.
+    #[must_use]
+    pub fn max_pillar_vega(&self) -> Decimal {
+        self.pillars
+            .iter()
+            .map(|p| p.atm_vol_pnl.abs())
+            .max()
+            .unwrap_or(Decimal::ZERO)
+    }
+}
+
+impl std::fmt::Display for VegaLadder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Vega ladder (atm {}, skew {}, curvature {}):",
+            self.bumps.atm_vol, self.bumps.skew, self.bumps.curvature
+        )?;
+        for p in &self.pillars {
+            writeln!(
+                f,
+                "  {:>6}d  atm {:>12.4}  skew {:>12.4}  curvature {:>12.4}",
+                p.pillar_days, p.atm_vol_pnl, p.skew_pnl, p.curvature_pnl
+            )?;
+        }
+        write!(
+            f,
+            "  total    atm {:>12.4}  skew {:>12.4}  curvature {:>12.4}",
+            self.total_atm_vol_pnl(),
+            self.total_skew_pnl(),
+            self.total_curvature_pnl()
+        )
+    }
+}
+
+/// Builds the vega ladder by bump-and-reprice.
+///
+/// Every exposure is priced off the surface, then repriced with each
+/// pillar's ATM volatility, skew and curvature bumped in turn. Because
+/// expiries between pillars interpolate, an exposure can load on two
+/// adjacent pillars.
+///
+/// # Arguments
+///
+/// * `surface` - Volatility surface
+/// * `spot` - Spot price of the underlying
+/// * `rate` - Risk-free rate
+/// * `exposures` - Positions to reprice
+/// * `bumps` - Bump sizes
+///
+/// # Errors
+///
+/// Returns `Error::NoDataAvailable` if the surface has no pillars, or a
+/// pricing error if any exposure cannot be priced.
+pub fn vega_ladder(
+    surface: &VolatilitySurface,
+    spot: Decimal,
+    rate: Decimal,
+    exposures: &[OptionExposure],
+    bumps: SurfaceBumpSizes,
+) -> Result<VegaLadder> {
+    if surface.is_empty() {
+        return Err(Error::no_data("volatility surface has no pillars"));
+    }
+    let base = portfolio_value(surface, spot, rate, exposures)?;
+    let reprice = |days: Decimal, shift: SmileParams| -> Result<Decimal> {
+        let bumped = surface.bumped(days, shift)?;
+        Ok(portfolio_value(&bumped, spot, rate, exposures)? - base)
+    };
+
+    let pillars = surface
+        .pillars()
+        .into_iter()
+        .map(|(days, _)| {
+            Ok(PillarSensitivity {
+                pillar_days: days,
+                atm_vol_pnl: reprice(
+                    days,
+                    SmileParams::new(bumps.atm_vol, Decimal::ZERO, Decimal::ZERO),
+                )?,
+                skew_pnl: reprice(
+                    days,
+                    SmileParams::new(Decimal::ZERO, bumps.skew, Decimal::ZERO),
+                )?,
+                curvature_pnl: reprice(
+                    days,
+                    SmileParams::new(Decimal::ZERO, Decimal::ZERO, bumps.curvature),
+                )?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(VegaLadder { bumps, pillars })
+}
+
+/// Values the exposures off a surface.
+fn portfolio_value(
+    surface: &VolatilitySurface,
+    spot: Decimal,
+    rate: Decimal,
+    exposures: &[OptionExposure],
+) -> Result<Decimal> {
+    exposures.iter().try_fold(Decimal::ZERO, |acc, e| {
+        let vol = surface.vol(e.days_to_expiry, e.strike, spot)?;
+        let price = PricingParams::new(spot, e.strike, e.days_to_expiry, vol, e.style)
+            .with_rate(rate)
+            .price()?;
+        Ok(acc + price * e.quantity)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn surface() -> VolatilitySurface {
+        VolatilitySurface::new()
+            .with_pillar(
+                dec!(30),
+                SmileParams::new(dec!(0.20), dec!(-0.10), dec!(0.50)),
+            )
+            .with_pillar(
+                dec!(90),
+                SmileParams::new(dec!(0.25), dec!(-0.10), dec!(0.50)),
+            )
+    }
+
+    #[test]
+    fn test_empty_surface_errors() {
+        let result = vega_ladder(
+            &VolatilitySurface::new(),
+            dec!(100),
+            Decimal::ZERO,
+            &[],
+            SurfaceBumpSizes::default(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_exposure_on_pillar_loads_only_that_pillar() {
+        let exposures = [OptionExposure::new(
+            dec!(100),
+            dec!(30),
+            OptionStyle::Call,
+            dec!(10),
+        )];
+        let ladder = vega_ladder(
+            &surface(),
+            dec!(100),
+            Decimal::ZERO,
+            &exposures,
+            SurfaceBumpSizes::default(),
+        )
+        .unwrap();
+
+        assert_eq!(ladder.pillars.len(), 2);
+        assert!(ladder.pillars[0].atm_vol_pnl > Decimal::ZERO);
+        assert!(ladder.pillars[1].atm_vol_pnl.is_zero());
+        // ATM strike: log-moneyness is zero so skew and curvature do nothing.
+        assert!(ladder.pillars[0].skew_pnl.abs() < dec!(0.0001));
+        assert_eq!(ladder.max_pillar_vega(), ladder.pillars[0].atm_vol_pnl);
+    }
+
+    #[test]
+    fn test_short_otm_put_skew_and_curvature() {
+        let exposures = [OptionExposure::new(
+            dec!(80),
+            dec!(90),
+            OptionStyle::Put,
+            dec!(-5),
+        )];
+        let ladder = vega_ladder(
+            &surface(),
+            dec!(100),
+            Decimal::ZERO,
+            &exposures,
+            SurfaceBumpSizes::default(),
+        )
+        .unwrap();
+        let far = ladder.pillars[1];
+
+        assert!(far.atm_vol_pnl < Decimal::ZERO);
+        // Downside strike: higher skew lowers its vol, which helps a short.
+        assert!(far.skew_pnl > Decimal::ZERO);
+        assert!(far.curvature_pnl < Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_interpolated_expiry_loads_both_pillars() {
+        let exposures = [OptionExposure::new(
+            dec!(100),
+            dec!(60),
+            OptionStyle::Call,
+            dec!(1),
+        )];
+        let ladder = vega_ladder(
+            &surface(),
+            dec!(100),
+            Decimal::ZERO,
+            &exposures,
+            SurfaceBumpSizes::default(),
+        )
+        .unwrap();
+
+        assert!(ladder.pillars.iter().all(|p| p.atm_vol_pnl > Decimal::ZERO));
+        assert!(ladder.to_string().contains("total"));
+    }
+}
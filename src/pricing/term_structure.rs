@@ -0,0 +1,330 @@
+//! Term structure module.
+//!
+//! This module provides the [`RateCurve`] of risk-free rates by expiry and
+//! the [`DividendSchedule`] of an underlying, discrete cash dividends plus
+//! continuous dividend yield and borrow rate curves, from which the forward
+//! price of each expiration is derived.
+//!
+//! ## Forward
+//!
+//! With zero rate `r(T)`, continuous yield `q(T)` (dividend yield plus
+//! borrow rate) and dividends `d_i` going ex at `t_i <= T`, the forward is
+//! `F = (S - sum d_i * exp(-r(t_i) * t_i)) * exp((r(T) - q(T)) * T)`.
+//! Pricing uses the escrowed dividend model: Black-Scholes on the spot net
+//! of the dividends' present value, which reproduces that forward. Rates
+//! are continuously compounded and annualized over 365 days.
+
+use super::params::{DAYS_PER_YEAR, PricingParams};
+use crate::error::{Error, Result};
+use rust_decimal::{Decimal, MathematicalOps};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Continuously compounded rates by days to expiry.
+///
+/// Between points the rate is interpolated linearly in days; outside them
+/// the nearest point applies.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateCurve {
+    /// Rate by days to expiry.
+    points: BTreeMap<Decimal, Decimal>,
+}
+
+impl RateCurve {
+    /// Creates an empty curve.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a curve with the same rate at every expiry.
+    #[must_use]
+    pub fn flat(rate: Decimal) -> Self {
+        Self::new().with_point(Decimal::ZERO, rate)
+    }
+
+    /// Sets the rate at a days to expiry.
+    #[must_use]
+    pub fn with_point(mut self, days: Decimal, rate: Decimal) -> Self {
+        self.insert(days, rate);
+        self
+    }
+
+    /// Sets the rate at a days to expiry.
+    pub fn insert(&mut self, days: Decimal, rate: Decimal) {
+        self.points.insert(days.normalize(), rate);
+    }
+
+    /// Returns the points sorted by days to expiry.
+    #[must_use]
+    pub fn points(&self) -> Vec<(Decimal, Decimal)> {
+        self.points.iter().map(|(d, r)| (*d, *r)).collect()
+    }
+
+    /// Returns the number of points.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Returns true if the curve has no points.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns the rate at a days to expiry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if the curve is empty.
+    pub fn rate(&self, days: Decimal) -> Result<Decimal> {
+        let below = self.points.range(..=days).next_back();
+        let above = self.points.range(days..).next();
+        match (below, above) {
+            (Some((d0, r0)), Some((d1, r1))) if d1 > d0 => {
+                Ok(r0 + (r1 - r0) * (days - d0) / (d1 - d0))
+            }
+            (Some((_, r)), _) | (None, Some((_, r))) => Ok(*r),
+            (None, None) => Err(Error::no_data("rate curve is empty")),
+        }
+    }
+
+    /// Returns the discount factor to a days to expiry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if the curve is empty.
+    pub fn discount_factor(&self, days: Decimal) -> Result<Decimal> {
+        Ok((-self.rate(days)? * days / DAYS_PER_YEAR).exp())
+    }
+}
+
+/// A cash dividend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscreteDividend {
+    /// Days until the ex-dividend date.
+    pub ex_days: Decimal,
+    /// Cash amount per share.
+    pub amount: Decimal,
+}
+
+impl DiscreteDividend {
+    /// Creates a cash dividend.
+    #[must_use]
+    pub const fn new(ex_days: Decimal, amount: Decimal) -> Self {
+        Self { ex_days, amount }
+    }
+}
+
+/// Dividends and borrow cost of one underlying.
+///
+/// An expiry sees the dividends going ex after today and on or before
+/// expiry; a missing yield or borrow curve counts as zero.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DividendSchedule {
+    /// Cash dividends sorted by ex-date.
+    dividends: Vec<DiscreteDividend>,
+    /// Continuous dividend yield by expiry.
+    yield_curve: Option<RateCurve>,
+    /// Stock borrow rate by expiry.
+    borrow_curve: Option<RateCurve>,
+}
+
+impl DividendSchedule {
+    /// Creates a schedule without dividends or borrow cost.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a cash dividend.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the amount is negative.
+    pub fn with_dividend(mut self, dividend: DiscreteDividend) -> Result<Self> {
+        if dividend.amount < Decimal::ZERO {
+            return Err(Error::validation("dividend amount must not be negative"));
+        }
+        let at = self
+            .dividends
+            .partition_point(|d| d.ex_days <= dividend.ex_days);
+        self.dividends.insert(at, dividend);
+        Ok(self)
+    }
+
+    /// Sets the continuous dividend yield curve.
+    #[must_use]
+    pub fn with_yield_curve(mut self, curve: RateCurve) -> Self {
+        self.yield_curve = Some(curve);
+        self
+    }
+
+    /// Sets the stock borrow rate curve.
+    #[must_use]
+    pub fn with_borrow_curve(mut self, curve: RateCurve) -> Self {
+        self.borrow_curve = Some(curve);
+        self
+    }
+
+    /// Returns the cash dividends sorted by ex-date.
+    #[must_use]
+    pub fn dividends(&self) -> &[DiscreteDividend] {
+        &self.dividends
+    }
+
+    /// Returns the continuous yield at an expiry: dividend yield plus
+    /// borrow rate.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if a curve that is set is empty.
+    pub fn carry_yield(&self, days: Decimal) -> Result<Decimal> {
+        let mut total = Decimal::ZERO;
+        for curve in self.yield_curve.iter().chain(&self.borrow_curve) {
+            total += curve.rate(days)?;
+        }
+        Ok(total)
+    }
+
+    /// Returns the present value of the cash dividends going ex before an
+    /// expiry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if dividends fall before expiry and the
+    /// rate curve is empty.
+    pub fn dividends_pv(&self, days: Decimal, rates: &RateCurve) -> Result<Decimal> {
+        let mut pv = Decimal::ZERO;
+        for dividend in &self.dividends {
+            if dividend.ex_days > days {
+                break;
+            }
+            if dividend.ex_days > Decimal::ZERO {
+                pv += dividend.amount * rates.discount_factor(dividend.ex_days)?;
+            }
+        }
+        Ok(pv)
+    }
+
+    /// Returns the forward price at an expiry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if the rate curve or a yield curve is
+    /// empty, or `Error::PricingError` if the dividends exceed the spot.
+    pub fn forward(&self, spot: Decimal, days: Decimal, rates: &RateCurve) -> Result<Decimal> {
+        let net_spot = self.net_spot(spot, days, rates)?;
+        let carry = rates.rate(days)? - self.carry_yield(days)?;
+        Ok(net_spot * (carry * days / DAYS_PER_YEAR).exp())
+    }
+
+    /// Returns pricing parameters reflecting the term structures at their
+    /// expiry.
+    ///
+    /// The spot is reduced by the dividends' present value, the rate is
+    /// the curve's rate and the dividend yield the carry yield. A negative
+    /// carry yield cannot be priced, so in that case it is folded into the
+    /// rate, which keeps the forward exact at the cost of the discounting.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if the rate curve or a yield curve is
+    /// empty, or `Error::PricingError` if the dividends exceed the spot.
+    pub fn apply(&self, params: PricingParams, rates: &RateCurve) -> Result<PricingParams> {
+        let days = params.days_to_expiry;
+        let spot = self.net_spot(params.spot, days, rates)?;
+        let rate = rates.rate(days)?;
+        let carry_yield = self.carry_yield(days)?;
+        let params = PricingParams { spot, ..params };
+        Ok(if carry_yield >= Decimal::ZERO {
+            params.with_rate(rate).with_dividend_yield(carry_yield)
+        } else {
+            params
+                .with_rate(rate - carry_yield)
+                .with_dividend_yield(Decimal::ZERO)
+        })
+    }
+
+    fn net_spot(&self, spot: Decimal, days: Decimal, rates: &RateCurve) -> Result<Decimal> {
+        let net = spot - self.dividends_pv(days, rates)?;
+        if net <= Decimal::ZERO {
+            return Err(Error::pricing("dividends before expiry exceed the spot"));
+        }
+        Ok(net)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::OptionStyle;
+    use rust_decimal_macros::dec;
+
+    fn assert_close(actual: Decimal, expected: Decimal) {
+        assert!(
+            (actual - expected).abs() < dec!(0.0001),
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn test_rate_curve_interpolation() {
+        let curve = RateCurve::new()
+            .with_point(dec!(30), dec!(0.04))
+            .with_point(dec!(90), dec!(0.05));
+        assert_eq!(curve.rate(dec!(10)).unwrap(), dec!(0.04));
+        assert_eq!(curve.rate(dec!(60)).unwrap(), dec!(0.045));
+        assert_eq!(curve.rate(dec!(365)).unwrap(), dec!(0.05));
+        assert_close(
+            curve.discount_factor(dec!(365)).unwrap(),
+            (-dec!(0.05)).exp(),
+        );
+        assert!(RateCurve::new().rate(dec!(30)).is_err());
+    }
+
+    #[test]
+    fn test_forward_per_expiration() {
+        let rates = RateCurve::flat(dec!(0.05));
+        let schedule = DividendSchedule::new()
+            .with_dividend(DiscreteDividend::new(dec!(73), dec!(2)))
+            .unwrap()
+            .with_borrow_curve(RateCurve::flat(dec!(0.01)));
+
+        // Before the ex-date only rate and borrow carry the forward.
+        let near = schedule.forward(dec!(100), dec!(36.5), &rates).unwrap();
+        assert_close(near, dec!(100) * dec!(0.004).exp());
+
+        let pv = dec!(2) * (-dec!(0.01)).exp();
+        let far = schedule.forward(dec!(100), dec!(365), &rates).unwrap();
+        assert_close(far, (dec!(100) - pv) * dec!(0.04).exp());
+        assert!(
+            DividendSchedule::new()
+                .with_dividend(DiscreteDividend::new(dec!(1), dec!(-1)))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_apply_prices_off_the_forward() {
+        let rates = RateCurve::flat(dec!(0.05));
+        let schedule = DividendSchedule::new()
+            .with_dividend(DiscreteDividend::new(dec!(100), dec!(3)))
+            .unwrap()
+            .with_yield_curve(RateCurve::flat(dec!(-0.02)));
+        let forward = schedule.forward(dec!(100), dec!(365), &rates).unwrap();
+
+        let call = PricingParams::new(dec!(100), dec!(95), dec!(365), dec!(0.2), OptionStyle::Call);
+        let call = schedule.apply(call, &rates).unwrap();
+        let put = PricingParams {
+            style: OptionStyle::Put,
+            ..call
+        };
+        // C - P = D * (F - K), with the negative yield folded into the rate.
+        let synthetic = call.price().unwrap() - put.price().unwrap();
+        let discount = (-call.rate).exp();
+        assert!((synthetic - discount * (forward - dec!(95))).abs() < dec!(0.01));
+        assert_eq!(call.dividend_yield, Decimal::ZERO);
+    }
+}
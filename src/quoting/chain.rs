@@ -0,0 +1,398 @@
+//! Chain quoting module.
+//!
+//! This module provides the [`ChainQuoter`], which quotes every contract of
+//! an underlying's option chain in one call: theos from the volatility
+//! surface, the spread model per contract, inventory skew per strike and
+//! spread widening per expiry.
+//!
+//! ## Per-strike skew
+//!
+//! A call and a put at the same strike share gamma and vega, so the net
+//! contracts held across both are the strike's volatility inventory. That
+//! net quantity is used as the spread model's inventory for both contracts,
+//! so a long call skews the put quote at its strike down as well.
+//!
+//! ## Fast pricing
+//!
+//! Theos and Greeks come from the `Decimal` reference pricer by default.
+//! [`ChainQuoter::with_fast_pricing`] prices each expiry in one
+//! [`price_and_delta_batch`] call instead, within
+//! [`FAST_PRICE_TOLERANCE`](crate::pricing::FAST_PRICE_TOLERANCE) of the
+//! reference; quotes are still generated in `Decimal`.
+
+use super::combo::price_volatility;
+use super::generated::GeneratedQuote;
+use super::params::QuoteParams;
+use super::spread::SpreadCalculator;
+use crate::error::{Error, Result};
+use crate::inventory::InventoryManager;
+use crate::orderbook::UnderlyingOrderBook;
+use crate::pricing::{
+    FastOption, Greeks, PricingParams, VolatilitySurface, price_and_delta_batch, to_decimal,
+};
+use optionstratlib::OptionStyle;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+/// Lowest price volatility handed to the spread model.
+const MIN_PRICE_VOLATILITY: Decimal = dec!(0.0001);
+
+/// Extra spread applied to expiries up to a number of days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ExpiryWidening {
+    /// Longest expiry, in days, the widening applies to.
+    max_days: Decimal,
+    /// Added to the spread, split evenly between bid and ask.
+    extra_spread: Decimal,
+}
+
+/// Quotes an underlying's whole option chain in one pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainQuoter {
+    /// Spread model.
+    calculator: SpreadCalculator,
+    /// Volatility of implied volatility, in vol points per square root of
+    /// a year.
+    vol_of_vol: Decimal,
+    /// Risk-free rate used for theos.
+    rate: Decimal,
+    /// Quote parameter template; theo, volatility and inventory are filled
+    /// per contract.
+    template: QuoteParams,
+    /// Widening tiers by increasing maximum days.
+    widening: Vec<ExpiryWidening>,
+    /// Whether theos and Greeks come from the `f64` batch pricer.
+    fast_pricing: bool,
+}
+
+impl ChainQuoter {
+    /// Creates a chain quoter without expiry widening.
+    ///
+    /// # Arguments
+    ///
+    /// * `calculator` - Spread model
+    /// * `vol_of_vol` - Volatility of implied volatility, in vol points per
+    ///   square root of a year
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the vol of vol is negative.
+    pub fn new(calculator: SpreadCalculator, vol_of_vol: Decimal) -> Result<Self> {
+        if vol_of_vol < Decimal::ZERO {
+            return Err(Error::configuration("vol of vol must be non-negative"));
+        }
+        Ok(Self {
+            calculator,
+            vol_of_vol,
+            rate: Decimal::ZERO,
+            template: QuoteParams::new(Decimal::ZERO, Decimal::ZERO),
+            widening: Vec::new(),
+            fast_pricing: false,
+        })
+    }
+
+    /// Sets the risk aversion, horizon and intensity used for every contract.
+    #[must_use]
+    pub const fn with_template(mut self, template: QuoteParams) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Sets the risk-free rate used for theos.
+    #[must_use]
+    pub const fn with_rate(mut self, rate: Decimal) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    /// Prices theos and Greeks with the `f64` batch pricer instead of the
+    /// `Decimal` reference.
+    #[must_use]
+    pub const fn with_fast_pricing(mut self, enabled: bool) -> Self {
+        self.fast_pricing = enabled;
+        self
+    }
+
+    /// Returns true if theos and Greeks come from the `f64` batch pricer.
+    #[must_use]
+    pub const fn fast_pricing(&self) -> bool {
+        self.fast_pricing
+    }
+
+    /// Widens the spread of expiries up to a number of days.
+    ///
+    /// An expiry takes the tier with the smallest `max_days` covering it.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_days` - Longest expiry, in days, the widening applies to
+    /// * `extra_spread` - Added to the spread, split between bid and ask
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if either value is negative.
+    pub fn with_expiry_widening(
+        mut self,
+        max_days: Decimal,
+        extra_spread: Decimal,
+    ) -> Result<Self> {
+        if max_days < Decimal::ZERO || extra_spread < Decimal::ZERO {
+            return Err(Error::configuration(
+                "expiry widening days and spread must be non-negative",
+            ));
+        }
+        self.widening.retain(|w| w.max_days != max_days);
+        self.widening.push(ExpiryWidening {
+            max_days,
+            extra_spread,
+        });
+        self.widening.sort_by_key(|w| w.max_days);
+        Ok(self)
+    }
+
+    /// Returns the extra spread applied at an expiry.
+    #[must_use]
+    pub fn widening_at(&self, days: Decimal) -> Decimal {
+        self.widening
+            .iter()
+            .find(|w| days <= w.max_days)
+            .map_or(Decimal::ZERO, |w| w.extra_spread)
+    }
+
+    /// Quotes every call and put of an underlying.
+    ///
+    /// Expired expirations are skipped. Quotes are returned per expiration
+    /// in book order, then by strike, call before put.
+    ///
+    /// # Arguments
+    ///
+    /// * `underlying` - Books of the underlying's option chain
+    /// * `spot` - Spot price of the underlying, in strike units
+    /// * `surface` - Implied volatility surface
+    /// * `inventory` - Positions the per-strike skew is read from
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if the surface has no pillars,
+    /// `Error::PricingError` if a contract cannot be priced, or
+    /// `Error::QuotingError` if the spread model rejects the inputs.
+    pub fn quote_chain(
+        &self,
+        underlying: &UnderlyingOrderBook,
+        spot: Decimal,
+        surface: &VolatilitySurface,
+        inventory: &InventoryManager,
+    ) -> Result<Vec<(String, GeneratedQuote)>> {
+        let mut quotes = Vec::new();
+        for expiration in underlying.expirations().iter() {
+            let Ok(days) = expiration.key().get_days().map(|d| d.to_dec()) else {
+                continue;
+            };
+            if days <= Decimal::ZERO {
+                continue;
+            }
+            let half_widening = self.widening_at(days) / Decimal::TWO;
+
+            let mut strikes: Vec<_> = expiration
+                .value()
+                .chain()
+                .strikes()
+                .iter()
+                .map(|e| e.value().clone())
+                .collect();
+            strikes.sort_by_key(|s| s.strike());
+            let mut contracts = Vec::with_capacity(strikes.len() * 2);
+            for strike_book in strikes {
+                let strike = Decimal::from(strike_book.strike());
+                let books = [strike_book.call(), strike_book.put()];
+                let strike_inventory: Decimal = books
+                    .iter()
+                    .filter_map(|b| inventory.position(b.symbol()))
+                    .map(|p| p.quantity())
+                    .sum();
+                for (book, style) in books.into_iter().zip([OptionStyle::Call, OptionStyle::Put]) {
+                    let pricing = surface
+                        .pricing_params(spot, strike, days, style)?
+                        .with_rate(self.rate);
+                    contracts.push((book.symbol().to_string(), pricing, strike_inventory));
+                }
+            }
+
+            let pricings: Vec<_> = contracts.iter().map(|(_, p, _)| *p).collect();
+            let valuations = self.value(&pricings)?;
+            for ((symbol, pricing, strike_inventory), (theo, greeks)) in
+                contracts.into_iter().zip(valuations)
+            {
+                let volatility =
+                    price_volatility(&greeks, spot, pricing.volatility, self.vol_of_vol)?
+                        .max(MIN_PRICE_VOLATILITY);
+                let params = QuoteParams {
+                    theo,
+                    volatility,
+                    inventory: strike_inventory,
+                    ..self.template
+                };
+                let mut quote = self.calculator.generate(&params)?;
+                quote.bid_price = (quote.bid_price - half_widening).max(Decimal::ZERO);
+                quote.ask_price += half_widening;
+                quotes.push((symbol, quote));
+            }
+        }
+        Ok(quotes)
+    }
+
+    /// Returns the theo and Greeks of each contract, from the batch pricer
+    /// if fast pricing is enabled; its theta and rho, unused by the spread
+    /// model, are left at zero.
+    fn value(&self, pricings: &[PricingParams]) -> Result<Vec<(Decimal, Greeks)>> {
+        if !self.fast_pricing {
+            return pricings
+                .iter()
+                .map(|p| Ok((p.price()?, p.greeks()?)))
+                .collect();
+        }
+        let options = pricings
+            .iter()
+            .map(FastOption::from_params)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| Error::pricing("pricing inputs out of f64 range"))?;
+        let priced = price_and_delta_batch(&options);
+        options
+            .iter()
+            .zip(priced)
+            .map(|(option, (price, delta))| {
+                let (gamma, vega) = option.gamma_and_vega();
+                let convert = |value: f64| {
+                    to_decimal(value).ok_or_else(|| Error::pricing("fast price is not finite"))
+                };
+                Ok((
+                    convert(price)?,
+                    Greeks {
+                        delta: convert(delta)?,
+                        gamma: convert(gamma)?,
+                        vega: convert(vega)?,
+                        ..Greeks::default()
+                    },
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::PositionLimits;
+    use crate::pricing::SmileParams;
+    use optionstratlib::ExpirationDate;
+    use optionstratlib::prelude::pos_or_panic;
+
+    fn setup() -> (UnderlyingOrderBook, VolatilitySurface, ChainQuoter) {
+        let underlying = UnderlyingOrderBook::new("SPX");
+        for days in [7.0, 60.0] {
+            let expiration =
+                underlying.get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(days)));
+            for strike in [90, 100, 110] {
+                expiration.get_or_create_strike(strike);
+            }
+        }
+        let surface = VolatilitySurface::new()
+            .with_pillar(dec!(30), SmileParams::new(dec!(0.2), dec!(-0.1), dec!(0.2)));
+        let quoter = ChainQuoter::new(SpreadCalculator::new(10), dec!(0.5))
+            .unwrap()
+            .with_template(
+                QuoteParams::new(Decimal::ZERO, Decimal::ZERO).with_time_horizon(dec!(0.01)),
+            );
+        (underlying, surface, quoter)
+    }
+
+    fn quote_of<'a>(quotes: &'a [(String, GeneratedQuote)], symbol: &str) -> &'a GeneratedQuote {
+        &quotes.iter().find(|(s, _)| s == symbol).unwrap().1
+    }
+
+    #[test]
+    fn test_quotes_whole_chain_with_strike_skew() {
+        let (underlying, surface, quoter) = setup();
+        let inventory = InventoryManager::new("SPX", PositionLimits::default()).unwrap();
+        let flat = quoter
+            .quote_chain(&underlying, dec!(100), &surface, &inventory)
+            .unwrap();
+        assert_eq!(flat.len(), 12);
+
+        let strike = underlying
+            .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(60.0)))
+            .get_or_create_strike(100);
+        let (call, put) = (strike.call().symbol(), strike.put().symbol());
+        inventory.record_trade(call, dec!(50), dec!(3)).unwrap();
+        let skewed = quoter
+            .quote_chain(&underlying, dec!(100), &surface, &inventory)
+            .unwrap();
+
+        // Long calls skew both contracts at the strike down.
+        for symbol in [call, put] {
+            let (before, after) = (quote_of(&flat, symbol), quote_of(&skewed, symbol));
+            assert_eq!(before.theo, after.theo);
+            assert!(after.reservation_price < before.reservation_price);
+        }
+        // Other strikes are unaffected.
+        let other = underlying
+            .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(60.0)))
+            .get_or_create_strike(110);
+        let other = other.call().symbol();
+        assert_eq!(quote_of(&skewed, other), quote_of(&flat, other));
+    }
+
+    #[test]
+    fn test_expiry_widening() {
+        let (underlying, surface, quoter) = setup();
+        let inventory = InventoryManager::new("SPX", PositionLimits::default()).unwrap();
+        let base = quoter
+            .quote_chain(&underlying, dec!(100), &surface, &inventory)
+            .unwrap();
+        let widened = quoter
+            .with_expiry_widening(dec!(14), dec!(0.2))
+            .unwrap()
+            .quote_chain(&underlying, dec!(100), &surface, &inventory)
+            .unwrap();
+
+        let near = underlying
+            .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(7.0)))
+            .get_or_create_strike(100);
+        let far = underlying
+            .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(60.0)))
+            .get_or_create_strike(100);
+        let near_symbol = near.call().symbol();
+        let far_symbol = far.call().symbol();
+        // The default spread model floors the near bid at zero, so the
+        // widening shows on the ask.
+        assert_eq!(
+            quote_of(&widened, near_symbol).ask_price,
+            quote_of(&base, near_symbol).ask_price + dec!(0.1)
+        );
+        assert_eq!(quote_of(&widened, far_symbol), quote_of(&base, far_symbol));
+        assert!(ChainQuoter::new(SpreadCalculator::new(10), dec!(-1)).is_err());
+    }
+
+    #[test]
+    fn test_fast_pricing_matches_reference() {
+        let (underlying, surface, quoter) = setup();
+        let inventory = InventoryManager::new("SPX", PositionLimits::default()).unwrap();
+        let reference = quoter
+            .clone()
+            .quote_chain(&underlying, dec!(100), &surface, &inventory)
+            .unwrap();
+        let quoter = quoter.with_fast_pricing(true);
+        assert!(quoter.fast_pricing());
+        let fast = quoter
+            .quote_chain(&underlying, dec!(100), &surface, &inventory)
+            .unwrap();
+
+        assert_eq!(fast.len(), reference.len());
+        for ((symbol, fast), (expected_symbol, expected)) in fast.iter().zip(&reference) {
+            assert_eq!(symbol, expected_symbol);
+            assert!((fast.theo - expected.theo).abs() < dec!(0.000001));
+            assert!((fast.bid_price - expected.bid_price).abs() < dec!(0.000001));
+            assert!((fast.ask_price - expected.ask_price).abs() < dec!(0.000001));
+        }
+    }
+}
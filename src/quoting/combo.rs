@@ -0,0 +1,573 @@
+//! Combo quoting module.
+//!
+//! This module provides quoting of listed combo instruments such as
+//! straddles and strangles, which some venues trade as a single contract:
+//!
+//! - [`ComboInstrument`] names the combo and its legs
+//! - [`ComboInstrument::value`] prices every leg off the volatility surface
+//!   and sums theo and Greeks by leg ratio
+//! - [`ComboQuoter`] quotes the combo with the spread model, using a price
+//!   volatility built from the combined delta, gamma and vega
+//! - [`ComboInstrument::decompose`] turns a combo fill into leg fills for
+//!   [`crate::inventory::InventoryManager::record_combo_fill`]
+//!
+//! ## Risk accounting
+//!
+//! The combo never holds a position. Fills are booked on the legs, and the
+//! combo's inventory skew is read from the leg positions' vega, so flow on
+//! the combo and on the leg books shares one set of positions and is not
+//! counted twice.
+
+use super::generated::GeneratedQuote;
+use super::params::QuoteParams;
+use super::spread::SpreadCalculator;
+use crate::error::{Error, Result};
+use crate::inventory::{ComboFill, ComboLegFill};
+use crate::pricing::{Greeks, PricingParams, VolatilitySurface};
+use optionstratlib::OptionStyle;
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// One leg of a combo instrument.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComboLeg {
+    /// Leg contract symbol.
+    pub symbol: String,
+    /// Call or put.
+    pub style: OptionStyle,
+    /// Strike price.
+    pub strike: Decimal,
+    /// Signed contracts per combo unit (negative = sold).
+    pub ratio: Decimal,
+}
+
+impl ComboLeg {
+    /// Creates a leg.
+    #[must_use]
+    pub fn new(
+        symbol: impl Into<String>,
+        style: OptionStyle,
+        strike: Decimal,
+        ratio: Decimal,
+    ) -> Self {
+        Self {
+            symbol: symbol.into(),
+            style,
+            strike,
+            ratio,
+        }
+    }
+}
+
+/// A combo instrument listed as a single contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComboInstrument {
+    /// Combo symbol.
+    pub symbol: String,
+    /// Days to the legs' common expiration.
+    pub days_to_expiry: Decimal,
+    /// Legs.
+    pub legs: Vec<ComboLeg>,
+}
+
+impl ComboInstrument {
+    /// Creates a combo instrument.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if there is no leg, a ratio is
+    /// zero, a strike is not positive or a leg symbol repeats.
+    pub fn new(
+        symbol: impl Into<String>,
+        days_to_expiry: Decimal,
+        legs: Vec<ComboLeg>,
+    ) -> Result<Self> {
+        let symbol = symbol.into();
+        if legs.is_empty() {
+            return Err(Error::configuration(format!("combo {symbol} has no legs")));
+        }
+        for (i, leg) in legs.iter().enumerate() {
+            if leg.ratio.is_zero() || leg.strike <= Decimal::ZERO {
+                return Err(Error::configuration(format!(
+                    "combo {symbol} leg {} needs a non-zero ratio and positive strike",
+                    leg.symbol
+                )));
+            }
+            if legs[..i].iter().any(|l| l.symbol == leg.symbol) {
+                return Err(Error::configuration(format!(
+                    "combo {symbol} lists leg {} twice",
+                    leg.symbol
+                )));
+            }
+        }
+        Ok(Self {
+            symbol,
+            days_to_expiry,
+            legs,
+        })
+    }
+
+    /// Creates a long straddle: one call and one put at the same strike.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the strike is not positive.
+    pub fn straddle(
+        symbol: impl Into<String>,
+        call_symbol: impl Into<String>,
+        put_symbol: impl Into<String>,
+        strike: Decimal,
+        days_to_expiry: Decimal,
+    ) -> Result<Self> {
+        Self::new(
+            symbol,
+            days_to_expiry,
+            vec![
+                ComboLeg::new(call_symbol, OptionStyle::Call, strike, Decimal::ONE),
+                ComboLeg::new(put_symbol, OptionStyle::Put, strike, Decimal::ONE),
+            ],
+        )
+    }
+
+    /// Creates a long strangle: one put below and one call above.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if a strike is not positive or
+    /// the put strike is not below the call strike.
+    pub fn strangle(
+        symbol: impl Into<String>,
+        put: (impl Into<String>, Decimal),
+        call: (impl Into<String>, Decimal),
+        days_to_expiry: Decimal,
+    ) -> Result<Self> {
+        if put.1 >= call.1 {
+            return Err(Error::configuration(
+                "strangle put strike must be below the call strike",
+            ));
+        }
+        Self::new(
+            symbol,
+            days_to_expiry,
+            vec![
+                ComboLeg::new(put.0, OptionStyle::Put, put.1, Decimal::ONE),
+                ComboLeg::new(call.0, OptionStyle::Call, call.1, Decimal::ONE),
+            ],
+        )
+    }
+
+    /// Creates a bull call spread: one call bought at the lower strike
+    /// and one sold at the higher strike.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if a strike is not positive or
+    /// the bought strike is not below the sold strike.
+    pub fn bull_call_spread(
+        symbol: impl Into<String>,
+        long: (impl Into<String>, Decimal),
+        short: (impl Into<String>, Decimal),
+        days_to_expiry: Decimal,
+    ) -> Result<Self> {
+        if long.1 >= short.1 {
+            return Err(Error::configuration(
+                "bull call spread long strike must be below the short strike",
+            ));
+        }
+        Self::new(
+            symbol,
+            days_to_expiry,
+            vec![
+                ComboLeg::new(long.0, OptionStyle::Call, long.1, Decimal::ONE),
+                ComboLeg::new(short.0, OptionStyle::Call, short.1, Decimal::NEGATIVE_ONE),
+            ],
+        )
+    }
+
+    /// Creates a bear put spread: one put bought at the higher strike and
+    /// one sold at the lower strike.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if a strike is not positive or
+    /// the bought strike is not above the sold strike.
+    pub fn bear_put_spread(
+        symbol: impl Into<String>,
+        long: (impl Into<String>, Decimal),
+        short: (impl Into<String>, Decimal),
+        days_to_expiry: Decimal,
+    ) -> Result<Self> {
+        if long.1 <= short.1 {
+            return Err(Error::configuration(
+                "bear put spread long strike must be above the short strike",
+            ));
+        }
+        Self::new(
+            symbol,
+            days_to_expiry,
+            vec![
+                ComboLeg::new(long.0, OptionStyle::Put, long.1, Decimal::ONE),
+                ComboLeg::new(short.0, OptionStyle::Put, short.1, Decimal::NEGATIVE_ONE),
+            ],
+        )
+    }
+
+    /// Returns the strike width if the combo is a debit vertical spread,
+    /// one option bought and one sold of the same style with the bought
+    /// one worth more, whose value lies between zero and the width.
+    #[must_use]
+    pub fn vertical_width(&self) -> Option<Decimal> {
+        let [first, second] = self.legs.as_slice() else {
+            return None;
+        };
+        let (long, short) = match (first.ratio, second.ratio) {
+            (a, b) if a == Decimal::ONE && b == Decimal::NEGATIVE_ONE => (first, second),
+            (a, b) if a == Decimal::NEGATIVE_ONE && b == Decimal::ONE => (second, first),
+            _ => return None,
+        };
+        if long.style != short.style {
+            return None;
+        }
+        let width = match long.style {
+            OptionStyle::Call => short.strike - long.strike,
+            OptionStyle::Put => long.strike - short.strike,
+        };
+        (width > Decimal::ZERO).then_some(width)
+    }
+
+    /// Prices the combo off a volatility surface.
+    ///
+    /// # Arguments
+    ///
+    /// * `spot` - Spot price of the underlying
+    /// * `rate` - Risk-free rate
+    /// * `surface` - Volatility surface the legs are read from
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if the surface is empty, or
+    /// `Error::PricingError` if a leg cannot be priced.
+    pub fn value(
+        &self,
+        spot: Decimal,
+        rate: Decimal,
+        surface: &VolatilitySurface,
+    ) -> Result<ComboValuation> {
+        let mut valuation = ComboValuation {
+            theo: Decimal::ZERO,
+            greeks: Greeks::zero(),
+            leg_theos: Vec::with_capacity(self.legs.len()),
+        };
+        for leg in &self.legs {
+            let vol = surface.vol(self.days_to_expiry, leg.strike, spot)?;
+            let params = PricingParams::new(spot, leg.strike, self.days_to_expiry, vol, leg.style)
+                .with_rate(rate);
+            let theo = params.price()?;
+            valuation.theo += theo * leg.ratio;
+            valuation.greeks += params.greeks()?.scaled(leg.ratio);
+            valuation.leg_theos.push(theo);
+        }
+        Ok(valuation)
+    }
+
+    /// Decomposes a combo fill into leg fills.
+    ///
+    /// Leg prices are the leg theos scaled so that the legs add up to the
+    /// combo price; if the combo theo is zero, the whole difference goes to
+    /// the first leg.
+    ///
+    /// # Arguments
+    ///
+    /// * `fill_id` - Fill identifier
+    /// * `quantity` - Signed combo quantity (positive buys)
+    /// * `price` - Combo price per unit
+    /// * `valuation` - Valuation of this combo at the fill
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the valuation does not match the
+    /// legs or the resulting fill is invalid.
+    pub fn decompose(
+        &self,
+        fill_id: impl Into<String>,
+        quantity: Decimal,
+        price: Decimal,
+        valuation: &ComboValuation,
+    ) -> Result<ComboFill> {
+        if valuation.leg_theos.len() != self.legs.len() {
+            return Err(Error::validation(format!(
+                "valuation has {} legs, combo {} has {}",
+                valuation.leg_theos.len(),
+                self.symbol,
+                self.legs.len()
+            )));
+        }
+        let leg_prices: Vec<Decimal> = if valuation.theo.is_zero() {
+            let mut prices = valuation.leg_theos.clone();
+            prices[0] += price / self.legs[0].ratio;
+            prices
+        } else {
+            let scale = price / valuation.theo;
+            valuation.leg_theos.iter().map(|t| t * scale).collect()
+        };
+        let fill = ComboFill {
+            fill_id: fill_id.into(),
+            combo_symbol: self.symbol.clone(),
+            quantity,
+            price,
+            legs: self
+                .legs
+                .iter()
+                .zip(leg_prices)
+                .map(|(leg, leg_price)| ComboLegFill {
+                    symbol: leg.symbol.clone(),
+                    quantity: quantity * leg.ratio,
+                    price: leg_price,
+                })
+                .collect(),
+        };
+        fill.validate()?;
+        Ok(fill)
+    }
+}
+
+/// Theo and Greeks of one combo unit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComboValuation {
+    /// Sum of leg theos by ratio.
+    pub theo: Decimal,
+    /// Sum of leg Greeks by ratio.
+    pub greeks: Greeks,
+    /// Theo of one contract of each leg, in leg order.
+    pub leg_theos: Vec<Decimal>,
+}
+
+/// Spread-model quoting of combo instruments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComboQuoter {
+    /// Spread model.
+    calculator: SpreadCalculator,
+    /// Volatility of implied volatility, in vol points per square root of
+    /// a year.
+    vol_of_vol: Decimal,
+    /// Quote parameter template; theo, volatility and inventory are
+    /// filled per combo.
+    template: QuoteParams,
+}
+
+impl ComboQuoter {
+    /// Creates a combo quoter.
+    ///
+    /// # Arguments
+    ///
+    /// * `calculator` - Spread model
+    /// * `vol_of_vol` - Volatility of implied volatility, in vol points per
+    ///   square root of a year
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the vol of vol is negative.
+    pub fn new(calculator: SpreadCalculator, vol_of_vol: Decimal) -> Result<Self> {
+        if vol_of_vol < Decimal::ZERO {
+            return Err(Error::configuration("vol of vol must be non-negative"));
+        }
+        Ok(Self {
+            calculator,
+            vol_of_vol,
+            template: QuoteParams::new(Decimal::ZERO, Decimal::ZERO),
+        })
+    }
+
+    /// Sets the risk aversion, horizon and intensity used for every combo.
+    #[must_use]
+    pub const fn with_template(mut self, template: QuoteParams) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Returns the price volatility of one combo unit.
+    ///
+    /// Combines the delta, gamma and vega exposures as independent sources:
+    /// `sqrt((Δ S σ)² + (½ Γ S² σ²)² + (ν ξ)²)`, where `ξ` is the vol of
+    /// vol. A straddle has little delta, so its spread is driven by gamma
+    /// and vega rather than by the spot move of either leg alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `greeks` - Greeks of one combo unit
+    /// * `spot` - Spot price of the underlying
+    /// * `spot_volatility` - Implied volatility of the underlying
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::QuotingError` if the variance overflows.
+    pub fn price_volatility(
+        &self,
+        greeks: &Greeks,
+        spot: Decimal,
+        spot_volatility: Decimal,
+    ) -> Result<Decimal> {
+        price_volatility(greeks, spot, spot_volatility, self.vol_of_vol)
+    }
+
+    /// Quotes one combo unit.
+    ///
+    /// The inventory is the leg positions' vega expressed in combo units,
+    /// so fills on the leg books skew the combo quote and vice versa.
+    ///
+    /// # Arguments
+    ///
+    /// * `valuation` - Valuation of the combo
+    /// * `spot` - Spot price of the underlying
+    /// * `spot_volatility` - Implied volatility of the underlying
+    /// * `leg_positions` - Aggregated Greeks of the leg positions
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::QuotingError` if the spread model rejects the inputs.
+    pub fn quote(
+        &self,
+        valuation: &ComboValuation,
+        spot: Decimal,
+        spot_volatility: Decimal,
+        leg_positions: &Greeks,
+    ) -> Result<GeneratedQuote> {
+        let volatility = self
+            .price_volatility(&valuation.greeks, spot, spot_volatility)?
+            .max(dec!(0.0001));
+        let inventory = if valuation.greeks.vega.is_zero() {
+            Decimal::ZERO
+        } else {
+            leg_positions.vega / valuation.greeks.vega
+        };
+        let params = QuoteParams {
+            theo: valuation.theo,
+            volatility,
+            inventory,
+            ..self.template
+        };
+        self.calculator.generate(&params)
+    }
+}
+
+/// Returns the price volatility of a position with the given Greeks,
+/// combining its delta, gamma and vega exposures as independent sources.
+pub(crate) fn price_volatility(
+    greeks: &Greeks,
+    spot: Decimal,
+    spot_volatility: Decimal,
+    vol_of_vol: Decimal,
+) -> Result<Decimal> {
+    let spot_move = spot * spot_volatility;
+    let delta_term = greeks.delta * spot_move;
+    let gamma_term = greeks.gamma * spot_move * spot_move / Decimal::TWO;
+    let vega_term = greeks.vega * vol_of_vol;
+    (delta_term * delta_term + gamma_term * gamma_term + vega_term * vega_term)
+        .sqrt()
+        .ok_or_else(|| Error::quoting("price variance out of range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::{InventoryManager, PositionLimits};
+    use crate::pricing::SmileParams;
+
+    fn surface() -> VolatilitySurface {
+        VolatilitySurface::new().with_pillar(
+            dec!(30),
+            SmileParams::new(dec!(0.5), Decimal::ZERO, Decimal::ZERO),
+        )
+    }
+
+    fn straddle() -> ComboInstrument {
+        ComboInstrument::straddle(
+            "BTC-STRAD-50000",
+            "BTC-C-50000",
+            "BTC-P-50000",
+            dec!(50000),
+            dec!(30),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_straddle_value_sums_legs() {
+        let combo = straddle();
+        let valuation = combo.value(dec!(50000), Decimal::ZERO, &surface()).unwrap();
+        assert_eq!(
+            valuation.theo,
+            valuation.leg_theos[0] + valuation.leg_theos[1]
+        );
+        // Call and put deltas offset; gamma and vega add up.
+        assert!(valuation.greeks.delta.abs() < dec!(0.1));
+        assert!(valuation.greeks.vega > Decimal::ZERO);
+
+        let quoter = ComboQuoter::new(SpreadCalculator::new(5), dec!(25)).unwrap();
+        let quote = quoter
+            .quote(&valuation, dec!(50000), dec!(0.5), &Greeks::zero())
+            .unwrap();
+        assert!(quote.bid_price < valuation.theo && quote.ask_price > valuation.theo);
+    }
+
+    #[test]
+    fn test_fill_decomposed_into_legs() {
+        let combo = straddle();
+        let valuation = combo.value(dec!(50000), Decimal::ZERO, &surface()).unwrap();
+        let price = (valuation.theo + dec!(50)).round_dp(2);
+        let fill = combo.decompose("F1", dec!(-2), price, &valuation).unwrap();
+        assert_eq!(fill.legs.len(), 2);
+        assert!(fill.legs.iter().all(|l| l.quantity == dec!(-2)));
+        assert_eq!(
+            (fill.leg_premium() - price * dec!(-2)).round_dp(8),
+            Decimal::ZERO
+        );
+
+        let inventory = InventoryManager::new("BTC", PositionLimits::default()).unwrap();
+        inventory.record_combo_fill(&fill).unwrap();
+        assert!(inventory.position("BTC-STRAD-50000").is_none());
+        assert_eq!(
+            inventory.position("BTC-C-50000").unwrap().quantity(),
+            dec!(-2)
+        );
+    }
+
+    #[test]
+    fn test_short_legs_skew_combo_quote() {
+        let combo = straddle();
+        let valuation = combo.value(dec!(50000), Decimal::ZERO, &surface()).unwrap();
+        let quoter = ComboQuoter::new(SpreadCalculator::new(5), dec!(25)).unwrap();
+        let flat = quoter
+            .quote(&valuation, dec!(50000), dec!(0.5), &Greeks::zero())
+            .unwrap();
+        // Short vega on the leg books: the combo is bid up to buy it back.
+        let short = valuation.greeks.scaled(dec!(-3));
+        let skewed = quoter
+            .quote(&valuation, dec!(50000), dec!(0.5), &short)
+            .unwrap();
+        assert!(skewed.reservation_price > flat.reservation_price);
+
+        assert!(
+            ComboInstrument::strangle("S", ("P", dec!(60000)), ("C", dec!(40000)), dec!(30))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_vertical_width() {
+        let bull =
+            ComboInstrument::bull_call_spread("V", ("C1", dec!(100)), ("C2", dec!(110)), dec!(30))
+                .unwrap();
+        assert_eq!(bull.vertical_width(), Some(dec!(10)));
+        let bear =
+            ComboInstrument::bear_put_spread("V", ("P2", dec!(110)), ("P1", dec!(100)), dec!(30))
+                .unwrap();
+        assert_eq!(bear.vertical_width(), Some(dec!(10)));
+        assert_eq!(straddle().vertical_width(), None);
+        assert!(
+            ComboInstrument::bear_put_spread("V", ("P1", dec!(100)), ("P2", dec!(110)), dec!(30))
+                .is_err()
+        );
+    }
+}
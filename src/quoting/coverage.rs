@@ -0,0 +1,508 @@
+//! Quote coverage module.
+//!
+//! This module provides the [`QuoteUptimeTracker`], which measures per
+//! contract how long we quoted two-sided within an exchange market-maker
+//! program's width and size requirements, and produces compliance reports
+//! over daily and monthly windows.
+//!
+//! Time is attributed to the state observed at the start of each interval:
+//! a quote observed at `t0` counts until the next observation of the same
+//! contract (or until [`QuoteUptimeTracker::flush`]).
+
+use crate::error::{Error, Result};
+use crate::orderbook::{ContractId, Quote};
+use chrono::{Datelike, NaiveDate};
+use crossbeam_skiplist::SkipMap;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Milliseconds in a day.
+const MS_PER_DAY: u64 = 86_400_000;
+
+/// Requirements of an exchange market-maker program.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgramSpec {
+    /// Program name.
+    pub name: String,
+    /// Required fraction of time quoted compliantly (e.g. `0.9`).
+    pub min_presence: Decimal,
+    /// Widest compliant spread in basis points of mid.
+    pub max_width_bps: Decimal,
+    /// Smallest compliant size on each side.
+    pub min_size: u64,
+}
+
+impl ProgramSpec {
+    /// Validates the program specification.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the presence is outside
+    /// `[0, 1]` or the width is not positive.
+    pub fn validate(&self) -> Result<()> {
+        if self.min_presence < Decimal::ZERO || self.min_presence > Decimal::ONE {
+            return Err(Error::configuration("min_presence must be within [0, 1]"));
+        }
+        if self.max_width_bps <= Decimal::ZERO {
+            return Err(Error::configuration("max_width_bps must be positive"));
+        }
+        Ok(())
+    }
+
+    /// Returns true if a quote satisfies the width and size requirements.
+    #[must_use]
+    pub fn is_compliant(&self, quote: &Quote) -> bool {
+        quote.is_two_sided()
+            && quote.bid_size() >= self.min_size
+            && quote.ask_size() >= self.min_size
+            && width_bps(quote).is_some_and(|w| w <= self.max_width_bps)
+    }
+}
+
+/// Time-weighted quoting statistics for one contract over one day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct CoverageStats {
+    /// Observed time in milliseconds.
+    pub observed_ms: u64,
+    /// Time quoted on both sides.
+    pub two_sided_ms: u64,
+    /// Time quoted within program requirements.
+    pub compliant_ms: u64,
+    /// Sum of width in bps times two-sided milliseconds.
+    width_bps_ms: Decimal,
+    /// Sum of the smaller side's size times two-sided milliseconds.
+    size_ms: Decimal,
+}
+
+impl CoverageStats {
+    /// Returns the fraction of observed time quoted compliantly.
+    #[must_use]
+    pub fn presence(&self) -> Decimal {
+        ratio(self.compliant_ms, self.observed_ms)
+    }
+
+    /// Returns the fraction of observed time quoted two-sided.
+    #[must_use]
+    pub fn two_sided_ratio(&self) -> Decimal {
+        ratio(self.two_sided_ms, self.observed_ms)
+    }
+
+    /// Returns the time-weighted average width in bps while two-sided.
+    #[must_use]
+    pub fn avg_width_bps(&self) -> Option<Decimal> {
+        (self.two_sided_ms > 0).then(|| self.width_bps_ms / Decimal::from(self.two_sided_ms))
+    }
+
+    /// Returns the time-weighted average of the smaller side's size while
+    /// two-sided.
+    #[must_use]
+    pub fn avg_size(&self) -> Option<Decimal> {
+        (self.two_sided_ms > 0).then(|| self.size_ms / Decimal::from(self.two_sided_ms))
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.observed_ms += other.observed_ms;
+        self.two_sided_ms += other.two_sided_ms;
+        self.compliant_ms += other.compliant_ms;
+        self.width_bps_ms += other.width_bps_ms;
+        self.size_ms += other.size_ms;
+    }
+}
+
+/// Compliance of one contract over a window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractCoverage {
+    /// Contract identifier.
+    pub contract: ContractId,
+    /// Statistics over the window.
+    pub stats: CoverageStats,
+    /// Whether presence met the program requirement.
+    pub meets_requirement: bool,
+}
+
+/// Program compliance report over a window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageReport {
+    /// Program name.
+    pub program: String,
+    /// Window start in milliseconds (inclusive, day aligned).
+    pub from_ms: u64,
+    /// Window end in milliseconds (exclusive, day aligned).
+    pub to_ms: u64,
+    /// Per-contract coverage, by contract id.
+    pub contracts: Vec<ContractCoverage>,
+}
+
+impl CoverageReport {
+    /// Returns the number of contracts meeting the requirement.
+    #[must_use]
+    pub fn compliant_contracts(&self) -> usize {
+        self.contracts
+            .iter()
+            .filter(|c| c.meets_requirement)
+            .count()
+    }
+
+    /// Returns true if every tracked contract met the requirement.
+    #[must_use]
+    pub fn is_compliant(&self) -> bool {
+        self.contracts.iter().all(|c| c.meets_requirement)
+    }
+}
+
+impl std::fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {}/{} contracts compliant",
+            self.program,
+            self.compliant_contracts(),
+            self.contracts.len()
+        )
+    }
+}
+
+/// Last observed state of a contract.
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    timestamp_ms: u64,
+    two_sided: bool,
+    compliant: bool,
+    width_bps: Decimal,
+    size: u64,
+}
+
+/// Tracking state of one contract.
+#[derive(Debug, Default)]
+struct ContractUptime {
+    last: Option<Sample>,
+    days: BTreeMap<u64, CoverageStats>,
+}
+
+impl ContractUptime {
+    /// Attributes the time since the last sample up to `to_ms`.
+    fn accrue(&mut self, to_ms: u64) {
+        let Some(sample) = self.last else {
+            return;
+        };
+        let mut from = sample.timestamp_ms;
+        while from < to_ms {
+            let day = from / MS_PER_DAY;
+            let end = to_ms.min((day + 1) * MS_PER_DAY);
+            let ms = end - from;
+            let stats = self.days.entry(day).or_default();
+            stats.observed_ms += ms;
+            if sample.two_sided {
+                stats.two_sided_ms += ms;
+                stats.width_bps_ms += sample.width_bps * Decimal::from(ms);
+                stats.size_ms += Decimal::from(sample.size) * Decimal::from(ms);
+            }
+            if sample.compliant {
+                stats.compliant_ms += ms;
+            }
+            from = end;
+        }
+        if let Some(last) = self.last.as_mut() {
+            last.timestamp_ms = last.timestamp_ms.max(to_ms);
+        }
+    }
+}
+
+/// Per-contract quoting uptime tracker for one market-maker program.
+///
+/// Uses `SkipMap` for thread-safe concurrent access across contracts.
+pub struct QuoteUptimeTracker {
+    /// Program requirements.
+    spec: ProgramSpec,
+    /// Tracking state by contract.
+    contracts: SkipMap<ContractId, Mutex<ContractUptime>>,
+}
+
+impl QuoteUptimeTracker {
+    /// Creates a tracker for a program.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the specification is invalid.
+    pub fn new(spec: ProgramSpec) -> Result<Self> {
+        spec.validate()?;
+        Ok(Self {
+            spec,
+            contracts: SkipMap::new(),
+        })
+    }
+
+    /// Returns the program requirements.
+    #[must_use]
+    pub const fn spec(&self) -> &ProgramSpec {
+        &self.spec
+    }
+
+    /// Returns the number of tracked contracts.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.contracts.len()
+    }
+
+    /// Returns true if no contract is tracked.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.contracts.is_empty()
+    }
+
+    /// Records our current quote for a contract.
+    ///
+    /// Observations older than the contract's last observation are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `contract` - Contract identifier
+    /// * `quote` - Our quote (an empty quote means not quoting)
+    /// * `now_ms` - Observation time in milliseconds
+    pub fn observe(&self, contract: ContractId, quote: &Quote, now_ms: u64) {
+        let entry = self
+            .contracts
+            .get_or_insert_with(contract, || Mutex::new(ContractUptime::default()));
+        let Ok(mut state) = entry.value().lock() else {
+            return;
+        };
+        if state.last.is_some_and(|s| now_ms < s.timestamp_ms) {
+            return;
+        }
+        state.accrue(now_ms);
+        state.last = Some(Sample {
+            timestamp_ms: now_ms,
+            two_sided: quote.is_two_sided(),
+            compliant: self.spec.is_compliant(quote),
+            width_bps: width_bps(quote).unwrap_or(Decimal::ZERO),
+            size: quote.bid_size().min(quote.ask_size()),
+        });
+    }
+
+    /// Attributes time up to `now_ms` for every contract without a new
+    /// observation, so reports include the current quoting state.
+    pub fn flush(&self, now_ms: u64) {
+        for entry in &self.contracts {
+            if let Ok(mut state) = entry.value().lock() {
+                state.accrue(now_ms);
+            }
+        }
+    }
+
+    /// Discards daily statistics before a time.
+    pub fn purge_before(&self, before_ms: u64) {
+        let first_kept = before_ms / MS_PER_DAY;
+        for entry in &self.contracts {
+            if let Ok(mut state) = entry.value().lock() {
+                state.days = state.days.split_off(&first_kept);
+            }
+        }
+    }
+
+    /// Returns a compliance report over whole days.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_ms` - Window start, rounded down to a day boundary
+    /// * `to_ms` - Window end, rounded up to a day boundary
+    #[must_use]
+    pub fn report(&self, from_ms: u64, to_ms: u64) -> CoverageReport {
+        let first = from_ms / MS_PER_DAY;
+        let last = to_ms.div_ceil(MS_PER_DAY);
+        let contracts = self
+            .contracts
+            .iter()
+            .filter_map(|entry| {
+                let state = entry.value().lock().ok()?;
+                let mut stats = CoverageStats::default();
+                for day in state.days.range(first..last).map(|(_, s)| s) {
+                    stats.merge(day);
+                }
+                Some(ContractCoverage {
+                    contract: *entry.key(),
+                    meets_requirement: stats.observed_ms > 0
+                        && stats.presence() >= self.spec.min_presence,
+                    stats,
+                })
+            })
+            .collect();
+
+        CoverageReport {
+            program: self.spec.name.clone(),
+            from_ms: first * MS_PER_DAY,
+            to_ms: last * MS_PER_DAY,
+            contracts,
+        }
+    }
+
+    /// Returns the compliance report for the UTC day containing a time.
+    #[must_use]
+    pub fn daily_report(&self, day_ms: u64) -> CoverageReport {
+        let start = day_ms - day_ms % MS_PER_DAY;
+        self.report(start, start + MS_PER_DAY)
+    }
+
+    /// Returns the compliance report for a UTC calendar month.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the month is invalid.
+    pub fn monthly_report(&self, year: i32, month: u32) -> Result<CoverageReport> {
+        let start = NaiveDate::from_ymd_opt(year, month, 1)
+            .ok_or_else(|| Error::validation(format!("invalid month {year}-{month}")))?;
+        let next = if start.month() == 12 {
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)
+        } else {
+            NaiveDate::from_ymd_opt(year, month + 1, 1)
+        }
+        .ok_or_else(|| Error::validation(format!("invalid month {year}-{month}")))?;
+        let to_ms = |d: NaiveDate| {
+            u64::try_from(
+                d.and_hms_opt(0, 0, 0)
+                    .map_or(0, |t| t.and_utc().timestamp_millis()),
+            )
+            .unwrap_or(0)
+        };
+        Ok(self.report(to_ms(start), to_ms(next)))
+    }
+}
+
+/// Returns the quote width in basis points of mid.
+fn width_bps(quote: &Quote) -> Option<Decimal> {
+    let (bid, ask) = (quote.bid_price()?, quote.ask_price()?);
+    if ask < bid || bid + ask == 0 {
+        return None;
+    }
+    let mid = Decimal::from(bid + ask) / Decimal::TWO;
+    Some(Decimal::from(ask - bid) / mid * dec!(10000))
+}
+
+/// Returns `part / whole`, or zero when `whole` is zero.
+fn ratio(part: u64, whole: u64) -> Decimal {
+    if whole == 0 {
+        Decimal::ZERO
+    } else {
+        Decimal::from(part) / Decimal::from(whole)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY0: u64 = 19_723 * MS_PER_DAY; // 2024-01-01
+    const HOUR: u64 = 3_600_000;
+
+    fn tracker() -> QuoteUptimeTracker {
+        QuoteUptimeTracker::new(ProgramSpec {
+            name: "MMP".to_string(),
+            min_presence: dec!(0.75),
+            max_width_bps: dec!(500),
+            min_size: 5,
+        })
+        .unwrap()
+    }
+
+    fn tight() -> Quote {
+        Quote::new(Some(99), 10, Some(101), 10, 0)
+    }
+
+    fn wide() -> Quote {
+        Quote::new(Some(90), 10, Some(110), 10, 0)
+    }
+
+    #[test]
+    fn test_invalid_spec() {
+        let spec = ProgramSpec {
+            name: "bad".to_string(),
+            min_presence: dec!(1.5),
+            max_width_bps: dec!(10),
+            min_size: 1,
+        };
+        assert!(QuoteUptimeTracker::new(spec).is_err());
+    }
+
+    #[test]
+    fn test_compliance_rules() {
+        let spec = tracker().spec().clone();
+        assert!(spec.is_compliant(&tight()));
+        assert!(!spec.is_compliant(&wide()));
+        assert!(!spec.is_compliant(&Quote::new(Some(99), 1, Some(101), 10, 0)));
+        assert!(!spec.is_compliant(&Quote::new(Some(99), 10, None, 0, 0)));
+    }
+
+    #[test]
+    fn test_time_weighted_presence() {
+        let tracker = tracker();
+        let id = ContractId::new(1);
+        tracker.observe(id, &tight(), DAY0);
+        tracker.observe(id, &wide(), DAY0 + 18 * HOUR);
+        tracker.observe(id, &Quote::empty(0), DAY0 + 20 * HOUR);
+        tracker.flush(DAY0 + 24 * HOUR);
+
+        let report = tracker.daily_report(DAY0 + HOUR);
+        let stats = report.contracts[0].stats;
+
+        assert_eq!(stats.observed_ms, 24 * HOUR);
+        assert_eq!(stats.compliant_ms, 18 * HOUR);
+        assert_eq!(stats.two_sided_ms, 20 * HOUR);
+        assert_eq!(stats.presence(), dec!(0.75));
+        assert!(report.contracts[0].meets_requirement);
+        // (200 bps * 18h + 2000 bps * 2h) / 20h
+        assert_eq!(stats.avg_width_bps(), Some(dec!(380)));
+        assert_eq!(stats.avg_size(), Some(dec!(10)));
+    }
+
+    #[test]
+    fn test_intervals_split_across_days() {
+        let tracker = tracker();
+        let id = ContractId::new(1);
+        tracker.observe(id, &tight(), DAY0 + 12 * HOUR);
+        tracker.flush(DAY0 + 36 * HOUR);
+
+        assert_eq!(
+            tracker.daily_report(DAY0).contracts[0].stats.observed_ms,
+            12 * HOUR
+        );
+        assert_eq!(
+            tracker.daily_report(DAY0 + MS_PER_DAY).contracts[0]
+                .stats
+                .observed_ms,
+            12 * HOUR
+        );
+    }
+
+    #[test]
+    fn test_monthly_report() {
+        let tracker = tracker();
+        tracker.observe(ContractId::new(1), &tight(), DAY0);
+        tracker.observe(ContractId::new(2), &wide(), DAY0);
+        tracker.flush(DAY0 + 3 * MS_PER_DAY);
+
+        let report = tracker.monthly_report(2024, 1).unwrap();
+        assert_eq!(report.from_ms, DAY0);
+        assert_eq!(report.to_ms, DAY0 + 31 * MS_PER_DAY);
+        assert_eq!(report.compliant_contracts(), 1);
+        assert!(!report.is_compliant());
+        assert!(tracker.monthly_report(2024, 13).is_err());
+        assert!(
+            tracker.monthly_report(2024, 2).unwrap().contracts[0]
+                .stats
+                .observed_ms
+                == 0
+        );
+    }
+
+    #[test]
+    fn test_purge_before() {
+        let tracker = tracker();
+        tracker.observe(ContractId::new(1), &tight(), DAY0);
+        tracker.flush(DAY0 + 2 * MS_PER_DAY);
+        tracker.purge_before(DAY0 + MS_PER_DAY);
+
+        assert_eq!(tracker.daily_report(DAY0).contracts[0].stats.observed_ms, 0);
+    }
+}
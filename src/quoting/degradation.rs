@@ -0,0 +1,510 @@
+//! Degradation module.
+//!
+//! This module provides explicit policies for quoting when pricing inputs
+//! are partially unavailable, instead of choosing between quoting on stale
+//! inputs and stopping everything.
+//!
+//! The [`DegradationMonitor`] tracks when each [`PricingInput`] was last
+//! updated (spot and rates per underlying, the surface per expiry). An
+//! input older than its policy's maximum age is missing, and the policy's
+//! [`DegradationAction`] applies to the affected expiries:
+//!
+//! - `Widen`: spreads are multiplied around the quote mid
+//! - `ImpliedQuote`: the model quote is replaced by one implied from parity
+//!   or neighboring strikes, or withdrawn if none is available
+//! - `ReduceSize`: sizes are scaled down
+//! - `Suspend`: the expiry is not quoted
+//!
+//! When several inputs are missing, `Suspend` wins, multipliers and size
+//! fractions compound, and an implied quote is used before widening.
+
+use super::engine::ForwardInputs;
+use super::generated::GeneratedQuote;
+use crate::error::{Error, Result};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// A pricing input whose freshness is tracked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PricingInput {
+    /// Underlying spot price.
+    Spot,
+    /// Volatility surface of an expiry.
+    Surface,
+    /// Interest rate or carry.
+    Rate,
+}
+
+/// What to do while an input is missing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DegradationAction {
+    /// Multiply spreads by a factor above one.
+    Widen {
+        /// Spread multiplier.
+        multiplier: Decimal,
+    },
+    /// Replace model quotes with parity- or neighbor-implied quotes.
+    ImpliedQuote,
+    /// Scale sizes by a fraction in `(0, 1]`.
+    ReduceSize {
+        /// Fraction of the size kept.
+        fraction: Decimal,
+    },
+    /// Stop quoting the affected expiries.
+    Suspend,
+}
+
+/// Maximum age and action for one input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DegradationPolicy {
+    /// Age in milliseconds after which the input counts as missing.
+    pub max_age_ms: u64,
+    /// Action while the input is missing.
+    pub action: DegradationAction,
+}
+
+impl DegradationPolicy {
+    /// Validates the policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if a widen multiplier is below
+    /// one or a size fraction is outside `(0, 1]`.
+    pub fn validate(&self) -> Result<()> {
+        match self.action {
+            DegradationAction::Widen { multiplier } if multiplier < Decimal::ONE => Err(
+                Error::configuration("degradation widen multiplier must be at least one"),
+            ),
+            DegradationAction::ReduceSize { fraction }
+                if fraction <= Decimal::ZERO || fraction > Decimal::ONE =>
+            {
+                Err(Error::configuration(
+                    "degradation size fraction must be in (0, 1]",
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Degradation policy per input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DegradationConfig {
+    /// Policy for a stale spot.
+    pub spot: DegradationPolicy,
+    /// Policy for a stale surface.
+    pub surface: DegradationPolicy,
+    /// Policy for a stale rate.
+    pub rate: DegradationPolicy,
+}
+
+impl Default for DegradationConfig {
+    fn default() -> Self {
+        Self {
+            spot: DegradationPolicy {
+                max_age_ms: 2_000,
+                action: DegradationAction::Suspend,
+            },
+            surface: DegradationPolicy {
+                max_age_ms: 30_000,
+                action: DegradationAction::Widen {
+                    multiplier: Decimal::TWO,
+                },
+            },
+            rate: DegradationPolicy {
+                max_age_ms: 24 * 60 * 60 * 1000,
+                action: DegradationAction::ReduceSize {
+                    fraction: Decimal::new(5, 1),
+                },
+            },
+        }
+    }
+}
+
+impl DegradationConfig {
+    /// Returns the policy of an input.
+    #[must_use]
+    pub const fn policy(&self, input: PricingInput) -> &DegradationPolicy {
+        match input {
+            PricingInput::Spot => &self.spot,
+            PricingInput::Surface => &self.surface,
+            PricingInput::Rate => &self.rate,
+        }
+    }
+
+    /// Validates every policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if a policy is invalid.
+    pub fn validate(&self) -> Result<()> {
+        self.spot.validate()?;
+        self.surface.validate()?;
+        self.rate.validate()
+    }
+}
+
+/// Degradation in effect for one expiry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DegradationState {
+    /// Expiration label.
+    pub expiration: String,
+    /// Inputs that are missing.
+    pub missing: Vec<PricingInput>,
+    /// True if the expiry is not quoted.
+    pub suspended: bool,
+    /// True if model quotes are replaced by implied quotes.
+    pub implied: bool,
+    /// Combined spread multiplier.
+    pub spread_multiplier: Decimal,
+    /// Combined size fraction.
+    pub size_fraction: Decimal,
+}
+
+impl DegradationState {
+    /// Returns true if any input is missing.
+    #[must_use]
+    pub fn is_degraded(&self) -> bool {
+        !self.missing.is_empty()
+    }
+
+    /// Applies the degradation to a model quote.
+    ///
+    /// Returns `None` if the expiry is suspended, or if an implied quote is
+    /// required and none is available.
+    ///
+    /// # Arguments
+    ///
+    /// * `quote` - Model quote
+    /// * `implied` - Parity- or neighbor-implied quote, if one could be built
+    #[must_use]
+    pub fn apply(
+        &self,
+        quote: GeneratedQuote,
+        implied: Option<GeneratedQuote>,
+    ) -> Option<GeneratedQuote> {
+        if self.suspended {
+            return None;
+        }
+        let mut quote = if self.implied { implied? } else { quote };
+        if self.spread_multiplier != Decimal::ONE {
+            let mid = quote.mid();
+            let half = quote.spread() * self.spread_multiplier / Decimal::TWO;
+            quote.bid_price = (mid - half).max(Decimal::ZERO);
+            quote.ask_price = mid + half;
+        }
+        if self.size_fraction != Decimal::ONE {
+            let scale = |size: u64| {
+                (Decimal::from(size) * self.size_fraction)
+                    .floor()
+                    .to_u64()
+                    .unwrap_or(0)
+            };
+            quote.bid_size = scale(quote.bid_size);
+            quote.ask_size = scale(quote.ask_size);
+        }
+        Some(quote)
+    }
+}
+
+/// Degradation of every tracked expiry at one point in time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DegradationReport {
+    /// Report time in milliseconds.
+    pub timestamp_ms: u64,
+    /// Degraded expiries; expiries with every input fresh are omitted.
+    pub degraded: Vec<DegradationState>,
+}
+
+impl DegradationReport {
+    /// Returns the suspended expiries.
+    #[must_use]
+    pub fn suspended(&self) -> Vec<&str> {
+        self.degraded
+            .iter()
+            .filter(|s| s.suspended)
+            .map(|s| s.expiration.as_str())
+            .collect()
+    }
+}
+
+/// Last update times of the tracked inputs.
+#[derive(Debug, Default)]
+struct Updates {
+    /// Spot update time.
+    spot_ms: Option<u64>,
+    /// Rate update time.
+    rate_ms: Option<u64>,
+    /// Surface update time by expiration.
+    surface_ms: HashMap<String, u64>,
+}
+
+/// Tracks input freshness and derives the degradation per expiry.
+pub struct DegradationMonitor {
+    /// Policies.
+    config: DegradationConfig,
+    /// Last update times.
+    updates: Mutex<Updates>,
+}
+
+impl DegradationMonitor {
+    /// Creates a monitor with no inputs received.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if a policy is invalid.
+    pub fn new(config: DegradationConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            updates: Mutex::new(Updates::default()),
+        })
+    }
+
+    /// Returns the policies.
+    #[must_use]
+    pub const fn config(&self) -> &DegradationConfig {
+        &self.config
+    }
+
+    /// Records a spot update.
+    pub fn spot_updated(&self, timestamp_ms: u64) {
+        self.lock().spot_ms = Some(timestamp_ms);
+    }
+
+    /// Records a rate update.
+    pub fn rate_updated(&self, timestamp_ms: u64) {
+        self.lock().rate_ms = Some(timestamp_ms);
+    }
+
+    /// Records a surface update for an expiry.
+    pub fn surface_updated(&self, expiration: impl Into<String>, timestamp_ms: u64) {
+        self.lock()
+            .surface_ms
+            .insert(expiration.into(), timestamp_ms);
+    }
+
+    /// Returns the degradation in effect for an expiry.
+    #[must_use]
+    pub fn state(&self, expiration: &str, now_ms: u64) -> DegradationState {
+        let updates = self.lock();
+        let inputs = [
+            (PricingInput::Spot, updates.spot_ms),
+            (
+                PricingInput::Surface,
+                updates.surface_ms.get(expiration).copied(),
+            ),
+            (PricingInput::Rate, updates.rate_ms),
+        ];
+        let mut state = DegradationState {
+            expiration: expiration.to_string(),
+            missing: Vec::new(),
+            suspended: false,
+            implied: false,
+            spread_multiplier: Decimal::ONE,
+            size_fraction: Decimal::ONE,
+        };
+        for (input, updated_ms) in inputs {
+            let policy = self.config.policy(input);
+            let fresh = updated_ms.is_some_and(|t| now_ms.saturating_sub(t) <= policy.max_age_ms);
+            if fresh {
+                continue;
+            }
+            state.missing.push(input);
+            match policy.action {
+                DegradationAction::Widen { multiplier } => state.spread_multiplier *= multiplier,
+                DegradationAction::ImpliedQuote => state.implied = true,
+                DegradationAction::ReduceSize { fraction } => state.size_fraction *= fraction,
+                DegradationAction::Suspend => state.suspended = true,
+            }
+        }
+        state
+    }
+
+    /// Returns the degraded expiries among `expirations`.
+    #[must_use]
+    pub fn report(&self, expirations: &[&str], now_ms: u64) -> DegradationReport {
+        DegradationReport {
+            timestamp_ms: now_ms,
+            degraded: expirations
+                .iter()
+                .map(|e| self.state(e, now_ms))
+                .filter(DegradationState::is_degraded)
+                .collect(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Updates> {
+        self.updates
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Builds a quote for one side of a strike from the other side's quote via
+/// put-call parity, `C - P = D * (F - K)`.
+///
+/// # Arguments
+///
+/// * `strike` - Strike price
+/// * `forward` - Forward inputs for the expiry
+/// * `other` - Quote of the opposite option at the strike
+/// * `want_call` - True to imply the call from the put, false for the reverse
+#[must_use]
+pub fn parity_implied_quote(
+    strike: Decimal,
+    forward: &ForwardInputs,
+    other: &GeneratedQuote,
+    want_call: bool,
+) -> GeneratedQuote {
+    let synthetic = forward.discount_factor() * (forward.forward() - strike);
+    let shift = if want_call { synthetic } else { -synthetic };
+    GeneratedQuote {
+        theo: (other.theo + shift).max(Decimal::ZERO),
+        reservation_price: (other.reservation_price + shift).max(Decimal::ZERO),
+        ..*other
+    }
+    .shifted(shift)
+}
+
+/// Builds a quote at a strike by linear interpolation between the quotes
+/// of the nearest strikes below and above it.
+///
+/// Sizes are the smaller of the two neighbors'.
+///
+/// # Errors
+///
+/// Returns `Error::ValidationError` if `strike` is not strictly between
+/// the neighbor strikes.
+pub fn neighbor_implied_quote(
+    strike: Decimal,
+    lower: (Decimal, &GeneratedQuote),
+    upper: (Decimal, &GeneratedQuote),
+) -> Result<GeneratedQuote> {
+    let ((k0, q0), (k1, q1)) = (lower, upper);
+    if !(k0 < strike && strike < k1) {
+        return Err(Error::validation(format!(
+            "strike {strike} is not between neighbors {k0} and {k1}"
+        )));
+    }
+    let w = (strike - k0) / (k1 - k0);
+    let lerp = |a: Decimal, b: Decimal| a + (b - a) * w;
+    Ok(GeneratedQuote {
+        theo: lerp(q0.theo, q1.theo),
+        reservation_price: lerp(q0.reservation_price, q1.reservation_price),
+        bid_price: lerp(q0.bid_price, q1.bid_price),
+        ask_price: lerp(q0.ask_price, q1.ask_price),
+        bid_size: q0.bid_size.min(q1.bid_size),
+        ask_size: q0.ask_size.min(q1.ask_size),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn quote(bid: Decimal, ask: Decimal) -> GeneratedQuote {
+        GeneratedQuote {
+            theo: (bid + ask) / dec!(2),
+            reservation_price: (bid + ask) / dec!(2),
+            bid_price: bid,
+            ask_price: ask,
+            bid_size: 10,
+            ask_size: 10,
+        }
+    }
+
+    fn monitor() -> DegradationMonitor {
+        let monitor = DegradationMonitor::new(DegradationConfig::default()).unwrap();
+        monitor.spot_updated(10_000);
+        monitor.rate_updated(10_000);
+        monitor.surface_updated("20240329", 10_000);
+        monitor.surface_updated("20240628", 0);
+        monitor
+    }
+
+    #[test]
+    fn test_stale_surface_widens_only_affected_expiry() {
+        let monitor = monitor();
+        monitor.spot_updated(35_000);
+        assert!(!monitor.state("20240329", 35_000).is_degraded());
+
+        let state = monitor.state("20240628", 35_000);
+        assert_eq!(state.missing, vec![PricingInput::Surface]);
+        let degraded = state.apply(quote(dec!(4.9), dec!(5.1)), None).unwrap();
+        assert_eq!(degraded.bid_price, dec!(4.8));
+        assert_eq!(degraded.ask_price, dec!(5.2));
+
+        let report = monitor.report(&["20240329", "20240628"], 35_000);
+        assert_eq!(report.degraded.len(), 1);
+        assert!(report.suspended().is_empty());
+    }
+
+    #[test]
+    fn test_stale_spot_suspends_and_stale_rate_reduces_size() {
+        let monitor = monitor();
+        let state = monitor.state("20240329", 13_000);
+        assert!(state.suspended);
+        assert!(state.apply(quote(dec!(4.9), dec!(5.1)), None).is_none());
+
+        monitor.spot_updated(100_000_000);
+        let state = monitor.state("20240329", 100_000_000);
+        assert_eq!(
+            state.missing,
+            vec![PricingInput::Surface, PricingInput::Rate]
+        );
+        let degraded = state.apply(quote(dec!(4.9), dec!(5.1)), None).unwrap();
+        assert_eq!(degraded.bid_size, 5);
+        assert_eq!(degraded.spread(), dec!(0.4));
+    }
+
+    #[test]
+    fn test_implied_quotes() {
+        let config = DegradationConfig {
+            surface: DegradationPolicy {
+                max_age_ms: 1_000,
+                action: DegradationAction::ImpliedQuote,
+            },
+            ..DegradationConfig::default()
+        };
+        let monitor = DegradationMonitor::new(config).unwrap();
+        monitor.spot_updated(5_000);
+        monitor.rate_updated(5_000);
+        let state = monitor.state("20240329", 5_000);
+        assert!(state.implied);
+
+        let forward = ForwardInputs::new(dec!(100), Decimal::ZERO, dec!(30));
+        let put = quote(dec!(2), dec!(2.2));
+        let call = parity_implied_quote(dec!(95), &forward, &put, true);
+        assert_eq!(call.bid_price, dec!(7));
+        assert_eq!(state.apply(put, Some(call)).unwrap(), call);
+        assert!(state.apply(put, None).is_none());
+
+        let mid = neighbor_implied_quote(
+            dec!(100),
+            (dec!(90), &quote(dec!(10), dec!(11))),
+            (dec!(110), &quote(dec!(2), dec!(3))),
+        )
+        .unwrap();
+        assert_eq!(mid.bid_price, dec!(6));
+        assert!(neighbor_implied_quote(dec!(90), (dec!(90), &put), (dec!(110), &put)).is_err());
+    }
+
+    #[test]
+    fn test_invalid_policy() {
+        let config = DegradationConfig {
+            spot: DegradationPolicy {
+                max_age_ms: 1,
+                action: DegradationAction::Widen {
+                    multiplier: dec!(0.5),
+                },
+            },
+            ..DegradationConfig::default()
+        };
+        assert!(DegradationMonitor::new(config).is_err());
+    }
+}
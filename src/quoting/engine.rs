@@ -0,0 +1,782 @@
+//! Quote engine module.
+//!
+//! This module provides the [`QuoteEngine`], which generates quotes per
+//! contract and, per strike, runs a parity-consistency pass so that our own
+//! call and put markets never offer a risk-free conversion or reversal
+//! against us beyond a configured tolerance.
+//!
+//! ## Parity pass
+//!
+//! With forward `F`, discount factor `D` and strike `K`, put-call parity
+//! values the synthetic forward `C - P` at `D * (F - K)`. Against our
+//! published markets a counterparty can:
+//!
+//! - sell our call bid and buy our put ask, receiving `C_bid - P_ask`
+//! - buy our call ask and sell our put bid, paying `C_ask - P_bid`
+//!
+//! The pass requires `C_bid - P_ask <= D * (F - K) + tolerance` and
+//! `C_ask - P_bid >= D * (F - K) - tolerance`. Violations are removed by
+//! moving the call and put quotes jointly in opposite directions by half
+//! the excess each, which preserves both widths. Bids are floored at zero;
+//! when a floor absorbs part of a leg's half, the other leg, which moves
+//! up, takes the rest. With a tick size, prices are rounded in the
+//! configured mode and re-checked, falling back to passive rounding if the
+//! mode reopened a violation, and asks are floored at one tick.
+
+use super::expiry_window::ExpiryPhase;
+use super::generated::GeneratedQuote;
+use super::intensity::IntensityEstimator;
+use super::params::QuoteParams;
+use super::pause::QuotingPauses;
+use super::projection::{self, LimitProjection, QuoteExposure};
+use super::rounding::RoundingMode;
+use super::sanity::QuoteSanity;
+use super::spread::SpreadCalculator;
+use crate::error::{Error, Result};
+use crate::pricing::{DAYS_PER_YEAR, DividendSchedule, Greeks, PricingParams, RateCurve};
+use crate::risk::RiskLimits;
+use optionstratlib::OptionStyle;
+use rust_decimal::{Decimal, MathematicalOps};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Configuration of the parity-consistency pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParityConfig {
+    /// Whether the pass runs.
+    pub enabled: bool,
+    /// Parity violation tolerated before quotes are adjusted.
+    pub tolerance: Decimal,
+    /// Tick size prices are rounded to after adjustment, if any.
+    pub tick_size: Option<Decimal>,
+    /// Direction adjusted prices are rounded to the tick size.
+    #[serde(default)]
+    pub rounding: RoundingMode,
+}
+
+impl Default for ParityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            tolerance: Decimal::ZERO,
+            tick_size: None,
+            rounding: RoundingMode::Passive,
+        }
+    }
+}
+
+/// Market inputs used to derive the implied forward at one expiry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForwardInputs {
+    /// Spot price of the underlying.
+    pub spot: Decimal,
+    /// Risk-free rate.
+    pub rate: Decimal,
+    /// Continuous dividend or carry yield.
+    pub dividend_yield: Decimal,
+    /// Days to expiry.
+    pub days_to_expiry: Decimal,
+}
+
+impl ForwardInputs {
+    /// Creates forward inputs with zero dividend yield.
+    #[must_use]
+    pub const fn new(spot: Decimal, rate: Decimal, days_to_expiry: Decimal) -> Self {
+        Self {
+            spot,
+            rate,
+            dividend_yield: Decimal::ZERO,
+            days_to_expiry,
+        }
+    }
+
+    /// Sets the dividend yield.
+    #[must_use]
+    pub const fn with_dividend_yield(mut self, dividend_yield: Decimal) -> Self {
+        self.dividend_yield = dividend_yield;
+        self
+    }
+
+    /// Creates forward inputs from rate and dividend term structures.
+    ///
+    /// The spot is net of the cash dividends' present value, so that
+    /// [`Self::forward`] matches [`DividendSchedule::forward`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of [`DividendSchedule::apply`].
+    pub fn from_term_structure(
+        spot: Decimal,
+        days_to_expiry: Decimal,
+        rates: &RateCurve,
+        dividends: &DividendSchedule,
+    ) -> Result<Self> {
+        let params =
+            PricingParams::new(spot, spot, days_to_expiry, Decimal::ZERO, OptionStyle::Call);
+        let params = dividends.apply(params, rates)?;
+        Ok(Self::new(params.spot, params.rate, days_to_expiry)
+            .with_dividend_yield(params.dividend_yield))
+    }
+
+    /// Returns the discount factor to expiry.
+    #[must_use]
+    pub fn discount_factor(&self) -> Decimal {
+        (-self.rate * self.days_to_expiry / DAYS_PER_YEAR).exp()
+    }
+
+    /// Returns the implied forward price at expiry.
+    #[must_use]
+    pub fn forward(&self) -> Decimal {
+        self.spot * ((self.rate - self.dividend_yield) * self.days_to_expiry / DAYS_PER_YEAR).exp()
+    }
+}
+
+/// Inputs for quoting the call/put pair at one strike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrikeQuoteRequest {
+    /// Strike price.
+    pub strike: Decimal,
+    /// Call quote parameters.
+    pub call: QuoteParams,
+    /// Put quote parameters.
+    pub put: QuoteParams,
+    /// Forward inputs for the expiry.
+    pub forward: ForwardInputs,
+}
+
+/// Record of a parity adjustment applied at a strike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParityAdjustment {
+    /// Implied forward used for the check.
+    pub implied_forward: Decimal,
+    /// Parity value of the synthetic forward, `D * (F - K)`.
+    pub synthetic_value: Decimal,
+    /// Amount added to both call prices.
+    pub call_shift: Decimal,
+    /// Amount added to both put prices.
+    pub put_shift: Decimal,
+}
+
+/// Call and put quotes generated at one strike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrikeQuotes {
+    /// Call quote.
+    pub call: GeneratedQuote,
+    /// Put quote.
+    pub put: GeneratedQuote,
+    /// Parity adjustment applied, if any.
+    pub parity: Option<ParityAdjustment>,
+}
+
+/// Quote generation engine.
+pub struct QuoteEngine {
+    /// Per-contract quote model.
+    calculator: SpreadCalculator,
+    /// Parity pass configuration.
+    parity: ParityConfig,
+    /// Number of strikes whose quotes were adjusted for parity.
+    parity_adjustments: AtomicU64,
+    /// Operator quoting pauses, if attached.
+    pauses: Option<Arc<QuotingPauses>>,
+    /// Pricing sanity check applied by [`Self::finalize`], if attached.
+    sanity: Option<QuoteSanity>,
+    /// Online arrival intensity applied by [`Self::generate_for`], if attached.
+    intensity: Option<Arc<IntensityEstimator>>,
+}
+
+impl QuoteEngine {
+    /// Creates a quote engine with the default parity configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `calculator` - Per-contract quote model
+    #[must_use]
+    pub fn new(calculator: SpreadCalculator) -> Self {
+        Self {
+            calculator,
+            parity: ParityConfig::default(),
+            parity_adjustments: AtomicU64::new(0),
+            pauses: None,
+            sanity: None,
+            intensity: None,
+        }
+    }
+
+    /// Attaches the registry of quoting pauses checked by
+    /// [`Self::generate_for`].
+    #[must_use]
+    pub fn with_pauses(mut self, pauses: Arc<QuotingPauses>) -> Self {
+        self.pauses = Some(pauses);
+        self
+    }
+
+    /// Attaches the pricing sanity check applied by [`Self::finalize`].
+    #[must_use]
+    pub fn with_sanity(mut self, sanity: QuoteSanity) -> Self {
+        self.sanity = Some(sanity);
+        self
+    }
+
+    /// Attaches the arrival intensity estimator applied by
+    /// [`Self::generate_for`].
+    #[must_use]
+    pub fn with_intensity(mut self, intensity: Arc<IntensityEstimator>) -> Self {
+        self.intensity = Some(intensity);
+        self
+    }
+
+    /// Sets the parity pass configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the tolerance is negative, the
+    /// tick size is not positive or the rounding mode is invalid.
+    pub fn with_parity(mut self, parity: ParityConfig) -> Result<Self> {
+        if parity.tolerance < Decimal::ZERO {
+            return Err(Error::configuration(
+                "parity tolerance must be non-negative",
+            ));
+        }
+        if parity.tick_size.is_some_and(|t| t <= Decimal::ZERO) {
+            return Err(Error::configuration("parity tick size must be positive"));
+        }
+        parity.rounding.validate()?;
+        self.parity = parity;
+        Ok(self)
+    }
+
+    /// Returns the per-contract quote model.
+    #[must_use]
+    pub const fn calculator(&self) -> &SpreadCalculator {
+        &self.calculator
+    }
+
+    /// Returns the parity pass configuration.
+    #[must_use]
+    pub const fn parity(&self) -> &ParityConfig {
+        &self.parity
+    }
+
+    /// Returns the attached registry of quoting pauses.
+    #[must_use]
+    pub fn pauses(&self) -> Option<&QuotingPauses> {
+        self.pauses.as_deref()
+    }
+
+    /// Returns the attached pricing sanity check.
+    #[must_use]
+    pub const fn sanity(&self) -> Option<&QuoteSanity> {
+        self.sanity.as_ref()
+    }
+
+    /// Returns the attached arrival intensity estimator.
+    #[must_use]
+    pub fn intensity(&self) -> Option<&IntensityEstimator> {
+        self.intensity.as_deref()
+    }
+
+    /// Returns the number of strikes adjusted for parity so far.
+    #[must_use]
+    pub fn parity_adjustments(&self) -> u64 {
+        self.parity_adjustments.load(Ordering::Relaxed)
+    }
+
+    /// Generates a quote for a single contract.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::QuotingError` if the parameters are invalid.
+    pub fn generate(&self, params: &QuoteParams) -> Result<GeneratedQuote> {
+        self.calculator.generate(params)
+    }
+
+    /// Generates parity-consistent call and put quotes at one strike.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::QuotingError` if either side's parameters are invalid.
+    pub fn generate_strike(&self, request: &StrikeQuoteRequest) -> Result<StrikeQuotes> {
+        let call = self.calculator.generate(&request.call)?;
+        let put = self.calculator.generate(&request.put)?;
+        if !self.parity.enabled {
+            return Ok(StrikeQuotes {
+                call,
+                put,
+                parity: None,
+            });
+        }
+        Ok(self.enforce_parity(request.strike, &request.forward, call, put))
+    }
+
+    /// Generates a quote for a contract in an expiry phase.
+    ///
+    /// Returns `None` when the phase allows no quotes. In
+    /// [`ExpiryPhase::SettlementOnly`] only the side that reduces the
+    /// inventory is kept, and a flat inventory is not quoted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::QuotingError` if the parameters are invalid.
+    pub fn generate_in_phase(
+        &self,
+        params: &QuoteParams,
+        phase: ExpiryPhase,
+    ) -> Result<Option<GeneratedQuote>> {
+        match phase {
+            ExpiryPhase::Normal => self.generate(params).map(Some),
+            ExpiryPhase::SettlementOnly => {
+                if params.inventory == Decimal::ZERO {
+                    return Ok(None);
+                }
+                let mut quote = self.generate(params)?;
+                if params.inventory > Decimal::ZERO {
+                    quote.bid_size = 0;
+                } else {
+                    quote.ask_size = 0;
+                }
+                Ok(Some(quote))
+            }
+            ExpiryPhase::Halted | ExpiryPhase::Settling | ExpiryPhase::Settled => Ok(None),
+        }
+    }
+
+    /// Generates a quote for a named contract, unless quoting it is paused.
+    ///
+    /// Returns `None` when an attached pause covers the contract or the
+    /// expiry phase allows no quotes; see [`Self::generate_in_phase`]. With
+    /// an intensity estimator attached, the contract's estimated `k`
+    /// replaces the arrival intensity in the parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - Contract symbol
+    /// * `params` - Quote parameters
+    /// * `phase` - Expiry phase of the contract
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::QuotingError` if the parameters are invalid.
+    pub fn generate_for(
+        &self,
+        symbol: &str,
+        params: &QuoteParams,
+        phase: ExpiryPhase,
+    ) -> Result<Option<GeneratedQuote>> {
+        if self.pauses.as_ref().is_some_and(|p| p.is_paused(symbol)) {
+            return Ok(None);
+        }
+        match &self.intensity {
+            Some(intensity) => self.generate_in_phase(&intensity.apply(symbol, *params), phase),
+            None => self.generate_in_phase(params, phase),
+        }
+    }
+
+    /// Finalizes a quote before it is sent, applying the pricing sanity
+    /// check if one is attached.
+    ///
+    /// Sides violating a no-arbitrage bound are corrected or pulled
+    /// according to the check's configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `quote` - Quote to finalize
+    /// * `pricing` - Pricing inputs of the contract
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PricingError` if the bounds cannot be computed.
+    pub fn finalize(
+        &self,
+        quote: GeneratedQuote,
+        pricing: &PricingParams,
+    ) -> Result<GeneratedQuote> {
+        match &self.sanity {
+            Some(sanity) => Ok(sanity.check(quote, pricing)?.quote),
+            None => Ok(quote),
+        }
+    }
+
+    /// Projects worst-case fills of an outgoing quote batch against the
+    /// portfolio's Greek limits and shrinks or withholds quote sides whose
+    /// fills could breach them.
+    ///
+    /// Quotes are fitted in batch order, so earlier quotes keep priority.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch` - Outgoing quotes with per-contract Greeks
+    /// * `current` - Current portfolio Greeks
+    /// * `limits` - Greek limits
+    #[must_use]
+    pub fn project_limits(
+        &self,
+        batch: &[QuoteExposure],
+        current: &Greeks,
+        limits: &RiskLimits,
+    ) -> LimitProjection {
+        projection::project(batch, current, limits)
+    }
+
+    /// Adjusts a call/put pair so it offers no conversion or reversal
+    /// beyond the tolerance.
+    ///
+    /// # Arguments
+    ///
+    /// * `strike` - Strike price
+    /// * `forward` - Forward inputs for the expiry
+    /// * `call` - Call quote
+    /// * `put` - Put quote
+    #[must_use]
+    pub fn enforce_parity(
+        &self,
+        strike: Decimal,
+        forward: &ForwardInputs,
+        call: GeneratedQuote,
+        put: GeneratedQuote,
+    ) -> StrikeQuotes {
+        let implied_forward = forward.forward();
+        let synthetic_value = forward.discount_factor() * (implied_forward - strike);
+        let tolerance = self.parity.tolerance;
+
+        // Excess of what a conversion or reversal against us would earn.
+        let excess = |call: &GeneratedQuote, put: &GeneratedQuote| {
+            (
+                call.bid_price - put.ask_price - (synthetic_value + tolerance),
+                (synthetic_value - tolerance) - (call.ask_price - put.bid_price),
+            )
+        };
+        let (conversion, reversal) = excess(&call, &put);
+        let shift = if conversion > Decimal::ZERO {
+            -conversion
+        } else if reversal > Decimal::ZERO {
+            reversal
+        } else {
+            return StrikeQuotes {
+                call,
+                put,
+                parity: None,
+            };
+        };
+
+        let half = shift / Decimal::TWO;
+        let (mut call_shift, mut put_shift) = (half, -half);
+        let mut call = call.shifted(call_shift);
+        let mut put = put.shifted(put_shift);
+        // A bid floored at zero absorbs less than its half; the leg moving
+        // up, which has no bound, takes the rest.
+        let (conversion, reversal) = excess(&call, &put);
+        if conversion > Decimal::ZERO {
+            put = put.shifted(conversion);
+            put_shift += conversion;
+        } else if reversal > Decimal::ZERO {
+            call = call.shifted(reversal);
+            call_shift += reversal;
+        }
+        if let Some(tick) = self.parity.tick_size {
+            let (rounded_call, rounded_put) = (
+                call.round_with(tick, self.parity.rounding),
+                put.round_with(tick, self.parity.rounding),
+            );
+            // Only passive rounding is sure to keep parity; fall back to it
+            // if the configured mode reopened a violation.
+            let (conversion, reversal) = excess(&rounded_call, &rounded_put);
+            (call, put) = if conversion > Decimal::ZERO || reversal > Decimal::ZERO {
+                (
+                    call.round_with(tick, RoundingMode::Passive),
+                    put.round_with(tick, RoundingMode::Passive),
+                )
+            } else {
+                (rounded_call, rounded_put)
+            };
+            // Raising an ask never reopens a violation.
+            call.ask_price = call.ask_price.max(tick);
+            put.ask_price = put.ask_price.max(tick);
+        }
+        self.parity_adjustments.fetch_add(1, Ordering::Relaxed);
+
+        StrikeQuotes {
+            call,
+            put,
+            parity: Some(ParityAdjustment {
+                implied_forward,
+                synthetic_value,
+                call_shift,
+                put_shift,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::DiscreteDividend;
+    use rust_decimal_macros::dec;
+
+    fn engine() -> QuoteEngine {
+        QuoteEngine::new(
+            SpreadCalculator::new(10)
+                .with_spread_bounds(dec!(0.20), dec!(0.20))
+                .unwrap(),
+        )
+    }
+
+    fn request(call_theo: Decimal, put_theo: Decimal) -> StrikeQuoteRequest {
+        StrikeQuoteRequest {
+            strike: dec!(100),
+            call: QuoteParams::new(call_theo, dec!(2)),
+            put: QuoteParams::new(put_theo, dec!(2)),
+            forward: ForwardInputs::new(dec!(100), Decimal::ZERO, dec!(30)),
+        }
+    }
+
+    fn assert_no_arbitrage(quotes: &StrikeQuotes, synthetic_value: Decimal) {
+        assert!(quotes.call.bid_price - quotes.put.ask_price <= synthetic_value);
+        assert!(quotes.call.ask_price - quotes.put.bid_price >= synthetic_value);
+    }
+
+    #[test]
+    fn test_forward_inputs() {
+        let inputs = ForwardInputs::new(dec!(100), dec!(0.05), dec!(365));
+        assert!((inputs.forward() - dec!(105.1271)).abs() < dec!(0.0001));
+        assert!((inputs.discount_factor() - dec!(0.951229)).abs() < dec!(0.000001));
+
+        let rates = RateCurve::flat(dec!(0.05));
+        let dividends = DividendSchedule::new()
+            .with_dividend(DiscreteDividend::new(dec!(100), dec!(2)))
+            .unwrap();
+        let inputs =
+            ForwardInputs::from_term_structure(dec!(100), dec!(365), &rates, &dividends).unwrap();
+        let expected = dividends.forward(dec!(100), dec!(365), &rates).unwrap();
+        assert!((inputs.forward() - expected).abs() < dec!(0.000001));
+    }
+
+    #[test]
+    fn test_consistent_quotes_untouched() {
+        let engine = engine();
+        let quotes = engine.generate_strike(&request(dec!(5), dec!(5))).unwrap();
+
+        assert!(quotes.parity.is_none());
+        assert_eq!(quotes.call.mid(), dec!(5));
+        assert_eq!(engine.parity_adjustments(), 0);
+    }
+
+    #[test]
+    fn test_rich_call_is_adjusted() {
+        let engine = engine();
+        // Call bid 5.9 against put ask 5.1 sells the synthetic 0.8 over parity.
+        let quotes = engine.generate_strike(&request(dec!(6), dec!(5))).unwrap();
+
+        let adjustment = quotes.parity.unwrap();
+        assert_eq!(adjustment.call_shift, dec!(-0.4));
+        assert_eq!(adjustment.put_shift, dec!(0.4));
+        assert_eq!(quotes.call.spread(), dec!(0.20));
+        assert_no_arbitrage(&quotes, Decimal::ZERO);
+        assert_eq!(engine.parity_adjustments(), 1);
+    }
+
+    #[test]
+    fn test_rich_put_is_adjusted() {
+        let quotes = engine()
+            .generate_strike(&request(dec!(4), dec!(5)))
+            .unwrap();
+
+        assert!(quotes.parity.unwrap().call_shift > Decimal::ZERO);
+        assert_no_arbitrage(&quotes, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_tolerance_allows_small_violations() {
+        let engine = engine()
+            .with_parity(ParityConfig {
+                tolerance: dec!(1),
+                ..ParityConfig::default()
+            })
+            .unwrap();
+        let quotes = engine.generate_strike(&request(dec!(6), dec!(5))).unwrap();
+        assert!(quotes.parity.is_none());
+    }
+
+    #[test]
+    fn test_disabled_pass() {
+        let engine = engine()
+            .with_parity(ParityConfig {
+                enabled: false,
+                ..ParityConfig::default()
+            })
+            .unwrap();
+        let quotes = engine.generate_strike(&request(dec!(6), dec!(5))).unwrap();
+        assert!(quotes.parity.is_none());
+        assert_eq!(quotes.call.mid(), dec!(6));
+    }
+
+    #[test]
+    fn test_tick_rounding_stays_arbitrage_free() {
+        let engine = engine()
+            .with_parity(ParityConfig {
+                tick_size: Some(dec!(0.05)),
+                ..ParityConfig::default()
+            })
+            .unwrap();
+        let forward = ForwardInputs::new(dec!(100), dec!(0.05), dec!(30));
+        let quotes = engine
+            .generate_strike(&StrikeQuoteRequest {
+                forward,
+                ..request(dec!(6.03), dec!(4.71))
+            })
+            .unwrap();
+
+        let synthetic = forward.discount_factor() * (forward.forward() - dec!(100));
+        assert!(quotes.parity.is_some());
+        assert_no_arbitrage(&quotes, synthetic);
+    }
+
+    #[test]
+    fn test_floored_leg_and_aggressive_rounding_stay_arbitrage_free() {
+        let engine = engine()
+            .with_parity(ParityConfig {
+                tick_size: Some(dec!(0.05)),
+                ..ParityConfig::default()
+            })
+            .unwrap();
+        // Deep in-the-money put: the call can only fall to zero, so the put
+        // takes the rest of the conversion excess.
+        let forward = ForwardInputs::new(dec!(80), Decimal::ZERO, dec!(30));
+        let quotes = engine
+            .generate_strike(&StrikeQuoteRequest {
+                forward,
+                ..request(dec!(0.15), dec!(19))
+            })
+            .unwrap();
+        assert_no_arbitrage(&quotes, dec!(-20));
+        assert_eq!(quotes.call.bid_price, Decimal::ZERO);
+        assert_eq!(quotes.call.ask_price, dec!(0.05));
+        assert_eq!(quotes.parity.unwrap().put_shift, dec!(0.9));
+
+        // Rounding towards the other side must not reopen a violation.
+        for rounding in [
+            RoundingMode::Aggressive,
+            RoundingMode::Nearest {
+                min_edge: Decimal::ZERO,
+            },
+        ] {
+            let engine = self::engine()
+                .with_parity(ParityConfig {
+                    tick_size: Some(dec!(0.05)),
+                    rounding,
+                    ..ParityConfig::default()
+                })
+                .unwrap();
+            let forward = ForwardInputs::new(dec!(100), dec!(0.05), dec!(30));
+            let quotes = engine
+                .generate_strike(&StrikeQuoteRequest {
+                    forward,
+                    ..request(dec!(6.03), dec!(4.71))
+                })
+                .unwrap();
+            let synthetic = forward.discount_factor() * (forward.forward() - dec!(100));
+            assert_no_arbitrage(&quotes, synthetic);
+        }
+    }
+
+    #[test]
+    fn test_invalid_parity_config() {
+        let config = ParityConfig {
+            tolerance: dec!(-0.01),
+            ..ParityConfig::default()
+        };
+        assert!(engine().with_parity(config).is_err());
+    }
+
+    #[test]
+    fn test_finalize_applies_sanity() {
+        use crate::quoting::SanityConfig;
+        use optionstratlib::OptionStyle;
+
+        let engine = engine().with_sanity(QuoteSanity::new(SanityConfig::default()).unwrap());
+        let pricing =
+            PricingParams::new(dec!(110), dec!(100), dec!(30), dec!(0.5), OptionStyle::Call);
+        let quote = GeneratedQuote {
+            theo: dec!(9.5),
+            reservation_price: dec!(9.5),
+            bid_price: dec!(9),
+            ask_price: dec!(9.8),
+            bid_size: 10,
+            ask_size: 10,
+        };
+
+        // Both sides sit below the intrinsic value of 10.
+        let finalized = engine.finalize(quote, &pricing).unwrap();
+        assert_eq!(finalized.ask_price, dec!(10));
+        assert_eq!(finalized.bid_price, dec!(10));
+        assert_eq!(finalized.bid_size, 0);
+        let stats = engine.sanity().unwrap().stats();
+        assert_eq!(stats.bid_below_zero_vol, 1);
+        assert_eq!(stats.crossed, 1);
+    }
+
+    #[test]
+    fn test_paused_contract_not_quoted() {
+        use crate::clock::ManualClock;
+        use crate::quoting::{PauseReason, PauseScope};
+
+        let pauses = Arc::new(QuotingPauses::new(Arc::new(ManualClock::new(0))));
+        let engine = engine().with_pauses(Arc::clone(&pauses));
+        let params = QuoteParams::new(dec!(5), dec!(0.5));
+        pauses
+            .pause(
+                PauseScope::Expiry {
+                    underlying: "BTC".to_string(),
+                    expiry: "20240329".to_string(),
+                },
+                PauseReason::Expiring,
+                "weekly",
+                "ops",
+                None,
+            )
+            .unwrap();
+
+        let paused = engine
+            .generate_for("BTC-20240329-50000-C", &params, ExpiryPhase::Normal)
+            .unwrap();
+        assert!(paused.is_none());
+        let quoted = engine
+            .generate_for("BTC-20240405-50000-C", &params, ExpiryPhase::Normal)
+            .unwrap();
+        assert!(quoted.is_some());
+    }
+
+    #[test]
+    fn test_estimated_intensity_feeds_generation() {
+        use crate::quoting::{IntensityConfig, IntensityEstimator};
+
+        let intensity = Arc::new(IntensityEstimator::new(IntensityConfig::default()).unwrap());
+        let engine =
+            QuoteEngine::new(SpreadCalculator::new(10)).with_intensity(Arc::clone(&intensity));
+        let params = QuoteParams::new(dec!(5), dec!(0.5));
+        let symbol = "BTC-20240329-50000-C";
+        let before = engine
+            .generate_for(symbol, &params, ExpiryPhase::Normal)
+            .unwrap()
+            .unwrap();
+
+        // Trades rarely reach past the touch: a steep decay, so a tighter
+        // spread than the static intensity gives.
+        for i in 0..40 {
+            let distance = if i % 4 == 0 { dec!(0.12) } else { dec!(0.01) };
+            intensity.on_trade(symbol, distance);
+        }
+        let k = intensity.estimate(symbol).unwrap().k;
+        assert!(k > params.arrival_intensity);
+        let after = engine
+            .generate_for(symbol, &params, ExpiryPhase::Normal)
+            .unwrap()
+            .unwrap();
+        assert!(after.spread() < before.spread());
+        assert_eq!(
+            after,
+            engine.generate(&params.with_arrival_intensity(k)).unwrap()
+        );
+    }
+}
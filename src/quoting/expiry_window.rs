@@ -0,0 +1,281 @@
+//! Expiry window module.
+//!
+//! This module provides per-venue [`ExpiryWindow`]s, which decide how a
+//! chain is quoted as its expiry approaches: normal quoting, a halt
+//! T-minus N, settlement-only quotes during a venue's settlement auction,
+//! and a settlement period after which quoting of the underlying resumes.
+//!
+//! ## Phases
+//!
+//! With expiry `T`:
+//!
+//! ```text
+//! Normal          now < T - halt_before
+//! Halted          T - halt_before <= now < T - settlement_only_before
+//! SettlementOnly  T - settlement_only_before <= now < T
+//! Settling        T <= now < T + settlement
+//! Settled         now >= T + settlement
+//! ```
+//!
+//! Expiries come from the [`ExpiryClock`], so a window follows the same
+//! clock as time-to-expiry updates.
+
+use crate::clock::ExpiryClock;
+use crate::error::{Error, Result};
+use crate::orderbook::{OptionChainOrderBook, UnderlyingOrderBook};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Quoting phase of a chain around its expiry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ExpiryPhase {
+    /// Regular two-sided quoting.
+    Normal,
+    /// No quotes before the settlement auction.
+    Halted,
+    /// Only quotes that reduce inventory into settlement.
+    SettlementOnly,
+    /// Expired, waiting for the settlement price.
+    Settling,
+    /// Settled; the chain is no longer quoted.
+    Settled,
+}
+
+impl ExpiryPhase {
+    /// Returns true if regular quoting is allowed.
+    #[must_use]
+    pub const fn is_normal(&self) -> bool {
+        matches!(self, Self::Normal)
+    }
+}
+
+/// Expiry behavior of one venue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExpiryWindow {
+    /// Regular quoting stops this long before expiry.
+    pub halt_before_ms: u64,
+    /// Settlement-only quoting starts this long before expiry.
+    pub settlement_only_before_ms: u64,
+    /// Time from expiry until the settlement price is known.
+    pub settlement_ms: u64,
+    /// Whether every chain of the underlying pauses while one chain is
+    /// between its halt and its settlement.
+    pub pause_underlying: bool,
+}
+
+impl ExpiryWindow {
+    /// Validates the window.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if settlement-only quoting starts
+    /// before regular quoting halts.
+    pub fn validate(&self) -> Result<()> {
+        if self.settlement_only_before_ms > self.halt_before_ms {
+            return Err(Error::configuration(
+                "settlement_only_before_ms must not exceed halt_before_ms",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the phase at a time for an expiry.
+    ///
+    /// # Arguments
+    ///
+    /// * `now_ms` - Current time in milliseconds
+    /// * `expiry_ms` - Expiry time in milliseconds
+    #[must_use]
+    pub const fn phase(&self, now_ms: u64, expiry_ms: u64) -> ExpiryPhase {
+        if now_ms >= expiry_ms {
+            if now_ms - expiry_ms >= self.settlement_ms {
+                ExpiryPhase::Settled
+            } else {
+                ExpiryPhase::Settling
+            }
+        } else {
+            let remaining = expiry_ms - now_ms;
+            if remaining <= self.settlement_only_before_ms {
+                ExpiryPhase::SettlementOnly
+            } else if remaining <= self.halt_before_ms {
+                ExpiryPhase::Halted
+            } else {
+                ExpiryPhase::Normal
+            }
+        }
+    }
+
+    /// Returns the phase of a chain, using the clock's time and expiry.
+    #[must_use]
+    pub fn chain_phase(&self, clock: &ExpiryClock, chain: &OptionChainOrderBook) -> ExpiryPhase {
+        self.phase(clock.now_ms(), clock.expiry_ms(chain.expiration()))
+    }
+
+    /// Returns true if quoting of every chain of an underlying pauses
+    /// because one of its chains is between halt and settlement.
+    ///
+    /// Always false unless [`ExpiryWindow::pause_underlying`] is set.
+    #[must_use]
+    pub fn underlying_paused(&self, clock: &ExpiryClock, underlying: &UnderlyingOrderBook) -> bool {
+        self.pause_underlying
+            && underlying.expirations().iter().any(|e| {
+                matches!(
+                    self.chain_phase(clock, e.value().chain()),
+                    ExpiryPhase::Halted | ExpiryPhase::SettlementOnly | ExpiryPhase::Settling
+                )
+            })
+    }
+}
+
+/// Expiry windows by venue, with a default for unlisted venues.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VenueExpiryWindows {
+    /// Window of venues without their own entry.
+    default: ExpiryWindow,
+    /// Windows by venue.
+    venues: HashMap<String, ExpiryWindow>,
+}
+
+impl VenueExpiryWindows {
+    /// Creates a configuration with a default window.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the window is invalid.
+    pub fn new(default: ExpiryWindow) -> Result<Self> {
+        default.validate()?;
+        Ok(Self {
+            default,
+            venues: HashMap::new(),
+        })
+    }
+
+    /// Sets the window of a venue.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the window is invalid.
+    pub fn with_venue(mut self, venue: impl Into<String>, window: ExpiryWindow) -> Result<Self> {
+        window.validate()?;
+        self.venues.insert(venue.into(), window);
+        Ok(self)
+    }
+
+    /// Returns the window of a venue.
+    #[must_use]
+    pub fn window(&self, venue: &str) -> &ExpiryWindow {
+        self.venues.get(venue).unwrap_or(&self.default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::orderbook::UnderlyingOrderBookManager;
+    use crate::quoting::{QuoteEngine, QuoteParams, SpreadCalculator};
+    use optionstratlib::ExpirationDate;
+    use optionstratlib::prelude::pos_or_panic;
+    use rust_decimal_macros::dec;
+    use std::sync::Arc;
+
+    const MINUTE: u64 = 60_000;
+
+    fn window() -> ExpiryWindow {
+        ExpiryWindow {
+            halt_before_ms: 30 * MINUTE,
+            settlement_only_before_ms: 10 * MINUTE,
+            settlement_ms: 5 * MINUTE,
+            pause_underlying: true,
+        }
+    }
+
+    #[test]
+    fn test_phases() {
+        let window = window();
+        let expiry = 1_000 * MINUTE;
+        assert_eq!(
+            window.phase(expiry - 31 * MINUTE, expiry),
+            ExpiryPhase::Normal
+        );
+        assert_eq!(
+            window.phase(expiry - 30 * MINUTE, expiry),
+            ExpiryPhase::Halted
+        );
+        assert_eq!(
+            window.phase(expiry - 5 * MINUTE, expiry),
+            ExpiryPhase::SettlementOnly
+        );
+        assert_eq!(window.phase(expiry + MINUTE, expiry), ExpiryPhase::Settling);
+        assert_eq!(
+            window.phase(expiry + 5 * MINUTE, expiry),
+            ExpiryPhase::Settled
+        );
+    }
+
+    #[test]
+    fn test_venue_windows() {
+        let invalid = ExpiryWindow {
+            settlement_only_before_ms: 2,
+            halt_before_ms: 1,
+            ..ExpiryWindow::default()
+        };
+        assert!(VenueExpiryWindows::new(invalid).is_err());
+
+        let windows = VenueExpiryWindows::new(ExpiryWindow::default())
+            .unwrap()
+            .with_venue("EUREX", window())
+            .unwrap();
+        assert_eq!(windows.window("EUREX").halt_before_ms, 30 * MINUTE);
+        assert_eq!(windows.window("CME").halt_before_ms, 0);
+    }
+
+    #[test]
+    fn test_settlement_only_quotes_reduce_inventory() {
+        let engine = QuoteEngine::new(SpreadCalculator::new(10));
+        let params = QuoteParams::new(dec!(5), dec!(2));
+
+        assert!(
+            engine
+                .generate_in_phase(&params, ExpiryPhase::Normal)
+                .unwrap()
+                .unwrap()
+                .is_two_sided()
+        );
+        assert!(
+            engine
+                .generate_in_phase(&params, ExpiryPhase::SettlementOnly)
+                .unwrap()
+                .is_none()
+        );
+        let long = engine
+            .generate_in_phase(&params.with_inventory(dec!(3)), ExpiryPhase::SettlementOnly)
+            .unwrap()
+            .unwrap();
+        assert_eq!((long.bid_size, long.ask_size), (0, 10));
+        assert!(
+            engine
+                .generate_in_phase(&params, ExpiryPhase::Halted)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_underlying_pauses_around_one_expiry() {
+        let clock = Arc::new(ManualClock::new(0));
+        let expiry = ExpiryClock::new(clock.clone(), 1_000).unwrap();
+        let manager = UnderlyingOrderBookManager::new();
+        let btc = manager.get_or_create("BTC");
+        btc.get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(1.0)));
+        btc.get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(30.0)));
+
+        let window = window();
+        assert!(!window.underlying_paused(&expiry, &btc));
+        clock.set(86_400_000 - 20 * MINUTE);
+        assert!(window.underlying_paused(&expiry, &btc));
+        clock.set(86_400_000 + 5 * MINUTE);
+        assert!(!window.underlying_paused(&expiry, &btc));
+    }
+}
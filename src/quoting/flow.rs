@@ -0,0 +1,289 @@
+//! Option flow module.
+//!
+//! This module provides the [`FlowTracker`], which aggregates signed
+//! customer volume per expiration and strike over fixed intervals and
+//! publishes each completed interval as a [`FlowReport`]. Desks read the
+//! reports to see where customer pressure sits and to adapt skew at
+//! specific strikes.
+//!
+//! Flow is signed from the customer's side: a customer buy (our sell) adds
+//! to the net volume, a customer sell (our buy) subtracts from it.
+
+use crate::error::{Error, Result};
+use optionstratlib::OptionStyle;
+use orderbook_rs::Side;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// A customer trade against our quotes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlowTrade {
+    /// Expiration label (e.g. "20240329").
+    pub expiration: String,
+    /// Strike price.
+    pub strike: u64,
+    /// Call or put.
+    pub style: OptionStyle,
+    /// Side the customer traded.
+    pub customer_side: Side,
+    /// Quantity in contracts.
+    pub quantity: u64,
+    /// Trade price.
+    pub price: Decimal,
+    /// Trade time in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// Aggregated flow at one strike of one expiration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StrikeFlow {
+    /// Expiration label.
+    pub expiration: String,
+    /// Strike price.
+    pub strike: u64,
+    /// Contracts bought by customers.
+    pub buy_volume: u64,
+    /// Contracts sold by customers.
+    pub sell_volume: u64,
+    /// Net customer volume in calls.
+    pub call_net: i64,
+    /// Net customer volume in puts.
+    pub put_net: i64,
+    /// Premium paid by customers minus premium received.
+    pub net_premium: Decimal,
+    /// Number of trades.
+    pub trades: u64,
+}
+
+impl StrikeFlow {
+    fn new(expiration: &str, strike: u64) -> Self {
+        Self {
+            expiration: expiration.to_string(),
+            strike,
+            buy_volume: 0,
+            sell_volume: 0,
+            call_net: 0,
+            put_net: 0,
+            net_premium: Decimal::ZERO,
+            trades: 0,
+        }
+    }
+
+    /// Returns customer buys minus customer sells.
+    #[must_use]
+    pub const fn net_volume(&self) -> i64 {
+        self.call_net + self.put_net
+    }
+
+    fn add(&mut self, trade: &FlowTrade) {
+        let signed = match trade.customer_side {
+            Side::Buy => {
+                self.buy_volume += trade.quantity;
+                trade.quantity as i64
+            }
+            Side::Sell => {
+                self.sell_volume += trade.quantity;
+                -(trade.quantity as i64)
+            }
+        };
+        match trade.style {
+            OptionStyle::Call => self.call_net += signed,
+            OptionStyle::Put => self.put_net += signed,
+        }
+        self.net_premium += Decimal::from(signed) * trade.price;
+        self.trades += 1;
+    }
+}
+
+/// Flow over one interval.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlowReport {
+    /// Interval start in milliseconds (inclusive).
+    pub from_ms: u64,
+    /// Interval end in milliseconds (exclusive).
+    pub to_ms: u64,
+    /// Per-strike flow, by expiration then strike.
+    pub strikes: Vec<StrikeFlow>,
+}
+
+impl FlowReport {
+    /// Returns the net customer volume across all strikes.
+    #[must_use]
+    pub fn net_volume(&self) -> i64 {
+        self.strikes.iter().map(StrikeFlow::net_volume).sum()
+    }
+
+    /// Returns the flow at one strike, if it traded.
+    #[must_use]
+    pub fn strike(&self, expiration: &str, strike: u64) -> Option<&StrikeFlow> {
+        self.strikes
+            .iter()
+            .find(|s| s.expiration == expiration && s.strike == strike)
+    }
+
+    /// Returns the strikes with the largest absolute net volume first.
+    #[must_use]
+    pub fn by_pressure(&self) -> Vec<&StrikeFlow> {
+        let mut strikes: Vec<&StrikeFlow> = self.strikes.iter().collect();
+        strikes.sort_by_key(|s| std::cmp::Reverse(s.net_volume().unsigned_abs()));
+        strikes
+    }
+}
+
+/// Flow of one interval keyed by expiration and strike.
+type IntervalFlow = BTreeMap<(String, u64), StrikeFlow>;
+
+/// Aggregates customer flow per strike over fixed intervals.
+pub struct FlowTracker {
+    /// Interval length in milliseconds.
+    interval_ms: u64,
+    /// Open intervals by start time.
+    intervals: Mutex<BTreeMap<u64, IntervalFlow>>,
+}
+
+impl FlowTracker {
+    /// Creates a tracker.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval_ms` - Interval length in milliseconds
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the interval is zero.
+    pub fn new(interval_ms: u64) -> Result<Self> {
+        if interval_ms == 0 {
+            return Err(Error::configuration("flow interval must be positive"));
+        }
+        Ok(Self {
+            interval_ms,
+            intervals: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    /// Returns the interval length in milliseconds.
+    #[must_use]
+    pub const fn interval_ms(&self) -> u64 {
+        self.interval_ms
+    }
+
+    /// Returns the number of intervals not yet published.
+    #[must_use]
+    pub fn pending_intervals(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Records a customer trade in the interval containing its timestamp.
+    pub fn record(&self, trade: &FlowTrade) {
+        let start = self.interval_start(trade.timestamp_ms);
+        self.lock()
+            .entry(start)
+            .or_default()
+            .entry((trade.expiration.clone(), trade.strike))
+            .or_insert_with(|| StrikeFlow::new(&trade.expiration, trade.strike))
+            .add(trade);
+    }
+
+    /// Removes and returns the reports of every interval that ended at or
+    /// before `now_ms`, oldest first.
+    pub fn publish(&self, now_ms: u64) -> Vec<FlowReport> {
+        let Some(cutoff) = now_ms.checked_sub(self.interval_ms) else {
+            return Vec::new();
+        };
+        let mut intervals = self.lock();
+        let open = intervals.split_off(&(cutoff + 1));
+        std::mem::replace(&mut *intervals, open)
+            .into_iter()
+            .map(|(start, flow)| self.report(start, flow))
+            .collect()
+    }
+
+    /// Returns the report of the interval containing `now_ms` so far,
+    /// without publishing it.
+    #[must_use]
+    pub fn current(&self, now_ms: u64) -> FlowReport {
+        let start = self.interval_start(now_ms);
+        let flow = self.lock().get(&start).cloned().unwrap_or_default();
+        self.report(start, flow)
+    }
+
+    fn interval_start(&self, timestamp_ms: u64) -> u64 {
+        timestamp_ms - timestamp_ms % self.interval_ms
+    }
+
+    fn report(&self, start: u64, flow: IntervalFlow) -> FlowReport {
+        FlowReport {
+            from_ms: start,
+            to_ms: start + self.interval_ms,
+            strikes: flow.into_values().collect(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<u64, IntervalFlow>> {
+        self.intervals
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn trade(strike: u64, style: OptionStyle, side: Side, quantity: u64, ts: u64) -> FlowTrade {
+        FlowTrade {
+            expiration: "20240329".to_string(),
+            strike,
+            style,
+            customer_side: side,
+            quantity,
+            price: dec!(2),
+            timestamp_ms: ts,
+        }
+    }
+
+    #[test]
+    fn test_net_flow_per_strike() {
+        let tracker = FlowTracker::new(60_000).unwrap();
+        tracker.record(&trade(100, OptionStyle::Call, Side::Buy, 10, 1_000));
+        tracker.record(&trade(100, OptionStyle::Put, Side::Sell, 4, 2_000));
+        tracker.record(&trade(110, OptionStyle::Call, Side::Sell, 20, 3_000));
+
+        let report = tracker.current(5_000);
+        let atm = report.strike("20240329", 100).unwrap();
+        assert_eq!(atm.call_net, 10);
+        assert_eq!(atm.put_net, -4);
+        assert_eq!(atm.net_volume(), 6);
+        assert_eq!(atm.net_premium, dec!(12));
+        assert_eq!(report.net_volume(), -14);
+        assert_eq!(report.by_pressure()[0].strike, 110);
+    }
+
+    #[test]
+    fn test_publish_completed_intervals() {
+        let tracker = FlowTracker::new(60_000).unwrap();
+        tracker.record(&trade(100, OptionStyle::Call, Side::Buy, 1, 10_000));
+        tracker.record(&trade(100, OptionStyle::Call, Side::Buy, 2, 70_000));
+        tracker.record(&trade(100, OptionStyle::Call, Side::Buy, 3, 130_000));
+
+        let published = tracker.publish(125_000);
+        assert_eq!(published.len(), 2);
+        assert_eq!(published[0].from_ms, 0);
+        assert_eq!(published[1].net_volume(), 2);
+        assert_eq!(tracker.pending_intervals(), 1);
+        assert!(tracker.publish(125_000).is_empty());
+    }
+
+    #[test]
+    fn test_report_serializes() {
+        let tracker = FlowTracker::new(1_000).unwrap();
+        tracker.record(&trade(100, OptionStyle::Put, Side::Buy, 5, 0));
+        let report = tracker.publish(1_000).remove(0);
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(serde_json::from_str::<FlowReport>(&json).unwrap(), report);
+        assert!(FlowTracker::new(0).is_err());
+    }
+}
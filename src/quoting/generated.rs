@@ -0,0 +1,177 @@
+//! Generated quote module.
+//!
+//! This module provides [`GeneratedQuote`], the output of the quoting
+//! models before it is sent to an order book.
+
+use super::rounding::RoundingMode;
+use super::tick_schedule::TickSchedule;
+use crate::orderbook::Quote;
+use orderbook_rs::Side;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+/// A two-sided quote produced by a quoting model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeneratedQuote {
+    /// Theoretical value the quote was built around.
+    pub theo: Decimal,
+    /// Inventory-adjusted reservation price.
+    pub reservation_price: Decimal,
+    /// Bid price.
+    pub bid_price: Decimal,
+    /// Ask price.
+    pub ask_price: Decimal,
+    /// Bid size in contracts.
+    pub bid_size: u64,
+    /// Ask size in contracts.
+    pub ask_size: u64,
+}
+
+impl GeneratedQuote {
+    /// Returns the midpoint of the bid and ask.
+    #[must_use]
+    pub fn mid(&self) -> Decimal {
+        (self.bid_price + self.ask_price) / Decimal::TWO
+    }
+
+    /// Returns the width of the quote.
+    #[must_use]
+    pub fn spread(&self) -> Decimal {
+        self.ask_price - self.bid_price
+    }
+
+    /// Returns true if both sides have size.
+    #[must_use]
+    pub const fn is_two_sided(&self) -> bool {
+        self.bid_size > 0 && self.ask_size > 0
+    }
+
+    /// Returns the quote with both prices moved by the same amount.
+    ///
+    /// Prices are floored at zero.
+    #[must_use]
+    pub fn shifted(mut self, shift: Decimal) -> Self {
+        self.bid_price = (self.bid_price + shift).max(Decimal::ZERO);
+        self.ask_price = (self.ask_price + shift).max(Decimal::ZERO);
+        self
+    }
+
+    /// Returns the quote with prices rounded to a tick size.
+    ///
+    /// Bids round down and asks round up, so rounding never tightens the
+    /// market. A non-positive tick size leaves the prices unchanged.
+    #[must_use]
+    pub fn round_to_tick(self, tick_size: Decimal) -> Self {
+        self.round_with(tick_size, RoundingMode::Passive)
+    }
+
+    /// Returns the quote with prices rounded to a tick size in a mode.
+    ///
+    /// [`RoundingMode::Nearest`] measures edge against `theo`. A
+    /// non-positive tick size leaves the prices unchanged.
+    #[must_use]
+    pub fn round_with(mut self, tick_size: Decimal, mode: RoundingMode) -> Self {
+        self.bid_price = mode.round(self.bid_price, Side::Buy, tick_size, self.theo);
+        self.ask_price = mode.round(self.ask_price, Side::Sell, tick_size, self.theo);
+        self
+    }
+
+    /// Returns the quote with each price rounded to the tick of its band in
+    /// a tick schedule.
+    #[must_use]
+    pub fn round_to_schedule(mut self, schedule: &TickSchedule, mode: RoundingMode) -> Self {
+        self.bid_price = schedule.round(self.bid_price, Side::Buy, mode, self.theo);
+        self.ask_price = schedule.round(self.ask_price, Side::Sell, mode, self.theo);
+        self
+    }
+
+    /// Converts the quote to an order book [`Quote`] in integer ticks.
+    ///
+    /// Returns `None` for a side whose price does not fit in ticks or whose
+    /// size is zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `tick_size` - Price of one book tick
+    /// * `timestamp_ms` - Quote timestamp in milliseconds
+    #[must_use]
+    pub fn to_book_quote(&self, tick_size: Decimal, timestamp_ms: u64) -> Quote {
+        let ticks = |price: Decimal, size: u64| {
+            if size == 0 || tick_size <= Decimal::ZERO {
+                return None;
+            }
+            (price / tick_size).round().to_u128()
+        };
+        let bid = ticks(self.bid_price, self.bid_size);
+        let ask = ticks(self.ask_price, self.ask_size);
+        Quote::new(
+            bid,
+            if bid.is_some() { self.bid_size } else { 0 },
+            ask,
+            if ask.is_some() { self.ask_size } else { 0 },
+            timestamp_ms,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quoting::TickBand;
+    use rust_decimal_macros::dec;
+
+    fn quote() -> GeneratedQuote {
+        GeneratedQuote {
+            theo: dec!(5.00),
+            reservation_price: dec!(5.00),
+            bid_price: dec!(4.87),
+            ask_price: dec!(5.13),
+            bid_size: 10,
+            ask_size: 10,
+        }
+    }
+
+    #[test]
+    fn test_round_to_tick_is_passive() {
+        let rounded = quote().round_to_tick(dec!(0.05));
+        assert_eq!(rounded.bid_price, dec!(4.85));
+        assert_eq!(rounded.ask_price, dec!(5.15));
+    }
+
+    #[test]
+    fn test_round_with_aggressive() {
+        let rounded = quote().round_with(dec!(0.05), RoundingMode::Aggressive);
+        assert_eq!(rounded.bid_price, dec!(4.90));
+        assert_eq!(rounded.ask_price, dec!(5.10));
+    }
+
+    #[test]
+    fn test_round_to_schedule_uses_band_tick() {
+        let schedule = TickSchedule::new(vec![
+            TickBand::new(Decimal::ZERO, dec!(0.01)),
+            TickBand::new(dec!(5), dec!(0.25)),
+        ])
+        .unwrap();
+        let rounded = quote().round_to_schedule(&schedule, RoundingMode::Passive);
+        assert_eq!(rounded.bid_price, dec!(4.87));
+        assert_eq!(rounded.ask_price, dec!(5.25));
+    }
+
+    #[test]
+    fn test_shifted_floors_at_zero() {
+        let shifted = quote().shifted(dec!(-4.9));
+        assert_eq!(shifted.bid_price, Decimal::ZERO);
+        assert_eq!(shifted.ask_price, dec!(0.23));
+    }
+
+    #[test]
+    fn test_to_book_quote() {
+        let book = quote()
+            .round_to_tick(dec!(0.05))
+            .to_book_quote(dec!(0.01), 7);
+        assert_eq!(book.bid_price(), Some(485));
+        assert_eq!(book.ask_price(), Some(515));
+        assert!(book.is_two_sided());
+    }
+}
@@ -0,0 +1,281 @@
+//! Implied quote module.
+//!
+//! This module provides the [`ImpliedQuoter`], which links the call and put
+//! books of a strike: once we quote one leg, conversion/reversal parity
+//! determines where we can quote the other, since a fill there can be laid
+//! off against our quote on the first leg and the underlying.
+//!
+//! ## Parity
+//!
+//! With discount factor `D` and forward `F`, `C - P = D * (F - K)`. From a
+//! call quote the implied put is the call less `D * (F - K)`, and from a put
+//! quote the implied call is the put plus it. Laying off a fill means
+//! trading the underlying too, so the hedge cost is taken off the implied
+//! bid and added to the implied ask. A bid that would not be positive is
+//! not quoted.
+//!
+//! ## Placement
+//!
+//! With a [`QuoteManager`] attached, [`ImpliedQuoter::place`] rests the
+//! implied quote in the other leg's book under the configured tag, and
+//! [`ImpliedQuoter::pull`] cancels every implied quote together without
+//! touching the directly generated ones.
+
+use super::engine::ForwardInputs;
+use super::generated::GeneratedQuote;
+use super::lifecycle::{LiveQuote, QuoteManager};
+use crate::error::{Error, Result};
+use crate::orderbook::StrikeOrderBook;
+use optionstratlib::OptionStyle;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Configuration of an [`ImpliedQuoter`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImpliedQuoteConfig {
+    /// Cost of the underlying hedge per option, in price units.
+    pub hedge_cost: Decimal,
+    /// Fraction of the source quote's sizes shown on the implied quote.
+    pub size_fraction: Decimal,
+    /// Tag of the implied quotes in the [`QuoteManager`].
+    pub tag: String,
+}
+
+impl Default for ImpliedQuoteConfig {
+    fn default() -> Self {
+        Self {
+            hedge_cost: Decimal::ZERO,
+            size_fraction: Decimal::ONE,
+            tag: "implied".to_string(),
+        }
+    }
+}
+
+impl ImpliedQuoteConfig {
+    /// Validates the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the hedge cost is negative,
+    /// the size fraction is not in `(0, 1]` or the tag is empty.
+    pub fn validate(&self) -> Result<()> {
+        if self.hedge_cost < Decimal::ZERO {
+            return Err(Error::configuration("hedge_cost must not be negative"));
+        }
+        if self.size_fraction <= Decimal::ZERO || self.size_fraction > Decimal::ONE {
+            return Err(Error::configuration("size_fraction must be in (0, 1]"));
+        }
+        if self.tag.is_empty() {
+            return Err(Error::configuration("tag must not be empty"));
+        }
+        Ok(())
+    }
+}
+
+/// Derives quotes on the other leg of a strike from parity.
+pub struct ImpliedQuoter {
+    /// Hedge cost, sizing and tag.
+    config: ImpliedQuoteConfig,
+    /// Manager implied quotes are placed through, if attached.
+    quotes: Option<Arc<QuoteManager>>,
+}
+
+impl ImpliedQuoter {
+    /// Creates an implied quoter.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the configuration is invalid.
+    pub fn new(config: ImpliedQuoteConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            quotes: None,
+        })
+    }
+
+    /// Attaches the quote manager implied quotes are placed through.
+    #[must_use]
+    pub fn with_quote_manager(mut self, quotes: Arc<QuoteManager>) -> Self {
+        self.quotes = Some(quotes);
+        self
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub const fn config(&self) -> &ImpliedQuoteConfig {
+        &self.config
+    }
+
+    /// Returns the quote implied on the other leg of a strike.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_style` - Leg the source quote is on
+    /// * `source` - Our quote on that leg
+    /// * `strike` - Strike price
+    /// * `forward` - Forward inputs for the expiry
+    #[must_use]
+    pub fn implied(
+        &self,
+        source_style: OptionStyle,
+        source: &GeneratedQuote,
+        strike: Decimal,
+        forward: &ForwardInputs,
+    ) -> GeneratedQuote {
+        let synthetic = forward.discount_factor() * (forward.forward() - strike);
+        let shift = match source_style {
+            OptionStyle::Call => -synthetic,
+            OptionStyle::Put => synthetic,
+        };
+        let mut implied = GeneratedQuote {
+            theo: source.theo + shift,
+            reservation_price: source.reservation_price + shift,
+            bid_price: source.bid_price + shift - self.config.hedge_cost,
+            ask_price: source.ask_price + shift + self.config.hedge_cost,
+            bid_size: self.scale(source.bid_size),
+            ask_size: self.scale(source.ask_size),
+        };
+        if implied.bid_price <= Decimal::ZERO {
+            implied.bid_price = Decimal::ZERO;
+            implied.bid_size = 0;
+        }
+        implied
+    }
+
+    /// Places the quote implied by our quote on one leg of a strike in the
+    /// other leg's book, tagged.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if no quote manager is attached,
+    /// or the errors of [`QuoteManager::refresh_tagged`].
+    pub fn place(
+        &self,
+        strike: &StrikeOrderBook,
+        source_style: OptionStyle,
+        source: &GeneratedQuote,
+        forward: &ForwardInputs,
+    ) -> Result<LiveQuote> {
+        let Some(quotes) = &self.quotes else {
+            return Err(Error::configuration("implied quoter has no quote manager"));
+        };
+        let target = match source_style {
+            OptionStyle::Call => strike.put(),
+            OptionStyle::Put => strike.call(),
+        };
+        let implied = self.implied(
+            source_style,
+            source,
+            Decimal::from(strike.strike()),
+            forward,
+        );
+        quotes.refresh_tagged(target.symbol(), &implied, &self.config.tag)
+    }
+
+    /// Pulls every implied quote.
+    ///
+    /// Returns the number of orders cancelled.
+    pub fn pull(&self) -> usize {
+        self.quotes
+            .as_ref()
+            .map_or(0, |quotes| quotes.cancel_tag(&self.config.tag))
+    }
+
+    fn scale(&self, size: u64) -> u64 {
+        let scaled = Decimal::from(size) * self.config.size_fraction;
+        u64::try_from(scaled.floor()).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::orderbook::UnderlyingOrderBookManager;
+    use crate::quoting::{PriceConverter, RoundingPolicy};
+    use optionstratlib::ExpirationDate;
+    use optionstratlib::prelude::pos_or_panic;
+    use rust_decimal_macros::dec;
+
+    fn call_quote() -> GeneratedQuote {
+        GeneratedQuote {
+            theo: dec!(5.00),
+            reservation_price: dec!(5.00),
+            bid_price: dec!(4.90),
+            ask_price: dec!(5.10),
+            bid_size: 10,
+            ask_size: 10,
+        }
+    }
+
+    #[test]
+    fn test_implied_put_satisfies_parity() {
+        let quoter = ImpliedQuoter::new(ImpliedQuoteConfig {
+            hedge_cost: dec!(0.05),
+            size_fraction: dec!(0.5),
+            ..ImpliedQuoteConfig::default()
+        })
+        .unwrap();
+        let forward = ForwardInputs::new(dec!(102), Decimal::ZERO, dec!(30));
+
+        // D * (F - K) = 2: the put sits 2 below the call, 0.05 wider a side.
+        let put = quoter.implied(OptionStyle::Call, &call_quote(), dec!(100), &forward);
+        assert_eq!((put.bid_price, put.ask_price), (dec!(2.85), dec!(3.15)));
+        assert_eq!(put.theo, dec!(3.00));
+        assert_eq!((put.bid_size, put.ask_size), (5, 5));
+
+        // Back to the call, the hedge cost widens it once more.
+        let call = quoter.implied(OptionStyle::Put, &put, dec!(100), &forward);
+        assert_eq!((call.bid_price, call.ask_price), (dec!(4.80), dec!(5.20)));
+
+        // Deep in-the-money the implied put bid is not quoted.
+        let put = quoter.implied(OptionStyle::Call, &call_quote(), dec!(96), &forward);
+        assert_eq!(put.bid_size, 0);
+        assert!(
+            ImpliedQuoter::new(ImpliedQuoteConfig {
+                size_fraction: dec!(1.5),
+                ..ImpliedQuoteConfig::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_implied_quotes_are_pulled_together() {
+        let manager = Arc::new(UnderlyingOrderBookManager::new());
+        let strike = manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(30.0)))
+            .get_or_create_strike(100);
+        let quotes = Arc::new(QuoteManager::new(
+            Arc::clone(&manager),
+            PriceConverter::new(dec!(0.01), RoundingPolicy::default()).unwrap(),
+            Arc::new(ManualClock::new(1_000)),
+        ));
+        let quoter = ImpliedQuoter::new(ImpliedQuoteConfig::default()).unwrap();
+        let forward = ForwardInputs::new(dec!(102), Decimal::ZERO, dec!(30));
+        assert!(
+            quoter
+                .place(&strike, OptionStyle::Call, &call_quote(), &forward)
+                .is_err()
+        );
+
+        let quoter = quoter.with_quote_manager(Arc::clone(&quotes));
+        quotes
+            .refresh(strike.call().symbol(), &call_quote())
+            .unwrap();
+        let live = quoter
+            .place(&strike, OptionStyle::Call, &call_quote(), &forward)
+            .unwrap();
+        assert_eq!(live.symbol, strike.put().symbol());
+        assert_eq!(live.bid.unwrap().price, 290);
+        assert_eq!(quotes.tagged("implied"), vec![live.symbol.clone()]);
+
+        assert_eq!(quoter.pull(), 2);
+        assert!(strike.put().is_empty());
+        assert_eq!(strike.call().order_count(), 2);
+        assert_eq!(quotes.symbols(), vec![strike.call().symbol().to_string()]);
+    }
+}
@@ -0,0 +1,354 @@
+//! Arrival intensity estimation module.
+//!
+//! This module provides the [`IntensityEstimator`], which learns the
+//! Avellaneda-Stoikov order arrival parameters per contract from fills and
+//! trades instead of relying on a static `arrival_intensity`.
+//!
+//! ## Model
+//!
+//! The model assumes a quote at distance `delta` from mid is filled with
+//! probability proportional to `A * e^(-k * delta)`. Observations are
+//! bucketed by distance and each bucket keeps exponentially weighted counts
+//! of exposures and fills. A weighted regression of `ln(fills / exposures)`
+//! on bucket distance gives `-k` as slope and `ln(A)` as intercept.
+//!
+//! Two kinds of events feed the buckets:
+//!
+//! - [`IntensityEstimator::observe`]: one of our quote sides, at a distance,
+//!   was or was not filled over a sampling interval
+//! - [`IntensityEstimator::on_trade`]: a public trade printed at a distance
+//!   from mid, so a quote at any smaller distance would have been filled
+//!
+//! Both sample the same decay in distance, so `k` mixes them consistently;
+//! `A` is only meaningful when the buckets are fed by `observe`.
+
+use super::params::QuoteParams;
+use crate::error::{Error, Result};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Settings of an [`IntensityEstimator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntensityConfig {
+    /// Width of a distance bucket, in price units.
+    pub bucket_width: Decimal,
+    /// Number of distance buckets; farther observations go to the last.
+    pub buckets: usize,
+    /// Weight kept by past observations at each new event, in `(0, 1]`.
+    pub decay: Decimal,
+    /// Weighted exposures required before an estimate is published.
+    pub min_observations: Decimal,
+    /// Lowest `k` published.
+    pub min_k: Decimal,
+    /// Highest `k` published.
+    pub max_k: Decimal,
+}
+
+impl Default for IntensityConfig {
+    fn default() -> Self {
+        Self {
+            bucket_width: dec!(0.05),
+            buckets: 10,
+            decay: dec!(0.995),
+            min_observations: dec!(20),
+            min_k: dec!(0.1),
+            max_k: dec!(100),
+        }
+    }
+}
+
+impl IntensityConfig {
+    /// Validates the settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the bucket width is not
+    /// positive, fewer than two buckets are configured, the decay is outside
+    /// `(0, 1]` or the `k` bounds are not positive and ordered.
+    pub fn validate(&self) -> Result<()> {
+        if self.bucket_width <= Decimal::ZERO || self.buckets < 2 {
+            return Err(Error::configuration(
+                "intensity buckets need a positive width and at least two buckets",
+            ));
+        }
+        if self.decay <= Decimal::ZERO || self.decay > Decimal::ONE {
+            return Err(Error::configuration("intensity decay must be in (0, 1]"));
+        }
+        if self.min_k <= Decimal::ZERO || self.min_k > self.max_k {
+            return Err(Error::configuration(
+                "intensity k bounds must be positive and ordered",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Arrival parameters estimated for a contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntensityEstimate {
+    /// Decay of fill probability with distance (k).
+    pub k: Decimal,
+    /// Fill probability scale at zero distance (A).
+    pub a: Decimal,
+    /// Weighted exposures behind the estimate.
+    pub observations: Decimal,
+    /// Buckets used in the regression.
+    pub buckets_used: usize,
+}
+
+/// Weighted exposure and fill counts per distance bucket.
+#[derive(Debug, Clone)]
+struct Buckets {
+    exposures: Vec<Decimal>,
+    fills: Vec<Decimal>,
+}
+
+impl Buckets {
+    fn new(len: usize) -> Self {
+        Self {
+            exposures: vec![Decimal::ZERO; len],
+            fills: vec![Decimal::ZERO; len],
+        }
+    }
+
+    fn decay(&mut self, decay: Decimal) {
+        for value in self.exposures.iter_mut().chain(self.fills.iter_mut()) {
+            *value *= decay;
+        }
+    }
+}
+
+/// Online per-contract estimator of the arrival intensity `k`.
+pub struct IntensityEstimator {
+    /// Settings.
+    config: IntensityConfig,
+    /// Buckets per contract.
+    symbols: Mutex<HashMap<String, Buckets>>,
+}
+
+impl IntensityEstimator {
+    /// Creates an estimator.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the settings are invalid.
+    pub fn new(config: IntensityConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            symbols: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Returns the settings.
+    #[must_use]
+    pub const fn config(&self) -> &IntensityConfig {
+        &self.config
+    }
+
+    /// Records whether one of our quote sides was filled over a sampling
+    /// interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - Contract symbol
+    /// * `distance` - Distance of the quote from mid, in price units
+    /// * `filled` - True if the side was filled during the interval
+    pub fn observe(&self, symbol: &str, distance: Decimal, filled: bool) {
+        let bucket = self.bucket(distance);
+        self.update(symbol, |buckets| {
+            buckets.exposures[bucket] += Decimal::ONE;
+            if filled {
+                buckets.fills[bucket] += Decimal::ONE;
+            }
+        });
+    }
+
+    /// Records a public trade printed at a distance from mid.
+    ///
+    /// Every bucket is exposed; buckets up to the trade's distance count as
+    /// filled.
+    pub fn on_trade(&self, symbol: &str, distance: Decimal) {
+        let reached = self.bucket(distance);
+        self.update(symbol, |buckets| {
+            for bucket in 0..buckets.exposures.len() {
+                buckets.exposures[bucket] += Decimal::ONE;
+                if bucket <= reached {
+                    buckets.fills[bucket] += Decimal::ONE;
+                }
+            }
+        });
+    }
+
+    /// Returns the current estimate for a contract.
+    ///
+    /// Returns `None` until enough observations are recorded, fewer than two
+    /// buckets have fills, or fill probability does not decrease with
+    /// distance.
+    #[must_use]
+    pub fn estimate(&self, symbol: &str) -> Option<IntensityEstimate> {
+        let symbols = self.lock();
+        let buckets = symbols.get(symbol)?;
+        let observations: Decimal = buckets.exposures.iter().sum();
+        if observations < self.config.min_observations {
+            return None;
+        }
+
+        let points: Vec<(Decimal, Decimal, Decimal)> = buckets
+            .exposures
+            .iter()
+            .zip(&buckets.fills)
+            .enumerate()
+            .filter(|(_, (exposure, fills))| **exposure > Decimal::ZERO && **fills > Decimal::ZERO)
+            .filter_map(|(i, (exposure, fills))| {
+                let probability = (*fills / *exposure).min(Decimal::ONE);
+                Some((self.center(i), probability.checked_ln()?, *exposure))
+            })
+            .collect();
+        if points.len() < 2 {
+            return None;
+        }
+
+        let weight: Decimal = points.iter().map(|(_, _, w)| w).sum();
+        let mean_x = points.iter().map(|(x, _, w)| x * w).sum::<Decimal>() / weight;
+        let mean_y = points.iter().map(|(_, y, w)| y * w).sum::<Decimal>() / weight;
+        let (mut sxy, mut sxx) = (Decimal::ZERO, Decimal::ZERO);
+        for (x, y, w) in &points {
+            sxy += w * (x - mean_x) * (y - mean_y);
+            sxx += w * (x - mean_x) * (x - mean_x);
+        }
+        if sxx.is_zero() {
+            return None;
+        }
+        let slope = sxy / sxx;
+        if slope >= Decimal::ZERO {
+            return None;
+        }
+        let intercept = mean_y - slope * mean_x;
+        Some(IntensityEstimate {
+            k: (-slope).clamp(self.config.min_k, self.config.max_k),
+            a: intercept.checked_exp()?,
+            observations,
+            buckets_used: points.len(),
+        })
+    }
+
+    /// Returns the parameters with the estimated `k` as arrival intensity,
+    /// or unchanged if there is no estimate for the contract.
+    #[must_use]
+    pub fn apply(&self, symbol: &str, params: QuoteParams) -> QuoteParams {
+        match self.estimate(symbol) {
+            Some(estimate) => params.with_arrival_intensity(estimate.k),
+            None => params,
+        }
+    }
+
+    /// Drops the observations of a contract.
+    pub fn reset(&self, symbol: &str) -> bool {
+        self.lock().remove(symbol).is_some()
+    }
+
+    /// Returns the number of contracts with observations.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns true if no contract has observations.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn update(&self, symbol: &str, f: impl FnOnce(&mut Buckets)) {
+        let mut symbols = self.lock();
+        let buckets = symbols
+            .entry(symbol.to_string())
+            .or_insert_with(|| Buckets::new(self.config.buckets));
+        buckets.decay(self.config.decay);
+        f(buckets);
+    }
+
+    /// Returns the bucket of a distance.
+    fn bucket(&self, distance: Decimal) -> usize {
+        (distance.abs() / self.config.bucket_width)
+            .floor()
+            .to_usize()
+            .unwrap_or(usize::MAX)
+            .min(self.config.buckets - 1)
+    }
+
+    /// Returns the distance at the center of a bucket.
+    fn center(&self, bucket: usize) -> Decimal {
+        (Decimal::from(bucket) + dec!(0.5)) * self.config.bucket_width
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Buckets>> {
+        self.symbols
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimator() -> IntensityEstimator {
+        IntensityEstimator::new(IntensityConfig {
+            decay: Decimal::ONE,
+            ..IntensityConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_recovers_decay_from_fill_outcomes() {
+        let estimator = estimator();
+        // Fill rates of e^(-10 * center): k = 10.
+        for (bucket, fills) in [(0, 78), (1, 47), (2, 29), (3, 17)] {
+            let distance = Decimal::from(bucket) * dec!(0.05) + dec!(0.01);
+            for i in 0..100 {
+                estimator.observe("C", distance, i < fills);
+            }
+        }
+        let estimate = estimator.estimate("C").unwrap();
+        assert!((estimate.k - dec!(10)).abs() < dec!(0.3), "{estimate:?}");
+        assert_eq!(estimate.buckets_used, 4);
+
+        let params = estimator.apply("C", QuoteParams::new(dec!(5), dec!(2)));
+        assert_eq!(params.arrival_intensity, estimate.k);
+        // Unknown contracts keep the static intensity.
+        let params = estimator.apply("P", QuoteParams::new(dec!(5), dec!(2)));
+        assert_eq!(params.arrival_intensity, dec!(1.5));
+    }
+
+    #[test]
+    fn test_trades_and_thresholds() {
+        let estimator = estimator();
+        assert!(estimator.estimate("C").is_none());
+        // Trades that only reach the first bucket give no slope.
+        for _ in 0..20 {
+            estimator.on_trade("C", dec!(0.01));
+        }
+        assert!(estimator.estimate("C").is_none());
+
+        for _ in 0..20 {
+            estimator.on_trade("C", dec!(0.12));
+        }
+        let estimate = estimator.estimate("C").unwrap();
+        assert!(estimate.k > Decimal::ZERO);
+        assert!(estimator.reset("C"));
+        assert!(estimator.is_empty());
+
+        let bad = IntensityConfig {
+            decay: dec!(1.5),
+            ..IntensityConfig::default()
+        };
+        assert!(IntensityEstimator::new(bad).is_err());
+    }
+}
@@ -0,0 +1,969 @@
+//! Quote lifecycle module.
+//!
+//! This module provides the [`QuoteManager`], which owns our resting
+//! two-sided quotes per contract: it places the orders of a
+//! [`GeneratedQuote`] in the contract's book, replaces them when the quote
+//! changes and pulls them all at once when trading is halted.
+//!
+//! ## Refresh
+//!
+//! A refresh compares the new quote with the resting orders side by side,
+//! as they rest in the book now: an order filled since the last refresh is
+//! placed again, and a partly filled one is compared by what is left of it.
+//! A side whose price and size are unchanged keeps its order and its queue
+//! priority, and a side only reduced in size at the same price is amended
+//! through [`OptionOrderBook::modify_order`], keeping its priority too. Any
+//! other changed side is cancelled before its replacement is placed, so old
+//! and new orders never rest together. If a placement fails, every
+//! order of the contract is pulled and the error is returned, leaving the
+//! contract unquoted rather than half-quoted.
+//!
+//! ## Risk
+//!
+//! With a [`RiskController`] attached, refreshes are refused unless the
+//! trading state permits quoting. Registered as a [`HaltListener`], the
+//! manager pulls every quote when the kill switch trips.
+//!
+//! ## Throttling
+//!
+//! With a [`RateLimiter`] attached, refreshes queued with
+//! [`QuoteManager::queue_refresh`] are sent by [`QuoteManager::flush`]
+//! within the venue's message limits. A flush first sends the cancels of
+//! every queued refresh, then the new orders, each in priority order
+//! (lowest first, e.g. distance from ATM), an amend counting as one
+//! replace message, and stops at the first refresh the limiter cannot
+//! cover so wings never overtake the money. Refreshes left over wait for
+//! the next flush, and a newer quote for the same contract replaces the
+//! queued one instead of adding messages. Direct refreshes and cancels,
+//! including the halt's mass cancel, are not throttled.
+//!
+//! ## Tags
+//!
+//! A quote refreshed with [`QuoteManager::refresh_tagged`] carries a tag,
+//! e.g. the strategy that generated it, and [`QuoteManager::cancel_tag`]
+//! pulls every quote with that tag at once. A plain refresh clears the tag.
+//!
+//! ## Self-trade prevention
+//!
+//! With a participant set, quote orders are added with
+//! [`OptionOrderBook::add_limit_order_as`], so a book's
+//! [`crate::orderbook::SelfTradePrevention`] policy keeps them from trading
+//! against our other orders under the same participant, such as hedges.
+
+use super::generated::GeneratedQuote;
+use super::rounding::{PriceConverter, RoundingContext};
+use crate::adapters::{MessageKind, RateLimiter};
+use crate::clock::Clock;
+use crate::error::{Error, Result};
+use crate::inventory::ChainCoordinates;
+use crate::orderbook::{ModifyOutcome, OptionOrderBook, UnderlyingOrderBookManager};
+use crate::risk::{HaltListener, KillSwitchTrip, RiskController};
+use orderbook_rs::{OrderId, Side, TimeInForce};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// One of our resting quote orders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiveOrder {
+    /// Order id in the book.
+    pub order_id: OrderId,
+    /// Limit price in ticks.
+    pub price: u128,
+    /// Quantity resting as of the last refresh.
+    pub quantity: u64,
+}
+
+/// Our resting quote in one contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiveQuote {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Resting bid, if any.
+    pub bid: Option<LiveOrder>,
+    /// Resting ask, if any.
+    pub ask: Option<LiveOrder>,
+    /// Time of the last refresh in milliseconds.
+    pub updated_at_ms: u64,
+    /// Tag of the quote, if any.
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+impl LiveQuote {
+    /// Returns the resting orders.
+    pub fn orders(&self) -> impl Iterator<Item = &LiveOrder> {
+        self.bid.iter().chain(self.ask.iter())
+    }
+}
+
+/// Counters of a [`QuoteManager`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuoteManagerStats {
+    /// Refresh calls.
+    pub refreshes: u64,
+    /// Orders placed.
+    pub placed: u64,
+    /// Orders kept on refresh because their side was unchanged.
+    pub kept: u64,
+    /// Orders reduced in size in place on refresh, keeping their priority.
+    pub amended: u64,
+    /// Orders cancelled.
+    pub cancelled: u64,
+    /// Mass cancels.
+    pub mass_cancels: u64,
+    /// Queued refreshes replaced by a newer quote before being sent.
+    pub coalesced: u64,
+}
+
+/// A refresh waiting for message capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingRefresh {
+    /// Quote to rest.
+    pub quote: GeneratedQuote,
+    /// Sending priority, lowest first.
+    pub priority: u64,
+}
+
+impl PendingRefresh {
+    /// Creates a pending refresh.
+    #[must_use]
+    pub const fn new(quote: GeneratedQuote, priority: u64) -> Self {
+        Self { quote, priority }
+    }
+
+    /// Creates a pending refresh prioritized by the distance of the
+    /// contract's strike from spot; symbols without a strike go last.
+    #[must_use]
+    pub fn by_moneyness(symbol: &str, quote: GeneratedQuote, spot: u64) -> Self {
+        let priority =
+            ChainCoordinates::parse(symbol).map_or(u64::MAX, |c| c.strike.abs_diff(spot));
+        Self::new(quote, priority)
+    }
+}
+
+/// Outcome of a [`QuoteManager::flush`].
+#[derive(Debug, Default)]
+pub struct FlushReport {
+    /// Quotes refreshed, in sending order.
+    pub refreshed: Vec<LiveQuote>,
+    /// Refreshes that failed, with their errors; their contracts are left
+    /// unquoted.
+    pub failed: Vec<(String, Error)>,
+    /// Refreshes still queued for lack of message capacity.
+    pub deferred: usize,
+}
+
+/// Mutable state of a [`QuoteManager`].
+#[derive(Debug, Default)]
+struct LifecycleState {
+    /// Live quotes by symbol.
+    quotes: BTreeMap<String, LiveQuote>,
+    /// Refreshes waiting for message capacity, by symbol.
+    pending: BTreeMap<String, PendingRefresh>,
+    /// Counters.
+    stats: QuoteManagerStats,
+}
+
+/// Owns our resting quotes and their orders.
+pub struct QuoteManager {
+    /// Books quotes are placed in.
+    manager: Arc<UnderlyingOrderBookManager>,
+    /// Rounds model prices to book ticks.
+    converter: PriceConverter,
+    /// Time source for quote timestamps.
+    clock: Arc<dyn Clock>,
+    /// Controller whose trading state gates refreshes.
+    risk: Option<Arc<RiskController>>,
+    /// Throttle of queued refreshes.
+    limiter: Option<Arc<RateLimiter>>,
+    /// Participant tag of our orders, for self-trade prevention.
+    participant: Option<String>,
+    /// Live quotes and counters; held across a whole refresh.
+    state: Mutex<LifecycleState>,
+}
+
+impl QuoteManager {
+    /// Creates a quote manager.
+    ///
+    /// # Arguments
+    ///
+    /// * `manager` - Books quotes are placed in
+    /// * `converter` - Rounds model prices to book ticks
+    /// * `clock` - Time source for quote timestamps
+    #[must_use]
+    pub fn new(
+        manager: Arc<UnderlyingOrderBookManager>,
+        converter: PriceConverter,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            manager,
+            converter,
+            clock,
+            risk: None,
+            limiter: None,
+            participant: None,
+            state: Mutex::new(LifecycleState::default()),
+        }
+    }
+
+    /// Attaches the risk controller whose trading state gates refreshes.
+    #[must_use]
+    pub fn with_risk(mut self, risk: Arc<RiskController>) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    /// Attaches the rate limiter queued refreshes are sent within.
+    #[must_use]
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Sets the participant tag our quote orders are added under.
+    #[must_use]
+    pub fn with_participant(mut self, participant: impl Into<String>) -> Self {
+        self.participant = Some(participant.into());
+        self
+    }
+
+    /// Replaces our quote in a contract.
+    ///
+    /// A side with zero size is pulled.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::RiskLimitBreached` if the trading state does not
+    /// permit quoting, `Error::ContractNotFound` if the symbol is not
+    /// listed, or `Error::OrderBookError` if the book rejects an order; the
+    /// contract is left without quotes in that case.
+    pub fn refresh(&self, symbol: &str, quote: &GeneratedQuote) -> Result<LiveQuote> {
+        self.refresh_with(symbol, quote, None)
+    }
+
+    /// Replaces our quote in a contract with a tagged quote.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`QuoteManager::refresh`].
+    pub fn refresh_tagged(
+        &self,
+        symbol: &str,
+        quote: &GeneratedQuote,
+        tag: &str,
+    ) -> Result<LiveQuote> {
+        self.refresh_with(symbol, quote, Some(tag))
+    }
+
+    fn refresh_with(
+        &self,
+        symbol: &str,
+        quote: &GeneratedQuote,
+        tag: Option<&str>,
+    ) -> Result<LiveQuote> {
+        if let Some(risk) = &self.risk
+            && !risk.state().permits_quoting()
+        {
+            self.cancel(symbol);
+            return Err(Error::risk_limit_breached(format!(
+                "quoting not permitted while {}",
+                risk.state()
+            )));
+        }
+        let book = self.manager.book(self.manager.contract_id(symbol)?)?;
+        let now_ms = self.clock.now_ms();
+        let target = self
+            .converter
+            .to_book_quote(*quote, RoundingContext::Quoting, now_ms);
+
+        let mut state = self.lock();
+        state.stats.refreshes += 1;
+        let current = state.quotes.remove(symbol);
+        let (old_bid, old_ask) = current.map_or((None, None), |q| {
+            (
+                q.bid.and_then(|order| resting(&book, order)),
+                q.ask.and_then(|order| resting(&book, order)),
+            )
+        });
+
+        let mut live = LiveQuote {
+            symbol: symbol.to_string(),
+            bid: None,
+            ask: None,
+            updated_at_ms: now_ms,
+            tag: tag.map(str::to_string),
+        };
+        let sides = [
+            (Side::Buy, old_bid, target.bid_price(), target.bid_size()),
+            (Side::Sell, old_ask, target.ask_price(), target.ask_size()),
+        ];
+        let mut failure = None;
+        for (side, old, price, quantity) in sides {
+            let wanted = price.filter(|_| quantity > 0);
+            let placed = match (side_action(old, wanted, quantity), old, wanted) {
+                (SideAction::Keep, Some(order), _) => {
+                    state.stats.kept += 1;
+                    Ok(Some(order))
+                }
+                (SideAction::Amend, Some(order), _) => amend(
+                    &book,
+                    side,
+                    order,
+                    quantity,
+                    self.participant.as_deref(),
+                    &mut state.stats,
+                )
+                .map(Some),
+                (_, old, wanted) => {
+                    if let Some(order) = old {
+                        cancel(&book, &order, &mut state.stats);
+                    }
+                    match wanted {
+                        Some(price) if failure.is_none() => place(
+                            &book,
+                            side,
+                            price,
+                            quantity,
+                            self.participant.as_deref(),
+                            &mut state.stats,
+                        )
+                        .map(Some),
+                        _ => Ok(None),
+                    }
+                }
+            };
+            match placed {
+                Ok(order) if side == Side::Buy => live.bid = order,
+                Ok(order) => live.ask = order,
+                Err(e) => failure = Some(e),
+            }
+        }
+
+        if let Some(e) = failure {
+            for order in live.orders() {
+                cancel(&book, order, &mut state.stats);
+            }
+            return Err(e);
+        }
+        if live.bid.is_some() || live.ask.is_some() {
+            state.quotes.insert(symbol.to_string(), live.clone());
+        }
+        Ok(live)
+    }
+
+    /// Queues a refresh for the next [`QuoteManager::flush`], replacing
+    /// any refresh already queued for the contract.
+    pub fn queue_refresh(&self, symbol: &str, refresh: PendingRefresh) {
+        let mut state = self.lock();
+        if state.pending.insert(symbol.to_string(), refresh).is_some() {
+            state.stats.coalesced += 1;
+        }
+    }
+
+    /// Returns the number of queued refreshes.
+    #[must_use]
+    pub fn pending_len(&self) -> usize {
+        self.lock().pending.len()
+    }
+
+    /// Sends queued refreshes within the rate limiter's capacity.
+    ///
+    /// Cancels of every queued refresh go first, then new orders, each in
+    /// priority order, stopping at the first refresh that does not fit.
+    pub fn flush(&self) -> FlushReport {
+        let mut queued: Vec<(String, PendingRefresh)> = self
+            .lock()
+            .pending
+            .iter()
+            .map(|(symbol, refresh)| (symbol.clone(), *refresh))
+            .collect();
+        queued.sort_by(|a, b| a.1.priority.cmp(&b.1.priority).then_with(|| a.0.cmp(&b.0)));
+        let mut report = FlushReport::default();
+
+        for (symbol, refresh) in &queued {
+            let (stale, _, _) = self.messages(symbol, &refresh.quote);
+            if stale.is_empty() {
+                continue;
+            }
+            if !self.acquire(MessageKind::Cancel, stale.len() as u64) {
+                break;
+            }
+            self.pull_sides(symbol, &stale);
+        }
+
+        for (symbol, refresh) in &queued {
+            let (stale, amends, new) = self.messages(symbol, &refresh.quote);
+            if !stale.is_empty()
+                || !self.acquire(MessageKind::Replace, amends)
+                || !self.acquire(MessageKind::NewOrder, new)
+            {
+                break;
+            }
+            {
+                let mut state = self.lock();
+                if state.pending.get(symbol) == Some(refresh) {
+                    state.pending.remove(symbol);
+                }
+            }
+            match self.refresh(symbol, &refresh.quote) {
+                Ok(live) => report.refreshed.push(live),
+                Err(e) => report.failed.push((symbol.clone(), e)),
+            }
+        }
+        report.deferred = self.pending_len();
+        report
+    }
+
+    /// Pulls our quote in a contract.
+    ///
+    /// Returns the number of orders cancelled.
+    pub fn cancel(&self, symbol: &str) -> usize {
+        let mut state = self.lock();
+        let Some(quote) = state.quotes.remove(symbol) else {
+            return 0;
+        };
+        self.cancel_quote(&quote, &mut state.stats)
+    }
+
+    /// Pulls every quote, e.g. on a risk halt.
+    ///
+    /// Returns the number of orders cancelled.
+    pub fn cancel_all(&self) -> usize {
+        let mut state = self.lock();
+        state.stats.mass_cancels += 1;
+        let quotes = std::mem::take(&mut state.quotes);
+        quotes
+            .values()
+            .map(|quote| self.cancel_quote(quote, &mut state.stats))
+            .sum()
+    }
+
+    /// Pulls every quote with a tag.
+    ///
+    /// Returns the number of orders cancelled.
+    pub fn cancel_tag(&self, tag: &str) -> usize {
+        let mut state = self.lock();
+        let state = &mut *state;
+        let (tagged, kept) = std::mem::take(&mut state.quotes)
+            .into_iter()
+            .partition(|(_, quote)| quote.tag.as_deref() == Some(tag));
+        state.quotes = kept;
+        let tagged: BTreeMap<String, LiveQuote> = tagged;
+        tagged
+            .values()
+            .map(|quote| self.cancel_quote(quote, &mut state.stats))
+            .sum()
+    }
+
+    /// Returns the symbols quoted with a tag.
+    #[must_use]
+    pub fn tagged(&self, tag: &str) -> Vec<String> {
+        self.lock()
+            .quotes
+            .values()
+            .filter(|quote| quote.tag.as_deref() == Some(tag))
+            .map(|quote| quote.symbol.clone())
+            .collect()
+    }
+
+    /// Returns our live quote in a contract.
+    #[must_use]
+    pub fn live(&self, symbol: &str) -> Option<LiveQuote> {
+        self.lock().quotes.get(symbol).cloned()
+    }
+
+    /// Returns the symbols we are quoting.
+    #[must_use]
+    pub fn symbols(&self) -> Vec<String> {
+        self.lock().quotes.keys().cloned().collect()
+    }
+
+    /// Returns the number of contracts we are quoting.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().quotes.len()
+    }
+
+    /// Returns true if we are quoting nothing.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().quotes.is_empty()
+    }
+
+    /// Returns the manager's counters.
+    #[must_use]
+    pub fn stats(&self) -> QuoteManagerStats {
+        self.lock().stats
+    }
+
+    /// Returns the sides of our quote in a contract a refresh to `quote`
+    /// would cancel, and the number of orders it would amend and place.
+    fn messages(&self, symbol: &str, quote: &GeneratedQuote) -> (Vec<Side>, u64, u64) {
+        let target =
+            self.converter
+                .to_book_quote(*quote, RoundingContext::Quoting, self.clock.now_ms());
+        let book = self
+            .manager
+            .contract_id(symbol)
+            .and_then(|id| self.manager.book(id))
+            .ok();
+        let state = self.lock();
+        let live = state.quotes.get(symbol);
+        let current = |order: Option<LiveOrder>| {
+            order.and_then(|order| book.as_ref().and_then(|book| resting(book, order)))
+        };
+        let sides = [
+            (
+                Side::Buy,
+                current(live.and_then(|q| q.bid)),
+                target.bid_price(),
+                target.bid_size(),
+            ),
+            (
+                Side::Sell,
+                current(live.and_then(|q| q.ask)),
+                target.ask_price(),
+                target.ask_size(),
+            ),
+        ];
+        let mut stale = Vec::new();
+        let (mut amends, mut new) = (0, 0);
+        for (side, old, price, quantity) in sides {
+            let wanted = price.filter(|_| quantity > 0);
+            match side_action(old, wanted, quantity) {
+                SideAction::Keep => {}
+                SideAction::Amend => amends += 1,
+                SideAction::Replace => {
+                    if old.is_some() {
+                        stale.push(side);
+                    }
+                    if wanted.is_some() {
+                        new += 1;
+                    }
+                }
+            }
+        }
+        (stale, amends, new)
+    }
+
+    /// Cancels some sides of our quote in a contract.
+    fn pull_sides(&self, symbol: &str, sides: &[Side]) {
+        let Ok(book) = self
+            .manager
+            .contract_id(symbol)
+            .and_then(|id| self.manager.book(id))
+        else {
+            return;
+        };
+        let mut state = self.lock();
+        let state = &mut *state;
+        let Some(live) = state.quotes.get_mut(symbol) else {
+            return;
+        };
+        for side in sides {
+            let order = match side {
+                Side::Buy => live.bid.take(),
+                Side::Sell => live.ask.take(),
+            };
+            if let Some(order) = order {
+                cancel(&book, &order, &mut state.stats);
+            }
+        }
+        if live.bid.is_none() && live.ask.is_none() {
+            state.quotes.remove(symbol);
+        }
+    }
+
+    /// Takes message capacity from the rate limiter, if any.
+    fn acquire(&self, kind: MessageKind, count: u64) -> bool {
+        self.limiter
+            .as_ref()
+            .is_none_or(|limiter| limiter.try_acquire(kind, count))
+    }
+
+    /// Cancels the orders of a quote whose book may have been delisted.
+    fn cancel_quote(&self, quote: &LiveQuote, stats: &mut QuoteManagerStats) -> usize {
+        let Ok(book) = self
+            .manager
+            .contract_id(&quote.symbol)
+            .and_then(|id| self.manager.book(id))
+        else {
+            return 0;
+        };
+        quote
+            .orders()
+            .filter(|order| cancel(&book, order, stats))
+            .count()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LifecycleState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl HaltListener for QuoteManager {
+    fn on_halt(&self, _trip: &KillSwitchTrip) {
+        self.cancel_all();
+    }
+}
+
+/// Places one quote order.
+fn place(
+    book: &OptionOrderBook,
+    side: Side,
+    price: u128,
+    quantity: u64,
+    participant: Option<&str>,
+    stats: &mut QuoteManagerStats,
+) -> Result<LiveOrder> {
+    let order_id = OrderId::new();
+    match participant {
+        Some(participant) => book.add_limit_order_as(
+            order_id,
+            side,
+            price,
+            quantity,
+            TimeInForce::Gtc,
+            participant,
+        )?,
+        None => book.add_limit_order(order_id, side, price, quantity)?,
+    }
+    stats.placed += 1;
+    Ok(LiveOrder {
+        order_id,
+        price,
+        quantity,
+    })
+}
+
+/// Returns a quote order as it rests in the book now, with its remaining
+/// quantity, or `None` if it has been filled or cancelled.
+fn resting(book: &OptionOrderBook, order: LiveOrder) -> Option<LiveOrder> {
+    book.inner()
+        .get_order(order.order_id)
+        .map(|resting| LiveOrder {
+            quantity: resting.visible_quantity() + resting.hidden_quantity(),
+            ..order
+        })
+}
+
+/// What a refresh does to one side of a quote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SideAction {
+    /// The resting order already matches.
+    Keep,
+    /// The resting order is reduced in size at the same price.
+    Amend,
+    /// The resting order, if any, is cancelled and the wanted one, if any,
+    /// placed.
+    Replace,
+}
+
+/// Returns what a refresh does to a side resting `old` that should rest
+/// `quantity` at `wanted`.
+fn side_action(old: Option<LiveOrder>, wanted: Option<u128>, quantity: u64) -> SideAction {
+    match (old, wanted) {
+        (Some(order), Some(price)) if order.price == price && order.quantity == quantity => {
+            SideAction::Keep
+        }
+        (Some(order), Some(price)) if order.price == price && quantity < order.quantity => {
+            SideAction::Amend
+        }
+        _ => SideAction::Replace,
+    }
+}
+
+/// Reduces a quote order's size in place through the book's modify.
+///
+/// An order no longer resting, e.g. filled since the last refresh, is
+/// placed afresh; one re-added by the book because a partial fill left it
+/// below the new size counts as a cancel and a placement.
+fn amend(
+    book: &OptionOrderBook,
+    side: Side,
+    order: LiveOrder,
+    quantity: u64,
+    participant: Option<&str>,
+    stats: &mut QuoteManagerStats,
+) -> Result<LiveOrder> {
+    match book.modify_order(order.order_id, order.price, quantity)? {
+        ModifyOutcome::Amended { quantity } => {
+            stats.amended += 1;
+            Ok(LiveOrder { quantity, ..order })
+        }
+        ModifyOutcome::Unchanged => {
+            stats.kept += 1;
+            Ok(LiveOrder { quantity, ..order })
+        }
+        ModifyOutcome::Replaced { price, quantity } => {
+            stats.cancelled += 1;
+            stats.placed += 1;
+            Ok(LiveOrder {
+                order_id: order.order_id,
+                price,
+                quantity,
+            })
+        }
+        ModifyOutcome::NotFound | ModifyOutcome::Cancelled => {
+            place(book, side, order.price, quantity, participant, stats)
+        }
+    }
+}
+
+/// Cancels one quote order, returning whether it was still resting.
+fn cancel(book: &OptionOrderBook, order: &LiveOrder, stats: &mut QuoteManagerStats) -> bool {
+    let cancelled = book.cancel_order(order.order_id).unwrap_or(false);
+    if cancelled {
+        stats.cancelled += 1;
+    }
+    cancelled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::RateLimit;
+    use crate::clock::ManualClock;
+    use crate::quoting::RoundingPolicy;
+    use optionstratlib::ExpirationDate;
+    use optionstratlib::prelude::pos_or_panic;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn setup() -> (Arc<UnderlyingOrderBookManager>, QuoteManager, String) {
+        let manager = Arc::new(UnderlyingOrderBookManager::new());
+        let strike = manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(30.0)))
+            .get_or_create_strike(50000);
+        let quotes = QuoteManager::new(
+            Arc::clone(&manager),
+            PriceConverter::new(dec!(0.01), RoundingPolicy::default()).unwrap(),
+            Arc::new(ManualClock::new(1_000)),
+        );
+        (manager, quotes, strike.call().symbol().to_string())
+    }
+
+    fn quote(bid: Decimal, ask: Decimal) -> GeneratedQuote {
+        GeneratedQuote {
+            theo: (bid + ask) / Decimal::TWO,
+            reservation_price: (bid + ask) / Decimal::TWO,
+            bid_price: bid,
+            ask_price: ask,
+            bid_size: 10,
+            ask_size: 10,
+        }
+    }
+
+    #[test]
+    fn test_refresh_replaces_only_changed_sides() {
+        let (manager, quotes, symbol) = setup();
+        let book = manager.book(manager.contract_id(&symbol).unwrap()).unwrap();
+
+        let first = quotes
+            .refresh(&symbol, &quote(dec!(4.90), dec!(5.10)))
+            .unwrap();
+        assert_eq!(first.bid.unwrap().price, 490);
+        assert_eq!(book.order_count(), 2);
+
+        let second = quotes
+            .refresh(&symbol, &quote(dec!(4.95), dec!(5.10)))
+            .unwrap();
+        assert_ne!(second.bid.unwrap().order_id, first.bid.unwrap().order_id);
+        assert_eq!(second.ask, first.ask);
+        assert_eq!(book.order_count(), 2);
+        assert_eq!(book.best_bid(), Some(495));
+
+        let stats = quotes.stats();
+        assert_eq!((stats.placed, stats.kept, stats.cancelled), (3, 1, 1));
+        assert_eq!(quotes.live(&symbol), Some(second));
+    }
+
+    #[test]
+    fn test_size_reduction_amends_in_place() {
+        let (manager, quotes, symbol) = setup();
+        let book = manager.book(manager.contract_id(&symbol).unwrap()).unwrap();
+        let first = quotes
+            .refresh(&symbol, &quote(dec!(4.90), dec!(5.10)))
+            .unwrap();
+
+        let mut smaller = quote(dec!(4.90), dec!(5.10));
+        smaller.bid_size = 4;
+        smaller.ask_size = 12;
+        let second = quotes.refresh(&symbol, &smaller).unwrap();
+        let bid = second.bid.unwrap();
+        // The reduced bid keeps its order; the increased ask is replaced.
+        assert_eq!(bid.order_id, first.bid.unwrap().order_id);
+        assert_eq!(bid.quantity, 4);
+        assert_eq!(book.bid_depth_at_price(490), 4);
+        assert_ne!(second.ask.unwrap().order_id, first.ask.unwrap().order_id);
+
+        let stats = quotes.stats();
+        assert_eq!((stats.amended, stats.cancelled, stats.placed), (1, 1, 3));
+    }
+
+    #[test]
+    fn test_refresh_requotes_filled_sides() {
+        let (manager, quotes, symbol) = setup();
+        let book = manager.book(manager.contract_id(&symbol).unwrap()).unwrap();
+        let first = quotes
+            .refresh(&symbol, &quote(dec!(4.90), dec!(5.10)))
+            .unwrap();
+
+        // The ask is filled in full and the bid in part.
+        book.add_limit_order_with_tif(OrderId::new(), Side::Buy, 510, 10, TimeInForce::Ioc)
+            .unwrap();
+        book.add_limit_order_with_tif(OrderId::new(), Side::Sell, 490, 3, TimeInForce::Ioc)
+            .unwrap();
+        assert_eq!(book.best_ask(), None);
+
+        let second = quotes
+            .refresh(&symbol, &quote(dec!(4.90), dec!(5.10)))
+            .unwrap();
+        let (bid, ask) = (second.bid.unwrap(), second.ask.unwrap());
+        assert_ne!(ask.order_id, first.ask.unwrap().order_id);
+        assert_eq!(ask.quantity, 10);
+        assert_eq!(book.ask_depth_at_price(510), 10);
+        assert_ne!(bid.order_id, first.bid.unwrap().order_id);
+        assert_eq!(bid.quantity, 10);
+        assert_eq!(book.bid_depth_at_price(490), 10);
+
+        let stats = quotes.stats();
+        assert_eq!((stats.kept, stats.cancelled, stats.placed), (0, 1, 4));
+    }
+
+    #[test]
+    fn test_cancel_all_pulls_every_order() {
+        let (manager, quotes, symbol) = setup();
+        let book = manager.book(manager.contract_id(&symbol).unwrap()).unwrap();
+        quotes
+            .refresh(&symbol, &quote(dec!(4.90), dec!(5.10)))
+            .unwrap();
+
+        // A one-sided refresh pulls the other side.
+        let mut bid_only = quote(dec!(4.90), dec!(5.10));
+        bid_only.ask_size = 0;
+        let live = quotes.refresh(&symbol, &bid_only).unwrap();
+        assert!(live.ask.is_none());
+        assert_eq!(book.order_count(), 1);
+
+        assert_eq!(quotes.cancel_all(), 1);
+        assert!(quotes.is_empty());
+        assert!(book.is_empty());
+        assert!(quotes.refresh("UNKNOWN", &bid_only).is_err());
+    }
+
+    #[test]
+    fn test_flush_throttles_by_priority_and_coalesces() {
+        let manager = Arc::new(UnderlyingOrderBookManager::new());
+        let expiration = manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(30.0)));
+        let atm = expiration
+            .get_or_create_strike(50000)
+            .call()
+            .symbol()
+            .to_string();
+        let wing = expiration
+            .get_or_create_strike(60000)
+            .call()
+            .symbol()
+            .to_string();
+        let clock = Arc::new(ManualClock::new(0));
+        let limiter = Arc::new(
+            RateLimiter::new(clock.clone())
+                .with_limit(MessageKind::NewOrder, RateLimit::new(2, 2).unwrap()),
+        );
+        let quotes = QuoteManager::new(
+            Arc::clone(&manager),
+            PriceConverter::new(dec!(0.01), RoundingPolicy::default()).unwrap(),
+            clock.clone(),
+        )
+        .with_rate_limiter(limiter);
+
+        quotes.queue_refresh(
+            &wing,
+            PendingRefresh::by_moneyness(&wing, quote(dec!(1.00), dec!(1.20)), 50000),
+        );
+        quotes.queue_refresh(
+            &atm,
+            PendingRefresh::by_moneyness(&atm, quote(dec!(4.90), dec!(5.10)), 50000),
+        );
+        let report = quotes.flush();
+        assert_eq!(report.refreshed.len(), 1);
+        assert_eq!(report.refreshed[0].symbol, atm);
+        assert_eq!(report.deferred, 1);
+
+        // A newer wing quote replaces the queued one.
+        quotes.queue_refresh(
+            &wing,
+            PendingRefresh::by_moneyness(&wing, quote(dec!(1.05), dec!(1.25)), 50000),
+        );
+        assert_eq!(quotes.stats().coalesced, 1);
+        assert_eq!(quotes.flush().deferred, 1);
+
+        clock.advance(1_000);
+        let report = quotes.flush();
+        assert_eq!(report.deferred, 0);
+        assert_eq!(report.refreshed[0].bid.unwrap().price, 105);
+    }
+
+    #[test]
+    fn test_flush_cancels_before_placing() {
+        let (manager, quotes, symbol) = setup();
+        let book = manager.book(manager.contract_id(&symbol).unwrap()).unwrap();
+        let clock = Arc::new(ManualClock::new(0));
+        let quotes = quotes.with_rate_limiter(Arc::new(
+            RateLimiter::new(clock)
+                .with_limit(MessageKind::NewOrder, RateLimit::new(1, 1).unwrap()),
+        ));
+        quotes
+            .refresh(&symbol, &quote(dec!(4.90), dec!(5.10)))
+            .unwrap();
+
+        // Both sides move but only one new order fits: the stale orders are
+        // pulled and the contract waits unquoted for capacity.
+        quotes.queue_refresh(
+            &symbol,
+            PendingRefresh::new(quote(dec!(4.80), dec!(5.00)), 0),
+        );
+        let report = quotes.flush();
+        assert!(report.refreshed.is_empty());
+        assert_eq!(report.deferred, 1);
+        assert!(book.is_empty());
+        assert!(quotes.live(&symbol).is_none());
+    }
+
+    #[test]
+    fn test_kill_switch_pulls_quotes_and_blocks_refresh() {
+        let (manager, quotes, symbol) = setup();
+        let risk = Arc::new(RiskController::new(crate::risk::RiskLimits::default()).unwrap());
+        let quotes = Arc::new(quotes.with_risk(Arc::clone(&risk)));
+        let listener: Arc<dyn HaltListener> = quotes.clone();
+        risk.add_halt_listener(&listener);
+        quotes
+            .refresh(&symbol, &quote(dec!(4.90), dec!(5.10)))
+            .unwrap();
+
+        risk.trip("manual");
+        let book = manager.book(manager.contract_id(&symbol).unwrap()).unwrap();
+        assert!(book.is_empty());
+        assert!(quotes.is_empty());
+        assert!(
+            quotes
+                .refresh(&symbol, &quote(dec!(4.90), dec!(5.10)))
+                .is_err()
+        );
+
+        risk.reset_kill_switch();
+        assert!(
+            quotes
+                .refresh(&symbol, &quote(dec!(4.90), dec!(5.10)))
+                .is_ok()
+        );
+    }
+}
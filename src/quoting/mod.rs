@@ -0,0 +1,98 @@
+//! Quoting module.
+//!
+//! This module provides quote generation for option contracts: the
+//! Avellaneda-Stoikov [`SpreadCalculator`] for a single contract and the
+//! [`QuoteEngine`], which quotes call/put pairs consistently with put-call
+//! parity.
+//!
+//! ## Components
+//!
+//! - [`QuoteParams`]: Per-contract model inputs (theo, inventory, volatility, intensity)
+//! - [`SpreadCalculator`]: Reservation price and optimal spread for one contract
+//! - [`IntensityEstimator`]: Online per-contract arrival intensity `k` from fills and trades
+//! - [`GeneratedQuote`]: Model output in price units, convertible to a book [`crate::orderbook::Quote`]
+//! - [`QuoteEngine`]: Per-strike quote generation with a parity-consistency pass
+//! - [`QuoteManager`]: Our resting quotes per contract, refreshed side by side, throttled by priority when queued, and mass-cancelled on halt or by tag
+//! - [`ImpliedQuoter`]: Parity-implied quotes on the other leg of a strike, net of hedge cost, placed and pulled under a tag
+//! - [`ChainQuoter`]: Whole-chain quotes from the volatility surface with per-strike skew, per-expiry widening and opt-in fast pricing
+//! - [`SmileAdjustedQuoter`]: Out-of-the-money spreads widened by local smile slope and vega
+//! - [`ComboQuoter`]: Listed straddles, strangles and verticals quoted from leg theos and combined vega/gamma, filled on the legs
+//! - [`VerticalQuoter`]: Bull call and bear put spreads quoted as packages within payoff bounds, sized by max package loss
+//! - [`PriceConverter`]: Decimal-to-tick conversion under a per-context [`RoundingPolicy`]
+//! - [`TickSchedule`]: Price-banded tick tables, per underlying through a [`TickScheduleRegistry`]
+//! - [`QuoteSanity`]: No-arbitrage price bounds enforced at quote finalization, with violation counters
+//! - [`DegradationMonitor`]: Widen, imply, shrink or suspend quotes per expiry when pricing inputs go stale
+//! - [`QuotingPauses`]: Operator pauses per underlying, expiry, strike range or contract, with reason codes
+//! - [`ExpiryWindow`]: Per-venue halt, settlement-only and settlement phases near expiry
+//! - [`StressSizer`]: Per-contract quote size caps from worst stress-scenario loss
+//! - [`StrikeBand`]: Quoted strikes kept centered on ATM, with hysteresis at the edges
+//! - [`LimitProjection`]: Quote sizes shrunk so worst-case fills stay within Greek limits
+//! - [`TieredQuoter`]: Pre-computed edge tiers per contract, displayed by competition
+//! - [`RequoteDecider`]: Keeps queue priority unless a new price is worth losing it
+//! - [`RequoteGate`]: Per-contract requote trigger at `max(tick, vega * vol tolerance)` of theo change
+//! - [`FlowTracker`]: Net customer option flow per strike, published per interval
+//! - [`QuoteUptimeTracker`]: Quoting presence versus exchange market-maker [`ProgramSpec`]s
+
+mod chain;
+mod combo;
+mod coverage;
+mod degradation;
+mod engine;
+mod expiry_window;
+mod flow;
+mod generated;
+mod implied;
+mod intensity;
+mod lifecycle;
+mod params;
+mod pause;
+mod projection;
+mod requote;
+mod rounding;
+mod sanity;
+mod smile;
+mod spread;
+mod stress_size;
+mod strike_band;
+mod tick_schedule;
+mod tiers;
+mod vertical;
+
+pub use chain::ChainQuoter;
+pub use combo::{ComboInstrument, ComboLeg, ComboQuoter, ComboValuation};
+pub use coverage::{
+    ContractCoverage, CoverageReport, CoverageStats, ProgramSpec, QuoteUptimeTracker,
+};
+pub use degradation::{
+    DegradationAction, DegradationConfig, DegradationMonitor, DegradationPolicy, DegradationReport,
+    DegradationState, PricingInput, neighbor_implied_quote, parity_implied_quote,
+};
+pub use engine::{
+    ForwardInputs, ParityAdjustment, ParityConfig, QuoteEngine, StrikeQuoteRequest, StrikeQuotes,
+};
+pub use expiry_window::{ExpiryPhase, ExpiryWindow, VenueExpiryWindows};
+pub use flow::{FlowReport, FlowTracker, FlowTrade, StrikeFlow};
+pub use generated::GeneratedQuote;
+pub use implied::{ImpliedQuoteConfig, ImpliedQuoter};
+pub use intensity::{IntensityConfig, IntensityEstimate, IntensityEstimator};
+pub use lifecycle::{
+    FlushReport, LiveOrder, LiveQuote, PendingRefresh, QuoteManager, QuoteManagerStats,
+};
+pub use params::QuoteParams;
+pub use pause::{ContractKey, PauseReason, PauseScope, QuotingPause, QuotingPauses};
+pub use projection::{LimitProjection, QuoteExposure, SizeAdjustment};
+pub use requote::{
+    RequoteAction, RequoteConfig, RequoteDecider, RequoteDecision, RequoteGate, RequoteGateStats,
+    RequoteReason, RequoteStats, RequoteTrigger,
+};
+pub use rounding::{PriceConverter, RoundingContext, RoundingMode, RoundingPolicy};
+pub use sanity::{
+    QuoteSanity, SanityAction, SanityConfig, SanityOutcome, SanityStats, SanityViolation,
+};
+pub use smile::{SmileAdjustedQuoter, SmileSkewConfig};
+pub use spread::SpreadCalculator;
+pub use stress_size::{StressCap, StressScenario, StressSizeConfig, StressSizer};
+pub use strike_band::{BandUpdate, StrikeBand, StrikeBandConfig};
+pub use tick_schedule::{TickBand, TickSchedule, TickScheduleRegistry};
+pub use tiers::{EdgeTierConfig, TierSelection, TieredQuote, TieredQuoter};
+pub use vertical::VerticalQuoter;
@@ -0,0 +1,141 @@
+//! Quote parameters module.
+//!
+//! This module provides [`QuoteParams`], the per-contract inputs of the
+//! Avellaneda-Stoikov spread model.
+
+use crate::error::{Error, Result};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Inputs for quoting a single contract.
+///
+/// `volatility` is the volatility of the contract price in price units per
+/// square root of a year, and `time_horizon` is in years.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuoteParams {
+    /// Theoretical value of the contract.
+    pub theo: Decimal,
+    /// Signed inventory in contracts.
+    pub inventory: Decimal,
+    /// Price volatility of the contract.
+    pub volatility: Decimal,
+    /// Risk aversion coefficient (gamma).
+    pub risk_aversion: Decimal,
+    /// Remaining quoting horizon in years.
+    pub time_horizon: Decimal,
+    /// Order arrival intensity decay (k).
+    pub arrival_intensity: Decimal,
+    /// Largest bid size allowed, e.g. by a stress-loss budget.
+    #[serde(default)]
+    pub max_bid_size: Option<u64>,
+    /// Largest ask size allowed, e.g. by a stress-loss budget.
+    #[serde(default)]
+    pub max_ask_size: Option<u64>,
+}
+
+impl QuoteParams {
+    /// Creates quote parameters with flat inventory and default model
+    /// coefficients.
+    ///
+    /// # Arguments
+    ///
+    /// * `theo` - Theoretical value of the contract
+    /// * `volatility` - Price volatility of the contract
+    #[must_use]
+    pub fn new(theo: Decimal, volatility: Decimal) -> Self {
+        Self {
+            theo,
+            inventory: Decimal::ZERO,
+            volatility,
+            risk_aversion: dec!(0.1),
+            time_horizon: dec!(0.01),
+            arrival_intensity: dec!(1.5),
+            max_bid_size: None,
+            max_ask_size: None,
+        }
+    }
+
+    /// Sets the signed inventory.
+    #[must_use]
+    pub const fn with_inventory(mut self, inventory: Decimal) -> Self {
+        self.inventory = inventory;
+        self
+    }
+
+    /// Sets the risk aversion coefficient.
+    #[must_use]
+    pub const fn with_risk_aversion(mut self, risk_aversion: Decimal) -> Self {
+        self.risk_aversion = risk_aversion;
+        self
+    }
+
+    /// Sets the quoting horizon in years.
+    #[must_use]
+    pub const fn with_time_horizon(mut self, time_horizon: Decimal) -> Self {
+        self.time_horizon = time_horizon;
+        self
+    }
+
+    /// Sets the order arrival intensity.
+    #[must_use]
+    pub const fn with_arrival_intensity(mut self, arrival_intensity: Decimal) -> Self {
+        self.arrival_intensity = arrival_intensity;
+        self
+    }
+
+    /// Caps the quoted sizes; `None` leaves a side uncapped.
+    #[must_use]
+    pub const fn with_size_cap(
+        mut self,
+        max_bid_size: Option<u64>,
+        max_ask_size: Option<u64>,
+    ) -> Self {
+        self.max_bid_size = max_bid_size;
+        self.max_ask_size = max_ask_size;
+        self
+    }
+
+    /// Validates the parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::QuotingError` if the theo, volatility or horizon is
+    /// negative, or the risk aversion or arrival intensity is not positive.
+    pub fn validate(&self) -> Result<()> {
+        if self.theo < Decimal::ZERO {
+            return Err(Error::quoting("theo must be non-negative"));
+        }
+        if self.volatility < Decimal::ZERO {
+            return Err(Error::quoting("volatility must be non-negative"));
+        }
+        if self.time_horizon < Decimal::ZERO {
+            return Err(Error::quoting("time horizon must be non-negative"));
+        }
+        if self.risk_aversion <= Decimal::ZERO {
+            return Err(Error::quoting("risk aversion must be positive"));
+        }
+        if self.arrival_intensity <= Decimal::ZERO {
+            return Err(Error::quoting("arrival intensity must be positive"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_are_valid() {
+        assert!(QuoteParams::new(dec!(5), dec!(2)).validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_coefficients() {
+        let params = QuoteParams::new(dec!(5), dec!(2));
+        assert!(params.with_risk_aversion(Decimal::ZERO).validate().is_err());
+        assert!(params.with_arrival_intensity(dec!(-1)).validate().is_err());
+        assert!(QuoteParams::new(dec!(-1), dec!(2)).validate().is_err());
+    }
+}
@@ -0,0 +1,474 @@
+//! Quoting pause module.
+//!
+//! This module provides [`QuotingPauses`], the operator registry of
+//! quoting pauses. A pause targets every contract, an underlying, one
+//! expiry, a strike range within an expiry, or a single contract, carries
+//! a reason code and may lapse on its own. The
+//! [`QuoteEngine`](super::QuoteEngine) consults the registry before quoting
+//! a contract, and the risk dashboard lists the active pauses.
+//!
+//! Scopes are matched against contract symbols of the form
+//! `UNDERLYING-YYYYMMDD-STRIKE-C` or `-P`.
+
+use crate::clock::Clock;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Contract identity parsed from its symbol.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractKey {
+    /// Full contract symbol.
+    pub symbol: String,
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Expiry as formatted in the symbol.
+    pub expiry: String,
+    /// Strike price.
+    pub strike: u64,
+}
+
+impl ContractKey {
+    /// Parses a contract symbol.
+    ///
+    /// Returns `None` if the symbol does not follow the
+    /// `UNDERLYING-EXPIRY-STRIKE-STYLE` form.
+    #[must_use]
+    pub fn parse(symbol: &str) -> Option<Self> {
+        let mut parts = symbol.rsplitn(4, '-');
+        let style = parts.next()?;
+        let strike = parts.next()?.parse().ok()?;
+        let expiry = parts.next()?;
+        let underlying = parts.next()?;
+        if !matches!(style, "C" | "P") || expiry.is_empty() || underlying.is_empty() {
+            return None;
+        }
+        Some(Self {
+            symbol: symbol.to_string(),
+            underlying: underlying.to_string(),
+            expiry: expiry.to_string(),
+            strike,
+        })
+    }
+}
+
+/// What a pause applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PauseScope {
+    /// Every contract, the global quoting switch.
+    All,
+    /// Every contract of an underlying.
+    Underlying(String),
+    /// Every contract of one expiry.
+    Expiry {
+        /// Underlying symbol.
+        underlying: String,
+        /// Expiry as formatted in contract symbols.
+        expiry: String,
+    },
+    /// Strikes within an inclusive range of one expiry.
+    StrikeRange {
+        /// Underlying symbol.
+        underlying: String,
+        /// Expiry as formatted in contract symbols.
+        expiry: String,
+        /// Lowest strike paused.
+        low: u64,
+        /// Highest strike paused.
+        high: u64,
+    },
+    /// A single contract.
+    Contract(String),
+}
+
+impl PauseScope {
+    /// Returns true if the scope covers the contract.
+    #[must_use]
+    pub fn covers(&self, contract: &ContractKey) -> bool {
+        match self {
+            Self::All => true,
+            Self::Underlying(underlying) => contract.underlying == *underlying,
+            Self::Expiry { underlying, expiry } => {
+                contract.underlying == *underlying && contract.expiry == *expiry
+            }
+            Self::StrikeRange {
+                underlying,
+                expiry,
+                low,
+                high,
+            } => {
+                contract.underlying == *underlying
+                    && contract.expiry == *expiry
+                    && (*low..=*high).contains(&contract.strike)
+            }
+            Self::Contract(symbol) => contract.symbol == *symbol,
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let blank = match self {
+            Self::All => false,
+            Self::Underlying(name) | Self::Contract(name) => name.is_empty(),
+            Self::Expiry { underlying, expiry } => underlying.is_empty() || expiry.is_empty(),
+            Self::StrikeRange {
+                underlying,
+                expiry,
+                low,
+                high,
+            } => {
+                if low > high {
+                    return Err(Error::validation(format!(
+                        "invalid strike range [{low}, {high}]"
+                    )));
+                }
+                underlying.is_empty() || expiry.is_empty()
+            }
+        };
+        if blank {
+            return Err(Error::validation("pause scope must name its target"));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for PauseScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::All => write!(f, "all"),
+            Self::Underlying(underlying) => write!(f, "{underlying}"),
+            Self::Expiry { underlying, expiry } => write!(f, "{underlying}-{expiry}"),
+            Self::StrikeRange {
+                underlying,
+                expiry,
+                low,
+                high,
+            } => write!(f, "{underlying}-{expiry}-[{low}..{high}]"),
+            Self::Contract(symbol) => write!(f, "{symbol}"),
+        }
+    }
+}
+
+/// Reason code of a pause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PauseReason {
+    /// Discretionary operator decision.
+    Operator,
+    /// Contracts close to expiry.
+    Expiring,
+    /// Scheduled or unfolding market event.
+    MarketEvent,
+    /// Unreliable pricing or market data.
+    DataIssue,
+    /// Pending risk review.
+    RiskReview,
+}
+
+impl PauseReason {
+    /// Returns the snake_case reason code.
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::Operator => "operator",
+            Self::Expiring => "expiring",
+            Self::MarketEvent => "market_event",
+            Self::DataIssue => "data_issue",
+            Self::RiskReview => "risk_review",
+        }
+    }
+}
+
+impl std::fmt::Display for PauseReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl std::str::FromStr for PauseReason {
+    type Err = Error;
+
+    fn from_str(code: &str) -> Result<Self> {
+        [
+            Self::Operator,
+            Self::Expiring,
+            Self::MarketEvent,
+            Self::DataIssue,
+            Self::RiskReview,
+        ]
+        .into_iter()
+        .find(|r| r.code() == code)
+        .ok_or_else(|| Error::validation(format!("unknown pause reason '{code}'")))
+    }
+}
+
+/// An active quoting pause.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotingPause {
+    /// Registry-assigned identifier.
+    pub id: u64,
+    /// What the pause applies to.
+    pub scope: PauseScope,
+    /// Reason code.
+    pub reason: PauseReason,
+    /// Free-text note.
+    pub note: String,
+    /// Who paused.
+    pub paused_by: String,
+    /// Creation time in milliseconds.
+    pub created_ms: u64,
+    /// Time the pause lapses, or `None` until resumed.
+    pub expires_ms: Option<u64>,
+}
+
+impl QuotingPause {
+    /// Returns true if the pause has lapsed at `now_ms`.
+    #[must_use]
+    pub fn is_expired(&self, now_ms: u64) -> bool {
+        self.expires_ms.is_some_and(|e| now_ms >= e)
+    }
+}
+
+/// Pauses and the next identifier, guarded together.
+#[derive(Debug, Default)]
+struct Pauses {
+    /// Active pauses in creation order.
+    active: Vec<QuotingPause>,
+    /// Next pause identifier.
+    next_id: u64,
+}
+
+/// Registry of quoting pauses.
+pub struct QuotingPauses {
+    /// Time source for creation and expiry.
+    clock: Arc<dyn Clock>,
+    /// Active pauses.
+    pauses: Mutex<Pauses>,
+}
+
+impl QuotingPauses {
+    /// Creates a registry with no pause.
+    #[must_use]
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            pauses: Mutex::new(Pauses::default()),
+        }
+    }
+
+    /// Pauses quoting in a scope.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - What to pause
+    /// * `reason` - Reason code
+    /// * `note` - Free-text note
+    /// * `paused_by` - Who pauses
+    /// * `ttl_ms` - Time until the pause lapses, or `None` until resumed
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the scope names no target, the
+    /// strike range is inverted, or the TTL is zero.
+    pub fn pause(
+        &self,
+        scope: PauseScope,
+        reason: PauseReason,
+        note: impl Into<String>,
+        paused_by: impl Into<String>,
+        ttl_ms: Option<u64>,
+    ) -> Result<QuotingPause> {
+        scope.validate()?;
+        if ttl_ms == Some(0) {
+            return Err(Error::validation("pause TTL must be positive"));
+        }
+        let now_ms = self.clock.now_ms();
+        let mut pauses = self.lock();
+        let pause = QuotingPause {
+            id: pauses.next_id,
+            scope,
+            reason,
+            note: note.into(),
+            paused_by: paused_by.into(),
+            created_ms: now_ms,
+            expires_ms: ttl_ms.map(|ttl| now_ms.saturating_add(ttl)),
+        };
+        pauses.next_id += 1;
+        pauses.active.push(pause.clone());
+        Ok(pause)
+    }
+
+    /// Resumes a pause by identifier.
+    ///
+    /// Returns the removed pause, or `None` if it is not active.
+    pub fn resume(&self, id: u64) -> Option<QuotingPause> {
+        let mut pauses = self.lock();
+        let index = pauses.active.iter().position(|p| p.id == id)?;
+        Some(pauses.active.remove(index))
+    }
+
+    /// Resumes every pause with exactly this scope.
+    ///
+    /// Returns the number of pauses removed.
+    pub fn resume_scope(&self, scope: &PauseScope) -> usize {
+        let mut pauses = self.lock();
+        let before = pauses.active.len();
+        pauses.active.retain(|p| p.scope != *scope);
+        before - pauses.active.len()
+    }
+
+    /// Returns the active pauses in creation order.
+    #[must_use]
+    pub fn active(&self) -> Vec<QuotingPause> {
+        self.lock_current().active.clone()
+    }
+
+    /// Returns the oldest active pause covering a contract.
+    ///
+    /// A symbol that cannot be parsed is matched by contract scopes only.
+    #[must_use]
+    pub fn pause_for(&self, symbol: &str) -> Option<QuotingPause> {
+        let contract = ContractKey::parse(symbol).unwrap_or_else(|| ContractKey {
+            symbol: symbol.to_string(),
+            underlying: String::new(),
+            expiry: String::new(),
+            strike: 0,
+        });
+        self.lock_current()
+            .active
+            .iter()
+            .find(|p| p.scope.covers(&contract))
+            .cloned()
+    }
+
+    /// Returns true if a pause with the global scope is active.
+    #[must_use]
+    pub fn is_paused_globally(&self) -> bool {
+        self.lock_current()
+            .active
+            .iter()
+            .any(|p| p.scope == PauseScope::All)
+    }
+
+    /// Returns true if quoting a contract is paused.
+    #[must_use]
+    pub fn is_paused(&self, symbol: &str) -> bool {
+        self.pause_for(symbol).is_some()
+    }
+
+    /// Locks the pauses after dropping lapsed ones.
+    fn lock_current(&self) -> MutexGuard<'_, Pauses> {
+        let now_ms = self.clock.now_ms();
+        let mut pauses = self.lock();
+        pauses.active.retain(|p| !p.is_expired(now_ms));
+        pauses
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Pauses> {
+        self.pauses
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    fn registry() -> (Arc<ManualClock>, QuotingPauses) {
+        let clock = Arc::new(ManualClock::new(0));
+        (Arc::clone(&clock), QuotingPauses::new(clock))
+    }
+
+    #[test]
+    fn test_parse_symbol() {
+        let key = ContractKey::parse("BTC-USD-20240329-50000-C").unwrap();
+        assert_eq!(key.underlying, "BTC-USD");
+        assert_eq!(key.expiry, "20240329");
+        assert_eq!(key.strike, 50000);
+        assert!(ContractKey::parse("BTC-20240329-50000").is_none());
+        assert!(ContractKey::parse("BTC-PERP").is_none());
+        assert_eq!(
+            "market_event".parse::<PauseReason>().unwrap(),
+            PauseReason::MarketEvent
+        );
+        assert!("later".parse::<PauseReason>().is_err());
+    }
+
+    #[test]
+    fn test_scopes_cover_contracts() {
+        let (_, pauses) = registry();
+        let weekly = PauseScope::Expiry {
+            underlying: "BTC".to_string(),
+            expiry: "20240329".to_string(),
+        };
+        pauses
+            .pause(weekly.clone(), PauseReason::Expiring, "", "ops", None)
+            .unwrap();
+        pauses
+            .pause(
+                PauseScope::StrikeRange {
+                    underlying: "ETH".to_string(),
+                    expiry: "20240329".to_string(),
+                    low: 3000,
+                    high: 3200,
+                },
+                PauseReason::DataIssue,
+                "bad feed",
+                "ops",
+                None,
+            )
+            .unwrap();
+
+        assert!(pauses.is_paused("BTC-20240329-50000-P"));
+        assert!(!pauses.is_paused("BTC-20240405-50000-P"));
+        assert!(pauses.is_paused("ETH-20240329-3200-C"));
+        assert!(!pauses.is_paused("ETH-20240329-3300-C"));
+        assert_eq!(
+            pauses.pause_for("ETH-20240329-3000-C").unwrap().reason,
+            PauseReason::DataIssue
+        );
+
+        assert_eq!(pauses.resume_scope(&weekly), 1);
+        assert!(!pauses.is_paused("BTC-20240329-50000-P"));
+    }
+
+    #[test]
+    fn test_pause_expires_and_resumes() {
+        let (clock, pauses) = registry();
+        let contract = PauseScope::Contract("BTC-20240329-50000-C".to_string());
+        let pause = pauses
+            .pause(contract, PauseReason::Operator, "", "ops", Some(1_000))
+            .unwrap();
+        assert!(pauses.is_paused("BTC-20240329-50000-C"));
+        clock.advance(1_000);
+        assert!(pauses.active().is_empty());
+        assert!(pauses.resume(pause.id).is_none());
+
+        let underlying = pauses
+            .pause(
+                PauseScope::Underlying("BTC".to_string()),
+                PauseReason::MarketEvent,
+                "CPI",
+                "ops",
+                None,
+            )
+            .unwrap();
+        assert!(pauses.resume(underlying.id).is_some());
+        assert!(
+            pauses
+                .pause(
+                    PauseScope::StrikeRange {
+                        underlying: "BTC".to_string(),
+                        expiry: "20240329".to_string(),
+                        low: 2,
+                        high: 1,
+                    },
+                    PauseReason::Operator,
+                    "",
+                    "ops",
+                    None,
+                )
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! Quote limit projection module.
+//!
+//! This module provides the worst-case fill projection used by
+//! [`QuoteEngine::project_limits`](super::QuoteEngine::project_limits).
+//!
+//! For each Greek the projection tracks two bounds starting at the current
+//! portfolio value: the highest value reachable if every quote side that
+//! raises it fills at full size, and the lowest value reachable if every
+//! side that lowers it fills. Quotes are processed in batch order; each
+//! side is shrunk to the largest size that keeps both bounds within the
+//! limit, and withheld when that size is zero. Sides that reduce an
+//! existing exposure are therefore still quoted when the portfolio is
+//! already over a limit.
+
+use super::generated::GeneratedQuote;
+use crate::pricing::Greeks;
+use crate::risk::{LimitKind, RiskLimits};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+/// Greek limits checked by the projection.
+const PROJECTED_LIMITS: [LimitKind; 4] = [
+    LimitKind::Delta,
+    LimitKind::Gamma,
+    LimitKind::Vega,
+    LimitKind::Theta,
+];
+
+/// An outgoing quote and the Greeks it would add per contract bought.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuoteExposure {
+    /// Outgoing quote.
+    pub quote: GeneratedQuote,
+    /// Greeks of one long contract, including the contract size.
+    pub greeks: Greeks,
+}
+
+/// A quote whose sizes were reduced by the projection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeAdjustment {
+    /// Position of the quote in the batch.
+    pub index: usize,
+    /// Bid size before the projection.
+    pub original_bid_size: u64,
+    /// Ask size before the projection.
+    pub original_ask_size: u64,
+    /// Bid size after the projection.
+    pub bid_size: u64,
+    /// Ask size after the projection.
+    pub ask_size: u64,
+    /// Limit that bound the tighter side.
+    pub limit: LimitKind,
+}
+
+/// Result of projecting a quote batch against Greek limits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitProjection {
+    /// Quotes with projected sizes, in batch order.
+    pub quotes: Vec<GeneratedQuote>,
+    /// Quotes whose sizes were reduced.
+    pub adjustments: Vec<SizeAdjustment>,
+    /// Highest reachable Greeks if every raising side fills.
+    pub worst_high: Greeks,
+    /// Lowest reachable Greeks if every lowering side fills.
+    pub worst_low: Greeks,
+}
+
+impl LimitProjection {
+    /// Returns the number of quote sides withheld entirely.
+    #[must_use]
+    pub fn withheld_sides(&self) -> usize {
+        self.adjustments
+            .iter()
+            .map(|a| {
+                usize::from(a.bid_size == 0 && a.original_bid_size > 0)
+                    + usize::from(a.ask_size == 0 && a.original_ask_size > 0)
+            })
+            .sum()
+    }
+}
+
+/// Projects worst-case fills of a quote batch and shrinks sizes to fit.
+pub(crate) fn project(
+    batch: &[QuoteExposure],
+    current: &Greeks,
+    limits: &RiskLimits,
+) -> LimitProjection {
+    let mut high = *current;
+    let mut low = *current;
+    let mut quotes = Vec::with_capacity(batch.len());
+    let mut adjustments = Vec::new();
+
+    for (index, exposure) in batch.iter().enumerate() {
+        let mut quote = exposure.quote;
+        let (bid_size, bid_limit) = fit(
+            quote.bid_size,
+            &exposure.greeks,
+            &mut high,
+            &mut low,
+            limits,
+        );
+        let (ask_size, ask_limit) = fit(
+            quote.ask_size,
+            &-exposure.greeks,
+            &mut high,
+            &mut low,
+            limits,
+        );
+        if bid_size < quote.bid_size || ask_size < quote.ask_size {
+            let bid_cut = quote.bid_size - bid_size;
+            let ask_cut = quote.ask_size - ask_size;
+            let limit = if bid_cut >= ask_cut {
+                bid_limit
+            } else {
+                ask_limit
+            };
+            adjustments.push(SizeAdjustment {
+                index,
+                original_bid_size: quote.bid_size,
+                original_ask_size: quote.ask_size,
+                bid_size,
+                ask_size,
+                limit: limit.unwrap_or(LimitKind::Delta),
+            });
+            quote.bid_size = bid_size;
+            quote.ask_size = ask_size;
+        }
+        quotes.push(quote);
+    }
+
+    LimitProjection {
+        quotes,
+        adjustments,
+        worst_high: high,
+        worst_low: low,
+    }
+}
+
+/// Fits one quote side, whose fill adds `per_unit` per contract, within
+/// the limits and widens the bounds by the fitted size.
+///
+/// Returns the fitted size and the limit that bound it, if any.
+fn fit(
+    size: u64,
+    per_unit: &Greeks,
+    high: &mut Greeks,
+    low: &mut Greeks,
+    limits: &RiskLimits,
+) -> (u64, Option<LimitKind>) {
+    let mut fitted = size;
+    let mut binding = None;
+    for kind in PROJECTED_LIMITS {
+        let g = component(per_unit, kind);
+        let limit = limits.limit(kind);
+        let headroom = if g > Decimal::ZERO {
+            (limit - component(high, kind)) / g
+        } else if g < Decimal::ZERO {
+            (component(low, kind) + limit) / -g
+        } else {
+            continue;
+        };
+        let max = headroom
+            .max(Decimal::ZERO)
+            .floor()
+            .to_u64()
+            .unwrap_or(u64::MAX);
+        if max < fitted {
+            fitted = max;
+            binding = Some(kind);
+        }
+    }
+
+    let added = *per_unit * Decimal::from(fitted);
+    for kind in PROJECTED_LIMITS {
+        let delta = component(&added, kind);
+        if delta > Decimal::ZERO {
+            *component_mut(high, kind) += delta;
+        } else {
+            *component_mut(low, kind) += delta;
+        }
+    }
+    (fitted, binding)
+}
+
+/// Returns the Greek checked by a limit.
+fn component(greeks: &Greeks, kind: LimitKind) -> Decimal {
+    match kind {
+        LimitKind::Delta => greeks.delta,
+        LimitKind::Gamma => greeks.gamma,
+        LimitKind::Vega => greeks.vega,
+        LimitKind::Theta => greeks.theta,
+        LimitKind::DailyLoss | LimitKind::PillarVega | LimitKind::Position => Decimal::ZERO,
+    }
+}
+
+/// Returns the Greek checked by a limit, mutably.
+fn component_mut(greeks: &mut Greeks, kind: LimitKind) -> &mut Decimal {
+    match kind {
+        LimitKind::Gamma => &mut greeks.gamma,
+        LimitKind::Vega => &mut greeks.vega,
+        LimitKind::Theta => &mut greeks.theta,
+        _ => &mut greeks.delta,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn exposure(delta: Decimal, vega: Decimal, size: u64) -> QuoteExposure {
+        QuoteExposure {
+            quote: GeneratedQuote {
+                theo: dec!(5),
+                reservation_price: dec!(5),
+                bid_price: dec!(4.9),
+                ask_price: dec!(5.1),
+                bid_size: size,
+                ask_size: size,
+            },
+            greeks: Greeks {
+                delta,
+                vega,
+                ..Greeks::zero()
+            },
+        }
+    }
+
+    fn current(delta: Decimal) -> Greeks {
+        Greeks {
+            delta,
+            ..Greeks::zero()
+        }
+    }
+
+    #[test]
+    fn test_within_limits_unchanged() {
+        let batch = [exposure(dec!(0.5), dec!(1), 10)];
+        let projection = project(&batch, &Greeks::zero(), &RiskLimits::default());
+
+        assert!(projection.adjustments.is_empty());
+        assert_eq!(projection.quotes[0].bid_size, 10);
+        assert_eq!(projection.worst_high.delta, dec!(5));
+        assert_eq!(projection.worst_low.delta, dec!(-5));
+    }
+
+    #[test]
+    fn test_shrinks_then_withholds() {
+        let batch = [
+            exposure(dec!(0.5), Decimal::ZERO, 50),
+            exposure(dec!(0.5), Decimal::ZERO, 10),
+        ];
+        let projection = project(&batch, &current(dec!(90)), &RiskLimits::default());
+
+        // Headroom of 10 delta allows 20 contracts on the first bid.
+        assert_eq!(projection.quotes[0].bid_size, 20);
+        assert_eq!(projection.quotes[0].ask_size, 50);
+        assert_eq!(projection.quotes[1].bid_size, 0);
+        assert_eq!(projection.quotes[1].ask_size, 10);
+        assert_eq!(projection.adjustments.len(), 2);
+        assert_eq!(projection.adjustments[0].limit, LimitKind::Delta);
+        assert_eq!(projection.withheld_sides(), 1);
+        assert_eq!(projection.worst_high.delta, dec!(100));
+    }
+
+    #[test]
+    fn test_reducing_side_quoted_when_over_limit() {
+        let batch = [exposure(dec!(0.5), Decimal::ZERO, 10)];
+        let projection = project(&batch, &current(dec!(150)), &RiskLimits::default());
+
+        assert_eq!(projection.quotes[0].bid_size, 0);
+        assert_eq!(projection.quotes[0].ask_size, 10);
+    }
+
+    #[test]
+    fn test_binding_limit_is_reported() {
+        let limits = RiskLimits::default();
+        let batch = [exposure(dec!(0.01), dec!(1000), 20)];
+        let projection = project(&batch, &Greeks::zero(), &limits);
+
+        assert_eq!(projection.quotes[0].bid_size, 10);
+        assert_eq!(projection.quotes[0].ask_size, 10);
+        assert_eq!(projection.adjustments[0].limit, LimitKind::Vega);
+    }
+}
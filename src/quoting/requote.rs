@@ -0,0 +1,577 @@
+//! Requote decision module.
+//!
+//! This module provides the [`RequoteDecider`], which decides whether a
+//! resting order should be cancelled and replaced after a theo change, or
+//! kept to preserve its queue priority.
+//!
+//! ## Model
+//!
+//! The probability of being filled before the quoting horizon ends is
+//! [`QueuePosition::fill_probability`], `exp(-(queue_ahead + quantity) /
+//! level_volume)`, where `level_volume` is the quantity expected to trade
+//! at a level over the horizon. The expected
+//! value of an order is that probability times its edge against theo times
+//! its quantity. An order is replaced only when the new price's expected
+//! value, starting at the back of its new queue, beats keeping the current
+//! order by at least `min_ev_gain`, or when the current order's edge has
+//! turned adverse beyond `max_adverse_edge`.
+//!
+//! ## Theo change trigger
+//!
+//! Before any of that, the [`RequoteGate`] filters theo changes per
+//! contract: a contract is repriced only when its theo moved by more than
+//! `max(tick_size, |vega| * vol_tolerance)` since it was last quoted. An
+//! at-the-money contract with large vega therefore ignores moves that are
+//! noise in vol terms, while a far wing whose vega is below a tick reacts
+//! to every full tick.
+
+use crate::error::{Error, Result};
+use crate::orderbook::QueuePosition;
+use crossbeam_skiplist::SkipMap;
+use orderbook_rs::{OrderId, Side};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Requote decision thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequoteConfig {
+    /// Quantity expected to trade at a price level over the horizon.
+    pub level_volume: Decimal,
+    /// Minimum expected value gain (in ticks times contracts) to replace.
+    pub min_ev_gain: Decimal,
+    /// Adverse edge per contract (in ticks) that forces a replace.
+    pub max_adverse_edge: Decimal,
+}
+
+impl RequoteConfig {
+    /// Validates the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the level volume is not
+    /// positive or a threshold is negative.
+    pub fn validate(&self) -> Result<()> {
+        if self.level_volume <= Decimal::ZERO {
+            return Err(Error::configuration("level volume must be positive"));
+        }
+        if self.min_ev_gain < Decimal::ZERO || self.max_adverse_edge < Decimal::ZERO {
+            return Err(Error::configuration(
+                "requote thresholds must be non-negative",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// What to do with a resting order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RequoteAction {
+    /// Keep the order and its queue position.
+    Keep,
+    /// Cancel the order and place it at the new price.
+    Replace,
+}
+
+/// Why a decision was made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RequoteReason {
+    /// The target price equals the resting price.
+    Unchanged,
+    /// The queue position is worth more than the better price.
+    QueueValue,
+    /// The new price is worth more despite losing priority.
+    EdgeGain,
+    /// The resting order now trades against us beyond tolerance.
+    AdverseEdge,
+}
+
+/// A requote decision with the values it was based on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequoteDecision {
+    /// Chosen action.
+    pub action: RequoteAction,
+    /// Reason for the action.
+    pub reason: RequoteReason,
+    /// Expected value of keeping the resting order.
+    pub keep_value: Decimal,
+    /// Expected value of the replacement order.
+    pub replace_value: Decimal,
+}
+
+/// Effectiveness counters of the decider.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequoteStats {
+    /// Decisions made.
+    pub evaluated: u64,
+    /// Decisions to keep.
+    pub kept: u64,
+    /// Decisions to replace for expected value.
+    pub replaced: u64,
+    /// Decisions to replace because the edge turned adverse.
+    pub forced: u64,
+    /// Fills received by orders that were kept at least once.
+    pub kept_orders_filled: u64,
+    /// Edge (ticks times contracts) captured by those fills.
+    pub kept_edge_captured: Decimal,
+}
+
+impl std::fmt::Display for RequoteStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "evaluated={} kept={} replaced={} forced={} kept_fills={} kept_edge={}",
+            self.evaluated,
+            self.kept,
+            self.replaced,
+            self.forced,
+            self.kept_orders_filled,
+            self.kept_edge_captured
+        )
+    }
+}
+
+/// Queue-position-aware requote decisions.
+pub struct RequoteDecider {
+    /// Decision thresholds.
+    config: RequoteConfig,
+    /// Decisions made.
+    evaluated: AtomicU64,
+    /// Decisions to keep.
+    kept: AtomicU64,
+    /// Decisions to replace for expected value.
+    replaced: AtomicU64,
+    /// Forced replacements.
+    forced: AtomicU64,
+    /// Fills of kept orders.
+    kept_orders_filled: AtomicU64,
+    /// Edge captured by kept orders.
+    kept_edge_captured: Mutex<Decimal>,
+    /// Kept orders and their side and price, for effectiveness tracking.
+    kept_orders: Mutex<HashMap<OrderId, (Side, u128)>>,
+}
+
+impl RequoteDecider {
+    /// Creates a decider.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the configuration is invalid.
+    pub fn new(config: RequoteConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            evaluated: AtomicU64::new(0),
+            kept: AtomicU64::new(0),
+            replaced: AtomicU64::new(0),
+            forced: AtomicU64::new(0),
+            kept_orders_filled: AtomicU64::new(0),
+            kept_edge_captured: Mutex::new(Decimal::ZERO),
+            kept_orders: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Returns the decision thresholds.
+    #[must_use]
+    pub const fn config(&self) -> &RequoteConfig {
+        &self.config
+    }
+
+    /// Returns the estimated probability that an order in a queue fills
+    /// completely over the horizon.
+    #[must_use]
+    pub fn fill_probability(&self, position: &QueuePosition) -> Decimal {
+        let level_volume = self.config.level_volume.to_f64().unwrap_or(f64::MAX);
+        Decimal::from_f64(position.fill_probability(level_volume)).unwrap_or(Decimal::ZERO)
+    }
+
+    /// Decides whether to keep or replace a resting order.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_id` - Resting order identifier
+    /// * `resting` - Estimated queue state of the resting order
+    /// * `new_price` - Target price in ticks after the theo change
+    /// * `new_queue_ahead` - Quantity resting at the target price
+    /// * `theo` - New theo in ticks
+    pub fn decide(
+        &self,
+        order_id: OrderId,
+        resting: &QueuePosition,
+        new_price: u128,
+        new_queue_ahead: u64,
+        theo: Decimal,
+    ) -> RequoteDecision {
+        self.evaluated.fetch_add(1, Ordering::Relaxed);
+        let quantity = Decimal::from(resting.quantity);
+        let old_edge = edge(resting.side, resting.price, theo);
+        // The replacement joins the back of the queue at its new price.
+        let replacement = QueuePosition {
+            price: new_price,
+            queue_ahead: new_queue_ahead,
+            queue_behind: 0,
+            ..*resting
+        };
+        let keep_value = self.fill_probability(resting) * old_edge * quantity;
+        let replace_value =
+            self.fill_probability(&replacement) * edge(resting.side, new_price, theo) * quantity;
+
+        let (action, reason) = if new_price == resting.price {
+            (RequoteAction::Keep, RequoteReason::Unchanged)
+        } else if old_edge < -self.config.max_adverse_edge {
+            (RequoteAction::Replace, RequoteReason::AdverseEdge)
+        } else if replace_value - keep_value > self.config.min_ev_gain {
+            (RequoteAction::Replace, RequoteReason::EdgeGain)
+        } else {
+            (RequoteAction::Keep, RequoteReason::QueueValue)
+        };
+
+        match reason {
+            RequoteReason::AdverseEdge => {
+                self.forced.fetch_add(1, Ordering::Relaxed);
+                self.kept_orders().remove(&order_id);
+            }
+            RequoteReason::EdgeGain => {
+                self.replaced.fetch_add(1, Ordering::Relaxed);
+                self.kept_orders().remove(&order_id);
+            }
+            RequoteReason::QueueValue => {
+                self.kept.fetch_add(1, Ordering::Relaxed);
+                self.kept_orders()
+                    .insert(order_id, (resting.side, resting.price));
+            }
+            RequoteReason::Unchanged => {
+                self.kept.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        RequoteDecision {
+            action,
+            reason,
+            keep_value,
+            replace_value,
+        }
+    }
+
+    /// Records a fill of one of our orders for effectiveness stats.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_id` - Filled order identifier
+    /// * `quantity` - Filled quantity
+    /// * `theo` - Theo in ticks at the time of the fill
+    pub fn record_fill(&self, order_id: OrderId, quantity: u64, theo: Decimal) {
+        let kept = self.kept_orders().get(&order_id).copied();
+        if let Some((side, price)) = kept {
+            self.kept_orders_filled.fetch_add(1, Ordering::Relaxed);
+            if let Ok(mut captured) = self.kept_edge_captured.lock() {
+                *captured += edge(side, price, theo) * Decimal::from(quantity);
+            }
+        }
+    }
+
+    /// Stops tracking an order that was cancelled or completely filled.
+    pub fn forget(&self, order_id: OrderId) {
+        self.kept_orders().remove(&order_id);
+    }
+
+    /// Returns the effectiveness counters.
+    #[must_use]
+    pub fn stats(&self) -> RequoteStats {
+        RequoteStats {
+            evaluated: self.evaluated.load(Ordering::Relaxed),
+            kept: self.kept.load(Ordering::Relaxed),
+            replaced: self.replaced.load(Ordering::Relaxed),
+            forced: self.forced.load(Ordering::Relaxed),
+            kept_orders_filled: self.kept_orders_filled.load(Ordering::Relaxed),
+            kept_edge_captured: self.kept_edge_captured.lock().map_or(Decimal::ZERO, |c| *c),
+        }
+    }
+
+    /// Locks the kept-order map, recovering from poisoning.
+    fn kept_orders(&self) -> std::sync::MutexGuard<'_, HashMap<OrderId, (Side, u128)>> {
+        self.kept_orders
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Per-contract theo change threshold from vega and tick size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequoteTrigger {
+    /// Theo move, in vol points, worth repricing for.
+    pub vol_tolerance: Decimal,
+    /// Smallest theo move worth repricing for, in price units.
+    pub tick_size: Decimal,
+}
+
+impl RequoteTrigger {
+    /// Validates the trigger.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the tick size is not positive
+    /// or the vol tolerance is negative.
+    pub fn validate(&self) -> Result<()> {
+        if self.tick_size <= Decimal::ZERO {
+            return Err(Error::configuration("requote tick size must be positive"));
+        }
+        if self.vol_tolerance < Decimal::ZERO {
+            return Err(Error::configuration(
+                "requote vol tolerance must be non-negative",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the theo move a contract must exceed to be repriced.
+    ///
+    /// # Arguments
+    ///
+    /// * `vega` - Vega of one contract per vol point
+    #[must_use]
+    pub fn threshold(&self, vega: Decimal) -> Decimal {
+        self.tick_size.max(vega.abs() * self.vol_tolerance)
+    }
+
+    /// Returns true if a theo move exceeds the contract's threshold.
+    #[must_use]
+    pub fn exceeded(&self, quoted_theo: Decimal, theo: Decimal, vega: Decimal) -> bool {
+        (theo - quoted_theo).abs() > self.threshold(vega)
+    }
+}
+
+/// Counters of the [`RequoteGate`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequoteGateStats {
+    /// Theo updates checked.
+    pub evaluated: u64,
+    /// Updates that triggered a requote.
+    pub triggered: u64,
+    /// Updates absorbed below the threshold.
+    pub suppressed: u64,
+}
+
+/// Per-contract requote gating on theo changes.
+///
+/// Keeps the theo each contract was last quoted at and lets an update
+/// through only when the move since then exceeds the contract's
+/// [`RequoteTrigger`] threshold, so small moves cannot creep past it one
+/// update at a time. Uses `SkipMap` for thread-safe concurrent access.
+pub struct RequoteGate {
+    /// Threshold rule.
+    trigger: RequoteTrigger,
+    /// Theo each contract was last quoted at.
+    quoted: SkipMap<String, Decimal>,
+    /// Updates checked.
+    evaluated: AtomicU64,
+    /// Updates that triggered a requote.
+    triggered: AtomicU64,
+}
+
+impl RequoteGate {
+    /// Creates a gate.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the trigger is invalid.
+    pub fn new(trigger: RequoteTrigger) -> Result<Self> {
+        trigger.validate()?;
+        Ok(Self {
+            trigger,
+            quoted: SkipMap::new(),
+            evaluated: AtomicU64::new(0),
+            triggered: AtomicU64::new(0),
+        })
+    }
+
+    /// Returns the threshold rule.
+    #[must_use]
+    pub const fn trigger(&self) -> &RequoteTrigger {
+        &self.trigger
+    }
+
+    /// Checks a theo update and returns true if the contract should be
+    /// requoted, recording the new theo as quoted if so.
+    ///
+    /// A contract seen for the first time is always requoted.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - Contract symbol
+    /// * `theo` - New theo
+    /// * `vega` - Vega of one contract per vol point
+    pub fn check(&self, symbol: &str, theo: Decimal, vega: Decimal) -> bool {
+        self.evaluated.fetch_add(1, Ordering::Relaxed);
+        let requote = self
+            .quoted
+            .get(symbol)
+            .is_none_or(|e| self.trigger.exceeded(*e.value(), theo, vega));
+        if requote {
+            self.triggered.fetch_add(1, Ordering::Relaxed);
+            self.quoted.insert(symbol.to_string(), theo);
+        }
+        requote
+    }
+
+    /// Returns the theo a contract was last quoted at.
+    #[must_use]
+    pub fn quoted_theo(&self, symbol: &str) -> Option<Decimal> {
+        self.quoted.get(symbol).map(|e| *e.value())
+    }
+
+    /// Forgets a contract, so its next update is requoted.
+    pub fn reset(&self, symbol: &str) -> bool {
+        self.quoted.remove(symbol).is_some()
+    }
+
+    /// Returns the counters.
+    #[must_use]
+    pub fn stats(&self) -> RequoteGateStats {
+        let evaluated = self.evaluated.load(Ordering::Relaxed);
+        let triggered = self.triggered.load(Ordering::Relaxed);
+        RequoteGateStats {
+            evaluated,
+            triggered,
+            suppressed: evaluated.saturating_sub(triggered),
+        }
+    }
+}
+
+/// Returns the edge per contract of resting at `price` against `theo`.
+fn edge(side: Side, price: u128, theo: Decimal) -> Decimal {
+    match side {
+        Side::Buy => theo - Decimal::from(price),
+        Side::Sell => Decimal::from(price) - theo,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn decider() -> RequoteDecider {
+        RequoteDecider::new(RequoteConfig {
+            level_volume: dec!(50),
+            min_ev_gain: dec!(1),
+            max_adverse_edge: dec!(0.5),
+        })
+        .unwrap()
+    }
+
+    fn bid(price: u128, queue_ahead: u64) -> QueuePosition {
+        QueuePosition {
+            side: Side::Buy,
+            price,
+            quantity: 10,
+            queue_ahead,
+            queue_behind: 100,
+            entered_at_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let config = RequoteConfig {
+            level_volume: Decimal::ZERO,
+            min_ev_gain: Decimal::ZERO,
+            max_adverse_edge: Decimal::ZERO,
+        };
+        assert!(RequoteDecider::new(config).is_err());
+    }
+
+    #[test]
+    fn test_front_of_queue_kept_on_small_move() {
+        let decider = decider();
+        // Theo 102.2: resting at 100 (front) vs moving up to 101 behind 200.
+        let decision = decider.decide(OrderId::new(), &bid(100, 0), 101, 200, dec!(102.2));
+
+        assert_eq!(decision.action, RequoteAction::Keep);
+        assert_eq!(decision.reason, RequoteReason::QueueValue);
+        assert!(decision.keep_value > decision.replace_value);
+    }
+
+    #[test]
+    fn test_back_of_queue_replaced() {
+        let decider = decider();
+        let decision = decider.decide(OrderId::new(), &bid(100, 200), 101, 0, dec!(102.2));
+
+        assert_eq!(decision.action, RequoteAction::Replace);
+        assert_eq!(decision.reason, RequoteReason::EdgeGain);
+    }
+
+    #[test]
+    fn test_adverse_edge_forces_replace() {
+        let decider = decider();
+        // Theo fell to 99: our bid at 100 overpays by 1 tick.
+        let decision = decider.decide(OrderId::new(), &bid(100, 0), 98, 500, dec!(99));
+
+        assert_eq!(decision.reason, RequoteReason::AdverseEdge);
+        assert_eq!(decider.stats().forced, 1);
+    }
+
+    #[test]
+    fn test_unchanged_price() {
+        let decision = decider().decide(OrderId::new(), &bid(100, 0), 100, 0, dec!(101));
+        assert_eq!(decision.reason, RequoteReason::Unchanged);
+    }
+
+    #[test]
+    fn test_effectiveness_stats() {
+        let decider = decider();
+        let kept = OrderId::new();
+        decider.decide(kept, &bid(100, 0), 101, 200, dec!(102.2));
+        decider.decide(OrderId::new(), &bid(100, 200), 101, 0, dec!(102.2));
+
+        decider.record_fill(kept, 4, dec!(102));
+        decider.record_fill(OrderId::new(), 4, dec!(102));
+
+        let stats = decider.stats();
+        assert_eq!(stats.evaluated, 2);
+        assert_eq!(stats.kept, 1);
+        assert_eq!(stats.replaced, 1);
+        assert_eq!(stats.kept_orders_filled, 1);
+        assert_eq!(stats.kept_edge_captured, dec!(8));
+
+        decider.forget(kept);
+        decider.record_fill(kept, 1, dec!(102));
+        assert_eq!(decider.stats().kept_orders_filled, 1);
+    }
+
+    #[test]
+    fn test_gate_threshold_scales_with_vega() {
+        let gate = RequoteGate::new(RequoteTrigger {
+            vol_tolerance: dec!(0.5),
+            tick_size: dec!(0.05),
+        })
+        .unwrap();
+        // ATM: vega 2 per vol point, threshold 1.0.
+        assert!(gate.check("ATM", dec!(20), dec!(2)));
+        assert!(!gate.check("ATM", dec!(20.6), dec!(2)));
+        assert!(!gate.check("ATM", dec!(20.9), dec!(2)));
+        // Measured from the quoted theo, not the last update.
+        assert!(gate.check("ATM", dec!(21.1), dec!(2)));
+        assert_eq!(gate.quoted_theo("ATM"), Some(dec!(21.1)));
+
+        // Wing: vega 0.01, so the tick floor applies.
+        assert!(gate.check("WING", dec!(0.2), dec!(0.01)));
+        assert!(!gate.check("WING", dec!(0.25), dec!(0.01)));
+        assert!(gate.check("WING", dec!(0.3), dec!(0.01)));
+
+        let stats = gate.stats();
+        assert_eq!(stats.evaluated, 7);
+        assert_eq!(stats.triggered, 4);
+        assert_eq!(stats.suppressed, 3);
+        assert!(
+            RequoteGate::new(RequoteTrigger {
+                vol_tolerance: dec!(0.5),
+                tick_size: Decimal::ZERO,
+            })
+            .is_err()
+        );
+    }
+}
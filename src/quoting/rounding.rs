@@ -0,0 +1,348 @@
+//! Tick rounding module.
+//!
+//! This module provides the rounding policy applied when model prices in
+//! `Decimal` are converted to book ticks. The direction is chosen per side
+//! and per context: resting quotes round passively so rounding never
+//! tightens the market, hedges round aggressively so they cross, and
+//! unwinds round to the nearest tick as long as a minimum edge to theo is
+//! kept.
+
+use super::generated::GeneratedQuote;
+use crate::error::{Error, Result};
+use crate::orderbook::Quote;
+use orderbook_rs::Side;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+/// Direction a price is rounded to the tick grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RoundingMode {
+    /// Bids round down and asks round up, away from the other side.
+    #[default]
+    Passive,
+    /// Bids round up and asks round down, towards the other side.
+    Aggressive,
+    /// Rounds to the nearest tick unless that leaves less than `min_edge`
+    /// to theo, in which case the side rounds passively.
+    Nearest {
+        /// Minimum distance from theo the rounded price must keep.
+        min_edge: Decimal,
+    },
+}
+
+impl RoundingMode {
+    /// Validates the mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a minimum edge is negative.
+    pub fn validate(&self) -> Result<()> {
+        if let Self::Nearest { min_edge } = self
+            && *min_edge < Decimal::ZERO
+        {
+            return Err(Error::configuration(
+                "rounding min_edge must not be negative",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Rounds a price for one side to a multiple of the tick size.
+    ///
+    /// A non-positive tick size leaves the price unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - Price to round
+    /// * `side` - Side of the order (`Buy` for bids, `Sell` for asks)
+    /// * `tick_size` - Price of one book tick
+    /// * `theo` - Theoretical value used by [`RoundingMode::Nearest`]
+    #[must_use]
+    pub fn round(&self, price: Decimal, side: Side, tick_size: Decimal, theo: Decimal) -> Decimal {
+        if tick_size <= Decimal::ZERO {
+            return price;
+        }
+        let ticks = price / tick_size;
+        let passive = match side {
+            Side::Buy => ticks.floor(),
+            Side::Sell => ticks.ceil(),
+        };
+        let rounded = match self {
+            Self::Passive => passive,
+            Self::Aggressive => match side {
+                Side::Buy => ticks.ceil(),
+                Side::Sell => ticks.floor(),
+            },
+            Self::Nearest { min_edge } => {
+                let nearest = ticks.round() * tick_size;
+                let edge = match side {
+                    Side::Buy => theo - nearest,
+                    Side::Sell => nearest - theo,
+                };
+                if edge >= *min_edge {
+                    return nearest;
+                }
+                passive
+            }
+        };
+        rounded * tick_size
+    }
+}
+
+/// Purpose of the order a price is rounded for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RoundingContext {
+    /// Resting two-sided quotes.
+    Quoting,
+    /// Orders that reduce risk and should cross.
+    Hedging,
+    /// Orders that work an existing position out.
+    Unwinding,
+}
+
+/// Rounding mode used for each [`RoundingContext`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoundingPolicy {
+    /// Mode for resting quotes.
+    pub quoting: RoundingMode,
+    /// Mode for hedge orders.
+    pub hedging: RoundingMode,
+    /// Mode for unwind orders.
+    pub unwinding: RoundingMode,
+}
+
+impl Default for RoundingPolicy {
+    fn default() -> Self {
+        Self {
+            quoting: RoundingMode::Passive,
+            hedging: RoundingMode::Aggressive,
+            unwinding: RoundingMode::Nearest {
+                min_edge: Decimal::ZERO,
+            },
+        }
+    }
+}
+
+impl RoundingPolicy {
+    /// Returns the mode used in a context.
+    #[must_use]
+    pub const fn mode(&self, context: RoundingContext) -> RoundingMode {
+        match context {
+            RoundingContext::Quoting => self.quoting,
+            RoundingContext::Hedging => self.hedging,
+            RoundingContext::Unwinding => self.unwinding,
+        }
+    }
+
+    /// Validates the policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a minimum edge is negative.
+    pub fn validate(&self) -> Result<()> {
+        self.quoting.validate()?;
+        self.hedging.validate()?;
+        self.unwinding.validate()
+    }
+}
+
+/// Converts `Decimal` prices to book ticks under a [`RoundingPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceConverter {
+    tick_size: Decimal,
+    policy: RoundingPolicy,
+}
+
+impl PriceConverter {
+    /// Creates a converter.
+    ///
+    /// # Arguments
+    ///
+    /// * `tick_size` - Price of one book tick
+    /// * `policy` - Rounding mode per context
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tick size is not positive or the policy is
+    /// invalid.
+    pub fn new(tick_size: Decimal, policy: RoundingPolicy) -> Result<Self> {
+        if tick_size <= Decimal::ZERO {
+            return Err(Error::configuration("tick_size must be positive"));
+        }
+        policy.validate()?;
+        Ok(Self { tick_size, policy })
+    }
+
+    /// Returns the tick size.
+    #[must_use]
+    pub const fn tick_size(&self) -> Decimal {
+        self.tick_size
+    }
+
+    /// Returns the rounding policy.
+    #[must_use]
+    pub const fn policy(&self) -> &RoundingPolicy {
+        &self.policy
+    }
+
+    /// Rounds a price for one side in a context.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - Price to round
+    /// * `side` - Side of the order
+    /// * `context` - Purpose of the order
+    /// * `theo` - Theoretical value of the contract
+    #[must_use]
+    pub fn round(
+        &self,
+        price: Decimal,
+        side: Side,
+        context: RoundingContext,
+        theo: Decimal,
+    ) -> Decimal {
+        self.policy
+            .mode(context)
+            .round(price, side, self.tick_size, theo)
+    }
+
+    /// Converts a price to integer book ticks.
+    ///
+    /// Returns `None` if the rounded price is negative or does not fit.
+    #[must_use]
+    pub fn to_ticks(
+        &self,
+        price: Decimal,
+        side: Side,
+        context: RoundingContext,
+        theo: Decimal,
+    ) -> Option<u128> {
+        (self.round(price, side, context, theo) / self.tick_size).to_u128()
+    }
+
+    /// Converts a book tick count back to a price.
+    #[must_use]
+    pub fn to_price(&self, ticks: u128) -> Decimal {
+        Decimal::from(ticks) * self.tick_size
+    }
+
+    /// Rounds both sides of a generated quote for a context.
+    #[must_use]
+    pub fn finalize(&self, quote: GeneratedQuote, context: RoundingContext) -> GeneratedQuote {
+        quote.round_with(self.tick_size, self.policy.mode(context))
+    }
+
+    /// Rounds a generated quote and converts it to a book [`Quote`].
+    ///
+    /// # Arguments
+    ///
+    /// * `quote` - Model quote
+    /// * `context` - Purpose of the orders
+    /// * `timestamp_ms` - Quote timestamp in milliseconds
+    #[must_use]
+    pub fn to_book_quote(
+        &self,
+        quote: GeneratedQuote,
+        context: RoundingContext,
+        timestamp_ms: u64,
+    ) -> Quote {
+        self.finalize(quote, context)
+            .to_book_quote(self.tick_size, timestamp_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn converter() -> PriceConverter {
+        PriceConverter::new(dec!(0.05), RoundingPolicy::default()).unwrap()
+    }
+
+    #[test]
+    fn test_modes_per_side() {
+        let tick = dec!(0.05);
+        let theo = dec!(5.00);
+        let passive = RoundingMode::Passive;
+        let aggressive = RoundingMode::Aggressive;
+        assert_eq!(passive.round(dec!(4.87), Side::Buy, tick, theo), dec!(4.85));
+        assert_eq!(
+            passive.round(dec!(5.13), Side::Sell, tick, theo),
+            dec!(5.15)
+        );
+        assert_eq!(
+            aggressive.round(dec!(4.87), Side::Buy, tick, theo),
+            dec!(4.90)
+        );
+        assert_eq!(
+            aggressive.round(dec!(5.13), Side::Sell, tick, theo),
+            dec!(5.10)
+        );
+    }
+
+    #[test]
+    fn test_nearest_keeps_min_edge() {
+        let tick = dec!(0.05);
+        let nearest = RoundingMode::Nearest {
+            min_edge: dec!(0.10),
+        };
+        // 4.88 rounds to 4.90, which keeps 0.10 of edge.
+        assert_eq!(
+            nearest.round(dec!(4.88), Side::Buy, tick, dec!(5.00)),
+            dec!(4.90)
+        );
+        // 4.93 would round to 4.95, leaving only 0.05, so it floors.
+        assert_eq!(
+            nearest.round(dec!(4.93), Side::Buy, tick, dec!(5.00)),
+            dec!(4.90)
+        );
+        // 5.06 would round to 5.05, so the ask rounds up instead.
+        assert_eq!(
+            nearest.round(dec!(5.06), Side::Sell, tick, dec!(5.00)),
+            dec!(5.10)
+        );
+    }
+
+    #[test]
+    fn test_converter_context() {
+        let converter = converter();
+        let theo = dec!(5.00);
+        assert_eq!(
+            converter.to_ticks(dec!(4.87), Side::Buy, RoundingContext::Quoting, theo),
+            Some(97)
+        );
+        assert_eq!(
+            converter.to_ticks(dec!(4.87), Side::Buy, RoundingContext::Hedging, theo),
+            Some(98)
+        );
+        assert_eq!(converter.to_price(98), dec!(4.90));
+    }
+
+    #[test]
+    fn test_finalize_quote() {
+        let quote = GeneratedQuote {
+            theo: dec!(5.00),
+            reservation_price: dec!(5.00),
+            bid_price: dec!(4.87),
+            ask_price: dec!(5.13),
+            bid_size: 10,
+            ask_size: 10,
+        };
+        let book = converter().to_book_quote(quote, RoundingContext::Hedging, 1);
+        assert_eq!(book.bid_price(), Some(98));
+        assert_eq!(book.ask_price(), Some(102));
+    }
+
+    #[test]
+    fn test_rejects_invalid() {
+        assert!(PriceConverter::new(Decimal::ZERO, RoundingPolicy::default()).is_err());
+        let policy = RoundingPolicy {
+            unwinding: RoundingMode::Nearest { min_edge: dec!(-1) },
+            ..RoundingPolicy::default()
+        };
+        assert!(PriceConverter::new(dec!(0.05), policy).is_err());
+    }
+}
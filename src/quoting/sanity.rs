@@ -0,0 +1,416 @@
+//! Quote sanity module.
+//!
+//! This module provides [`QuoteSanity`], the last pricing check applied
+//! when a quote is finalized. Whatever the model produced, the finalized
+//! quote must respect the no-arbitrage bounds of a European option:
+//!
+//! - The bid never exceeds the upper bound (`S e^{-qT}` for a call,
+//!   `K e^{-rT}` for a put)
+//! - The bid never sits below the zero-volatility price, which no
+//!   non-negative volatility can produce
+//! - The ask never sits below intrinsic value plus a minimum time value,
+//!   and deep in-the-money asks keep a larger configured premium
+//! - Corrections never leave the quote crossed
+//!
+//! Each violation is either corrected to the bound or blocked by pulling
+//! the side, and counted in [`SanityStats`].
+
+use super::generated::GeneratedQuote;
+use crate::error::{Error, Result};
+use crate::pricing::{DAYS_PER_YEAR, PricingParams};
+use optionstratlib::OptionStyle;
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// What to do with a side that violates a bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SanityAction {
+    /// Move the price to the bound.
+    #[default]
+    Correct,
+    /// Pull the side.
+    Block,
+}
+
+/// Pricing sanity configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SanityConfig {
+    /// Smallest time value an ask keeps above intrinsic.
+    pub min_time_value: Decimal,
+    /// Moneyness (intrinsic over spot) from which a contract is deep in
+    /// the money.
+    pub deep_itm_moneyness: Decimal,
+    /// Smallest premium over intrinsic of a deep in-the-money ask.
+    pub deep_itm_premium: Decimal,
+    /// Tick size corrected prices are rounded to, if any.
+    pub tick_size: Option<Decimal>,
+    /// What to do with violations.
+    pub action: SanityAction,
+}
+
+impl Default for SanityConfig {
+    fn default() -> Self {
+        Self {
+            min_time_value: Decimal::ZERO,
+            deep_itm_moneyness: dec!(0.2),
+            deep_itm_premium: Decimal::ZERO,
+            tick_size: None,
+            action: SanityAction::Correct,
+        }
+    }
+}
+
+impl SanityConfig {
+    /// Validates the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if a minimum is negative, the
+    /// deep in-the-money moneyness is not positive, or the tick size is
+    /// not positive.
+    pub fn validate(&self) -> Result<()> {
+        if self.min_time_value < Decimal::ZERO || self.deep_itm_premium < Decimal::ZERO {
+            return Err(Error::configuration(
+                "sanity minimum premiums must be non-negative",
+            ));
+        }
+        if self.deep_itm_moneyness <= Decimal::ZERO {
+            return Err(Error::configuration(
+                "deep in-the-money moneyness must be positive",
+            ));
+        }
+        if self.tick_size.is_some_and(|t| t <= Decimal::ZERO) {
+            return Err(Error::configuration("sanity tick size must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// A pricing sanity violation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SanityViolation {
+    /// Bid above the no-arbitrage upper bound.
+    BidAboveUpperBound,
+    /// Bid below the zero-volatility price.
+    BidBelowZeroVol,
+    /// Ask below intrinsic plus the minimum time value.
+    AskBelowIntrinsic,
+    /// Deep in-the-money ask below intrinsic plus the premium.
+    DeepItmFloor,
+    /// Corrections crossed the quote; the bid is pulled.
+    Crossed,
+}
+
+impl SanityViolation {
+    /// Position of the violation's counter.
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Result of a sanity check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SanityOutcome {
+    /// The finalized quote; a blocked side has zero size.
+    pub quote: GeneratedQuote,
+    /// Violations found, in check order.
+    pub violations: Vec<SanityViolation>,
+}
+
+impl SanityOutcome {
+    /// Returns true if the quote passed unchanged.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Counters of sanity checks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SanityStats {
+    /// Quotes checked.
+    pub checked: u64,
+    /// Bids above the upper bound.
+    pub bid_above_upper_bound: u64,
+    /// Bids below the zero-volatility price.
+    pub bid_below_zero_vol: u64,
+    /// Asks below intrinsic plus the minimum time value.
+    pub ask_below_intrinsic: u64,
+    /// Deep in-the-money asks below the floor.
+    pub deep_itm_floor: u64,
+    /// Bids pulled because corrections crossed the quote.
+    pub crossed: u64,
+    /// Sides corrected.
+    pub corrected: u64,
+    /// Sides blocked.
+    pub blocked: u64,
+}
+
+/// No-arbitrage bounds of one contract.
+struct Bounds {
+    /// Zero-volatility price.
+    lower: Decimal,
+    /// Upper bound.
+    upper: Decimal,
+    /// Undiscounted intrinsic value.
+    intrinsic: Decimal,
+    /// True if deep in the money.
+    deep_itm: bool,
+}
+
+/// Pricing sanity check applied at quote finalization.
+pub struct QuoteSanity {
+    /// Bounds and action.
+    config: SanityConfig,
+    /// Quotes checked.
+    checked: AtomicU64,
+    /// Violations by kind.
+    violations: [AtomicU64; 5],
+    /// Sides corrected.
+    corrected: AtomicU64,
+    /// Sides blocked.
+    blocked: AtomicU64,
+}
+
+impl QuoteSanity {
+    /// Creates a sanity check.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the configuration is invalid.
+    pub fn new(config: SanityConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            checked: AtomicU64::new(0),
+            violations: Default::default(),
+            corrected: AtomicU64::new(0),
+            blocked: AtomicU64::new(0),
+        })
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub const fn config(&self) -> &SanityConfig {
+        &self.config
+    }
+
+    /// Checks a quote against the contract's bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `quote` - Quote to finalize
+    /// * `pricing` - Spot, strike, expiry, rates and style of the contract
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PricingError` if the discount factors overflow.
+    pub fn check(&self, quote: GeneratedQuote, pricing: &PricingParams) -> Result<SanityOutcome> {
+        let bounds = self.bounds(pricing)?;
+        let mut quote = quote;
+        let mut violations = Vec::new();
+
+        if quote.bid_size > 0 && quote.bid_price > bounds.upper {
+            let price = self.floor_tick(bounds.upper);
+            self.apply_bid(&mut quote, price, SanityViolation::BidAboveUpperBound);
+            violations.push(SanityViolation::BidAboveUpperBound);
+        }
+        if quote.bid_size > 0 && quote.bid_price < bounds.lower {
+            let price = self.ceil_tick(bounds.lower);
+            self.apply_bid(&mut quote, price, SanityViolation::BidBelowZeroVol);
+            violations.push(SanityViolation::BidBelowZeroVol);
+        }
+        let (floor, violation) = if bounds.deep_itm {
+            (
+                bounds.intrinsic + self.config.deep_itm_premium.max(self.config.min_time_value),
+                SanityViolation::DeepItmFloor,
+            )
+        } else {
+            (
+                bounds.intrinsic + self.config.min_time_value,
+                SanityViolation::AskBelowIntrinsic,
+            )
+        };
+        if quote.ask_size > 0 && quote.ask_price < floor {
+            let price = self.ceil_tick(floor);
+            match self.config.action {
+                SanityAction::Correct => {
+                    quote.ask_price = price;
+                    self.corrected.fetch_add(1, Ordering::Relaxed);
+                }
+                SanityAction::Block => {
+                    quote.ask_size = 0;
+                    self.blocked.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            self.count(violation);
+            violations.push(violation);
+        }
+        if quote.is_two_sided() && quote.bid_price >= quote.ask_price {
+            quote.bid_size = 0;
+            self.blocked.fetch_add(1, Ordering::Relaxed);
+            self.count(SanityViolation::Crossed);
+            violations.push(SanityViolation::Crossed);
+        }
+
+        self.checked.fetch_add(1, Ordering::Relaxed);
+        Ok(SanityOutcome { quote, violations })
+    }
+
+    /// Returns the counters.
+    #[must_use]
+    pub fn stats(&self) -> SanityStats {
+        let count = |v: SanityViolation| self.violations[v.index()].load(Ordering::Relaxed);
+        SanityStats {
+            checked: self.checked.load(Ordering::Relaxed),
+            bid_above_upper_bound: count(SanityViolation::BidAboveUpperBound),
+            bid_below_zero_vol: count(SanityViolation::BidBelowZeroVol),
+            ask_below_intrinsic: count(SanityViolation::AskBelowIntrinsic),
+            deep_itm_floor: count(SanityViolation::DeepItmFloor),
+            crossed: count(SanityViolation::Crossed),
+            corrected: self.corrected.load(Ordering::Relaxed),
+            blocked: self.blocked.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Corrects or blocks the bid.
+    fn apply_bid(&self, quote: &mut GeneratedQuote, price: Decimal, violation: SanityViolation) {
+        match self.config.action {
+            SanityAction::Correct => {
+                quote.bid_price = price;
+                self.corrected.fetch_add(1, Ordering::Relaxed);
+            }
+            SanityAction::Block => {
+                quote.bid_size = 0;
+                self.blocked.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count(violation);
+    }
+
+    fn count(&self, violation: SanityViolation) {
+        self.violations[violation.index()].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn bounds(&self, pricing: &PricingParams) -> Result<Bounds> {
+        let years = pricing.days_to_expiry.max(Decimal::ZERO) / DAYS_PER_YEAR;
+        let discount = |rate: Decimal| {
+            (-rate * years)
+                .checked_exp()
+                .ok_or_else(|| Error::pricing("discount factor out of range"))
+        };
+        let spot = pricing.spot * discount(pricing.dividend_yield)?;
+        let strike = pricing.strike * discount(pricing.rate)?;
+        let (forward_value, upper, intrinsic) = match pricing.style {
+            OptionStyle::Call => (spot - strike, spot, pricing.spot - pricing.strike),
+            OptionStyle::Put => (strike - spot, strike, pricing.strike - pricing.spot),
+        };
+        let intrinsic = intrinsic.max(Decimal::ZERO);
+        let deep_itm = pricing.spot > Decimal::ZERO
+            && intrinsic / pricing.spot >= self.config.deep_itm_moneyness;
+        Ok(Bounds {
+            lower: forward_value.max(Decimal::ZERO),
+            upper,
+            intrinsic,
+            deep_itm,
+        })
+    }
+
+    fn floor_tick(&self, price: Decimal) -> Decimal {
+        self.config
+            .tick_size
+            .map_or(price, |t| (price / t).floor() * t)
+    }
+
+    fn ceil_tick(&self, price: Decimal) -> Decimal {
+        self.config
+            .tick_size
+            .map_or(price, |t| (price / t).ceil() * t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(bid: Decimal, ask: Decimal) -> GeneratedQuote {
+        GeneratedQuote {
+            theo: (bid + ask) / Decimal::TWO,
+            reservation_price: (bid + ask) / Decimal::TWO,
+            bid_price: bid,
+            ask_price: ask,
+            bid_size: 10,
+            ask_size: 10,
+        }
+    }
+
+    fn put(strike: Decimal) -> PricingParams {
+        PricingParams::new(dec!(100), strike, dec!(30), dec!(0.5), OptionStyle::Put)
+    }
+
+    #[test]
+    fn test_clean_quote_untouched() {
+        let sanity = QuoteSanity::new(SanityConfig::default()).unwrap();
+        let outcome = sanity
+            .check(quote(dec!(5), dec!(6)), &put(dec!(100)))
+            .unwrap();
+        assert!(outcome.is_clean());
+        assert_eq!(outcome.quote, quote(dec!(5), dec!(6)));
+        assert_eq!(sanity.stats().checked, 1);
+    }
+
+    #[test]
+    fn test_deep_itm_floor_and_zero_vol_bid() {
+        let sanity = QuoteSanity::new(SanityConfig {
+            deep_itm_premium: dec!(0.5),
+            tick_size: Some(dec!(0.05)),
+            ..SanityConfig::default()
+        })
+        .unwrap();
+        // Zero rates: intrinsic 30 is also the zero-volatility price.
+        let outcome = sanity
+            .check(quote(dec!(29.5), dec!(30.2)), &put(dec!(130)))
+            .unwrap();
+        assert_eq!(
+            outcome.violations,
+            vec![
+                SanityViolation::BidBelowZeroVol,
+                SanityViolation::DeepItmFloor
+            ]
+        );
+        assert_eq!(outcome.quote.bid_price, dec!(30));
+        assert_eq!(outcome.quote.ask_price, dec!(30.5));
+
+        let stats = sanity.stats();
+        assert_eq!(stats.deep_itm_floor, 1);
+        assert_eq!(stats.corrected, 2);
+    }
+
+    #[test]
+    fn test_block_action_and_upper_bound() {
+        let sanity = QuoteSanity::new(SanityConfig {
+            action: SanityAction::Block,
+            ..SanityConfig::default()
+        })
+        .unwrap();
+        // A put is worth at most its strike.
+        let outcome = sanity
+            .check(quote(dec!(101), dec!(102)), &put(dec!(100)))
+            .unwrap();
+        assert_eq!(
+            outcome.violations,
+            vec![SanityViolation::BidAboveUpperBound]
+        );
+        assert_eq!(outcome.quote.bid_size, 0);
+        assert_eq!(outcome.quote.ask_size, 10);
+        assert_eq!(sanity.stats().blocked, 1);
+
+        let invalid = SanityConfig {
+            min_time_value: dec!(-1),
+            ..SanityConfig::default()
+        };
+        assert!(QuoteSanity::new(invalid).is_err());
+    }
+}
@@ -0,0 +1,302 @@
+//! Smile-adjusted quoting module.
+//!
+//! This module provides the [`SmileAdjustedQuoter`], which widens the
+//! spreads of out-of-the-money contracts according to the shape of the
+//! volatility smile instead of quoting every strike as if volatility were
+//! flat.
+//!
+//! ## Wing widening
+//!
+//! At log-moneyness `x = ln(K / S)` the local smile slope is
+//! `skew + 2 * curvature * x`. The implied volatility of a wing contract is
+//! uncertain by roughly `|slope * x|`, the vol the smile adds or removes
+//! between ATM and the strike. That uncertainty costs `vega` per vol point,
+//! so the extra spread is
+//!
+//! `wing_weight * vega * |slope * x| * 100`
+//!
+//! split evenly between bid and ask and capped at `max_multiplier` times the
+//! model spread. In-the-money contracts are quoted unchanged.
+
+use super::generated::GeneratedQuote;
+use super::params::QuoteParams;
+use super::spread::SpreadCalculator;
+use crate::error::{Error, Result};
+use crate::pricing::VolatilitySurface;
+use optionstratlib::OptionStyle;
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Vol points per unit of volatility.
+const VOL_POINTS: Decimal = dec!(100);
+
+/// Settings of a [`SmileAdjustedQuoter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmileSkewConfig {
+    /// Share of the smile's vol uncertainty charged in the spread.
+    pub wing_weight: Decimal,
+    /// Widest wing spread as a multiple of the model spread.
+    pub max_multiplier: Decimal,
+}
+
+impl Default for SmileSkewConfig {
+    fn default() -> Self {
+        Self {
+            wing_weight: dec!(0.5),
+            max_multiplier: dec!(3),
+        }
+    }
+}
+
+impl SmileSkewConfig {
+    /// Validates the settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the wing weight is negative or
+    /// the maximum multiplier is below one.
+    pub fn validate(&self) -> Result<()> {
+        if self.wing_weight < Decimal::ZERO {
+            return Err(Error::configuration("wing weight must be non-negative"));
+        }
+        if self.max_multiplier < Decimal::ONE {
+            return Err(Error::configuration(
+                "maximum wing multiplier must be at least one",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Quotes contracts with spreads widened on the wings of the smile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmileAdjustedQuoter {
+    /// Spread model.
+    calculator: SpreadCalculator,
+    /// Implied volatility surface the smile is read from.
+    surface: VolatilitySurface,
+    /// Settings.
+    config: SmileSkewConfig,
+}
+
+impl SmileAdjustedQuoter {
+    /// Creates a smile-adjusted quoter.
+    ///
+    /// # Arguments
+    ///
+    /// * `calculator` - Spread model
+    /// * `surface` - Implied volatility surface
+    /// * `config` - Wing widening settings
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the settings are invalid.
+    pub fn new(
+        calculator: SpreadCalculator,
+        surface: VolatilitySurface,
+        config: SmileSkewConfig,
+    ) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            calculator,
+            surface,
+            config,
+        })
+    }
+
+    /// Returns the spread model.
+    #[must_use]
+    pub const fn calculator(&self) -> &SpreadCalculator {
+        &self.calculator
+    }
+
+    /// Returns the volatility surface.
+    #[must_use]
+    pub const fn surface(&self) -> &VolatilitySurface {
+        &self.surface
+    }
+
+    /// Returns the settings.
+    #[must_use]
+    pub const fn config(&self) -> &SmileSkewConfig {
+        &self.config
+    }
+
+    /// Replaces the volatility surface, e.g. after a refit.
+    pub fn set_surface(&mut self, surface: VolatilitySurface) {
+        self.surface = surface;
+    }
+
+    /// Returns the slope of the smile, in volatility per unit of
+    /// log-moneyness, at a strike.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if the surface has no pillars, or
+    /// `Error::PricingError` if the strike or spot is not positive.
+    pub fn local_slope(&self, days: Decimal, strike: Decimal, spot: Decimal) -> Result<Decimal> {
+        let x = log_moneyness(strike, spot)?;
+        let smile = self.surface.smile(days)?;
+        Ok(smile.skew + Decimal::TWO * smile.curvature * x)
+    }
+
+    /// Returns the extra spread of a contract before the cap, zero if it is
+    /// not out of the money.
+    ///
+    /// # Arguments
+    ///
+    /// * `days` - Days to expiry
+    /// * `strike` - Strike price
+    /// * `spot` - Spot price of the underlying
+    /// * `style` - Call or put
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NoDataAvailable` if the surface has no pillars, or
+    /// `Error::PricingError` if the contract cannot be priced.
+    pub fn wing_widening(
+        &self,
+        days: Decimal,
+        strike: Decimal,
+        spot: Decimal,
+        style: OptionStyle,
+    ) -> Result<Decimal> {
+        let out_of_the_money = match style {
+            OptionStyle::Call => strike > spot,
+            OptionStyle::Put => strike < spot,
+        };
+        if !out_of_the_money {
+            return Ok(Decimal::ZERO);
+        }
+        let x = log_moneyness(strike, spot)?;
+        let vol_uncertainty = (self.local_slope(days, strike, spot)? * x).abs();
+        let vega = self
+            .surface
+            .pricing_params(spot, strike, days, style)?
+            .greeks()?
+            .vega
+            .abs();
+        Ok(self.config.wing_weight * vega * vol_uncertainty * VOL_POINTS)
+    }
+
+    /// Generates a quote with the wing widening applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Spread model inputs of the contract
+    /// * `days` - Days to expiry
+    /// * `strike` - Strike price
+    /// * `spot` - Spot price of the underlying
+    /// * `style` - Call or put
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::QuotingError` if the spread model rejects the inputs,
+    /// `Error::NoDataAvailable` if the surface has no pillars, or
+    /// `Error::PricingError` if the contract cannot be priced.
+    pub fn generate(
+        &self,
+        params: &QuoteParams,
+        days: Decimal,
+        strike: Decimal,
+        spot: Decimal,
+        style: OptionStyle,
+    ) -> Result<GeneratedQuote> {
+        let mut quote = self.calculator.generate(params)?;
+        let spread = quote.spread();
+        let cap = spread * (self.config.max_multiplier - Decimal::ONE);
+        let half = self.wing_widening(days, strike, spot, style)?.min(cap) / Decimal::TWO;
+        quote.bid_price = (quote.bid_price - half).max(Decimal::ZERO);
+        quote.ask_price += half;
+        Ok(quote)
+    }
+}
+
+/// Returns `ln(strike / spot)`.
+fn log_moneyness(strike: Decimal, spot: Decimal) -> Result<Decimal> {
+    if strike <= Decimal::ZERO || spot <= Decimal::ZERO {
+        return Err(Error::pricing("strike and spot must be positive"));
+    }
+    Ok((strike / spot).ln())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::SmileParams;
+
+    fn quoter(config: SmileSkewConfig) -> SmileAdjustedQuoter {
+        let surface = VolatilitySurface::new()
+            .with_pillar(dec!(30), SmileParams::new(dec!(0.2), dec!(-0.3), dec!(0.5)));
+        SmileAdjustedQuoter::new(SpreadCalculator::new(10), surface, config).unwrap()
+    }
+
+    fn relative_spread(
+        quoter: &SmileAdjustedQuoter,
+        strike: Decimal,
+        style: OptionStyle,
+    ) -> Decimal {
+        let pricing = quoter
+            .surface()
+            .pricing_params(dec!(100), strike, dec!(30), style)
+            .unwrap();
+        let params =
+            QuoteParams::new(pricing.price().unwrap(), dec!(0.5)).with_time_horizon(dec!(0.01));
+        let quote = quoter
+            .generate(&params, dec!(30), strike, dec!(100), style)
+            .unwrap();
+        quote.spread() / quote.theo
+    }
+
+    #[test]
+    fn test_wings_quoted_wider_than_atm() {
+        let quoter = quoter(SmileSkewConfig::default());
+        assert_eq!(
+            quoter
+                .wing_widening(dec!(30), dec!(100), dec!(100), OptionStyle::Call)
+                .unwrap(),
+            Decimal::ZERO
+        );
+        // In the money: unchanged.
+        assert_eq!(
+            quoter
+                .wing_widening(dec!(30), dec!(80), dec!(100), OptionStyle::Call)
+                .unwrap(),
+            Decimal::ZERO
+        );
+
+        let atm = relative_spread(&quoter, dec!(100), OptionStyle::Put);
+        let put_wing = relative_spread(&quoter, dec!(85), OptionStyle::Put);
+        let call_wing = relative_spread(&quoter, dec!(115), OptionStyle::Call);
+        assert!(put_wing > atm, "{put_wing} vs {atm}");
+        assert!(call_wing > atm, "{call_wing} vs {atm}");
+
+        // The put wing sits where the skew steepens the smile.
+        assert!(
+            quoter.local_slope(dec!(30), dec!(85), dec!(100)).unwrap()
+                < quoter.local_slope(dec!(30), dec!(100), dec!(100)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_widening_capped_and_config_validated() {
+        let quoter = quoter(SmileSkewConfig {
+            wing_weight: dec!(1000),
+            max_multiplier: dec!(2),
+        });
+        let params = QuoteParams::new(dec!(10), dec!(0.5)).with_time_horizon(dec!(0.01));
+        let base = quoter.calculator().generate(&params).unwrap();
+        let quote = quoter
+            .generate(&params, dec!(30), dec!(85), dec!(100), OptionStyle::Put)
+            .unwrap();
+        assert_eq!(quote.spread(), base.spread() * dec!(2));
+        assert_eq!(quote.mid(), base.mid());
+
+        let bad = SmileSkewConfig {
+            wing_weight: dec!(-1),
+            ..SmileSkewConfig::default()
+        };
+        assert!(bad.validate().is_err());
+    }
+}
@@ -0,0 +1,171 @@
+//! Spread calculator module.
+//!
+//! This module provides [`SpreadCalculator`], which turns [`QuoteParams`]
+//! into a [`GeneratedQuote`] using the Avellaneda-Stoikov model:
+//!
+//! - reservation price `r = theo - q * gamma * sigma^2 * tau`
+//! - optimal spread `delta = gamma * sigma^2 * tau + (2 / gamma) * ln(1 + gamma / k)`
+
+use super::generated::GeneratedQuote;
+use super::params::QuoteParams;
+use crate::error::{Error, Result};
+use rust_decimal::{Decimal, MathematicalOps};
+use serde::{Deserialize, Serialize};
+
+/// Avellaneda-Stoikov quote generator for a single contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpreadCalculator {
+    /// Size quoted on each side when flat.
+    base_size: u64,
+    /// Narrowest spread allowed.
+    min_spread: Decimal,
+    /// Widest spread allowed.
+    max_spread: Option<Decimal>,
+}
+
+impl SpreadCalculator {
+    /// Creates a spread calculator with no spread bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_size` - Size quoted on each side
+    #[must_use]
+    pub const fn new(base_size: u64) -> Self {
+        Self {
+            base_size,
+            min_spread: Decimal::ZERO,
+            max_spread: None,
+        }
+    }
+
+    /// Sets the spread bounds.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the minimum is negative or
+    /// above the maximum.
+    pub fn with_spread_bounds(mut self, min_spread: Decimal, max_spread: Decimal) -> Result<Self> {
+        if min_spread < Decimal::ZERO || min_spread > max_spread {
+            return Err(Error::configuration(format!(
+                "invalid spread bounds [{min_spread}, {max_spread}]"
+            )));
+        }
+        self.min_spread = min_spread;
+        self.max_spread = Some(max_spread);
+        Ok(self)
+    }
+
+    /// Returns the size quoted on each side.
+    #[must_use]
+    pub const fn base_size(&self) -> u64 {
+        self.base_size
+    }
+
+    /// Returns the optimal spread before bounds are applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::QuotingError` if the parameters are invalid.
+    pub fn optimal_spread(&self, params: &QuoteParams) -> Result<Decimal> {
+        params.validate()?;
+        let gamma = params.risk_aversion;
+        let variance_term = gamma * params.volatility * params.volatility * params.time_horizon;
+        let intensity_term = (Decimal::ONE + gamma / params.arrival_intensity)
+            .checked_ln()
+            .ok_or_else(|| Error::quoting("arrival intensity term out of range"))?;
+        Ok(variance_term + Decimal::TWO / gamma * intensity_term)
+    }
+
+    /// Generates a quote.
+    ///
+    /// Each side is quoted at the base size, capped by the side's maximum
+    /// size in the parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::QuotingError` if the parameters are invalid.
+    pub fn generate(&self, params: &QuoteParams) -> Result<GeneratedQuote> {
+        let mut spread = self.optimal_spread(params)?.max(self.min_spread);
+        if let Some(max) = self.max_spread {
+            spread = spread.min(max);
+        }
+        let reservation_price = params.theo
+            - params.inventory
+                * params.risk_aversion
+                * params.volatility
+                * params.volatility
+                * params.time_horizon;
+        let half = spread / Decimal::TWO;
+
+        Ok(GeneratedQuote {
+            theo: params.theo,
+            reservation_price,
+            bid_price: (reservation_price - half).max(Decimal::ZERO),
+            ask_price: (reservation_price + half).max(Decimal::ZERO),
+            bid_size: params
+                .max_bid_size
+                .map_or(self.base_size, |max| self.base_size.min(max)),
+            ask_size: params
+                .max_ask_size
+                .map_or(self.base_size, |max| self.base_size.min(max)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_flat_inventory_is_centered() {
+        let quote = SpreadCalculator::new(10)
+            .generate(&QuoteParams::new(dec!(5), dec!(2)))
+            .unwrap();
+
+        assert_eq!(quote.reservation_price, dec!(5));
+        assert_eq!(quote.mid(), dec!(5));
+        assert!(quote.spread() > Decimal::ZERO);
+        assert_eq!(quote.bid_size, 10);
+    }
+
+    #[test]
+    fn test_size_cap() {
+        let quote = SpreadCalculator::new(10)
+            .generate(&QuoteParams::new(dec!(5), dec!(2)).with_size_cap(Some(3), Some(50)))
+            .unwrap();
+
+        assert_eq!(quote.bid_size, 3);
+        assert_eq!(quote.ask_size, 10);
+    }
+
+    #[test]
+    fn test_long_inventory_skews_down() {
+        let calculator = SpreadCalculator::new(10);
+        let params = QuoteParams::new(dec!(5), dec!(2));
+        let flat = calculator.generate(&params).unwrap();
+        let long = calculator
+            .generate(&params.with_inventory(dec!(50)))
+            .unwrap();
+
+        assert!(long.reservation_price < flat.reservation_price);
+        assert_eq!(long.spread(), flat.spread());
+    }
+
+    #[test]
+    fn test_spread_bounds() {
+        let calculator = SpreadCalculator::new(10)
+            .with_spread_bounds(dec!(0.10), dec!(0.20))
+            .unwrap();
+        let quote = calculator
+            .generate(&QuoteParams::new(dec!(5), dec!(2)))
+            .unwrap();
+        assert_eq!(quote.spread(), dec!(0.20));
+
+        assert!(
+            SpreadCalculator::new(10)
+                .with_spread_bounds(dec!(0.3), dec!(0.2))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,360 @@
+//! Stress size module.
+//!
+//! This module provides the [`StressSizer`], which derives the largest
+//! quote size per contract and side from the worst loss a fill would take
+//! across a set of [`StressScenario`]s, against a per-contract loss budget.
+//!
+//! A fill of `n` contracts on the bid loses `n * L_long` in the worst
+//! scenario, where `L_long` is the largest drop in the contract's value;
+//! the bid is therefore capped at `floor(budget / L_long)`, and the ask
+//! likewise from the largest rise. Far wings, whose value can multiply in
+//! a spot or vol shock, end up with small caps on the ask.
+//!
+//! Caps are cached per contract and recomputed only when spot or
+//! volatility move by more than the configured tolerances.
+
+use super::params::QuoteParams;
+use crate::error::{Error, Result};
+use crate::pricing::PricingParams;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Smallest volatility a scenario can shock to.
+const MIN_STRESS_VOLATILITY: Decimal = dec!(0.0001);
+
+/// A joint spot and volatility shock.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StressScenario {
+    /// Scenario name.
+    pub name: String,
+    /// Relative spot move (`-0.1` = spot down 10%).
+    pub spot_shift: Decimal,
+    /// Absolute volatility move (`0.05` = vol up 5 points).
+    pub vol_shift: Decimal,
+}
+
+impl StressScenario {
+    /// Creates a scenario.
+    #[must_use]
+    pub fn new(name: impl Into<String>, spot_shift: Decimal, vol_shift: Decimal) -> Self {
+        Self {
+            name: name.into(),
+            spot_shift,
+            vol_shift,
+        }
+    }
+}
+
+/// Configuration of the [`StressSizer`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StressSizeConfig {
+    /// Scenarios a fill is stressed under.
+    pub scenarios: Vec<StressScenario>,
+    /// Largest tolerated worst-scenario loss of one quoted side.
+    pub loss_budget: Decimal,
+    /// Contract multiplier applied to price changes.
+    pub contract_size: Decimal,
+    /// Relative spot move that triggers a recompute.
+    pub spot_tolerance: Decimal,
+    /// Absolute volatility move that triggers a recompute.
+    pub vol_tolerance: Decimal,
+}
+
+impl Default for StressSizeConfig {
+    fn default() -> Self {
+        Self {
+            scenarios: vec![
+                StressScenario::new("crash", dec!(-0.2), dec!(0.15)),
+                StressScenario::new("down", dec!(-0.1), dec!(0.05)),
+                StressScenario::new("up", dec!(0.1), dec!(-0.02)),
+                StressScenario::new("squeeze", dec!(0.2), dec!(0.1)),
+            ],
+            loss_budget: dec!(10000),
+            contract_size: Decimal::ONE,
+            spot_tolerance: dec!(0.01),
+            vol_tolerance: dec!(0.01),
+        }
+    }
+}
+
+impl StressSizeConfig {
+    /// Validates the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if there are no scenarios, a
+    /// scenario takes spot to zero or below, the budget or contract size is
+    /// not positive, or a tolerance is negative.
+    pub fn validate(&self) -> Result<()> {
+        if self.scenarios.is_empty() {
+            return Err(Error::configuration(
+                "at least one stress scenario is required",
+            ));
+        }
+        if let Some(s) = self
+            .scenarios
+            .iter()
+            .find(|s| s.spot_shift <= -Decimal::ONE)
+        {
+            return Err(Error::configuration(format!(
+                "stress scenario {} moves spot to zero or below",
+                s.name
+            )));
+        }
+        if self.loss_budget <= Decimal::ZERO {
+            return Err(Error::configuration("stress loss budget must be positive"));
+        }
+        if self.contract_size <= Decimal::ZERO {
+            return Err(Error::configuration("contract size must be positive"));
+        }
+        if self.spot_tolerance < Decimal::ZERO || self.vol_tolerance < Decimal::ZERO {
+            return Err(Error::configuration(
+                "stress recompute tolerances must be non-negative",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Largest quote sizes of one contract under the stress budget.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StressCap {
+    /// Spot the cap was computed at.
+    pub spot: Decimal,
+    /// Volatility the cap was computed at.
+    pub volatility: Decimal,
+    /// Worst loss of one long contract.
+    pub long_loss: Decimal,
+    /// Worst loss of one short contract.
+    pub short_loss: Decimal,
+    /// Scenario behind the long loss.
+    pub long_scenario: Option<String>,
+    /// Scenario behind the short loss.
+    pub short_scenario: Option<String>,
+    /// Largest bid size, `None` if no scenario loses on a long.
+    pub max_bid_size: Option<u64>,
+    /// Largest ask size, `None` if no scenario loses on a short.
+    pub max_ask_size: Option<u64>,
+}
+
+/// Per-contract quote size caps from stress losses.
+pub struct StressSizer {
+    /// Configuration.
+    config: StressSizeConfig,
+    /// Cached caps by contract symbol.
+    caps: Mutex<HashMap<String, StressCap>>,
+}
+
+impl StressSizer {
+    /// Creates a sizer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the configuration is invalid.
+    pub fn new(config: StressSizeConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            caps: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub const fn config(&self) -> &StressSizeConfig {
+        &self.config
+    }
+
+    /// Returns the cap of a contract, recomputing it if there is none or
+    /// spot or volatility moved beyond the tolerances since it was
+    /// computed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PricingError` if the contract cannot be priced.
+    pub fn cap(&self, symbol: &str, params: &PricingParams) -> Result<StressCap> {
+        if let Some(cap) = self.lock().get(symbol)
+            && !self.moved(cap, params)
+        {
+            return Ok(cap.clone());
+        }
+        let cap = self.compute(params)?;
+        self.lock().insert(symbol.to_string(), cap.clone());
+        Ok(cap)
+    }
+
+    /// Caps the sizes in quote parameters by the contract's stress cap.
+    ///
+    /// Existing caps in `quote` are kept if they are tighter.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PricingError` if the contract cannot be priced.
+    pub fn apply(
+        &self,
+        symbol: &str,
+        pricing: &PricingParams,
+        quote: QuoteParams,
+    ) -> Result<QuoteParams> {
+        let cap = self.cap(symbol, pricing)?;
+        let tighter = |a: Option<u64>, b: Option<u64>| match (a, b) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        Ok(quote.with_size_cap(
+            tighter(quote.max_bid_size, cap.max_bid_size),
+            tighter(quote.max_ask_size, cap.max_ask_size),
+        ))
+    }
+
+    /// Drops the cached cap of a contract, e.g. after a surface refit.
+    pub fn invalidate(&self, symbol: &str) {
+        self.lock().remove(symbol);
+    }
+
+    /// Drops every cached cap.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Returns the number of cached caps.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns true if no cap is cached.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn moved(&self, cap: &StressCap, params: &PricingParams) -> bool {
+        let spot_move = if cap.spot > Decimal::ZERO {
+            ((params.spot - cap.spot) / cap.spot).abs()
+        } else {
+            Decimal::MAX
+        };
+        spot_move > self.config.spot_tolerance
+            || (params.volatility - cap.volatility).abs() > self.config.vol_tolerance
+    }
+
+    fn compute(&self, params: &PricingParams) -> Result<StressCap> {
+        let value = params.price()?;
+        let mut long: (Decimal, Option<&str>) = (Decimal::ZERO, None);
+        let mut short: (Decimal, Option<&str>) = (Decimal::ZERO, None);
+        for scenario in &self.config.scenarios {
+            let shocked = PricingParams {
+                spot: params.spot * (Decimal::ONE + scenario.spot_shift),
+                volatility: (params.volatility + scenario.vol_shift).max(MIN_STRESS_VOLATILITY),
+                ..*params
+            };
+            let change = (shocked.price()? - value) * self.config.contract_size;
+            if -change > long.0 {
+                long = (-change, Some(&scenario.name));
+            }
+            if change > short.0 {
+                short = (change, Some(&scenario.name));
+            }
+        }
+        let max_size = |loss: Decimal| {
+            (loss > Decimal::ZERO).then(|| {
+                (self.config.loss_budget / loss)
+                    .floor()
+                    .to_u64()
+                    .unwrap_or(u64::MAX)
+            })
+        };
+        Ok(StressCap {
+            spot: params.spot,
+            volatility: params.volatility,
+            long_loss: long.0,
+            short_loss: short.0,
+            long_scenario: long.1.map(str::to_string),
+            short_scenario: short.1.map(str::to_string),
+            max_bid_size: max_size(long.0),
+            max_ask_size: max_size(short.0),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, StressCap>> {
+        self.caps
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quoting::SpreadCalculator;
+    use optionstratlib::OptionStyle;
+
+    fn config(budget: Decimal) -> StressSizeConfig {
+        StressSizeConfig {
+            loss_budget: budget,
+            ..StressSizeConfig::default()
+        }
+    }
+
+    fn call(strike: Decimal) -> PricingParams {
+        PricingParams::new(dec!(100), strike, dec!(30), dec!(0.5), OptionStyle::Call)
+    }
+
+    #[test]
+    fn test_wing_ask_capped_tighter_than_atm() {
+        let sizer = StressSizer::new(config(dec!(20))).unwrap();
+        let atm = sizer.cap("ATM", &call(dec!(100))).unwrap();
+        let wing = sizer.cap("WING", &call(dec!(140))).unwrap();
+
+        assert_eq!(atm.short_scenario.as_deref(), Some("squeeze"));
+        assert_eq!(atm.long_scenario.as_deref(), Some("crash"));
+        assert!(atm.max_ask_size.unwrap() >= 1);
+        // The wing's value multiplies in the squeeze, so its ask cap per unit
+        // of premium is far tighter.
+        assert!(wing.short_loss / wing.long_loss > atm.short_loss / atm.long_loss);
+
+        let quote = SpreadCalculator::new(100)
+            .generate(
+                &sizer
+                    .apply("ATM", &call(dec!(100)), QuoteParams::new(dec!(5), dec!(2)))
+                    .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(Some(quote.ask_size), atm.max_ask_size);
+    }
+
+    #[test]
+    fn test_recomputes_only_on_material_move() {
+        let sizer = StressSizer::new(config(dec!(100))).unwrap();
+        let first = sizer.cap("C", &call(dec!(100))).unwrap();
+
+        let small = PricingParams {
+            spot: dec!(100.5),
+            ..call(dec!(100))
+        };
+        assert_eq!(sizer.cap("C", &small).unwrap(), first);
+
+        let large = PricingParams {
+            spot: dec!(105),
+            ..call(dec!(100))
+        };
+        let moved = sizer.cap("C", &large).unwrap();
+        assert_eq!(moved.spot, dec!(105));
+        assert_eq!(sizer.len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(StressSizer::new(config(Decimal::ZERO)).is_err());
+        let no_scenarios = StressSizeConfig {
+            scenarios: Vec::new(),
+            ..StressSizeConfig::default()
+        };
+        assert!(StressSizer::new(no_scenarios).is_err());
+    }
+}
@@ -0,0 +1,258 @@
+//! Strike band module.
+//!
+//! This module provides the [`StrikeBand`], which maintains the band of
+//! quoted strikes of one chain around ATM as spot drifts. Strikes entering
+//! the band are onboarded (their books are created and a seeding callback
+//! places initial quotes) and strikes leaving it are offboarded (their
+//! quotes are pulled and, optionally, their books evicted).
+//!
+//! ## Hysteresis
+//!
+//! A strike is onboarded once it is within `strikes_each_side` listed
+//! strikes of ATM, but only offboarded once it is more than
+//! `strikes_each_side + hysteresis` strikes away. Spot oscillating around
+//! the midpoint between two strikes therefore does not add and remove the
+//! edge strikes on every update.
+
+use crate::error::{Error, Result};
+use crate::orderbook::{OptionChainOrderBook, StrikeOrderBook};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::{Mutex, MutexGuard};
+
+/// Configuration of a [`StrikeBand`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StrikeBandConfig {
+    /// Listed strikes quoted on each side of ATM.
+    pub strikes_each_side: usize,
+    /// Extra strikes beyond the band before a strike is offboarded.
+    pub hysteresis: usize,
+    /// Whether offboarded strikes' books are removed from the chain.
+    pub evict_books: bool,
+}
+
+impl Default for StrikeBandConfig {
+    fn default() -> Self {
+        Self {
+            strikes_each_side: 10,
+            hysteresis: 1,
+            evict_books: false,
+        }
+    }
+}
+
+/// Changes made by one band update.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct BandUpdate {
+    /// ATM strike the band is centered on.
+    pub atm: u64,
+    /// Strikes that entered the band, ascending.
+    pub onboarded: Vec<u64>,
+    /// Strikes that left the band, ascending.
+    pub offboarded: Vec<u64>,
+}
+
+impl BandUpdate {
+    /// Returns true if the band did not change.
+    #[must_use]
+    pub fn is_unchanged(&self) -> bool {
+        self.onboarded.is_empty() && self.offboarded.is_empty()
+    }
+}
+
+/// Band of quoted strikes re-centered on ATM as spot drifts.
+pub struct StrikeBand {
+    /// Configuration.
+    config: StrikeBandConfig,
+    /// Listed strikes, ascending.
+    listed: Vec<u64>,
+    /// Strikes currently in the band.
+    active: Mutex<BTreeSet<u64>>,
+}
+
+impl StrikeBand {
+    /// Creates an empty band over the listed strikes.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Band configuration
+    /// * `listed` - Strikes listed by the venue, in any order
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if no strike is listed.
+    pub fn new(config: StrikeBandConfig, mut listed: Vec<u64>) -> Result<Self> {
+        listed.sort_unstable();
+        listed.dedup();
+        if listed.is_empty() {
+            return Err(Error::configuration("strike band needs listed strikes"));
+        }
+        Ok(Self {
+            config,
+            listed,
+            active: Mutex::new(BTreeSet::new()),
+        })
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub const fn config(&self) -> &StrikeBandConfig {
+        &self.config
+    }
+
+    /// Returns the strikes currently in the band, ascending.
+    #[must_use]
+    pub fn active(&self) -> Vec<u64> {
+        self.lock().iter().copied().collect()
+    }
+
+    /// Returns true if a strike is in the band.
+    #[must_use]
+    pub fn contains(&self, strike: u64) -> bool {
+        self.lock().contains(&strike)
+    }
+
+    /// Returns the listed strike closest to spot.
+    #[must_use]
+    pub fn atm(&self, spot: u64) -> u64 {
+        self.listed[self.atm_index(spot)]
+    }
+
+    /// Re-centers the band on spot and applies the changes to a chain.
+    ///
+    /// Onboarded strikes are created in the chain and passed to `seed`.
+    /// Offboarded strikes have their orders cleared and, if configured, are
+    /// removed from the chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain` - Chain the band is quoted on
+    /// * `spot` - Current spot price
+    /// * `seed` - Places initial quotes on an onboarded strike
+    pub fn update<F: FnMut(&StrikeOrderBook)>(
+        &self,
+        chain: &OptionChainOrderBook,
+        spot: u64,
+        mut seed: F,
+    ) -> BandUpdate {
+        let update = self.recenter(spot);
+        for &strike in &update.offboarded {
+            if let Ok(book) = chain.get_strike(strike) {
+                book.clear();
+            }
+            if self.config.evict_books {
+                chain.strikes().remove(strike);
+            }
+        }
+        for &strike in &update.onboarded {
+            seed(&chain.get_or_create_strike(strike));
+        }
+        update
+    }
+
+    /// Re-centers the band on spot without touching any chain.
+    pub fn recenter(&self, spot: u64) -> BandUpdate {
+        let atm_index = self.atm_index(spot);
+        let keep = self.window(
+            atm_index,
+            self.config.strikes_each_side + self.config.hysteresis,
+        );
+        let band = self.window(atm_index, self.config.strikes_each_side);
+
+        let mut active = self.lock();
+        let offboarded: Vec<u64> = active
+            .iter()
+            .copied()
+            .filter(|s| !keep.contains(s))
+            .collect();
+        for strike in &offboarded {
+            active.remove(strike);
+        }
+        let onboarded: Vec<u64> = band.iter().copied().filter(|s| active.insert(*s)).collect();
+        BandUpdate {
+            atm: self.listed[atm_index],
+            onboarded,
+            offboarded,
+        }
+    }
+
+    /// Returns the listed strikes within `width` of the ATM index.
+    fn window(&self, atm_index: usize, width: usize) -> &[u64] {
+        let lo = atm_index.saturating_sub(width);
+        let hi = (atm_index + width + 1).min(self.listed.len());
+        &self.listed[lo..hi]
+    }
+
+    fn atm_index(&self, spot: u64) -> usize {
+        let i = self.listed.partition_point(|&k| k < spot);
+        let below_is_closer =
+            i > 0 && (i == self.listed.len() || spot - self.listed[i - 1] <= self.listed[i] - spot);
+        if below_is_closer { i - 1 } else { i }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeSet<u64>> {
+        self.active
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::ExpirationDate;
+    use optionstratlib::prelude::pos_or_panic;
+
+    fn band(hysteresis: usize, evict_books: bool) -> StrikeBand {
+        let config = StrikeBandConfig {
+            strikes_each_side: 2,
+            hysteresis,
+            evict_books,
+        };
+        StrikeBand::new(config, (80..=120).step_by(5).collect()).unwrap()
+    }
+
+    #[test]
+    fn test_initial_band_is_onboarded_and_seeded() {
+        let band = band(1, false);
+        let chain = OptionChainOrderBook::new("BTC", ExpirationDate::Days(pos_or_panic!(30.0)));
+        let mut seeded = Vec::new();
+        let update = band.update(&chain, 101, |s| seeded.push(s.strike()));
+
+        assert_eq!(update.atm, 100);
+        assert_eq!(update.onboarded, vec![90, 95, 100, 105, 110]);
+        assert_eq!(seeded, update.onboarded);
+        assert_eq!(chain.strike_prices(), vec![90, 95, 100, 105, 110]);
+    }
+
+    #[test]
+    fn test_hysteresis_avoids_thrashing() {
+        let band = band(1, false);
+        band.recenter(100);
+
+        // One strike up: 115 joins, 90 stays within the hysteresis.
+        let up = band.recenter(105);
+        assert_eq!(up.onboarded, vec![115]);
+        assert!(up.offboarded.is_empty());
+        // Back down: nothing changes.
+        assert!(band.recenter(100).is_unchanged());
+
+        // Two more strikes up puts 90 and 95 beyond band plus hysteresis.
+        let far = band.recenter(115);
+        assert_eq!(far.offboarded, vec![90, 95]);
+        assert_eq!(far.onboarded, vec![120]);
+    }
+
+    #[test]
+    fn test_offboarding_evicts_books() {
+        let band = band(0, true);
+        let chain = OptionChainOrderBook::new("BTC", ExpirationDate::Days(pos_or_panic!(30.0)));
+        band.update(&chain, 100, |_| {});
+        let update = band.update(&chain, 110, |_| {});
+
+        assert_eq!(update.offboarded, vec![90, 95]);
+        assert!(!chain.strikes().contains(90));
+        assert_eq!(chain.strike_prices(), band.active());
+        assert!(StrikeBand::new(StrikeBandConfig::default(), Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,260 @@
+//! Tick schedule module.
+//!
+//! This module provides [`TickSchedule`], a venue tick table whose tick size
+//! depends on the price (e.g. 0.01 below 3.00 and 0.05 from 3.00), and the
+//! [`TickScheduleRegistry`] holding one schedule per underlying.
+//!
+//! Book prices are integer ticks of the schedule's smallest tick; a price
+//! is valid when it is a multiple of the tick of the band it falls in.
+//! Every band must start on a multiple of its own tick, so rounding a
+//! price to its band never lands on an invalid price of the neighbouring
+//! band.
+
+use super::rounding::RoundingMode;
+use crate::error::{Error, Result};
+use orderbook_rs::Side;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One band of a tick table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickBand {
+    /// Lowest price of the band, inclusive.
+    pub from_price: Decimal,
+    /// Tick size from `from_price` up to the next band.
+    pub tick_size: Decimal,
+}
+
+impl TickBand {
+    /// Creates a band.
+    #[must_use]
+    pub const fn new(from_price: Decimal, tick_size: Decimal) -> Self {
+        Self {
+            from_price,
+            tick_size,
+        }
+    }
+}
+
+/// Price-dependent tick sizes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickSchedule {
+    /// Bands in increasing price order, the first starting at zero.
+    bands: Vec<TickBand>,
+}
+
+impl TickSchedule {
+    /// Creates a schedule from its bands.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if there are no bands, the first
+    /// band does not start at zero, bands are not in increasing price
+    /// order, a tick size is not positive, or a band does not start on a
+    /// multiple of its tick.
+    pub fn new(bands: Vec<TickBand>) -> Result<Self> {
+        let Some(first) = bands.first() else {
+            return Err(Error::configuration("tick schedule needs a band"));
+        };
+        if !first.from_price.is_zero() {
+            return Err(Error::configuration("first tick band must start at zero"));
+        }
+        for band in &bands {
+            if band.tick_size <= Decimal::ZERO {
+                return Err(Error::configuration("tick sizes must be positive"));
+            }
+            if !(band.from_price % band.tick_size).is_zero() {
+                return Err(Error::configuration(format!(
+                    "tick band at {} does not start on a multiple of {}",
+                    band.from_price, band.tick_size
+                )));
+            }
+        }
+        if bands.windows(2).any(|w| w[1].from_price <= w[0].from_price) {
+            return Err(Error::configuration(
+                "tick bands must be in increasing price order",
+            ));
+        }
+        Ok(Self { bands })
+    }
+
+    /// Creates a schedule with one tick size at every price.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the tick size is not positive.
+    pub fn flat(tick_size: Decimal) -> Result<Self> {
+        Self::new(vec![TickBand::new(Decimal::ZERO, tick_size)])
+    }
+
+    /// Returns the bands in increasing price order.
+    #[must_use]
+    pub fn bands(&self) -> &[TickBand] {
+        &self.bands
+    }
+
+    /// Returns the smallest tick size, the unit of book prices.
+    #[must_use]
+    pub fn min_tick(&self) -> Decimal {
+        self.bands
+            .iter()
+            .map(|b| b.tick_size)
+            .min()
+            .unwrap_or(Decimal::ONE)
+    }
+
+    /// Returns the tick size at a price.
+    #[must_use]
+    pub fn tick_size_at(&self, price: Decimal) -> Decimal {
+        self.bands
+            .iter()
+            .rev()
+            .find(|b| price >= b.from_price)
+            .unwrap_or(&self.bands[0])
+            .tick_size
+    }
+
+    /// Rounds a price for one side to the tick of its band.
+    ///
+    /// # Arguments
+    ///
+    /// * `price` - Price to round
+    /// * `side` - Side of the order (`Buy` for bids, `Sell` for asks)
+    /// * `mode` - Rounding direction
+    /// * `theo` - Theoretical value used by [`RoundingMode::Nearest`]
+    #[must_use]
+    pub fn round(&self, price: Decimal, side: Side, mode: RoundingMode, theo: Decimal) -> Decimal {
+        mode.round(price, side, self.tick_size_at(price), theo)
+    }
+
+    /// Returns true if a price is on the tick grid of its band.
+    #[must_use]
+    pub fn is_valid_price(&self, price: Decimal) -> bool {
+        price >= Decimal::ZERO && (price % self.tick_size_at(price)).is_zero()
+    }
+
+    /// Checks a book price in ticks of [`TickSchedule::min_tick`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the price is off the tick grid
+    /// of its band.
+    pub fn validate_ticks(&self, ticks: u128) -> Result<()> {
+        let price = Decimal::from(ticks) * self.min_tick();
+        if !self.is_valid_price(price) {
+            return Err(Error::validation(format!(
+                "price {price} is not a multiple of tick {}",
+                self.tick_size_at(price)
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Tick schedules per underlying with a default.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickScheduleRegistry {
+    /// Schedule of underlyings without their own.
+    default: TickSchedule,
+    /// Schedules by underlying symbol.
+    by_underlying: BTreeMap<String, TickSchedule>,
+}
+
+impl TickScheduleRegistry {
+    /// Creates a registry applying `default` to every underlying.
+    #[must_use]
+    pub fn new(default: TickSchedule) -> Self {
+        Self {
+            default,
+            by_underlying: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the schedule of an underlying.
+    #[must_use]
+    pub fn with_underlying(
+        mut self,
+        underlying: impl Into<String>,
+        schedule: TickSchedule,
+    ) -> Self {
+        self.by_underlying.insert(underlying.into(), schedule);
+        self
+    }
+
+    /// Returns the schedule of an underlying.
+    #[must_use]
+    pub fn for_underlying(&self, underlying: &str) -> &TickSchedule {
+        self.by_underlying.get(underlying).unwrap_or(&self.default)
+    }
+
+    /// Returns the schedule of a contract symbol of the form
+    /// `UNDERLYING-EXPIRY-STRIKE-C|P`; other symbols get the schedule of
+    /// the whole symbol as underlying.
+    #[must_use]
+    pub fn for_symbol(&self, symbol: &str) -> &TickSchedule {
+        let underlying = symbol.rsplitn(4, '-').nth(3).unwrap_or(symbol);
+        self.for_underlying(underlying)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn schedule() -> TickSchedule {
+        TickSchedule::new(vec![
+            TickBand::new(Decimal::ZERO, dec!(0.01)),
+            TickBand::new(dec!(3), dec!(0.05)),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_banded_rounding_and_validation() {
+        let schedule = schedule();
+        assert_eq!(schedule.min_tick(), dec!(0.01));
+        assert_eq!(schedule.tick_size_at(dec!(2.99)), dec!(0.01));
+        assert_eq!(schedule.tick_size_at(dec!(3)), dec!(0.05));
+
+        let passive = RoundingMode::Passive;
+        assert_eq!(
+            schedule.round(dec!(2.987), Side::Buy, passive, Decimal::ZERO),
+            dec!(2.98)
+        );
+        assert_eq!(
+            schedule.round(dec!(3.02), Side::Buy, passive, Decimal::ZERO),
+            dec!(3.00)
+        );
+        assert_eq!(
+            schedule.round(dec!(3.02), Side::Sell, passive, Decimal::ZERO),
+            dec!(3.05)
+        );
+
+        assert!(schedule.validate_ticks(299).is_ok());
+        assert!(schedule.validate_ticks(305).is_ok());
+        assert!(schedule.validate_ticks(302).is_err());
+    }
+
+    #[test]
+    fn test_invalid_schedules_and_registry() {
+        assert!(TickSchedule::new(Vec::new()).is_err());
+        assert!(TickSchedule::flat(Decimal::ZERO).is_err());
+        assert!(
+            TickSchedule::new(vec![
+                TickBand::new(Decimal::ZERO, dec!(0.01)),
+                TickBand::new(dec!(3.02), dec!(0.05)),
+            ])
+            .is_err()
+        );
+
+        let registry = TickScheduleRegistry::new(TickSchedule::flat(dec!(0.5)).unwrap())
+            .with_underlying("SPX", schedule());
+        assert_eq!(registry.for_symbol("SPX-20240329-5000-C"), &schedule());
+        assert_eq!(
+            registry.for_symbol("BTC-20240329-50000-P").min_tick(),
+            dec!(0.5)
+        );
+    }
+}
@@ -0,0 +1,348 @@
+//! Edge tier module.
+//!
+//! This module provides tiered quoting: each contract keeps a small set of
+//! pre-computed quote levels at increasing edge, measured in vol ticks
+//! (e.g. 1, 2 and 4 ticks of vega either side of the reservation price),
+//! and the [`TieredQuoter`] picks which tier to display per side from the
+//! competition, without repricing.
+//!
+//! ## Selection
+//!
+//! Per side, the widest tier that still joins or improves the external
+//! best price is displayed, so edge is kept whenever the market is wide.
+//! If even the tightest tier is behind the external best, the tightest
+//! tier is displayed rather than giving up more edge. Without an external
+//! price on a side, the widest tier is displayed.
+
+use super::generated::GeneratedQuote;
+use crate::error::{Error, Result};
+use crossbeam_skiplist::SkipMap;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Configuration of the edge tiers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeTierConfig {
+    /// Edge of each tier in vol ticks, strictly increasing.
+    pub tiers: Vec<Decimal>,
+    /// Vol points per vol tick.
+    pub vol_tick: Decimal,
+    /// Minimum edge per side in price units.
+    pub min_edge: Decimal,
+}
+
+impl Default for EdgeTierConfig {
+    fn default() -> Self {
+        Self {
+            tiers: vec![Decimal::ONE, Decimal::TWO, Decimal::from(4)],
+            vol_tick: Decimal::new(1, 1),
+            min_edge: Decimal::ZERO,
+        }
+    }
+}
+
+impl EdgeTierConfig {
+    /// Validates the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if there is no tier, tiers are
+    /// not positive and strictly increasing, the vol tick is not positive
+    /// or the minimum edge is negative.
+    pub fn validate(&self) -> Result<()> {
+        if self.tiers.is_empty() {
+            return Err(Error::configuration("edge tiers must not be empty"));
+        }
+        if self.tiers[0] <= Decimal::ZERO || self.tiers.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(Error::configuration(
+                "edge tiers must be positive and strictly increasing",
+            ));
+        }
+        if self.vol_tick <= Decimal::ZERO {
+            return Err(Error::configuration("vol tick must be positive"));
+        }
+        if self.min_edge < Decimal::ZERO {
+            return Err(Error::configuration("minimum edge must not be negative"));
+        }
+        Ok(())
+    }
+}
+
+/// Tier displayed on each side, where zero is the tightest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TierSelection {
+    /// Bid tier.
+    pub bid_tier: usize,
+    /// Ask tier.
+    pub ask_tier: usize,
+}
+
+/// Pre-computed quote levels of one contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TieredQuote {
+    /// Model quote the tiers are centered on.
+    pub base: GeneratedQuote,
+    /// Edge per side of each tier in price units, increasing.
+    pub edges: Vec<Decimal>,
+}
+
+impl TieredQuote {
+    /// Builds the tiers around a model quote's reservation price.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - Model quote supplying the reservation price and sizes
+    /// * `vega` - Vega of one contract per vol point
+    /// * `config` - Edge tiers
+    #[must_use]
+    pub fn build(base: GeneratedQuote, vega: Decimal, config: &EdgeTierConfig) -> Self {
+        let per_tick = vega.abs() * config.vol_tick;
+        Self {
+            base,
+            edges: config
+                .tiers
+                .iter()
+                .map(|t| (per_tick * t).max(config.min_edge))
+                .collect(),
+        }
+    }
+
+    /// Returns the number of tiers.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Returns true if there is no tier.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// Returns the bid price of a tier, clamped to the widest.
+    #[must_use]
+    pub fn bid(&self, tier: usize) -> Decimal {
+        (self.base.reservation_price - self.edge(tier)).max(Decimal::ZERO)
+    }
+
+    /// Returns the ask price of a tier, clamped to the widest.
+    #[must_use]
+    pub fn ask(&self, tier: usize) -> Decimal {
+        self.base.reservation_price + self.edge(tier)
+    }
+
+    /// Selects the tier per side against the external best prices.
+    ///
+    /// # Arguments
+    ///
+    /// * `external_bid` - Best bid excluding ours, if any
+    /// * `external_ask` - Best ask excluding ours, if any
+    #[must_use]
+    pub fn select(
+        &self,
+        external_bid: Option<Decimal>,
+        external_ask: Option<Decimal>,
+    ) -> TierSelection {
+        let widest = self.len().saturating_sub(1);
+        let pick = |competitive: &dyn Fn(usize) -> bool| {
+            (0..self.len()).rev().find(|&t| competitive(t)).unwrap_or(0)
+        };
+        TierSelection {
+            bid_tier: external_bid.map_or(widest, |best| pick(&|t| self.bid(t) >= best)),
+            ask_tier: external_ask.map_or(widest, |best| pick(&|t| self.ask(t) <= best)),
+        }
+    }
+
+    /// Returns the quote displayed for a selection.
+    #[must_use]
+    pub fn display(&self, selection: TierSelection) -> GeneratedQuote {
+        GeneratedQuote {
+            bid_price: self.bid(selection.bid_tier),
+            ask_price: self.ask(selection.ask_tier),
+            ..self.base
+        }
+    }
+
+    fn edge(&self, tier: usize) -> Decimal {
+        self.edges[tier.min(self.edges.len() - 1)]
+    }
+}
+
+/// Tiered quotes of every contract and the tiers currently displayed.
+///
+/// Uses `SkipMap` for thread-safe concurrent access across contracts.
+pub struct TieredQuoter {
+    /// Edge tiers.
+    config: EdgeTierConfig,
+    /// Tiers by symbol.
+    quotes: SkipMap<String, TieredQuote>,
+    /// Displayed selection by symbol.
+    selections: SkipMap<String, TierSelection>,
+    /// Number of selection changes.
+    switches: AtomicU64,
+}
+
+impl TieredQuoter {
+    /// Creates a quoter with no contract.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the tiers are invalid.
+    pub fn new(config: EdgeTierConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            quotes: SkipMap::new(),
+            selections: SkipMap::new(),
+            switches: AtomicU64::new(0),
+        })
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub const fn config(&self) -> &EdgeTierConfig {
+        &self.config
+    }
+
+    /// Recomputes the tiers of a contract after a repricing.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - Contract symbol
+    /// * `base` - Model quote
+    /// * `vega` - Vega of one contract per vol point
+    pub fn update(&self, symbol: impl Into<String>, base: GeneratedQuote, vega: Decimal) {
+        self.quotes
+            .insert(symbol.into(), TieredQuote::build(base, vega, &self.config));
+    }
+
+    /// Returns the tiers of a contract.
+    #[must_use]
+    pub fn tiers(&self, symbol: &str) -> Option<TieredQuote> {
+        self.quotes.get(symbol).map(|e| e.value().clone())
+    }
+
+    /// Selects the tiers to display against the competition.
+    ///
+    /// Returns `None` if the contract has no tiers.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - Contract symbol
+    /// * `external_bid` - Best bid excluding ours, if any
+    /// * `external_ask` - Best ask excluding ours, if any
+    pub fn select(
+        &self,
+        symbol: &str,
+        external_bid: Option<Decimal>,
+        external_ask: Option<Decimal>,
+    ) -> Option<(TierSelection, GeneratedQuote)> {
+        let entry = self.quotes.get(symbol)?;
+        let tiers = entry.value();
+        let selection = tiers.select(external_bid, external_ask);
+        if self
+            .selections
+            .get(symbol)
+            .is_some_and(|e| *e.value() != selection)
+        {
+            self.switches.fetch_add(1, Ordering::Relaxed);
+        }
+        self.selections.insert(symbol.to_string(), selection);
+        Some((selection, tiers.display(selection)))
+    }
+
+    /// Returns the displayed selection of a contract.
+    #[must_use]
+    pub fn selection(&self, symbol: &str) -> Option<TierSelection> {
+        self.selections.get(symbol).map(|e| *e.value())
+    }
+
+    /// Returns the number of selection changes since creation.
+    #[must_use]
+    pub fn switches(&self) -> u64 {
+        self.switches.load(Ordering::Relaxed)
+    }
+
+    /// Stops quoting a contract.
+    pub fn remove(&self, symbol: &str) -> bool {
+        self.selections.remove(symbol);
+        self.quotes.remove(symbol).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn base() -> GeneratedQuote {
+        GeneratedQuote {
+            theo: dec!(5),
+            reservation_price: dec!(5),
+            bid_price: dec!(4.9),
+            ask_price: dec!(5.1),
+            bid_size: 10,
+            ask_size: 10,
+        }
+    }
+
+    #[test]
+    fn test_tiers_from_vega() {
+        // Vega 0.5 per vol point and 0.1 vol points per tick: 0.05 per tick.
+        let tiers = TieredQuote::build(base(), dec!(0.5), &EdgeTierConfig::default());
+        assert_eq!(tiers.edges, vec![dec!(0.05), dec!(0.10), dec!(0.20)]);
+        assert_eq!(tiers.bid(2), dec!(4.80));
+        assert_eq!(tiers.ask(9), dec!(5.20));
+    }
+
+    #[test]
+    fn test_selection_follows_competition() {
+        let quoter = TieredQuoter::new(EdgeTierConfig::default()).unwrap();
+        quoter.update("C1", base(), dec!(0.5));
+
+        // Wide market: widest tiers still join the external best.
+        let (selection, quote) = quoter.select("C1", Some(dec!(4.7)), None).unwrap();
+        assert_eq!(
+            selection,
+            TierSelection {
+                bid_tier: 2,
+                ask_tier: 2
+            }
+        );
+        assert_eq!(quote.bid_price, dec!(4.80));
+
+        // Competitor bids 4.92: only the tightest tier joins.
+        let (selection, quote) = quoter
+            .select("C1", Some(dec!(4.92)), Some(dec!(5.1)))
+            .unwrap();
+        assert_eq!(
+            selection,
+            TierSelection {
+                bid_tier: 0,
+                ask_tier: 1
+            }
+        );
+        assert_eq!(quote.ask_price, dec!(5.10));
+        assert_eq!(quoter.switches(), 1);
+
+        // Competitor inside our tightest tier: keep the minimum edge.
+        let (selection, _) = quoter.select("C1", Some(dec!(4.99)), None).unwrap();
+        assert_eq!(selection.bid_tier, 0);
+        assert!(quoter.select("P1", None, None).is_none());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let config = EdgeTierConfig {
+            tiers: vec![dec!(2), dec!(1)],
+            ..EdgeTierConfig::default()
+        };
+        assert!(TieredQuoter::new(config).is_err());
+        let config = EdgeTierConfig {
+            vol_tick: Decimal::ZERO,
+            ..EdgeTierConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
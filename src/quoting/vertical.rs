@@ -0,0 +1,213 @@
+//! Vertical spread quoting module.
+//!
+//! This module provides the [`VerticalQuoter`], which quotes defined-risk
+//! vertical spreads (bull call and bear put spreads) as packages on a combo
+//! book. The package is valued and spread by the [`ComboQuoter`], then held
+//! to the spread's payoff bounds and sized by the loss budget.
+//!
+//! ## Package risk
+//!
+//! A debit vertical pays between zero and the strike width at expiry, so
+//! the risk of each side is known when the quote is placed:
+//!
+//! - Buying the package at the bid loses at most the bid price per unit
+//! - Selling it at the ask loses at most the width less the ask price
+//!
+//! Each side shows the largest size whose worst-case loss fits within the
+//! maximum package loss, capped by the spread model's size. A bid at or
+//! below zero or an ask at or above the width is not quoted.
+
+use super::combo::{ComboInstrument, ComboQuoter};
+use super::generated::GeneratedQuote;
+use crate::error::{Error, Result};
+use crate::pricing::{Greeks, VolatilitySurface};
+use rust_decimal::Decimal;
+
+/// Package quoting of vertical spreads under a loss budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerticalQuoter {
+    /// Values and spreads the package.
+    quoter: ComboQuoter,
+    /// Worst-case loss allowed on each side of the quote, in price units.
+    max_package_loss: Decimal,
+}
+
+impl VerticalQuoter {
+    /// Creates a vertical spread quoter.
+    ///
+    /// # Arguments
+    ///
+    /// * `quoter` - Combo quoter valuing and spreading the package
+    /// * `max_package_loss` - Worst-case loss allowed on each side of the
+    ///   quote, in price units
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the loss budget is not
+    /// positive.
+    pub fn new(quoter: ComboQuoter, max_package_loss: Decimal) -> Result<Self> {
+        if max_package_loss <= Decimal::ZERO {
+            return Err(Error::configuration("max package loss must be positive"));
+        }
+        Ok(Self {
+            quoter,
+            max_package_loss,
+        })
+    }
+
+    /// Returns the worst-case loss allowed on each side of the quote.
+    #[must_use]
+    pub const fn max_package_loss(&self) -> Decimal {
+        self.max_package_loss
+    }
+
+    /// Quotes one package of a vertical spread.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertical` - Debit vertical spread
+    /// * `spot` - Spot price of the underlying
+    /// * `rate` - Risk-free rate
+    /// * `surface` - Volatility surface the legs are read from
+    /// * `spot_volatility` - Implied volatility of the underlying
+    /// * `leg_positions` - Aggregated Greeks of the leg positions
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the combo is not a debit
+    /// vertical spread, or the errors of [`ComboInstrument::value`] and
+    /// [`ComboQuoter::quote`].
+    pub fn quote(
+        &self,
+        vertical: &ComboInstrument,
+        spot: Decimal,
+        rate: Decimal,
+        surface: &VolatilitySurface,
+        spot_volatility: Decimal,
+        leg_positions: &Greeks,
+    ) -> Result<GeneratedQuote> {
+        let Some(width) = vertical.vertical_width() else {
+            return Err(Error::configuration(format!(
+                "combo {} is not a debit vertical spread",
+                vertical.symbol
+            )));
+        };
+        let valuation = vertical.value(spot, rate, surface)?;
+        let mut quote = self
+            .quoter
+            .quote(&valuation, spot, spot_volatility, leg_positions)?;
+        quote.theo = quote.theo.clamp(Decimal::ZERO, width);
+        quote.ask_price = quote.ask_price.min(width);
+        quote.bid_size = self.size(quote.bid_size, quote.bid_price);
+        quote.ask_size = self.size(quote.ask_size, width - quote.ask_price);
+        Ok(quote)
+    }
+
+    /// Returns the size whose worst-case loss fits the budget, capped by
+    /// the model size; nothing if the side risks nothing.
+    fn size(&self, model_size: u64, unit_loss: Decimal) -> u64 {
+        if unit_loss <= Decimal::ZERO {
+            return 0;
+        }
+        let affordable = (self.max_package_loss / unit_loss).floor();
+        u64::try_from(affordable).map_or(model_size, |n| n.min(model_size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::SmileParams;
+    use crate::quoting::SpreadCalculator;
+    use rust_decimal_macros::dec;
+
+    fn surface() -> VolatilitySurface {
+        VolatilitySurface::new().with_pillar(
+            dec!(30),
+            SmileParams::new(dec!(0.5), Decimal::ZERO, Decimal::ZERO),
+        )
+    }
+
+    fn quoter(max_package_loss: Decimal) -> VerticalQuoter {
+        let combo = ComboQuoter::new(SpreadCalculator::new(100), dec!(0.5)).unwrap();
+        VerticalQuoter::new(combo, max_package_loss).unwrap()
+    }
+
+    #[test]
+    fn test_bull_call_spread_sized_by_package_loss() {
+        let vertical = ComboInstrument::bull_call_spread(
+            "BTC-BCS-100-110",
+            ("BTC-C-100", dec!(100)),
+            ("BTC-C-110", dec!(110)),
+            dec!(30),
+        )
+        .unwrap();
+        let quote = quoter(dec!(100))
+            .quote(
+                &vertical,
+                dec!(105),
+                Decimal::ZERO,
+                &surface(),
+                dec!(0.5),
+                &Greeks::zero(),
+            )
+            .unwrap();
+
+        assert!(quote.bid_price > Decimal::ZERO && quote.ask_price <= dec!(10));
+        assert!(quote.bid_price < quote.theo && quote.theo < quote.ask_price);
+        // Near the money each side risks about half the width per package.
+        let bid_loss = quote.bid_price * Decimal::from(quote.bid_size);
+        let ask_loss = (dec!(10) - quote.ask_price) * Decimal::from(quote.ask_size);
+        assert!(bid_loss <= dec!(100) && ask_loss <= dec!(100));
+        assert!(quote.bid_size > 0 && quote.bid_size < 100);
+        assert!(quote.ask_size > 0 && quote.ask_size < 100);
+    }
+
+    #[test]
+    fn test_deep_spread_pulls_riskless_side() {
+        // Both puts far in the money: the bear put spread is worth about
+        // the width, so selling it at the width risks nothing and is not
+        // quoted.
+        let vertical = ComboInstrument::bear_put_spread(
+            "BTC-BPS-110-100",
+            ("BTC-P-110", dec!(110)),
+            ("BTC-P-100", dec!(100)),
+            dec!(30),
+        )
+        .unwrap();
+        let quote = quoter(dec!(1000))
+            .quote(
+                &vertical,
+                dec!(50),
+                Decimal::ZERO,
+                &surface(),
+                dec!(0.5),
+                &Greeks::zero(),
+            )
+            .unwrap();
+        assert_eq!(quote.ask_price, dec!(10));
+        assert_eq!(quote.ask_size, 0);
+        assert!(quote.bid_size > 0);
+
+        let straddle = ComboInstrument::straddle("S", "C", "P", dec!(100), dec!(30)).unwrap();
+        assert!(
+            quoter(dec!(1))
+                .quote(
+                    &straddle,
+                    dec!(100),
+                    Decimal::ZERO,
+                    &surface(),
+                    dec!(0.5),
+                    &Greeks::zero(),
+                )
+                .is_err()
+        );
+        assert!(
+            VerticalQuoter::new(
+                ComboQuoter::new(SpreadCalculator::new(1), dec!(1)).unwrap(),
+                dec!(0)
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,74 @@
+//! Journal module.
+//!
+//! This module provides the [`JournalEntry`] state changes recorded by a
+//! [`super::JournaledState`], in sequence, so that state can be rebuilt
+//! from a snapshot plus the entries recorded after it.
+
+use crate::inventory::TiedTrade;
+use crate::pricing::Greeks;
+use orderbook_rs::{OrderId, Side};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// A resting order tracked for recovery.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenOrder {
+    /// Order id in the book.
+    pub order_id: OrderId,
+    /// Contract symbol.
+    pub symbol: String,
+    /// Buy or Sell side.
+    pub side: Side,
+    /// Limit price in smallest units.
+    pub price: u128,
+    /// Remaining quantity in smallest units.
+    pub quantity: u64,
+}
+
+/// One state change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JournalEntry {
+    /// An option or hedge trade booked into inventory.
+    Trade {
+        /// Contract symbol.
+        symbol: String,
+        /// Signed quantity (positive buys).
+        quantity: Decimal,
+        /// Trade price.
+        price: Decimal,
+    },
+    /// A tied-to-stock package booked into inventory.
+    TiedTrade(TiedTrade),
+    /// New per-contract Greeks for a position.
+    Greeks {
+        /// Contract symbol.
+        symbol: String,
+        /// Greeks of one long contract.
+        greeks: Greeks,
+    },
+    /// An order started resting.
+    OrderOpened(OpenOrder),
+    /// The remaining quantity of a resting order changed.
+    OrderReduced {
+        /// Order id in the book.
+        order_id: OrderId,
+        /// New remaining quantity.
+        quantity: u64,
+    },
+    /// An order stopped resting.
+    OrderClosed {
+        /// Order id in the book.
+        order_id: OrderId,
+    },
+}
+
+/// A journal entry with its position in the journal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalRecord {
+    /// Sequence number, starting at one.
+    pub sequence: u64,
+    /// Time the entry was applied in milliseconds.
+    pub timestamp_ms: u64,
+    /// The state change.
+    pub entry: JournalEntry,
+}
@@ -0,0 +1,36 @@
+//! Recovery module.
+//!
+//! This module provides journaled state for crash recovery and a restart
+//! drill that proves recovery works: the inventory and open orders of an
+//! underlying are rebuilt from a snapshot plus the journal tail and diffed
+//! against the live state.
+//!
+//! ## Components
+//!
+//! - [`JournaledState`]: Inventory and open orders whose changes are journaled
+//! - [`StateSnapshot`]: Point-in-time copy a state can be restored from
+//! - [`SystemSnapshot`]: Versioned snapshot, saved through a [`StateStore`], of every chain, book, inventory and realized P&L ledger
+//! - [`JournalEntry`]: A journaled state change
+//! - [`JournalStore`], [`StateStore`]: Pluggable persistence of any [`SequencedRecord`] journal and serializable snapshot, with file-backed implementations
+//! - [`SegmentedJournalStore`]: Journal store of checksummed segment files with torn-tail repair and truncation
+//! - [`ConsistencyWatchdog`]: Periodic order and position consistency check with repairs
+//! - [`restart_drill`]: Simulated crash and restore, reported as a [`DrillReport`]
+
+mod drill;
+mod journal;
+mod segmented;
+mod state;
+mod store;
+mod system;
+mod watchdog;
+
+pub use drill::{Discrepancy, DiscrepancyKind, DrillReport, restart_drill};
+pub use journal::{JournalEntry, JournalRecord, OpenOrder};
+pub use segmented::{SegmentConfig, SegmentedJournalStore};
+pub use state::{JournaledState, StateSnapshot};
+pub use store::{FileJournalStore, FileStateStore, JournalStore, SequencedRecord, StateStore};
+pub use system::{BookState, InventoryState, SYSTEM_SNAPSHOT_VERSION, SystemSnapshot};
+pub use watchdog::{
+    ConsistencyWatchdog, Divergence, DivergenceKind, RepairAction, VenueStateSource,
+    WatchdogConfig, WatchdogReport,
+};
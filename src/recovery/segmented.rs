@@ -0,0 +1,515 @@
+//! Segmented journal store module.
+//!
+//! This module provides [`SegmentedJournalStore`], a [`JournalStore`] that
+//! writes records into a directory of checksummed segment files. It backs
+//! the order book [`EventJournal`](crate::orderbook::EventJournal) and
+//! stores any [`SequencedRecord`].
+//!
+//! ## Format
+//!
+//! Segment files are named after the sequence of their first record. Each
+//! record is framed as a little-endian `u32` payload length, a
+//! little-endian `u32` CRC-32 of the payload, then the payload, the JSON
+//! record. A segment is closed once it reaches the configured size and the
+//! next record opens a new one.
+//!
+//! On open, a torn or corrupt record at the end of the last segment, left
+//! by a crash mid-write, is truncated away; corruption anywhere else is an
+//! error. A write that fails mid-record is cut off before the append
+//! returns its error, or before the next append if that fails too, so later
+//! records never follow a torn one.
+//!
+//! ## Truncation
+//!
+//! [`JournalStore::truncate_through`] deletes the segments a snapshot fully
+//! covers and rewrites the one it covers in part. Truncating every record
+//! leaves an empty segment named after the next sequence, so the sequence
+//! keeps counting up after a reopen.
+
+use super::store::{JournalStore, SequencedRecord};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// Extension of segment files.
+const SEGMENT_EXTENSION: &str = "wal";
+
+/// Bytes of the length and checksum preceding each payload.
+const FRAME_HEADER_LEN: usize = 8;
+
+/// CRC-32 (IEEE) lookup table.
+const CRC_TABLE: [u32; 256] = crc_table();
+
+/// Configuration of a [`SegmentedJournalStore`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentConfig {
+    /// Size in bytes at which a segment is closed.
+    pub max_segment_bytes: u64,
+    /// Whether every append is synced to disk before it returns.
+    pub sync_writes: bool,
+}
+
+impl Default for SegmentConfig {
+    fn default() -> Self {
+        Self {
+            max_segment_bytes: 64 * 1024 * 1024,
+            sync_writes: true,
+        }
+    }
+}
+
+impl SegmentConfig {
+    /// Validates the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the segment size is zero.
+    pub fn validate(&self) -> Result<()> {
+        if self.max_segment_bytes == 0 {
+            return Err(Error::configuration(
+                "journal max_segment_bytes must be positive",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Segment being written.
+struct ActiveSegment {
+    /// Open file.
+    file: File,
+    /// Bytes written.
+    len: u64,
+    /// Whether a failed write may have left a torn frame past `len`.
+    torn: bool,
+}
+
+/// Mutable state of a [`SegmentedJournalStore`].
+#[derive(Default)]
+struct SegmentState {
+    /// Segment being written, opened on the next append if `None`.
+    segment: Option<ActiveSegment>,
+    /// Segments opened since open.
+    rotations: u64,
+}
+
+/// Journal store writing checksummed records into segment files.
+pub struct SegmentedJournalStore {
+    /// Segment directory.
+    dir: PathBuf,
+    /// Store configuration.
+    config: SegmentConfig,
+    /// Active segment, guarding appends and truncations.
+    state: Mutex<SegmentState>,
+}
+
+impl SegmentedJournalStore {
+    /// Opens the store in `dir`, creating the directory if needed and
+    /// truncating a torn record at the end of the last segment.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the configuration is invalid,
+    /// or `Error::IoError` if the directory cannot be read or the torn
+    /// record cannot be truncated.
+    pub fn open(dir: impl Into<PathBuf>, config: SegmentConfig) -> Result<Self> {
+        config.validate()?;
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        if let Some((_, path)) = segments(&dir)?.pop() {
+            let bytes = fs::read(&path)?;
+            let (_, valid) = frames(&bytes);
+            if valid < bytes.len() {
+                OpenOptions::new()
+                    .write(true)
+                    .open(&path)?
+                    .set_len(valid as u64)?;
+            }
+        }
+        Ok(Self {
+            dir,
+            config,
+            state: Mutex::new(SegmentState::default()),
+        })
+    }
+
+    /// Returns the segment directory.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the number of segment files.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::IoError` if the directory cannot be read.
+    pub fn segment_count(&self) -> Result<usize> {
+        Ok(segments(&self.dir)?.len())
+    }
+
+    /// Returns the number of segments opened since open.
+    #[must_use]
+    pub fn rotations(&self) -> u64 {
+        self.lock().rotations
+    }
+
+    fn open_segment(&self, first_sequence: u64) -> Result<ActiveSegment> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(segment_path(&self.dir, first_sequence))?;
+        let len = file.metadata()?.len();
+        Ok(ActiveSegment {
+            file,
+            len,
+            torn: false,
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SegmentState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl<R: SequencedRecord> JournalStore<R> for SegmentedJournalStore {
+    fn append(&self, records: &[R]) -> Result<()> {
+        let mut state = self.lock();
+        // Cut a torn frame off before writing, so no record follows it.
+        if let Some(segment) = state.segment.as_mut()
+            && segment.torn
+        {
+            segment.file.set_len(segment.len)?;
+            segment.torn = false;
+        }
+        for record in records {
+            let mut frame = Vec::new();
+            encode(record, &mut frame)?;
+            let full = state.segment.as_ref().is_some_and(|s| {
+                s.len > 0 && s.len + frame.len() as u64 > self.config.max_segment_bytes
+            });
+            if full || state.segment.is_none() {
+                if self.config.sync_writes
+                    && let Some(closed) = state.segment.as_ref()
+                {
+                    closed.file.sync_data()?;
+                }
+                state.segment = Some(self.open_segment(record.sequence())?);
+                state.rotations += 1;
+            }
+            if let Some(segment) = state.segment.as_mut() {
+                if let Err(error) = segment.file.write_all(&frame) {
+                    segment.torn = segment.file.set_len(segment.len).is_err();
+                    return Err(error.into());
+                }
+                segment.len += frame.len() as u64;
+            }
+        }
+        if self.config.sync_writes
+            && let Some(segment) = state.segment.as_ref()
+        {
+            segment.file.sync_data()?;
+        }
+        Ok(())
+    }
+
+    fn iterate(&self, sequence: u64, f: &mut dyn FnMut(R) -> Result<()>) -> Result<()> {
+        let segments = segments(&self.dir)?;
+        let mut segments = segments.iter().peekable();
+        while let Some((_, path)) = segments.next() {
+            // Segments after the next one's start hold nothing newer.
+            if segments
+                .peek()
+                .is_some_and(|(next, _)| *next <= sequence + 1)
+            {
+                continue;
+            }
+            for record in read_segment::<R>(path)? {
+                if record.sequence() > sequence {
+                    f(record)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn truncate_through(&self, sequence: u64) -> Result<()> {
+        let mut state = self.lock();
+        let segments = segments(&self.dir)?;
+        for (index, (first, path)) in segments.iter().enumerate() {
+            let next = segments.get(index + 1).map(|(next, _)| *next);
+            if next.is_some_and(|next| next <= sequence + 1) {
+                fs::remove_file(path)?;
+                continue;
+            }
+            if *first > sequence {
+                break;
+            }
+            // The segment holds records on both sides of `sequence`, or is
+            // the last one: keep its newer records in a segment named after
+            // the first of them.
+            let records: Vec<R> = read_segment(path)?;
+            let kept: Vec<&R> = records.iter().filter(|r| r.sequence() > sequence).collect();
+            if kept.len() == records.len() {
+                break;
+            }
+            let last = records.last().map_or(first.saturating_sub(1), R::sequence);
+            let kept_first = kept.first().map_or(last + 1, |r| r.sequence());
+            let mut bytes = Vec::new();
+            for record in kept {
+                encode(record, &mut bytes)?;
+            }
+            if next.is_none() {
+                state.segment = None;
+            }
+            let tmp = path.with_extension("tmp");
+            {
+                let mut file = File::create(&tmp)?;
+                file.write_all(&bytes)?;
+                file.sync_data()?;
+            }
+            fs::rename(&tmp, segment_path(&self.dir, kept_first))?;
+            if kept_first != *first {
+                fs::remove_file(path)?;
+            }
+            break;
+        }
+        Ok(())
+    }
+
+    fn last_sequence(&self) -> Result<u64> {
+        let Some((first, path)) = segments(&self.dir)?.pop() else {
+            return Ok(0);
+        };
+        let records: Vec<R> = read_segment(&path)?;
+        Ok(records.last().map_or(first.saturating_sub(1), R::sequence))
+    }
+
+    fn first_sequence(&self) -> Result<Option<u64>> {
+        // Segments are named after their first record; only the last one
+        // can be empty.
+        let segments = segments(&self.dir)?;
+        match segments.as_slice() {
+            [] => Ok(None),
+            [(_, path)] if fs::metadata(path)?.len() == 0 => Ok(None),
+            [(first, _), ..] => Ok(Some(*first)),
+        }
+    }
+}
+
+/// Returns the path of the segment starting at a sequence.
+fn segment_path(dir: &Path, first_sequence: u64) -> PathBuf {
+    dir.join(format!("{first_sequence:020}.{SEGMENT_EXTENSION}"))
+}
+
+/// Returns the segment files of a directory with their first sequence,
+/// oldest first.
+fn segments(dir: &Path) -> Result<Vec<(u64, PathBuf)>> {
+    let mut segments = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(SEGMENT_EXTENSION) {
+            continue;
+        }
+        if let Some(first) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.parse().ok())
+        {
+            segments.push((first, path));
+        }
+    }
+    segments.sort_unstable();
+    Ok(segments)
+}
+
+/// Reads every record of a segment.
+///
+/// # Errors
+///
+/// Returns `Error::IoError` if the segment cannot be read, or
+/// `Error::ValidationError` if it is corrupt.
+fn read_segment<R: SequencedRecord>(path: &Path) -> Result<Vec<R>> {
+    let bytes = fs::read(path)?;
+    let (payloads, valid) = frames(&bytes);
+    let corrupt = || {
+        Error::validation(format!(
+            "journal segment {} is corrupt at offset {valid}",
+            path.display()
+        ))
+    };
+    if valid < bytes.len() {
+        return Err(corrupt());
+    }
+    payloads
+        .into_iter()
+        .map(|payload| serde_json::from_slice(payload).map_err(|_| corrupt()))
+        .collect()
+}
+
+/// Appends the frame of a record to `out`.
+fn encode<R: Serialize>(record: &R, out: &mut Vec<u8>) -> Result<()> {
+    let payload = serde_json::to_vec(record)?;
+    let len = u32::try_from(payload.len())
+        .map_err(|_| Error::validation("journal record exceeds 4 GiB"))?;
+    out.reserve(FRAME_HEADER_LEN + payload.len());
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(&crc32(&payload).to_le_bytes());
+    out.extend_from_slice(&payload);
+    Ok(())
+}
+
+/// Splits a segment into record payloads up to the first torn or corrupt
+/// frame, returning them with the length of the valid prefix.
+fn frames(bytes: &[u8]) -> (Vec<&[u8]>, usize) {
+    let mut payloads = Vec::new();
+    let mut offset = 0;
+    while let Some(header) = bytes.get(offset..offset + FRAME_HEADER_LEN) {
+        let len = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let crc = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        let start = offset + FRAME_HEADER_LEN;
+        let Some(payload) = bytes.get(start..start + len) else {
+            break;
+        };
+        if crc32(payload) != crc {
+            break;
+        }
+        payloads.push(payload);
+        offset = start + len;
+    }
+    (payloads, offset)
+}
+
+/// Builds the CRC-32 (IEEE, reflected) lookup table.
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                0xEDB8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Returns the CRC-32 (IEEE) of the bytes.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &b| {
+        CRC_TABLE[((crc ^ u32::from(b)) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recovery::{JournalEntry, JournalRecord};
+    use rust_decimal_macros::dec;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ocob-seg-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn record(sequence: u64) -> JournalRecord {
+        JournalRecord {
+            sequence,
+            timestamp_ms: sequence,
+            entry: JournalEntry::Trade {
+                symbol: "C1".to_string(),
+                quantity: dec!(1),
+                price: dec!(5),
+            },
+        }
+    }
+
+    fn sequences(store: &dyn JournalStore) -> Vec<u64> {
+        store.load(0).unwrap().iter().map(|r| r.sequence).collect()
+    }
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_truncate_through_keeps_sequence() {
+        let dir = temp_dir("truncate");
+        let config = SegmentConfig {
+            max_segment_bytes: 256,
+            sync_writes: false,
+        };
+        let segmented = SegmentedJournalStore::open(&dir, config).unwrap();
+        let store: &dyn JournalStore = &segmented;
+        store
+            .append(&(1..=8).map(record).collect::<Vec<_>>())
+            .unwrap();
+        let before = segmented.segment_count().unwrap();
+        assert!(before > 2);
+
+        // Whole segments below the checkpoint go, the straddling one is cut.
+        store.truncate_through(5).unwrap();
+        assert_eq!(sequences(store), vec![6, 7, 8]);
+        assert!(segmented.segment_count().unwrap() < before);
+        store.append(&[record(9)]).unwrap();
+        assert_eq!(sequences(store), vec![6, 7, 8, 9]);
+
+        // An emptied journal still knows where it stopped after a reopen.
+        store.truncate_through(9).unwrap();
+        drop(segmented);
+        let segmented = SegmentedJournalStore::open(&dir, config).unwrap();
+        let store: &dyn JournalStore = &segmented;
+        assert!(sequences(store).is_empty());
+        assert_eq!(store.last_sequence().unwrap(), 9);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_failed_write_is_cut_before_next_append() {
+        let dir = temp_dir("torn-write");
+        let config = SegmentConfig {
+            max_segment_bytes: 1 << 20,
+            sync_writes: false,
+        };
+        let segmented = SegmentedJournalStore::open(&dir, config).unwrap();
+        let store: &dyn JournalStore = &segmented;
+        store.append(&[record(1)]).unwrap();
+        let (_, path) = segments(&dir).unwrap().pop().unwrap();
+
+        // A write fails after part of the frame reached the file, and the
+        // file cannot be cut back right away.
+        segmented.lock().segment.as_mut().unwrap().file = File::open(&path).unwrap();
+        assert!(store.append(&[record(2)]).is_err());
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(&[42, 0, 0, 0, 1, 2])
+            .unwrap();
+        assert!(store.append(&[record(2)]).is_err());
+
+        // Once the file is writable again the torn bytes go first.
+        segmented.lock().segment.as_mut().unwrap().file =
+            OpenOptions::new().append(true).open(&path).unwrap();
+        store.append(&[record(2)]).unwrap();
+        store.append(&[record(3)]).unwrap();
+        drop(segmented);
+        let segmented = SegmentedJournalStore::open(&dir, config).unwrap();
+        assert_eq!(sequences(&segmented), vec![1, 2, 3]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,421 @@
+//! Journaled state module.
+//!
+//! This module provides [`JournaledState`], which owns the inventory and
+//! open orders of one underlying and records every change it applies, and
+//! [`StateSnapshot`], a point-in-time copy it can be rebuilt from.
+//!
+//! The state persists through a [`JournalStore`] and a [`StateStore`]:
+//! [`JournaledState::flush`] appends new entries, [`JournaledState::checkpoint`]
+//! saves a snapshot and truncates the journal it covers, and
+//! [`JournaledState::load`] rebuilds the state from both.
+
+use super::journal::{JournalEntry, JournalRecord, OpenOrder};
+use super::store::{JournalStore, StateStore};
+use crate::error::{Error, Result};
+use crate::inventory::{InventoryManager, Position, PositionLimits};
+use orderbook_rs::OrderId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// A point-in-time copy of a [`JournaledState`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    /// Sequence of the last journal entry included.
+    pub sequence: u64,
+    /// Snapshot time in milliseconds.
+    pub timestamp_ms: u64,
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Position limits.
+    pub limits: PositionLimits,
+    /// Positions sorted by symbol.
+    pub positions: Vec<Position>,
+    /// Open orders.
+    pub open_orders: Vec<OpenOrder>,
+}
+
+/// Journal and open orders, guarded together.
+#[derive(Debug, Default)]
+struct Journal {
+    /// Last assigned sequence.
+    sequence: u64,
+    /// Last sequence appended to a journal store.
+    persisted: u64,
+    /// Recorded entries, oldest first.
+    records: Vec<JournalRecord>,
+    /// Open orders by id.
+    open_orders: HashMap<OrderId, OpenOrder>,
+}
+
+/// Inventory and open orders whose changes are journaled.
+///
+/// Changes are applied under a single lock, so a snapshot or a read of the
+/// journal always sees a consistent state.
+pub struct JournaledState {
+    /// Positions and limits.
+    inventory: InventoryManager,
+    /// Journal and open orders.
+    journal: Mutex<Journal>,
+}
+
+impl JournaledState {
+    /// Creates an empty state for an underlying.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the limits are invalid.
+    pub fn new(underlying: impl Into<String>, limits: PositionLimits) -> Result<Self> {
+        Ok(Self {
+            inventory: InventoryManager::new(underlying, limits)?,
+            journal: Mutex::new(Journal::default()),
+        })
+    }
+
+    /// Rebuilds a state from a snapshot and the journal entries after it.
+    ///
+    /// Entries at or before the snapshot sequence are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the entries after the snapshot
+    /// are not contiguous, or the error of an entry that fails to apply.
+    pub fn restore(snapshot: &StateSnapshot, records: &[JournalRecord]) -> Result<Self> {
+        let state = Self::new(snapshot.underlying.clone(), snapshot.limits)?;
+        for position in &snapshot.positions {
+            state.inventory.add_position(position.clone());
+        }
+        {
+            let mut journal = state.lock();
+            journal.sequence = snapshot.sequence;
+            journal.open_orders = snapshot
+                .open_orders
+                .iter()
+                .map(|o| (o.order_id, o.clone()))
+                .collect();
+        }
+        for record in records.iter().filter(|r| r.sequence > snapshot.sequence) {
+            let expected = state.sequence() + 1;
+            if record.sequence != expected {
+                return Err(Error::validation(format!(
+                    "journal gap: expected entry {expected}, found {}",
+                    record.sequence
+                )));
+            }
+            state.apply(record.entry.clone(), record.timestamp_ms)?;
+        }
+        Ok(state)
+    }
+
+    /// Rebuilds a state from the stored snapshot and journal.
+    ///
+    /// Returns `None` if no snapshot was saved. The restored entries count
+    /// as persisted, so the next [`flush`](Self::flush) only appends new
+    /// ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns the storage error, or the [`restore`](Self::restore) error.
+    pub fn load(states: &dyn StateStore, journal: &dyn JournalStore) -> Result<Option<Self>> {
+        let Some(snapshot) = states.load()? else {
+            return Ok(None);
+        };
+        let state = Self::restore(&snapshot, &journal.load(snapshot.sequence)?)?;
+        {
+            let mut inner = state.lock();
+            inner.persisted = inner.sequence;
+        }
+        Ok(Some(state))
+    }
+
+    /// Returns the inventory.
+    #[must_use]
+    pub const fn inventory(&self) -> &InventoryManager {
+        &self.inventory
+    }
+
+    /// Returns the sequence of the last applied entry.
+    #[must_use]
+    pub fn sequence(&self) -> u64 {
+        self.lock().sequence
+    }
+
+    /// Returns the open orders sorted by id.
+    #[must_use]
+    pub fn open_orders(&self) -> Vec<OpenOrder> {
+        sorted_orders(&self.lock().open_orders)
+    }
+
+    /// Applies a change and records it in the journal.
+    ///
+    /// Returns the sequence assigned to the entry. Nothing is recorded if
+    /// the change fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns the inventory error of a rejected trade or Greeks update,
+    /// or `Error::ValidationError` for an unknown or duplicate order.
+    pub fn apply(&self, entry: JournalEntry, timestamp_ms: u64) -> Result<u64> {
+        let mut journal = self.lock();
+        match &entry {
+            JournalEntry::Trade {
+                symbol,
+                quantity,
+                price,
+            } => {
+                self.inventory.record_trade(symbol, *quantity, *price)?;
+            }
+            JournalEntry::TiedTrade(trade) => {
+                self.inventory.record_tied_trade(trade)?;
+            }
+            JournalEntry::Greeks { symbol, greeks } => {
+                self.inventory.set_greeks(symbol, *greeks)?;
+            }
+            JournalEntry::OrderOpened(order) => {
+                if journal.open_orders.contains_key(&order.order_id) {
+                    return Err(Error::validation(format!(
+                        "order {} is already open",
+                        order.order_id
+                    )));
+                }
+                journal.open_orders.insert(order.order_id, order.clone());
+            }
+            JournalEntry::OrderReduced { order_id, quantity } => {
+                let order = journal
+                    .open_orders
+                    .get_mut(order_id)
+                    .ok_or_else(|| Error::validation(format!("order {order_id} is not open")))?;
+                order.quantity = *quantity;
+            }
+            JournalEntry::OrderClosed { order_id } => {
+                if journal.open_orders.remove(order_id).is_none() {
+                    return Err(Error::validation(format!("order {order_id} is not open")));
+                }
+            }
+        }
+        journal.sequence += 1;
+        let sequence = journal.sequence;
+        journal.records.push(JournalRecord {
+            sequence,
+            timestamp_ms,
+            entry,
+        });
+        Ok(sequence)
+    }
+
+    /// Takes a snapshot of the current state.
+    #[must_use]
+    pub fn snapshot(&self, timestamp_ms: u64) -> StateSnapshot {
+        let journal = self.lock();
+        self.snapshot_locked(&journal, timestamp_ms)
+    }
+
+    /// Returns the journal entries recorded after a sequence.
+    #[must_use]
+    pub fn records_since(&self, sequence: u64) -> Vec<JournalRecord> {
+        let journal = self.lock();
+        journal
+            .records
+            .iter()
+            .filter(|r| r.sequence > sequence)
+            .cloned()
+            .collect()
+    }
+
+    /// Drops journal entries up to and including a sequence, e.g. once a
+    /// snapshot covering them has been persisted.
+    ///
+    /// Returns the number of entries dropped.
+    pub fn truncate_through(&self, sequence: u64) -> usize {
+        let mut journal = self.lock();
+        let before = journal.records.len();
+        journal.records.retain(|r| r.sequence > sequence);
+        before - journal.records.len()
+    }
+
+    /// Appends the entries not yet persisted to a journal store.
+    ///
+    /// Returns the number of entries appended. Changes are blocked while
+    /// appending, so the store receives entries in sequence order.
+    ///
+    /// # Errors
+    ///
+    /// Returns the storage error; the entries are retried on the next
+    /// flush.
+    pub fn flush(&self, store: &dyn JournalStore) -> Result<usize> {
+        flush_locked(&mut self.lock(), store)
+    }
+
+    /// Flushes the journal, saves a snapshot and drops the journal entries
+    /// it covers from both the store and memory.
+    ///
+    /// Returns the saved snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Returns the storage error. The journal is only truncated once the
+    /// snapshot is saved.
+    pub fn checkpoint(
+        &self,
+        states: &dyn StateStore,
+        store: &dyn JournalStore,
+        timestamp_ms: u64,
+    ) -> Result<StateSnapshot> {
+        let snapshot = {
+            let mut journal = self.lock();
+            flush_locked(&mut journal, store)?;
+            let snapshot = self.snapshot_locked(&journal, timestamp_ms);
+            states.snapshot(&snapshot)?;
+            snapshot
+        };
+        store.truncate_through(snapshot.sequence)?;
+        self.truncate_through(snapshot.sequence);
+        Ok(snapshot)
+    }
+
+    /// Runs `f` on a snapshot and the journal tail after `sequence`, with
+    /// changes blocked, so both describe the same state.
+    pub(crate) fn with_consistent_view<R>(
+        &self,
+        sequence: u64,
+        timestamp_ms: u64,
+        f: impl FnOnce(StateSnapshot, &[JournalRecord]) -> R,
+    ) -> R {
+        let journal = self.lock();
+        let snapshot = self.snapshot_locked(&journal, timestamp_ms);
+        let start = journal.records.partition_point(|r| r.sequence <= sequence);
+        f(snapshot, &journal.records[start..])
+    }
+
+    fn snapshot_locked(&self, journal: &Journal, timestamp_ms: u64) -> StateSnapshot {
+        StateSnapshot {
+            sequence: journal.sequence,
+            timestamp_ms,
+            underlying: self.inventory.underlying().to_string(),
+            limits: *self.inventory.limits(),
+            positions: self.inventory.positions(),
+            open_orders: sorted_orders(&journal.open_orders),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Journal> {
+        self.journal
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Appends the entries after the persisted sequence to a store.
+fn flush_locked(journal: &mut Journal, store: &dyn JournalStore) -> Result<usize> {
+    let start = journal
+        .records
+        .partition_point(|r| r.sequence <= journal.persisted);
+    let pending = &journal.records[start..];
+    store.append(pending)?;
+    let appended = pending.len();
+    journal.persisted = journal.sequence;
+    Ok(appended)
+}
+
+/// Returns open orders sorted by id.
+fn sorted_orders(orders: &HashMap<OrderId, OpenOrder>) -> Vec<OpenOrder> {
+    let mut orders: Vec<OpenOrder> = orders.values().cloned().collect();
+    orders.sort_by_key(|o| o.order_id.to_string());
+    orders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use orderbook_rs::Side;
+    use rust_decimal_macros::dec;
+
+    fn trade(symbol: &str, quantity: rust_decimal::Decimal) -> JournalEntry {
+        JournalEntry::Trade {
+            symbol: symbol.to_string(),
+            quantity,
+            price: dec!(5),
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_and_load_through_stores() {
+        let dir = std::env::temp_dir();
+        let pid = std::process::id();
+        let states = crate::recovery::FileStateStore::new(dir.join(format!("ocob-cp-{pid}.json")));
+        let journal =
+            crate::recovery::FileJournalStore::new(dir.join(format!("ocob-cp-{pid}.jsonl")));
+
+        let state = JournaledState::new("BTC", PositionLimits::default()).unwrap();
+        state.apply(trade("C1", dec!(10)), 1).unwrap();
+        let snapshot = state.checkpoint(&states, &journal, 2).unwrap();
+        assert_eq!(snapshot.sequence, 1);
+        assert!(state.records_since(0).is_empty());
+
+        state.apply(trade("C1", dec!(-3)), 3).unwrap();
+        assert_eq!(state.flush(&journal).unwrap(), 1);
+        assert_eq!(state.flush(&journal).unwrap(), 0);
+
+        let loaded = JournaledState::load(&states, &journal).unwrap().unwrap();
+        assert_eq!(loaded.sequence(), 2);
+        assert_eq!(
+            loaded.inventory().position("C1").unwrap().quantity(),
+            dec!(7)
+        );
+        assert_eq!(loaded.flush(&journal).unwrap(), 0);
+
+        std::fs::remove_file(states.path()).unwrap();
+        std::fs::remove_file(journal.path()).unwrap();
+    }
+
+    #[test]
+    fn test_restore_replays_tail() {
+        let state = JournaledState::new("BTC", PositionLimits::default()).unwrap();
+        state.apply(trade("C1", dec!(10)), 1).unwrap();
+        let snapshot = state.snapshot(2);
+        state.apply(trade("C1", dec!(-4)), 3).unwrap();
+        state
+            .apply(
+                JournalEntry::OrderOpened(OpenOrder {
+                    order_id: OrderId::new(),
+                    symbol: "C1".to_string(),
+                    side: Side::Buy,
+                    price: 500,
+                    quantity: 3,
+                }),
+                4,
+            )
+            .unwrap();
+
+        let tail = state.records_since(snapshot.sequence);
+        assert_eq!(tail.len(), 2);
+        let restored = JournaledState::restore(&snapshot, &tail).unwrap();
+        assert_eq!(restored.sequence(), 3);
+        assert_eq!(restored.snapshot(0), state.snapshot(0));
+    }
+
+    #[test]
+    fn test_rejected_change_is_not_journaled() {
+        let state = JournaledState::new("BTC", PositionLimits::default()).unwrap();
+        let result = state.apply(
+            JournalEntry::OrderClosed {
+                order_id: OrderId::new(),
+            },
+            1,
+        );
+        assert!(result.is_err());
+        assert_eq!(state.sequence(), 0);
+        assert!(state.records_since(0).is_empty());
+    }
+
+    #[test]
+    fn test_restore_detects_gap() {
+        let state = JournaledState::new("BTC", PositionLimits::default()).unwrap();
+        let snapshot = state.snapshot(0);
+        state.apply(trade("C1", dec!(1)), 1).unwrap();
+        state.apply(trade("C1", dec!(1)), 2).unwrap();
+        let tail = state.records_since(1);
+        assert!(JournaledState::restore(&snapshot, &tail).is_err());
+        assert_eq!(state.truncate_through(1), 1);
+    }
+}
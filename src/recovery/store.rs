@@ -0,0 +1,330 @@
+//! Persistence store module.
+//!
+//! This module provides the [`JournalStore`] and [`StateStore`] traits that
+//! every persisted journal and snapshot goes through, and file-backed
+//! implementations of both. Users back recovery with another storage
+//! system (RocksDB, Postgres, S3, ...) by implementing the two traits.
+//!
+//! Both traits are generic over what they store: a journal stores any
+//! [`SequencedRecord`], and a state store any serializable snapshot. They
+//! default to the recovery journal's [`JournalRecord`] and
+//! [`StateSnapshot`].
+//!
+//! Journals are written as JSON lines, one record per line. Snapshots are
+//! written to a temporary file and renamed into place, so a crash mid-write
+//! leaves the previous snapshot intact.
+
+use super::journal::JournalRecord;
+use super::state::StateSnapshot;
+use crate::error::Result;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// A journal record that knows its position in the journal.
+pub trait SequencedRecord: Serialize + DeserializeOwned {
+    /// Returns the record's sequence number.
+    fn sequence(&self) -> u64;
+}
+
+impl SequencedRecord for JournalRecord {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+}
+
+/// Append-only storage for journal records.
+///
+/// Records are appended in sequence order; implementations may rely on it.
+pub trait JournalStore<R = JournalRecord>: Send + Sync {
+    /// Durably appends records.
+    ///
+    /// # Errors
+    ///
+    /// Returns the storage error; a failed append may have stored a prefix
+    /// of the records.
+    fn append(&self, records: &[R]) -> Result<()>;
+
+    /// Calls `f` on every record after `sequence`, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns the storage error, or the first error returned by `f`.
+    fn iterate(&self, sequence: u64, f: &mut dyn FnMut(R) -> Result<()>) -> Result<()>;
+
+    /// Drops records up to and including `sequence`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the storage error.
+    fn truncate_through(&self, sequence: u64) -> Result<()>;
+
+    /// Returns the sequence of the last stored record, or zero if none.
+    ///
+    /// The default reads every record; stores that keep their position
+    /// across truncation should override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the storage error.
+    fn last_sequence(&self) -> Result<u64>
+    where
+        R: SequencedRecord,
+    {
+        let mut last = 0;
+        self.iterate(0, &mut |record| {
+            last = record.sequence();
+            Ok(())
+        })?;
+        Ok(last)
+    }
+
+    /// Returns the sequence of the first stored record, or `None` if none.
+    ///
+    /// The default reads every record; stores that can find their first
+    /// record cheaply should override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the storage error.
+    fn first_sequence(&self) -> Result<Option<u64>>
+    where
+        R: SequencedRecord,
+    {
+        let mut first = None;
+        self.iterate(0, &mut |record| {
+            first.get_or_insert(record.sequence());
+            Ok(())
+        })?;
+        Ok(first)
+    }
+
+    /// Returns every record after `sequence`, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns the storage error.
+    fn load(&self, sequence: u64) -> Result<Vec<R>> {
+        let mut records = Vec::new();
+        self.iterate(sequence, &mut |record| {
+            records.push(record);
+            Ok(())
+        })?;
+        Ok(records)
+    }
+}
+
+/// Storage for the latest snapshot of some state.
+pub trait StateStore<S = StateSnapshot>: Send + Sync {
+    /// Durably replaces the stored snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Returns the storage error; the previous snapshot must survive a
+    /// failed save.
+    fn snapshot(&self, snapshot: &S) -> Result<()>;
+
+    /// Returns the stored snapshot, or `None` if none was saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns the storage error.
+    fn load(&self) -> Result<Option<S>>;
+}
+
+/// Journal stored as a JSON-lines file.
+pub struct FileJournalStore {
+    /// File path.
+    path: PathBuf,
+    /// Serializes appends and truncations.
+    write: Mutex<()>,
+}
+
+impl FileJournalStore {
+    /// Creates a store at `path`; the file is created on first append.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write: Mutex::new(()),
+        }
+    }
+
+    /// Returns the file path.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ()> {
+        self.write
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl<R: SequencedRecord> JournalStore<R> for FileJournalStore {
+    fn append(&self, records: &[R]) -> Result<()> {
+        let _write = self.lock();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut writer = BufWriter::new(file);
+        for record in records {
+            serde_json::to_writer(&mut writer, record)?;
+            writer.write_all(b"\n")?;
+        }
+        writer
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_data()?;
+        Ok(())
+    }
+
+    fn iterate(&self, sequence: u64, f: &mut dyn FnMut(R) -> Result<()>) -> Result<()> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            let record: R = serde_json::from_str(&line)?;
+            if record.sequence() > sequence {
+                f(record)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn truncate_through(&self, sequence: u64) -> Result<()> {
+        let _write = self.lock();
+        let kept = JournalStore::<R>::load(self, sequence)?;
+        let tmp = self.path.with_extension("tmp");
+        {
+            let mut writer = BufWriter::new(File::create(&tmp)?);
+            for record in &kept {
+                serde_json::to_writer(&mut writer, record)?;
+                writer.write_all(b"\n")?;
+            }
+            writer
+                .into_inner()
+                .map_err(|e| e.into_error())?
+                .sync_data()?;
+        }
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Snapshot stored as a JSON file.
+pub struct FileStateStore {
+    /// File path.
+    path: PathBuf,
+}
+
+impl FileStateStore {
+    /// Creates a store at `path`.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the file path.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl<S: Serialize + DeserializeOwned> StateStore<S> for FileStateStore {
+    fn snapshot(&self, snapshot: &S) -> Result<()> {
+        let tmp = self.path.with_extension("tmp");
+        {
+            let file = File::create(&tmp)?;
+            serde_json::to_writer(&file, snapshot)?;
+            file.sync_data()?;
+        }
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Option<S>> {
+        match fs::read(&self.path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recovery::JournalEntry;
+    use rust_decimal_macros::dec;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ocob-store-{name}-{}", std::process::id()))
+    }
+
+    fn record(sequence: u64) -> JournalRecord {
+        JournalRecord {
+            sequence,
+            timestamp_ms: sequence * 10,
+            entry: JournalEntry::Trade {
+                symbol: "C1".to_string(),
+                quantity: dec!(1),
+                price: dec!(5),
+            },
+        }
+    }
+
+    #[test]
+    fn test_file_journal_append_load_truncate() {
+        let path = temp_path("journal");
+        let file = FileJournalStore::new(&path);
+        let store: &dyn JournalStore = &file;
+        assert!(store.load(0).unwrap().is_empty());
+
+        store.append(&[record(1), record(2)]).unwrap();
+        store.append(&[record(3)]).unwrap();
+        assert_eq!(store.load(1).unwrap(), vec![record(2), record(3)]);
+
+        store.truncate_through(2).unwrap();
+        assert_eq!(store.load(0).unwrap(), vec![record(3)]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_file_state_roundtrip() {
+        let path = temp_path("state");
+        let file = FileStateStore::new(&path);
+        let store: &dyn StateStore = &file;
+        assert!(store.load().unwrap().is_none());
+
+        let snapshot = StateSnapshot {
+            sequence: 7,
+            timestamp_ms: 70,
+            underlying: "BTC".to_string(),
+            limits: crate::inventory::PositionLimits::default(),
+            positions: Vec::new(),
+            open_orders: Vec::new(),
+        };
+        store.snapshot(&snapshot).unwrap();
+        assert_eq!(store.load().unwrap(), Some(snapshot));
+
+        // The same store persists any serializable state.
+        let marks = std::collections::BTreeMap::from([("C1".to_string(), dec!(5))]);
+        StateStore::snapshot(&file, &marks).unwrap();
+        assert_eq!(StateStore::load(&file).unwrap(), Some(marks));
+        fs::remove_file(path).unwrap();
+    }
+}
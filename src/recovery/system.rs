@@ -0,0 +1,296 @@
+//! System snapshot module.
+//!
+//! This module provides [`SystemSnapshot`], a versioned copy of everything a
+//! market maker needs to restart: the option chains and the resting orders
+//! of every book with their expiries and participants, the positions of
+//! every inventory, and the realized P&L ledger of the fill router.
+//!
+//! [`PnLCalculator`](crate::pnl::PnLCalculator) holds no state beyond its
+//! configuration, so P&L survives a restart through the positions' realized
+//! P&L and the fill router's per-contract ledger.
+//!
+//! Snapshots persist through a [`StateStore`], e.g. a
+//! [`FileStateStore`](super::FileStateStore) that writes JSON to a
+//! temporary file and renames it into place. Loading rejects snapshots
+//! written by a newer format version.
+
+use super::store::StateStore;
+use crate::adapters::FillRouter;
+use crate::error::{Error, Result};
+use crate::inventory::{InventoryManager, Position, PositionLimits};
+use crate::orderbook::{OrderTags, UnderlyingOrderBookManager};
+use optionstratlib::{ExpirationDate, OptionStyle};
+use orderbook_rs::OrderBookSnapshot;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Format version written by [`SystemSnapshot::save`].
+pub const SYSTEM_SNAPSHOT_VERSION: u32 = 2;
+
+/// Contents of one option book and its place in the chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookState {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Expiration of the contract.
+    pub expiration: ExpirationDate,
+    /// Strike price.
+    pub strike: u64,
+    /// Call or put.
+    pub style: OptionStyle,
+    /// Every price level and resting order of the book.
+    pub book: OrderBookSnapshot,
+    /// Expiries and participants of the resting orders, which the book
+    /// snapshot does not carry.
+    #[serde(default)]
+    pub tags: Vec<OrderTags>,
+}
+
+/// Positions and limits of one inventory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InventoryState {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Position limits.
+    pub limits: PositionLimits,
+    /// Positions sorted by symbol.
+    pub positions: Vec<Position>,
+}
+
+/// A versioned copy of chains, books, positions and P&L.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemSnapshot {
+    /// Format version.
+    pub version: u32,
+    /// Snapshot time in milliseconds.
+    pub timestamp_ms: u64,
+    /// Every option book, empty ones included so the chain is rebuilt.
+    pub books: Vec<BookState>,
+    /// Every captured inventory.
+    pub inventories: Vec<InventoryState>,
+    /// Realized P&L per contract from the fill router.
+    #[serde(default)]
+    pub realized_pnl: BTreeMap<String, Decimal>,
+}
+
+impl SystemSnapshot {
+    /// Captures the chains, books and inventories.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp_ms` - Snapshot time in milliseconds
+    /// * `books` - Order books of every underlying
+    /// * `inventories` - Inventories to capture
+    #[must_use]
+    pub fn capture(
+        timestamp_ms: u64,
+        books: &UnderlyingOrderBookManager,
+        inventories: &[&InventoryManager],
+    ) -> Self {
+        let mut states = Vec::new();
+        for underlying in books.iter() {
+            for expiration in underlying.value().expirations().iter() {
+                let expiration = expiration.value();
+                for strike in expiration.chain().strikes().iter() {
+                    for style in [OptionStyle::Call, OptionStyle::Put] {
+                        let book = strike.value().get(style);
+                        states.push(BookState {
+                            underlying: underlying.key().clone(),
+                            expiration: *expiration.expiration(),
+                            strike: *strike.key(),
+                            style,
+                            book: book.snapshot(usize::MAX),
+                            tags: book.order_tags(),
+                        });
+                    }
+                }
+            }
+        }
+        Self {
+            version: SYSTEM_SNAPSHOT_VERSION,
+            timestamp_ms,
+            books: states,
+            inventories: inventories
+                .iter()
+                .map(|inventory| InventoryState {
+                    underlying: inventory.underlying().to_string(),
+                    limits: *inventory.limits(),
+                    positions: inventory.positions(),
+                })
+                .collect(),
+            realized_pnl: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the fill router's realized P&L ledger.
+    #[must_use]
+    pub fn with_realized_pnl(mut self, router: &FillRouter) -> Self {
+        self.realized_pnl = router.realized_by_contract();
+        self
+    }
+
+    /// Rebuilds the chains and restores every book's resting orders with
+    /// their Good-Till-Date expiries and self-trade participants.
+    ///
+    /// Returns the number of books restored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::OrderBookError` if a rebuilt book's symbol differs
+    /// from the captured one, e.g. for an expiration given in days.
+    pub fn restore_books(&self, books: &UnderlyingOrderBookManager) -> Result<usize> {
+        for state in &self.books {
+            books
+                .get_or_create(state.underlying.as_str())
+                .get_or_create_expiration(state.expiration)
+                .get_or_create_strike(state.strike)
+                .get(state.style)
+                .restore_with_tags(state.book.clone(), &state.tags)?;
+        }
+        Ok(self.books.len())
+    }
+
+    /// Rebuilds every captured inventory.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if captured limits are invalid.
+    pub fn restore_inventories(&self) -> Result<Vec<InventoryManager>> {
+        self.inventories
+            .iter()
+            .map(|state| {
+                let inventory = InventoryManager::new(state.underlying.clone(), state.limits)?;
+                for position in &state.positions {
+                    inventory.add_position(position.clone());
+                }
+                Ok(inventory)
+            })
+            .collect()
+    }
+
+    /// Restores the fill router's realized P&L ledger.
+    pub fn restore_realized_pnl(&self, router: &FillRouter) {
+        router.restore_realized_pnl(self.realized_pnl.clone());
+    }
+
+    /// Saves the snapshot to a store, replacing the stored one.
+    ///
+    /// # Errors
+    ///
+    /// Returns the store's error; the previously stored snapshot is left
+    /// intact.
+    pub fn save(&self, store: &dyn StateStore<Self>) -> Result<()> {
+        store.snapshot(self)
+    }
+
+    /// Loads the snapshot saved in a store, or `None` if none was saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns the store's error, or `Error::ValidationError` if the
+    /// snapshot was written by an unsupported format version.
+    pub fn load(store: &dyn StateStore<Self>) -> Result<Option<Self>> {
+        let Some(snapshot) = store.load()? else {
+            return Ok(None);
+        };
+        if snapshot.version == 0 || snapshot.version > SYSTEM_SNAPSHOT_VERSION {
+            return Err(Error::validation(format!(
+                "unsupported system snapshot version {} (supported up to {SYSTEM_SNAPSHOT_VERSION})",
+                snapshot.version
+            )));
+        }
+        Ok(Some(snapshot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recovery::FileStateStore;
+    use chrono::{TimeZone, Utc};
+    use orderbook_rs::{OrderId, Side, TimeInForce};
+    use rust_decimal_macros::dec;
+    use std::sync::Arc;
+
+    fn expiration() -> ExpirationDate {
+        ExpirationDate::DateTime(Utc.with_ymd_and_hms(2030, 6, 21, 0, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn test_save_and_restore_roundtrip() {
+        let books = UnderlyingOrderBookManager::new();
+        let strike = books
+            .get_or_create("SPX")
+            .get_or_create_expiration(expiration())
+            .get_or_create_strike(5000);
+        let gtd = OrderId::new();
+        strike
+            .call()
+            .add_limit_order_as(
+                gtd,
+                Side::Buy,
+                120,
+                5,
+                TimeInForce::Gtd(4_102_444_800),
+                "mm",
+            )
+            .unwrap();
+        strike
+            .put()
+            .add_limit_order(OrderId::new(), Side::Sell, 90, 3)
+            .unwrap();
+        let symbol = strike.call().symbol().to_string();
+
+        let inventory = Arc::new(InventoryManager::new("SPX", PositionLimits::default()).unwrap());
+        inventory.record_trade(&symbol, dec!(4), dec!(10)).unwrap();
+        inventory.record_trade(&symbol, dec!(-1), dec!(12)).unwrap();
+        let router = FillRouter::new(Arc::clone(&inventory));
+        router.restore_realized_pnl(BTreeMap::from([(symbol.clone(), dec!(2))]));
+
+        let store = FileStateStore::new(
+            std::env::temp_dir().join(format!("ocob-system-{}.json", std::process::id())),
+        );
+        assert!(SystemSnapshot::load(&store).unwrap().is_none());
+        SystemSnapshot::capture(1, &books, &[&inventory])
+            .with_realized_pnl(&router)
+            .save(&store)
+            .unwrap();
+        let loaded = SystemSnapshot::load(&store).unwrap().unwrap();
+        std::fs::remove_file(store.path()).unwrap();
+        assert_eq!(loaded.version, SYSTEM_SNAPSHOT_VERSION);
+        assert_eq!(loaded.books.len(), 2);
+
+        let restored = UnderlyingOrderBookManager::new();
+        assert_eq!(loaded.restore_books(&restored).unwrap(), 2);
+        let strike = restored
+            .get("SPX")
+            .unwrap()
+            .get_or_create_expiration(expiration())
+            .get_or_create_strike(5000);
+        assert_eq!(strike.call().best_bid(), Some(120));
+        assert_eq!(strike.put().best_ask(), Some(90));
+        assert_eq!(strike.call().order_expiry(gtd), Some(4_102_444_800_000));
+        assert_eq!(strike.call().participant(gtd).as_deref(), Some("mm"));
+
+        let inventories = loaded.restore_inventories().unwrap();
+        assert_eq!(inventories[0].positions(), inventory.positions());
+        let new_router = FillRouter::new(Arc::new(
+            InventoryManager::new("SPX", PositionLimits::default()).unwrap(),
+        ));
+        loaded.restore_realized_pnl(&new_router);
+        assert_eq!(new_router.realized_pnl(&symbol), dec!(2));
+    }
+
+    #[test]
+    fn test_rejects_newer_version() {
+        let store = FileStateStore::new(
+            std::env::temp_dir().join(format!("ocob-system-v-{}.json", std::process::id())),
+        );
+        let mut snapshot = SystemSnapshot::capture(0, &UnderlyingOrderBookManager::new(), &[]);
+        snapshot.version = SYSTEM_SNAPSHOT_VERSION + 1;
+        snapshot.save(&store).unwrap();
+        assert!(SystemSnapshot::load(&store).is_err());
+        std::fs::remove_file(store.path()).unwrap();
+    }
+}
@@ -0,0 +1,611 @@
+//! Consistency watchdog module.
+//!
+//! This module provides the [`ConsistencyWatchdog`], a periodic check that
+//! the own-order and position state of a [`JournaledState`] still agrees
+//! with the books, with the fills seen since the last check and, when a
+//! [`VenueStateSource`] is attached, with the venue.
+//!
+//! ## Checks
+//!
+//! - Every tracked order must rest in its book with the same remaining
+//!   quantity ([`DivergenceKind::MissingOrder`], [`DivergenceKind::OrderQuantity`])
+//! - Each position must have moved by exactly the fills recorded since the
+//!   last check ([`DivergenceKind::Position`])
+//! - Tracked orders and positions must match the venue's
+//!   ([`DivergenceKind::VenueOrder`], [`DivergenceKind::VenuePosition`])
+//!
+//! ## Repairs
+//!
+//! Repairs go through the journal like any other change. With
+//! [`RepairAction::RebuildTracker`], tracked orders are rebuilt from the
+//! books and positions from the recorded fills; with
+//! [`RepairAction::ResyncFromAdapter`], both are set to the venue's view.
+//! Position corrections are booked at the position's average price so they
+//! do not realize P&L. Every check that finds divergences raises one alert
+//! per kind.
+
+use super::journal::{JournalEntry, OpenOrder};
+use super::state::JournaledState;
+use crate::alerting::{AlertEvent, AlertEventKind, AlertSeverity};
+use crate::error::Result;
+use crate::orderbook::UnderlyingOrderBookManager;
+use orderbook_rs::OrderId;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Kind of divergence between tracked and observed state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DivergenceKind {
+    /// A tracked order is not in its book.
+    MissingOrder,
+    /// A tracked order rests with a different remaining quantity.
+    OrderQuantity,
+    /// A position moved by other than the recorded fills.
+    Position,
+    /// A tracked order differs from the venue's.
+    VenueOrder,
+    /// A position differs from the venue's.
+    VenuePosition,
+}
+
+impl std::fmt::Display for DivergenceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingOrder => write!(f, "missing_order"),
+            Self::OrderQuantity => write!(f, "order_quantity"),
+            Self::Position => write!(f, "position"),
+            Self::VenueOrder => write!(f, "venue_order"),
+            Self::VenuePosition => write!(f, "venue_position"),
+        }
+    }
+}
+
+/// Repair applied to a divergence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RepairAction {
+    /// Report and alert only.
+    #[default]
+    AlertOnly,
+    /// Rebuild tracked orders from the books and positions from fills.
+    RebuildTracker,
+    /// Set tracked orders and positions to the venue's view.
+    ResyncFromAdapter,
+}
+
+/// Watchdog repair and alert settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct WatchdogConfig {
+    /// Repair for order divergences.
+    pub order_repair: RepairAction,
+    /// Repair for position divergences.
+    pub position_repair: RepairAction,
+    /// Severity of the raised alerts.
+    pub severity: AlertSeverity,
+}
+
+/// Source of the venue's view of our orders and positions.
+///
+/// Implemented by exchange adapters.
+pub trait VenueStateSource: Send + Sync {
+    /// Returns our resting orders on an underlying.
+    ///
+    /// # Errors
+    ///
+    /// Returns the adapter error if the venue cannot be queried.
+    fn open_orders(&self, underlying: &str) -> Result<Vec<OpenOrder>>;
+
+    /// Returns our net position per symbol on an underlying.
+    ///
+    /// # Errors
+    ///
+    /// Returns the adapter error if the venue cannot be queried.
+    fn positions(&self, underlying: &str) -> Result<Vec<(String, Decimal)>>;
+}
+
+/// A difference found by the watchdog.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Divergence {
+    /// Kind of divergence.
+    pub kind: DivergenceKind,
+    /// Symbol or order id the difference is in.
+    pub key: String,
+    /// Tracked value, `None` if absent.
+    pub tracked: Option<String>,
+    /// Observed value, `None` if absent.
+    pub observed: Option<String>,
+    /// Repair applied, `None` if left as is.
+    pub repair: Option<RepairAction>,
+}
+
+/// Result of one watchdog check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchdogReport {
+    /// Check time in milliseconds.
+    pub timestamp_ms: u64,
+    /// Tracked orders compared.
+    pub orders_checked: usize,
+    /// Positions compared.
+    pub positions_checked: usize,
+    /// Differences found.
+    pub divergences: Vec<Divergence>,
+    /// Alerts raised, one per divergence kind.
+    pub alerts: Vec<AlertEvent>,
+}
+
+impl WatchdogReport {
+    /// Returns true if no divergence was found.
+    #[must_use]
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+
+    /// Returns the number of divergences repaired.
+    #[must_use]
+    pub fn repaired(&self) -> usize {
+        self.divergences
+            .iter()
+            .filter(|d| d.repair.is_some())
+            .count()
+    }
+}
+
+/// Positions at the last check and fills since.
+#[derive(Debug, Default)]
+struct FillLedger {
+    /// Positions at the last check, `None` before the first.
+    baseline: Option<HashMap<String, Decimal>>,
+    /// Signed fill quantity per symbol since the last check.
+    fills: HashMap<String, Decimal>,
+}
+
+/// Periodic consistency check of tracked orders and positions.
+pub struct ConsistencyWatchdog {
+    /// Repair and alert settings.
+    config: WatchdogConfig,
+    /// Venue view, if attached.
+    venue: Option<Arc<dyn VenueStateSource>>,
+    /// Fill ledger.
+    ledger: Mutex<FillLedger>,
+}
+
+impl ConsistencyWatchdog {
+    /// Creates a watchdog without a venue source.
+    #[must_use]
+    pub fn new(config: WatchdogConfig) -> Self {
+        Self {
+            config,
+            venue: None,
+            ledger: Mutex::new(FillLedger::default()),
+        }
+    }
+
+    /// Attaches the venue's view of orders and positions.
+    #[must_use]
+    pub fn with_venue(mut self, venue: Arc<dyn VenueStateSource>) -> Self {
+        self.venue = Some(venue);
+        self
+    }
+
+    /// Returns the settings.
+    #[must_use]
+    pub const fn config(&self) -> &WatchdogConfig {
+        &self.config
+    }
+
+    /// Records a fill reported by the fill stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - Contract symbol
+    /// * `quantity` - Signed quantity (positive buys)
+    pub fn record_fill(&self, symbol: &str, quantity: Decimal) {
+        *self.lock().fills.entry(symbol.to_string()).or_default() += quantity;
+    }
+
+    /// Checks the state against the books, fills and venue, applying the
+    /// configured repairs.
+    ///
+    /// The first check only records the position baseline; positions are
+    /// compared against fills from the second check on.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - Tracked orders and positions
+    /// * `books` - Books the tracked orders rest in
+    /// * `now_ms` - Check time in milliseconds
+    ///
+    /// # Errors
+    ///
+    /// Returns the venue error, or the error of a repair that fails to
+    /// apply; repairs applied before the failure are kept.
+    pub fn check(
+        &self,
+        state: &JournaledState,
+        books: &UnderlyingOrderBookManager,
+        now_ms: u64,
+    ) -> Result<WatchdogReport> {
+        let mut divergences = Vec::new();
+        let orders_checked = self.check_books(state, books, now_ms, &mut divergences)?;
+        let positions_checked = self.check_fills(state, now_ms, &mut divergences)?;
+        if let Some(venue) = &self.venue {
+            self.check_venue(venue.as_ref(), state, now_ms, &mut divergences)?;
+        }
+        let mut ledger = self.lock();
+        ledger.baseline = Some(quantities(state));
+        ledger.fills.clear();
+        drop(ledger);
+
+        Ok(WatchdogReport {
+            timestamp_ms: now_ms,
+            orders_checked,
+            positions_checked,
+            alerts: self.alerts(&divergences, now_ms),
+            divergences,
+        })
+    }
+
+    /// Compares tracked orders with their books.
+    fn check_books(
+        &self,
+        state: &JournaledState,
+        books: &UnderlyingOrderBookManager,
+        now_ms: u64,
+        out: &mut Vec<Divergence>,
+    ) -> Result<usize> {
+        let orders = state.open_orders();
+        let repair = self.config.order_repair == RepairAction::RebuildTracker;
+        for order in &orders {
+            let resting = books
+                .contract_id(&order.symbol)
+                .and_then(|id| books.book(id))
+                .ok()
+                .and_then(|book| book.inner().get_order(order.order_id))
+                .map(|o| o.visible_quantity());
+            let (kind, entry) = match resting {
+                None => (
+                    DivergenceKind::MissingOrder,
+                    JournalEntry::OrderClosed {
+                        order_id: order.order_id,
+                    },
+                ),
+                Some(quantity) if quantity != order.quantity => (
+                    DivergenceKind::OrderQuantity,
+                    JournalEntry::OrderReduced {
+                        order_id: order.order_id,
+                        quantity,
+                    },
+                ),
+                Some(_) => continue,
+            };
+            if repair {
+                state.apply(entry, now_ms)?;
+            }
+            out.push(Divergence {
+                kind,
+                key: order.order_id.to_string(),
+                tracked: Some(order.quantity.to_string()),
+                observed: resting.map(|q| q.to_string()),
+                repair: repair.then_some(RepairAction::RebuildTracker),
+            });
+        }
+        Ok(orders.len())
+    }
+
+    /// Compares position moves with recorded fills.
+    fn check_fills(
+        &self,
+        state: &JournaledState,
+        now_ms: u64,
+        out: &mut Vec<Divergence>,
+    ) -> Result<usize> {
+        let ledger = self.lock();
+        let Some(baseline) = &ledger.baseline else {
+            return Ok(0);
+        };
+        let mut expected: BTreeMap<String, Decimal> =
+            baseline.iter().map(|(s, q)| (s.clone(), *q)).collect();
+        for (symbol, quantity) in &ledger.fills {
+            *expected.entry(symbol.clone()).or_default() += quantity;
+        }
+        drop(ledger);
+        for symbol in quantities(state).into_keys() {
+            expected.entry(symbol).or_default();
+        }
+
+        let repair = self.config.position_repair == RepairAction::RebuildTracker;
+        for (symbol, quantity) in &expected {
+            reconcile_position(
+                state,
+                symbol,
+                *quantity,
+                DivergenceKind::Position,
+                repair.then_some(RepairAction::RebuildTracker),
+                now_ms,
+                out,
+            )?;
+        }
+        Ok(expected.len())
+    }
+
+    /// Compares tracked orders and positions with the venue's.
+    fn check_venue(
+        &self,
+        venue: &dyn VenueStateSource,
+        state: &JournaledState,
+        now_ms: u64,
+        out: &mut Vec<Divergence>,
+    ) -> Result<()> {
+        let underlying = state.inventory().underlying().to_string();
+        let venue_orders: HashMap<OrderId, OpenOrder> = venue
+            .open_orders(&underlying)?
+            .into_iter()
+            .map(|o| (o.order_id, o))
+            .collect();
+        let tracked: HashMap<OrderId, OpenOrder> = state
+            .open_orders()
+            .into_iter()
+            .map(|o| (o.order_id, o))
+            .collect();
+
+        let resync = self.config.order_repair == RepairAction::ResyncFromAdapter;
+        let mut ids: Vec<OrderId> = tracked.keys().chain(venue_orders.keys()).copied().collect();
+        ids.sort_unstable_by_key(ToString::to_string);
+        ids.dedup();
+        for order_id in ids {
+            let (t, v) = (tracked.get(&order_id), venue_orders.get(&order_id));
+            let entry = match (t, v) {
+                (Some(t), Some(v)) if t == v => continue,
+                (Some(_), Some(v)) => JournalEntry::OrderReduced {
+                    order_id,
+                    quantity: v.quantity,
+                },
+                (Some(_), None) => JournalEntry::OrderClosed { order_id },
+                (None, Some(v)) => JournalEntry::OrderOpened(v.clone()),
+                (None, None) => continue,
+            };
+            if resync {
+                state.apply(entry, now_ms)?;
+            }
+            out.push(Divergence {
+                kind: DivergenceKind::VenueOrder,
+                key: order_id.to_string(),
+                tracked: t.map(|o| format!("{o:?}")),
+                observed: v.map(|o| format!("{o:?}")),
+                repair: resync.then_some(RepairAction::ResyncFromAdapter),
+            });
+        }
+
+        let mut positions: BTreeMap<String, Decimal> =
+            venue.positions(&underlying)?.into_iter().collect();
+        for symbol in quantities(state).into_keys() {
+            positions.entry(symbol).or_default();
+        }
+        let resync = self.config.position_repair == RepairAction::ResyncFromAdapter;
+        for (symbol, quantity) in &positions {
+            reconcile_position(
+                state,
+                symbol,
+                *quantity,
+                DivergenceKind::VenuePosition,
+                resync.then_some(RepairAction::ResyncFromAdapter),
+                now_ms,
+                out,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Raises one alert per divergence kind, valued at its count.
+    fn alerts(&self, divergences: &[Divergence], now_ms: u64) -> Vec<AlertEvent> {
+        let mut counts: BTreeMap<DivergenceKind, u32> = BTreeMap::new();
+        for divergence in divergences {
+            *counts.entry(divergence.kind).or_default() += 1;
+        }
+        counts
+            .into_iter()
+            .map(|(kind, count)| AlertEvent {
+                rule: format!("watchdog.{kind}"),
+                kind: AlertEventKind::Fired,
+                severity: self.config.severity,
+                value: Some(Decimal::from(count)),
+                timestamp_ms: now_ms,
+            })
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FillLedger> {
+        self.ledger
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Records a divergence if a position differs from `observed`, booking
+/// the difference at the average price when a repair is given.
+fn reconcile_position(
+    state: &JournaledState,
+    symbol: &str,
+    observed: Decimal,
+    kind: DivergenceKind,
+    repair: Option<RepairAction>,
+    now_ms: u64,
+    out: &mut Vec<Divergence>,
+) -> Result<()> {
+    let position = state.inventory().position(symbol);
+    let tracked = position.as_ref().map_or(Decimal::ZERO, |p| p.quantity());
+    if tracked == observed {
+        return Ok(());
+    }
+    if repair.is_some() {
+        state.apply(
+            JournalEntry::Trade {
+                symbol: symbol.to_string(),
+                quantity: observed - tracked,
+                price: position.map_or(Decimal::ZERO, |p| p.average_price()),
+            },
+            now_ms,
+        )?;
+    }
+    out.push(Divergence {
+        kind,
+        key: symbol.to_string(),
+        tracked: Some(tracked.to_string()),
+        observed: Some(observed.to_string()),
+        repair,
+    });
+    Ok(())
+}
+
+/// Returns the position quantity per symbol.
+fn quantities(state: &JournaledState) -> HashMap<String, Decimal> {
+    let mut quantities = HashMap::new();
+    state.inventory().for_each_position(|p| {
+        quantities.insert(p.symbol().to_string(), p.quantity());
+    });
+    quantities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::PositionLimits;
+    use optionstratlib::ExpirationDate;
+    use optionstratlib::prelude::pos_or_panic;
+    use orderbook_rs::Side;
+    use rust_decimal_macros::dec;
+
+    struct Venue(Vec<OpenOrder>, Vec<(String, Decimal)>);
+
+    impl VenueStateSource for Venue {
+        fn open_orders(&self, _underlying: &str) -> Result<Vec<OpenOrder>> {
+            Ok(self.0.clone())
+        }
+
+        fn positions(&self, _underlying: &str) -> Result<Vec<(String, Decimal)>> {
+            Ok(self.1.clone())
+        }
+    }
+
+    fn setup() -> (JournaledState, UnderlyingOrderBookManager, String) {
+        let books = UnderlyingOrderBookManager::new();
+        let strike = books
+            .get_or_create("BTC")
+            .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(30.0)))
+            .get_or_create_strike(50000);
+        let symbol = strike.call().symbol().to_string();
+        (
+            JournaledState::new("BTC", PositionLimits::default()).unwrap(),
+            books,
+            symbol,
+        )
+    }
+
+    fn open(state: &JournaledState, symbol: &str, quantity: u64) -> OpenOrder {
+        let order = OpenOrder {
+            order_id: OrderId::new(),
+            symbol: symbol.to_string(),
+            side: Side::Buy,
+            price: 100,
+            quantity,
+        };
+        state
+            .apply(JournalEntry::OrderOpened(order.clone()), 0)
+            .unwrap();
+        order
+    }
+
+    #[test]
+    fn test_rebuilds_tracker_from_books() {
+        let (state, books, symbol) = setup();
+        let book = books.book(books.contract_id(&symbol).unwrap()).unwrap();
+        let resting = open(&state, &symbol, 10);
+        book.add_limit_order(resting.order_id, Side::Buy, 100, 6)
+            .unwrap();
+        open(&state, &symbol, 5);
+
+        let config = WatchdogConfig {
+            order_repair: RepairAction::RebuildTracker,
+            ..WatchdogConfig::default()
+        };
+        let report = ConsistencyWatchdog::new(config)
+            .check(&state, &books, 1)
+            .unwrap();
+
+        assert_eq!(report.orders_checked, 2);
+        assert_eq!(report.repaired(), 2);
+        assert_eq!(report.alerts.len(), 2);
+        assert_eq!(state.open_orders().len(), 1);
+        assert_eq!(state.open_orders()[0].quantity, 6);
+    }
+
+    #[test]
+    fn test_position_divergence_from_fills() {
+        let (state, books, symbol) = setup();
+        let watchdog = ConsistencyWatchdog::new(WatchdogConfig {
+            position_repair: RepairAction::RebuildTracker,
+            ..WatchdogConfig::default()
+        });
+        assert!(watchdog.check(&state, &books, 0).unwrap().is_consistent());
+
+        // Two fills reported, but only one reached the inventory.
+        watchdog.record_fill(&symbol, dec!(3));
+        watchdog.record_fill(&symbol, dec!(2));
+        state
+            .apply(
+                JournalEntry::Trade {
+                    symbol: symbol.clone(),
+                    quantity: dec!(3),
+                    price: dec!(5),
+                },
+                1,
+            )
+            .unwrap();
+
+        let report = watchdog.check(&state, &books, 2).unwrap();
+        assert_eq!(report.divergences[0].kind, DivergenceKind::Position);
+        assert_eq!(report.alerts[0].rule, "watchdog.position");
+        let position = state.inventory().position(&symbol).unwrap();
+        assert_eq!(position.quantity(), dec!(5));
+        assert_eq!(position.average_price(), dec!(5));
+        assert!(watchdog.check(&state, &books, 3).unwrap().is_consistent());
+    }
+
+    #[test]
+    fn test_resync_from_venue() {
+        let (state, books, symbol) = setup();
+        let stale = open(&state, &symbol, 5);
+        let live = OpenOrder {
+            order_id: OrderId::new(),
+            ..stale.clone()
+        };
+        let venue = Venue(vec![live.clone()], vec![(symbol.clone(), dec!(-2))]);
+        let config = WatchdogConfig {
+            order_repair: RepairAction::ResyncFromAdapter,
+            position_repair: RepairAction::ResyncFromAdapter,
+            severity: AlertSeverity::Critical,
+        };
+        let report = ConsistencyWatchdog::new(config)
+            .with_venue(Arc::new(venue))
+            .check(&state, &books, 1)
+            .unwrap();
+
+        // The stale order is also missing from the book, but book repairs
+        // are not configured.
+        let kinds: Vec<_> = report.divergences.iter().map(|d| d.kind).collect();
+        assert!(kinds.contains(&DivergenceKind::MissingOrder));
+        assert_eq!(report.repaired(), 3);
+        assert_eq!(state.open_orders(), vec![live]);
+        assert_eq!(
+            state.inventory().position(&symbol).unwrap().quantity(),
+            dec!(-2)
+        );
+        assert!(
+            report
+                .alerts
+                .iter()
+                .all(|a| a.severity == AlertSeverity::Critical)
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! Batch risk check module.
+//!
+//! This module provides the inputs and result of
+//! [`RiskController::check_batch`](super::RiskController::check_batch),
+//! which evaluates every quote of an outgoing batch for one underlying
+//! against the limits in a single call.
+//!
+//! Each quote side is checked on its own against the current exposures:
+//! the side is allowed if no limit would be breached after a full fill, or
+//! if every limit it would leave breached is one it moves towards zero.
+//! While the controller is in `ReducedRisk`, or the daily loss limit is
+//! already breached, only sides that increase no absolute Greek are
+//! allowed; while `Halted`, none are.
+
+use super::limits::{LimitKind, LimitUtilization, RiskLimits};
+use super::state::TradingState;
+use crate::pricing::Greeks;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Greek limits evaluated per quote side.
+const GREEK_LIMITS: [LimitKind; 4] = [
+    LimitKind::Delta,
+    LimitKind::Gamma,
+    LimitKind::Vega,
+    LimitKind::Theta,
+];
+
+/// Change in portfolio Greeks if either side of one contract's quote fills.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectedExposure {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Greeks added by a full fill of our bid.
+    pub bid_fill: Greeks,
+    /// Greeks added by a full fill of our ask.
+    pub ask_fill: Greeks,
+}
+
+/// Outgoing quote batch for one underlying.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskBatch {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Current portfolio Greeks.
+    pub current: Greeks,
+    /// P&L of the trading day.
+    pub pnl_today: Decimal,
+    /// Projected exposures, one per quoted contract.
+    pub quotes: Vec<ProjectedExposure>,
+}
+
+/// Verdict on one contract's quote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractVerdict {
+    /// Whether the bid may be quoted.
+    pub bid_allowed: bool,
+    /// Whether the ask may be quoted.
+    pub ask_allowed: bool,
+    /// Most utilized limit after a fill of either side.
+    pub binding: Option<LimitKind>,
+}
+
+impl ContractVerdict {
+    /// Returns true if neither side may be quoted.
+    #[must_use]
+    pub const fn is_rejected(&self) -> bool {
+        !self.bid_allowed && !self.ask_allowed
+    }
+}
+
+/// Result of a batch risk check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchVerdict {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Trading state the batch was checked under.
+    pub state: TradingState,
+    /// Verdicts in batch order.
+    pub verdicts: Vec<(String, ContractVerdict)>,
+    /// Most utilized limit across every projected fill.
+    pub binding: Option<LimitUtilization>,
+}
+
+impl BatchVerdict {
+    /// Returns the verdict of a contract.
+    #[must_use]
+    pub fn verdict(&self, symbol: &str) -> Option<&ContractVerdict> {
+        self.verdicts
+            .iter()
+            .find(|(s, _)| s == symbol)
+            .map(|(_, v)| v)
+    }
+
+    /// Returns the number of quote sides withheld.
+    #[must_use]
+    pub fn withheld_sides(&self) -> usize {
+        self.verdicts
+            .iter()
+            .map(|(_, v)| usize::from(!v.bid_allowed) + usize::from(!v.ask_allowed))
+            .sum()
+    }
+}
+
+/// Evaluates a batch against the limits under a trading state.
+pub(crate) fn evaluate(
+    batch: &RiskBatch,
+    limits: &RiskLimits,
+    state: TradingState,
+) -> BatchVerdict {
+    let reducing_only =
+        state == TradingState::ReducedRisk || -batch.pnl_today > limits.limit(LimitKind::DailyLoss);
+    let current = components(&batch.current);
+    let mut binding: Option<LimitUtilization> = None;
+
+    let verdicts = batch
+        .quotes
+        .iter()
+        .map(|exposure| {
+            let bid = side(&current, &exposure.bid_fill, limits, reducing_only);
+            let ask = side(&current, &exposure.ask_fill, limits, reducing_only);
+            let top = [bid.1, ask.1]
+                .into_iter()
+                .flatten()
+                .max_by(|a, b| a.utilization.cmp(&b.utilization));
+            if let Some(top) = top
+                && binding.is_none_or(|b| top.utilization > b.utilization)
+            {
+                binding = Some(top);
+            }
+            let verdict = ContractVerdict {
+                bid_allowed: bid.0 && state != TradingState::Halted,
+                ask_allowed: ask.0 && state != TradingState::Halted,
+                binding: top.map(|u| u.kind),
+            };
+            (exposure.symbol.clone(), verdict)
+        })
+        .collect();
+
+    BatchVerdict {
+        underlying: batch.underlying.clone(),
+        state,
+        verdicts,
+        binding,
+    }
+}
+
+/// Checks one quote side.
+///
+/// Returns whether the side is allowed and its most utilized limit.
+fn side(
+    current: &[Decimal; 4],
+    fill: &Greeks,
+    limits: &RiskLimits,
+    reducing_only: bool,
+) -> (bool, Option<LimitUtilization>) {
+    let change = components(fill);
+    let mut allowed = true;
+    let mut top: Option<LimitUtilization> = None;
+    for (i, kind) in GREEK_LIMITS.into_iter().enumerate() {
+        if change[i].is_zero() {
+            continue;
+        }
+        let after = (current[i] + change[i]).abs();
+        let increases = after > current[i].abs();
+        let usage = LimitUtilization::new(kind, after, limits.limit(kind));
+        if increases && (reducing_only || usage.is_breached()) {
+            allowed = false;
+        }
+        if top.is_none_or(|t| usage.utilization > t.utilization) {
+            top = Some(usage);
+        }
+    }
+    (allowed, top)
+}
+
+/// Returns the Greeks checked by [`GREEK_LIMITS`], in order.
+const fn components(greeks: &Greeks) -> [Decimal; 4] {
+    [greeks.delta, greeks.gamma, greeks.vega, greeks.theta]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn delta(delta: Decimal) -> Greeks {
+        Greeks {
+            delta,
+            ..Greeks::zero()
+        }
+    }
+
+    fn batch(current: Decimal, pnl_today: Decimal) -> RiskBatch {
+        let quote = |symbol: &str, d: Decimal| ProjectedExposure {
+            symbol: symbol.to_string(),
+            bid_fill: delta(d),
+            ask_fill: delta(-d),
+        };
+        RiskBatch {
+            underlying: "BTC".to_string(),
+            current: delta(current),
+            pnl_today,
+            quotes: vec![quote("C1", dec!(5)), quote("C2", dec!(20))],
+        }
+    }
+
+    #[test]
+    fn test_per_contract_verdicts_and_binding() {
+        let verdict = evaluate(
+            &batch(dec!(90), Decimal::ZERO),
+            &RiskLimits::default(),
+            TradingState::Active,
+        );
+
+        let c1 = verdict.verdict("C1").unwrap();
+        assert!(c1.bid_allowed && c1.ask_allowed);
+        let c2 = verdict.verdict("C2").unwrap();
+        assert!(!c2.bid_allowed);
+        assert!(c2.ask_allowed);
+        assert_eq!(c2.binding, Some(LimitKind::Delta));
+
+        let binding = verdict.binding.unwrap();
+        assert_eq!(binding.current, dec!(110));
+        assert_eq!(verdict.withheld_sides(), 1);
+    }
+
+    #[test]
+    fn test_reduced_risk_and_halted() {
+        let limits = RiskLimits::default();
+        let reduced = evaluate(
+            &batch(dec!(10), Decimal::ZERO),
+            &limits,
+            TradingState::ReducedRisk,
+        );
+        assert!(
+            reduced
+                .verdicts
+                .iter()
+                .all(|(_, v)| !v.bid_allowed && v.ask_allowed)
+        );
+
+        // A breached daily loss also restricts to risk-reducing sides.
+        let losing = evaluate(
+            &batch(dec!(10), dec!(-60000)),
+            &limits,
+            TradingState::Active,
+        );
+        assert_eq!(losing.withheld_sides(), 2);
+
+        let halted = evaluate(
+            &batch(dec!(10), Decimal::ZERO),
+            &limits,
+            TradingState::Halted,
+        );
+        assert!(halted.verdicts.iter().all(|(_, v)| v.is_rejected()));
+    }
+}
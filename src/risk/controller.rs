@@ -0,0 +1,794 @@
+//! Risk controller module.
+//!
+//! This module provides the [`RiskController`], which checks portfolio
+//! exposures against [`RiskLimits`], keeps a history of breaches, holds the
+//! current [`TradingState`] and assembles the [`RiskDashboard`].
+//!
+//! ## Kill switch
+//!
+//! Limits configured as hard with [`RiskController::with_hard_limits`] trip
+//! the kill switch when a check finds them breached, as does a manual
+//! [`RiskController::trip`]. A trip halts trading, notifies every
+//! [`HaltListener`] so quotes are pulled, and latches: the state stays
+//! `Halted` until [`RiskController::reset_kill_switch`] is called.
+//!
+//! ## Limit reloads
+//!
+//! Limits may be set in [`LayeredLimits`], of which the most restrictive
+//! apply. [`RiskController::reload`] and [`RiskController::reload_layer`]
+//! swap them at runtime: checks see either the old or the new limits,
+//! never a mix, and the reload returns which effective limits changed.
+//! Breaches already recorded keep the limit they were checked against.
+
+use super::batch::{self, BatchVerdict, RiskBatch};
+use super::dashboard::{
+    GreeksSource, HedgerStatusSource, PnLSource, QuoteCoverageSource, QuotingPauseSource,
+    RiskDashboard,
+};
+use super::kill_switch::{HaltListener, KillSwitchTrip};
+use super::layers::{LayeredLimits, LimitLayer};
+use super::limits::{LimitBreach, LimitChange, LimitKind, LimitUtilization, RiskLimits};
+use super::state::TradingState;
+use crate::clock::{Clock, SystemClock};
+use crate::error::{Error, Result};
+use crate::pricing::{Greeks, VegaLadder};
+use crossbeam_skiplist::SkipMap;
+use rust_decimal::Decimal;
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+use std::sync::{
+    Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak,
+};
+
+/// Window of breach history shown on the dashboard, in milliseconds.
+const RECENT_BREACH_WINDOW_MS: u64 = 60 * 60 * 1000;
+
+/// Most breaches kept in the history; the oldest go first.
+const MAX_BREACH_HISTORY: usize = 4096;
+
+/// Configured limit layers and the limits they make effective.
+#[derive(Debug, Clone)]
+struct LimitState {
+    /// Limits by layer.
+    layers: LayeredLimits,
+    /// Most restrictive limits across the layers.
+    effective: RiskLimits,
+}
+
+impl LimitState {
+    fn new(layers: LayeredLimits) -> Result<Self> {
+        layers.validate()?;
+        let effective = layers
+            .effective()
+            .ok_or_else(|| Error::configuration("no limit layer set"))?;
+        Ok(Self { layers, effective })
+    }
+}
+
+/// Portfolio risk controller.
+///
+/// Data sources are optional; missing Greeks or P&L sources report zero and
+/// missing hedger, quote or pause sources are omitted from the dashboard.
+///
+/// Uses `SkipMap` for thread-safe concurrent access to the breach history.
+pub struct RiskController {
+    /// Configured limits, swapped whole on reload.
+    limits: RwLock<LimitState>,
+    /// Current trading state.
+    state: AtomicU8,
+    /// Breach history indexed by sequence number.
+    breaches: SkipMap<u64, LimitBreach>,
+    /// Next breach sequence number.
+    next_breach: AtomicU64,
+    /// Source of portfolio Greeks.
+    greeks_source: Option<Arc<dyn GreeksSource>>,
+    /// Source of daily P&L.
+    pnl_source: Option<Arc<dyn PnLSource>>,
+    /// Source of hedger status.
+    hedger_source: Option<Arc<dyn HedgerStatusSource>>,
+    /// Source of quote coverage.
+    quote_source: Option<Arc<dyn QuoteCoverageSource>>,
+    /// Source of quoting pauses.
+    pause_source: Option<Arc<dyn QuotingPauseSource>>,
+    /// Limit kinds whose breach trips the kill switch.
+    hard_limits: Vec<LimitKind>,
+    /// Latched kill switch trip, if tripped.
+    trip: Mutex<Option<KillSwitchTrip>>,
+    /// Components notified when the kill switch trips.
+    halt_listeners: Mutex<Vec<Weak<dyn HaltListener>>>,
+    /// Clock stamping breaches and trips.
+    clock: Arc<dyn Clock>,
+}
+
+impl RiskController {
+    /// Creates a new risk controller.
+    ///
+    /// # Arguments
+    ///
+    /// * `limits` - Portfolio risk limits
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the limits are invalid.
+    pub fn new(limits: RiskLimits) -> Result<Self> {
+        Self::with_layers(limits.into())
+    }
+
+    /// Creates a risk controller applying the most restrictive of layered
+    /// limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if no layer is set or a layer's
+    /// limits are invalid.
+    pub fn with_layers(layers: LayeredLimits) -> Result<Self> {
+        Ok(Self {
+            limits: RwLock::new(LimitState::new(layers)?),
+            state: AtomicU8::new(TradingState::Active.as_u8()),
+            breaches: SkipMap::new(),
+            next_breach: AtomicU64::new(0),
+            greeks_source: None,
+            pnl_source: None,
+            hedger_source: None,
+            quote_source: None,
+            pause_source: None,
+            hard_limits: Vec::new(),
+            trip: Mutex::new(None),
+            halt_listeners: Mutex::new(Vec::new()),
+            clock: Arc::new(SystemClock),
+        })
+    }
+
+    /// Sets the clock stamping breaches and trips, e.g. a simulated clock in
+    /// tests and replays.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Sets the limit kinds whose breach trips the kill switch.
+    ///
+    /// Other limits are soft: their breaches are recorded only.
+    #[must_use]
+    pub fn with_hard_limits(mut self, kinds: impl IntoIterator<Item = LimitKind>) -> Self {
+        self.hard_limits = kinds.into_iter().collect();
+        self
+    }
+
+    /// Attaches the source of portfolio Greeks.
+    #[must_use]
+    pub fn with_greeks_source(mut self, source: Arc<dyn GreeksSource>) -> Self {
+        self.greeks_source = Some(source);
+        self
+    }
+
+    /// Attaches the source of daily P&L.
+    #[must_use]
+    pub fn with_pnl_source(mut self, source: Arc<dyn PnLSource>) -> Self {
+        self.pnl_source = Some(source);
+        self
+    }
+
+    /// Attaches the source of hedger status.
+    #[must_use]
+    pub fn with_hedger_source(mut self, source: Arc<dyn HedgerStatusSource>) -> Self {
+        self.hedger_source = Some(source);
+        self
+    }
+
+    /// Attaches the source of quote coverage.
+    #[must_use]
+    pub fn with_quote_source(mut self, source: Arc<dyn QuoteCoverageSource>) -> Self {
+        self.quote_source = Some(source);
+        self
+    }
+
+    /// Attaches the source of quoting pauses.
+    #[must_use]
+    pub fn with_pause_source(mut self, source: Arc<dyn QuotingPauseSource>) -> Self {
+        self.pause_source = Some(source);
+        self
+    }
+
+    /// Returns the effective limits, the most restrictive of the layers.
+    #[must_use]
+    pub fn limits(&self) -> RiskLimits {
+        self.read_limits().effective
+    }
+
+    /// Returns the configured limit layers.
+    #[must_use]
+    pub fn layered_limits(&self) -> LayeredLimits {
+        self.read_limits().layers.clone()
+    }
+
+    /// Replaces every limit layer at once.
+    ///
+    /// Returns the effective limits that changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if no layer is set or a layer's
+    /// limits are invalid; the current limits are kept.
+    pub fn reload(&self, limits: impl Into<LayeredLimits>) -> Result<Vec<LimitChange>> {
+        let next = LimitState::new(limits.into())?;
+        let mut state = self.write_limits();
+        let changes = state.effective.diff(&next.effective);
+        *state = next;
+        Ok(changes)
+    }
+
+    /// Replaces the limits of one layer, keeping the others.
+    ///
+    /// Returns the effective limits that changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the limits are invalid; the
+    /// current limits are kept.
+    pub fn reload_layer(&self, layer: LimitLayer, limits: RiskLimits) -> Result<Vec<LimitChange>> {
+        let mut state = self.write_limits();
+        let next = LimitState::new(state.layers.clone().with_layer(layer, limits))?;
+        let changes = state.effective.diff(&next.effective);
+        *state = next;
+        Ok(changes)
+    }
+
+    fn read_limits(&self) -> RwLockReadGuard<'_, LimitState> {
+        self.limits.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write_limits(&self) -> RwLockWriteGuard<'_, LimitState> {
+        self.limits.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the current trading state.
+    #[must_use]
+    pub fn state(&self) -> TradingState {
+        TradingState::from_u8(self.state.load(Ordering::Acquire))
+    }
+
+    /// Sets the trading state.
+    ///
+    /// While the kill switch is tripped the state stays `Halted`; use
+    /// [`RiskController::reset_kill_switch`] to resume.
+    pub fn set_state(&self, state: TradingState) {
+        if self.is_tripped() {
+            return;
+        }
+        self.state.store(state.as_u8(), Ordering::Release);
+    }
+
+    /// Registers a component to notify when the kill switch trips.
+    ///
+    /// The controller holds the listener weakly, so a dropped component
+    /// is simply skipped.
+    pub fn add_halt_listener(&self, listener: &Arc<dyn HaltListener>) {
+        let mut listeners = self
+            .halt_listeners
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        listeners.retain(|l| l.strong_count() > 0);
+        listeners.push(Arc::downgrade(listener));
+    }
+
+    /// Trips the kill switch: halts trading and notifies halt listeners.
+    ///
+    /// Returns false if the switch was already tripped, in which case the
+    /// original trip is kept and nobody is notified again.
+    pub fn trip(&self, reason: impl Into<String>) -> bool {
+        self.trip_with(reason.into(), Vec::new())
+    }
+
+    /// Returns the latched trip, if the kill switch is tripped.
+    #[must_use]
+    pub fn kill_switch(&self) -> Option<KillSwitchTrip> {
+        self.lock_trip().clone()
+    }
+
+    /// Returns true if the kill switch is tripped.
+    #[must_use]
+    pub fn is_tripped(&self) -> bool {
+        self.lock_trip().is_some()
+    }
+
+    /// Resets a tripped kill switch and resumes active trading.
+    ///
+    /// Returns the trip that was cleared, if any.
+    pub fn reset_kill_switch(&self) -> Option<KillSwitchTrip> {
+        let mut trip = self.lock_trip();
+        let cleared = trip.take();
+        if cleared.is_some() {
+            self.state
+                .store(TradingState::Active.as_u8(), Ordering::Release);
+        }
+        cleared
+    }
+
+    /// Latches a trip and notifies listeners outside the latch.
+    fn trip_with(&self, reason: String, breaches: Vec<LimitBreach>) -> bool {
+        let trip = {
+            let mut latched = self.lock_trip();
+            if latched.is_some() {
+                return false;
+            }
+            let trip = KillSwitchTrip {
+                reason,
+                breaches,
+                tripped_at_ms: self.clock.now_ms(),
+            };
+            self.state
+                .store(TradingState::Halted.as_u8(), Ordering::Release);
+            *latched = Some(trip.clone());
+            trip
+        };
+        let listeners: Vec<Arc<dyn HaltListener>> = self
+            .halt_listeners
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect();
+        for listener in listeners {
+            listener.on_halt(&trip);
+        }
+        true
+    }
+
+    fn lock_trip(&self) -> MutexGuard<'_, Option<KillSwitchTrip>> {
+        self.trip.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Checks exposures against every limit, recording any breaches.
+    ///
+    /// # Arguments
+    ///
+    /// * `greeks` - Portfolio Greeks
+    /// * `pnl_today` - P&L of the trading day
+    ///
+    /// Returns the breaches found by this check.
+    pub fn check(&self, greeks: &Greeks, pnl_today: Decimal) -> Vec<LimitBreach> {
+        let timestamp_ms = self.clock.now_ms();
+        let breaches: Vec<LimitBreach> = self
+            .limits()
+            .utilization(greeks, pnl_today)
+            .into_iter()
+            .filter(|u| u.is_breached())
+            .map(|u| LimitBreach {
+                kind: u.kind,
+                current: u.current,
+                limit: u.limit,
+                timestamp_ms,
+            })
+            .collect();
+
+        self.record(&breaches);
+        breaches
+    }
+
+    /// Checks the projected fills of a whole quote batch against every
+    /// limit in one pass.
+    ///
+    /// Projected exposures are hypothetical, so no breach is recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch` - Current exposures and per-contract projected fills
+    #[must_use]
+    pub fn check_batch(&self, batch: &RiskBatch) -> BatchVerdict {
+        batch::evaluate(batch, &self.limits(), self.state())
+    }
+
+    /// Checks every pillar of a vega ladder against the pillar vega limit,
+    /// recording any breaches.
+    ///
+    /// # Arguments
+    ///
+    /// * `ladder` - Vega ladder by pillar
+    ///
+    /// Returns the breaches found by this check.
+    pub fn check_vega_ladder(&self, ladder: &VegaLadder) -> Vec<LimitBreach> {
+        let timestamp_ms = self.clock.now_ms();
+        let limit = self.limits().limit(LimitKind::PillarVega);
+        let breaches: Vec<LimitBreach> = ladder
+            .pillars
+            .iter()
+            .map(|p| LimitUtilization::new(LimitKind::PillarVega, p.atm_vol_pnl.abs(), limit))
+            .filter(|u| u.is_breached())
+            .map(|u| LimitBreach {
+                kind: u.kind,
+                current: u.current,
+                limit: u.limit,
+                timestamp_ms,
+            })
+            .collect();
+
+        self.record(&breaches);
+        breaches
+    }
+
+    /// Records breaches found outside the controller, e.g. of inventory
+    /// position limits by fills that were booked regardless.
+    ///
+    /// Like the breaches of a check, they are kept in the history and trip
+    /// the kill switch if their kind is a hard limit.
+    pub fn report_breaches(&self, breaches: &[LimitBreach]) {
+        self.record(breaches);
+    }
+
+    /// Appends breaches to the history, tripping the kill switch on any
+    /// hard-limit breach.
+    ///
+    /// A breach that persists is recorded on every check, e.g. after every
+    /// fill, so the history keeps only the newest [`MAX_BREACH_HISTORY`].
+    fn record(&self, breaches: &[LimitBreach]) {
+        for breach in breaches {
+            let seq = self.next_breach.fetch_add(1, Ordering::Relaxed);
+            self.breaches.insert(seq, *breach);
+        }
+        while self.breaches.len() > MAX_BREACH_HISTORY {
+            self.breaches.pop_front();
+        }
+        let hard: Vec<LimitBreach> = breaches
+            .iter()
+            .filter(|b| self.hard_limits.contains(&b.kind))
+            .copied()
+            .collect();
+        if let Some(first) = hard.first() {
+            let reason = format!("hard {} limit breached", first.kind);
+            self.trip_with(reason, hard);
+        }
+    }
+
+    /// Returns the recorded breaches at or after a timestamp, oldest first.
+    #[must_use]
+    pub fn breaches_since(&self, since_ms: u64) -> Vec<LimitBreach> {
+        self.breaches
+            .iter()
+            .map(|e| *e.value())
+            .filter(|b| b.timestamp_ms >= since_ms)
+            .collect()
+    }
+
+    /// Discards breaches recorded before a timestamp.
+    ///
+    /// Returns the number of breaches discarded.
+    pub fn purge_breaches_before(&self, before_ms: u64) -> usize {
+        let stale: Vec<u64> = self
+            .breaches
+            .iter()
+            .filter(|e| e.value().timestamp_ms < before_ms)
+            .map(|e| *e.key())
+            .collect();
+        for seq in &stale {
+            self.breaches.remove(seq);
+        }
+        stale.len()
+    }
+
+    /// Returns a snapshot of every risk input.
+    ///
+    /// Pulls Greeks, P&L, hedger status, quote coverage and quoting pauses
+    /// from the attached sources without recording breaches.
+    #[must_use]
+    pub fn dashboard(&self) -> RiskDashboard {
+        let timestamp_ms = self.clock.now_ms();
+        let greeks = self
+            .greeks_source
+            .as_ref()
+            .map_or_else(Greeks::zero, |s| s.portfolio_greeks());
+        let pnl_today = self
+            .pnl_source
+            .as_ref()
+            .map_or(Decimal::ZERO, |s| s.pnl_today());
+
+        RiskDashboard {
+            timestamp_ms,
+            state: self.state(),
+            greeks,
+            pnl_today,
+            utilization: self.limits().utilization(&greeks, pnl_today),
+            recent_breaches: self
+                .breaches_since(timestamp_ms.saturating_sub(RECENT_BREACH_WINDOW_MS)),
+            hedger: self.hedger_source.as_ref().map(|s| s.hedger_status()),
+            quote_coverage: self.quote_source.as_ref().map(|s| s.quote_coverage()),
+            quoting_pauses: self
+                .pause_source
+                .as_ref()
+                .map_or_else(Vec::new, |s| s.quoting_pauses()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orderbook::UnderlyingOrderBookManager;
+    use crate::pricing::{PillarSensitivity, SurfaceBumpSizes};
+    use crate::risk::HedgerStatus;
+    use rust_decimal_macros::dec;
+
+    struct FixedGreeks(Greeks);
+
+    impl GreeksSource for FixedGreeks {
+        fn portfolio_greeks(&self) -> Greeks {
+            self.0
+        }
+    }
+
+    struct FixedPnL(Decimal);
+
+    impl PnLSource for FixedPnL {
+        fn pnl_today(&self) -> Decimal {
+            self.0
+        }
+    }
+
+    struct IdleHedger;
+
+    impl HedgerStatusSource for IdleHedger {
+        fn hedger_status(&self) -> HedgerStatus {
+            HedgerStatus {
+                enabled: true,
+                ..HedgerStatus::default()
+            }
+        }
+    }
+
+    fn over_delta() -> Greeks {
+        Greeks::new(dec!(150), dec!(1), dec!(-10), dec!(100), dec!(0))
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_limits() {
+        let limits = RiskLimits {
+            max_delta: dec!(-1),
+            ..RiskLimits::default()
+        };
+        assert!(RiskController::new(limits).is_err());
+    }
+
+    #[test]
+    fn test_reload_layers_reports_effective_changes() {
+        let controller = RiskController::new(RiskLimits::default()).unwrap();
+        let greeks = Greeks::new(dec!(40), dec!(0), dec!(0), dec!(0), dec!(0));
+        assert!(controller.check(&greeks, Decimal::ZERO).is_empty());
+
+        // A tighter trader delta limit applies; a looser vega limit does not.
+        let trader = RiskLimits {
+            max_delta: dec!(30),
+            max_vega: dec!(20000),
+            ..RiskLimits::default()
+        };
+        let changes = controller.reload_layer(LimitLayer::Trader, trader).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, LimitKind::Delta);
+        assert!(changes[0].is_tightened());
+        assert_eq!(controller.check(&greeks, Decimal::ZERO).len(), 1);
+
+        // An invalid reload keeps the current limits.
+        let invalid = RiskLimits {
+            max_theta: dec!(0),
+            ..RiskLimits::default()
+        };
+        assert!(controller.reload(invalid).is_err());
+        assert_eq!(controller.limits().max_delta, dec!(30));
+
+        let changes = controller.reload(RiskLimits::default()).unwrap();
+        assert_eq!(changes[0].current, dec!(100));
+        assert_eq!(controller.layered_limits().layers().len(), 1);
+    }
+
+    #[test]
+    fn test_check_records_breaches() {
+        let controller = RiskController::new(RiskLimits::default()).unwrap();
+
+        let breaches = controller.check(&over_delta(), dec!(-60000));
+
+        assert_eq!(breaches.len(), 2);
+        assert_eq!(breaches[0].kind, LimitKind::Delta);
+        assert_eq!(breaches[1].kind, LimitKind::DailyLoss);
+        assert_eq!(controller.breaches_since(0).len(), 2);
+        assert!(controller.check(&Greeks::zero(), dec!(0)).is_empty());
+        assert_eq!(controller.breaches_since(0).len(), 2);
+    }
+
+    #[test]
+    fn test_breach_history_is_bounded() {
+        let controller = RiskController::new(RiskLimits::default()).unwrap();
+        let breach = |timestamp_ms| LimitBreach {
+            kind: LimitKind::Delta,
+            current: dec!(150),
+            limit: dec!(100),
+            timestamp_ms,
+        };
+
+        for timestamp_ms in 0..MAX_BREACH_HISTORY as u64 + 10 {
+            controller.report_breaches(&[breach(timestamp_ms)]);
+        }
+
+        let history = controller.breaches_since(0);
+        assert_eq!(history.len(), MAX_BREACH_HISTORY);
+        assert_eq!(history[0].timestamp_ms, 10);
+    }
+
+    #[test]
+    fn test_check_vega_ladder() {
+        let controller = RiskController::new(RiskLimits::default()).unwrap();
+        let pillar = |days, atm_vol_pnl| PillarSensitivity {
+            pillar_days: days,
+            atm_vol_pnl,
+            skew_pnl: Decimal::ZERO,
+            curvature_pnl: Decimal::ZERO,
+        };
+        let ladder = VegaLadder {
+            bumps: SurfaceBumpSizes::default(),
+            pillars: vec![pillar(dec!(30), dec!(-6000)), pillar(dec!(90), dec!(4000))],
+        };
+
+        let breaches = controller.check_vega_ladder(&ladder);
+
+        assert_eq!(breaches.len(), 1);
+        assert_eq!(breaches[0].kind, LimitKind::PillarVega);
+        assert_eq!(breaches[0].current, dec!(6000));
+        assert_eq!(controller.breaches_since(0).len(), 1);
+    }
+
+    #[test]
+    fn test_check_batch_records_nothing() {
+        let controller = RiskController::new(RiskLimits::default()).unwrap();
+        let batch = RiskBatch {
+            underlying: "BTC".to_string(),
+            current: Greeks::zero(),
+            pnl_today: Decimal::ZERO,
+            quotes: vec![crate::risk::ProjectedExposure {
+                symbol: "C1".to_string(),
+                bid_fill: over_delta(),
+                ask_fill: -over_delta(),
+            }],
+        };
+
+        let verdict = controller.check_batch(&batch);
+
+        assert_eq!(verdict.withheld_sides(), 2);
+        assert_eq!(verdict.binding.unwrap().kind, LimitKind::Delta);
+        assert!(controller.breaches_since(0).is_empty());
+    }
+
+    #[test]
+    fn test_purge_breaches() {
+        let controller = RiskController::new(RiskLimits::default()).unwrap();
+        controller.check(&over_delta(), dec!(0));
+
+        assert_eq!(controller.purge_breaches_before(0), 0);
+        assert_eq!(controller.purge_breaches_before(u64::MAX), 1);
+        assert!(controller.breaches_since(0).is_empty());
+    }
+
+    #[test]
+    fn test_state() {
+        let controller = RiskController::new(RiskLimits::default()).unwrap();
+        assert_eq!(controller.state(), TradingState::Active);
+
+        controller.set_state(TradingState::Halted);
+        assert_eq!(controller.state(), TradingState::Halted);
+    }
+
+    #[test]
+    fn test_hard_limit_trips_kill_switch() {
+        struct Counter(AtomicU64);
+
+        impl HaltListener for Counter {
+            fn on_halt(&self, _trip: &KillSwitchTrip) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let controller = RiskController::new(RiskLimits::default())
+            .unwrap()
+            .with_hard_limits([LimitKind::Delta])
+            .with_clock(Arc::new(crate::clock::ManualClock::new(42)));
+        let counter = Arc::new(Counter(AtomicU64::new(0)));
+        let listener: Arc<dyn HaltListener> = counter.clone();
+        controller.add_halt_listener(&listener);
+
+        // Soft breaches are recorded without halting.
+        controller.check(
+            &Greeks::new(dec!(0), dec!(80), dec!(0), dec!(0), dec!(0)),
+            dec!(0),
+        );
+        assert_eq!(controller.state(), TradingState::Active);
+
+        controller.check(&over_delta(), dec!(0));
+        controller.check(&over_delta(), dec!(0));
+        let trip = controller.kill_switch().unwrap();
+        assert_eq!(trip.breaches[0].kind, LimitKind::Delta);
+        assert_eq!(trip.breaches[0].timestamp_ms, 42);
+        assert_eq!(trip.tripped_at_ms, 42);
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+
+        // Latched until reset.
+        controller.set_state(TradingState::Active);
+        assert_eq!(controller.state(), TradingState::Halted);
+        assert!(controller.reset_kill_switch().is_some());
+        assert_eq!(controller.state(), TradingState::Active);
+        assert!(controller.trip("operator"));
+        assert!(!controller.trip("again"));
+        assert_eq!(counter.0.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_dashboard_without_sources() {
+        let controller = RiskController::new(RiskLimits::default()).unwrap();
+
+        let dashboard = controller.dashboard();
+
+        assert_eq!(dashboard.greeks, Greeks::zero());
+        assert_eq!(dashboard.pnl_today, Decimal::ZERO);
+        assert_eq!(dashboard.utilization.len(), 5);
+        assert!(dashboard.hedger.is_none());
+        assert!(dashboard.quote_coverage.is_none());
+    }
+
+    #[test]
+    fn test_dashboard_aggregates_sources() {
+        let manager = Arc::new(UnderlyingOrderBookManager::new());
+        let controller = RiskController::new(RiskLimits::default())
+            .unwrap()
+            .with_greeks_source(Arc::new(FixedGreeks(over_delta())))
+            .with_pnl_source(Arc::new(FixedPnL(dec!(-250))))
+            .with_hedger_source(Arc::new(IdleHedger))
+            .with_quote_source(manager);
+        controller.check(&over_delta(), dec!(-250));
+        controller.set_state(TradingState::ReducedRisk);
+
+        let dashboard = controller.dashboard();
+
+        assert_eq!(dashboard.state, TradingState::ReducedRisk);
+        assert_eq!(dashboard.greeks.delta, dec!(150));
+        assert_eq!(dashboard.pnl_today, dec!(-250));
+        assert_eq!(dashboard.max_utilization(), dec!(1.5));
+        assert_eq!(dashboard.recent_breaches.len(), 1);
+        assert!(dashboard.hedger.unwrap().enabled);
+        assert_eq!(dashboard.quote_coverage.unwrap().total_contracts, 0);
+    }
+
+    #[test]
+    fn test_dashboard_lists_quoting_pauses() {
+        use crate::clock::ManualClock;
+        use crate::quoting::{PauseReason, PauseScope, QuotingPauses};
+
+        let pauses = Arc::new(QuotingPauses::new(Arc::new(ManualClock::new(0))));
+        pauses
+            .pause(
+                PauseScope::Underlying("BTC".to_string()),
+                PauseReason::MarketEvent,
+                "FOMC",
+                "ops",
+                Some(60_000),
+            )
+            .unwrap();
+        let controller = RiskController::new(RiskLimits::default())
+            .unwrap()
+            .with_pause_source(pauses);
+
+        let dashboard = controller.dashboard();
+
+        assert_eq!(dashboard.quoting_pauses.len(), 1);
+        assert_eq!(dashboard.quoting_pauses[0].reason, PauseReason::MarketEvent);
+    }
+
+    #[test]
+    fn test_dashboard_serializes() {
+        let controller = RiskController::new(RiskLimits::default())
+            .unwrap()
+            .with_greeks_source(Arc::new(FixedGreeks(over_delta())));
+
+        let dashboard = controller.dashboard();
+        let json = serde_json::to_string(&dashboard).unwrap();
+        let restored: RiskDashboard = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, dashboard);
+        assert!(dashboard.to_string().contains("Active"));
+    }
+}
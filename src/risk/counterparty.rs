@@ -0,0 +1,439 @@
+//! Counterparty limits module.
+//!
+//! This module provides the [`CounterpartyRegistry`], which tracks
+//! per-counterparty notional, delta and vega exposure from accepted client
+//! trades, enforces per-counterparty limits before new flow is accepted,
+//! and releases exposure as trades settle.
+
+use crate::error::{Error, Result};
+use crossbeam_skiplist::SkipMap;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// Exposure to a counterparty.
+///
+/// Notional is gross (always added); delta and vega are signed from our
+/// side and limited in absolute value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct CounterpartyExposure {
+    /// Gross notional.
+    pub notional: Decimal,
+    /// Net delta.
+    pub delta: Decimal,
+    /// Net vega.
+    pub vega: Decimal,
+}
+
+impl CounterpartyExposure {
+    /// Creates an exposure.
+    #[must_use]
+    pub const fn new(notional: Decimal, delta: Decimal, vega: Decimal) -> Self {
+        Self {
+            notional,
+            delta,
+            vega,
+        }
+    }
+}
+
+impl std::ops::Add for CounterpartyExposure {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            notional: self.notional + rhs.notional.abs(),
+            delta: self.delta + rhs.delta,
+            vega: self.vega + rhs.vega,
+        }
+    }
+}
+
+impl std::ops::Sub for CounterpartyExposure {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            notional: (self.notional - rhs.notional.abs()).max(Decimal::ZERO),
+            delta: self.delta - rhs.delta,
+            vega: self.vega - rhs.vega,
+        }
+    }
+}
+
+/// Exposure limits for one counterparty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CounterpartyLimits {
+    /// Maximum gross notional.
+    pub max_notional: Decimal,
+    /// Maximum absolute net delta.
+    pub max_delta: Decimal,
+    /// Maximum absolute net vega.
+    pub max_vega: Decimal,
+}
+
+impl CounterpartyLimits {
+    /// Validates the limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if any limit is negative.
+    pub fn validate(&self) -> Result<()> {
+        if self.max_notional < Decimal::ZERO
+            || self.max_delta < Decimal::ZERO
+            || self.max_vega < Decimal::ZERO
+        {
+            return Err(Error::configuration(
+                "counterparty limits must be non-negative",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks an exposure against the limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::RiskLimitBreached` naming the first breached limit.
+    fn check(&self, counterparty: &str, exposure: &CounterpartyExposure) -> Result<()> {
+        let checks = [
+            ("notional", exposure.notional, self.max_notional),
+            ("delta", exposure.delta.abs(), self.max_delta),
+            ("vega", exposure.vega.abs(), self.max_vega),
+        ];
+        for (kind, current, limit) in checks {
+            if current > limit {
+                return Err(Error::risk_limit_breached(format!(
+                    "counterparty {counterparty} {kind} {current} > {limit}"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A client trade that holds exposure until it settles.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CounterpartyTrade {
+    /// Trade identifier, unique per counterparty.
+    pub trade_id: String,
+    /// Exposure created by the trade.
+    pub exposure: CounterpartyExposure,
+    /// Settlement time in milliseconds.
+    pub settles_at_ms: u64,
+}
+
+/// Mutable state of one counterparty.
+#[derive(Debug)]
+struct AccountState {
+    limits: CounterpartyLimits,
+    exposure: CounterpartyExposure,
+    open_trades: Vec<CounterpartyTrade>,
+}
+
+/// Per-counterparty exposure tracking and limits.
+///
+/// Uses `SkipMap` for thread-safe concurrent access across counterparties;
+/// each counterparty's check-and-record is serialized by its own lock.
+#[derive(Default)]
+pub struct CounterpartyRegistry {
+    /// Accounts indexed by counterparty identifier.
+    accounts: SkipMap<String, Arc<Mutex<AccountState>>>,
+}
+
+impl CounterpartyRegistry {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a counterparty, or replaces its limits if already known.
+    ///
+    /// # Arguments
+    ///
+    /// * `counterparty` - Counterparty identifier
+    /// * `limits` - Exposure limits
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the limits are invalid.
+    pub fn register(
+        &self,
+        counterparty: impl Into<String>,
+        limits: CounterpartyLimits,
+    ) -> Result<()> {
+        limits.validate()?;
+        let counterparty = counterparty.into();
+        if let Some(entry) = self.accounts.get(&counterparty) {
+            let mut state = lock(entry.value())?;
+            state.limits = limits;
+            return Ok(());
+        }
+        self.accounts.insert(
+            counterparty,
+            Arc::new(Mutex::new(AccountState {
+                limits,
+                exposure: CounterpartyExposure::default(),
+                open_trades: Vec::new(),
+            })),
+        );
+        Ok(())
+    }
+
+    /// Removes a counterparty and its open trades.
+    pub fn remove(&self, counterparty: &str) -> bool {
+        self.accounts.remove(counterparty).is_some()
+    }
+
+    /// Returns the number of registered counterparties.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    /// Returns true if no counterparty is registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Returns the limits of a counterparty.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the counterparty is unknown.
+    pub fn limits(&self, counterparty: &str) -> Result<CounterpartyLimits> {
+        let account = self.account(counterparty)?;
+        let state = lock(&account)?;
+        Ok(state.limits)
+    }
+
+    /// Returns the current exposure to a counterparty.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the counterparty is unknown.
+    pub fn exposure(&self, counterparty: &str) -> Result<CounterpartyExposure> {
+        let account = self.account(counterparty)?;
+        let state = lock(&account)?;
+        Ok(state.exposure)
+    }
+
+    /// Checks whether additional exposure would fit within the limits,
+    /// without recording it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the counterparty is unknown, or
+    /// `Error::RiskLimitBreached` if a limit would be breached.
+    pub fn check(&self, counterparty: &str, exposure: &CounterpartyExposure) -> Result<()> {
+        let account = self.account(counterparty)?;
+        let state = lock(&account)?;
+        state
+            .limits
+            .check(counterparty, &(state.exposure + *exposure))
+    }
+
+    /// Accepts a trade if it fits within the limits and records its
+    /// exposure until settlement.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the counterparty is unknown or
+    /// the trade id is already open, or `Error::RiskLimitBreached` if a
+    /// limit would be breached. Nothing is recorded on error.
+    pub fn accept(&self, counterparty: &str, trade: CounterpartyTrade) -> Result<()> {
+        let account = self.account(counterparty)?;
+        let mut state = lock(&account)?;
+        if state
+            .open_trades
+            .iter()
+            .any(|t| t.trade_id == trade.trade_id)
+        {
+            return Err(Error::validation(format!(
+                "trade {} already open for counterparty {counterparty}",
+                trade.trade_id
+            )));
+        }
+        let projected = state.exposure + trade.exposure;
+        state.limits.check(counterparty, &projected)?;
+        state.exposure = projected;
+        state.open_trades.push(trade);
+        Ok(())
+    }
+
+    /// Releases the exposure of one trade.
+    ///
+    /// Returns false if the trade is not open.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the counterparty is unknown.
+    pub fn settle_trade(&self, counterparty: &str, trade_id: &str) -> Result<bool> {
+        let account = self.account(counterparty)?;
+        let mut state = lock(&account)?;
+        match state
+            .open_trades
+            .iter()
+            .position(|t| t.trade_id == trade_id)
+        {
+            Some(index) => {
+                let trade = state.open_trades.swap_remove(index);
+                state.exposure = state.exposure - trade.exposure;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Releases the exposure of every trade settled by a time, across all
+    /// counterparties.
+    ///
+    /// Returns the number of trades settled.
+    pub fn settle_due(&self, now_ms: u64) -> usize {
+        let mut settled = 0;
+        for entry in &self.accounts {
+            let Ok(mut state) = entry.value().lock() else {
+                continue;
+            };
+            let (due, open): (Vec<_>, Vec<_>) = std::mem::take(&mut state.open_trades)
+                .into_iter()
+                .partition(|t| t.settles_at_ms <= now_ms);
+            for trade in &due {
+                state.exposure = state.exposure - trade.exposure;
+            }
+            state.open_trades = open;
+            settled += due.len();
+        }
+        settled
+    }
+
+    /// Returns the open trades of a counterparty.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the counterparty is unknown.
+    pub fn open_trades(&self, counterparty: &str) -> Result<Vec<CounterpartyTrade>> {
+        let account = self.account(counterparty)?;
+        let state = lock(&account)?;
+        Ok(state.open_trades.clone())
+    }
+
+    /// Looks up a counterparty account.
+    fn account(&self, counterparty: &str) -> Result<Arc<Mutex<AccountState>>> {
+        self.accounts
+            .get(counterparty)
+            .map(|e| Arc::clone(e.value()))
+            .ok_or_else(|| Error::validation(format!("unknown counterparty: {counterparty}")))
+    }
+}
+
+/// Locks an account, mapping poisoning to an error.
+fn lock(account: &Mutex<AccountState>) -> Result<std::sync::MutexGuard<'_, AccountState>> {
+    account
+        .lock()
+        .map_err(|_| Error::validation("counterparty account lock poisoned"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn registry() -> CounterpartyRegistry {
+        let registry = CounterpartyRegistry::new();
+        registry
+            .register(
+                "ACME",
+                CounterpartyLimits {
+                    max_notional: dec!(1000000),
+                    max_delta: dec!(50),
+                    max_vega: dec!(2000),
+                },
+            )
+            .unwrap();
+        registry
+    }
+
+    fn trade(id: &str, delta: Decimal, settles_at_ms: u64) -> CounterpartyTrade {
+        CounterpartyTrade {
+            trade_id: id.to_string(),
+            exposure: CounterpartyExposure::new(dec!(400000), delta, dec!(500)),
+            settles_at_ms,
+        }
+    }
+
+    #[test]
+    fn test_unknown_counterparty() {
+        let registry = registry();
+        assert!(registry.exposure("NOPE").is_err());
+        assert!(registry.accept("NOPE", trade("t1", dec!(1), 0)).is_err());
+    }
+
+    #[test]
+    fn test_invalid_limits() {
+        let limits = CounterpartyLimits {
+            max_notional: dec!(-1),
+            max_delta: dec!(1),
+            max_vega: dec!(1),
+        };
+        assert!(CounterpartyRegistry::new().register("X", limits).is_err());
+    }
+
+    #[test]
+    fn test_accept_accumulates_exposure() {
+        let registry = registry();
+        registry.accept("ACME", trade("t1", dec!(30), 100)).unwrap();
+        registry
+            .accept("ACME", trade("t2", dec!(-10), 100))
+            .unwrap();
+
+        let exposure = registry.exposure("ACME").unwrap();
+        assert_eq!(exposure.notional, dec!(800000));
+        assert_eq!(exposure.delta, dec!(20));
+        assert_eq!(exposure.vega, dec!(1000));
+    }
+
+    #[test]
+    fn test_breach_rejects_without_recording() {
+        let registry = registry();
+        registry.accept("ACME", trade("t1", dec!(30), 100)).unwrap();
+
+        let err = registry.accept("ACME", trade("t2", dec!(30), 100));
+
+        assert!(matches!(err, Err(Error::RiskLimitBreached { .. })));
+        assert_eq!(registry.exposure("ACME").unwrap().delta, dec!(30));
+        assert!(
+            registry
+                .check(
+                    "ACME",
+                    &CounterpartyExposure::new(dec!(700000), dec!(0), dec!(0))
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_duplicate_trade_id() {
+        let registry = registry();
+        registry.accept("ACME", trade("t1", dec!(1), 100)).unwrap();
+        assert!(registry.accept("ACME", trade("t1", dec!(1), 100)).is_err());
+    }
+
+    #[test]
+    fn test_settlement_releases_exposure() {
+        let registry = registry();
+        registry.accept("ACME", trade("t1", dec!(30), 100)).unwrap();
+        registry.accept("ACME", trade("t2", dec!(10), 200)).unwrap();
+
+        assert_eq!(registry.settle_due(150), 1);
+        assert_eq!(registry.exposure("ACME").unwrap().delta, dec!(10));
+        assert!(registry.settle_trade("ACME", "t2").unwrap());
+        assert!(!registry.settle_trade("ACME", "t2").unwrap());
+        assert_eq!(
+            registry.exposure("ACME").unwrap(),
+            CounterpartyExposure::default()
+        );
+    }
+}
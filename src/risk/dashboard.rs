@@ -0,0 +1,204 @@
+//! Risk dashboard module.
+//!
+//! This module provides the [`RiskDashboard`] snapshot and the source traits
+//! through which the risk controller pulls Greeks, P&L, hedger status and
+//! quote coverage from the components that own them.
+
+use super::limits::{LimitBreach, LimitUtilization};
+use super::state::TradingState;
+use crate::inventory::InventoryManager;
+use crate::orderbook::UnderlyingOrderBookManager;
+use crate::pricing::Greeks;
+use crate::quoting::{QuotingPause, QuotingPauses};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Provides current portfolio Greeks.
+pub trait GreeksSource: Send + Sync {
+    /// Returns the current portfolio Greeks.
+    fn portfolio_greeks(&self) -> Greeks;
+}
+
+/// Provides P&L of the current trading day.
+pub trait PnLSource: Send + Sync {
+    /// Returns the P&L of the current trading day.
+    fn pnl_today(&self) -> Decimal;
+}
+
+/// Provides the status of the delta hedger.
+pub trait HedgerStatusSource: Send + Sync {
+    /// Returns the current hedger status.
+    fn hedger_status(&self) -> HedgerStatus;
+}
+
+/// Provides quote coverage statistics.
+pub trait QuoteCoverageSource: Send + Sync {
+    /// Returns the current quote coverage.
+    fn quote_coverage(&self) -> QuoteCoverage;
+}
+
+/// Provides the active quoting pauses.
+pub trait QuotingPauseSource: Send + Sync {
+    /// Returns the active quoting pauses.
+    fn quoting_pauses(&self) -> Vec<QuotingPause>;
+}
+
+/// Status of the delta hedger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct HedgerStatus {
+    /// Whether the hedger is enabled.
+    pub enabled: bool,
+    /// Delta remaining after pending hedges complete.
+    pub residual_delta: Decimal,
+    /// Number of hedge orders in flight.
+    pub pending_orders: usize,
+    /// Timestamp of the last hedge in milliseconds.
+    pub last_hedge_ms: Option<u64>,
+}
+
+/// Quote coverage across listed contracts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct QuoteCoverage {
+    /// Number of listed contracts.
+    pub total_contracts: usize,
+    /// Contracts with both a bid and an ask.
+    pub two_sided: usize,
+    /// Contracts with only one side.
+    pub one_sided: usize,
+    /// Contracts with no quote.
+    pub unquoted: usize,
+}
+
+impl QuoteCoverage {
+    /// Returns the fraction of contracts quoted two-sided.
+    #[must_use]
+    pub fn two_sided_ratio(&self) -> Decimal {
+        if self.total_contracts == 0 {
+            return Decimal::ZERO;
+        }
+        Decimal::from(self.two_sided) / Decimal::from(self.total_contracts)
+    }
+}
+
+impl GreeksSource for InventoryManager {
+    fn portfolio_greeks(&self) -> Greeks {
+        self.total_greeks()
+    }
+}
+
+impl QuotingPauseSource for QuotingPauses {
+    fn quoting_pauses(&self) -> Vec<QuotingPause> {
+        self.active()
+    }
+}
+
+impl QuoteCoverageSource for UnderlyingOrderBookManager {
+    fn quote_coverage(&self) -> QuoteCoverage {
+        let mut coverage = QuoteCoverage::default();
+        for book in self.registry().books() {
+            let quote = book.best_quote();
+            coverage.total_contracts += 1;
+            if quote.is_two_sided() {
+                coverage.two_sided += 1;
+            } else if quote.is_empty() {
+                coverage.unquoted += 1;
+            } else {
+                coverage.one_sided += 1;
+            }
+        }
+        coverage
+    }
+}
+
+/// Point-in-time snapshot of every risk input, suitable for polling by UIs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskDashboard {
+    /// Snapshot timestamp in milliseconds.
+    pub timestamp_ms: u64,
+    /// Current trading state.
+    pub state: TradingState,
+    /// Current portfolio Greeks.
+    pub greeks: Greeks,
+    /// P&L of the current trading day.
+    pub pnl_today: Decimal,
+    /// Utilization of every limit.
+    pub utilization: Vec<LimitUtilization>,
+    /// Breaches recorded in the last hour, oldest first.
+    pub recent_breaches: Vec<LimitBreach>,
+    /// Hedger status, if a hedger is attached.
+    pub hedger: Option<HedgerStatus>,
+    /// Quote coverage, if a quote source is attached.
+    pub quote_coverage: Option<QuoteCoverage>,
+    /// Active quoting pauses, if a pause source is attached.
+    #[serde(default)]
+    pub quoting_pauses: Vec<QuotingPause>,
+}
+
+impl RiskDashboard {
+    /// Returns the highest limit utilization.
+    #[must_use]
+    pub fn max_utilization(&self) -> Decimal {
+        self.utilization
+            .iter()
+            .map(|u| u.utilization)
+            .max()
+            .unwrap_or(Decimal::ZERO)
+    }
+}
+
+impl std::fmt::Display for RiskDashboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} | {} | P&L {} | max utilization {} | {} breaches in last hour",
+            self.state,
+            self.greeks,
+            self.pnl_today,
+            self.max_utilization(),
+            self.recent_breaches.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::ExpirationDate;
+    use optionstratlib::prelude::pos_or_panic;
+    use orderbook_rs::{OrderId, Side};
+
+    #[test]
+    fn test_quote_coverage_from_hierarchy() {
+        let manager = UnderlyingOrderBookManager::new();
+        let chain = manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(ExpirationDate::Days(pos_or_panic!(30.0)));
+        let strike = chain.get_or_create_strike(50000);
+        strike
+            .call()
+            .add_limit_order(OrderId::new(), Side::Buy, 100, 1)
+            .unwrap();
+        strike
+            .call()
+            .add_limit_order(OrderId::new(), Side::Sell, 110, 1)
+            .unwrap();
+        strike
+            .put()
+            .add_limit_order(OrderId::new(), Side::Buy, 90, 1)
+            .unwrap();
+        drop(chain.get_or_create_strike(55000));
+
+        let coverage = manager.quote_coverage();
+
+        assert_eq!(coverage.total_contracts, 4);
+        assert_eq!(coverage.two_sided, 1);
+        assert_eq!(coverage.one_sided, 1);
+        assert_eq!(coverage.unquoted, 2);
+        assert_eq!(coverage.two_sided_ratio(), Decimal::new(25, 2));
+    }
+
+    #[test]
+    fn test_empty_coverage_ratio() {
+        assert_eq!(QuoteCoverage::default().two_sided_ratio(), Decimal::ZERO);
+    }
+}
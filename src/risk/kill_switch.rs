@@ -0,0 +1,28 @@
+//! Kill switch module.
+//!
+//! This module provides [`KillSwitchTrip`], the latched record of why the
+//! risk controller halted trading, and the [`HaltListener`] trait through
+//! which components pull their quotes when it trips.
+
+use super::limits::LimitBreach;
+use serde::{Deserialize, Serialize};
+
+/// Why and when the kill switch tripped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KillSwitchTrip {
+    /// Reason given for the halt.
+    pub reason: String,
+    /// Hard-limit breaches that tripped the switch; empty for a manual trip.
+    pub breaches: Vec<LimitBreach>,
+    /// Trip time in milliseconds.
+    pub tripped_at_ms: u64,
+}
+
+/// Component notified when the kill switch trips.
+///
+/// Listeners run on the thread that tripped the switch and should only pull
+/// quotes or cancel orders.
+pub trait HaltListener: Send + Sync {
+    /// Called once per trip.
+    fn on_halt(&self, trip: &KillSwitchTrip);
+}
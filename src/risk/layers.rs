@@ -0,0 +1,178 @@
+//! Limit layers module.
+//!
+//! This module provides [`LayeredLimits`], risk limits set independently by
+//! the exchange, the firm, the desk and the trader. The effective limits
+//! are the most restrictive of the layers, limit by limit, so a trader's
+//! tighter delta limit and the firm's tighter loss limit both apply.
+
+use super::limits::{LimitKind, RiskLimits};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A party imposing risk limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LimitLayer {
+    /// Limits imposed by the exchange.
+    Exchange,
+    /// Firm-wide limits.
+    Firm,
+    /// Limits of the trading desk.
+    Desk,
+    /// Limits of the individual trader.
+    Trader,
+}
+
+impl std::fmt::Display for LimitLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exchange => write!(f, "exchange"),
+            Self::Firm => write!(f, "firm"),
+            Self::Desk => write!(f, "desk"),
+            Self::Trader => write!(f, "trader"),
+        }
+    }
+}
+
+/// Risk limits by layer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayeredLimits {
+    /// Limits of each layer set.
+    layers: BTreeMap<LimitLayer, RiskLimits>,
+}
+
+impl LayeredLimits {
+    /// Creates limits without any layer.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the limits of a layer.
+    #[must_use]
+    pub fn with_layer(mut self, layer: LimitLayer, limits: RiskLimits) -> Self {
+        self.layers.insert(layer, limits);
+        self
+    }
+
+    /// Sets the limits of a layer, returning the previous ones.
+    pub fn set(&mut self, layer: LimitLayer, limits: RiskLimits) -> Option<RiskLimits> {
+        self.layers.insert(layer, limits)
+    }
+
+    /// Removes a layer, returning its limits.
+    pub fn remove(&mut self, layer: LimitLayer) -> Option<RiskLimits> {
+        self.layers.remove(&layer)
+    }
+
+    /// Returns the limits of a layer.
+    #[must_use]
+    pub fn layer(&self, layer: LimitLayer) -> Option<&RiskLimits> {
+        self.layers.get(&layer)
+    }
+
+    /// Returns the layers set, outermost first.
+    #[must_use]
+    pub fn layers(&self) -> Vec<(LimitLayer, RiskLimits)> {
+        self.layers.iter().map(|(l, r)| (*l, *r)).collect()
+    }
+
+    /// Returns the most restrictive limits across the layers, or `None`
+    /// if no layer is set.
+    #[must_use]
+    pub fn effective(&self) -> Option<RiskLimits> {
+        self.layers.values().copied().reduce(|a, b| a.min(&b))
+    }
+
+    /// Returns the layer setting the effective limit of a kind; on a tie
+    /// the outermost layer.
+    #[must_use]
+    pub fn binding_layer(&self, kind: LimitKind) -> Option<LimitLayer> {
+        self.layers
+            .iter()
+            .min_by_key(|(_, limits)| limits.limit(kind))
+            .map(|(layer, _)| *layer)
+    }
+
+    /// Validates every layer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if no layer is set or a layer's
+    /// limits are invalid.
+    pub fn validate(&self) -> Result<()> {
+        if self.layers.is_empty() {
+            return Err(Error::configuration("at least one limit layer is required"));
+        }
+        for (layer, limits) in &self.layers {
+            if let Err(Error::ConfigurationError { message }) = limits.validate() {
+                return Err(Error::configuration(format!("{layer} {message}")));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<RiskLimits> for LayeredLimits {
+    /// Sets the limits as the firm layer.
+    fn from(limits: RiskLimits) -> Self {
+        Self::new().with_layer(LimitLayer::Firm, limits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_effective_is_most_restrictive() {
+        let layers = LayeredLimits::new()
+            .with_layer(
+                LimitLayer::Exchange,
+                RiskLimits {
+                    max_daily_loss: dec!(20000),
+                    ..RiskLimits::default()
+                },
+            )
+            .with_layer(
+                LimitLayer::Trader,
+                RiskLimits {
+                    max_delta: dec!(25),
+                    max_daily_loss: dec!(90000),
+                    ..RiskLimits::default()
+                },
+            );
+
+        let effective = layers.effective().unwrap();
+        assert_eq!(effective.max_delta, dec!(25));
+        assert_eq!(effective.max_daily_loss, dec!(20000));
+        assert_eq!(effective.max_vega, RiskLimits::default().max_vega);
+        assert_eq!(
+            layers.binding_layer(LimitKind::Delta),
+            Some(LimitLayer::Trader)
+        );
+        assert_eq!(
+            layers.binding_layer(LimitKind::DailyLoss),
+            Some(LimitLayer::Exchange)
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(LayeredLimits::new().validate().is_err());
+        assert!(
+            LayeredLimits::from(RiskLimits::default())
+                .validate()
+                .is_ok()
+        );
+        let invalid = LayeredLimits::from(RiskLimits::default()).with_layer(
+            LimitLayer::Desk,
+            RiskLimits {
+                max_gamma: dec!(0),
+                ..RiskLimits::default()
+            },
+        );
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -0,0 +1,305 @@
+//! Risk limits module.
+//!
+//! This module provides [`RiskLimits`], the limit kinds they define, and the
+//! utilization and breach records produced when exposures are checked
+//! against them.
+
+use crate::error::{Error, Result};
+use crate::pricing::Greeks;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// A limited risk dimension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LimitKind {
+    /// Absolute portfolio delta.
+    Delta,
+    /// Absolute portfolio gamma.
+    Gamma,
+    /// Absolute portfolio vega.
+    Vega,
+    /// Absolute portfolio theta.
+    Theta,
+    /// Loss over the trading day.
+    DailyLoss,
+    /// Absolute ATM vega of a single volatility surface pillar.
+    PillarVega,
+    /// Inventory position in contracts, limited by the inventory's
+    /// `PositionLimits` rather than by [`RiskLimits`].
+    Position,
+}
+
+impl std::fmt::Display for LimitKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Delta => write!(f, "delta"),
+            Self::Gamma => write!(f, "gamma"),
+            Self::Vega => write!(f, "vega"),
+            Self::Theta => write!(f, "theta"),
+            Self::DailyLoss => write!(f, "daily_loss"),
+            Self::PillarVega => write!(f, "pillar_vega"),
+            Self::Position => write!(f, "position"),
+        }
+    }
+}
+
+/// Portfolio risk limits.
+///
+/// Greek limits apply to absolute values. `max_daily_loss` is a positive
+/// amount compared against the negated P&L of the day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskLimits {
+    /// Maximum absolute delta.
+    pub max_delta: Decimal,
+    /// Maximum absolute gamma.
+    pub max_gamma: Decimal,
+    /// Maximum absolute vega.
+    pub max_vega: Decimal,
+    /// Maximum absolute theta.
+    pub max_theta: Decimal,
+    /// Maximum loss over the trading day.
+    pub max_daily_loss: Decimal,
+    /// Maximum absolute ATM vega of any single surface pillar.
+    #[serde(default = "default_max_pillar_vega")]
+    pub max_pillar_vega: Decimal,
+}
+
+fn default_max_pillar_vega() -> Decimal {
+    dec!(5000)
+}
+
+impl Default for RiskLimits {
+    fn default() -> Self {
+        Self {
+            max_delta: dec!(100),
+            max_gamma: dec!(50),
+            max_vega: dec!(10000),
+            max_theta: dec!(5000),
+            max_daily_loss: dec!(50000),
+            max_pillar_vega: default_max_pillar_vega(),
+        }
+    }
+}
+
+impl RiskLimits {
+    /// Returns the limit for a kind.
+    ///
+    /// Position limits are not part of the risk limits, so their limit is
+    /// zero here.
+    #[must_use]
+    pub const fn limit(&self, kind: LimitKind) -> Decimal {
+        match kind {
+            LimitKind::Delta => self.max_delta,
+            LimitKind::Gamma => self.max_gamma,
+            LimitKind::Vega => self.max_vega,
+            LimitKind::Theta => self.max_theta,
+            LimitKind::DailyLoss => self.max_daily_loss,
+            LimitKind::PillarVega => self.max_pillar_vega,
+            LimitKind::Position => Decimal::ZERO,
+        }
+    }
+
+    /// Validates the limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if any limit is not positive.
+    pub fn validate(&self) -> Result<()> {
+        for kind in ALL_LIMITS.into_iter().chain([LimitKind::PillarVega]) {
+            if self.limit(kind) <= Decimal::ZERO {
+                return Err(Error::configuration(format!(
+                    "{kind} limit must be positive"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the utilization of every limit for the given exposures.
+    ///
+    /// # Arguments
+    ///
+    /// * `greeks` - Portfolio Greeks
+    /// * `pnl_today` - P&L of the trading day
+    #[must_use]
+    pub fn utilization(&self, greeks: &Greeks, pnl_today: Decimal) -> Vec<LimitUtilization> {
+        ALL_LIMITS
+            .iter()
+            .map(|&kind| {
+                let current = match kind {
+                    LimitKind::Delta => greeks.delta.abs(),
+                    LimitKind::Gamma => greeks.gamma.abs(),
+                    LimitKind::Vega => greeks.vega.abs(),
+                    LimitKind::Theta => greeks.theta.abs(),
+                    LimitKind::DailyLoss => (-pnl_today).max(Decimal::ZERO),
+                    LimitKind::PillarVega | LimitKind::Position => Decimal::ZERO,
+                };
+                LimitUtilization::new(kind, current, self.limit(kind))
+            })
+            .collect()
+    }
+
+    /// Returns the tighter of two sets of limits, limit by limit.
+    #[must_use]
+    pub fn min(&self, other: &Self) -> Self {
+        Self {
+            max_delta: self.max_delta.min(other.max_delta),
+            max_gamma: self.max_gamma.min(other.max_gamma),
+            max_vega: self.max_vega.min(other.max_vega),
+            max_theta: self.max_theta.min(other.max_theta),
+            max_daily_loss: self.max_daily_loss.min(other.max_daily_loss),
+            max_pillar_vega: self.max_pillar_vega.min(other.max_pillar_vega),
+        }
+    }
+
+    /// Returns the limits that differ in `other`, in reporting order.
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> Vec<LimitChange> {
+        ALL_LIMITS
+            .into_iter()
+            .chain([LimitKind::PillarVega])
+            .filter(|&kind| self.limit(kind) != other.limit(kind))
+            .map(|kind| LimitChange {
+                kind,
+                previous: self.limit(kind),
+                current: other.limit(kind),
+            })
+            .collect()
+    }
+}
+
+/// A limit whose value changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitChange {
+    /// The limited dimension.
+    pub kind: LimitKind,
+    /// Limit before the change.
+    pub previous: Decimal,
+    /// Limit after the change.
+    pub current: Decimal,
+}
+
+impl LimitChange {
+    /// Returns true if the limit was lowered.
+    #[must_use]
+    pub fn is_tightened(&self) -> bool {
+        self.current < self.previous
+    }
+}
+
+/// Portfolio-level limit kinds, in reporting order.
+///
+/// Pillar vega is checked against a vega ladder rather than portfolio Greeks.
+const ALL_LIMITS: [LimitKind; 5] = [
+    LimitKind::Delta,
+    LimitKind::Gamma,
+    LimitKind::Vega,
+    LimitKind::Theta,
+    LimitKind::DailyLoss,
+];
+
+/// Usage of a single limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitUtilization {
+    /// The limited dimension.
+    pub kind: LimitKind,
+    /// Current exposure.
+    pub current: Decimal,
+    /// Configured limit.
+    pub limit: Decimal,
+    /// Fraction of the limit in use (1 = at the limit).
+    pub utilization: Decimal,
+}
+
+impl LimitUtilization {
+    /// Creates a utilization record.
+    #[must_use]
+    pub fn new(kind: LimitKind, current: Decimal, limit: Decimal) -> Self {
+        let utilization = if limit.is_zero() {
+            Decimal::ZERO
+        } else {
+            current / limit
+        };
+        Self {
+            kind,
+            current,
+            limit,
+            utilization,
+        }
+    }
+
+    /// Returns true if the exposure exceeds the limit.
+    #[must_use]
+    pub fn is_breached(&self) -> bool {
+        self.current > self.limit
+    }
+}
+
+/// A recorded limit breach.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitBreach {
+    /// The breached dimension.
+    pub kind: LimitKind,
+    /// Exposure at the time of the breach.
+    pub current: Decimal,
+    /// Limit at the time of the breach.
+    pub limit: Decimal,
+    /// Breach timestamp in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+impl std::fmt::Display for LimitBreach {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} limit breached: {} > {} at {}",
+            self.kind, self.current, self.limit, self.timestamp_ms
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_limits_are_valid() {
+        assert!(RiskLimits::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_non_positive() {
+        let limits = RiskLimits {
+            max_vega: Decimal::ZERO,
+            ..RiskLimits::default()
+        };
+        assert!(limits.validate().is_err());
+
+        let limits = RiskLimits {
+            max_pillar_vega: Decimal::ZERO,
+            ..RiskLimits::default()
+        };
+        assert!(limits.validate().is_err());
+    }
+
+    #[test]
+    fn test_utilization() {
+        let limits = RiskLimits::default();
+        let greeks = Greeks::new(dec!(-50), dec!(10), dec!(-100), dec!(12000), dec!(0));
+
+        let usage = limits.utilization(&greeks, dec!(-1000));
+
+        assert_eq!(usage.len(), 5);
+        assert_eq!(usage[0].kind, LimitKind::Delta);
+        assert_eq!(usage[0].utilization, dec!(0.5));
+        assert!(usage[2].is_breached());
+        assert_eq!(usage[4].current, dec!(1000));
+    }
+
+    #[test]
+    fn test_daily_loss_ignores_profit() {
+        let usage = RiskLimits::default().utilization(&Greeks::zero(), dec!(5000));
+        assert_eq!(usage[4].current, Decimal::ZERO);
+    }
+}
@@ -0,0 +1,60 @@
+//! Risk module.
+//!
+//! This module provides portfolio-level risk controls: limit definitions,
+//! the [`RiskController`] that evaluates exposures against them and records
+//! breaches, and the [`RiskDashboard`] snapshot consumed by operator UIs.
+//!
+//! ## Components
+//!
+//! - [`RiskLimits`]: Greek and loss limits
+//! - [`LayeredLimits`]: Exchange, firm, desk and trader limits, the most restrictive applying, hot-reloaded with a [`LimitChange`] diff
+//! - [`RiskController`]: Limit checks, breach history and trading state
+//! - [`RiskBatch`]: Projected fills of a quote batch checked in one call
+//! - [`TradingState`]: Current trading permission level
+//! - [`KillSwitchTrip`]: Latched halt on a hard-limit breach, with [`HaltListener`]s pulling quotes
+//! - [`RiskDashboard`]: Serializable snapshot aggregating every risk input
+//! - [`CounterpartyRegistry`]: Per-counterparty exposure limits for client flow
+//! - [`MarketQualityMonitor`]: Crossed, locked, wide-versus-surface and stale quote detection across the chain, streamed as [`QualityAlert`]s
+//! - [`ScenarioEngine`]: Spot, vol and time-decay stress grid with P&L per grid point and expiration
+//! - [`ScenarioLibrary`]: Bundled historical stress scenarios (1987, 2008, COVID, ...) run against a book
+//!
+//! ## Data Sources
+//!
+//! The controller does not own positions, P&L, the hedger or quotes. It pulls
+//! them through the [`GreeksSource`], [`PnLSource`], [`HedgerStatusSource`],
+//! [`QuoteCoverageSource`] and [`QuotingPauseSource`] traits, which the owning
+//! components implement.
+
+mod batch;
+mod controller;
+mod counterparty;
+mod dashboard;
+mod kill_switch;
+mod layers;
+mod limits;
+mod quality;
+mod scenarios;
+mod state;
+mod stress;
+
+pub use batch::{BatchVerdict, ContractVerdict, ProjectedExposure, RiskBatch};
+pub use controller::RiskController;
+pub use counterparty::{
+    CounterpartyExposure, CounterpartyLimits, CounterpartyRegistry, CounterpartyTrade,
+};
+pub use dashboard::{
+    GreeksSource, HedgerStatus, HedgerStatusSource, PnLSource, QuoteCoverage, QuoteCoverageSource,
+    QuotingPauseSource, RiskDashboard,
+};
+pub use kill_switch::{HaltListener, KillSwitchTrip};
+pub use layers::{LayeredLimits, LimitLayer};
+pub use limits::{LimitBreach, LimitChange, LimitKind, LimitUtilization, RiskLimits};
+pub use quality::{
+    MarketQualityMonitor, QualityAlert, QualityConfig, QualityIssue, QualityListener, QualityStats,
+};
+pub use scenarios::{
+    AssetClass, HistoricalScenario, PositionImpact, ScenarioLibrary, ScenarioPosition,
+    ScenarioResult, ScenarioShock, TermShock,
+};
+pub use state::TradingState;
+pub use stress::{ScenarioEngine, StressGrid, StressMatrix, StressPoint};
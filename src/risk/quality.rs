@@ -0,0 +1,478 @@
+//! Market quality module.
+//!
+//! This module provides the [`MarketQualityMonitor`], which scans every
+//! option book of the hierarchy and emits a [`QualityAlert`] for each
+//! quote that should not be trusted as a reference or traded against:
+//!
+//! - Crossed: the best bid is above the best ask
+//! - Locked: the best bid equals the best ask
+//! - Wide: the spread, expressed in vol points through the contract's vega
+//!   on a reference surface, exceeds a maximum
+//! - Stale: neither side of the quote has changed for longer than a
+//!   threshold
+//!
+//! Staleness is measured from the monitor's own observations, so a quote
+//! is first seen fresh. A condition that persists is reported on every
+//! scan. Alerts are pushed to subscribed [`QualityListener`]s, e.g. the
+//! risk layer, and counted in [`QualityStats`].
+
+use crate::error::{Error, Result};
+use crate::inventory::ChainCoordinates;
+use crate::orderbook::UnderlyingOrderBookManager;
+use crate::pricing::VolatilitySurface;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+
+/// Thresholds of a [`MarketQualityMonitor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QualityConfig {
+    /// Price of one book tick.
+    pub tick_size: Decimal,
+    /// Age after which an unchanged quote is stale, in milliseconds.
+    pub stale_after_ms: u64,
+    /// Widest spread tolerated, in vol points.
+    pub max_vol_width: Decimal,
+    /// Spreads at or below this price are never reported wide.
+    pub min_width: Decimal,
+}
+
+impl Default for QualityConfig {
+    fn default() -> Self {
+        Self {
+            tick_size: Decimal::ONE,
+            stale_after_ms: 5_000,
+            max_vol_width: dec!(5),
+            min_width: Decimal::ZERO,
+        }
+    }
+}
+
+impl QualityConfig {
+    /// Validates the thresholds.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the tick size, staleness or
+    /// vol width is not positive, or the minimum width is negative.
+    pub fn validate(&self) -> Result<()> {
+        if self.tick_size <= Decimal::ZERO {
+            return Err(Error::configuration("tick_size must be positive"));
+        }
+        if self.stale_after_ms == 0 {
+            return Err(Error::configuration("stale_after_ms must be positive"));
+        }
+        if self.max_vol_width <= Decimal::ZERO {
+            return Err(Error::configuration("max_vol_width must be positive"));
+        }
+        if self.min_width < Decimal::ZERO {
+            return Err(Error::configuration("min_width must not be negative"));
+        }
+        Ok(())
+    }
+}
+
+/// What is wrong with a quote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QualityIssue {
+    /// The best bid is above the best ask.
+    Crossed {
+        /// Best bid in ticks.
+        bid: u128,
+        /// Best ask in ticks.
+        ask: u128,
+    },
+    /// The best bid equals the best ask.
+    Locked {
+        /// Price of both sides in ticks.
+        price: u128,
+    },
+    /// The spread is wider than the reference allows.
+    Wide {
+        /// Spread in price.
+        spread: Decimal,
+        /// Spread in vol points.
+        vol_width: Decimal,
+    },
+    /// The quote has not changed for too long.
+    Stale {
+        /// Time since the quote last changed, in milliseconds.
+        age_ms: u64,
+    },
+}
+
+/// One quality problem found by a scan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QualityAlert {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Underlying symbol.
+    pub underlying: String,
+    /// The problem.
+    pub issue: QualityIssue,
+    /// Scan time in milliseconds.
+    pub timestamp_ms: u64,
+}
+
+/// Receives quality alerts as they are found.
+pub trait QualityListener: Send + Sync {
+    /// Called once per alert.
+    fn on_quality_alert(&self, alert: &QualityAlert);
+}
+
+/// Alert counts since the monitor was created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QualityStats {
+    /// Scans run.
+    pub scans: u64,
+    /// Crossed quotes reported.
+    pub crossed: u64,
+    /// Locked quotes reported.
+    pub locked: u64,
+    /// Wide quotes reported.
+    pub wide: u64,
+    /// Stale quotes reported.
+    pub stale: u64,
+}
+
+/// Reference used to judge spread widths of one underlying.
+#[derive(Debug, Clone)]
+struct Reference {
+    /// Implied volatility surface.
+    surface: VolatilitySurface,
+    /// Spot price.
+    spot: Decimal,
+}
+
+/// Last observed top of book of a contract.
+#[derive(Debug, Clone, Copy)]
+struct Observed {
+    bid: Option<u128>,
+    ask: Option<u128>,
+    /// When the top of book last changed.
+    changed_ms: u64,
+}
+
+/// Mutable scan state.
+#[derive(Debug, Default)]
+struct State {
+    observed: HashMap<String, Observed>,
+    stats: QualityStats,
+}
+
+/// Scans the option books for crossed, locked, wide and stale quotes.
+pub struct MarketQualityMonitor {
+    /// Thresholds.
+    config: QualityConfig,
+    /// Reference surfaces by underlying.
+    references: RwLock<HashMap<String, Reference>>,
+    /// Subscribed listeners.
+    listeners: RwLock<Vec<Arc<dyn QualityListener>>>,
+    /// Observations and counters.
+    state: Mutex<State>,
+}
+
+impl MarketQualityMonitor {
+    /// Creates a monitor.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the thresholds are invalid.
+    pub fn new(config: QualityConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            references: RwLock::new(HashMap::new()),
+            listeners: RwLock::new(Vec::new()),
+            state: Mutex::new(State::default()),
+        })
+    }
+
+    /// Returns the thresholds.
+    #[must_use]
+    pub const fn config(&self) -> &QualityConfig {
+        &self.config
+    }
+
+    /// Sets the surface and spot spread widths of an underlying are judged
+    /// against. Underlyings without a reference are never reported wide.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the spot is not positive.
+    pub fn set_reference(
+        &self,
+        underlying: impl Into<String>,
+        surface: VolatilitySurface,
+        spot: Decimal,
+    ) -> Result<()> {
+        if spot <= Decimal::ZERO {
+            return Err(Error::validation("reference spot must be positive"));
+        }
+        if let Ok(mut references) = self.references.write() {
+            references.insert(underlying.into(), Reference { surface, spot });
+        }
+        Ok(())
+    }
+
+    /// Subscribes a listener to every alert.
+    pub fn subscribe(&self, listener: Arc<dyn QualityListener>) {
+        if let Ok(mut listeners) = self.listeners.write() {
+            listeners.push(listener);
+        }
+    }
+
+    /// Returns the alert counts.
+    #[must_use]
+    pub fn stats(&self) -> QualityStats {
+        self.lock().stats
+    }
+
+    /// Scans every option book and returns the alerts found, after pushing
+    /// them to the listeners.
+    ///
+    /// # Arguments
+    ///
+    /// * `manager` - Order book hierarchy to scan
+    /// * `now_ms` - Scan time in milliseconds
+    pub fn scan(&self, manager: &UnderlyingOrderBookManager, now_ms: u64) -> Vec<QualityAlert> {
+        let references = self
+            .references
+            .read()
+            .map(|r| r.clone())
+            .unwrap_or_default();
+        let mut alerts = Vec::new();
+        {
+            let mut state = self.lock();
+            state.stats.scans += 1;
+            for entry in manager.iter() {
+                let underlying = entry.key();
+                let reference = references.get(underlying);
+                entry.value().for_each_quote(|book, quote| {
+                    let (bid, ask) = (quote.bid_price(), quote.ask_price());
+                    let mut alert = |issue| {
+                        alerts.push(QualityAlert {
+                            symbol: book.symbol().to_string(),
+                            underlying: underlying.clone(),
+                            issue,
+                            timestamp_ms: now_ms,
+                        });
+                    };
+                    if let (Some(bid), Some(ask)) = (bid, ask) {
+                        if bid > ask {
+                            alert(QualityIssue::Crossed { bid, ask });
+                        } else if bid == ask {
+                            alert(QualityIssue::Locked { price: bid });
+                        } else if let Some(issue) =
+                            reference.and_then(|r| self.wide(book.symbol(), bid, ask, r, now_ms))
+                        {
+                            alert(issue);
+                        }
+                    }
+
+                    let observed =
+                        state
+                            .observed
+                            .entry(book.symbol().to_string())
+                            .or_insert(Observed {
+                                bid,
+                                ask,
+                                changed_ms: now_ms,
+                            });
+                    if observed.bid != bid || observed.ask != ask {
+                        *observed = Observed {
+                            bid,
+                            ask,
+                            changed_ms: now_ms,
+                        };
+                    }
+                    let age_ms = now_ms.saturating_sub(observed.changed_ms);
+                    if (bid.is_some() || ask.is_some()) && age_ms > self.config.stale_after_ms {
+                        alert(QualityIssue::Stale { age_ms });
+                    }
+                });
+            }
+            for alert in &alerts {
+                let counter = match alert.issue {
+                    QualityIssue::Crossed { .. } => &mut state.stats.crossed,
+                    QualityIssue::Locked { .. } => &mut state.stats.locked,
+                    QualityIssue::Wide { .. } => &mut state.stats.wide,
+                    QualityIssue::Stale { .. } => &mut state.stats.stale,
+                };
+                *counter += 1;
+            }
+        }
+
+        let listeners = self.listeners.read().map(|l| l.clone()).unwrap_or_default();
+        for alert in &alerts {
+            for listener in &listeners {
+                listener.on_quality_alert(alert);
+            }
+        }
+        alerts
+    }
+
+    /// Returns a wide-spread issue if the quote is wider than the
+    /// reference allows. Contracts that cannot be priced are skipped.
+    fn wide(
+        &self,
+        symbol: &str,
+        bid: u128,
+        ask: u128,
+        reference: &Reference,
+        now_ms: u64,
+    ) -> Option<QualityIssue> {
+        let spread = Decimal::from(ask - bid) * self.config.tick_size;
+        if spread <= self.config.min_width {
+            return None;
+        }
+        let coordinates = ChainCoordinates::parse(symbol)?;
+        let days = coordinates.days_to_expiry(now_ms)?;
+        if days <= Decimal::ZERO {
+            return None;
+        }
+        let vega = reference
+            .surface
+            .pricing_params(
+                reference.spot,
+                Decimal::from(coordinates.strike),
+                days,
+                coordinates.style,
+            )
+            .ok()?
+            .greeks()
+            .ok()?
+            .vega
+            .abs();
+        if vega.is_zero() {
+            return None;
+        }
+        let vol_width = spread / vega;
+        (vol_width > self.config.max_vol_width).then_some(QualityIssue::Wide { spread, vol_width })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::SmileParams;
+    use chrono::{TimeZone, Utc};
+    use optionstratlib::ExpirationDate;
+    use orderbook_rs::{OrderId, Side};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Counter(AtomicUsize);
+
+    impl QualityListener for Counter {
+        fn on_quality_alert(&self, _alert: &QualityAlert) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Lists a call and restores a top of book into it, so crossed and
+    /// locked markets mirrored from a venue can be represented.
+    fn quote(manager: &UnderlyingOrderBookManager, strike: u64, bid: u128, ask: u128) -> String {
+        let expiration =
+            ExpirationDate::DateTime(Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap());
+        let book = manager
+            .get_or_create("BTC")
+            .get_or_create_expiration(expiration)
+            .get_or_create_strike(strike)
+            .call_arc();
+        book.add_limit_order(OrderId::new(), Side::Sell, ask, 1)
+            .unwrap();
+        let asks = book.snapshot(1).asks;
+        book.clear();
+        book.add_limit_order(OrderId::new(), Side::Buy, bid, 1)
+            .unwrap();
+        let mut snapshot = book.snapshot(1);
+        snapshot.asks = asks;
+        snapshot.refresh_aggregates();
+        book.restore(snapshot).unwrap();
+        book.symbol().to_string()
+    }
+
+    #[test]
+    fn test_crossed_locked_and_stale() {
+        let manager = UnderlyingOrderBookManager::new();
+        let monitor = MarketQualityMonitor::new(QualityConfig::default()).unwrap();
+        let counter = Arc::new(Counter(AtomicUsize::new(0)));
+        monitor.subscribe(counter.clone());
+
+        let crossed = quote(&manager, 100, 12, 10);
+        quote(&manager, 110, 10, 10);
+        quote(&manager, 120, 10, 11);
+
+        let alerts = monitor.scan(&manager, 1_000);
+        assert_eq!(alerts.len(), 2);
+        assert!(
+            alerts
+                .iter()
+                .any(|a| a.symbol == crossed
+                    && a.issue == QualityIssue::Crossed { bid: 12, ask: 10 })
+        );
+        assert!(
+            alerts
+                .iter()
+                .any(|a| a.issue == QualityIssue::Locked { price: 10 })
+        );
+
+        // Nothing changed for longer than the threshold: every quote is stale.
+        let alerts = monitor.scan(&manager, 7_000);
+        let stale = alerts
+            .iter()
+            .filter(|a| matches!(a.issue, QualityIssue::Stale { age_ms: 6_000 }))
+            .count();
+        assert_eq!(stale, 3);
+        assert_eq!(counter.0.load(Ordering::Relaxed), alerts.len() + 2);
+        let stats = monitor.stats();
+        assert_eq!((stats.scans, stats.crossed, stats.stale), (2, 2, 3));
+    }
+
+    #[test]
+    fn test_wide_versus_reference_surface() {
+        let manager = UnderlyingOrderBookManager::new();
+        let monitor = MarketQualityMonitor::new(QualityConfig {
+            tick_size: dec!(0.1),
+            ..QualityConfig::default()
+        })
+        .unwrap();
+        let tight = quote(&manager, 100, 50, 51);
+        let wide = quote(&manager, 105, 10, 900);
+
+        let now = u64::try_from(
+            Utc.with_ymd_and_hms(2029, 12, 1, 0, 0, 0)
+                .unwrap()
+                .timestamp_millis(),
+        )
+        .unwrap();
+        assert!(monitor.scan(&manager, now).is_empty());
+        let surface = VolatilitySurface::new().with_pillar(
+            dec!(30),
+            SmileParams::new(dec!(0.5), Decimal::ZERO, Decimal::ZERO),
+        );
+        monitor.set_reference("BTC", surface, dec!(100)).unwrap();
+
+        let alerts = monitor.scan(&manager, now);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].symbol, wide);
+        assert_ne!(alerts[0].symbol, tight);
+        assert!(matches!(alerts[0].issue, QualityIssue::Wide { .. }));
+        assert!(
+            QualityConfig {
+                max_vol_width: Decimal::ZERO,
+                ..QualityConfig::default()
+            }
+            .validate()
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,395 @@
+//! Historical scenario module.
+//!
+//! This module provides the [`ScenarioLibrary`], a curated set of named
+//! historical stress scenarios (Black Monday 1987, the 2008 crisis, the
+//! COVID crash, ...) bundled with the crate as data. Each scenario holds a
+//! spot move and a volatility move per [`AssetClass`], with optional
+//! per-tenor volatility moves for the term structure, calibrated to the
+//! peak-to-trough behaviour of the event.
+//!
+//! A scenario can be run directly against a book of [`ScenarioPosition`]s
+//! with [`ScenarioLibrary::run`], or converted into [`StressScenario`]s for
+//! the [`crate::quoting::StressSizer`].
+//!
+//! Custom libraries use the same JSON layout as the bundled one and are
+//! loaded with [`ScenarioLibrary::from_json`].
+
+use crate::error::{Error, Result};
+use crate::pricing::PricingParams;
+use crate::quoting::StressScenario;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Bundled scenario data.
+const BUILTIN_SCENARIOS: &str = include_str!("scenarios.json");
+
+/// Smallest volatility a scenario can shock to.
+const MIN_SCENARIO_VOLATILITY: Decimal = dec!(0.0001);
+
+/// Asset class a scenario shock is calibrated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetClass {
+    /// Equity indices and single stocks.
+    Equity,
+    /// Crypto assets.
+    Crypto,
+    /// Currency pairs.
+    Fx,
+    /// Commodities.
+    Commodity,
+}
+
+/// Volatility move for expiries up to a tenor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TermShock {
+    /// Longest expiry, in days, the move applies to.
+    pub max_days: Decimal,
+    /// Absolute volatility move.
+    pub vol_shift: Decimal,
+}
+
+/// Spot and volatility moves of one asset class in a scenario.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScenarioShock {
+    /// Relative spot move (`-0.3` = spot down 30%).
+    pub spot_shift: Decimal,
+    /// Absolute volatility move beyond the last term bucket.
+    pub vol_shift: Decimal,
+    /// Volatility moves by tenor, by increasing `max_days`.
+    #[serde(default)]
+    pub term_structure: Vec<TermShock>,
+}
+
+impl ScenarioShock {
+    /// Returns the volatility move of an expiry.
+    ///
+    /// # Arguments
+    ///
+    /// * `days_to_expiry` - Days to expiration
+    #[must_use]
+    pub fn vol_shift_at(&self, days_to_expiry: Decimal) -> Decimal {
+        self.term_structure
+            .iter()
+            .find(|t| days_to_expiry <= t.max_days)
+            .map_or(self.vol_shift, |t| t.vol_shift)
+    }
+
+    /// Applies the shock to a contract's pricing inputs.
+    #[must_use]
+    pub fn apply(&self, params: &PricingParams) -> PricingParams {
+        PricingParams {
+            spot: params.spot * (Decimal::ONE + self.spot_shift),
+            volatility: (params.volatility + self.vol_shift_at(params.days_to_expiry))
+                .max(MIN_SCENARIO_VOLATILITY),
+            ..*params
+        }
+    }
+}
+
+/// A named historical stress scenario.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoricalScenario {
+    /// Scenario name, e.g. `COVID-March-2020`.
+    pub name: String,
+    /// What happened.
+    pub description: String,
+    /// Date or date range the shock is calibrated on.
+    pub period: String,
+    /// Shocks by asset class; classes not listed were not affected.
+    pub shocks: BTreeMap<AssetClass, ScenarioShock>,
+}
+
+impl HistoricalScenario {
+    /// Returns the shock of an asset class.
+    #[must_use]
+    pub fn shock(&self, asset_class: AssetClass) -> Option<&ScenarioShock> {
+        self.shocks.get(&asset_class)
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (class, shock) in &self.shocks {
+            if shock.spot_shift <= -Decimal::ONE {
+                return Err(Error::configuration(format!(
+                    "scenario {} moves {class:?} spot to zero or below",
+                    self.name
+                )));
+            }
+            if shock
+                .term_structure
+                .windows(2)
+                .any(|w| w[1].max_days <= w[0].max_days)
+            {
+                return Err(Error::configuration(format!(
+                    "scenario {} {class:?} term structure must be by increasing tenor",
+                    self.name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A position valued under a scenario.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScenarioPosition {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Asset class of the underlying.
+    pub asset_class: AssetClass,
+    /// Current pricing inputs.
+    pub pricing: PricingParams,
+    /// Signed quantity in contracts (negative = short).
+    pub quantity: Decimal,
+}
+
+/// Scenario P&L of one position.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionImpact {
+    /// Contract symbol.
+    pub symbol: String,
+    /// Current value.
+    pub value: Decimal,
+    /// Value under the scenario.
+    pub shocked_value: Decimal,
+    /// Scenario P&L.
+    pub pnl: Decimal,
+}
+
+/// Result of running a scenario against a book.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScenarioResult {
+    /// Scenario name.
+    pub scenario: String,
+    /// Total scenario P&L.
+    pub pnl: Decimal,
+    /// P&L by position, in book order.
+    pub positions: Vec<PositionImpact>,
+    /// Symbols whose asset class the scenario does not shock.
+    pub unaffected: Vec<String>,
+}
+
+/// Library of named historical stress scenarios.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScenarioLibrary {
+    /// Scenarios in load order.
+    scenarios: Vec<HistoricalScenario>,
+}
+
+impl ScenarioLibrary {
+    /// Returns the scenarios bundled with the crate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bundled data is invalid, which the test suite rules
+    /// out.
+    #[must_use]
+    pub fn builtin() -> Self {
+        Self::from_json(BUILTIN_SCENARIOS).expect("bundled scenarios are valid")
+    }
+
+    /// Loads a library from a JSON array of scenarios.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::SerializationError` if the JSON is malformed, or
+    /// `Error::ConfigurationError` if a name is duplicated, a shock moves
+    /// spot to zero or below, or a term structure is not by increasing
+    /// tenor.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let scenarios: Vec<HistoricalScenario> = serde_json::from_str(json)?;
+        for (i, scenario) in scenarios.iter().enumerate() {
+            scenario.validate()?;
+            if scenarios[..i].iter().any(|s| s.name == scenario.name) {
+                return Err(Error::configuration(format!(
+                    "duplicate scenario {}",
+                    scenario.name
+                )));
+            }
+        }
+        Ok(Self { scenarios })
+    }
+
+    /// Returns a scenario by name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&HistoricalScenario> {
+        self.scenarios.iter().find(|s| s.name == name)
+    }
+
+    /// Returns the scenario names in load order.
+    #[must_use]
+    pub fn names(&self) -> Vec<&str> {
+        self.scenarios.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    /// Returns every scenario in load order.
+    #[must_use]
+    pub fn scenarios(&self) -> &[HistoricalScenario] {
+        &self.scenarios
+    }
+
+    /// Returns the number of scenarios.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.scenarios.len()
+    }
+
+    /// Returns true if the library holds no scenario.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.scenarios.is_empty()
+    }
+
+    /// Converts the scenarios shocking an asset class into stress
+    /// scenarios for the [`crate::quoting::StressSizer`].
+    ///
+    /// Stress scenarios carry a single volatility move, so the term
+    /// structure is read at one tenor.
+    ///
+    /// # Arguments
+    ///
+    /// * `asset_class` - Asset class of the book
+    /// * `days_to_expiry` - Tenor the volatility move is read at
+    #[must_use]
+    pub fn stress_scenarios(
+        &self,
+        asset_class: AssetClass,
+        days_to_expiry: Decimal,
+    ) -> Vec<StressScenario> {
+        self.scenarios
+            .iter()
+            .filter_map(|s| {
+                s.shock(asset_class).map(|shock| {
+                    StressScenario::new(
+                        s.name.clone(),
+                        shock.spot_shift,
+                        shock.vol_shift_at(days_to_expiry),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Revalues a book under a named scenario.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Scenario name
+    /// * `positions` - Current book
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the scenario is unknown, or
+    /// `Error::PricingError` if a position cannot be priced.
+    pub fn run(&self, name: &str, positions: &[ScenarioPosition]) -> Result<ScenarioResult> {
+        let scenario = self
+            .get(name)
+            .ok_or_else(|| Error::validation(format!("unknown scenario {name}")))?;
+        let mut result = ScenarioResult {
+            scenario: scenario.name.clone(),
+            pnl: Decimal::ZERO,
+            positions: Vec::with_capacity(positions.len()),
+            unaffected: Vec::new(),
+        };
+        for position in positions {
+            let Some(shock) = scenario.shock(position.asset_class) else {
+                result.unaffected.push(position.symbol.clone());
+                continue;
+            };
+            let value = position.pricing.price()? * position.quantity;
+            let shocked_value = shock.apply(&position.pricing).price()? * position.quantity;
+            let pnl = shocked_value - value;
+            result.pnl += pnl;
+            result.positions.push(PositionImpact {
+                symbol: position.symbol.clone(),
+                value,
+                shocked_value,
+                pnl,
+            });
+        }
+        Ok(result)
+    }
+
+    /// Runs every scenario against a book, worst P&L first.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PricingError` if a position cannot be priced.
+    pub fn run_all(&self, positions: &[ScenarioPosition]) -> Result<Vec<ScenarioResult>> {
+        let mut results = self
+            .scenarios
+            .iter()
+            .map(|s| self.run(&s.name, positions))
+            .collect::<Result<Vec<_>>>()?;
+        results.sort_by_key(|r| r.pnl);
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optionstratlib::OptionStyle;
+
+    fn position(symbol: &str, class: AssetClass, quantity: Decimal) -> ScenarioPosition {
+        ScenarioPosition {
+            symbol: symbol.to_string(),
+            asset_class: class,
+            pricing: PricingParams::new(
+                dec!(100),
+                dec!(100),
+                dec!(20),
+                dec!(0.3),
+                OptionStyle::Put,
+            ),
+            quantity,
+        }
+    }
+
+    #[test]
+    fn test_builtin_library() {
+        let library = ScenarioLibrary::builtin();
+        assert!(library.len() >= 6);
+        let covid = library.get("COVID-March-2020").unwrap();
+        let equity = covid.shock(AssetClass::Equity).unwrap();
+        assert_eq!(equity.spot_shift, dec!(-0.34));
+        // Front-month vol moves more than the long end.
+        assert!(equity.vol_shift_at(dec!(7)) > equity.vol_shift_at(dec!(180)));
+        assert_eq!(equity.vol_shift_at(dec!(1000)), equity.vol_shift);
+
+        let stress = library.stress_scenarios(AssetClass::Crypto, dec!(30));
+        assert!(stress.iter().any(|s| s.name == "FTX-Nov-2022"));
+        assert!(stress.iter().all(|s| s.name != "GFC-Oct-2008"));
+    }
+
+    #[test]
+    fn test_run_against_book() {
+        let library = ScenarioLibrary::builtin();
+        let book = vec![
+            position("SPX-P", AssetClass::Equity, dec!(-10)),
+            position("BTC-P", AssetClass::Crypto, dec!(5)),
+        ];
+        let result = library.run("GFC-Oct-2008", &book).unwrap();
+        assert_eq!(result.positions.len(), 1);
+        assert_eq!(result.unaffected, vec!["BTC-P".to_string()]);
+        // Short puts lose in a crash.
+        assert!(result.pnl < Decimal::ZERO);
+
+        let all = library.run_all(&book).unwrap();
+        assert_eq!(all.len(), library.len());
+        assert!(all.windows(2).all(|w| w[0].pnl <= w[1].pnl));
+        assert!(library.run("Unknown", &book).is_err());
+    }
+
+    #[test]
+    fn test_invalid_custom_library() {
+        let json = r#"[{"name": "Wipeout", "description": "", "period": "",
+            "shocks": {"equity": {"spot_shift": "-1", "vol_shift": "0"}}}]"#;
+        assert!(ScenarioLibrary::from_json(json).is_err());
+        let json = r#"[{"name": "Twice", "description": "", "period": "", "shocks": {}},
+            {"name": "Twice", "description": "", "period": "", "shocks": {}}]"#;
+        assert!(ScenarioLibrary::from_json(json).is_err());
+    }
+}
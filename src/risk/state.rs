@@ -0,0 +1,101 @@
+//! Trading state module.
+//!
+//! This module provides [`TradingState`], the permission level the risk
+//! controller grants to quoting and order entry.
+
+use serde::{Deserialize, Serialize};
+
+/// Permission level granted by the risk controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum TradingState {
+    /// Normal trading.
+    #[default]
+    Active,
+    /// Only risk-reducing activity is permitted.
+    ReducedRisk,
+    /// All trading is stopped.
+    Halted,
+}
+
+impl TradingState {
+    /// Returns true if new quotes may be placed.
+    #[must_use]
+    pub const fn permits_quoting(self) -> bool {
+        matches!(self, Self::Active)
+    }
+
+    /// Returns true if an order may be submitted.
+    ///
+    /// # Arguments
+    ///
+    /// * `reduces_risk` - Whether the order only reduces exposure
+    #[must_use]
+    pub const fn permits_order(self, reduces_risk: bool) -> bool {
+        match self {
+            Self::Active => true,
+            Self::ReducedRisk => reduces_risk,
+            Self::Halted => false,
+        }
+    }
+
+    /// Returns the compact representation used for atomic storage.
+    #[must_use]
+    pub(crate) const fn as_u8(self) -> u8 {
+        match self {
+            Self::Active => 0,
+            Self::ReducedRisk => 1,
+            Self::Halted => 2,
+        }
+    }
+
+    /// Restores a state from its compact representation.
+    #[must_use]
+    pub(crate) const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Active,
+            1 => Self::ReducedRisk,
+            _ => Self::Halted,
+        }
+    }
+}
+
+impl std::fmt::Display for TradingState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Active => write!(f, "Active"),
+            Self::ReducedRisk => write!(f, "ReducedRisk"),
+            Self::Halted => write!(f, "Halted"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trading_state_roundtrip() {
+        for state in [
+            TradingState::Active,
+            TradingState::ReducedRisk,
+            TradingState::Halted,
+        ] {
+            assert_eq!(TradingState::from_u8(state.as_u8()), state);
+        }
+        assert_eq!(TradingState::default(), TradingState::Active);
+    }
+
+    #[test]
+    fn test_trading_state_permissions() {
+        assert!(TradingState::Active.permits_quoting());
+        assert!(!TradingState::ReducedRisk.permits_quoting());
+        assert!(TradingState::ReducedRisk.permits_order(true));
+        assert!(!TradingState::ReducedRisk.permits_order(false));
+        assert!(!TradingState::Halted.permits_order(true));
+    }
+
+    #[test]
+    fn test_trading_state_display() {
+        assert_eq!(TradingState::Halted.to_string(), "Halted");
+    }
+}
@@ -0,0 +1,326 @@
+//! Stress grid module.
+//!
+//! This module provides the [`ScenarioEngine`], which values a book under a
+//! grid of spot and volatility shocks with time decay and reports a
+//! [`StressMatrix`] of P&L per grid point and per expiration.
+//!
+//! ## Valuation
+//!
+//! [`ScenarioEngine::run`] reprices every position under every shock. When
+//! only Greeks are at hand, [`ScenarioEngine::run_greeks`] uses the
+//! second-order approximation
+//!
+//! ```text
+//! pnl = delta * dS + gamma * dS^2 / 2 + vega * dVol * 100 + theta * days
+//! ```
+//!
+//! with `dS = spot * spot_shift`, `dVol` an absolute volatility move (vega
+//! is per vol point) and `days` the decay horizon.
+
+use super::scenarios::ScenarioPosition;
+use crate::error::{Error, Result};
+use crate::inventory::ChainCoordinates;
+use crate::pricing::Greeks;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Smallest volatility a shock can move to.
+const MIN_STRESS_VOLATILITY: Decimal = dec!(0.0001);
+
+/// Shortest time to expiry a decayed position is valued at, in days.
+const MIN_STRESS_DAYS: Decimal = dec!(0.001);
+
+/// Shocks applied by a [`ScenarioEngine`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StressGrid {
+    /// Relative spot moves (`-0.1` = spot down 10%).
+    pub spot_shifts: Vec<Decimal>,
+    /// Absolute volatility moves (`0.05` = up 5 vol points).
+    pub vol_shifts: Vec<Decimal>,
+    /// Days of time decay applied at every grid point.
+    pub days_forward: Decimal,
+}
+
+impl Default for StressGrid {
+    fn default() -> Self {
+        Self {
+            spot_shifts: vec![
+                dec!(-0.20),
+                dec!(-0.10),
+                dec!(-0.05),
+                Decimal::ZERO,
+                dec!(0.05),
+                dec!(0.10),
+                dec!(0.20),
+            ],
+            vol_shifts: vec![
+                dec!(-0.10),
+                dec!(-0.05),
+                Decimal::ZERO,
+                dec!(0.05),
+                dec!(0.10),
+            ],
+            days_forward: Decimal::ONE,
+        }
+    }
+}
+
+impl StressGrid {
+    /// Validates the grid.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if an axis is empty, a spot move
+    /// takes spot to zero or below, or the decay horizon is negative.
+    pub fn validate(&self) -> Result<()> {
+        if self.spot_shifts.is_empty() || self.vol_shifts.is_empty() {
+            return Err(Error::configuration("stress grid axes must not be empty"));
+        }
+        if self.spot_shifts.iter().any(|s| *s <= -Decimal::ONE) {
+            return Err(Error::configuration(
+                "stress spot shifts must be above -100%",
+            ));
+        }
+        if self.days_forward < Decimal::ZERO {
+            return Err(Error::configuration(
+                "stress days forward must be non-negative",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// P&L at one point of the stress grid.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StressPoint {
+    /// Relative spot move.
+    pub spot_shift: Decimal,
+    /// Absolute volatility move.
+    pub vol_shift: Decimal,
+    /// Total P&L.
+    pub pnl: Decimal,
+    /// P&L per expiration bucket.
+    pub by_expiration: BTreeMap<String, Decimal>,
+}
+
+/// Stress P&L over a whole grid.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StressMatrix {
+    /// Days of time decay applied.
+    pub days_forward: Decimal,
+    /// One point per spot and volatility move, spot-major.
+    pub points: Vec<StressPoint>,
+}
+
+impl StressMatrix {
+    /// Returns the P&L at a grid point.
+    #[must_use]
+    pub fn pnl_at(&self, spot_shift: Decimal, vol_shift: Decimal) -> Option<Decimal> {
+        self.points
+            .iter()
+            .find(|p| p.spot_shift == spot_shift && p.vol_shift == vol_shift)
+            .map(|p| p.pnl)
+    }
+
+    /// Returns the grid point with the lowest P&L.
+    #[must_use]
+    pub fn worst(&self) -> Option<&StressPoint> {
+        self.points.iter().min_by_key(|p| p.pnl)
+    }
+
+    /// Returns the worst P&L of each expiration bucket over the grid.
+    #[must_use]
+    pub fn worst_by_expiration(&self) -> BTreeMap<String, Decimal> {
+        let mut worst: BTreeMap<String, Decimal> = BTreeMap::new();
+        for point in &self.points {
+            for (bucket, pnl) in &point.by_expiration {
+                worst
+                    .entry(bucket.clone())
+                    .and_modify(|w| *w = (*w).min(*pnl))
+                    .or_insert(*pnl);
+            }
+        }
+        worst
+    }
+}
+
+/// Values a book under a grid of spot, volatility and time shocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScenarioEngine {
+    /// Shocks applied.
+    grid: StressGrid,
+}
+
+impl ScenarioEngine {
+    /// Creates a scenario engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ConfigurationError` if the grid is invalid.
+    pub fn new(grid: StressGrid) -> Result<Self> {
+        grid.validate()?;
+        Ok(Self { grid })
+    }
+
+    /// Returns the shocks applied.
+    #[must_use]
+    pub const fn grid(&self) -> &StressGrid {
+        &self.grid
+    }
+
+    /// Reprices every position at every grid point.
+    ///
+    /// Positions are bucketed by the expiration in their symbol, or else by
+    /// their days to expiry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PricingError` if a position cannot be priced.
+    pub fn run(&self, positions: &[ScenarioPosition]) -> Result<StressMatrix> {
+        let base: Vec<(String, Decimal)> = positions
+            .iter()
+            .map(|p| Ok((bucket(p), p.pricing.price()? * p.quantity)))
+            .collect::<Result<_>>()?;
+
+        let mut points = Vec::new();
+        for &spot_shift in &self.grid.spot_shifts {
+            for &vol_shift in &self.grid.vol_shifts {
+                let mut point = StressPoint {
+                    spot_shift,
+                    vol_shift,
+                    pnl: Decimal::ZERO,
+                    by_expiration: BTreeMap::new(),
+                };
+                for (position, (bucket, value)) in positions.iter().zip(&base) {
+                    let mut shocked = position.pricing;
+                    shocked.spot *= Decimal::ONE + spot_shift;
+                    shocked.volatility =
+                        (shocked.volatility + vol_shift).max(MIN_STRESS_VOLATILITY);
+                    shocked.days_to_expiry =
+                        (shocked.days_to_expiry - self.grid.days_forward).max(MIN_STRESS_DAYS);
+                    let pnl = shocked.price()? * position.quantity - value;
+                    point.pnl += pnl;
+                    *point.by_expiration.entry(bucket.clone()).or_default() += pnl;
+                }
+                points.push(point);
+            }
+        }
+        Ok(StressMatrix {
+            days_forward: self.grid.days_forward,
+            points,
+        })
+    }
+
+    /// Approximates grid P&L from position Greeks per expiration, e.g.
+    /// from `InventoryManager::greeks_by_expiration`.
+    ///
+    /// # Arguments
+    ///
+    /// * `spot` - Current spot price
+    /// * `greeks` - Position Greeks by expiration bucket
+    #[must_use]
+    pub fn run_greeks(&self, spot: Decimal, greeks: &BTreeMap<String, Greeks>) -> StressMatrix {
+        let mut points = Vec::new();
+        for &spot_shift in &self.grid.spot_shifts {
+            let ds = spot * spot_shift;
+            for &vol_shift in &self.grid.vol_shifts {
+                let by_expiration: BTreeMap<String, Decimal> = greeks
+                    .iter()
+                    .map(|(bucket, g)| {
+                        let pnl = g.delta * ds
+                            + g.gamma * ds * ds / Decimal::TWO
+                            + g.vega * vol_shift * Decimal::ONE_HUNDRED
+                            + g.theta * self.grid.days_forward;
+                        (bucket.clone(), pnl)
+                    })
+                    .collect();
+                points.push(StressPoint {
+                    spot_shift,
+                    vol_shift,
+                    pnl: by_expiration.values().sum(),
+                    by_expiration,
+                });
+            }
+        }
+        StressMatrix {
+            days_forward: self.grid.days_forward,
+            points,
+        }
+    }
+}
+
+/// Returns the expiration bucket of a position.
+fn bucket(position: &ScenarioPosition) -> String {
+    ChainCoordinates::parse(&position.symbol).map_or_else(
+        || format!("{}d", position.pricing.days_to_expiry.normalize()),
+        |c| c.expiration,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::PricingParams;
+    use crate::risk::AssetClass;
+    use optionstratlib::OptionStyle;
+
+    fn position(symbol: &str, days: Decimal, quantity: Decimal) -> ScenarioPosition {
+        ScenarioPosition {
+            symbol: symbol.to_string(),
+            asset_class: AssetClass::Equity,
+            pricing: PricingParams::new(dec!(100), dec!(100), days, dec!(0.2), OptionStyle::Call),
+            quantity,
+        }
+    }
+
+    #[test]
+    fn test_full_revaluation_matrix() {
+        let engine = ScenarioEngine::new(StressGrid::default()).unwrap();
+        let book = [
+            position("SPX-20250620-100-C", dec!(30), dec!(10)),
+            position("far", dec!(90), dec!(-5)),
+        ];
+        let matrix = engine.run(&book).unwrap();
+        assert_eq!(matrix.points.len(), 35);
+
+        // Net long near-dated calls bleed a day of theta when nothing moves.
+        let flat = matrix.pnl_at(Decimal::ZERO, Decimal::ZERO).unwrap();
+        assert!(flat < Decimal::ZERO);
+        let rally = &matrix.points[30 + 2];
+        assert!(rally.by_expiration["20250620"] > Decimal::ZERO);
+        assert!(rally.by_expiration["90d"] < Decimal::ZERO);
+        assert_eq!(
+            rally.pnl,
+            rally.by_expiration.values().copied().sum::<Decimal>()
+        );
+        assert!(matrix.worst().unwrap().pnl <= flat);
+        assert_eq!(matrix.worst_by_expiration().len(), 2);
+    }
+
+    #[test]
+    fn test_greeks_approximation() {
+        let grid = StressGrid {
+            spot_shifts: vec![dec!(-0.1), dec!(0.1)],
+            vol_shifts: vec![dec!(0.05)],
+            days_forward: dec!(2),
+        };
+        let engine = ScenarioEngine::new(grid).unwrap();
+        let greeks = BTreeMap::from([(
+            "20250620".to_string(),
+            Greeks::new(dec!(10), dec!(1), dec!(-3), dec!(20), Decimal::ZERO),
+        )]);
+        let matrix = engine.run_greeks(dec!(100), &greeks);
+        // -100 + 50 + 100 - 6
+        assert_eq!(matrix.pnl_at(dec!(-0.1), dec!(0.05)), Some(dec!(44)));
+        // 100 + 50 + 100 - 6
+        assert_eq!(matrix.pnl_at(dec!(0.1), dec!(0.05)), Some(dec!(244)));
+
+        let bad = StressGrid {
+            spot_shifts: vec![dec!(-1)],
+            ..StressGrid::default()
+        };
+        assert!(ScenarioEngine::new(bad).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+//! Shared memory layout module.
+//!
+//! This module defines the word layout of the region header and contract
+//! slots, and the seqlock protocol used to write and read slots.
+
+use crate::orderbook::Quote;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering, fence};
+
+/// Magic number identifying a region ("OCOBSHM1").
+pub(crate) const MAGIC: u64 = u64::from_be_bytes(*b"OCOBSHM1");
+/// Layout version.
+pub(crate) const VERSION: u64 = 1;
+/// Words in the region header.
+pub(crate) const HEADER_WORDS: usize = 8;
+/// Words in a contract slot (two cache lines).
+pub(crate) const SLOT_WORDS: usize = 16;
+
+/// Header word holding the magic number.
+pub(crate) const HEADER_MAGIC: usize = 0;
+/// Header word holding the layout version.
+pub(crate) const HEADER_VERSION: usize = 1;
+/// Header word holding the slot capacity.
+pub(crate) const HEADER_CAPACITY: usize = 2;
+/// Header word holding the slot size in words.
+pub(crate) const HEADER_SLOT_WORDS: usize = 3;
+
+/// Slot word holding the sequence number.
+const SEQ: usize = 0;
+/// Flag set when a bid is present.
+const HAS_BID: u64 = 1;
+/// Flag set when an ask is present.
+const HAS_ASK: u64 = 1 << 1;
+
+/// Per-contract hot data shared between processes.
+///
+/// Values are stored as `f64` and `u64` so they can be published without
+/// allocation; use the Decimal-based types for risk and P&L.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ContractSnapshot {
+    /// Publication timestamp in milliseconds.
+    pub timestamp_ms: u64,
+    /// Mark price.
+    pub mark: f64,
+    /// Theoretical value.
+    pub theo: f64,
+    /// Delta.
+    pub delta: f64,
+    /// Gamma.
+    pub gamma: f64,
+    /// Theta per calendar day.
+    pub theta: f64,
+    /// Vega per vol point.
+    pub vega: f64,
+    /// Rho per percentage point.
+    pub rho: f64,
+    /// Best bid price in smallest units.
+    pub bid_price: Option<u64>,
+    /// Size at best bid.
+    pub bid_size: u64,
+    /// Best ask price in smallest units.
+    pub ask_price: Option<u64>,
+    /// Size at best ask.
+    pub ask_size: u64,
+}
+
+impl ContractSnapshot {
+    /// Returns the snapshot with its best bid and ask taken from a quote.
+    ///
+    /// Prices above `u64::MAX` are saturated.
+    #[must_use]
+    pub fn with_quote(mut self, quote: &Quote) -> Self {
+        let to_u64 = |p: u128| u64::try_from(p).unwrap_or(u64::MAX);
+        self.bid_price = quote.bid_price().map(to_u64);
+        self.bid_size = quote.bid_size();
+        self.ask_price = quote.ask_price().map(to_u64);
+        self.ask_size = quote.ask_size();
+        self
+    }
+
+    /// Encodes the snapshot into slot payload words (excluding the sequence).
+    fn encode(&self) -> [u64; SLOT_WORDS - 1] {
+        let flags = u64::from(self.bid_price.is_some()) * HAS_BID
+            + u64::from(self.ask_price.is_some()) * HAS_ASK;
+        let mut words = [0u64; SLOT_WORDS - 1];
+        words[0] = self.timestamp_ms;
+        words[1] = self.mark.to_bits();
+        words[2] = self.theo.to_bits();
+        words[3] = self.delta.to_bits();
+        words[4] = self.gamma.to_bits();
+        words[5] = self.theta.to_bits();
+        words[6] = self.vega.to_bits();
+        words[7] = self.rho.to_bits();
+        words[8] = self.bid_price.unwrap_or(0);
+        words[9] = self.bid_size;
+        words[10] = self.ask_price.unwrap_or(0);
+        words[11] = self.ask_size;
+        words[12] = flags;
+        words
+    }
+
+    /// Decodes a snapshot from slot payload words.
+    fn decode(words: &[u64; SLOT_WORDS - 1]) -> Self {
+        let flags = words[12];
+        Self {
+            timestamp_ms: words[0],
+            mark: f64::from_bits(words[1]),
+            theo: f64::from_bits(words[2]),
+            delta: f64::from_bits(words[3]),
+            gamma: f64::from_bits(words[4]),
+            theta: f64::from_bits(words[5]),
+            vega: f64::from_bits(words[6]),
+            rho: f64::from_bits(words[7]),
+            bid_price: (flags & HAS_BID != 0).then_some(words[8]),
+            bid_size: words[9],
+            ask_price: (flags & HAS_ASK != 0).then_some(words[10]),
+            ask_size: words[11],
+        }
+    }
+}
+
+/// Writes a snapshot into a slot using the seqlock protocol.
+///
+/// Must only be called by the single writer of the region.
+pub(crate) fn write_slot(slot: &[AtomicU64], snapshot: &ContractSnapshot) {
+    let seq = slot[SEQ].load(Ordering::Relaxed);
+    slot[SEQ].store(seq.wrapping_add(1), Ordering::Relaxed);
+    fence(Ordering::Release);
+    for (word, value) in slot[SEQ + 1..].iter().zip(snapshot.encode()) {
+        word.store(value, Ordering::Relaxed);
+    }
+    slot[SEQ].store(seq.wrapping_add(2), Ordering::Release);
+}
+
+/// Attempts one consistent read of a slot.
+///
+/// Returns `Some(None)` if the slot was never written, `Some(Some(_))` on a
+/// consistent read, and `None` if a write was in progress.
+pub(crate) fn try_read_slot(slot: &[AtomicU64]) -> Option<Option<ContractSnapshot>> {
+    let before = slot[SEQ].load(Ordering::Acquire);
+    if before == 0 {
+        return Some(None);
+    }
+    if before % 2 == 1 {
+        return None;
+    }
+    let mut words = [0u64; SLOT_WORDS - 1];
+    for (value, word) in words.iter_mut().zip(&slot[SEQ + 1..]) {
+        *value = word.load(Ordering::Relaxed);
+    }
+    fence(Ordering::Acquire);
+    let after = slot[SEQ].load(Ordering::Relaxed);
+    (before == after).then(|| Some(ContractSnapshot::decode(&words)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot() -> Vec<AtomicU64> {
+        (0..SLOT_WORDS).map(|_| AtomicU64::new(0)).collect()
+    }
+
+    fn sample() -> ContractSnapshot {
+        ContractSnapshot {
+            timestamp_ms: 42,
+            mark: 101.5,
+            theo: 101.25,
+            delta: 0.5,
+            gamma: 0.01,
+            theta: -0.2,
+            vega: 0.3,
+            rho: 0.05,
+            bid_price: Some(100),
+            bid_size: 5,
+            ask_price: None,
+            ask_size: 0,
+        }
+    }
+
+    #[test]
+    fn test_unwritten_slot_reads_none() {
+        assert_eq!(try_read_slot(&slot()), Some(None));
+    }
+
+    #[test]
+    fn test_write_then_read_roundtrip() {
+        let slot = slot();
+        write_slot(&slot, &sample());
+
+        assert_eq!(try_read_slot(&slot), Some(Some(sample())));
+        assert_eq!(slot[SEQ].load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_with_quote() {
+        let quote = Quote::new(Some(100), 5, Some(110), 3, 0);
+        let snapshot = ContractSnapshot::default().with_quote(&quote);
+
+        assert_eq!(snapshot.bid_price, Some(100));
+        assert_eq!(snapshot.ask_size, 3);
+    }
+
+    #[test]
+    fn test_read_during_write_retries() {
+        let slot = slot();
+        write_slot(&slot, &sample());
+        slot[SEQ].store(3, Ordering::Relaxed);
+
+        assert_eq!(try_read_slot(&slot), None);
+    }
+}
@@ -0,0 +1,26 @@
+//! Shared memory module.
+//!
+//! This module provides a shared-memory layout for per-contract hot data
+//! (mark, theoretical value, Greeks and best quote), so a multi-process
+//! deployment (pricer, quoter, risk) can share one view of the chain without
+//! serialization. Available with the `shm` feature.
+//!
+//! ## Layout
+//!
+//! The region is a memory-mapped file with a fixed header followed by one
+//! fixed-size slot per [`ContractId`](crate::orderbook::ContractId). Slots are
+//! indexed directly by the raw contract id, so the writer and readers must
+//! share the same `ContractRegistry` numbering.
+//!
+//! ## Concurrency
+//!
+//! Each slot is protected by a seqlock. There must be exactly one
+//! [`ShmWriter`] per region; any number of [`ShmReader`]s in any process can
+//! read concurrently without blocking the writer. Readers retry while a slot
+//! is being written and never observe a torn snapshot.
+
+mod layout;
+mod region;
+
+pub use layout::ContractSnapshot;
+pub use region::{ShmReader, ShmWriter};
@@ -0,0 +1,301 @@
+//! Shared memory region module.
+//!
+//! This module provides the [`ShmWriter`] that creates and publishes into a
+//! memory-mapped region, and the [`ShmReader`] that opens it from any process.
+
+use super::layout::{
+    ContractSnapshot, HEADER_CAPACITY, HEADER_MAGIC, HEADER_SLOT_WORDS, HEADER_VERSION,
+    HEADER_WORDS, MAGIC, SLOT_WORDS, VERSION, try_read_slot, write_slot,
+};
+use crate::error::{Error, Result};
+use crate::orderbook::ContractId;
+use memmap2::{Mmap, MmapMut};
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Maximum read attempts before a contended slot is reported as an error.
+const MAX_READ_ATTEMPTS: usize = 1024;
+
+/// Returns the region size in bytes for a slot capacity.
+///
+/// # Errors
+///
+/// Returns `Error::IoError` of kind `InvalidData` if the size overflows.
+fn region_bytes(capacity: usize) -> Result<usize> {
+    capacity
+        .checked_mul(SLOT_WORDS)
+        .and_then(|words| words.checked_add(HEADER_WORDS))
+        .and_then(|words| words.checked_mul(std::mem::size_of::<u64>()))
+        .ok_or_else(|| {
+            Error::IoError(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("shared memory capacity {capacity} overflows the region size"),
+            ))
+        })
+}
+
+/// Views mapped bytes as atomic words.
+fn words(bytes: &[u8]) -> &[AtomicU64] {
+    // SAFETY: mappings are page aligned, which satisfies `AtomicU64`
+    // alignment, and the length is truncated to whole words. `AtomicU64` has
+    // the same in-memory representation as `u64`, and all access goes through
+    // atomic operations, so concurrent access from other processes is not a
+    // data race. Readers only perform atomic loads, which are sound on
+    // read-only memory for lock-free 64-bit atomics.
+    unsafe {
+        std::slice::from_raw_parts(
+            bytes.as_ptr().cast::<AtomicU64>(),
+            bytes.len() / std::mem::size_of::<u64>(),
+        )
+    }
+}
+
+/// Returns the slot for a contract id, if within capacity.
+fn slot(words: &[AtomicU64], capacity: usize, id: ContractId) -> Result<&[AtomicU64]> {
+    let index = id.as_u32() as usize;
+    if index >= capacity {
+        return Err(Error::validation(format!(
+            "contract {id} exceeds shared memory capacity {capacity}"
+        )));
+    }
+    let start = HEADER_WORDS + index * SLOT_WORDS;
+    Ok(&words[start..start + SLOT_WORDS])
+}
+
+/// Single writer of a shared memory region.
+///
+/// Only one writer may exist per region; the seqlock protocol does not
+/// coordinate multiple writers.
+pub struct ShmWriter {
+    /// The writable mapping.
+    map: MmapMut,
+    /// Number of contract slots.
+    capacity: usize,
+}
+
+impl ShmWriter {
+    /// Creates (or truncates) a region file with room for `capacity` contracts.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Backing file, e.g. under `/dev/shm` on Linux
+    /// * `capacity` - Number of contract slots
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the capacity is zero, or
+    /// `Error::IoError` if the region size overflows or the file cannot be
+    /// created or mapped.
+    pub fn create(path: impl AsRef<Path>, capacity: usize) -> Result<Self> {
+        if capacity == 0 {
+            return Err(Error::validation("shared memory capacity must be positive"));
+        }
+        let len = region_bytes(capacity)?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(len as u64)?;
+        // SAFETY: the file was just sized by this writer; other processes only
+        // access it through atomic operations.
+        let map = unsafe { MmapMut::map_mut(&file)? };
+
+        let writer = Self { map, capacity };
+        let header = words(&writer.map);
+        header[HEADER_VERSION].store(VERSION, Ordering::Relaxed);
+        header[HEADER_CAPACITY].store(capacity as u64, Ordering::Relaxed);
+        header[HEADER_SLOT_WORDS].store(SLOT_WORDS as u64, Ordering::Relaxed);
+        header[HEADER_MAGIC].store(MAGIC, Ordering::Release);
+        Ok(writer)
+    }
+
+    /// Returns the number of contract slots.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Publishes a contract snapshot.
+    ///
+    /// Takes `&mut self` so the borrow checker enforces the single writer
+    /// the seqlock protocol relies on.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the id exceeds the capacity.
+    pub fn write(&mut self, id: ContractId, snapshot: &ContractSnapshot) -> Result<()> {
+        write_slot(slot(words(&self.map), self.capacity, id)?, snapshot);
+        Ok(())
+    }
+}
+
+/// Reader of a shared memory region.
+///
+/// Any number of readers may exist in any process.
+pub struct ShmReader {
+    /// The read-only mapping.
+    map: Mmap,
+    /// Number of contract slots.
+    capacity: usize,
+}
+
+impl ShmReader {
+    /// Opens an existing region.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::IoError` if the file cannot be opened or mapped or
+    /// its capacity overflows the region size, or `Error::ValidationError`
+    /// if it is not a compatible region.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = OpenOptions::new().read(true).open(path)?;
+        // SAFETY: the mapping is only accessed through atomic loads.
+        let map = unsafe { Mmap::map(&file)? };
+        if map.len() < region_bytes(0)? {
+            return Err(Error::validation("shared memory region too small"));
+        }
+
+        let header = words(&map);
+        if header[HEADER_MAGIC].load(Ordering::Acquire) != MAGIC {
+            return Err(Error::validation("not a shared memory chain region"));
+        }
+        if header[HEADER_VERSION].load(Ordering::Relaxed) != VERSION
+            || header[HEADER_SLOT_WORDS].load(Ordering::Relaxed) != SLOT_WORDS as u64
+        {
+            return Err(Error::validation("incompatible shared memory layout"));
+        }
+        let capacity = usize::try_from(header[HEADER_CAPACITY].load(Ordering::Relaxed))
+            .map_err(|_| Error::validation("invalid shared memory capacity"))?;
+        if map.len() < region_bytes(capacity)? {
+            return Err(Error::validation("shared memory region truncated"));
+        }
+        Ok(Self { map, capacity })
+    }
+
+    /// Returns the number of contract slots.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Reads a contract snapshot.
+    ///
+    /// Returns `Ok(None)` if the contract was never published.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValidationError` if the id exceeds the capacity, or
+    /// `Error::MarketDataError` if no consistent read was possible because the
+    /// slot was being rewritten continuously.
+    pub fn read(&self, id: ContractId) -> Result<Option<ContractSnapshot>> {
+        let slot = slot(words(&self.map), self.capacity, id)?;
+        for _ in 0..MAX_READ_ATTEMPTS {
+            if let Some(snapshot) = try_read_slot(slot) {
+                return Ok(snapshot);
+            }
+            std::hint::spin_loop();
+        }
+        Err(Error::market_data(format!(
+            "contract {id} slot contended in shared memory"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("ocob-shm-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let path = temp_path("rw");
+        let mut writer = ShmWriter::create(&path, 4).unwrap();
+        let reader = ShmReader::open(&path).unwrap();
+        let snapshot = ContractSnapshot {
+            mark: 12.5,
+            delta: 0.4,
+            ..ContractSnapshot::default()
+        };
+
+        assert_eq!(reader.capacity(), 4);
+        assert_eq!(reader.read(ContractId::new(1)).unwrap(), None);
+
+        writer.write(ContractId::new(1), &snapshot).unwrap();
+        assert_eq!(reader.read(ContractId::new(1)).unwrap(), Some(snapshot));
+        assert!(writer.write(ContractId::new(4), &snapshot).is_err());
+        assert!(reader.read(ContractId::new(4)).is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_open_rejects_foreign_file() {
+        let path = temp_path("foreign");
+        std::fs::write(&path, vec![0u8; 4096]).unwrap();
+
+        assert!(ShmReader::open(&path).is_err());
+        assert!(ShmReader::open(temp_path("missing")).is_err());
+        assert!(ShmWriter::create(temp_path("zero"), 0).is_err());
+        assert!(matches!(
+            ShmWriter::create(temp_path("huge"), usize::MAX),
+            Err(Error::IoError(_))
+        ));
+
+        // A header claiming more slots than the address space holds.
+        let mut header = vec![0u8; 4096];
+        let mut put = |word: usize, value: u64| {
+            header[word * 8..word * 8 + 8].copy_from_slice(&value.to_ne_bytes());
+        };
+        put(HEADER_MAGIC, MAGIC);
+        put(HEADER_VERSION, VERSION);
+        put(HEADER_SLOT_WORDS, SLOT_WORDS as u64);
+        put(HEADER_CAPACITY, u64::MAX);
+        std::fs::write(&path, header).unwrap();
+        assert!(matches!(ShmReader::open(&path), Err(Error::IoError(_))));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_reads_are_consistent() {
+        let path = temp_path("concurrent");
+        let mut writer = ShmWriter::create(&path, 1).unwrap();
+        let id = ContractId::new(0);
+
+        let publisher = std::thread::spawn(move || {
+            for i in 1..=20_000u64 {
+                let value = i as f64;
+                let snapshot = ContractSnapshot {
+                    timestamp_ms: i,
+                    mark: value,
+                    theo: value,
+                    delta: value,
+                    ..ContractSnapshot::default()
+                };
+                writer.write(id, &snapshot).unwrap();
+            }
+        });
+
+        let reader = ShmReader::open(&path).unwrap();
+        let mut last = 0;
+        while last < 20_000 {
+            if let Ok(Some(snapshot)) = reader.read(id) {
+                let expected = snapshot.timestamp_ms as f64;
+                assert_eq!(snapshot.mark, expected);
+                assert_eq!(snapshot.theo, expected);
+                assert_eq!(snapshot.delta, expected);
+                assert!(snapshot.timestamp_ms >= last);
+                last = snapshot.timestamp_ms;
+            }
+        }
+        publisher.join().unwrap();
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,203 @@
+//! Case generator module.
+//!
+//! This module provides [`Gen`], a seeded generator of trades, quotes and
+//! Greeks, and [`check`], which runs a property over many generated cases.
+
+use super::invariants::InvariantViolation;
+use crate::pricing::Greeks;
+use crate::quoting::GeneratedQuote;
+use rust_decimal::Decimal;
+
+/// One trade of a generated sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradeCase {
+    /// Signed quantity, never zero (positive buys).
+    pub quantity: Decimal,
+    /// Trade price, always positive.
+    pub price: Decimal,
+}
+
+/// Seeded generator of test inputs.
+///
+/// The same seed always yields the same sequence.
+#[derive(Debug, Clone)]
+pub struct Gen {
+    /// Generator state.
+    state: u64,
+}
+
+impl Gen {
+    /// Creates a generator.
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next raw value (SplitMix64).
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in `[low, high]`.
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        if high <= low {
+            return low;
+        }
+        low + self.next_u64() % (high - low + 1)
+    }
+
+    /// Returns true with probability `numerator / denominator`.
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.range(1, denominator.max(1)) <= numerator
+    }
+
+    /// Returns a decimal in `[low, high]` with two decimal places.
+    pub fn decimal(&mut self, low: i64, high: i64) -> Decimal {
+        let cents = self.range(0, (high - low).unsigned_abs() * 100);
+        Decimal::from(low) + Decimal::new(i64::try_from(cents).unwrap_or(0), 2)
+    }
+
+    /// Returns a positive price in `[0.01, 1000]`.
+    pub fn price(&mut self) -> Decimal {
+        self.decimal(0, 1000).max(Decimal::new(1, 2))
+    }
+
+    /// Returns a signed, non-zero whole quantity in `[-50, 50]`.
+    pub fn quantity(&mut self) -> Decimal {
+        let magnitude = Decimal::from(self.range(1, 50));
+        if self.chance(1, 2) {
+            magnitude
+        } else {
+            -magnitude
+        }
+    }
+
+    /// Returns one trade.
+    pub fn trade(&mut self) -> TradeCase {
+        TradeCase {
+            quantity: self.quantity(),
+            price: self.price(),
+        }
+    }
+
+    /// Returns up to `max_len` trades (at least one).
+    pub fn trades(&mut self, max_len: usize) -> Vec<TradeCase> {
+        let len = self.range(1, max_len.max(1) as u64);
+        (0..len).map(|_| self.trade()).collect()
+    }
+
+    /// Returns per-contract Greeks with plausible signs and magnitudes.
+    pub fn greeks(&mut self) -> Greeks {
+        Greeks::new(
+            self.decimal(-1, 1),
+            self.decimal(0, 1) / Decimal::ONE_HUNDRED,
+            -self.decimal(0, 50),
+            self.decimal(0, 100),
+            self.decimal(-10, 10),
+        )
+    }
+
+    /// Returns a two-sided quote around a random theo.
+    pub fn quote(&mut self) -> GeneratedQuote {
+        let theo = self.decimal(1, 500);
+        let half = self.decimal(0, 5);
+        GeneratedQuote {
+            theo,
+            reservation_price: theo,
+            bid_price: (theo - half).max(Decimal::ZERO),
+            ask_price: theo + half,
+            bid_size: self.range(1, 100),
+            ask_size: self.range(1, 100),
+        }
+    }
+}
+
+/// A failed property case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseFailure {
+    /// Index of the failing case.
+    pub case: u64,
+    /// Seed that regenerates the failing case with `Gen::new`.
+    pub seed: u64,
+    /// The violated invariant.
+    pub violation: InvariantViolation,
+}
+
+impl std::fmt::Display for CaseFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "case {} (seed {:#x}) failed: {}",
+            self.case, self.seed, self.violation
+        )
+    }
+}
+
+/// Runs a property over `cases` generated cases.
+///
+/// Each case gets its own generator seeded from `seed` and the case index,
+/// so a failure can be replayed alone with `Gen::new(failure.seed)`.
+///
+/// # Errors
+///
+/// Returns the first failing case.
+pub fn check<F>(cases: u64, seed: u64, mut property: F) -> Result<(), CaseFailure>
+where
+    F: FnMut(&mut Gen) -> Result<(), InvariantViolation>,
+{
+    let mut seeds = Gen::new(seed);
+    for case in 0..cases {
+        let case_seed = seeds.next_u64();
+        let mut generator = Gen::new(case_seed);
+        if let Err(violation) = property(&mut generator) {
+            return Err(CaseFailure {
+                case,
+                seed: case_seed,
+                violation,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic() {
+        let mut a = Gen::new(7);
+        let mut b = Gen::new(7);
+        assert_eq!(a.trades(10), b.trades(10));
+    }
+
+    #[test]
+    fn test_generated_ranges() {
+        let mut g = Gen::new(1);
+        for _ in 0..1000 {
+            let trade = g.trade();
+            assert!(!trade.quantity.is_zero());
+            assert!(trade.price > Decimal::ZERO);
+            let quote = g.quote();
+            assert!(quote.bid_price <= quote.ask_price);
+        }
+    }
+
+    #[test]
+    fn test_failure_is_reproducible() {
+        let failure = check(100, 3, |g| {
+            if g.range(0, 9) == 0 {
+                Err(InvariantViolation::new("test", "hit"))
+            } else {
+                Ok(())
+            }
+        })
+        .unwrap_err();
+
+        assert_eq!(Gen::new(failure.seed).range(0, 9), 0);
+    }
+}
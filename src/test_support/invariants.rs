@@ -0,0 +1,244 @@
+//! Invariants module.
+//!
+//! This module provides the position, P&L, Greeks and limit invariants
+//! that hold for every input, as checks returning an
+//! [`InvariantViolation`] describing the first mismatch.
+
+use super::generator::TradeCase;
+use crate::inventory::{InventoryManager, Position, PositionLimits};
+use crate::pricing::Greeks;
+use crate::risk::RiskLimits;
+use rust_decimal::Decimal;
+
+/// Absolute tolerance for comparisons that go through average-price division.
+const TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 9);
+
+/// A violated invariant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvariantViolation {
+    /// Name of the invariant.
+    pub invariant: String,
+    /// What did not hold.
+    pub detail: String,
+}
+
+impl InvariantViolation {
+    /// Creates a violation.
+    #[must_use]
+    pub fn new(invariant: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            invariant: invariant.into(),
+            detail: detail.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.invariant, self.detail)
+    }
+}
+
+impl std::error::Error for InvariantViolation {}
+
+/// Checks that realized plus unrealized P&L equals the cash-flow P&L.
+///
+/// For any trade sequence, the sum of the realized P&L returned by each
+/// fill must equal the position's realized P&L, and its total P&L at
+/// `mark` must equal the cash paid and received plus the open quantity
+/// valued at `mark`.
+///
+/// # Arguments
+///
+/// * `trades` - Trades applied in order
+/// * `mark` - Mark price for the open quantity
+/// * `contract_size` - Underlying amount per contract
+///
+/// # Errors
+///
+/// Returns the violated part of the invariant.
+pub fn pnl_consistency(
+    trades: &[TradeCase],
+    mark: Decimal,
+    contract_size: Decimal,
+) -> Result<(), InvariantViolation> {
+    const NAME: &str = "pnl_consistency";
+    let mut position = Position::new("INVARIANT").with_contract_size(contract_size);
+    let mut cash = Decimal::ZERO;
+    let mut realized = Decimal::ZERO;
+    for trade in trades {
+        realized += position
+            .apply_fill(trade.quantity, trade.price)
+            .map_err(|e| InvariantViolation::new(NAME, e.to_string()))?;
+        cash -= trade.quantity * trade.price * contract_size;
+    }
+
+    if (realized - position.realized_pnl()).abs() > TOLERANCE {
+        return Err(InvariantViolation::new(
+            NAME,
+            format!(
+                "sum of fill P&L {realized} != realized P&L {}",
+                position.realized_pnl()
+            ),
+        ));
+    }
+    let expected = cash + position.quantity() * mark * contract_size;
+    let actual = position.total_pnl(mark);
+    if (expected - actual).abs() > TOLERANCE {
+        return Err(InvariantViolation::new(
+            NAME,
+            format!("total P&L {actual} != cash-flow P&L {expected}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that aggregated Greeks are additive and scale with quantity.
+///
+/// Booking every `(greeks, quantity)` pair into an inventory must give
+/// the sum of `greeks * quantity`, and booking `quantity * factor` must
+/// give that total times `factor`.
+///
+/// # Arguments
+///
+/// * `items` - Per-contract Greeks and signed quantity of each position
+/// * `factor` - Quantity scale factor
+///
+/// # Errors
+///
+/// Returns the violated part of the invariant.
+pub fn greeks_linearity(
+    items: &[(Greeks, Decimal)],
+    factor: Decimal,
+) -> Result<(), InvariantViolation> {
+    const NAME: &str = "greeks_linearity";
+    let expected: Greeks = items.iter().map(|(g, q)| *g * *q).sum();
+    let total = aggregate(items, Decimal::ONE).map_err(|e| InvariantViolation::new(NAME, e))?;
+    if total != expected {
+        return Err(InvariantViolation::new(
+            NAME,
+            format!("aggregate {total} != sum of positions {expected}"),
+        ));
+    }
+    let scaled = aggregate(items, factor).map_err(|e| InvariantViolation::new(NAME, e))?;
+    if scaled != total * factor {
+        return Err(InvariantViolation::new(
+            NAME,
+            format!("aggregate at {factor}x {scaled} != {factor} * {total}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that scaling exposure up never clears a breached limit.
+///
+/// Every limit breached by `greeks` and `pnl_today` must still be breached
+/// when both are multiplied by `factor`.
+///
+/// # Arguments
+///
+/// * `limits` - Risk limits
+/// * `greeks` - Portfolio Greeks
+/// * `pnl_today` - P&L for the day
+/// * `factor` - Scale factor, at least one
+///
+/// # Errors
+///
+/// Returns the violated part of the invariant, or a violation if `factor`
+/// is below one.
+pub fn limit_monotonicity(
+    limits: &RiskLimits,
+    greeks: &Greeks,
+    pnl_today: Decimal,
+    factor: Decimal,
+) -> Result<(), InvariantViolation> {
+    const NAME: &str = "limit_monotonicity";
+    if factor < Decimal::ONE {
+        return Err(InvariantViolation::new(
+            NAME,
+            format!("factor {factor} must be at least one"),
+        ));
+    }
+    let scaled = limits.utilization(&(*greeks * factor), pnl_today * factor);
+    for (base, scaled) in limits.utilization(greeks, pnl_today).iter().zip(&scaled) {
+        if base.is_breached() && !scaled.is_breached() {
+            return Err(InvariantViolation::new(
+                NAME,
+                format!(
+                    "{} breached at {} but not at {}x ({})",
+                    base.kind, base.current, factor, scaled.current
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Books positions into a fresh inventory and returns its total Greeks.
+fn aggregate(items: &[(Greeks, Decimal)], factor: Decimal) -> Result<Greeks, String> {
+    let limits = PositionLimits {
+        per_option: Decimal::MAX,
+        per_strike: Decimal::MAX,
+        per_expiration: Decimal::MAX,
+        per_underlying: Decimal::MAX,
+        ..PositionLimits::default()
+    };
+    let inventory = InventoryManager::new("INVARIANT", limits).map_err(|e| e.to_string())?;
+    for (index, (greeks, quantity)) in items.iter().enumerate() {
+        let symbol = format!("C{index}");
+        inventory
+            .record_trade(&symbol, *quantity * factor, Decimal::ONE)
+            .map_err(|e| e.to_string())?;
+        inventory
+            .set_greeks(&symbol, *greeks)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(inventory.total_greeks())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::check;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_pnl_consistency_property() {
+        check(200, 11, |g| {
+            let trades = g.trades(30);
+            let mark = g.price();
+            let contract_size = g.decimal(1, 10).max(Decimal::ONE);
+            pnl_consistency(&trades, mark, contract_size)
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_greeks_linearity_property() {
+        check(100, 12, |g| {
+            let items: Vec<_> = (0..g.range(1, 10))
+                .map(|_| (g.greeks(), g.quantity()))
+                .collect();
+            greeks_linearity(&items, Decimal::from(g.range(1, 5)))
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_limit_monotonicity_property() {
+        let limits = RiskLimits::default();
+        check(200, 13, |g| {
+            let greeks = g.greeks() * Decimal::from(g.range(1, 500));
+            let pnl = -g.decimal(0, 100_000);
+            limit_monotonicity(&limits, &greeks, pnl, g.decimal(1, 3))
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_violation_reported() {
+        let limits = RiskLimits::default();
+        let err = limit_monotonicity(&limits, &Greeks::zero(), Decimal::ZERO, dec!(0.5));
+        assert_eq!(err.unwrap_err().invariant, "limit_monotonicity");
+    }
+}
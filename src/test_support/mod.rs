@@ -0,0 +1,54 @@
+//! Test support module.
+//!
+//! This module exposes the crate's position, P&L, Greeks and limit
+//! invariants as reusable checks, with a deterministic input generator and
+//! a small property runner, and [`proptest`] strategies for the same inputs,
+//! so integrators can assert the same invariants against their own code
+//! paths. Available with the `test-support` feature, which also enables the
+//! `proptest` dependency.
+//!
+//! ## Components
+//!
+//! - [`Gen`]: Seeded generator of trades, quotes and Greeks
+//! - [`check`]: Runs a property over generated cases and reports a reproducible seed on failure
+//! - [`arb_trades`], [`arb_greeks`] and [`arb_position`]: Shrinking `proptest` strategies for trades, Greeks and positions
+//! - [`pnl_consistency`]: Realized plus unrealized P&L equals the cash-flow P&L of any trade sequence
+//! - [`greeks_linearity`]: Aggregated Greeks are additive and scale with quantity
+//! - [`limit_monotonicity`]: Scaling exposure up never clears a breached limit
+//!
+//! The invariants take plain inputs, so they can be driven by [`Gen`] or
+//! by the `proptest` strategies, which shrink a failing case to a minimal
+//! one.
+//!
+//! ## Examples
+//!
+//! ```rust,ignore
+//! use option_chain_orderbook::test_support::{check, pnl_consistency};
+//!
+//! check(256, 42, |g| {
+//!     let trades = g.trades(20);
+//!     pnl_consistency(&trades, g.price(), g.decimal(1, 10))
+//! })
+//! .unwrap();
+//! ```
+//!
+//! ```rust,ignore
+//! use option_chain_orderbook::test_support::{arb_price, arb_trades, pnl_consistency};
+//! use proptest::prelude::*;
+//! use rust_decimal::Decimal;
+//!
+//! proptest! {
+//!     #[test]
+//!     fn pnl_is_consistent(trades in arb_trades(20), mark in arb_price()) {
+//!         prop_assert!(pnl_consistency(&trades, mark, Decimal::ONE).is_ok());
+//!     }
+//! }
+//! ```
+
+mod generator;
+mod invariants;
+mod strategy;
+
+pub use generator::{CaseFailure, Gen, TradeCase, check};
+pub use invariants::{InvariantViolation, greeks_linearity, limit_monotonicity, pnl_consistency};
+pub use strategy::{arb_greeks, arb_position, arb_price, arb_quantity, arb_trade, arb_trades};
@@ -0,0 +1,106 @@
+//! Proptest strategy module.
+//!
+//! This module provides [`proptest`] strategies for the same inputs [`Gen`]
+//! produces: trades, trade sequences, Greeks and positions. Unlike [`Gen`],
+//! failing cases shrink: quantities towards a one-lot buy, prices towards
+//! one cent and sequences towards a single trade.
+//!
+//! [`Gen`]: super::Gen
+
+use super::generator::TradeCase;
+use crate::inventory::Position;
+use crate::pricing::Greeks;
+use proptest::prelude::*;
+use rust_decimal::Decimal;
+
+/// Returns a strategy for a decimal in `[low, high]` with two decimal
+/// places.
+fn cents(low: i64, high: i64) -> impl Strategy<Value = Decimal> {
+    (low * 100..=high * 100).prop_map(|cents| Decimal::new(cents, 2))
+}
+
+/// Returns a strategy for a signed, non-zero whole quantity in `[-50, 50]`.
+pub fn arb_quantity() -> impl Strategy<Value = Decimal> {
+    (1i64..=50, any::<bool>())
+        .prop_map(|(magnitude, sell)| Decimal::from(if sell { -magnitude } else { magnitude }))
+}
+
+/// Returns a strategy for a positive price in `[0.01, 1000]`.
+pub fn arb_price() -> impl Strategy<Value = Decimal> {
+    (1i64..=100_000).prop_map(|cents| Decimal::new(cents, 2))
+}
+
+/// Returns a strategy for one trade.
+pub fn arb_trade() -> impl Strategy<Value = TradeCase> {
+    (arb_quantity(), arb_price()).prop_map(|(quantity, price)| TradeCase { quantity, price })
+}
+
+/// Returns a strategy for up to `max_len` trades (at least one).
+pub fn arb_trades(max_len: usize) -> impl Strategy<Value = Vec<TradeCase>> {
+    prop::collection::vec(arb_trade(), 1..=max_len.max(1))
+}
+
+/// Returns a strategy for per-contract Greeks with plausible signs and
+/// magnitudes, the ranges of [`Gen::greeks`](super::Gen::greeks).
+pub fn arb_greeks() -> impl Strategy<Value = Greeks> {
+    (
+        cents(-1, 1),
+        cents(0, 1),
+        cents(0, 50),
+        cents(0, 100),
+        cents(-10, 10),
+    )
+        .prop_map(|(delta, gamma, theta, vega, rho)| {
+            Greeks::new(delta, gamma / Decimal::ONE_HUNDRED, -theta, vega, rho)
+        })
+}
+
+/// Returns a strategy for a position in `symbol` built from up to
+/// `max_trades` fills, with per-contract Greeks.
+pub fn arb_position(symbol: &str, max_trades: usize) -> impl Strategy<Value = Position> {
+    let symbol = symbol.to_string();
+    (arb_trades(max_trades), arb_greeks()).prop_map(move |(trades, greeks)| {
+        let mut position = Position::new(symbol.as_str());
+        for trade in trades {
+            // Generated prices are positive, so fills cannot fail.
+            let _ = position.apply_fill(trade.quantity, trade.price);
+        }
+        position.set_greeks(greeks);
+        position
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::pnl_consistency;
+    use proptest::test_runner::TestRunner;
+
+    proptest! {
+        #[test]
+        fn test_pnl_consistency_holds(trades in arb_trades(20), mark in arb_price()) {
+            prop_assert!(pnl_consistency(&trades, mark, Decimal::ONE).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_failures_shrink() {
+        // A property failing on any sell shrinks to a single one-lot sell
+        // at the lowest price.
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&arb_trades(20), |trades| {
+            prop_assert!(trades.iter().all(|t| t.quantity > Decimal::ZERO));
+            Ok(())
+        });
+        let Err(proptest::test_runner::TestError::Fail(_, minimal)) = result else {
+            panic!("property should fail");
+        };
+        assert_eq!(
+            minimal,
+            vec![TradeCase {
+                quantity: Decimal::NEGATIVE_ONE,
+                price: Decimal::new(1, 2),
+            }]
+        );
+    }
+}
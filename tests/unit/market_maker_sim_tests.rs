@@ -0,0 +1,9 @@
+//! Integration tests running the market maker simulation session.
+
+use crate::market_maker_sim::run_session;
+
+#[test]
+fn test_market_maker_session_sanity() {
+    // One simulated hour; `run_session` panics if a final check fails.
+    run_session(60);
+}
@@ -1,3 +1,8 @@
 //! Unit tests for option-chain-orderbook library.
 
+#[allow(dead_code)]
+#[path = "../../examples/market_maker_sim.rs"]
+mod market_maker_sim;
+
+mod market_maker_sim_tests;
 mod orderbook_tests;